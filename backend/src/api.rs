@@ -0,0 +1,818 @@
+//! API request handling helpers
+//!
+//! Shared pieces for the POST /api/* endpoints:
+//! - `ApiJson`: JSON body extractor that also validates the request
+//! - `ApiResponse`: success body (`{"ok": true, ...}`)
+//! - `ApiError`: error body (`{"ok": false, "error": "...", "field": "..."}`)
+//! - `handle_json_rejection`: middleware turning any other error response
+//!   into an `ApiError` body
+//! - `Health`: GET /api/health body
+//! - `EventsRequest`/`EventsResponse`: POST /api/events bodies, with a
+//!   result for every event
+//! - `ScheduleRequest`/`ScheduleResponse`: POST /api/events/schedule bodies
+//! - `Validate` implementations for every request type
+
+use city_events::*;
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Maximum length (in characters) of free-text fields such as `message`
+pub const MAX_TEXT_LENGTH: usize = 500;
+
+/// Maximum length (in characters) of a team name
+pub const MAX_TEAM_LENGTH: usize = 64;
+
+pub use city_events::MAX_BUILDING_ID;
+
+/// Highest road ID the dashboard knows about (roads 0-2 run vertically, 3-4
+/// horizontally)
+pub const MAX_ROAD_ID: usize = 4;
+
+/// Maximum number of events in one POST /api/events/batch request
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// Default maximum number of events in one POST /api/events request
+pub const DEFAULT_EVENTS_LIMIT: usize = 50;
+
+/// Longest delay accepted by POST /api/events/schedule (one day)
+pub const MAX_SCHEDULE_DELAY_SECS: u64 = 24 * 60 * 60;
+
+/// Longest round accepted by POST /api/timer/start (one day)
+pub const MAX_TIMER_DURATION_SECS: u32 = 24 * 60 * 60;
+
+/// Fastest speed (pixels per second) POST /api/traffic/green_wave accepts
+pub const MAX_GREEN_WAVE_SPEED: u32 = 500;
+
+// ============================================================================
+// Responses
+// ============================================================================
+
+/// Body returned by the event endpoints on success
+#[derive(Debug, Serialize)]
+pub struct ApiResponse {
+    /// Always true; failures return an `ApiError` body with `ok: false`
+    pub ok: bool,
+
+    pub message: &'static str,
+
+    /// Name of the broadcast event type (e.g. "BarrierBroken")
+    pub event: &'static str,
+}
+
+impl ApiResponse {
+    /// Response for a successfully broadcast event
+    pub fn triggered(event: &GameEvent) -> Json<Self> {
+        Json(Self {
+            ok: true,
+            message: "Event triggered",
+            event: event.event_name(),
+        })
+    }
+}
+
+/// Body returned by POST /api/events/batch
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub ok: bool,
+
+    /// Number of events broadcast
+    pub triggered: usize,
+}
+
+impl BatchResponse {
+    /// Response for a successfully broadcast batch
+    pub fn triggered(triggered: usize) -> Json<Self> {
+        Json(Self { ok: true, triggered })
+    }
+}
+
+/// Result for one event of a POST /api/events request
+#[derive(Debug, Serialize)]
+pub struct EventResult {
+    /// Position of the event in the request
+    pub index: usize,
+
+    /// Whether the event is valid
+    pub ok: bool,
+
+    /// Name of the event type, for a valid event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event: Option<&'static str>,
+
+    /// Why the event is invalid, and the field at fault if there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl EventResult {
+    fn valid(index: usize, event: &GameEvent) -> Self {
+        Self {
+            index,
+            ok: true,
+            event: Some(event.event_name()),
+            error: None,
+            field: None,
+        }
+    }
+
+    fn invalid(index: usize, error: ApiError) -> Self {
+        Self {
+            index,
+            ok: false,
+            event: None,
+            error: Some(error.error),
+            field: error.field,
+        }
+    }
+}
+
+/// Body returned by POST /api/events
+///
+/// `ok` is true only if every event was valid and all of them were
+/// broadcast; otherwise none were.
+#[derive(Debug, Serialize)]
+pub struct EventsResponse {
+    pub ok: bool,
+
+    /// Number of events broadcast
+    pub triggered: usize,
+
+    /// One result per event, in request order
+    pub results: Vec<EventResult>,
+}
+
+impl IntoResponse for EventsResponse {
+    fn into_response(self) -> Response {
+        let status = if self.ok {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+/// Body returned by POST and DELETE /api/events/schedule
+#[derive(Debug, Serialize)]
+pub struct ScheduleResponse {
+    pub ok: bool,
+
+    /// ID to cancel the event with via DELETE /api/events/schedule/:id
+    pub scheduled_id: Uuid,
+}
+
+impl ScheduleResponse {
+    /// Response naming a scheduled (or just cancelled) event
+    pub fn scheduled(scheduled_id: Uuid) -> Json<Self> {
+        Json(Self {
+            ok: true,
+            scheduled_id,
+        })
+    }
+}
+
+/// Body returned by GET /api/health
+///
+/// Serialized as `{"status": "ok", "clients": 2, "queue_depth": 17}` or
+/// `{"status": "degraded"}`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Health {
+    /// Events can be broadcast
+    Ok {
+        /// Number of connected SSE clients
+        clients: usize,
+
+        /// Number of events in the history buffer
+        queue_depth: usize,
+    },
+
+    /// The broadcast channel is closed, so no events reach clients
+    Degraded,
+}
+
+impl IntoResponse for Health {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Health::Ok { .. } => StatusCode::OK,
+            Health::Degraded => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        (status, Json(self)).into_response()
+    }
+}
+
+/// Error returned by the API endpoints
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    error: String,
+    field: Option<String>,
+    max: Option<u64>,
+    holder: Option<String>,
+    message: Option<String>,
+}
+
+impl ApiError {
+    /// 422 Unprocessable Entity for an invalid field
+    pub fn invalid(field: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            error: error.into(),
+            field: Some(field.into()),
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// Name of the invalid field, if the error is about one
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Reports the largest value (or length) the invalid field accepts
+    fn with_max(mut self, max: u64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Adds a human-readable description next to a machine-readable error code
+    fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Names the team holding what the request conflicts with
+    pub fn with_holder(mut self, holder: impl Into<String>) -> Self {
+        self.holder = Some(holder.into());
+        self
+    }
+
+    /// Prefixes the field with the index of the batch element it belongs to
+    fn at_index(mut self, index: usize) -> Self {
+        self.field = Some(match self.field {
+            Some(field) => format!("[{}].{}", index, field),
+            None => format!("[{}]", index),
+        });
+        self
+    }
+
+    /// Prefixes the field with the name of the object it is nested in
+    pub fn within(mut self, parent: &str) -> Self {
+        self.field = Some(match self.field {
+            Some(field) => format!("{}.{}", parent, field),
+            None => parent.to_string(),
+        });
+        self
+    }
+
+    /// 404 Not Found
+    pub fn not_found(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 401 Unauthorized for a request without credentials
+    pub fn unauthorized(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 403 Forbidden for credentials that are not accepted
+    pub fn forbidden(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::FORBIDDEN,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 409 Conflict for a request that doesn't fit the current state
+    pub fn conflict(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::CONFLICT,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 413 Payload Too Large for a request holding more than `max` items
+    pub fn payload_too_large(error: impl Into<String>, max: u64) -> Self {
+        Self {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            error: error.into(),
+            field: None,
+            max: Some(max),
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 429 Too Many Requests for a client over its rate limit
+    pub fn too_many_requests(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+
+    /// 400 Bad Request for a body that could not be read or parsed
+    pub fn bad_request(error: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            error: error.into(),
+            field: None,
+            max: None,
+            holder: None,
+            message: None,
+        }
+    }
+}
+
+/// JSON form of an `ApiError`
+#[derive(Debug, Serialize)]
+pub struct ApiErrorBody {
+    ok: bool,
+    error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    holder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl From<ApiError> for ApiErrorBody {
+    fn from(error: ApiError) -> Self {
+        Self {
+            ok: false,
+            error: error.error,
+            field: error.field,
+            max: error.max,
+            holder: error.holder,
+            message: error.message,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        (status, Json(ApiErrorBody::from(self))).into_response()
+    }
+}
+
+/// Rewrites error responses that are not JSON into an `ApiError` body
+///
+/// Covers errors produced outside the handlers: axum's extractor rejections
+/// (bad query strings or path parameters), unknown routes, wrong methods,
+/// and the rate limiter. The status code and other headers are kept.
+pub async fn handle_json_rejection(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let text = axum::body::to_bytes(body, MAX_REJECTION_BODY)
+        .await
+        .map(|bytes| String::from_utf8_lossy(&bytes).trim().to_string())
+        .unwrap_or_default();
+    let error = if text.is_empty() {
+        status.canonical_reason().unwrap_or("Request failed").to_string()
+    } else {
+        text
+    };
+
+    let mut rewritten = ApiError {
+        status,
+        error,
+        field: None,
+        max: None,
+        holder: None,
+        message: None,
+    }
+    .into_response();
+    for (name, value) in &parts.headers {
+        if name != header::CONTENT_TYPE && name != header::CONTENT_LENGTH {
+            rewritten.headers_mut().insert(name, value.clone());
+        }
+    }
+    rewritten
+}
+
+/// Longest error body `handle_json_rejection` reads before giving up on it
+const MAX_REJECTION_BODY: usize = 64 * 1024;
+
+// ============================================================================
+// Extraction
+// ============================================================================
+
+/// Request types that can check their own fields
+pub trait Validate {
+    /// Returns an error describing the first invalid field
+    fn validate(&self) -> Result<(), ApiError>;
+}
+
+/// JSON body extractor that validates the request
+///
+/// Unlike `axum::Json`, errors are reported as `ApiError`s: malformed JSON
+/// gives 400, and missing fields, wrong types, unknown enum values, or
+/// failed validation give 422 with the offending field.
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let bytes = Bytes::from_request(req, state)
+            .await
+            .map_err(|e| ApiError::bad_request(e.body_text()))?;
+
+        let deserializer = &mut serde_json::Deserializer::from_slice(&bytes);
+        parse_validated(deserializer).map(ApiJson)
+    }
+}
+
+/// Deserializes a request and validates it
+///
+/// Malformed JSON gives 400; missing fields, wrong types, unknown enum values,
+/// or failed validation give 422 with the offending field.
+pub fn parse_validated<'de, T, D>(deserializer: D) -> Result<T, ApiError>
+where
+    T: Deserialize<'de> + Validate,
+    D: serde::Deserializer<'de, Error = serde_json::Error>,
+{
+    let value: T = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        let inner = e.into_inner();
+        if inner.is_data() {
+            ApiError::invalid(error_field(&path, &inner), inner.to_string())
+        } else {
+            ApiError::bad_request(format!("Invalid JSON: {}", inner))
+        }
+    })?;
+
+    value.validate()?;
+    Ok(value)
+}
+
+/// Name of the field a deserialization error refers to
+///
+/// Missing fields are reported at their parent's path, so the name is taken
+/// from serde's "missing field `name`" message instead.
+fn error_field(path: &str, error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let missing = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next());
+
+    match (missing, path) {
+        (Some(name), ".") => name.to_string(),
+        (Some(name), parent) => format!("{}.{}", parent, name),
+        (None, path) => path.to_string(),
+    }
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Requires a non-blank team name of at most `MAX_TEAM_LENGTH` characters
+fn validate_team(team: &str) -> Result<(), ApiError> {
+    if team.trim().is_empty() {
+        return Err(ApiError::invalid("team", "team must not be empty"));
+    }
+    validate_length_at_most("team", team, MAX_TEAM_LENGTH)
+}
+
+/// Requires a free-text field to be at most `MAX_TEXT_LENGTH` characters
+fn validate_length(field: &str, value: &str) -> Result<(), ApiError> {
+    validate_length_at_most(field, value, MAX_TEXT_LENGTH)
+}
+
+/// Requires a text field to be at most `max` characters
+fn validate_length_at_most(field: &str, value: &str, max: usize) -> Result<(), ApiError> {
+    if value.chars().count() > max {
+        return Err(ApiError::invalid(
+            field,
+            format!("{} must be at most {} characters", field, max),
+        )
+        .with_max(max as u64));
+    }
+    Ok(())
+}
+
+/// Requires an optional free-text field to be at most `MAX_TEXT_LENGTH` characters
+fn validate_optional_length(field: &str, value: Option<&str>) -> Result<(), ApiError> {
+    value.map_or(Ok(()), |value| validate_length(field, value))
+}
+
+/// Requires a building ID, when given, to be one the dashboard knows
+fn validate_building_id(building_id: Option<u32>) -> Result<(), ApiError> {
+    match building_id {
+        Some(id) if !(1..=MAX_BUILDING_ID).contains(&id) => {
+            Err(ApiError::invalid("building_id", "invalid_building_id")
+                .with_message(format!("building_id must be between 1 and {}", MAX_BUILDING_ID))
+                .with_max(MAX_BUILDING_ID.into()))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Validate for BarrierBrokenRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_team(&self.team)?;
+        validate_optional_length("message", self.message.as_deref())
+    }
+}
+
+impl Validate for BarrierRepairedRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        self.team.as_deref().map_or(Ok(()), validate_team)
+    }
+}
+
+impl Validate for LedDisplayBrokenRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_team(&self.team)?;
+        validate_optional_length("message", self.message.as_deref())
+    }
+}
+
+impl Validate for ScadaCompromisedRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_building_id(self.building_id)?;
+        validate_team(&self.team)?;
+        validate_optional_length("message", self.message.as_deref())
+    }
+}
+
+impl Validate for ScadaRestoredRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_building_id(self.building_id)
+    }
+}
+
+impl Validate for BuildingFireRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_building_id(Some(self.building_id))?;
+        match self.intensity {
+            Some(intensity) if !(0.0..=1.0).contains(&intensity) => Err(ApiError::invalid(
+                "intensity",
+                "intensity must be between 0 and 1",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Validate for BuildingFireExtinguishRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_building_id(Some(self.building_id))
+    }
+}
+
+impl Validate for PowerRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.district.trim().is_empty() {
+            return Err(ApiError::invalid("district", "district must not be empty"));
+        }
+        validate_length("district", &self.district)
+    }
+}
+
+impl Validate for RoadRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.road_id > MAX_ROAD_ID {
+            return Err(ApiError::invalid(
+                "road_id",
+                format!("road_id must be between 0 and {}", MAX_ROAD_ID),
+            )
+            .with_max(MAX_ROAD_ID as u64));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for WeatherChangeRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !(0.0..=1.0).contains(&self.rain_intensity) {
+            return Err(ApiError::invalid(
+                "rain_intensity",
+                "rain_intensity must be between 0 and 1",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for GreenWaveRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        match self.speed {
+            Some(speed) if !(speed > 0.0 && speed <= MAX_GREEN_WAVE_SPEED as f32) => Err(
+                ApiError::invalid(
+                    "speed",
+                    format!("speed must be above 0 and at most {}", MAX_GREEN_WAVE_SPEED),
+                )
+                .with_max(MAX_GREEN_WAVE_SPEED.into()),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Validate for EmergencyStopRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_length("reason", &self.reason)
+    }
+}
+
+impl Validate for DangerModeRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_length("reason", &self.reason)
+    }
+}
+
+impl Validate for TeamScoreUpdateRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_team(&self.team)
+    }
+}
+
+impl Validate for TimerStartRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if !(1..=MAX_TIMER_DURATION_SECS).contains(&self.duration_secs) {
+            return Err(ApiError::invalid(
+                "duration_secs",
+                format!(
+                    "duration_secs must be between 1 and {}",
+                    MAX_TIMER_DURATION_SECS
+                ),
+            )
+            .with_max(MAX_TIMER_DURATION_SECS.into()));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for LogMessageRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        validate_length("message", &self.message)
+    }
+}
+
+impl Validate for EventRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        match self {
+            EventRequest::BarrierBreak(req) => req.validate(),
+            EventRequest::BarrierRepair(req) => req.validate(),
+            EventRequest::LedBreak(req) => req.validate(),
+            EventRequest::ScadaCompromise(req) => req.validate(),
+            EventRequest::ScadaRestore(req) => req.validate(),
+            EventRequest::BuildingFire(req) => req.validate(),
+            EventRequest::BuildingFireExtinguish(req) => req.validate(),
+            EventRequest::PowerOutage(req) | EventRequest::PowerRestore(req) => req.validate(),
+            EventRequest::RoadClose(req) | EventRequest::RoadOpen(req) => req.validate(),
+            EventRequest::WeatherChange(req) => req.validate(),
+            EventRequest::GreenWave(req) => req.validate(),
+            EventRequest::EmergencyStart(req) => req.validate(),
+            EventRequest::DangerActivate(req) => req.validate(),
+            EventRequest::Log(req) => req.validate(),
+            EventRequest::LedRepair
+            | EventRequest::EmergencyStop
+            | EventRequest::DangerDeactivate => Ok(()),
+        }
+    }
+}
+
+/// Body of POST /api/events/batch: a JSON array of tagged events
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct BatchRequest {
+    pub events: Vec<EventRequest>,
+}
+
+impl Validate for BatchRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.events.len() > MAX_BATCH_SIZE {
+            return Err(ApiError::invalid(
+                "events",
+                format!("a batch may contain at most {} events", MAX_BATCH_SIZE),
+            )
+            .with_max(MAX_BATCH_SIZE as u64));
+        }
+        for (index, event) in self.events.iter().enumerate() {
+            event.validate().map_err(|e| e.at_index(index))?;
+        }
+        Ok(())
+    }
+}
+
+/// Body of POST /api/events: `{"events": [...]}` with tagged events
+///
+/// The events are kept as raw JSON so each one can be checked on its own
+/// by `check_events` and get its own result.
+#[derive(Debug, Deserialize)]
+pub struct EventsRequest {
+    pub events: Vec<serde_json::Value>,
+}
+
+impl Validate for EventsRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        // The size limit is configurable, so the handler enforces it
+        Ok(())
+    }
+}
+
+/// Parses and validates every event of a POST /api/events request
+///
+/// Each event is checked with the same rules as a batch element, and so
+/// as its single-event endpoint.
+///
+/// # Returns
+/// A result per event, and the events to broadcast if every one is valid
+pub fn check_events(events: Vec<serde_json::Value>) -> (Vec<EventResult>, Option<Vec<GameEvent>>) {
+    let mut results = Vec::with_capacity(events.len());
+    let mut valid = Vec::with_capacity(events.len());
+    for (index, value) in events.into_iter().enumerate() {
+        match parse_validated::<EventRequest, _>(value) {
+            Ok(request) => {
+                let event = GameEvent::from(request);
+                results.push(EventResult::valid(index, &event));
+                valid.push(event);
+            }
+            Err(error) => results.push(EventResult::invalid(index, error)),
+        }
+    }
+    let all_valid = valid.len() == results.len();
+    (results, all_valid.then_some(valid))
+}
+
+/// Body of POST /api/events/schedule: one tagged event and when to send it
+#[derive(Debug, Deserialize)]
+pub struct ScheduleRequest {
+    /// Seconds to wait before applying and broadcasting the event
+    pub delay_secs: u64,
+
+    /// Event in the same format as a batch element
+    pub event: EventRequest,
+}
+
+impl Validate for ScheduleRequest {
+    fn validate(&self) -> Result<(), ApiError> {
+        if self.delay_secs > MAX_SCHEDULE_DELAY_SECS {
+            return Err(ApiError::invalid(
+                "delay_secs",
+                format!("delay_secs must be at most {}", MAX_SCHEDULE_DELAY_SECS),
+            )
+            .with_max(MAX_SCHEDULE_DELAY_SECS));
+        }
+        self.event.validate().map_err(|e| e.within("event"))
+    }
+}
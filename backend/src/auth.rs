@@ -0,0 +1,206 @@
+//! API key authentication
+//!
+//! Bearer-token check applied to the event-triggering endpoints. Keys are
+//! loaded once at startup from `API_KEYS`, a comma-separated list of
+//! `name:key` pairs. A request without an `Authorization: Bearer <key>` header
+//! is rejected with 401 Unauthorized, and one carrying a key that is not in the
+//! list (unknown or revoked) with 403 Forbidden. With no keys configured the
+//! check is disabled.
+
+use crate::api::ApiError;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::future::Future;
+use std::sync::Arc;
+use tracing::warn;
+
+tokio::task_local! {
+    /// Name of the key that authorized the request being handled
+    static KEY_NAME: String;
+}
+
+/// A named API key
+#[derive(Debug, Clone)]
+struct ApiKey {
+    name: String,
+    key: String,
+}
+
+/// The set of API keys accepted by the event-triggering endpoints
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: Vec<ApiKey>,
+}
+
+impl ApiKeys {
+    /// Parses a comma-separated list of `name:key` pairs
+    ///
+    /// Surrounding whitespace is ignored. Entries without a name or key are
+    /// skipped with a warning.
+    pub fn parse(spec: &str) -> Self {
+        let keys = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| match entry.split_once(':') {
+                Some((name, key)) if !name.trim().is_empty() && !key.trim().is_empty() => {
+                    Some(ApiKey {
+                        name: name.trim().to_string(),
+                        key: key.trim().to_string(),
+                    })
+                }
+                _ => {
+                    warn!("Ignoring API key entry without a name: expected name:key");
+                    None
+                }
+            })
+            .collect();
+        Self { keys }
+    }
+
+    /// Whether no keys are configured, i.e. authentication is disabled
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Names of the configured keys
+    pub fn names(&self) -> Vec<&str> {
+        self.keys.iter().map(|key| key.name.as_str()).collect()
+    }
+
+    /// Looks up the name of a key
+    ///
+    /// # Returns
+    /// The key's name, or None if the key is unknown
+    pub fn lookup(&self, key: &str) -> Option<&str> {
+        self.keys
+            .iter()
+            .find(|candidate| constant_time_eq(candidate.key.as_bytes(), key.as_bytes()))
+            .map(|candidate| candidate.name.as_str())
+    }
+}
+
+/// Compares two byte strings without exiting early on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Extracts the token from an `Authorization: Bearer <token>` header
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    let token = token.trim();
+    (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+}
+
+/// Name of the key that authorized the current request, if any
+///
+/// Only set inside requests that passed `require_api_key`, and inside tasks
+/// started with `with_key_name`.
+pub fn current_key_name() -> Option<String> {
+    KEY_NAME.try_with(Clone::clone).ok()
+}
+
+/// Runs `future` with `name` as the current key name, if there is one
+///
+/// Used to keep the key name for work a request hands off to another task.
+pub async fn with_key_name<F: Future>(name: Option<String>, future: F) -> F::Output {
+    match name {
+        Some(name) => KEY_NAME.scope(name, future).await,
+        None => future.await,
+    }
+}
+
+/// Why a request's API key was not accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// No `Authorization: Bearer <key>` header
+    MissingKey,
+
+    /// A key that is not in the list
+    UnknownKey,
+}
+
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::MissingKey => {
+                ApiError::unauthorized("Missing API key: expected Authorization: Bearer <key>")
+            }
+            AuthError::UnknownKey => ApiError::forbidden("Unknown or revoked API key"),
+        }
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::MissingKey => {
+                ([(header::WWW_AUTHENTICATE, "Bearer")], ApiError::from(self)).into_response()
+            }
+            AuthError::UnknownKey => ApiError::from(self).into_response(),
+        }
+    }
+}
+
+/// Checks the API key in a request's headers
+///
+/// # Returns
+/// The name of the key, or None when no keys are configured
+pub fn authorize(keys: &ApiKeys, headers: &HeaderMap) -> Result<Option<String>, AuthError> {
+    if keys.is_empty() {
+        return Ok(None);
+    }
+    let token = bearer_token(headers).ok_or(AuthError::MissingKey)?;
+    keys.lookup(token)
+        .map(|name| Some(name.to_string()))
+        .ok_or(AuthError::UnknownKey)
+}
+
+/// Middleware rejecting requests without a valid API key
+pub async fn require_api_key(
+    State(keys): State<Arc<ApiKeys>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match authorize(&keys, request.headers()) {
+        Ok(name) => with_key_name(name, next.run(request)).await,
+        Err(error) => error.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_parse_named_keys() {
+        let keys = ApiKeys::parse(" red:abc123 , blue:def:456,,bad-entry,:nameless");
+        assert_eq!(keys.names(), ["red", "blue"]);
+        assert_eq!(keys.lookup("abc123"), Some("red"));
+        assert_eq!(keys.lookup("def:456"), Some("blue"));
+        assert_eq!(keys.lookup("abc12"), None);
+        assert_eq!(keys.lookup("bad-entry"), None);
+        assert!(ApiKeys::parse("").is_empty());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer abc123"));
+        assert_eq!(bearer_token(&headers), Some("abc123"));
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic abc123"));
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Bearer "));
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
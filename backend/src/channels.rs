@@ -0,0 +1,432 @@
+//! Event channels
+//!
+//! Exercises running side by side each use their own channel, so their
+//! dashboards only receive their own events:
+//! - `ChannelRegistry`: channels by name, opened on first use
+//! - `ChannelState`: extractor for the channel a request names
+//! - `OpenChannel`: extractor for the channel a client subscribes to
+//! - `resolve_channel`: middleware reading the channel name from a request
+//! - `collect_idle_channels`: background task removing unused channels
+//!
+//! Requests name their channel with a `channel` query parameter or, on POST
+//! requests with a JSON object body, a top-level `channel` field. Requests
+//! that name none use the "default" channel, which is never removed.
+//!
+//! Only subscribing and POST requests open a channel; other requests naming
+//! a channel that isn't open get 404 Not Found. At most `MAX_CHANNELS` are
+//! open at once.
+//!
+//! A channel opened again after it was removed starts with a fresh game state
+//! but carries on with the event IDs it had reached, so event IDs are never
+//! reused within a channel, in the event log or by `Last-Event-ID`.
+
+use crate::api::ApiError;
+use crate::clients::ClientRegistry;
+use crate::event_log::EventLog;
+use crate::metrics::Metrics;
+use crate::{AppState, Channel};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, FromRequestParts, Query, Request},
+    http::{header, request::Parts, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// Channel used by requests that don't name one
+pub const DEFAULT_CHANNEL: &str = "default";
+
+/// Maximum length (in characters) of a channel name
+pub const MAX_CHANNEL_NAME_LENGTH: usize = 64;
+
+/// Most channels open at once, the default one included
+pub const MAX_CHANNELS: usize = 100;
+
+/// How long a channel without subscribers is kept after its last activity
+pub const IDLE_CHANNEL_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// How often idle channels are looked for
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// JSON form of a channel, returned by GET /api/channels
+#[derive(Debug, Serialize)]
+pub struct ChannelSummary {
+    pub name: String,
+
+    /// Number of connected SSE and WebSocket clients
+    pub subscribers: usize,
+
+    /// Whole seconds since an event was last published or a client subscribed
+    pub idle_secs: u64,
+}
+
+/// Event channels by name
+pub struct ChannelRegistry {
+    channels: DashMap<String, Arc<Channel>>,
+
+    /// Last event ID of each removed channel, carried on if it is opened again
+    last_ids: DashMap<String, u64>,
+
+    /// Settings new channels are created with
+    history_capacity: usize,
+    broadcast_capacity: usize,
+    clients: ClientRegistry,
+    metrics: Arc<Metrics>,
+    event_log: Option<Arc<EventLog>>,
+}
+
+impl ChannelRegistry {
+    /// Creates a registry holding only the default channel
+    ///
+    /// # Arguments
+    /// * `history_capacity` - Maximum number of events kept in each channel's history
+    /// * `broadcast_capacity` - Number of events buffered for each SSE client
+    /// * `clients` - Registry of connected SSE clients
+    /// * `metrics` - Server metrics the channels' events are counted in
+    pub fn new(
+        history_capacity: usize,
+        broadcast_capacity: usize,
+        clients: ClientRegistry,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        let registry = Self {
+            channels: DashMap::new(),
+            last_ids: DashMap::new(),
+            history_capacity,
+            broadcast_capacity,
+            clients,
+            metrics,
+            event_log: None,
+        };
+        registry.get(DEFAULT_CHANNEL);
+        registry
+    }
+
+    /// Recreates the registry with `capacity` events buffered per SSE client
+    ///
+    /// Must be called before any client subscribes.
+    pub fn with_broadcast_capacity(self, capacity: usize) -> Self {
+        Self::new(self.history_capacity, capacity, self.clients, self.metrics)
+    }
+
+    /// Recreates the registry with every channel's events appended to `event_log`
+    ///
+    /// Must be called before any event is published.
+    pub fn with_event_log(self, event_log: Arc<EventLog>) -> Self {
+        let registry = Self {
+            channels: DashMap::new(),
+            event_log: Some(event_log),
+            ..self
+        };
+        registry.get(DEFAULT_CHANNEL);
+        registry
+    }
+
+    /// Writes out the events still queued for the event log and stops logging
+    pub async fn close_event_log(&self) {
+        if let Some(event_log) = &self.event_log {
+            event_log.close().await;
+        }
+    }
+
+    /// Returns the named channel, creating it if it doesn't exist yet
+    pub fn get(&self, name: &str) -> Arc<Channel> {
+        if let Some(channel) = self.channels.get(name) {
+            return channel.clone();
+        }
+        self.channels
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                info!("Opening channel '{}'", name);
+                let channel = Channel::new(
+                    name,
+                    self.history_capacity,
+                    self.broadcast_capacity,
+                    self.clients.clone(),
+                    self.metrics.clone(),
+                    self.event_log.clone(),
+                );
+                if let Some((_, last_id)) = self.last_ids.remove(name) {
+                    channel.history.lock().unwrap().restore(last_id, None, 0);
+                }
+                Arc::new(channel)
+            })
+            .clone()
+    }
+
+    /// Returns the named channel, or None if it isn't open
+    pub fn find(&self, name: &str) -> Option<Arc<Channel>> {
+        self.channels.get(name).map(|channel| channel.clone())
+    }
+
+    /// Returns the named channel, opening it if there is room for another
+    ///
+    /// # Returns
+    /// The channel, or 409 Conflict if `MAX_CHANNELS` are already open
+    pub fn open(&self, name: &str) -> Result<Arc<Channel>, ApiError> {
+        if let Some(channel) = self.find(name) {
+            return Ok(channel);
+        }
+        if self.channels.len() >= MAX_CHANNELS {
+            return Err(ApiError::conflict(format!(
+                "cannot open channel '{}': {} channels are already open",
+                name, MAX_CHANNELS
+            )));
+        }
+        Ok(self.get(name))
+    }
+
+    /// Every open channel, in no particular order
+    pub fn all(&self) -> Vec<Arc<Channel>> {
+        self.channels.iter().map(|channel| channel.clone()).collect()
+    }
+
+    /// Number of clients subscribed to any channel
+    pub fn subscriber_count(&self) -> usize {
+        self.channels
+            .iter()
+            .map(|channel| channel.event_tx.receiver_count())
+            .sum()
+    }
+
+    /// Returns the open channels, sorted by name
+    pub fn list(&self) -> Vec<ChannelSummary> {
+        let now = Instant::now();
+        let mut channels: Vec<_> = self
+            .channels
+            .iter()
+            .map(|channel| ChannelSummary {
+                name: channel.name.clone(),
+                subscribers: channel.event_tx.receiver_count(),
+                idle_secs: now
+                    .saturating_duration_since(*channel.last_active.lock().unwrap())
+                    .as_secs(),
+            })
+            .collect();
+        channels.sort_by(|a, b| a.name.cmp(&b.name));
+        channels
+    }
+
+    /// Removes every channel except the default one that has been idle for `timeout`
+    ///
+    /// Each removed channel's last event ID is kept for when it is opened again.
+    ///
+    /// # Returns
+    /// Names of the removed channels
+    pub fn remove_idle(&self, now: Instant, timeout: Duration) -> Vec<String> {
+        let mut removed = Vec::new();
+        self.channels.retain(|name, channel| {
+            let idle = name != DEFAULT_CHANNEL && channel.is_idle(now, timeout);
+            if idle {
+                self.last_ids.insert(name.clone(), channel.close());
+                removed.push(name.clone());
+            }
+            !idle
+        });
+        removed
+    }
+}
+
+/// Removes idle channels every minute until the server stops
+pub fn collect_idle_channels(state: Arc<AppState>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(IDLE_CHECK_INTERVAL);
+        loop {
+            ticks.tick().await;
+            for name in state.channels.remove_idle(Instant::now(), IDLE_CHANNEL_TIMEOUT) {
+                info!("Closed idle channel '{}'", name);
+            }
+        }
+    })
+}
+
+// ============================================================================
+// Extraction
+// ============================================================================
+
+/// Name of the channel a request is for, set by `resolve_channel`
+#[derive(Debug, Clone)]
+struct ChannelName(String);
+
+/// The `channel` query parameter or JSON field
+#[derive(Debug, Default, Deserialize)]
+struct ChannelField {
+    channel: Option<String>,
+}
+
+/// Requires a channel name of 1 to `MAX_CHANNEL_NAME_LENGTH` letters, digits,
+/// `-` or `_`
+fn validate_name(name: &str) -> Result<(), ApiError> {
+    if name.is_empty() || name.len() > MAX_CHANNEL_NAME_LENGTH {
+        return Err(ApiError::invalid(
+            "channel",
+            format!("channel must be 1 to {} characters", MAX_CHANNEL_NAME_LENGTH),
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(ApiError::invalid(
+            "channel",
+            "channel may only contain letters, digits, '-' and '_'",
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the channel a request names and records it for `ChannelState`
+///
+/// The query parameter is checked first. Without one, a POST request's JSON
+/// body is buffered to look for a `channel` field, and passed on unchanged.
+/// Bodies that are not JSON objects are left for the handler to reject.
+pub async fn resolve_channel(request: Request, next: Next) -> Response {
+    let query = Query::<ChannelField>::try_from_uri(request.uri())
+        .map(|Query(field)| field)
+        .unwrap_or_default();
+
+    let (request, name) = match query.channel {
+        Some(name) => (request, Some(name)),
+        None if request.method() == Method::POST && has_json_body(&request) => {
+            let (parts, body) = request.into_parts();
+            let bytes = match Bytes::from_request(Request::from_parts(parts.clone(), body), &())
+                .await
+            {
+                Ok(bytes) => bytes,
+                Err(rejection) => return rejection.into_response(),
+            };
+            let field: ChannelField = serde_json::from_slice(&bytes).unwrap_or_default();
+            (Request::from_parts(parts, bytes.into()), field.channel)
+        }
+        None => (request, None),
+    };
+
+    let mut request = request;
+    if let Some(name) = name {
+        if let Err(error) = validate_name(&name) {
+            return error.into_response();
+        }
+        request.extensions_mut().insert(ChannelName(name));
+    }
+    next.run(request).await
+}
+
+/// Checks if a request declares a JSON body
+fn has_json_body(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"))
+}
+
+/// Extractor for the channel a request names
+///
+/// POST requests open the channel if it doesn't exist yet; other requests
+/// for a channel that isn't open are rejected with 404 Not Found.
+pub struct ChannelState(pub Arc<Channel>);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for ChannelState {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let channel = match parts.extensions.get::<ChannelName>() {
+            Some(ChannelName(name)) if parts.method == Method::POST => state.channels.open(name)?,
+            Some(ChannelName(name)) => state
+                .channels
+                .find(name)
+                .ok_or_else(|| ApiError::not_found(format!("channel '{}' is not open", name)))?,
+            None => state.default_channel(),
+        };
+        Ok(ChannelState(channel))
+    }
+}
+
+/// Extractor for the channel a client subscribes to, opened if it doesn't
+/// exist yet
+pub struct OpenChannel(pub Arc<Channel>);
+
+#[async_trait]
+impl FromRequestParts<Arc<AppState>> for OpenChannel {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let channel = match parts.extensions.get::<ChannelName>() {
+            Some(ChannelName(name)) => state.channels.open(name)?,
+            None => state.default_channel(),
+        };
+        Ok(OpenChannel(channel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_only_unused_channels_are_removed() {
+        let registry = ChannelRegistry::new(
+            10,
+            10,
+            ClientRegistry::default(),
+            Arc::new(Metrics::new()),
+        );
+        let _rx = registry.get("watched").event_tx.subscribe();
+        registry.get("idle");
+
+        let names = |registry: &ChannelRegistry| -> Vec<String> {
+            registry.list().into_iter().map(|channel| channel.name).collect()
+        };
+        assert_eq!(names(&registry), ["default", "idle", "watched"]);
+
+        // Nothing has been idle long enough yet
+        assert!(registry.remove_idle(Instant::now(), IDLE_CHANNEL_TIMEOUT).is_empty());
+
+        let later = Instant::now() + IDLE_CHANNEL_TIMEOUT;
+        assert_eq!(registry.remove_idle(later, IDLE_CHANNEL_TIMEOUT), ["idle"]);
+        assert_eq!(names(&registry), ["default", "watched"]);
+    }
+
+    #[test]
+    fn test_channels_open_up_to_the_limit() {
+        let registry = ChannelRegistry::new(
+            10,
+            10,
+            ClientRegistry::default(),
+            Arc::new(Metrics::new()),
+        );
+        assert!(registry.find("alpha").is_none());
+        for index in 1..MAX_CHANNELS {
+            assert!(registry.open(&format!("channel-{}", index)).is_ok());
+        }
+
+        let error = registry.open("one-too-many").err().unwrap();
+        assert_eq!(error.into_response().status(), StatusCode::CONFLICT);
+        assert!(registry.find("one-too-many").is_none());
+        // Channels already open are still found
+        assert!(registry.open("channel-1").is_ok());
+    }
+
+    #[test]
+    fn test_channel_names_are_validated() {
+        assert!(validate_name("alpha-2_b").is_ok());
+        for name in ["", "has space", "ünïcode", &"x".repeat(MAX_CHANNEL_NAME_LENGTH + 1)] {
+            assert_eq!(validate_name(name).unwrap_err().field(), Some("channel"), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Registry of connected SSE clients
+//!
+//! Every SSE connection registers itself with some metadata when it opens and
+//! is removed again when its stream is dropped, so GET /api/clients can show
+//! how many dashboards are watching right now and where they connect from.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::info;
+use uuid::Uuid;
+
+/// Metadata recorded for a connected SSE client
+#[derive(Debug, Clone)]
+pub struct ClientInfo {
+    /// When the SSE stream was opened
+    pub connected_at: Instant,
+
+    /// Client IP address, if the server knows it
+    pub ip: Option<IpAddr>,
+
+    /// `User-Agent` header sent by the client
+    pub user_agent: Option<String>,
+}
+
+/// JSON form of a connected client, returned by GET /api/clients
+#[derive(Debug, Serialize)]
+pub struct ClientSummary {
+    pub id: Uuid,
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+
+    /// Whole seconds since the client connected
+    pub connected_secs: u64,
+}
+
+/// Connected SSE clients, keyed by a per-connection ID
+#[derive(Debug, Clone, Default)]
+pub struct ClientRegistry {
+    clients: Arc<Mutex<HashMap<Uuid, ClientInfo>>>,
+}
+
+impl ClientRegistry {
+    /// Registers a new client under a fresh ID
+    ///
+    /// # Returns
+    /// A guard that removes the client again when dropped; keep it alive for
+    /// as long as the client's stream is open
+    pub fn register(&self, info: ClientInfo) -> ClientGuard {
+        let id = Uuid::new_v4();
+        self.clients.lock().unwrap().insert(id, info);
+        ClientGuard {
+            id,
+            clients: self.clients.clone(),
+        }
+    }
+
+    /// Number of connected clients
+    pub fn len(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Returns the connected clients, longest-connected first
+    pub fn list(&self) -> Vec<ClientSummary> {
+        let clients = self.clients.lock().unwrap();
+        let mut entries: Vec<_> = clients.iter().collect();
+        entries.sort_by_key(|(_, info)| info.connected_at);
+
+        entries
+            .into_iter()
+            .map(|(id, info)| ClientSummary {
+                id: *id,
+                ip: info.ip,
+                user_agent: info.user_agent.clone(),
+                connected_secs: info.connected_at.elapsed().as_secs(),
+            })
+            .collect()
+    }
+}
+
+/// Keeps a client registered until dropped
+#[derive(Debug)]
+pub struct ClientGuard {
+    id: Uuid,
+    clients: Arc<Mutex<HashMap<Uuid, ClientInfo>>>,
+}
+
+impl ClientGuard {
+    /// ID the client is registered under
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        self.clients.lock().unwrap().remove(&self.id);
+        info!("SSE client {} disconnected", self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn client(connected_at: Instant, user_agent: &str) -> ClientInfo {
+        ClientInfo {
+            connected_at,
+            ip: Some(IpAddr::from([10, 0, 0, 1])),
+            user_agent: Some(user_agent.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_guard_removes_client_on_drop() {
+        let registry = ClientRegistry::default();
+        let first = registry.register(client(Instant::now(), "first"));
+        let second = registry.register(client(Instant::now(), "second"));
+        assert_ne!(first.id(), second.id());
+        assert_eq!(registry.len(), 2);
+
+        drop(first);
+        let remaining = registry.list();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, second.id());
+    }
+
+    #[test]
+    fn test_list_is_oldest_first() {
+        let registry = ClientRegistry::default();
+        let now = Instant::now();
+        let _newer = registry.register(client(now, "newer"));
+        let _older = registry.register(client(now - Duration::from_secs(90), "older"));
+
+        let list = registry.list();
+        assert_eq!(list[0].user_agent.as_deref(), Some("older"));
+        assert!(list[0].connected_secs >= 90);
+        assert_eq!(list[1].user_agent.as_deref(), Some("newer"));
+    }
+}
@@ -0,0 +1,108 @@
+//! CORS policy
+//!
+//! The origins browsers may call the API from are read once at startup from
+//! `CORS_ALLOWED_ORIGINS`, a comma-separated list of exact origins such as
+//! `https://ops.example.com,http://localhost:8000`. `*`, the default when the
+//! variable is not set, allows every origin, which is convenient during
+//! development. Either way only GET and POST requests with the `Content-Type`
+//! and `Authorization` headers are allowed, and `X-Request-Id` is exposed to
+//! scripts.
+
+use axum::http::{header, HeaderName, HeaderValue, Method, Uri};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Origins allowed to call the API from a browser
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Any origin
+    #[default]
+    Any,
+
+    /// Only these exact origins
+    List(Vec<HeaderValue>),
+}
+
+impl AllowedOrigins {
+    /// Parses `*` or a comma-separated list of origins
+    ///
+    /// Surrounding whitespace and empty entries are ignored.
+    ///
+    /// # Returns
+    /// The allowed origins, or a message naming the first entry that is not
+    /// an origin (`scheme://host[:port]`, without path or trailing slash)
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if spec.trim() == "*" {
+            return Ok(Self::Any);
+        }
+        let origins: Vec<_> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                parse_origin(entry).ok_or_else(|| {
+                    format!(
+                        "'{}' is not an origin: expected scheme://host[:port], e.g. https://example.com",
+                        entry
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        if origins.is_empty() {
+            return Err("no origins given: use * to allow every origin".to_string());
+        }
+        Ok(Self::List(origins))
+    }
+
+    /// Builds the CORS layer enforcing this policy
+    pub fn layer(&self) -> CorsLayer {
+        let allow_origin = match self {
+            Self::Any => AllowOrigin::any(),
+            Self::List(origins) => AllowOrigin::list(origins.iter().cloned()),
+        };
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST])
+            .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+            .expose_headers([HeaderName::from_static("x-request-id")])
+    }
+}
+
+/// Checks that an entry is an http(s) origin, as browsers send it
+fn parse_origin(entry: &str) -> Option<HeaderValue> {
+    let uri: Uri = entry.parse().ok()?;
+    let scheme_ok = matches!(uri.scheme_str(), Some("http" | "https"));
+    let bare = uri.path_and_query().is_none_or(|path| path == "/") && !entry.ends_with('/');
+    let host_ok = uri.authority().is_some_and(|authority| !authority.host().is_empty());
+    if !(scheme_ok && bare && host_ok) {
+        return None;
+    }
+    HeaderValue::from_str(entry).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_accepts_origins_and_rejects_anything_else() {
+        assert_eq!(AllowedOrigins::parse(" * "), Ok(AllowedOrigins::Any));
+        assert_eq!(
+            AllowedOrigins::parse("https://ops.example.com, http://localhost:8000,"),
+            Ok(AllowedOrigins::List(vec![
+                HeaderValue::from_static("https://ops.example.com"),
+                HeaderValue::from_static("http://localhost:8000"),
+            ]))
+        );
+
+        for spec in [
+            "ops.example.com",
+            "https://ops.example.com/",
+            "https://ops.example.com/dashboard",
+            "ftp://ops.example.com",
+            "https://ops.example.com,*",
+            " , ",
+        ] {
+            assert!(AllowedOrigins::parse(spec).is_err(), "{}", spec);
+        }
+    }
+}
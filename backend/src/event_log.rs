@@ -0,0 +1,239 @@
+//! Event log
+//!
+//! When `EVENT_LOG_PATH` is set, every broadcast event is appended to that
+//! file as one JSON line, so an exercise survives a server restart:
+//! - `EventLog`: hands events to a writer task, so publishing never waits on
+//!   the disk
+//! - The writer task buffers lines and flushes them every second, or sooner
+//!   once `FLUSH_EVERY_EVENTS` are waiting
+//! - `read_log`: reads the file back at startup to rebuild each channel's
+//!   game state and history
+//!
+//! Each line records the channel, sequence ID and broadcast time with the
+//! event:
+//!
+//! ```json
+//! {"channel":"default","id":3,"timestamp":1700000000000,"event":{"type":"led_display_repaired"}}
+//! ```
+
+use city_events::GameEvent;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{error, warn};
+
+/// Most events buffered before the log is flushed
+const FLUSH_EVERY_EVENTS: usize = 100;
+
+/// Longest time events stay buffered before the log is flushed
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggedEvent {
+    /// Channel the event was broadcast on
+    pub channel: String,
+
+    /// Sequence ID within the channel (same as the SSE event ID)
+    pub id: u64,
+
+    /// Milliseconds since the Unix epoch when the event was broadcast
+    pub timestamp: u64,
+
+    pub event: GameEvent,
+}
+
+/// Appends broadcast events to the log file from a background task
+pub struct EventLog {
+    /// Sender feeding the writer task, taken on `close`
+    tx: Mutex<Option<mpsc::UnboundedSender<LoggedEvent>>>,
+
+    /// The writer task, awaited on `close`
+    writer: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl EventLog {
+    /// Opens the log file for appending and starts the writer task
+    ///
+    /// The file is created if it doesn't exist. If the last line of an
+    /// existing file was cut off, new events start on a fresh line.
+    pub async fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)
+            .await?;
+        if !ends_with_newline(&mut file).await? {
+            file.write_all(b"\n").await?;
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(write_events(path, file, rx));
+        Ok(Self {
+            tx: Mutex::new(Some(tx)),
+            writer: Mutex::new(Some(writer)),
+        })
+    }
+
+    /// Queues an event to be written; does nothing once the log is closed
+    pub fn record(&self, entry: LoggedEvent) {
+        if let Some(tx) = self.tx.lock().unwrap().as_ref() {
+            let _ = tx.send(entry);
+        }
+    }
+
+    /// Writes out every queued event and stops the writer task
+    pub async fn close(&self) {
+        self.tx.lock().unwrap().take();
+        let writer = self.writer.lock().unwrap().take();
+        if let Some(writer) = writer {
+            let _ = writer.await;
+        }
+    }
+}
+
+/// Checks if a file is empty or ends with a line break
+async fn ends_with_newline(file: &mut File) -> io::Result<bool> {
+    if file.metadata().await?.len() == 0 {
+        return Ok(true);
+    }
+    file.seek(io::SeekFrom::End(-1)).await?;
+    let mut last = [0];
+    file.read_exact(&mut last).await?;
+    Ok(last[0] == b'\n')
+}
+
+/// Writes queued events to the log until every sender is gone
+async fn write_events(path: PathBuf, file: File, mut rx: mpsc::UnboundedReceiver<LoggedEvent>) {
+    let mut writer = BufWriter::new(file);
+    let mut unflushed = 0;
+    let mut ticks = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            entry = rx.recv() => {
+                let Some(entry) = entry else {
+                    break;
+                };
+                let mut line = match serde_json::to_vec(&entry) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        warn!("Failed to serialize event for the event log: {}", e);
+                        continue;
+                    }
+                };
+                line.push(b'\n');
+                if let Err(e) = writer.write_all(&line).await {
+                    error!("Cannot write to event log {}: {}", path.display(), e);
+                }
+                unflushed += 1;
+                if unflushed >= FLUSH_EVERY_EVENTS {
+                    flush(&path, &mut writer).await;
+                    unflushed = 0;
+                }
+            }
+            _ = ticks.tick(), if unflushed > 0 => {
+                flush(&path, &mut writer).await;
+                unflushed = 0;
+            }
+        }
+    }
+    flush(&path, &mut writer).await;
+}
+
+/// Flushes buffered lines to the file, logging failures
+async fn flush(path: &Path, writer: &mut BufWriter<File>) {
+    if let Err(e) = writer.flush().await {
+        error!("Cannot write to event log {}: {}", path.display(), e);
+    }
+}
+
+/// Reads every event from a log file, oldest first
+///
+/// Lines that can't be parsed, such as one cut off when the server stopped
+/// mid-write, are skipped with a warning.
+///
+/// # Returns
+/// The logged events, none if the file doesn't exist, or an error if it
+/// can't be read
+pub fn read_log(path: &Path) -> io::Result<Vec<LoggedEvent>> {
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(
+                "Skipping line {} of event log {}: {}",
+                index + 1,
+                path.display(),
+                e
+            ),
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logged(id: u64, message: &str) -> LoggedEvent {
+        LoggedEvent {
+            channel: "default".to_string(),
+            id,
+            timestamp: 1_700_000_000_000 + id,
+            event: GameEvent::LogMessage {
+                level: city_events::LogLevel::Info,
+                message: message.to_string(),
+            },
+        }
+    }
+
+    /// Path of a log file no other test uses
+    fn temp_log_path() -> PathBuf {
+        std::env::temp_dir().join(format!("event-log-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_events_written_are_read_back_skipping_corrupted_lines() {
+        let path = temp_log_path();
+        assert!(read_log(&path).unwrap().is_empty());
+
+        let log = EventLog::open(&path).await.unwrap();
+        log.record(logged(1, "a"));
+        log.record(logged(2, "b"));
+        log.close().await;
+        log.record(logged(3, "after close"));
+
+        // A line cut off mid-write, then more events after a restart
+        std::fs::write(
+            &path,
+            std::fs::read_to_string(&path).unwrap() + r#"{"channel":"default","id":3,"#,
+        )
+        .unwrap();
+        let log = EventLog::open(&path).await.unwrap();
+        log.record(logged(4, "d"));
+        log.close().await;
+
+        let entries = read_log(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(entries, [logged(1, "a"), logged(2, "b"), logged(4, "d")]);
+    }
+}
@@ -0,0 +1,76 @@
+//! Heartbeats
+//!
+//! The SSE keep-alive comments never reach a browser's `EventSource`
+//! listeners, so a quiet stream looks the same to a dashboard as a broken
+//! one. Every `HEARTBEAT_INTERVAL_SECS` (10 by default) each channel with
+//! clients connected sends them a `Heartbeat` event carrying the server's
+//! uptime, the channel's client count and a sequence number counting up
+//! from 1.
+//!
+//! Heartbeats are not events of the exercise: they are neither applied to
+//! the game state nor kept in the history or the event log. Channels without
+//! clients skip the beat, and pick up again once someone connects.
+
+use crate::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+
+/// Default time between heartbeats
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Sends a heartbeat on every channel with clients once per `period`
+///
+/// # Arguments
+/// * `state` - Application state holding the channels
+/// * `period` - Time between heartbeats
+pub fn send_heartbeats(state: Arc<AppState>, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let started = Instant::now();
+        let mut ticks = tokio::time::interval_at(started + period, period);
+        // A server too busy to beat on time beats once, not in a burst
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            let uptime_secs = started.elapsed().as_secs();
+            for channel in state.channels.all() {
+                channel.heartbeat(uptime_secs);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::DEFAULT_HISTORY_CAPACITY;
+    use city_events::GameEvent;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeats_count_up_only_while_clients_listen() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+        let task = send_heartbeats(state.clone(), DEFAULT_HEARTBEAT_INTERVAL);
+
+        // Nobody listening for the first three beats, so they are skipped
+        tokio::time::sleep(DEFAULT_HEARTBEAT_INTERVAL * 7 / 2).await;
+        let (_, mut rx) = channel.subscribe_with_replay(None);
+
+        let mut beats = Vec::new();
+        for _ in 0..2 {
+            match rx.recv().await.unwrap().event {
+                GameEvent::Heartbeat {
+                    uptime_secs,
+                    connected_clients,
+                    seq,
+                } => beats.push((uptime_secs, connected_clients, seq)),
+                other => panic!("expected a heartbeat, got {:?}", other),
+            }
+        }
+        assert_eq!(beats, [(40, 1, 1), (50, 1, 2)]);
+        // Kept out of the history, so reconnecting clients never replay them
+        assert_eq!(channel.history.lock().unwrap().len(), 0);
+        task.abort();
+    }
+}
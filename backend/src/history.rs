@@ -0,0 +1,218 @@
+//! Event history
+//!
+//! Ring buffer of recently broadcast events, each tagged with a sequence ID
+//! and the wall-clock time it was sent. Used to replay missed events to
+//! reconnecting SSE clients and to serve GET /api/events/history.
+
+use city_events::GameEvent;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default number of events kept in the history
+pub const DEFAULT_HISTORY_CAPACITY: usize = 500;
+
+/// One recorded event, as returned by GET /api/events/history
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    /// Sequence ID (same as the SSE event ID)
+    pub id: u64,
+
+    /// Milliseconds since the Unix epoch when the event was broadcast
+    pub timestamp: u64,
+
+    pub event: GameEvent,
+}
+
+/// Events returned for a `since` query, as returned by GET /api/events/history
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HistoryPage {
+    pub events: Vec<HistoryEntry>,
+
+    /// True if some events newer than `since` were already dropped from the
+    /// history, so `events` does not cover everything that was missed
+    pub truncated: bool,
+}
+
+/// Bounded history of broadcast events, oldest first
+pub struct EventHistory {
+    /// Sequence ID assigned to the next recorded event (starts at 1)
+    next_id: u64,
+
+    /// Maximum number of events kept
+    capacity: usize,
+
+    /// Recorded events as (sequence ID, event, broadcast time)
+    events: VecDeque<(u64, GameEvent, SystemTime)>,
+}
+
+impl EventHistory {
+    /// Creates an empty history keeping at most `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            next_id: 1,
+            capacity,
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records an event, dropping the oldest one if the history is full
+    ///
+    /// # Returns
+    /// The sequence ID assigned to the event
+    pub fn push(&mut self, event: GameEvent) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back((id, event, SystemTime::now()));
+
+        id
+    }
+
+    /// Records an event from before a restart under its original ID and time
+    ///
+    /// Events must be restored oldest first. The next event pushed gets the
+    /// ID after the restored one.
+    ///
+    /// # Arguments
+    /// * `id` - Sequence ID the event was broadcast with
+    /// * `event` - The event, or None to only skip past its ID
+    /// * `timestamp` - Milliseconds since the Unix epoch when it was broadcast
+    pub fn restore(&mut self, id: u64, event: Option<GameEvent>, timestamp: u64) {
+        self.next_id = self.next_id.max(id + 1);
+        let Some(event) = event else {
+            return;
+        };
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        let time = UNIX_EPOCH + Duration::from_millis(timestamp);
+        self.events.push_back((id, event, time));
+    }
+
+    /// Number of events currently kept
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns the recorded events newer than `since`, oldest first
+    ///
+    /// # Arguments
+    /// * `since` - Only include events with a greater sequence ID (None = all)
+    /// * `limit` - Maximum number of events to return (None = no limit)
+    pub fn entries(&self, since: Option<u64>, limit: Option<usize>) -> Vec<HistoryEntry> {
+        let since = since.unwrap_or(0);
+        self.events
+            .iter()
+            .filter(|(id, _, _)| *id > since)
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|(id, event, time)| HistoryEntry {
+                id: *id,
+                timestamp: unix_millis(*time),
+                event: event.clone(),
+            })
+            .collect()
+    }
+
+    /// Like `entries`, also reporting whether events newer than `since` have
+    /// already been dropped
+    ///
+    /// An ID that was never issued (e.g. one from before a server restart)
+    /// also counts as truncated, since the events after it are unknown.
+    pub fn page(&self, since: Option<u64>, limit: Option<usize>) -> HistoryPage {
+        let since_id = since.unwrap_or(0);
+        let oldest_id = self.events.front().map_or(self.next_id, |(id, _, _)| *id);
+        HistoryPage {
+            events: self.entries(since, limit),
+            truncated: since_id + 1 < oldest_id || since_id >= self.next_id,
+        }
+    }
+}
+
+impl Default for EventHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+/// Converts a wall-clock time to milliseconds since the Unix epoch
+pub fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(message: &str) -> GameEvent {
+        GameEvent::LogMessage {
+            level: city_events::LogLevel::Info,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_push_assigns_sequential_ids_and_drops_oldest() {
+        let mut history = EventHistory::new(2);
+        assert_eq!(history.push(log("a")), 1);
+        assert_eq!(history.push(log("b")), 2);
+        assert_eq!(history.push(log("c")), 3);
+
+        let ids: Vec<_> = history.entries(None, None).iter().map(|e| e.id).collect();
+        assert_eq!(ids, [2, 3]);
+    }
+
+    #[test]
+    fn test_entries_since_and_limit() {
+        let mut history = EventHistory::default();
+        for i in 0..5 {
+            history.push(log(&i.to_string()));
+        }
+
+        let ids: Vec<_> = history.entries(Some(2), Some(2)).iter().map(|e| e.id).collect();
+        assert_eq!(ids, [3, 4]);
+        assert!(history.entries(Some(5), None).is_empty());
+    }
+
+    #[test]
+    fn test_page_is_truncated_once_missed_events_are_dropped() {
+        let mut history = EventHistory::new(3);
+        for i in 0..5 {
+            history.push(log(&i.to_string()));
+        }
+
+        // Events 1 and 2 are gone, 3-5 are kept
+        let page = history.page(Some(1), None);
+        assert!(page.truncated);
+        let ids: Vec<_> = page.events.iter().map(|e| e.id).collect();
+        assert_eq!(ids, [3, 4, 5]);
+        assert!(history.page(None, None).truncated);
+
+        assert!(!history.page(Some(2), None).truncated);
+        assert!(!history.page(Some(5), None).truncated);
+        assert!(!EventHistory::new(3).page(None, None).truncated);
+
+        // Unknown future ID, e.g. from before a restart
+        assert!(history.page(Some(9), None).truncated);
+    }
+
+    #[test]
+    fn test_restored_events_keep_their_ids_and_times() {
+        let mut history = EventHistory::new(2);
+        history.restore(4, Some(log("a")), 1_000);
+        history.restore(5, Some(log("b")), 2_000);
+        history.restore(6, None, 3_000);
+        assert_eq!(history.push(log("c")), 7);
+
+        let entries = history.entries(None, None);
+        assert_eq!((entries[0].id, entries[0].timestamp), (5, 2_000));
+        assert_eq!(entries[1].id, 7);
+        assert!(!history.page(Some(5), None).truncated);
+    }
+}
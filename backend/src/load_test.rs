@@ -0,0 +1,473 @@
+//! Load testing endpoints
+//!
+//! Stress-testing tools for the broadcast channel. Compiled only with the
+//! `load-test` feature and served only when `LOAD_TEST_ENABLED=true` at
+//! startup; otherwise every route responds with 404.
+//!
+//! Provides:
+//! - GET /load-test/simulate-clients?count=N&duration_secs=D
+//! - POST /load-test/flood-events?count=N&event_type=log_message
+//! - GET /load-test/results
+
+use city_events::{GameEvent, LogLevel};
+use crate::history::unix_millis;
+use crate::{AppState, SequencedEvent};
+use axum::{
+    extract::{Query, Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{info, warn};
+
+/// Default SSE endpoint the simulated clients connect to
+const DEFAULT_TARGET_URL: &str = "http://127.0.0.1:3000/events";
+
+/// Upper bound on simulated clients per run
+const MAX_SIMULATED_CLIENTS: usize = 1000;
+
+/// Upper bound on events per flood run
+const MAX_FLOOD_EVENTS: usize = 100_000;
+
+// ============================================================================
+// State and Reports
+// ============================================================================
+
+/// State shared by the load-test routes
+struct LoadTestState {
+    /// Application state whose broadcast channel is being tested
+    app: Arc<AppState>,
+    /// Whether the routes are served, from `LOAD_TEST_ENABLED`
+    enabled: bool,
+    /// Statistics from the most recent runs
+    results: Mutex<LoadTestResults>,
+}
+
+/// Statistics from the most recent load-test runs
+#[derive(Debug, Clone, Default, Serialize)]
+struct LoadTestResults {
+    simulate_clients: Option<SimulateClientsReport>,
+    flood_events: Option<FloodEventsReport>,
+}
+
+/// Per-client statistics from a simulate-clients run
+#[derive(Debug, Clone, Serialize)]
+struct ClientStats {
+    client_id: usize,
+    events_received: u64,
+    /// Fraction of broadcast events this client never received (0.0 - 1.0)
+    drop_rate: f64,
+    /// Time until the SSE response headers arrived, if the client connected
+    connection_time_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Report for GET /load-test/simulate-clients
+#[derive(Debug, Clone, Serialize)]
+struct SimulateClientsReport {
+    client_count: usize,
+    duration_secs: u64,
+    /// Events broadcast while the clients were listening
+    events_broadcast: u64,
+    clients: Vec<ClientStats>,
+    average_events_received: f64,
+    average_drop_rate: f64,
+    average_connection_time_ms: f64,
+}
+
+/// Report for POST /load-test/flood-events
+#[derive(Debug, Clone, Serialize)]
+struct FloodEventsReport {
+    event_type: String,
+    count: usize,
+    elapsed_ms: f64,
+    events_per_sec: f64,
+}
+
+/// Query parameters for GET /load-test/simulate-clients
+#[derive(Debug, Deserialize)]
+struct SimulateClientsParams {
+    count: usize,
+    duration_secs: u64,
+}
+
+/// Query parameters for POST /load-test/flood-events
+#[derive(Debug, Deserialize)]
+struct FloodEventsParams {
+    count: usize,
+    #[serde(default = "default_flood_event_type")]
+    event_type: String,
+}
+
+fn default_flood_event_type() -> String {
+    "log_message".to_string()
+}
+
+// ============================================================================
+// Router
+// ============================================================================
+
+/// Builds the load-test router, to be nested under `/load-test`
+pub fn router(app: Arc<AppState>) -> Router {
+    router_with(app, load_test_enabled())
+}
+
+/// Builds the load-test router, serving its routes only if `enabled`
+fn router_with(app: Arc<AppState>, enabled: bool) -> Router {
+    let state = Arc::new(LoadTestState {
+        app,
+        enabled,
+        results: Mutex::new(LoadTestResults::default()),
+    });
+
+    Router::new()
+        .route("/simulate-clients", get(simulate_clients))
+        .route("/flood-events", post(flood_events))
+        .route("/results", get(results))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_enabled))
+        .with_state(state)
+}
+
+/// Returns true when `LOAD_TEST_ENABLED=true`
+fn load_test_enabled() -> bool {
+    std::env::var("LOAD_TEST_ENABLED").is_ok_and(|value| value == "true")
+}
+
+/// Hides every load-test route unless explicitly enabled
+async fn require_enabled(
+    State(state): State<Arc<LoadTestState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.enabled {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    next.run(request).await
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// GET /load-test/simulate-clients
+///
+/// Spawns `count` SSE clients that each listen for `duration_secs` seconds,
+/// then reports what every client received.
+async fn simulate_clients(
+    State(state): State<Arc<LoadTestState>>,
+    Query(params): Query<SimulateClientsParams>,
+) -> Response {
+    if params.count == 0 || params.count > MAX_SIMULATED_CLIENTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("count must be between 1 and {}", MAX_SIMULATED_CLIENTS),
+        )
+            .into_response();
+    }
+
+    let url = std::env::var("LOAD_TEST_TARGET_URL")
+        .unwrap_or_else(|_| DEFAULT_TARGET_URL.to_string());
+    let duration = Duration::from_secs(params.duration_secs);
+    let client = reqwest::Client::new();
+
+    info!(
+        "Load test: simulating {} SSE clients for {}s against {}",
+        params.count, params.duration_secs, url
+    );
+
+    // Count what the channel itself carries during the run, so each
+    // client's drop rate can be measured against it
+    let monitor = tokio::spawn(count_broadcast_events(
+        state.app.default_channel().event_tx.subscribe(),
+        duration,
+    ));
+
+    let handles: Vec<_> = (0..params.count)
+        .map(|client_id| {
+            let client = client.clone();
+            let url = url.clone();
+            tokio::spawn(run_simulated_client(client, url, client_id, duration))
+        })
+        .collect();
+
+    let mut clients = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(stats) => clients.push(stats),
+            Err(e) => warn!("Simulated client task failed: {}", e),
+        }
+    }
+
+    let events_broadcast = monitor.await.unwrap_or(0);
+    for stats in &mut clients {
+        stats.drop_rate = drop_rate(stats.events_received, events_broadcast);
+    }
+
+    let report = SimulateClientsReport {
+        client_count: params.count,
+        duration_secs: params.duration_secs,
+        events_broadcast,
+        average_events_received: average(clients.iter().map(|c| c.events_received as f64)),
+        average_drop_rate: average(clients.iter().map(|c| c.drop_rate)),
+        average_connection_time_ms: average(
+            clients
+                .iter()
+                .filter_map(|c| c.connection_time_ms.map(|ms| ms as f64)),
+        ),
+        clients,
+    };
+
+    state.results.lock().await.simulate_clients = Some(report.clone());
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// POST /load-test/flood-events
+///
+/// Broadcasts `count` events as fast as possible and measures throughput.
+/// The events update the game state like any other broadcast.
+async fn flood_events(
+    State(state): State<Arc<LoadTestState>>,
+    Query(params): Query<FloodEventsParams>,
+) -> Response {
+    if params.count == 0 || params.count > MAX_FLOOD_EVENTS {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("count must be between 1 and {}", MAX_FLOOD_EVENTS),
+        )
+            .into_response();
+    }
+
+    let Some(make_event) = flood_event_factory(&params.event_type) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Unsupported event_type: {}", params.event_type),
+        )
+            .into_response();
+    };
+
+    info!(
+        "Load test: flooding {} '{}' events",
+        params.count, params.event_type
+    );
+
+    // Apply and publish without Channel::broadcast's per-event logging,
+    // which would dominate the measurement
+    let channel = state.app.default_channel();
+    let start = Instant::now();
+    let mut game = channel.game.write().await;
+    for i in 0..params.count {
+        let event = make_event(i);
+        channel.apply(&mut game, &event, unix_millis(SystemTime::now()));
+        channel.publish(event);
+    }
+    drop(game);
+    let elapsed = start.elapsed();
+
+    let elapsed_secs = elapsed.as_secs_f64();
+    let report = FloodEventsReport {
+        event_type: params.event_type,
+        count: params.count,
+        elapsed_ms: elapsed_secs * 1000.0,
+        events_per_sec: if elapsed_secs > 0.0 {
+            params.count as f64 / elapsed_secs
+        } else {
+            params.count as f64
+        },
+    };
+
+    state.results.lock().await.flood_events = Some(report.clone());
+    (StatusCode::OK, Json(report)).into_response()
+}
+
+/// GET /load-test/results
+async fn results(State(state): State<Arc<LoadTestState>>) -> Response {
+    let results = state.results.lock().await.clone();
+    (StatusCode::OK, Json(results)).into_response()
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Connects one simulated client and counts SSE events until `duration` ends
+async fn run_simulated_client(
+    client: reqwest::Client,
+    url: String,
+    client_id: usize,
+    duration: Duration,
+) -> ClientStats {
+    let mut stats = ClientStats {
+        client_id,
+        events_received: 0,
+        drop_rate: 0.0,
+        connection_time_ms: None,
+        error: None,
+    };
+
+    let deadline = tokio::time::Instant::now() + duration;
+    let connect_start = Instant::now();
+
+    let mut response = match tokio::time::timeout_at(deadline, client.get(&url).send()).await {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            stats.error = Some(e.to_string());
+            return stats;
+        }
+        Err(_) => {
+            stats.error = Some("Timed out while connecting".to_string());
+            return stats;
+        }
+    };
+    stats.connection_time_ms = Some(connect_start.elapsed().as_millis() as u64);
+
+    // SSE frames can be split across chunks, so only count complete lines.
+    // Every broadcast event carries an `id:` line; the per-client greeting doesn't.
+    let mut buffer = String::new();
+    loop {
+        match tokio::time::timeout_at(deadline, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => {
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+                while let Some(newline) = buffer.find('\n') {
+                    if buffer[..newline].starts_with("id:") {
+                        stats.events_received += 1;
+                    }
+                    buffer.drain(..=newline);
+                }
+            }
+            Ok(Ok(None)) => {
+                stats.error = Some("Server closed the stream".to_string());
+                break;
+            }
+            Ok(Err(e)) => {
+                stats.error = Some(e.to_string());
+                break;
+            }
+            // Listening window is over
+            Err(_) => break,
+        }
+    }
+
+    stats
+}
+
+/// Counts the events carried by the broadcast channel for `duration`
+///
+/// Heartbeats have no `id:` line, so clients don't count them and neither
+/// does this. Events skipped while lagging are all counted, as their IDs
+/// are unknown; heartbeats are too rare to skew the drop rate.
+async fn count_broadcast_events(
+    mut rx: broadcast::Receiver<SequencedEvent>,
+    duration: Duration,
+) -> u64 {
+    let deadline = tokio::time::Instant::now() + duration;
+    let mut count = 0;
+
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Ok(SequencedEvent { id: Some(_), .. })) => count += 1,
+            Ok(Ok(SequencedEvent { id: None, .. })) => {}
+            Ok(Err(broadcast::error::RecvError::Lagged(skipped))) => count += skipped,
+            Ok(Err(broadcast::error::RecvError::Closed)) | Err(_) => break,
+        }
+    }
+
+    count
+}
+
+/// Builds the event factory for a flood run, or None if the type is unsupported
+fn flood_event_factory(event_type: &str) -> Option<fn(usize) -> GameEvent> {
+    match event_type {
+        "log_message" => Some(|i| GameEvent::LogMessage {
+            level: LogLevel::Info,
+            message: format!("Load test event #{}", i),
+        }),
+        "danger_mode_activated" => Some(|i| GameEvent::DangerModeActivated {
+            reason: format!("Load test event #{}", i),
+        }),
+        "emergency_stop" => Some(|i| GameEvent::EmergencyStop {
+            reason: format!("Load test event #{}", i),
+        }),
+        _ => None,
+    }
+}
+
+/// Fraction of broadcast events a client missed
+fn drop_rate(received: u64, broadcast: u64) -> f64 {
+    if broadcast == 0 {
+        return 0.0;
+    }
+    1.0 - (received.min(broadcast) as f64 / broadcast as f64)
+}
+
+/// Mean of the values, or 0.0 when there are none
+fn average(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::DEFAULT_HISTORY_CAPACITY;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    /// Sends a request to the load-test router and returns its status
+    async fn status(router: Router, method: &str, uri: &str) -> StatusCode {
+        let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        router.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_routes_are_hidden_unless_enabled() {
+        let app = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let router = router_with(app.clone(), false);
+        for (method, uri) in [
+            ("GET", "/results"),
+            ("POST", "/flood-events?count=1"),
+            ("GET", "/simulate-clients?count=1&duration_secs=1"),
+        ] {
+            assert_eq!(status(router.clone(), method, uri).await, StatusCode::NOT_FOUND);
+        }
+        assert_eq!(app.default_channel().history.lock().unwrap().len(), 0);
+
+        assert_eq!(status(router_with(app, true), "GET", "/results").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_counts_and_event_types_are_validated() {
+        let router = router_with(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), true);
+        let too_many_events = format!("/flood-events?count={}", MAX_FLOOD_EVENTS + 1);
+        let too_many_clients =
+            format!("/simulate-clients?count={}&duration_secs=1", MAX_SIMULATED_CLIENTS + 1);
+        for (method, uri) in [
+            ("POST", "/flood-events?count=0"),
+            ("POST", too_many_events.as_str()),
+            ("POST", "/flood-events?count=1&event_type=barrier_broken"),
+            ("GET", "/simulate-clients?count=0&duration_secs=1"),
+            ("GET", too_many_clients.as_str()),
+        ] {
+            let status = status(router.clone(), method, uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flooded_events_update_the_game_state() {
+        let app = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let router = router_with(app.clone(), true);
+        let uri = "/flood-events?count=3&event_type=emergency_stop";
+        assert_eq!(status(router, "POST", uri).await, StatusCode::OK);
+
+        let channel = app.default_channel();
+        assert!(channel.game.read().await.emergency_stop_active);
+        assert_eq!(channel.history.lock().unwrap().len(), 3);
+    }
+}
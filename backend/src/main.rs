@@ -4,99 +4,876 @@
 //! - SSE endpoint at GET /events for real-time event streaming
 //! - API endpoints for triggering events (POST /api/*)
 //! - Automatic event broadcasting to all connected clients
+//! - Separate channels for exercises running side by side (`?channel=alpha`)
+//! - WebSocket endpoint at GET /ws mirroring the SSE stream and taking commands
+//! - Prometheus metrics at GET /metrics
+//! - Heartbeat events while clients are connected, so they can tell a quiet
+//!   stream from a broken one
+//! - Per-building SCADA states at GET /api/scada, rejecting compromises of
+//!   held buildings and restores of working ones
+//! - OpenAPI description of every route at GET /api/openapi.json
 
-mod events;
+mod api;
+mod auth;
+mod channels;
+mod clients;
+mod cors;
+mod event_log;
+mod heartbeat;
+mod history;
+#[cfg(feature = "load-test")]
+mod load_test;
+mod metrics;
+mod rate_limit;
+mod scada;
+mod shutdown;
+mod ws;
 
 use axum::{
-    extract::State,
-    http::{header, StatusCode},
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware,
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse, Response,
     },
-    routing::{get, post},
-    Json, Router,
+    routing::{delete, get, post},
+    Extension, Json, Router,
 };
-use events::*;
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use api::{
+    ApiError, ApiJson, ApiResponse, BatchRequest, BatchResponse, EventsRequest, EventsResponse,
+    Health, ScheduleRequest, ScheduleResponse, DEFAULT_EVENTS_LIMIT,
+};
+use auth::ApiKeys;
+use axum_extra::TypedHeader;
+use channels::{ChannelRegistry, ChannelState, OpenChannel, DEFAULT_CHANNEL};
+use clients::{ClientInfo, ClientRegistry};
+use cors::AllowedOrigins;
+use city_events::*;
+use event_log::{EventLog, LoggedEvent};
+use history::{unix_millis, EventHistory, HistoryPage, DEFAULT_HISTORY_CAPACITY};
+use metrics::{Metrics, TrackedStream};
+use rate_limit::{RateLimiter, DEFAULT_RATE_LIMIT_PER_SEC};
+use scada::{ScadaBoard, ScadaChange, ScadaResponse};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, warn};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use tracing::{error, info, info_span, warn, Instrument, Level, Span};
+use uuid::Uuid;
+
+/// Default number of events buffered for SSE clients that fall behind
+const DEFAULT_BROADCAST_CAPACITY: usize = 100;
+
+/// Smallest and largest accepted `BROADCAST_CAPACITY`
+const MIN_BROADCAST_CAPACITY: usize = 10;
+const MAX_BROADCAST_CAPACITY: usize = 10_000;
+
+/// Fraction of the broadcast capacity a client may fall behind before a
+/// warning is logged; past the full capacity it starts missing events
+const BROADCAST_LAG_WARNING_RATIO: f64 = 0.8;
+
+/// An event tagged with its SSE event ID
+#[derive(Debug, Clone)]
+struct SequencedEvent {
+    /// Monotonically increasing event ID (starts at 1), or None for
+    /// heartbeats, which are not kept in the history
+    id: Option<u64>,
+    event: GameEvent,
+}
 
 /// Shared application state
-#[derive(Clone)]
 struct AppState {
-    /// Broadcast channel for sending events to all SSE clients
-    event_tx: broadcast::Sender<GameEvent>,
+    /// Event channels by name, one per exercise
+    channels: ChannelRegistry,
+
+    /// Connected SSE clients, for GET /api/clients
+    clients: ClientRegistry,
+
+    /// Keys accepted by the event-triggering endpoints (empty = no auth)
+    api_keys: Arc<ApiKeys>,
+
+    /// Origins browsers may call the API from
+    cors_origins: AllowedOrigins,
+
+    /// Counters and gauges served at GET /metrics
+    metrics: Arc<Metrics>,
+
+    /// Most events accepted in one POST /api/events request
+    events_limit: usize,
 }
 
 impl AppState {
-    fn new() -> Self {
-        // Create broadcast channel with capacity of 100 events
-        let (tx, _) = broadcast::channel(100);
-        Self { event_tx: tx }
+    /// Creates the application state with only the default channel
+    ///
+    /// # Arguments
+    /// * `history_capacity` - Maximum number of events kept in each channel's history
+    fn new(history_capacity: usize) -> Self {
+        let clients = ClientRegistry::default();
+        let metrics = Arc::new(Metrics::new());
+        Self {
+            channels: ChannelRegistry::new(
+                history_capacity,
+                DEFAULT_BROADCAST_CAPACITY,
+                clients.clone(),
+                metrics.clone(),
+            ),
+            clients,
+            api_keys: Arc::new(ApiKeys::default()),
+            cors_origins: AllowedOrigins::default(),
+            metrics,
+            events_limit: DEFAULT_EVENTS_LIMIT,
+        }
+    }
+
+    /// Buffers up to `capacity` events for each SSE client
+    ///
+    /// Must be called before any client subscribes.
+    fn with_broadcast_capacity(mut self, capacity: usize) -> Self {
+        self.channels = self.channels.with_broadcast_capacity(capacity);
+        self
+    }
+
+    /// Requires one of `api_keys` on the event-triggering endpoints
+    fn with_api_keys(mut self, api_keys: ApiKeys) -> Self {
+        self.api_keys = Arc::new(api_keys);
+        self
+    }
+
+    /// Only lets browsers call the API from `origins`
+    fn with_cors_origins(mut self, origins: AllowedOrigins) -> Self {
+        self.cors_origins = origins;
+        self
+    }
+
+    /// Accepts up to `limit` events in one POST /api/events request
+    fn with_events_limit(mut self, limit: usize) -> Self {
+        self.events_limit = limit;
+        self
+    }
+
+    /// Appends every event broadcast on any channel to `event_log`
+    ///
+    /// Must be called before any event is broadcast.
+    fn with_event_log(mut self, event_log: Arc<EventLog>) -> Self {
+        self.channels = self.channels.with_event_log(event_log);
+        self
+    }
+
+    /// Rebuilds the channels' game states and histories from logged events
+    ///
+    /// # Arguments
+    /// * `entries` - Events read from the event log, oldest first
+    async fn restore(&self, entries: Vec<LoggedEvent>) {
+        let mut by_channel: HashMap<String, Vec<LoggedEvent>> = HashMap::new();
+        for entry in entries {
+            by_channel.entry(entry.channel.clone()).or_default().push(entry);
+        }
+        for (name, entries) in by_channel {
+            self.channels.get(&name).restore(entries).await;
+        }
+    }
+
+    /// The channel requests use when they don't name one
+    fn default_channel(&self) -> Arc<Channel> {
+        self.channels.get(DEFAULT_CHANNEL)
+    }
+
+    /// Reports whether events can still be broadcast, with client and history counts
+    ///
+    /// Clients are counted across all channels; the history is the default
+    /// channel's.
+    fn health(&self) -> Health {
+        let channel = self.default_channel();
+        // The channel closes once the sender is gone; a fresh receiver reports it
+        if channel.event_tx.subscribe().is_closed() {
+            return Health::Degraded;
+        }
+        Health::Ok {
+            clients: self.channels.subscriber_count(),
+            queue_depth: channel.history.lock().unwrap().len(),
+        }
+    }
+}
+
+/// One exercise's event stream and game state
+///
+/// Each channel has its own broadcast sender, history and game state, so
+/// exercises running side by side never see each other's events or scores.
+struct Channel {
+    /// Name clients subscribe with, e.g. "default" or "alpha"
+    name: String,
+
+    /// Broadcast channel for sending events to the channel's SSE clients
+    event_tx: broadcast::Sender<SequencedEvent>,
+
+    /// Number of events the broadcast channel buffers per client
+    broadcast_capacity: usize,
+
+    /// Whether the slowest SSE client is past the lag warning threshold, so
+    /// the warning is logged once per episode rather than for every event
+    clients_lagging: AtomicBool,
+
+    /// Recent events, for Last-Event-ID replay and GET /api/events/history
+    history: Mutex<EventHistory>,
+
+    /// Connected SSE clients of every channel, for the broadcast log
+    clients: ClientRegistry,
+
+    /// Current game state, written by the API handlers
+    ///
+    /// Handlers hold the write lock while broadcasting, so a reader holding the
+    /// read lock sees a state that matches the events still to come.
+    game: RwLock<GameState>,
+
+    /// SCADA state of every building, updated along with the game state
+    scada: Mutex<ScadaBoard>,
+
+    /// Pending events from POST /api/events/schedule, by scheduled ID
+    scheduled: Mutex<HashMap<Uuid, JoinHandle<()>>>,
+
+    /// Task counting down the round timer, if one was started
+    ///
+    /// Held across the broadcasts that start and stop the timer, so
+    /// concurrent requests can't leave two countdowns running.
+    timer: tokio::sync::Mutex<Option<JoinHandle<()>>>,
+
+    /// Counters and gauges served at GET /metrics, shared by all channels
+    metrics: Arc<Metrics>,
+
+    /// When an event was last published or a client last subscribed
+    last_active: Mutex<Instant>,
+
+    /// File every published event is appended to, if `EVENT_LOG_PATH` is set
+    event_log: Option<Arc<EventLog>>,
+
+    /// Number of heartbeats sent to the channel's clients
+    heartbeats: AtomicU64,
+}
+
+impl Channel {
+    /// Creates an empty channel
+    ///
+    /// # Arguments
+    /// * `name` - Channel name
+    /// * `history_capacity` - Maximum number of events kept in the history
+    /// * `broadcast_capacity` - Number of events buffered for each SSE client
+    /// * `clients` - Registry of connected SSE clients
+    /// * `metrics` - Server metrics the channel's events are counted in
+    /// * `event_log` - File to append the channel's events to, if any
+    fn new(
+        name: &str,
+        history_capacity: usize,
+        broadcast_capacity: usize,
+        clients: ClientRegistry,
+        metrics: Arc<Metrics>,
+        event_log: Option<Arc<EventLog>>,
+    ) -> Self {
+        let (tx, _) = broadcast::channel(broadcast_capacity);
+        Self {
+            name: name.to_string(),
+            event_tx: tx,
+            broadcast_capacity,
+            clients_lagging: AtomicBool::new(false),
+            history: Mutex::new(EventHistory::new(history_capacity)),
+            clients,
+            game: RwLock::new(GameState::default()),
+            scada: Mutex::new(ScadaBoard::default()),
+            scheduled: Mutex::new(HashMap::new()),
+            timer: tokio::sync::Mutex::new(None),
+            metrics,
+            last_active: Mutex::new(Instant::now()),
+            event_log,
+            heartbeats: AtomicU64::new(0),
+        }
+    }
+
+    /// Marks the channel as in use now
+    fn touch(&self) {
+        *self.last_active.lock().unwrap() = Instant::now();
+    }
+
+    /// Checks if the channel can be removed
+    ///
+    /// A channel is idle once it has no subscribers, no pending scheduled
+    /// events, and has not been used for `timeout`.
+    fn is_idle(&self, now: Instant, timeout: Duration) -> bool {
+        self.event_tx.receiver_count() == 0
+            && self.scheduled.lock().unwrap().is_empty()
+            && now.saturating_duration_since(*self.last_active.lock().unwrap()) >= timeout
+    }
+
+    /// Marks in the event log that the channel is being removed
+    ///
+    /// A `GameReset` logged under the next sequence ID tells a restart that
+    /// the channel's state started over, as it does when the channel is
+    /// opened again. Nothing is sent to clients, since an idle channel has none.
+    ///
+    /// # Returns
+    /// Sequence ID of the channel's last event, including the reset
+    fn close(&self) -> u64 {
+        let mut history = self.history.lock().unwrap();
+        let id = history.push(GameEvent::GameReset);
+        if let Some(event_log) = &self.event_log {
+            event_log.record(LoggedEvent {
+                channel: self.name.clone(),
+                id,
+                timestamp: unix_millis(SystemTime::now()),
+                event: GameEvent::GameReset,
+            });
+        }
+        id
+    }
+
+    /// Records the event in the history under the next sequence ID and sends it
+    ///
+    /// The history lock is held while sending so that `subscribe_with_replay`
+    /// sees every event either in the history or on its new receiver, never both.
+    /// The event is queued for the event log, if there is one, in ID order.
+    ///
+    /// # Returns
+    /// Number of SSE clients that received the event
+    fn publish(&self, event: GameEvent) -> usize {
+        let mut history = self.history.lock().unwrap();
+        self.touch();
+        let id = history.push(event.clone());
+        self.metrics.record_event(event.event_name());
+        if let Some(event_log) = &self.event_log {
+            event_log.record(LoggedEvent {
+                channel: self.name.clone(),
+                id,
+                timestamp: unix_millis(SystemTime::now()),
+                event: event.clone(),
+            });
+        }
+        let receivers = match self.event_tx.send(SequencedEvent { id: Some(id), event }) {
+            Ok(receivers) => receivers,
+            Err(_) => {
+                self.metrics.record_broadcast_error();
+                0
+            }
+        };
+        self.check_client_lag();
+        receivers
     }
 
-    /// Broadcast an event to all connected SSE clients
+    /// Warns when an SSE client falls close to the broadcast capacity behind
+    ///
+    /// The sender's queue holds every event not yet received by all clients,
+    /// so its length is the backlog of the slowest client's receiver.
+    ///
+    /// # Returns
+    /// Whether a client is past the warning threshold
+    fn check_client_lag(&self) -> bool {
+        let backlog = self.event_tx.len();
+        let threshold = self.broadcast_capacity as f64 * BROADCAST_LAG_WARNING_RATIO;
+        let lagging = backlog as f64 > threshold;
+        if lagging && !self.clients_lagging.swap(true, Ordering::Relaxed) {
+            warn!(
+                "An SSE client is {} events behind (BROADCAST_CAPACITY is {}); it will miss events if it falls further behind",
+                backlog, self.broadcast_capacity
+            );
+        } else if !lagging {
+            self.clients_lagging.store(false, Ordering::Relaxed);
+        }
+        lagging
+    }
+
+    /// Broadcast an event to the channel's SSE clients
     fn broadcast(&self, event: GameEvent) {
-        match self.event_tx.send(event.clone()) {
-            Ok(receivers) => {
-                info!("Event broadcast to {} clients: {:?}", receivers, event);
+        let receivers = self.publish(event.clone());
+        let source = auth::current_key_name()
+            .map(|name| format!(" from key '{}'", name))
+            .unwrap_or_default();
+        if receivers > 0 {
+            info!(
+                "Event{} broadcast to {} clients on channel '{}' ({} dashboards connected): {:?}",
+                source,
+                receivers,
+                self.name,
+                self.clients.len(),
+                event
+            );
+        } else {
+            warn!("No active SSE clients on channel '{}' to receive event{}", self.name, source);
+        }
+    }
+
+    /// Sends a heartbeat to the channel's clients, if it has any
+    ///
+    /// The heartbeat bypasses the game state, history and event log, and
+    /// does not count as activity keeping an idle channel open.
+    ///
+    /// # Arguments
+    /// * `uptime_secs` - Whole seconds since the server started
+    ///
+    /// # Returns
+    /// The heartbeat's sequence number, or None if nobody was listening
+    fn heartbeat(&self, uptime_secs: u64) -> Option<u64> {
+        let connected_clients = self.event_tx.receiver_count();
+        if connected_clients == 0 {
+            return None;
+        }
+        let seq = self.heartbeats.fetch_add(1, Ordering::Relaxed) + 1;
+        let event = GameEvent::Heartbeat {
+            uptime_secs,
+            connected_clients,
+            seq,
+        };
+        // The clients may have left since they were counted
+        let _ = self.event_tx.send(SequencedEvent { id: None, event });
+        Some(seq)
+    }
+
+    /// Tells the channel's clients the server is shutting down
+    ///
+    /// SSE streams end, and WebSockets close, after this event.
+    fn announce_shutdown(&self) {
+        self.broadcast(shutdown::shutdown_event());
+    }
+
+    /// Applies an event to the game state and broadcasts it
+    ///
+    /// # Returns
+    /// Success response naming the event
+    async fn trigger(&self, event: GameEvent) -> Json<ApiResponse> {
+        let response = ApiResponse::triggered(&event);
+        self.trigger_all(vec![event]).await;
+        response
+    }
+
+    /// Builds an event from the current game state, then applies and broadcasts it
+    ///
+    /// The write lock is held from building the event to broadcasting it, so
+    /// concurrent requests cannot compute from the same stale state.
+    ///
+    /// # Returns
+    /// Success response naming the event
+    async fn trigger_with(
+        &self,
+        build: impl FnOnce(&GameState) -> GameEvent,
+    ) -> Json<ApiResponse> {
+        let mut game = self.game.write().await;
+        let event = build(&game);
+        let response = ApiResponse::triggered(&event);
+        self.apply(&mut game, &event, unix_millis(SystemTime::now()));
+        self.broadcast(event);
+        response
+    }
+
+    /// Checks a SCADA request against the buildings' states, then applies
+    /// and broadcasts the events it results in
+    ///
+    /// The write lock is held from the check to the broadcast, so two teams
+    /// racing for the same building cannot both win it.
+    ///
+    /// # Returns
+    /// Response with a result for every building, or 409 Conflict if no
+    /// building could change state
+    async fn trigger_scada(
+        &self,
+        check: impl FnOnce(&ScadaBoard) -> Result<ScadaChange, ApiError>,
+    ) -> Result<Json<ScadaResponse>, ApiError> {
+        let mut game = self.game.write().await;
+        let change = check(&self.scada.lock().unwrap())?;
+        let (events, response) = change.into_parts();
+        for event in events {
+            self.apply(&mut game, &event, unix_millis(SystemTime::now()));
+            self.broadcast(event);
+        }
+        Ok(response)
+    }
+
+    /// Applies an event to the game state and the SCADA building states
+    ///
+    /// # Arguments
+    /// * `game` - Game state, write-locked by the caller
+    /// * `event` - Event to apply
+    /// * `timestamp` - When the event was broadcast, in milliseconds since the Unix epoch
+    fn apply(&self, game: &mut GameState, event: &GameEvent, timestamp: u64) {
+        game.apply(event);
+        self.scada.lock().unwrap().apply(event, timestamp);
+    }
+
+    /// Applies and broadcasts events in order
+    ///
+    /// The game state write lock is held throughout, so no other request's
+    /// events are interleaved and state readers never see a partial batch.
+    ///
+    /// # Returns
+    /// Number of events broadcast
+    async fn trigger_all(&self, events: Vec<GameEvent>) -> usize {
+        let mut game = self.game.write().await;
+        let count = events.len();
+        for event in events {
+            self.apply(&mut game, &event, unix_millis(SystemTime::now()));
+            self.broadcast(event);
+        }
+        count
+    }
+
+    /// Applies and broadcasts an event after a delay
+    ///
+    /// The event stays cancellable until its delay has passed; once it starts
+    /// firing it is no longer listed as pending.
+    ///
+    /// # Returns
+    /// ID to cancel the event with
+    fn schedule(self: &Arc<Self>, delay: Duration, event: GameEvent) -> Uuid {
+        let id = Uuid::new_v4();
+        // Hold the lock until the handle is stored, so the task cannot look
+        // itself up before it has been inserted
+        let mut scheduled = self.scheduled.lock().unwrap();
+        let state = self.clone();
+        // The event is logged under the key that scheduled it
+        // and in the span of the request that scheduled it
+        let key_name = auth::current_key_name();
+        let fire = async move {
+            tokio::time::sleep(delay).await;
+            if state.scheduled.lock().unwrap().remove(&id).is_some() {
+                info!("Scheduled event {} firing", id);
+                state.trigger_all(vec![event]).await;
             }
-            Err(_) => {
-                warn!("No active SSE clients to receive event");
+        };
+        let task = tokio::spawn(auth::with_key_name(key_name, fire.instrument(Span::current())));
+        scheduled.insert(id, task);
+        id
+    }
+
+    /// Cancels a pending scheduled event
+    ///
+    /// # Returns
+    /// Whether the event was still pending
+    fn cancel_scheduled(&self, id: Uuid) -> bool {
+        match self.scheduled.lock().unwrap().remove(&id) {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancels every pending scheduled event
+    ///
+    /// # Returns
+    /// Number of events cancelled
+    fn cancel_all_scheduled(&self) -> usize {
+        let mut scheduled = self.scheduled.lock().unwrap();
+        let count = scheduled.len();
+        for (_, task) in scheduled.drain() {
+            task.abort();
+        }
+        count
+    }
+
+    /// Starts the round countdown, replacing any countdown already running
+    ///
+    /// Broadcasts a `TimerUpdate` now and every second after it, then
+    /// `TimerExpired` once the countdown reaches zero.
+    ///
+    /// # Returns
+    /// Success response naming the first `TimerUpdate`
+    async fn start_timer(self: &Arc<Self>, duration_secs: u32) -> Json<ApiResponse> {
+        let mut timer = self.timer.lock().await;
+        if let Some(task) = timer.take() {
+            task.abort();
+        }
+
+        let response = self
+            .trigger(GameEvent::TimerUpdate {
+                remaining_secs: duration_secs,
+                running: true,
+            })
+            .await;
+
+        let state = self.clone();
+        *timer = Some(tokio::spawn(async move {
+            let period = Duration::from_secs(1);
+            let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            for remaining_secs in (0..duration_secs).rev() {
+                ticks.tick().await;
+                let event = if remaining_secs == 0 {
+                    GameEvent::TimerExpired
+                } else {
+                    GameEvent::TimerUpdate {
+                        remaining_secs,
+                        running: true,
+                    }
+                };
+                state.trigger_all(vec![event]).await;
+            }
+        }));
+        response
+    }
+
+    /// Stops the round countdown, broadcasting a final `TimerUpdate` with
+    /// `running: false` and the time that was left
+    ///
+    /// # Returns
+    /// Success response, or None if no countdown was running
+    async fn stop_timer(&self) -> Option<Json<ApiResponse>> {
+        let mut timer = self.timer.lock().await;
+        let task = timer.take().filter(|task| !task.is_finished())?;
+        task.abort();
+
+        let response = self
+            .trigger_with(|game| GameEvent::TimerUpdate {
+                remaining_secs: game.timer_remaining_secs.unwrap_or(0),
+                running: false,
+            })
+            .await;
+        Some(response)
+    }
+
+    /// Rebuilds the game state and history from events logged before a restart
+    ///
+    /// Every event is applied to the game state, and the most recent ones
+    /// fill the history under their original IDs, so reconnecting clients
+    /// can still catch up. The shutdown announcement is left out of the
+    /// history, since replaying it would end the client's stream again.
+    ///
+    /// A round countdown that was still running when the log ends is
+    /// started again from the time it had left.
+    ///
+    /// # Arguments
+    /// * `entries` - The channel's logged events, oldest first
+    async fn restore(self: &Arc<Self>, entries: Vec<LoggedEvent>) {
+        let timer_remaining_secs = {
+            let mut game = self.game.write().await;
+            let mut history = self.history.lock().unwrap();
+            for entry in entries {
+                self.apply(&mut game, &entry.event, entry.timestamp);
+                let event = Some(entry.event).filter(|event| !shutdown::is_shutdown_event(event));
+                history.restore(entry.id, event, entry.timestamp);
             }
+            game.timer_remaining_secs
+        };
+        if let Some(remaining_secs) = timer_remaining_secs {
+            let _ = self.start_timer(remaining_secs).await;
         }
     }
+
+    /// Subscribes to live events and collects buffered events newer than `last_event_id`
+    ///
+    /// # Returns
+    /// Tuple of (events to replay, receiver for live events). The replay is
+    /// marked truncated if some missed events are no longer in the history.
+    fn subscribe_with_replay(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> (HistoryPage, broadcast::Receiver<SequencedEvent>) {
+        let history = self.history.lock().unwrap();
+        self.touch();
+        let rx = self.event_tx.subscribe();
+
+        let missed = match last_event_id {
+            Some(last_id) => history.page(Some(last_id), None),
+            None => HistoryPage::default(),
+        };
+
+        (missed, rx)
+    }
+}
+
+// ============================================================================
+// Last-Event-ID Header
+// ============================================================================
+
+static LAST_EVENT_ID: HeaderName = HeaderName::from_static("last-event-id");
+
+/// The `Last-Event-ID` header sent by reconnecting SSE clients
+#[derive(Debug, Clone, Copy)]
+struct LastEventId(u64);
+
+impl headers::Header for LastEventId {
+    fn name() -> &'static HeaderName {
+        &LAST_EVENT_ID
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        values
+            .next()
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(LastEventId)
+            .ok_or_else(headers::Error::invalid)
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(std::iter::once(HeaderValue::from(self.0)));
+    }
 }
 
 // ============================================================================
 // SSE Endpoint
 // ============================================================================
 
+/// Query parameters for the SSE endpoint
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    /// Comma-separated event types to subscribe to (default: all)
+    types: Option<String>,
+}
+
+/// Converts a game event into an SSE event, tagging it with its ID if it has one
+///
+/// The SSE `event` field carries the variant name (e.g. "BarrierBroken") so
+/// browser clients can attach typed `EventSource` listeners.
+fn to_sse_event(id: Option<u64>, event: &GameEvent) -> Option<Event> {
+    match serde_json::to_string(event) {
+        Ok(json) => {
+            let sse_event = Event::default().event(event.event_name()).data(json);
+            Some(match id {
+                Some(id) => sse_event.id(id.to_string()),
+                None => sse_event,
+            })
+        }
+        Err(e) => {
+            warn!("Failed to serialize event: {}", e);
+            None
+        }
+    }
+}
+
 /// SSE endpoint that streams events to clients
 ///
 /// GET /events
-async fn sse_handler(State(state): State<Arc<AppState>>) -> Sse<impl tokio_stream::Stream<Item = Result<Event, std::convert::Infallible>>> {
-    info!("New SSE client connected");
+/// GET /events?types=barrier_broken,scada_compromised
+/// GET /events?channel=alpha
+///
+/// Clients reconnecting with a `Last-Event-ID` header first receive every
+/// buffered event newer than that ID, then the live stream. If some of the
+/// missed events are no longer buffered, they get a fresh state snapshot
+/// instead of a partial replay.
+async fn sse_handler(
+    State(state): State<Arc<AppState>>,
+    OpenChannel(channel): OpenChannel,
+    Query(query): Query<EventsQuery>,
+    last_event_id: Option<TypedHeader<LastEventId>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    user_agent: Option<TypedHeader<headers::UserAgent>>,
+) -> Result<
+    Sse<impl tokio_stream::Stream<Item = Result<Event, std::convert::Infallible>>>,
+    ApiError,
+> {
+    // Validate the subscription filter before subscribing
+    let filter = match query.types.as_deref() {
+        Some(spec) => EventFilter::parse(spec).map_err(|invalid| {
+            ApiError::bad_request(format!("Unknown event types: {}", invalid.join(", ")))
+        })?,
+        None => EventFilter::default(),
+    };
+    let last_event_id = last_event_id.map(|TypedHeader(LastEventId(id))| id);
+
+    // Subscribe to broadcast channel, collecting missed events atomically
+    // Hold the game state read lock so the snapshot matches the live stream
+    let game = channel.game.read().await;
+    let (missed, rx) = channel.subscribe_with_replay(last_event_id);
+    let snapshot = game.clone();
+    drop(game);
 
-    // Subscribe to broadcast channel
-    let rx = state.event_tx.subscribe();
-    let stream = BroadcastStream::new(rx);
+    // A partial replay would leave the client in a wrong state, so clients
+    // whose missed events were dropped start over from the snapshot
+    let resync = last_event_id.is_none() || missed.truncated;
+    let mut missed = if resync { Vec::new() } else { missed.events };
 
-    // Send initial connection event
-    let initial_event = GameEvent::ConnectionStatus {
-        connected: true,
-        error: None,
+    // Live events already covered by the replay are skipped
+    let replayed_up_to = missed.last().map(|e| e.id);
+    missed.retain(|e| filter.matches(&e.event));
+
+    // Registered until the stream below is dropped
+    let client = state.clients.register(ClientInfo {
+        connected_at: std::time::Instant::now(),
+        ip: connect_info.map(|ConnectInfo(addr)| addr.ip()),
+        user_agent: user_agent.map(|TypedHeader(agent)| agent.to_string()),
+    });
+    match last_event_id {
+        Some(id) if resync => warn!(
+            "SSE client {} reconnected after event {}, which is older than the history; sending a snapshot",
+            client.id(),
+            id
+        ),
+        Some(id) => info!(
+            "SSE client {} reconnected after event {}, replaying {} events",
+            client.id(),
+            id,
+            missed.len()
+        ),
+        None => info!("New SSE client {} connected to channel '{}'", client.id(), channel.name),
+    }
+
+    // New clients start from a snapshot of the current state; reconnecting
+    // clients catch up from the replayed events instead
+    let initial_event = if resync {
+        GameEvent::StateSnapshot {
+            state: Box::new(snapshot),
+        }
+    } else {
+        GameEvent::ConnectionStatus {
+            connected: true,
+            error: None,
+        }
     };
-    let _ = state.event_tx.send(initial_event);
-
-    // Convert broadcast stream to SSE event stream
-    let event_stream = stream.filter_map(|result| match result {
-        Ok(event) => {
-            // Serialize event to JSON
-            match serde_json::to_string(&event) {
-                Ok(json) => Some(Ok(Event::default().data(json))),
+    let initial = tokio_stream::iter(to_sse_event(None, &initial_event).map(Ok));
+
+    // Replayed events arrive before any live events
+    let replayed = tokio_stream::iter(
+        missed
+            .into_iter()
+            .filter_map(|e| to_sse_event(Some(e.id), &e.event))
+            .map(Ok),
+    );
+
+    // Convert broadcast stream to SSE event stream, ending it when the
+    // server shuts down
+    // The closure owns the client guard, so the client stays registered
+    // exactly as long as the stream is alive
+    let live = BroadcastStream::new(rx)
+        .take_while(|result| !matches!(result, Ok(s) if shutdown::is_shutdown_event(&s.event)))
+        .filter_map(move |result| {
+            let _client = &client;
+            match result {
+                Ok(sequenced) if !filter.matches(&sequenced.event) => None,
+                Ok(sequenced)
+                    if sequenced.id.zip(replayed_up_to).is_some_and(|(id, last)| id <= last) =>
+                {
+                    None
+                }
+                Ok(sequenced) => to_sse_event(sequenced.id, &sequenced.event).map(Ok),
                 Err(e) => {
-                    warn!("Failed to serialize event: {}", e);
+                    warn!("Broadcast receive error: {}", e);
                     None
                 }
             }
-        }
-        Err(e) => {
-            warn!("Broadcast receive error: {}", e);
-            None
-        }
-    });
+        });
+
+    // Sent last, whatever the filter, so the client knows not to expect more
+    let closing = tokio_stream::iter(to_sse_event(None, &shutdown::shutdown_event()).map(Ok));
+
+    // Counted in city_sse_clients until the client disconnects
+    let event_stream = initial.chain(replayed).chain(live).chain(closing);
+    let event_stream = TrackedStream::new(event_stream, state.metrics.clone());
 
     // Configure keep-alive to send heartbeat every 15 seconds
     // This prevents connection timeouts on idle connections
-    Sse::new(event_stream).keep_alive(
+    Ok(Sse::new(event_stream).keep_alive(
         KeepAlive::new()
             .interval(std::time::Duration::from_secs(15))
             .text("keepalive")
-    )
+    ))
 }
 
 // ============================================================================
@@ -105,176 +882,495 @@ async fn sse_handler(State(state): State<Arc<AppState>>) -> Sse<impl tokio_strea
 
 /// POST /api/barrier/break
 async fn barrier_break(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<BarrierBrokenRequest>,
-) -> Response {
-    let event = GameEvent::BarrierBroken {
-        team: req.team,
-        message: req.message,
-    };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<BarrierBrokenRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/barrier/repair
 async fn barrier_repair(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<BarrierRepairedRequest>,
-) -> Response {
-    let event = GameEvent::BarrierRepaired { team: req.team };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<BarrierRepairedRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/led/break
 async fn led_break(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<LedDisplayBrokenRequest>,
-) -> Response {
-    let event = GameEvent::LedDisplayBroken {
-        team: req.team,
-        message: req.message,
-    };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<LedDisplayBrokenRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/led/repair
-async fn led_repair(State(state): State<Arc<AppState>>) -> Response {
-    let event = GameEvent::LedDisplayRepaired;
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+async fn led_repair(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    channel.trigger(GameEvent::LedDisplayRepaired).await
 }
 
 /// POST /api/scada/compromise
+///
+/// Answers 409 Conflict, naming the holder, if the building is already
+/// compromised. Without a `building_id`, compromises every building not
+/// yet held and reports a result for each.
 async fn scada_compromise(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<ScadaCompromisedRequest>,
-) -> Response {
-    let event = GameEvent::ScadaCompromised {
-        building_id: req.building_id,
-        team: req.team,
-        message: req.message,
-    };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<ScadaCompromisedRequest>,
+) -> Result<Json<ScadaResponse>, ApiError> {
+    channel.trigger_scada(|scada| scada.compromise(req)).await
 }
 
 /// POST /api/scada/restore
+///
+/// Answers 409 Conflict if the building is working. Without a
+/// `building_id`, restores every compromised building and reports a result
+/// for each.
 async fn scada_restore(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<ScadaRestoredRequest>,
-) -> Response {
-    let event = GameEvent::ScadaRestored {
-        building_id: req.building_id,
-    };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<ScadaRestoredRequest>,
+) -> Result<Json<ScadaResponse>, ApiError> {
+    channel.trigger_scada(|scada| scada.restore(req.building_id)).await
+}
+
+/// GET /api/scada
+///
+/// Returns the SCADA state of every building as a
+/// `{"building_id": {"status": ...}}` object.
+async fn scada_status(ChannelState(channel): ChannelState) -> Response {
+    let buildings = channel.scada.lock().unwrap().buildings().clone();
+    (StatusCode::OK, Json(buildings)).into_response()
+}
+
+/// POST /api/building/fire
+async fn building_fire(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<BuildingFireRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
+}
+
+/// POST /api/building/fire/extinguish
+async fn building_fire_extinguish(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<BuildingFireExtinguishRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
+}
+
+/// POST /api/power/outage
+async fn power_outage(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<PowerRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel
+        .trigger(GameEvent::PowerOutage {
+            district: req.district,
+        })
+        .await)
+}
+
+/// POST /api/power/restore
+async fn power_restore(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<PowerRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel
+        .trigger(GameEvent::PowerRestored {
+            district: req.district,
+        })
+        .await)
+}
+
+/// POST /api/road/close
+async fn road_close(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<RoadRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel
+        .trigger(GameEvent::RoadClosure {
+            road_id: req.road_id,
+            blocked: true,
+        })
+        .await)
+}
+
+/// POST /api/road/open
+async fn road_open(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<RoadRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel
+        .trigger(GameEvent::RoadClosure {
+            road_id: req.road_id,
+            blocked: false,
+        })
+        .await)
+}
+
+/// POST /api/weather
+async fn weather_change(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<WeatherChangeRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
+}
+
+/// POST /api/traffic/green_wave
+async fn green_wave(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<GreenWaveRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/emergency/start
 async fn emergency_start(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<EmergencyStopRequest>,
-) -> Response {
-    let event = GameEvent::EmergencyStop { reason: req.reason };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<EmergencyStopRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/emergency/stop
-async fn emergency_stop(State(state): State<Arc<AppState>>) -> Response {
-    let event = GameEvent::EmergencyStopDeactivated;
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+async fn emergency_stop(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    channel.trigger(GameEvent::EmergencyStopDeactivated).await
 }
 
 /// POST /api/danger/activate
 async fn danger_activate(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<DangerModeRequest>,
-) -> Response {
-    let event = GameEvent::DangerModeActivated { reason: req.reason };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<DangerModeRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
 /// POST /api/danger/deactivate
-async fn danger_deactivate(State(state): State<Arc<AppState>>) -> Response {
-    let event = GameEvent::DangerModeDeactivated;
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+async fn danger_deactivate(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    channel.trigger(GameEvent::DangerModeDeactivated).await
 }
 
 /// POST /api/log
 async fn log_message(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<LogMessageRequest>,
-) -> Response {
-    let event = GameEvent::LogMessage {
-        level: req.level,
-        message: req.message,
-    };
-    state.broadcast(event);
-    (StatusCode::OK, "Event triggered").into_response()
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<LogMessageRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel.trigger(req.into()).await)
 }
 
-/// GET / - Info page
-async fn index() -> Response {
-    let html = r#"<!DOCTYPE html>
-<html>
-<head>
-    <title>City Dashboard SSE Server</title>
-    <style>
-        body {
-            font-family: monospace;
-            background: #1e1e1e;
-            color: #d4d4d4;
-            padding: 20px;
-            line-height: 1.6;
-        }
-        h1 { color: #4ec9b0; }
-        h2 { color: #dcdcaa; }
-        .endpoint { color: #ce9178; }
-        .method { color: #569cd6; font-weight: bold; }
-        code {
-            background: #2d2d30;
-            padding: 2px 6px;
-            border-radius: 3px;
-        }
-        pre {
-            background: #2d2d30;
-            padding: 15px;
-            border-left: 3px solid #007acc;
-            overflow-x: auto;
-        }
-        ul { padding-left: 20px; }
-        .example { margin: 10px 0; }
-    </style>
-</head>
-<body>
-    <h1>🏙️ City Dashboard SSE Server</h1>
-    <p>Real-time event server for the City Dashboard application</p>
+/// POST /api/team/score
+///
+/// Adds `delta` to the team's score and broadcasts the new total.
+async fn team_score(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<TeamScoreUpdateRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    Ok(channel
+        .trigger_with(|game| game.score_update(req.team, req.delta))
+        .await)
+}
 
-    <h2>SSE Endpoint</h2>
-    <p><span class="method">GET</span> <span class="endpoint">/events</span></p>
-    <p>Server-Sent Events stream. Connect from dashboard with:</p>
-    <pre>SSE_URL=http://localhost:3000/events cargo run</pre>
+/// GET /api/team/scores
+///
+/// Returns every team's current score as a `{"team": score}` object.
+async fn team_scores(ChannelState(channel): ChannelState) -> Response {
+    let scores = channel.game.read().await.team_scores.clone();
+    (StatusCode::OK, Json(scores)).into_response()
+}
 
-    <h2>API Endpoints</h2>
+/// POST /api/timer/start
+///
+/// Starts a round countdown of `duration_secs`, restarting it if one is
+/// already running.
+async fn timer_start(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<TimerStartRequest>,
+) -> Result<Json<ApiResponse>, ApiError> {
+    info!("Starting {}s round timer", req.duration_secs);
+    Ok(channel.start_timer(req.duration_secs).await)
+}
 
-    <h3>Barrier Events</h3>
-    <div class="example">
-        <p><span class="method">POST</span> <span class="endpoint">/api/barrier/break</span></p>
-        <pre>curl -X POST http://localhost:3000/api/barrier/break \
-  -H "Content-Type: application/json" \
-  -d '{"team": "Red Team", "message": "Gate destroyed"}'</pre>
-    </div>
+/// POST /api/timer/stop
+///
+/// Stops the round countdown before it expires.
+async fn timer_stop(ChannelState(channel): ChannelState) -> Result<Json<ApiResponse>, ApiError> {
+    channel
+        .stop_timer()
+        .await
+        .ok_or_else(|| ApiError::conflict("no round timer is running"))
+}
 
-    <div class="example">
-        <p><span class="method">POST</span> <span class="endpoint">/api/barrier/repair</span></p>
-        <pre>curl -X POST http://localhost:3000/api/barrier/repair \
-  -H "Content-Type: application/json" \
+/// POST /api/reset
+///
+/// Returns the whole city to its initial state, e.g. between CTF rounds.
+/// A running round countdown and every pending scheduled event are cancelled.
+async fn reset_game(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    let mut timer = channel.timer.lock().await;
+    if let Some(task) = timer.take() {
+        task.abort();
+    }
+    let cancelled = channel.cancel_all_scheduled();
+    if cancelled > 0 {
+        info!("Reset cancelled {} scheduled events", cancelled);
+    }
+    channel.trigger(GameEvent::GameReset).await
+}
+
+/// POST /api/city/save
+///
+/// Asks the dashboards to save their simulation (cars, traffic lights,
+/// parked cars) to their save file. The server's own state is kept in its
+/// event log.
+async fn city_save(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    channel.trigger(GameEvent::CitySave).await
+}
+
+/// POST /api/city/load
+///
+/// Asks the dashboards to put their simulation back as it was last saved.
+async fn city_load(ChannelState(channel): ChannelState) -> Json<ApiResponse> {
+    channel.trigger(GameEvent::CityLoad).await
+}
+
+/// POST /api/events/batch
+///
+/// Validates every event first, then applies and broadcasts them in order
+/// without other requests interleaving.
+async fn events_batch(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<BatchRequest>,
+) -> Result<Json<BatchResponse>, ApiError> {
+    let events: Vec<GameEvent> = req.events.into_iter().map(GameEvent::from).collect();
+    let triggered = channel.trigger_all(events).await;
+    Ok(BatchResponse::triggered(triggered))
+}
+
+/// POST /api/events
+///
+/// Checks every event first and reports a result for each. Only if all of
+/// them are valid are they applied and broadcast in order, without other
+/// requests interleaving; otherwise nothing is sent and the status is 422.
+/// More than the configured limit of events gives 413.
+async fn events(
+    State(state): State<Arc<AppState>>,
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<EventsRequest>,
+) -> Result<EventsResponse, ApiError> {
+    if req.events.len() > state.events_limit {
+        return Err(ApiError::payload_too_large(
+            format!("a request may contain at most {} events", state.events_limit),
+            state.events_limit as u64,
+        ));
+    }
+
+    let (results, events) = api::check_events(req.events);
+    let Some(events) = events else {
+        return Ok(EventsResponse {
+            ok: false,
+            triggered: 0,
+            results,
+        });
+    };
+    let triggered = channel.trigger_all(events).await;
+    Ok(EventsResponse {
+        ok: true,
+        triggered,
+        results,
+    })
+}
+
+/// POST /api/events/schedule
+///
+/// Validates the event now and broadcasts it after `delay_secs`, returning
+/// its scheduled ID immediately.
+async fn schedule_event(
+    ChannelState(channel): ChannelState,
+    ApiJson(req): ApiJson<ScheduleRequest>,
+) -> Result<Json<ScheduleResponse>, ApiError> {
+    let event = GameEvent::from(req.event);
+    info!("Scheduling {} in {}s", event.event_name(), req.delay_secs);
+    let scheduled_id = channel.schedule(Duration::from_secs(req.delay_secs), event);
+    Ok(ScheduleResponse::scheduled(scheduled_id))
+}
+
+/// DELETE /api/events/schedule/:id
+///
+/// Cancels a scheduled event that has not fired yet.
+async fn cancel_scheduled_event(
+    ChannelState(channel): ChannelState,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ScheduleResponse>, ApiError> {
+    if channel.cancel_scheduled(id) {
+        info!("Cancelled scheduled event {}", id);
+        Ok(ScheduleResponse::scheduled(id))
+    } else {
+        Err(ApiError::not_found(format!("no pending scheduled event {}", id)))
+    }
+}
+
+/// GET /api/state
+///
+/// Returns the current game state so dashboards can hydrate their display
+/// without waiting for the next event.
+async fn get_state(ChannelState(channel): ChannelState) -> Response {
+    let game = channel.game.read().await.clone();
+    match serde_json::to_string(&game) {
+        Ok(json) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            json,
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Failed to serialize game state: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to serialize game state").into_response()
+        }
+    }
+}
+
+/// Query parameters for GET /api/events/history
+#[derive(Debug, Deserialize)]
+struct HistoryQuery {
+    /// Only return events with a greater sequence ID
+    since: Option<u64>,
+    /// Maximum number of events to return
+    limit: Option<usize>,
+}
+
+/// GET /api/events/history?since=<seq_id>&limit=<n>
+///
+/// Returns recorded events as `{id, timestamp, event}` objects, oldest first,
+/// with `truncated: true` if events newer than `since` were already dropped.
+async fn event_history(
+    ChannelState(channel): ChannelState,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    let page = channel
+        .history
+        .lock()
+        .unwrap()
+        .page(query.since, query.limit);
+    (StatusCode::OK, Json(page)).into_response()
+}
+
+/// GET /api/channels
+///
+/// Lists the open channels with their subscriber counts, sorted by name.
+async fn list_channels(State(state): State<Arc<AppState>>) -> Response {
+    (StatusCode::OK, Json(state.channels.list())).into_response()
+}
+
+/// GET /api/clients
+///
+/// Lists the connected SSE clients, longest-connected first.
+async fn list_clients(State(state): State<Arc<AppState>>) -> Response {
+    (StatusCode::OK, Json(state.clients.list())).into_response()
+}
+
+/// GET /api/health
+///
+/// Health probe for containers and monitoring: 200 with the SSE client count
+/// and history size, or 503 if events can no longer be broadcast.
+async fn health(State(state): State<Arc<AppState>>) -> Health {
+    state.health()
+}
+
+/// GET /metrics
+///
+/// Prometheus metrics in the text exposition format.
+async fn metrics(State(state): State<Arc<AppState>>) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, metrics::CONTENT_TYPE)],
+        state.metrics.render(),
+    )
+        .into_response()
+}
+
+/// GET /api/event-types
+///
+/// Lists every event type the server can emit, with descriptions and fields.
+async fn event_types() -> Response {
+    (StatusCode::OK, Json(EventTypeRegistry::all())).into_response()
+}
+
+/// GET /api/openapi.json
+///
+/// Describes every route, its request and response bodies, and the events
+/// on the SSE stream as an OpenAPI 3.0 document.
+async fn openapi_json() -> Response {
+    (StatusCode::OK, Json(openapi_spec())).into_response()
+}
+
+/// GET / - Info page
+async fn index() -> Response {
+    let html = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>City Dashboard SSE Server</title>
+    <style>
+        body {
+            font-family: monospace;
+            background: #1e1e1e;
+            color: #d4d4d4;
+            padding: 20px;
+            line-height: 1.6;
+        }
+        h1 { color: #4ec9b0; }
+        h2 { color: #dcdcaa; }
+        .endpoint { color: #ce9178; }
+        .method { color: #569cd6; font-weight: bold; }
+        code {
+            background: #2d2d30;
+            padding: 2px 6px;
+            border-radius: 3px;
+        }
+        pre {
+            background: #2d2d30;
+            padding: 15px;
+            border-left: 3px solid #007acc;
+            overflow-x: auto;
+        }
+        ul { padding-left: 20px; }
+        .example { margin: 10px 0; }
+    </style>
+</head>
+<body>
+    <h1>🏙️ City Dashboard SSE Server</h1>
+    <p>Real-time event server for the City Dashboard application</p>
+
+    <h2>SSE Endpoint</h2>
+    <p><span class="method">GET</span> <span class="endpoint">/events</span></p>
+    <p>Server-Sent Events stream. Connect from dashboard with:</p>
+    <pre>SSE_URL=http://localhost:3000/events cargo run</pre>
+    <p>Subscribe to specific event types only (see <code>/api/event-types</code>):</p>
+    <pre>curl -N "http://localhost:3000/events?types=barrier_broken,scada_compromised"</pre>
+    <p>Each event is named after its type, so browsers can listen for one kind:</p>
+    <pre>es.addEventListener("BarrierBroken", (e) =&gt; console.log(JSON.parse(e.data)));</pre>
+    <p>Subscribe to one exercise's channel; POST endpoints take the same <code>channel</code> query parameter or JSON field (default: <code>default</code>):</p>
+    <pre>curl -N "http://localhost:3000/events?channel=alpha"</pre>
+
+    <h2>WebSocket Endpoint</h2>
+    <p><span class="method">GET</span> <span class="endpoint">/ws</span></p>
+    <p>The same events as JSON text frames, plus commands in the other direction; each command is answered with an <code>ack</code> or <code>error</code> frame:</p>
+    <pre>{"command": "barrier_break", "payload": {"team": "Red Team"}}</pre>
+
+    <h2>API Endpoints</h2>
+
+    <p>If the server was started with <code>API_KEYS</code>, every <span class="method">POST</span> endpoint below needs <code>-H "Authorization: Bearer &lt;key&gt;"</code> (401 without it, 403 for an unknown key).</p>
+
+    <h3>Barrier Events</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/barrier/break</span></p>
+        <pre>curl -X POST http://localhost:3000/api/barrier/break \
+  -H "Content-Type: application/json" \
+  -d '{"team": "Red Team", "message": "Gate destroyed"}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/barrier/repair</span></p>
+        <pre>curl -X POST http://localhost:3000/api/barrier/repair \
+  -H "Content-Type: application/json" \
   -d '{"team": "Blue Team"}'</pre>
     </div>
 
@@ -294,6 +1390,7 @@ async fn index() -> Response {
     <h3>SCADA Events</h3>
     <div class="example">
         <p><span class="method">POST</span> <span class="endpoint">/api/scada/compromise</span></p>
+        <p>Answers 409 Conflict, with the <code>holder</code>, if the building is already compromised. Leave out <code>building_id</code> to compromise every building not yet held; the response has a result for each.</p>
         <pre>curl -X POST http://localhost:3000/api/scada/compromise \
   -H "Content-Type: application/json" \
   -d '{"team": "Red Team", "building_id": 5, "message": "System hacked"}'</pre>
@@ -301,11 +1398,84 @@ async fn index() -> Response {
 
     <div class="example">
         <p><span class="method">POST</span> <span class="endpoint">/api/scada/restore</span></p>
+        <p>Answers 409 Conflict if the building is working; <code>null</code> restores every compromised building.</p>
         <pre>curl -X POST http://localhost:3000/api/scada/restore \
   -H "Content-Type: application/json" \
   -d '{"building_id": null}'</pre>
     </div>
 
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/scada</span></p>
+        <p>Returns every building's SCADA state, with the holding team and since when for compromised ones.</p>
+        <pre>curl http://localhost:3000/api/scada</pre>
+    </div>
+
+    <h3>Building Fires</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/building/fire</span></p>
+        <p>Sets a building (1-12) on fire. <code>intensity</code> is 0 to 1 and defaults to 1.</p>
+        <pre>curl -X POST http://localhost:3000/api/building/fire \
+  -H "Content-Type: application/json" \
+  -d '{"building_id": 3, "intensity": 0.8}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/building/fire/extinguish</span></p>
+        <pre>curl -X POST http://localhost:3000/api/building/fire/extinguish \
+  -H "Content-Type: application/json" \
+  -d '{"building_id": 3}'</pre>
+    </div>
+
+    <h3>Power Outages</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/power/outage</span></p>
+        <p>Cuts power to a district (<code>west</code>, <code>central</code> or <code>east</code>): its traffic lights flash yellow and its windows go dark.</p>
+        <pre>curl -X POST http://localhost:3000/api/power/outage \
+  -H "Content-Type: application/json" \
+  -d '{"district": "central"}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/power/restore</span></p>
+        <pre>curl -X POST http://localhost:3000/api/power/restore \
+  -H "Content-Type: application/json" \
+  -d '{"district": "central"}'</pre>
+    </div>
+
+    <h3>Road Closures</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/road/close</span></p>
+        <p>Closes a road (<code>0</code>-<code>2</code> vertical from left to right, <code>3</code>-<code>4</code> horizontal from top to bottom): cones line it, no cars enter it and traffic waits at the intersections it crosses.</p>
+        <pre>curl -X POST http://localhost:3000/api/road/close \
+  -H "Content-Type: application/json" \
+  -d '{"road_id": 3}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/road/open</span></p>
+        <pre>curl -X POST http://localhost:3000/api/road/open \
+  -H "Content-Type: application/json" \
+  -d '{"road_id": 3}'</pre>
+    </div>
+
+    <h3>Weather</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/weather</span></p>
+        <p>Sets the rain from <code>0</code> (dry) to <code>1</code> (downpour). Cars slow down in heavy rain.</p>
+        <pre>curl -X POST http://localhost:3000/api/weather \
+  -H "Content-Type: application/json" \
+  -d '{"rain_intensity": 0.7}'</pre>
+    </div>
+
+    <h3>Traffic</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/traffic/green_wave</span></p>
+        <p>Times the lights along each vertical road so cars driving down at <code>speed</code> pixels per second meet green lights. Leave <code>speed</code> out to use the usual car speed.</p>
+        <pre>curl -X POST http://localhost:3000/api/traffic/green_wave \
+  -H "Content-Type: application/json" \
+  -d '{"speed": 60}'</pre>
+    </div>
+
     <h3>Emergency Stop</h3>
     <div class="example">
         <p><span class="method">POST</span> <span class="endpoint">/api/emergency/start</span></p>
@@ -332,6 +1502,53 @@ async fn index() -> Response {
         <pre>curl -X POST http://localhost:3000/api/danger/deactivate</pre>
     </div>
 
+    <h3>Game Reset</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/reset</span></p>
+        <p>Returns the whole city to its initial state, e.g. between rounds.</p>
+        <pre>curl -X POST http://localhost:3000/api/reset</pre>
+    </div>
+
+    <h3>Save and Load</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/city/save</span></p>
+        <p>Dashboards save their cars, traffic lights and parked cars to their save file.</p>
+        <pre>curl -X POST http://localhost:3000/api/city/save</pre>
+    </div>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/city/load</span></p>
+        <p>Dashboards restore the city as it was last saved.</p>
+        <pre>curl -X POST http://localhost:3000/api/city/load</pre>
+    </div>
+
+    <h3>Team Scores</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/team/score</span></p>
+        <pre>curl -X POST http://localhost:3000/api/team/score \
+  -H "Content-Type: application/json" \
+  -d '{"team": "Red Team", "delta": 100}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/team/scores</span></p>
+        <p>Returns every team's current score.</p>
+        <pre>curl http://localhost:3000/api/team/scores</pre>
+    </div>
+
+    <h3>Round Timer</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/timer/start</span></p>
+        <p>Counts down on the LED display, broadcasting <code>timer_update</code> every second and <code>timer_expired</code> at zero.</p>
+        <pre>curl -X POST http://localhost:3000/api/timer/start \
+  -H "Content-Type: application/json" \
+  -d '{"duration_secs": 600}'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/timer/stop</span></p>
+        <pre>curl -X POST http://localhost:3000/api/timer/stop</pre>
+    </div>
+
     <h3>Custom Log Message</h3>
     <div class="example">
         <p><span class="method">POST</span> <span class="endpoint">/api/log</span></p>
@@ -340,6 +1557,90 @@ async fn index() -> Response {
   -d '{"level": "critical", "message": "Custom event message"}'</pre>
     </div>
 
+    <h3>Batch Events</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/events/batch</span></p>
+        <p>Triggers several events in order; nothing is sent if any of them is invalid.</p>
+        <pre>curl -X POST http://localhost:3000/api/events/batch \
+  -H "Content-Type: application/json" \
+  -d '[{"type": "barrier_break", "team": "Red"}, {"type": "danger_activate", "reason": "Breach"}]'</pre>
+    </div>
+
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/events</span></p>
+        <p>Same events under an <code>events</code> key, with a result for each one.</p>
+        <pre>curl -X POST http://localhost:3000/api/events \
+  -H "Content-Type: application/json" \
+  -d '{"events": [{"type": "barrier_break", "team": "Red"}, {"type": "led_repair"}]}'</pre>
+    </div>
+
+    <h3>Scheduled Events</h3>
+    <div class="example">
+        <p><span class="method">POST</span> <span class="endpoint">/api/events/schedule</span></p>
+        <p>Broadcasts one event after <code>delay_secs</code> and returns its <code>scheduled_id</code>.</p>
+        <pre>curl -X POST http://localhost:3000/api/events/schedule \
+  -H "Content-Type: application/json" \
+  -d '{"delay_secs": 30, "event": {"type": "emergency_start", "reason": "Timed drill"}}'</pre>
+        <p><span class="method">DELETE</span> <span class="endpoint">/api/events/schedule/:id</span></p>
+        <p>Cancels a scheduled event that has not fired yet.</p>
+    </div>
+
+    <h3>Game State</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/state</span></p>
+        <p>Returns the current game state. New SSE clients receive it as a <code>state_snapshot</code> event first.</p>
+        <pre>curl http://localhost:3000/api/state</pre>
+    </div>
+
+    <h3>Event History</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/events/history?since=&lt;id&gt;&amp;limit=&lt;n&gt;</span></p>
+        <p>Returns recent events as <code>{id, timestamp, event}</code> objects, oldest first, under <code>events</code>. <code>truncated</code> is true if events after <code>since</code> were already dropped.</p>
+        <pre>curl "http://localhost:3000/api/events/history?since=0&amp;limit=10"</pre>
+    </div>
+
+    <h3>Event Types</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/event-types</span></p>
+        <p>Lists every event type with its description and fields.</p>
+        <pre>curl http://localhost:3000/api/event-types</pre>
+    </div>
+
+    <h3>OpenAPI Description</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/openapi.json</span></p>
+        <p>OpenAPI 3.0 document describing every route with its request and response schemas.</p>
+        <pre>curl http://localhost:3000/api/openapi.json</pre>
+    </div>
+
+    <h3>Channels</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/channels</span></p>
+        <p>Lists open channels as <code>{name, subscribers, idle_secs}</code> objects. Unused channels are closed after an hour.</p>
+        <pre>curl http://localhost:3000/api/channels</pre>
+    </div>
+
+    <h3>Connected Clients</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/clients</span></p>
+        <p>Lists connected SSE clients as <code>{id, ip, user_agent, connected_secs}</code> objects.</p>
+        <pre>curl http://localhost:3000/api/clients</pre>
+    </div>
+
+    <h3>Health</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/api/health</span></p>
+        <p>Returns <code>{status, clients, queue_depth}</code>, or 503 with <code>{"status": "degraded"}</code> if events can no longer be broadcast.</p>
+        <pre>curl http://localhost:3000/api/health</pre>
+    </div>
+
+    <h3>Metrics</h3>
+    <div class="example">
+        <p><span class="method">GET</span> <span class="endpoint">/metrics</span></p>
+        <p>Prometheus metrics: <code>city_events_total{type}</code>, <code>city_sse_clients</code>, <code>city_broadcast_errors_total</code>, <code>city_http_requests_total{method,path,status}</code> and <code>city_rejected_requests_total</code>.</p>
+        <pre>curl http://localhost:3000/metrics</pre>
+    </div>
+
     <h2>Testing</h2>
     <p>Watch SSE stream:</p>
     <pre>curl -N http://localhost:3000/events</pre>
@@ -353,50 +1654,2629 @@ async fn index() -> Response {
 // Main Application
 // ============================================================================
 
-#[tokio::main]
-async fn main() {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+/// Address the server listens on unless `BIND_ADDR` or `--port` say otherwise
+const DEFAULT_BIND_ADDR: SocketAddr =
+    SocketAddr::new(std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED), 3000);
 
-    // Create shared state
-    let state = Arc::new(AppState::new());
+/// Works out the address to listen on
+///
+/// `BIND_ADDR` is either a full socket address (`127.0.0.1:8080`) or just an
+/// IP address, which keeps the default port. A `--port <port>` (or
+/// `--port=<port>`) argument overrides the port either way.
+///
+/// # Arguments
+/// * `bind_addr` - Value of the `BIND_ADDR` environment variable, if set
+/// * `args` - Command line arguments, without the program name
+///
+/// # Returns
+/// The address, or a message saying which setting is invalid
+fn bind_addr(
+    bind_addr: Option<String>,
+    mut args: impl Iterator<Item = String>,
+) -> Result<SocketAddr, String> {
+    let mut addr = match bind_addr {
+        None => DEFAULT_BIND_ADDR,
+        Some(value) => value
+            .parse::<SocketAddr>()
+            .or_else(|_| {
+                value
+                    .parse()
+                    .map(|ip| SocketAddr::new(ip, DEFAULT_BIND_ADDR.port()))
+            })
+            .map_err(|_| format!("BIND_ADDR '{}' is not an address like 0.0.0.0:3000", value))?,
+    };
 
-    // Configure CORS to allow requests from anywhere
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    while let Some(arg) = args.next() {
+        let port = if arg == "--port" {
+            args.next()
+        } else if let Some(port) = arg.strip_prefix("--port=") {
+            Some(port.to_string())
+        } else {
+            return Err(format!("Unknown argument '{}' (usage: backend [--port <port>])", arg));
+        };
+        let port = port.ok_or("--port needs a port number")?;
+        addr.set_port(
+            port.parse()
+                .map_err(|_| format!("--port '{}' is not a port number", port))?,
+        );
+    }
+    Ok(addr)
+}
 
-    // Build router
-    let app = Router::new()
-        .route("/", get(index))
-        .route("/events", get(sse_handler))
-        // Barrier endpoints
-        .route("/api/barrier/break", post(barrier_break))
-        .route("/api/barrier/repair", post(barrier_repair))
-        // LED display endpoints
-        .route("/api/led/break", post(led_break))
-        .route("/api/led/repair", post(led_repair))
-        // SCADA endpoints
-        .route("/api/scada/compromise", post(scada_compromise))
-        .route("/api/scada/restore", post(scada_restore))
-        // Emergency endpoints
-        .route("/api/emergency/start", post(emergency_start))
-        .route("/api/emergency/stop", post(emergency_stop))
-        // Danger mode endpoints
-        .route("/api/danger/activate", post(danger_activate))
-        .route("/api/danger/deactivate", post(danger_deactivate))
-        // Log endpoint
-        .route("/api/log", post(log_message))
-        .layer(cors)
-        .with_state(state);
+/// Reads the event history capacity from `EVENT_HISTORY_CAPACITY`
+fn history_capacity() -> usize {
+    std::env::var("EVENT_HISTORY_CAPACITY")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_HISTORY_CAPACITY)
+}
 
-    // Start server
-    let addr = "0.0.0.0:3000";
-    info!("🚀 Server starting on http://{}", addr);
-    info!("📡 SSE endpoint: http://{}/events", addr);
-    info!("📝 API docs: http://{}/", addr);
+/// Reads the broadcast channel capacity from `BROADCAST_CAPACITY`
+///
+/// Values outside 10..=10000 are clamped into that range.
+fn broadcast_capacity() -> usize {
+    let Some(capacity) = std::env::var("BROADCAST_CAPACITY")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+    else {
+        return DEFAULT_BROADCAST_CAPACITY;
+    };
+
+    let clamped = capacity.clamp(MIN_BROADCAST_CAPACITY, MAX_BROADCAST_CAPACITY);
+    if clamped != capacity {
+        warn!(
+            "BROADCAST_CAPACITY {} is out of range, using {}",
+            capacity, clamped
+        );
+    }
+    clamped
+}
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+/// Reads the time between heartbeats from `HEARTBEAT_INTERVAL_SECS`
+///
+/// Zero or unparseable values fall back to the default of 10 seconds.
+fn heartbeat_interval() -> Duration {
+    std::env::var("HEARTBEAT_INTERVAL_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&secs| secs > 0)
+        .map_or(heartbeat::DEFAULT_HEARTBEAT_INTERVAL, Duration::from_secs)
+}
+
+/// Reads the per-IP request limit for POST /api/* from `RATE_LIMIT_PER_SEC`
+fn rate_limit_per_sec() -> u32 {
+    std::env::var("RATE_LIMIT_PER_SEC")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RATE_LIMIT_PER_SEC)
+}
+
+/// Reads the per-IP burst for POST /api/* from `RATE_LIMIT_BURST`, if set
+fn rate_limit_burst() -> Option<u32> {
+    std::env::var("RATE_LIMIT_BURST")
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
+/// Reads from `TRUST_FORWARDED_FOR` whether the server is behind a proxy whose
+/// `X-Forwarded-For` header identifies clients
+fn trust_forwarded_for() -> bool {
+    matches!(
+        std::env::var("TRUST_FORWARDED_FOR").as_deref(),
+        Ok("1" | "true")
+    )
+}
+
+/// Reads the path of the file events are logged to from `EVENT_LOG_PATH`, if set
+fn event_log_path() -> Option<PathBuf> {
+    std::env::var_os("EVENT_LOG_PATH")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Reads the most events accepted by one POST /api/events from `EVENTS_LIMIT`
+fn events_limit() -> usize {
+    std::env::var("EVENTS_LIMIT")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_EVENTS_LIMIT)
+}
+
+/// Reads the keys accepted by POST /api/* from `API_KEYS` (`name:key,...`)
+fn api_keys() -> ApiKeys {
+    std::env::var("API_KEYS")
+        .map(|spec| ApiKeys::parse(&spec))
+        .unwrap_or_default()
+}
+
+/// Reads the origins browsers may call the API from, `CORS_ALLOWED_ORIGINS`
+///
+/// # Returns
+/// Every origin if the variable is not set, or an error naming an invalid entry
+fn cors_origins() -> Result<AllowedOrigins, String> {
+    match std::env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(spec) => AllowedOrigins::parse(&spec)
+            .map_err(|message| format!("Invalid CORS_ALLOWED_ORIGINS: {}", message)),
+        Err(_) => Ok(AllowedOrigins::default()),
+    }
+}
+
+/// Opens the tracing span a request is handled in
+///
+/// The span carries the request's ID from the `X-Request-Id` header, so
+/// every log line written while handling the request can be matched to the
+/// response the client got.
+fn request_span(request: &axum::extract::Request) -> Span {
+    let request_id = request
+        .headers()
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        request_id = %request_id,
+    )
+}
+
+/// Builds the application router
+///
+/// # Arguments
+/// * `state` - Shared application state
+/// * `limiter` - Rate limiter for the event-triggering endpoints
+fn app(state: Arc<AppState>, limiter: Arc<RateLimiter>) -> Router {
+    // Only the configured origins may call the API from a browser
+    let cors = state.cors_origins.layer();
+
+    // Event-triggering endpoints
+    let api = Router::new()
+        // Barrier endpoints
+        .route("/api/barrier/break", post(barrier_break))
+        .route("/api/barrier/repair", post(barrier_repair))
+        // LED display endpoints
+        .route("/api/led/break", post(led_break))
+        .route("/api/led/repair", post(led_repair))
+        // SCADA endpoints
+        .route("/api/scada/compromise", post(scada_compromise))
+        .route("/api/scada/restore", post(scada_restore))
+        // Building fire endpoints
+        .route("/api/building/fire", post(building_fire))
+        .route("/api/building/fire/extinguish", post(building_fire_extinguish))
+        // Power endpoints
+        .route("/api/power/outage", post(power_outage))
+        .route("/api/power/restore", post(power_restore))
+        // Road closure endpoints
+        .route("/api/road/close", post(road_close))
+        .route("/api/road/open", post(road_open))
+        // Weather endpoint
+        .route("/api/weather", post(weather_change))
+        // Traffic endpoint
+        .route("/api/traffic/green_wave", post(green_wave))
+        // Emergency endpoints
+        .route("/api/emergency/start", post(emergency_start))
+        .route("/api/emergency/stop", post(emergency_stop))
+        // Danger mode endpoints
+        .route("/api/danger/activate", post(danger_activate))
+        .route("/api/danger/deactivate", post(danger_deactivate))
+        // Log endpoint
+        .route("/api/log", post(log_message))
+        // Score endpoint
+        .route("/api/team/score", post(team_score))
+        // Round timer endpoints
+        .route("/api/timer/start", post(timer_start))
+        .route("/api/timer/stop", post(timer_stop))
+        // Reset endpoint
+        .route("/api/reset", post(reset_game))
+        // Save and load endpoints
+        .route("/api/city/save", post(city_save))
+        .route("/api/city/load", post(city_load))
+        // Batch endpoints
+        .route("/api/events", post(events))
+        .route("/api/events/batch", post(events_batch))
+        // Scheduled events
+        .route("/api/events/schedule", post(schedule_event))
+        .route("/api/events/schedule/:id", delete(cancel_scheduled_event))
+        .route_layer(middleware::from_fn_with_state(
+            state.api_keys.clone(),
+            auth::require_api_key,
+        ))
+        // Rate limiting runs first, so unauthorized clients are throttled too
+        .route_layer(middleware::from_fn_with_state(limiter.clone(), rate_limit::rate_limit));
+
+    // Build router
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/events", get(sse_handler))
+        // WebSocket commands are rate limited one by one
+        .route("/ws", get(ws::ws_handler).layer(Extension(limiter)))
+        .merge(api)
+        // State endpoints
+        .route("/api/state", get(get_state))
+        .route("/api/events/history", get(event_history))
+        .route("/api/team/scores", get(team_scores))
+        .route("/api/scada", get(scada_status))
+        // Introspection endpoints
+        .route("/api/event-types", get(event_types))
+        .route("/api/openapi.json", get(openapi_json))
+        .route("/api/channels", get(list_channels))
+        .route("/api/clients", get(list_clients))
+        .route("/api/health", get(health))
+        .route("/metrics", get(metrics));
+
+    // Load-test endpoints (only served when LOAD_TEST_ENABLED=true)
+    #[cfg(feature = "load-test")]
+    let app = app.nest_service("/load-test", load_test::router(state.clone()));
+
+    // Requests are counted by the route they matched
+    app.layer(middleware::from_fn(channels::resolve_channel))
+        .layer(middleware::from_fn_with_state(state.metrics.clone(), metrics::track_requests))
+        // Error responses not produced by the handlers also get a JSON body
+        .layer(middleware::from_fn(api::handle_json_rejection))
+        .layer(cors)
+        // Each request gets an ID (unless the client sent one), handled in a
+        // span carrying it, and returned as X-Request-Id
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(request_span)
+                .on_response(DefaultOnResponse::new().level(Level::INFO)),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .with_state(state)
+}
+
+// ============================================================================
+// OpenAPI Description
+// ============================================================================
+
+/// How a documented route responds
+#[derive(Debug, Clone, Copy)]
+enum ResponseDoc {
+    /// JSON body of the given Rust type (see `schema_of`)
+    Json(&'static str),
+
+    /// Stream of server-sent `GameEvent`s
+    EventStream,
+
+    /// Upgrade to a WebSocket
+    WebSocket,
+
+    /// Body of the given content type, e.g. "text/html"
+    Text(&'static str),
+}
+
+/// One route as described in GET /api/openapi.json
+#[derive(Debug, Clone, Copy)]
+struct RouteDoc {
+    method: &'static str,
+
+    /// Path in router syntax (`:id` for a path parameter)
+    path: &'static str,
+
+    summary: &'static str,
+
+    /// Rust type of the JSON request body, if the route takes one
+    body: Option<&'static str>,
+
+    /// Query parameters as (name, Rust type, description)
+    query: &'static [(&'static str, &'static str, &'static str)],
+
+    /// Whether the route works on a channel named by the `channel` query
+    /// parameter
+    channel: bool,
+
+    response: ResponseDoc,
+}
+
+impl RouteDoc {
+    /// An event-triggering POST route answering with an `ApiResponse`, on a channel
+    const fn post(path: &'static str, summary: &'static str, body: Option<&'static str>) -> Self {
+        Self {
+            method: "post",
+            path,
+            summary,
+            body,
+            query: &[],
+            channel: true,
+            response: ResponseDoc::Json("ApiResponse"),
+        }
+    }
+
+    /// A GET route without a request body
+    const fn get(path: &'static str, summary: &'static str, response: ResponseDoc) -> Self {
+        Self {
+            method: "get",
+            path,
+            summary,
+            body: None,
+            query: &[],
+            channel: false,
+            response,
+        }
+    }
+
+    const fn with_method(self, method: &'static str) -> Self {
+        Self { method, ..self }
+    }
+
+    const fn with_query(
+        self,
+        query: &'static [(&'static str, &'static str, &'static str)],
+    ) -> Self {
+        Self { query, ..self }
+    }
+
+    const fn with_channel(self) -> Self {
+        Self { channel: true, ..self }
+    }
+
+    const fn with_response(self, response: ResponseDoc) -> Self {
+        Self { response, ..self }
+    }
+
+    /// Path in OpenAPI syntax (`{id}` for a path parameter)
+    fn openapi_path(&self) -> String {
+        self.path
+            .split('/')
+            .map(|segment| match segment.strip_prefix(':') {
+                Some(name) => format!("{{{}}}", name),
+                None => segment.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+/// Every route registered in `app`, in the same order
+///
+/// A test checks this list against the routes in `app`, so a new route
+/// needs an entry here. The `/load-test` routes are left out, as they only
+/// exist in builds with the `load-test` feature.
+const ROUTE_DOCS: &[RouteDoc] = &[
+    RouteDoc::post("/api/barrier/break", "Break the barrier gate", Some("BarrierBrokenRequest")),
+    RouteDoc::post(
+        "/api/barrier/repair",
+        "Repair the barrier gate",
+        Some("BarrierRepairedRequest"),
+    ),
+    RouteDoc::post("/api/led/break", "Break the LED display", Some("LedDisplayBrokenRequest")),
+    RouteDoc::post("/api/led/repair", "Repair the LED display", None),
+    RouteDoc::post(
+        "/api/scada/compromise",
+        "Compromise one SCADA building, or all of them",
+        Some("ScadaCompromisedRequest"),
+    )
+    .with_response(ResponseDoc::Json("ScadaResponse")),
+    RouteDoc::post(
+        "/api/scada/restore",
+        "Restore one SCADA building, or all of them",
+        Some("ScadaRestoredRequest"),
+    )
+    .with_response(ResponseDoc::Json("ScadaResponse")),
+    RouteDoc::post("/api/building/fire", "Set a building on fire", Some("BuildingFireRequest")),
+    RouteDoc::post(
+        "/api/building/fire/extinguish",
+        "Put out a building fire",
+        Some("BuildingFireExtinguishRequest"),
+    ),
+    RouteDoc::post("/api/power/outage", "Cut the power in a district", Some("PowerRequest")),
+    RouteDoc::post("/api/power/restore", "Restore the power in a district", Some("PowerRequest")),
+    RouteDoc::post("/api/road/close", "Close a road to traffic", Some("RoadRequest")),
+    RouteDoc::post("/api/road/open", "Reopen a closed road", Some("RoadRequest")),
+    RouteDoc::post("/api/weather", "Change the rain intensity", Some("WeatherChangeRequest")),
+    RouteDoc::post(
+        "/api/traffic/green_wave",
+        "Time the lights along vertical roads for a green wave",
+        Some("GreenWaveRequest"),
+    ),
+    RouteDoc::post(
+        "/api/emergency/start",
+        "Turn every traffic light red",
+        Some("EmergencyStopRequest"),
+    ),
+    RouteDoc::post("/api/emergency/stop", "End the emergency stop", None),
+    RouteDoc::post(
+        "/api/danger/activate",
+        "Show the danger warning on the LED display",
+        Some("DangerModeRequest"),
+    ),
+    RouteDoc::post("/api/danger/deactivate", "End danger mode", None),
+    RouteDoc::post("/api/log", "Send a message to the dashboard log", Some("LogMessageRequest")),
+    RouteDoc::post(
+        "/api/team/score",
+        "Add points to a team's score",
+        Some("TeamScoreUpdateRequest"),
+    ),
+    RouteDoc::post(
+        "/api/timer/start",
+        "Start (or restart) the round countdown",
+        Some("TimerStartRequest"),
+    ),
+    RouteDoc::post("/api/timer/stop", "Stop the round countdown", None),
+    RouteDoc::post("/api/reset", "Reset the city to its initial state", None),
+    RouteDoc::post("/api/city/save", "Have dashboards save their simulation", None),
+    RouteDoc::post("/api/city/load", "Have dashboards restore their last save", None),
+    RouteDoc::post(
+        "/api/events",
+        "Check events one by one, and broadcast them only if all are valid",
+        Some("EventsRequest"),
+    )
+    .with_response(ResponseDoc::Json("EventsResponse")),
+    RouteDoc::post(
+        "/api/events/batch",
+        "Broadcast a batch of events in order",
+        Some("BatchRequest"),
+    )
+    .with_response(ResponseDoc::Json("BatchResponse")),
+    RouteDoc::post(
+        "/api/events/schedule",
+        "Broadcast an event after a delay",
+        Some("ScheduleRequest"),
+    )
+    .with_response(ResponseDoc::Json("ScheduleResponse")),
+    RouteDoc::post(
+        "/api/events/schedule/:id",
+        "Cancel a scheduled event",
+        None,
+    )
+    .with_method("delete")
+    .with_response(ResponseDoc::Json("ScheduleResponse")),
+    RouteDoc::get("/", "Info page", ResponseDoc::Text("text/html")),
+    RouteDoc::get("/events", "Stream of game events", ResponseDoc::EventStream)
+        .with_query(&[(
+            "types",
+            "Option<String>",
+            "Comma-separated event types to receive (default: all)",
+        )])
+        .with_channel(),
+    RouteDoc::get(
+        "/ws",
+        "WebSocket mirroring the event stream and taking commands",
+        ResponseDoc::WebSocket,
+    )
+    .with_channel(),
+    RouteDoc::get("/api/state", "Current game state", ResponseDoc::Json("GameState"))
+        .with_channel(),
+    RouteDoc::get(
+        "/api/events/history",
+        "Recently broadcast events, oldest first",
+        ResponseDoc::Json("HistoryPage"),
+    )
+    .with_query(&[
+        ("since", "Option<u64>", "Only return events with a greater ID"),
+        ("limit", "Option<usize>", "Maximum number of events to return"),
+    ])
+    .with_channel(),
+    RouteDoc::get(
+        "/api/team/scores",
+        "Every team's score",
+        ResponseDoc::Json("HashMap<String, i32>"),
+    )
+    .with_channel(),
+    RouteDoc::get(
+        "/api/scada",
+        "SCADA state of every building",
+        ResponseDoc::Json("HashMap<String, ScadaStatus>"),
+    )
+    .with_channel(),
+    RouteDoc::get(
+        "/api/event-types",
+        "Every event type with its description and fields",
+        ResponseDoc::Json("Vec<EventTypeInfo>"),
+    ),
+    RouteDoc::get(
+        "/api/openapi.json",
+        "This OpenAPI description",
+        ResponseDoc::Json("serde_json::Value"),
+    ),
+    RouteDoc::get(
+        "/api/channels",
+        "Open channels with their subscriber counts",
+        ResponseDoc::Json("Vec<ChannelSummary>"),
+    ),
+    RouteDoc::get(
+        "/api/clients",
+        "Connected SSE clients, longest-connected first",
+        ResponseDoc::Json("Vec<ClientSummary>"),
+    ),
+    RouteDoc::get("/api/health", "Health probe", ResponseDoc::Json("Health")),
+    RouteDoc::get("/metrics", "Prometheus metrics", ResponseDoc::Text("text/plain")),
+];
+
+/// Fields of the request and response bodies, as (name, Rust type)
+///
+/// Kept in step with the structs of the same name by hand; a test parses
+/// a sample of every event request built from these fields.
+const SCHEMA_FIELDS: &[(&str, &[(&str, &str)])] = &[
+    ("BarrierBrokenRequest", &[("team", "String"), ("message", "Option<String>")]),
+    ("BarrierRepairedRequest", &[("team", "Option<String>")]),
+    ("LedDisplayBrokenRequest", &[("team", "String"), ("message", "Option<String>")]),
+    (
+        "ScadaCompromisedRequest",
+        &[
+            ("building_id", "Option<u32>"),
+            ("team", "String"),
+            ("message", "Option<String>"),
+        ],
+    ),
+    ("ScadaRestoredRequest", &[("building_id", "Option<u32>")]),
+    ("BuildingFireRequest", &[("building_id", "u32"), ("intensity", "Option<f32>")]),
+    ("BuildingFireExtinguishRequest", &[("building_id", "u32")]),
+    ("PowerRequest", &[("district", "String")]),
+    ("RoadRequest", &[("road_id", "usize")]),
+    ("WeatherChangeRequest", &[("rain_intensity", "f32")]),
+    ("GreenWaveRequest", &[("speed", "Option<f32>")]),
+    ("EmergencyStopRequest", &[("reason", "String")]),
+    ("DangerModeRequest", &[("reason", "String")]),
+    ("LogMessageRequest", &[("level", "LogLevel"), ("message", "String")]),
+    ("TeamScoreUpdateRequest", &[("team", "String"), ("delta", "i32")]),
+    ("TimerStartRequest", &[("duration_secs", "u32")]),
+    ("EventsRequest", &[("events", "Vec<serde_json::Value>")]),
+    ("ScheduleRequest", &[("delay_secs", "u64"), ("event", "EventRequest")]),
+    ("ApiResponse", &[("ok", "bool"), ("message", "String"), ("event", "String")]),
+    ("BatchResponse", &[("ok", "bool"), ("triggered", "usize")]),
+    (
+        "EventResult",
+        &[
+            ("index", "usize"),
+            ("ok", "bool"),
+            ("event", "Option<String>"),
+            ("error", "Option<String>"),
+            ("field", "Option<String>"),
+        ],
+    ),
+    (
+        "EventsResponse",
+        &[("ok", "bool"), ("triggered", "usize"), ("results", "Vec<EventResult>")],
+    ),
+    ("ScheduleResponse", &[("ok", "bool"), ("scheduled_id", "Uuid")]),
+    (
+        "BuildingResult",
+        &[
+            ("building_id", "u32"),
+            ("ok", "bool"),
+            ("error", "Option<String>"),
+            ("holder", "Option<String>"),
+        ],
+    ),
+    (
+        "ScadaResponse",
+        &[
+            ("ok", "bool"),
+            ("message", "String"),
+            ("event", "String"),
+            ("results", "Vec<BuildingResult>"),
+        ],
+    ),
+    (
+        "ScadaStatus",
+        &[("status", "String"), ("team", "Option<String>"), ("since", "Option<u64>")],
+    ),
+    (
+        "ApiError",
+        &[
+            ("ok", "bool"),
+            ("error", "String"),
+            ("field", "Option<String>"),
+            ("max", "Option<u64>"),
+            ("holder", "Option<String>"),
+            ("message", "Option<String>"),
+        ],
+    ),
+    (
+        "GameState",
+        &[
+            ("barrier_broken", "bool"),
+            ("barrier_broken_by", "Option<String>"),
+            ("led_broken", "Option<String>"),
+            ("danger_mode_active", "bool"),
+            ("emergency_stop_active", "bool"),
+            ("scada_compromised_buildings", "HashMap<String, bool>"),
+            ("scada_compromised_by", "HashMap<String, String>"),
+            ("buildings_on_fire", "HashMap<String, f32>"),
+            ("districts_without_power", "HashSet<String>"),
+            ("closed_roads", "HashSet<usize>"),
+            ("team_scores", "HashMap<String, i32>"),
+            ("timer_remaining_secs", "Option<u32>"),
+            ("rain_intensity", "f32"),
+        ],
+    ),
+    ("HistoryEntry", &[("id", "u64"), ("timestamp", "u64"), ("event", "GameEvent")]),
+    ("HistoryPage", &[("events", "Vec<HistoryEntry>"), ("truncated", "bool")]),
+    (
+        "EventTypeInfo",
+        &[
+            ("name", "String"),
+            ("type", "String"),
+            ("description", "String"),
+            ("fields", "Vec<FieldInfo>"),
+        ],
+    ),
+    ("FieldInfo", &[("name", "String"), ("type", "String")]),
+    ("ChannelSummary", &[("name", "String"), ("subscribers", "usize"), ("idle_secs", "u64")]),
+    (
+        "ClientSummary",
+        &[
+            ("id", "Uuid"),
+            ("ip", "Option<String>"),
+            ("user_agent", "Option<String>"),
+            ("connected_secs", "u64"),
+        ],
+    ),
+    (
+        "Health",
+        &[
+            ("status", "String"),
+            ("clients", "Option<usize>"),
+            ("queue_depth", "Option<usize>"),
+        ],
+    ),
+];
+
+/// `type` tags of the `EventRequest` variants, with the body each one carries
+const EVENT_REQUEST_BODIES: &[(&str, Option<&str>)] = &[
+    ("barrier_break", Some("BarrierBrokenRequest")),
+    ("barrier_repair", Some("BarrierRepairedRequest")),
+    ("led_break", Some("LedDisplayBrokenRequest")),
+    ("led_repair", None),
+    ("scada_compromise", Some("ScadaCompromisedRequest")),
+    ("scada_restore", Some("ScadaRestoredRequest")),
+    ("building_fire", Some("BuildingFireRequest")),
+    ("building_fire_extinguish", Some("BuildingFireExtinguishRequest")),
+    ("power_outage", Some("PowerRequest")),
+    ("power_restore", Some("PowerRequest")),
+    ("road_close", Some("RoadRequest")),
+    ("road_open", Some("RoadRequest")),
+    ("weather_change", Some("WeatherChangeRequest")),
+    ("green_wave", Some("GreenWaveRequest")),
+    ("emergency_start", Some("EmergencyStopRequest")),
+    ("emergency_stop", None),
+    ("danger_activate", Some("DangerModeRequest")),
+    ("danger_deactivate", None),
+    ("log", Some("LogMessageRequest")),
+];
+
+/// JSON schema of a Rust type, as written in `SCHEMA_FIELDS` or `FieldInfo`
+///
+/// Named types other than the primitives refer to the component schema of
+/// the same name.
+fn schema_of(ty: &str) -> serde_json::Value {
+    use serde_json::json;
+
+    let ty: String = ty.chars().filter(|c| !c.is_whitespace()).collect();
+    let inner = |prefix: &str| ty.strip_prefix(prefix).and_then(|rest| rest.strip_suffix('>'));
+
+    if let Some(inner) = inner("Option<") {
+        let mut schema = schema_of(inner);
+        schema["nullable"] = json!(true);
+        return schema;
+    }
+    if let Some(inner) = inner("Box<") {
+        return schema_of(inner);
+    }
+    if let Some(inner) = inner("Vec<") {
+        return json!({ "type": "array", "items": schema_of(inner) });
+    }
+    if let Some(inner) = inner("HashSet<") {
+        return json!({ "type": "array", "items": schema_of(inner), "uniqueItems": true });
+    }
+    if let Some((_, value)) = inner("HashMap<").and_then(|inner| inner.split_once(',')) {
+        return json!({ "type": "object", "additionalProperties": schema_of(value) });
+    }
+    match ty.as_str() {
+        "String" => json!({ "type": "string" }),
+        "bool" => json!({ "type": "boolean" }),
+        "u32" | "u64" | "usize" => json!({ "type": "integer", "minimum": 0 }),
+        "i32" => json!({ "type": "integer" }),
+        "f32" => json!({ "type": "number" }),
+        "Uuid" => json!({ "type": "string", "format": "uuid" }),
+        "serde_json::Value" => json!({}),
+        name => json!({ "$ref": format!("#/components/schemas/{}", name) }),
+    }
+}
+
+/// JSON schema of an object, with every field not wrapped in `Option` required
+///
+/// # Arguments
+/// * `fields` - Field names and Rust types
+fn object_schema(fields: &[(&str, &str)]) -> serde_json::Value {
+    let properties: serde_json::Map<_, _> = fields
+        .iter()
+        .map(|(name, ty)| (name.to_string(), schema_of(ty)))
+        .collect();
+    let required: Vec<_> = fields
+        .iter()
+        .filter(|(_, ty)| !ty.starts_with("Option<"))
+        .map(|(name, _)| *name)
+        .collect();
+
+    let mut schema = serde_json::json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = serde_json::json!(required);
+    }
+    schema
+}
+
+/// Schema of a value internally tagged with `type`, like `GameEvent`
+///
+/// # Arguments
+/// * `tag` - Value of the `type` field
+/// * `fields` - The other fields, as (name, Rust type)
+fn tagged_schema<'a>(
+    tag: &str,
+    fields: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> serde_json::Value {
+    let tag_field = [("type", "String")];
+    let fields: Vec<_> = tag_field.into_iter().chain(fields).collect();
+    let mut schema = object_schema(&fields);
+    schema["properties"]["type"]["enum"] = serde_json::json!([tag]);
+    schema
+}
+
+/// Builds the OpenAPI 3.0 description served at GET /api/openapi.json
+///
+/// Paths come from `ROUTE_DOCS`, body schemas from `SCHEMA_FIELDS`, and the
+/// `GameEvent` schema from the event type registry, so it covers new event
+/// types without changes here.
+fn openapi_spec() -> serde_json::Value {
+    use serde_json::json;
+
+    let mut schemas: serde_json::Map<_, _> = SCHEMA_FIELDS
+        .iter()
+        .map(|(name, fields)| (name.to_string(), object_schema(fields)))
+        .collect();
+    let game_events: Vec<_> = EventTypeRegistry::all()
+        .iter()
+        .map(|info| {
+            let mut schema =
+                tagged_schema(&info.wire_name(), info.fields.iter().map(|f| (f.name, f.ty)));
+            schema["description"] = json!(info.description.trim());
+            schema
+        })
+        .collect();
+    schemas.insert("GameEvent".to_string(), json!({ "oneOf": game_events }));
+    let event_requests: Vec<_> = EVENT_REQUEST_BODIES
+        .iter()
+        .map(|(tag, body)| {
+            let fields = body
+                .and_then(|body| SCHEMA_FIELDS.iter().find(|(name, _)| *name == body))
+                .map_or(&[][..], |(_, fields)| fields);
+            tagged_schema(tag, fields.iter().copied())
+        })
+        .collect();
+    schemas.insert("EventRequest".to_string(), json!({ "oneOf": event_requests }));
+    schemas.insert("BatchRequest".to_string(), schema_of("Vec<EventRequest>"));
+    schemas.insert(
+        "LogLevel".to_string(),
+        json!({ "type": "string", "enum": ["info", "warning", "error", "critical"] }),
+    );
+
+    let mut paths = serde_json::Map::new();
+    let channel: &[_] = &[(
+        "channel",
+        "Option<String>",
+        "Channel to use, opened by POST requests and subscriptions if needed \
+            (default: the default channel)",
+    )];
+    for route in ROUTE_DOCS {
+        let channel = if route.channel { channel } else { &[] };
+        let mut parameters: Vec<_> = channel
+            .iter()
+            .chain(route.query)
+            .map(|(name, ty, description)| {
+                json!({
+                    "name": name,
+                    "in": "query",
+                    "required": !ty.starts_with("Option<"),
+                    "description": description,
+                    "schema": schema_of(ty),
+                })
+            })
+            .collect();
+        for segment in route.path.split('/').filter_map(|s| s.strip_prefix(':')) {
+            parameters.push(json!({
+                "name": segment,
+                "in": "path",
+                "required": true,
+                "schema": schema_of("Uuid"),
+            }));
+        }
+
+        let success = match route.response {
+            ResponseDoc::Json(ty) => json!({
+                "200": {
+                    "description": "OK",
+                    "content": { "application/json": { "schema": schema_of(ty) } },
+                }
+            }),
+            ResponseDoc::EventStream => json!({
+                "200": {
+                    "description": "Server-sent events, each carrying one GameEvent as JSON",
+                    "content": { "text/event-stream": { "schema": schema_of("GameEvent") } },
+                }
+            }),
+            ResponseDoc::WebSocket => json!({
+                "101": { "description": "Switching to the WebSocket protocol" }
+            }),
+            ResponseDoc::Text(content_type) => json!({
+                "200": {
+                    "description": "OK",
+                    "content": { content_type: { "schema": { "type": "string" } } },
+                }
+            }),
+        };
+        let mut operation = json!({ "summary": route.summary, "responses": success });
+        if !parameters.is_empty() {
+            operation["parameters"] = json!(parameters);
+        }
+        if let Some(body) = route.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema_of(body) } },
+            });
+        }
+        // Everything but the GET routes needs an API key once keys are configured
+        if route.method != "get" {
+            operation["security"] = json!([{ "apiKey": [] }]);
+            operation["responses"]["4XX"] = json!({
+                "description": "Invalid request, missing or unknown API key, or rate limited",
+                "content": { "application/json": { "schema": schema_of("ApiError") } },
+            });
+        }
+
+        let path = paths.entry(route.openapi_path()).or_insert_with(|| json!({}));
+        path[route.method] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "City Dashboard SSE Server",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Every endpoint working on a channel takes a `channel` query \
+                parameter, or a `channel` field in POST bodies; without one it uses the \
+                default channel.",
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "apiKey": { "type": "http", "scheme": "bearer" },
+            },
+        },
+    })
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    // Keys for the event-triggering endpoints
+    let api_keys = api_keys();
+    if api_keys.is_empty() {
+        warn!("API_KEYS is not set: event-triggering endpoints accept requests without a key");
+    } else {
+        info!("🔑 API keys loaded: {}", api_keys.names().join(", "));
+    }
+
+    // Origins browsers may call the API from
+    let cors_origins = match cors_origins() {
+        Ok(origins) => origins,
+        Err(message) => {
+            error!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    match &cors_origins {
+        AllowedOrigins::Any => warn!("CORS_ALLOWED_ORIGINS allows any origin to call the API"),
+        AllowedOrigins::List(origins) => {
+            let origins: Vec<_> = origins.iter().filter_map(|o| o.to_str().ok()).collect();
+            info!("🌐 CORS origins: {}", origins.join(", "));
+        }
+    }
+
+    // Create shared state
+    let mut state = AppState::new(history_capacity())
+        .with_broadcast_capacity(broadcast_capacity())
+        .with_api_keys(api_keys)
+        .with_cors_origins(cors_origins)
+        .with_events_limit(events_limit());
+
+    // Pick up where the last run left off, then keep logging events
+    let mut logged_events = Vec::new();
+    if let Some(path) = event_log_path() {
+        logged_events = match event_log::read_log(&path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("Cannot read event log {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let event_log = match EventLog::open(&path).await {
+            Ok(event_log) => event_log,
+            Err(e) => {
+                error!("Cannot open event log {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        };
+        info!("📼 Logging events to {}", path.display());
+        state = state.with_event_log(Arc::new(event_log));
+    }
+    let state = Arc::new(state);
+    if !logged_events.is_empty() {
+        info!("Restoring {} logged events", logged_events.len());
+        state.restore(logged_events).await;
+    }
+
+    // Close channels nobody has used for an hour
+    channels::collect_idle_channels(state.clone());
+
+    // Let connected clients know the stream is alive
+    let heartbeat_interval = heartbeat_interval();
+    info!("💓 Heartbeat every {}s while clients are connected", heartbeat_interval.as_secs());
+    heartbeat::send_heartbeats(state.clone(), heartbeat_interval);
+
+    // Rate limit the event-triggering endpoints per client IP
+    let mut limiter =
+        RateLimiter::new(rate_limit_per_sec()).with_trusted_proxy(trust_forwarded_for());
+    if let Some(burst) = rate_limit_burst() {
+        limiter = limiter.with_burst(burst);
+    }
+    let limiter = Arc::new(limiter);
+
+    let app = app(state.clone(), limiter);
+
+    // Start server
+    let addr = match bind_addr(std::env::var("BIND_ADDR").ok(), std::env::args().skip(1)) {
+        Ok(addr) => addr,
+        Err(message) => {
+            error!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Cannot listen on {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!("🚀 Server starting on http://{}", addr);
+    info!("📡 SSE endpoint: http://{}/events", addr);
+    info!("📝 API docs: http://{}/", addr);
+
+    // Ctrl-C or SIGTERM tells clients the server is going away before exiting
+    let served = shutdown::serve(listener, app, state.clone(), shutdown::signal()).await;
+    state.channels.close_event_log().await;
+    if let Err(e) = served {
+        error!("Server error: {}", e);
+        return ExitCode::FAILURE;
+    }
+    info!("Server stopped");
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_state_reflects_handlers() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+
+        assert!(barrier_break(
+            ChannelState(channel.clone()),
+            ApiJson(BarrierBrokenRequest {
+                team: "Red Team".to_string(),
+                message: None,
+            }),
+        )
+        .await
+        .is_ok());
+        assert!(scada_compromise(
+            ChannelState(channel.clone()),
+            ApiJson(ScadaCompromisedRequest {
+                building_id: Some(5),
+                team: "Red Team".to_string(),
+                message: None,
+            }),
+        )
+        .await
+        .is_ok());
+        assert!(danger_activate(
+            ChannelState(channel.clone()),
+            ApiJson(DangerModeRequest {
+                reason: "Test".to_string(),
+            }),
+        )
+        .await
+        .is_ok());
+
+        let response = get_state(ChannelState(channel.clone())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/json"
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["barrier_broken"], true);
+        assert_eq!(json["danger_mode_active"], true);
+        assert_eq!(json["emergency_stop_active"], false);
+        assert_eq!(json["led_broken"], serde_json::Value::Null);
+        assert_eq!(json["scada_compromised_buildings"]["5"], true);
+    }
+
+    #[tokio::test]
+    async fn test_event_history_since_and_limit() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        for reason in ["one", "two", "three"] {
+            assert!(emergency_start(
+                ChannelState(state.default_channel()),
+                ApiJson(EmergencyStopRequest {
+                    reason: reason.to_string(),
+                }),
+            )
+            .await
+            .is_ok());
+        }
+
+        let response = event_history(
+            ChannelState(state.default_channel()),
+            Query(HistoryQuery {
+                since: Some(1),
+                limit: Some(1),
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["truncated"], false);
+        let entries = json["events"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["id"], 2);
+        assert_eq!(entries[0]["event"]["reason"], "two");
+        assert!(entries[0]["timestamp"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_restart_restores_state_and_history_from_event_log() {
+        let path = std::env::temp_dir().join(format!("event-log-{}.jsonl", Uuid::new_v4()));
+        let event_log = Arc::new(EventLog::open(&path).await.unwrap());
+        let state = AppState::new(2).with_event_log(event_log);
+        let channel = state.default_channel();
+        channel
+            .trigger_all(vec![
+                GameEvent::BarrierBroken {
+                    team: "Red Team".to_string(),
+                    message: None,
+                },
+                GameEvent::DangerModeActivated {
+                    reason: "Breach".to_string(),
+                },
+                GameEvent::LedDisplayRepaired,
+            ])
+            .await;
+        let alpha = state.channels.get("alpha");
+        let score = alpha.game.read().await.score_update("Blue".to_string(), 5);
+        alpha.trigger_all(vec![score]).await;
+        // The log ends in the middle of a countdown
+        let timer_update = GameEvent::TimerUpdate {
+            remaining_secs: 90,
+            running: true,
+        };
+        state.channels.get("beta").trigger_all(vec![timer_update.clone()]).await;
+        channel.announce_shutdown();
+        state.channels.close_event_log().await;
+
+        let restarted = AppState::new(2);
+        restarted.restore(event_log::read_log(&path).unwrap()).await;
+        std::fs::remove_file(&path).unwrap();
+
+        let channel = restarted.default_channel();
+        assert_eq!(*channel.game.read().await, *state.default_channel().game.read().await);
+        assert!(channel.game.read().await.barrier_broken);
+        assert_eq!(restarted.channels.get("alpha").game.read().await.team_score("Blue"), 5);
+
+        // The countdown carries on from where the log left it, announced
+        // with a new TimerUpdate after the logged one (ID 1)
+        let beta = restarted.channels.get("beta");
+        assert_eq!(beta.game.read().await.timer_remaining_secs, Some(90));
+        let last = beta.history.lock().unwrap().entries(None, None).last().unwrap().clone();
+        assert_eq!((last.id, last.event), (2, timer_update));
+        assert!(beta.timer.lock().await.as_ref().is_some_and(|task| !task.is_finished()));
+        assert!(beta.stop_timer().await.is_some());
+
+        // The history holds the last events before the shutdown, and new
+        // events carry on after the shutdown announcement's ID
+        let ids = |channel: &Channel| -> Vec<u64> {
+            channel.history.lock().unwrap().entries(None, None).iter().map(|e| e.id).collect()
+        };
+        assert_eq!(ids(&channel), [2, 3]);
+        channel.trigger_all(vec![GameEvent::GameReset]).await;
+        assert_eq!(ids(&channel), [3, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_reopened_channel_carries_on_event_ids_across_restart() {
+        let path = std::env::temp_dir().join(format!("event-log-{}.jsonl", Uuid::new_v4()));
+        let event_log = Arc::new(EventLog::open(&path).await.unwrap());
+        let state = AppState::new(10).with_event_log(event_log);
+        let barrier_broken = GameEvent::BarrierBroken {
+            team: "Red Team".to_string(),
+            message: None,
+        };
+        state.channels.get("alpha").trigger_all(vec![barrier_broken]).await;
+
+        // The idle channel is removed, then opened again with a fresh state
+        let later = Instant::now() + channels::IDLE_CHANNEL_TIMEOUT;
+        assert_eq!(state.channels.remove_idle(later, channels::IDLE_CHANNEL_TIMEOUT), ["alpha"]);
+        let alpha = state.channels.get("alpha");
+        assert_eq!(*alpha.game.read().await, GameState::default());
+        alpha.trigger_all(vec![GameEvent::LedDisplayRepaired]).await;
+        state.channels.close_event_log().await;
+
+        // Event IDs never repeat, and a restart rebuilds the reopened channel
+        let logged = event_log::read_log(&path).unwrap();
+        let ids: Vec<_> = logged.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(logged[1].event, GameEvent::GameReset);
+
+        let restarted = AppState::new(10);
+        restarted.restore(logged).await;
+        std::fs::remove_file(&path).unwrap();
+        let restored = restarted.channels.get("alpha");
+        assert_eq!(*restored.game.read().await, *alpha.game.read().await);
+        restored.trigger_all(vec![GameEvent::GameReset]).await;
+        let last = restored.history.lock().unwrap().entries(None, None).last().unwrap().id;
+        assert_eq!(last, 4);
+    }
+
+    /// Connects to /events with a `Last-Event-ID` header and reads the first
+    /// `count` SSE frames
+    async fn sse_frames(state: Arc<AppState>, last_event_id: u64, count: usize) -> Vec<String> {
+        let request = axum::http::Request::get("/events")
+            .header("Last-Event-ID", last_event_id.to_string())
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let mut body = send(state, request).await.into_body().into_data_stream();
+
+        let mut frames = Vec::new();
+        while frames.len() < count {
+            frames.push(next_sse_frame(&mut body).await);
+        }
+        frames
+    }
+
+    /// Reads the next SSE frame of a stream
+    async fn next_sse_frame(body: &mut axum::body::BodyDataStream) -> String {
+        let chunk = tokio::time::timeout(Duration::from_secs(1), body.next())
+            .await
+            .expect("SSE frame")
+            .unwrap()
+            .unwrap();
+        String::from_utf8(chunk.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_subscribing_and_posting_open_channels() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let get = |uri: &str| {
+            axum::http::Request::get(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // Reading a channel that isn't open doesn't open it
+        for uri in [
+            "/api/state?channel=ghost",
+            "/api/scada?channel=ghost",
+            "/api/events/history?channel=ghost",
+        ] {
+            let (status, json) = into_parts(send(state.clone(), get(uri)).await).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+            assert_eq!(json["error"], "channel 'ghost' is not open");
+        }
+        assert!(state.channels.find("ghost").is_none());
+
+        // Subscribing or posting does
+        let response = send(state.clone(), get("/events?channel=alpha")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (status, _) =
+            post_json_with(state.clone(), "/api/barrier/break?channel=bravo", r#"{"team": "Red"}"#)
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        for name in ["alpha", "bravo"] {
+            assert!(state.channels.find(name).is_some(), "{}", name);
+        }
+        let (status, _) = into_parts(send(state, get("/api/state?channel=bravo")).await).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_channels_only_see_their_own_events() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let mut streams = Vec::new();
+        for uri in ["/events?channel=alpha", "/events?channel=bravo"] {
+            let request = axum::http::Request::get(uri)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let mut body = send(state.clone(), request).await.into_body().into_data_stream();
+            assert!(next_sse_frame(&mut body).await.starts_with("event: StateSnapshot"));
+            streams.push(body);
+        }
+
+        // Channels named by a JSON field and by the query string
+        for (uri, body) in [
+            ("/api/danger/activate", r#"{"channel": "bravo", "reason": "Breach"}"#),
+            ("/api/barrier/break?channel=alpha", r#"{"team": "Red"}"#),
+            ("/api/log", r#"{"channel": "bravo", "level": "info", "message": "Hi"}"#),
+        ] {
+            let (status, json) = post_json_with(state.clone(), uri, body).await;
+            assert_eq!(status, StatusCode::OK, "{}", json);
+        }
+
+        let [alpha, bravo] = &mut streams[..] else { unreachable!() };
+        assert!(next_sse_frame(alpha).await.starts_with("event: BarrierBroken"));
+        assert!(next_sse_frame(bravo).await.starts_with("event: DangerModeActivated"));
+        assert!(next_sse_frame(bravo).await.starts_with("event: LogMessage"));
+
+        // Each channel keeps its own game state
+        assert!(state.channels.get("alpha").game.read().await.barrier_broken);
+        assert!(!state.default_channel().game.read().await.barrier_broken);
+
+        let request = axum::http::Request::get("/api/channels")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, json) = into_parts(send(state.clone(), request).await).await;
+        assert_eq!(status, StatusCode::OK);
+        let subscribers: Vec<_> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|channel| (channel["name"].clone(), channel["subscribers"].clone()))
+            .collect();
+        assert_eq!(
+            subscribers,
+            [("alpha".into(), 1.into()), ("bravo".into(), 1.into()), ("default".into(), 0.into())]
+        );
+
+        let (status, json) = post_json_with(state, "/api/led/repair?channel=a%20b", "").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["field"], "channel");
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_replays_missed_events_then_live_events() {
+        let state = Arc::new(AppState::new(3));
+        for _ in 0..3 {
+            state.default_channel().trigger_all(vec![GameEvent::LedDisplayRepaired]).await;
+        }
+
+        let reader = tokio::spawn(sse_frames(state.clone(), 1, 4));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        state.default_channel().trigger_all(vec![GameEvent::DangerModeDeactivated]).await;
+
+        let frames = reader.await.unwrap();
+        assert!(frames[0].starts_with("event: ConnectionStatus"));
+        assert!(frames[1].contains("id: 2"));
+        assert!(frames[2].contains("id: 3"));
+        assert!(frames[3].starts_with("event: DangerModeDeactivated") && frames[3].contains("id: 4"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_past_history_gets_snapshot() {
+        let state = Arc::new(AppState::new(2));
+        state
+            .default_channel()
+            .trigger_all(vec![GameEvent::EmergencyStop { reason: "a".to_string() }])
+            .await;
+        for _ in 0..3 {
+            state.default_channel().trigger_all(vec![GameEvent::LedDisplayRepaired]).await;
+        }
+
+        // Event 2 was dropped, and 99 was never issued
+        for last_event_id in [1, 99] {
+            let frames = sse_frames(state.clone(), last_event_id, 1).await;
+            assert!(frames[0].starts_with("event: StateSnapshot"), "{}", frames[0]);
+            assert!(frames[0].contains(r#""emergency_stop_active":true"#));
+        }
+    }
+
+    /// Sends a JSON POST through the full router
+    ///
+    /// # Returns
+    /// Tuple of (status code, parsed JSON body)
+    async fn post_json(uri: &str, body: &str) -> (StatusCode, serde_json::Value) {
+        post_json_with(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), uri, body).await
+    }
+
+    /// Sends a request through the full router, against existing state
+    async fn send(state: Arc<AppState>, request: axum::http::Request<axum::body::Body>) -> Response {
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let limiter = Arc::new(RateLimiter::new(DEFAULT_RATE_LIMIT_PER_SEC));
+        let app = app(state, limiter).layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        app.oneshot(request).await.unwrap()
+    }
+
+    /// Like `post_json`, against existing state
+    async fn post_json_with(
+        state: Arc<AppState>,
+        uri: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = axum::http::Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        into_parts(send(state, request).await).await
+    }
+
+    /// Sends a DELETE request against existing state
+    async fn delete_with(state: Arc<AppState>, uri: &str) -> StatusCode {
+        let request = axum::http::Request::delete(uri)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        send(state, request).await.status()
+    }
+
+    async fn assert_invalid(uri: &str, body: &str, field: &str) {
+        let (status, json) = post_json(uri, body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{} {}", uri, body);
+        assert_eq!(json["ok"], false, "{} {}", uri, body);
+        assert_eq!(json["field"], field, "{} {}", uri, body);
+        assert!(json["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_barrier_endpoints_validate_team() {
+        let (status, json) =
+            post_json("/api/barrier/break", r#"{"team": "Red Team", "message": "Down"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["ok"], true);
+        assert_eq!(json["event"], "BarrierBroken");
+
+        assert_invalid("/api/barrier/break", r#"{"team": ""}"#, "team").await;
+        assert_invalid("/api/barrier/repair", r#"{"team": "  "}"#, "team").await;
+    }
+
+    #[tokio::test]
+    async fn test_led_endpoints_cap_message_length() {
+        let (status, _) = post_json("/api/led/break", r#"{"team": "Red Team"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let long = "x".repeat(api::MAX_TEXT_LENGTH + 1);
+        let body = format!(r#"{{"team": "Red Team", "message": "{}"}}"#, long);
+        assert_invalid("/api/led/break", &body, "message").await;
+    }
+
+    #[tokio::test]
+    async fn test_scada_endpoints_check_building_range() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"{"team": "Red Team", "building_id": 8}"#;
+        let (status, _) = post_json_with(state.clone(), "/api/scada/compromise", body).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_json_with(state, "/api/scada/restore", "{}").await;
+        assert_eq!(status, StatusCode::OK);
+
+        assert_invalid(
+            "/api/scada/compromise",
+            r#"{"team": "Red Team", "building_id": 99}"#,
+            "building_id",
+        )
+        .await;
+        assert_invalid("/api/scada/restore", r#"{"building_id": 0}"#, "building_id").await;
+
+        let (status, json) =
+            post_json("/api/scada/compromise", r#"{"team": "Red Team", "building_id": 0}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["error"], "invalid_building_id");
+        assert_eq!(json["max"], api::MAX_BUILDING_ID);
+        assert_eq!(json["message"], "building_id must be between 1 and 12");
+
+        let long_team = "x".repeat(api::MAX_TEAM_LENGTH + 1);
+        let body = format!(r#"{{"team": "{}", "building_id": 3}}"#, long_team);
+        assert_invalid("/api/scada/compromise", &body, "team").await;
+    }
+
+    #[tokio::test]
+    async fn test_scada_endpoints_reject_conflicting_requests() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let mut rx = state.default_channel().event_tx.subscribe();
+        let compromise = |team: &str, building: &str| {
+            format!(r#"{{"team": "{}", "building_id": {}}}"#, team, building)
+        };
+
+        let (status, json) =
+            post_json_with(state.clone(), "/api/scada/compromise", &compromise("Red Team", "5"))
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "ScadaCompromised");
+        assert_eq!(json["results"], serde_json::json!([{"building_id": 5, "ok": true}]));
+
+        // Another team can't take a building that is already held
+        let (status, json) =
+            post_json_with(state.clone(), "/api/scada/compromise", &compromise("Blue Team", "5"))
+                .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["holder"], "Red Team");
+
+        // Nor can a building nobody compromised be restored
+        let (status, json) =
+            post_json_with(state.clone(), "/api/scada/restore", r#"{"building_id": 6}"#).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(json.get("holder").is_none());
+
+        // Compromising every building skips the held one, one event per building
+        let (status, json) =
+            post_json_with(state.clone(), "/api/scada/compromise", &compromise("Blue Team", "null"))
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        let results = json["results"].as_array().unwrap();
+        assert_eq!(results.len(), api::MAX_BUILDING_ID as usize);
+        assert_eq!(results[4]["ok"], false);
+        assert_eq!(results[4]["holder"], "Red Team");
+        assert_eq!(results.iter().filter(|r| r["ok"] == true).count(), 11);
+
+        let mut broadcast = Vec::new();
+        while let Ok(sequenced) = rx.try_recv() {
+            broadcast.push(sequenced.event);
+        }
+        assert_eq!(broadcast.len(), 12);
+        assert!(broadcast.iter().all(|event| matches!(
+            event,
+            GameEvent::ScadaCompromised { building_id: Some(_), .. }
+        )));
+
+        let request = axum::http::Request::get("/api/scada")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, json) = into_parts(send(state.clone(), request).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["5"]["status"], "compromised");
+        assert_eq!(json["5"]["team"], "Red Team");
+        assert!(json["5"]["since"].is_u64());
+        assert_eq!(json["6"]["team"], "Blue Team");
+
+        // Restoring every building, then again with none left to restore
+        let (status, json) = post_json_with(state.clone(), "/api/scada/restore", "{}").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "ScadaRestored");
+        assert_eq!(rx.try_recv().unwrap().event, GameEvent::ScadaRestored { building_id: None });
+        let (status, _) = post_json_with(state, "/api/scada/restore", "{}").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_game_state_and_scada_board_agree_after_compromising_every_building() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"{"team": "Red Team"}"#;
+        let (status, _) = post_json_with(state.clone(), "/api/scada/compromise", body).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let channel = state.default_channel();
+        let game = channel.game.read().await;
+        let board = channel.scada.lock().unwrap().buildings().clone();
+        assert_eq!(board.len(), api::MAX_BUILDING_ID as usize);
+        assert_eq!(game.scada_compromised_buildings.len(), board.len());
+        for (id, status) in &board {
+            let holder = match status {
+                scada::ScadaStatus::Compromised { team, .. } => Some(team.as_str()),
+                scada::ScadaStatus::Working => None,
+            };
+            assert_eq!(holder, Some("Red Team"));
+            assert_eq!(game.scada_compromised_buildings.get(id), Some(&true));
+            assert_eq!(game.scada_compromised_by.get(id).map(String::as_str), holder);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_building_fire_endpoints_track_burning_buildings() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (status, json) = post_json_with(
+            state.clone(),
+            "/api/building/fire",
+            r#"{"building_id": 3, "intensity": 0.5}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "BuildingFire");
+        post_json_with(state.clone(), "/api/building/fire", r#"{"building_id": 7}"#).await;
+        assert_eq!(
+            state.default_channel().game.read().await.buildings_on_fire,
+            [(3, 0.5), (7, 1.0)].into()
+        );
+
+        let (status, json) = post_json_with(
+            state.clone(),
+            "/api/building/fire/extinguish",
+            r#"{"building_id": 3}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "BuildingFireExtinguished");
+        assert_eq!(state.default_channel().game.read().await.buildings_on_fire, [(7, 1.0)].into());
+
+        assert_invalid("/api/building/fire", r#"{"building_id": 13}"#, "building_id").await;
+        assert_invalid(
+            "/api/building/fire",
+            r#"{"building_id": 3, "intensity": 1.5}"#,
+            "intensity",
+        )
+        .await;
+        assert_invalid("/api/building/fire/extinguish", r#"{"building_id": 0}"#, "building_id")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_power_endpoints_track_districts() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        for district in ["west", "east"] {
+            let body = format!(r#"{{"district": "{}"}}"#, district);
+            let (status, json) = post_json_with(state.clone(), "/api/power/outage", &body).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(json["event"], "PowerOutage");
+        }
+        let (status, json) =
+            post_json_with(state.clone(), "/api/power/restore", r#"{"district": "west"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "PowerRestored");
+        assert_eq!(
+            state.default_channel().game.read().await.districts_without_power,
+            ["east".to_string()].into()
+        );
+
+        assert_invalid("/api/power/outage", r#"{"district": " "}"#, "district").await;
+    }
+
+    #[tokio::test]
+    async fn test_road_endpoints_track_closed_roads() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        for road_id in [0, 4] {
+            let body = format!(r#"{{"road_id": {}}}"#, road_id);
+            let (status, json) = post_json_with(state.clone(), "/api/road/close", &body).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(json["event"], "RoadClosure");
+        }
+        let (status, _) =
+            post_json_with(state.clone(), "/api/road/open", r#"{"road_id": 0}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(state.default_channel().game.read().await.closed_roads, [4].into());
+
+        assert_invalid("/api/road/close", r#"{"road_id": 5}"#, "road_id").await;
+        assert_invalid("/api/road/open", r#"{"road_id": -1}"#, "road_id").await;
+    }
+
+    #[tokio::test]
+    async fn test_weather_endpoint_tracks_rain_intensity() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (status, json) =
+            post_json_with(state.clone(), "/api/weather", r#"{"rain_intensity": 0.25}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "WeatherChange");
+        assert_eq!(state.default_channel().game.read().await.rain_intensity, 0.25);
+
+        assert_invalid("/api/weather", r#"{"rain_intensity": 1.5}"#, "rain_intensity").await;
+        assert_invalid("/api/weather", "{}", "rain_intensity").await;
+    }
+
+    #[tokio::test]
+    async fn test_green_wave_endpoint_validates_speed() {
+        let (status, json) = post_json("/api/traffic/green_wave", r#"{"speed": 60}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "GreenWave");
+
+        let (status, _) = post_json("/api/traffic/green_wave", "{}").await;
+        assert_eq!(status, StatusCode::OK);
+
+        assert_invalid("/api/traffic/green_wave", r#"{"speed": 0}"#, "speed").await;
+        assert_invalid("/api/traffic/green_wave", r#"{"speed": 501}"#, "speed").await;
+    }
+
+    #[tokio::test]
+    async fn test_emergency_and_danger_endpoints_cap_reason_length() {
+        let (status, _) = post_json("/api/emergency/start", r#"{"reason": "Drill"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_json("/api/danger/activate", r#"{"reason": "Breach"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let body = format!(r#"{{"reason": "{}"}}"#, "x".repeat(api::MAX_TEXT_LENGTH + 1));
+        assert_invalid("/api/emergency/start", &body, "reason").await;
+        assert_invalid("/api/danger/activate", &body, "reason").await;
+    }
+
+    #[tokio::test]
+    async fn test_log_endpoint_rejects_unknown_level() {
+        let (status, _) =
+            post_json("/api/log", r#"{"level": "warning", "message": "Heads up"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        assert_invalid("/api/log", r#"{"level": "panic", "message": "Hi"}"#, "level").await;
+        assert_invalid("/api/log", r#"{"level": "info"}"#, "message").await;
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_bad_request() {
+        let (status, json) = post_json("/api/log", "{not json").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["ok"], false);
+        assert!(json.get("field").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rejections_outside_handlers_are_json() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let get = |uri: &str| {
+            axum::http::Request::get(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // Bad query string, bad path parameter, unknown route, unknown SSE filter
+        for (request, expected) in [
+            (get("/api/events/history?since=abc"), StatusCode::BAD_REQUEST),
+            (
+                axum::http::Request::delete("/api/events/schedule/not-a-uuid")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+                StatusCode::BAD_REQUEST,
+            ),
+            (get("/api/nope"), StatusCode::NOT_FOUND),
+            (get("/events?types=bogus"), StatusCode::BAD_REQUEST),
+        ] {
+            let uri = request.uri().to_string();
+            let (status, json) = into_parts(send(state.clone(), request).await).await;
+            assert_eq!(status, expected, "{}", uri);
+            assert_eq!(json["ok"], false, "{}", uri);
+            assert!(!json["error"].as_str().unwrap().is_empty(), "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_rejection_is_json_with_retry_after() {
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let limiter = Arc::new(RateLimiter::new(1));
+        let app = app(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), limiter)
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        let mut last = None;
+        for _ in 0..3 {
+            let request = axum::http::Request::post("/api/led/repair")
+                .body(axum::body::Body::empty())
+                .unwrap();
+            last = Some(app.clone().oneshot(request).await.unwrap());
+        }
+
+        let response = last.unwrap();
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        let (status, json) = into_parts(response).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(json, serde_json::json!({"ok": false, "error": "Rate limit exceeded"}));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_allows_burst_of_twenty_by_default() {
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let limiter = Arc::new(RateLimiter::new(DEFAULT_RATE_LIMIT_PER_SEC));
+        let app = app(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), limiter)
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))));
+        let mut statuses = Vec::new();
+        for _ in 0..21 {
+            let request = axum::http::Request::post("/api/led/repair")
+                .body(axum::body::Body::empty())
+                .unwrap();
+            statuses.push(app.clone().oneshot(request).await.unwrap().status());
+        }
+
+        assert!(statuses[..20].iter().all(|status| status.is_success()), "{:?}", statuses);
+        assert_eq!(statuses[20], StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_required_on_trigger_endpoints() {
+        let state = Arc::new(
+            AppState::new(DEFAULT_HISTORY_CAPACITY).with_api_keys(ApiKeys::parse("ops:s3cret")),
+        );
+        let post = |authorization: Option<&str>| {
+            let mut request = axum::http::Request::post("/api/led/repair");
+            if let Some(value) = authorization {
+                request = request.header(header::AUTHORIZATION, value);
+            }
+            request.body(axum::body::Body::empty()).unwrap()
+        };
+
+        // Missing header
+        let response = send(state.clone(), post(None)).await;
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        let (status, json) = into_parts(response).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(json["ok"], false);
+
+        // Unknown key
+        let (status, json) = into_parts(send(state.clone(), post(Some("Bearer wrong"))).await).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json["ok"], false);
+        assert!(state.default_channel().history.lock().unwrap().entries(None, None).is_empty());
+
+        // Valid key
+        let (status, json) = into_parts(send(state.clone(), post(Some("Bearer s3cret"))).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "LedDisplayRepaired");
+
+        // Read-only endpoints stay public
+        let request = axum::http::Request::get("/api/state")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        assert_eq!(send(state.clone(), request).await.status(), StatusCode::OK);
+        let request = axum::http::Request::get("/")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        assert_eq!(send(state, request).await.status(), StatusCode::OK);
+    }
+
+    /// Scrapes GET /metrics
+    async fn scrape_metrics(state: Arc<AppState>) -> String {
+        let request = axum::http::Request::get("/metrics")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(state, request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_events_and_requests() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (status, _) =
+            post_json_with(state.clone(), "/api/barrier/break", r#"{"team": "Red"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_json_with(state.clone(), "/api/barrier/break", "{}").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let text = scrape_metrics(state).await;
+        assert!(text.contains(r#"city_events_total{type="BarrierBroken"} 1"#));
+        assert!(text.contains(r#"city_events_total{type="BarrierRepaired"} 0"#));
+        assert!(text.contains("city_broadcast_errors_total 1"));
+        assert!(text.contains("city_sse_clients 0"));
+        assert!(text.contains(
+            r#"city_http_requests_total{method="POST",path="/api/barrier/break",status="200"} 1"#
+        ));
+        assert!(text.contains(
+            r#"city_http_requests_total{method="POST",path="/api/barrier/break",status="422"} 1"#
+        ));
+        assert!(text.contains("city_rejected_requests_total 1"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_sse_clients_until_they_disconnect() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let request = axum::http::Request::get("/events")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let mut body = send(state.clone(), request).await.into_body().into_data_stream();
+        body.next().await.unwrap().unwrap();
+        assert!(scrape_metrics(state.clone()).await.contains("city_sse_clients 1"));
+
+        // Disconnecting in the middle of the stream
+        drop(body);
+        assert!(scrape_metrics(state).await.contains("city_sse_clients 0"));
+    }
+
+    #[tokio::test]
+    async fn test_responses_carry_a_request_id() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+
+        let request = axum::http::Request::get("/api/state")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(state.clone(), request).await;
+        let request_id = response.headers()["x-request-id"].to_str().unwrap();
+        assert!(request_id.parse::<Uuid>().is_ok());
+
+        // An ID sent by the client is kept
+        let request = axum::http::Request::post("/api/led/repair")
+            .header("x-request-id", "dashboard-42")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(state, request).await;
+        assert_eq!(response.headers()["x-request-id"], "dashboard-42");
+    }
+
+    #[tokio::test]
+    async fn test_cors_allows_only_configured_origins() {
+        let origins = AllowedOrigins::parse("https://ops.example.com").unwrap();
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY).with_cors_origins(origins));
+        let allowed_origin = |response: &Response| {
+            response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+        };
+
+        // The configured origin is echoed back, any other gets no header
+        for (origin, expected) in [
+            ("https://ops.example.com", Some("https://ops.example.com")),
+            ("https://evil.example.com", None),
+        ] {
+            let request = axum::http::Request::get("/api/state")
+                .header(header::ORIGIN, origin)
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = send(state.clone(), request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(allowed_origin(&response).as_ref().map(|v| v.to_str().unwrap()), expected);
+        }
+
+        // Preflight for an authenticated JSON POST from the configured origin
+        let preflight = |origin: &str| {
+            axum::http::Request::options("/api/barrier/break")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type,authorization")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let response = send(state.clone(), preflight("https://ops.example.com")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(allowed_origin(&response).unwrap(), "https://ops.example.com");
+        let methods = &response.headers()[header::ACCESS_CONTROL_ALLOW_METHODS];
+        assert_eq!(methods, "GET,POST");
+        let headers = &response.headers()[header::ACCESS_CONTROL_ALLOW_HEADERS];
+        assert!(headers.to_str().unwrap().contains("authorization"));
+
+        let response = send(state, preflight("https://evil.example.com")).await;
+        assert_eq!(allowed_origin(&response), None);
+
+        // By default every origin is allowed
+        let request = axum::http::Request::get("/api/state")
+            .header(header::ORIGIN, "http://localhost:8000")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), request).await;
+        assert_eq!(allowed_origin(&response).unwrap(), "*");
+    }
+
+    #[tokio::test]
+    async fn test_events_batch_applies_events_in_order() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"[
+            {"type": "barrier_break", "team": "Red", "message": "Gate down"},
+            {"type": "danger_activate", "reason": "Breach"},
+            {"type": "led_repair"}
+        ]"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events/batch", body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["triggered"], 3);
+
+        let names: Vec<_> = state
+            .default_channel()
+            .history
+            .lock()
+            .unwrap()
+            .entries(None, None)
+            .iter()
+            .map(|entry| entry.event.event_name())
+            .collect();
+        assert_eq!(names, ["BarrierBroken", "DangerModeActivated", "LedDisplayRepaired"]);
+
+        let channel = state.default_channel();
+        let game = channel.game.read().await;
+        assert!(game.barrier_broken && game.danger_mode_active);
+    }
+
+    #[tokio::test]
+    async fn test_events_batch_rejects_whole_batch_on_invalid_event() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"[
+            {"type": "danger_activate", "reason": "Breach"},
+            {"type": "barrier_break", "team": ""}
+        ]"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events/batch", body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["field"], "[1].team");
+        assert!(state.default_channel().history.lock().unwrap().entries(None, None).is_empty());
+
+        let (status, json) =
+            post_json_with(state.clone(), "/api/events/batch", r#"[{"type": "explode"}]"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["field"], "[0].type");
+    }
+
+    #[tokio::test]
+    async fn test_events_broadcasts_valid_batch_in_order() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (_, mut rx) = state.default_channel().subscribe_with_replay(None);
+        let body = r#"{"events": [
+            {"type": "barrier_break", "team": "Red"},
+            {"type": "log", "level": "warning", "message": "Gate down"},
+            {"type": "danger_activate", "reason": "Breach"}
+        ]}"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events", body).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert_eq!(json["ok"], true);
+        assert_eq!(json["triggered"], 3);
+        assert_eq!(
+            json["results"][1],
+            serde_json::json!({"index": 1, "ok": true, "event": "LogMessage"})
+        );
+
+        let mut names = Vec::new();
+        while let Ok(sequenced) = rx.try_recv() {
+            names.push(sequenced.event.event_name());
+        }
+        assert_eq!(names, ["BarrierBroken", "LogMessage", "DangerModeActivated"]);
+    }
+
+    #[tokio::test]
+    async fn test_events_with_invalid_entry_broadcasts_nothing() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (_, mut rx) = state.default_channel().subscribe_with_replay(None);
+        let body = r#"{"events": [
+            {"type": "danger_activate", "reason": "Breach"},
+            {"type": "barrier_break", "team": ""},
+            {"type": "explode"}
+        ]}"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events", body).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["ok"], false);
+        assert_eq!(json["triggered"], 0);
+        let results = json["results"].as_array().unwrap();
+        assert_eq!(results[0]["ok"], true);
+        assert_eq!((&results[1]["ok"], &results[1]["field"]), (&false.into(), &"team".into()));
+        assert_eq!(results[2]["field"], "type");
+        assert!(rx.try_recv().is_err());
+        assert!(!state.default_channel().game.read().await.danger_mode_active);
+    }
+
+    #[tokio::test]
+    async fn test_events_over_limit_is_rejected() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY).with_events_limit(2));
+        let body = r#"{"events": [
+            {"type": "led_repair"}, {"type": "led_repair"}, {"type": "led_repair"}
+        ]}"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events", body).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(json["max"], 2);
+        assert!(state.default_channel().history.lock().unwrap().entries(None, None).is_empty());
+    }
+
+    /// Status and JSON body of a response
+    async fn into_parts(response: Response) -> (StatusCode, serde_json::Value) {
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_reset_clears_game_state() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"[
+            {"type": "barrier_break", "team": "Red"},
+            {"type": "scada_compromise", "team": "Red", "building_id": 4},
+            {"type": "danger_activate", "reason": "Breach"}
+        ]"#;
+        post_json_with(state.clone(), "/api/events/batch", body).await;
+        let channel = state.default_channel();
+        let scheduled = channel.schedule(Duration::from_secs(60), GameEvent::LedDisplayRepaired);
+
+        let (status, json) = post_json_with(state.clone(), "/api/reset", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["event"], "GameReset");
+        assert_eq!(*channel.game.read().await, GameState::default());
+
+        // Events scheduled before the reset never fire
+        assert!(channel.scheduled.lock().unwrap().is_empty());
+        assert!(!channel.cancel_scheduled(scheduled));
+    }
+
+    #[tokio::test]
+    async fn test_team_score_broadcasts_running_total() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let (_, mut rx) = state.default_channel().subscribe_with_replay(None);
+        for body in [
+            r#"{"team": "Red", "delta": 100}"#,
+            r#"{"team": "Blue", "delta": 25}"#,
+            r#"{"team": "Red", "delta": -40}"#,
+        ] {
+            let (status, json) = post_json_with(state.clone(), "/api/team/score", body).await;
+            assert_eq!(status, StatusCode::OK, "{}", json);
+            assert_eq!(json["event"], "TeamScoreUpdate");
+        }
+
+        let mut totals = Vec::new();
+        while let Ok(sequenced) = rx.try_recv() {
+            if let GameEvent::TeamScoreUpdate { team, total, .. } = sequenced.event {
+                totals.push((team, total));
+            }
+        }
+        assert_eq!(
+            totals,
+            [("Red".to_string(), 100), ("Blue".to_string(), 25), ("Red".to_string(), 60)]
+        );
+
+        let request = axum::http::Request::get("/api/team/scores")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, json) = into_parts(send(state.clone(), request).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json, serde_json::json!({"Red": 60, "Blue": 25}));
+
+        let (status, json) =
+            post_json_with(state, "/api/team/score", r#"{"team": " ", "delta": 5}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["field"], "team");
+    }
+
+    #[tokio::test]
+    async fn test_timer_counts_down_and_expires() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+        let (_, mut rx) = channel.subscribe_with_replay(None);
+
+        let (status, json) =
+            post_json_with(state.clone(), "/api/timer/start", r#"{"duration_secs": 2}"#).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert_eq!(channel.game.read().await.timer_remaining_secs, Some(2));
+
+        let mut events = Vec::new();
+        for _ in 0..3 {
+            let next = tokio::time::timeout(Duration::from_secs(2), rx.recv()).await;
+            events.push(next.unwrap().unwrap().event);
+        }
+        assert_eq!(
+            events,
+            [
+                GameEvent::TimerUpdate {
+                    remaining_secs: 2,
+                    running: true
+                },
+                GameEvent::TimerUpdate {
+                    remaining_secs: 1,
+                    running: true
+                },
+                GameEvent::TimerExpired,
+            ]
+        );
+        assert_eq!(channel.game.read().await.timer_remaining_secs, None);
+
+        // Nothing left to stop once it has expired
+        let (status, _) = post_json_with(state, "/api/timer/stop", "").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_timer_stop_reports_time_left() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+        let (status, _) =
+            post_json_with(state.clone(), "/api/timer/start", r#"{"duration_secs": 600}"#).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, mut rx) = channel.subscribe_with_replay(None);
+        let (status, json) = post_json_with(state.clone(), "/api/timer/stop", "").await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert_eq!(
+            rx.try_recv().unwrap().event,
+            GameEvent::TimerUpdate {
+                remaining_secs: 600,
+                running: false
+            }
+        );
+        assert_eq!(channel.game.read().await.timer_remaining_secs, None);
+        assert!(channel.timer.lock().await.is_none());
+
+        let (status, json) = post_json_with(state.clone(), "/api/timer/stop", "").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(json["ok"], false);
+
+        let (status, json) =
+            post_json_with(state, "/api/timer/start", r#"{"duration_secs": 0}"#).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(json["field"], "duration_secs");
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_event_is_broadcast_after_delay() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+        let (_, mut rx) = channel.subscribe_with_replay(None);
+        let body = r#"{"delay_secs": 0, "event": {"type": "danger_activate", "reason": "Timer"}}"#;
+
+        let (status, json) = post_json_with(state.clone(), "/api/events/schedule", body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["scheduled_id"].as_str().unwrap().parse::<Uuid>().is_ok());
+
+        let fired = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fired.event.event_name(), "DangerModeActivated");
+        assert!(channel.game.read().await.danger_mode_active);
+        assert!(channel.scheduled.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_event_can_be_cancelled() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let body = r#"{"delay_secs": 60, "event": {"type": "led_repair"}}"#;
+        let (_, json) = post_json_with(state.clone(), "/api/events/schedule", body).await;
+        let uri = format!("/api/events/schedule/{}", json["scheduled_id"].as_str().unwrap());
+
+        assert_eq!(delete_with(state.clone(), &uri).await, StatusCode::OK);
+        assert_eq!(delete_with(state.clone(), &uri).await, StatusCode::NOT_FOUND);
+        assert!(state.default_channel().scheduled.lock().unwrap().is_empty());
+        assert_eq!(state.default_channel().history.lock().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_validates_delay_and_event() {
+        let too_long = format!(
+            r#"{{"delay_secs": {}, "event": {{"type": "led_repair"}}}}"#,
+            api::MAX_SCHEDULE_DELAY_SECS + 1
+        );
+        assert_invalid("/api/events/schedule", &too_long, "delay_secs").await;
+        assert_invalid(
+            "/api/events/schedule",
+            r#"{"delay_secs": 5, "event": {"type": "barrier_break", "team": ""}}"#,
+            "event.team",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_clients_and_queue_depth() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let channel = state.default_channel();
+        let (_, _rx) = channel.subscribe_with_replay(None);
+        channel.broadcast(GameEvent::LedDisplayRepaired);
+        channel.broadcast(GameEvent::EmergencyStopDeactivated);
+
+        let (status, json) = into_parts(health(State(state)).await.into_response()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            json,
+            serde_json::json!({"status": "ok", "clients": 1, "queue_depth": 2})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sse_events_are_named_after_their_variant() {
+        let event = to_sse_event(
+            Some(7),
+            &GameEvent::DangerModeActivated {
+                reason: "Test".to_string(),
+            },
+        )
+        .unwrap();
+        let stream = tokio_stream::iter([Ok::<_, std::convert::Infallible>(event)]);
+        let body = axum::body::to_bytes(Sse::new(stream).into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::str::from_utf8(&body).unwrap(),
+            "event: DangerModeActivated\n\
+             data: {\"type\":\"danger_mode_activated\",\"reason\":\"Test\"}\n\
+             id: 7\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_degraded_health_is_service_unavailable() {
+        let (status, json) = into_parts(Health::Degraded.into_response()).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(json, serde_json::json!({"status": "degraded"}));
+    }
+    #[test]
+    fn test_slow_client_lag_is_detected_near_capacity() {
+        let state = AppState::new(DEFAULT_HISTORY_CAPACITY).with_broadcast_capacity(10);
+        let channel = state.default_channel();
+        let mut rx = channel.event_tx.subscribe();
+
+        for _ in 0..8 {
+            channel.publish(GameEvent::LedDisplayRepaired);
+        }
+        assert!(!channel.check_client_lag());
+
+        channel.publish(GameEvent::LedDisplayRepaired);
+        assert!(channel.check_client_lag());
+        assert_eq!(rx.len(), 9);
+
+        // Catching up clears the lag
+        while rx.try_recv().is_ok() {}
+        assert!(!channel.check_client_lag());
+    }
+
+    #[test]
+    fn test_bind_addr_reads_env_and_port_argument() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(bind_addr(None, args(&[]).into_iter()), Ok(DEFAULT_BIND_ADDR));
+        assert_eq!(
+            bind_addr(Some("127.0.0.1:8080".to_string()), args(&[]).into_iter()),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 8080)))
+        );
+        // An IP alone keeps the default port; --port overrides either
+        assert_eq!(
+            bind_addr(Some("127.0.0.1".to_string()), args(&["--port", "4000"]).into_iter()),
+            Ok(SocketAddr::from(([127, 0, 0, 1], 4000)))
+        );
+        assert_eq!(
+            bind_addr(None, args(&["--port=4001"]).into_iter()).map(|addr| addr.port()),
+            Ok(4001)
+        );
+
+        assert!(bind_addr(Some("localhost".to_string()), args(&[]).into_iter()).is_err());
+        assert!(bind_addr(None, args(&["--port"]).into_iter()).is_err());
+        assert!(bind_addr(None, args(&["--port", "99999"]).into_iter()).is_err());
+        assert!(bind_addr(None, args(&["--verbose"]).into_iter()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_notifies_clients_and_stops_server() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let limiter = Arc::new(RateLimiter::new(DEFAULT_RATE_LIMIT_PER_SEC));
+        let (stop_tx, stop_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(shutdown::serve(
+            listener,
+            app(state.clone(), limiter),
+            state.clone(),
+            async move {
+                let _ = stop_rx.await;
+            },
+        ));
+
+        // An SSE client, read as raw HTTP
+        let mut sse = tokio::net::TcpStream::connect(addr).await.unwrap();
+        sse.write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buffer = vec![0; 4096];
+        let read = sse.read(&mut buffer).await.unwrap();
+        assert!(String::from_utf8_lossy(&buffer[..read]).contains("StateSnapshot"));
+
+        // And a WebSocket client
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        assert_eq!(ws_next(&mut ws).await["type"], "state_snapshot");
+
+        stop_tx.send(()).unwrap();
+
+        // The SSE stream ends with the shutdown status
+        let mut rest = Vec::new();
+        tokio::time::timeout(Duration::from_secs(2), sse.read_to_end(&mut rest))
+            .await
+            .expect("SSE stream ended")
+            .unwrap();
+        let rest = String::from_utf8_lossy(&rest);
+        assert!(rest.contains("event: ConnectionStatus"), "{}", rest);
+        assert!(rest.contains("server shutting down"), "{}", rest);
+
+        // The WebSocket gets the same event, then a close frame
+        let event = ws_next(&mut ws).await;
+        assert_eq!(event["type"], "connection_status");
+        assert_eq!(event["connected"], false);
+        assert_eq!(event["error"], "server shutting down");
+        let closing = tokio::time::timeout(Duration::from_secs(1), ws.next()).await.unwrap();
+        assert!(matches!(closing, Some(Ok(Message::Close(_))) | None), "{:?}", closing);
+        drop(ws);
+
+        // With every connection closed the server stops well within the grace period
+        tokio::time::timeout(shutdown::SHUTDOWN_GRACE_PERIOD, server)
+            .await
+            .expect("server stopped")
+            .unwrap()
+            .unwrap();
+    }
+
+    type WsClient = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    /// Serves the router on a local port and opens a WebSocket to /ws
+    ///
+    /// # Returns
+    /// The client, after it has read the state snapshot sent on connect
+    async fn ws_connect(state: Arc<AppState>, authorization: Option<&str>) -> WsClient {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let limiter = Arc::new(RateLimiter::new(DEFAULT_RATE_LIMIT_PER_SEC));
+        let service = app(state, limiter).into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, service).await.unwrap() });
+
+        let mut request = format!("ws://{}/ws", addr).into_client_request().unwrap();
+        if let Some(value) = authorization {
+            request
+                .headers_mut()
+                .insert(header::AUTHORIZATION, value.parse().unwrap());
+        }
+        let (mut socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        assert_eq!(ws_next(&mut socket).await["type"], "state_snapshot");
+        socket
+    }
+
+    /// Reads the next text frame as JSON
+    async fn ws_next(socket: &mut WsClient) -> serde_json::Value {
+        let message = tokio::time::timeout(Duration::from_secs(1), socket.next())
+            .await
+            .expect("WebSocket frame")
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(message.to_text().unwrap()).unwrap()
+    }
+
+    /// Sends a command frame and reads the reply
+    async fn ws_command(socket: &mut WsClient, command: serde_json::Value) -> serde_json::Value {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        socket.send(Message::Text(command.to_string())).await.unwrap();
+        ws_next(socket).await
+    }
+
+    #[tokio::test]
+    async fn test_websocket_streams_events_and_acks_commands() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let mut socket = ws_connect(state.clone(), None).await;
+
+        // Server to client
+        state.default_channel().trigger_all(vec![GameEvent::LedDisplayRepaired]).await;
+        assert_eq!(ws_next(&mut socket).await["type"], "led_display_repaired");
+
+        // Client to server: the ack, then the event broadcast to every client
+        let command = serde_json::json!({"command": "barrier_break", "payload": {"team": "Red"}});
+        let reply = ws_command(&mut socket, command).await;
+        assert_eq!(reply["type"], "ack");
+        assert_eq!(reply["command"], "barrier_break");
+        assert_eq!(reply["ok"], true);
+        assert_eq!(reply["event"], "BarrierBroken");
+        let event = ws_next(&mut socket).await;
+        assert_eq!(event["type"], "barrier_broken");
+        assert_eq!(event["team"], "Red");
+        assert!(state.default_channel().game.read().await.barrier_broken);
+
+        // Commands without fields may leave out the payload
+        let reply = ws_command(&mut socket, serde_json::json!({"command": "led_repair"})).await;
+        assert_eq!(reply["event"], "LedDisplayRepaired");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_rejects_invalid_commands() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY));
+        let mut socket = ws_connect(state.clone(), None).await;
+
+        let empty_team = serde_json::json!({"command": "barrier_break", "payload": {"team": ""}});
+        for (command, field) in [
+            (serde_json::json!({"command": "explode"}), "command"),
+            (empty_team, "payload.team"),
+            (serde_json::json!({"command": "barrier_break", "payload": "Red"}), "payload"),
+        ] {
+            let reply = ws_command(&mut socket, command.clone()).await;
+            assert_eq!(reply["type"], "error", "{}", command);
+            assert_eq!(reply["ok"], false, "{}", command);
+            assert_eq!(reply["field"], field, "{}", command);
+            assert_eq!(reply["command"], command["command"]);
+        }
+
+        let reply = ws_command(&mut socket, serde_json::json!({"payload": {}})).await;
+        assert_eq!(reply["type"], "error");
+        assert!(reply.get("command").is_none());
+        assert!(state.default_channel().history.lock().unwrap().entries(None, None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_websocket_commands_need_an_api_key() {
+        let state = Arc::new(
+            AppState::new(DEFAULT_HISTORY_CAPACITY).with_api_keys(ApiKeys::parse("ops:s3cret")),
+        );
+        let command = serde_json::json!({"command": "led_repair"});
+
+        let mut socket = ws_connect(state.clone(), None).await;
+        let reply = ws_command(&mut socket, command.clone()).await;
+        assert_eq!(reply["type"], "error");
+        assert!(state.default_channel().history.lock().unwrap().entries(None, None).is_empty());
+
+        let mut socket = ws_connect(state.clone(), Some("Bearer s3cret")).await;
+        let reply = ws_command(&mut socket, command).await;
+        assert_eq!(reply["type"], "ack");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_reports_missed_events() {
+        let state = Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY).with_broadcast_capacity(16));
+        let mut socket = ws_connect(state.clone(), None).await;
+
+        // The single-threaded test runtime cannot forward any of these
+        // before they are all sent
+        for _ in 0..30 {
+            state.default_channel().publish(GameEvent::LedDisplayRepaired);
+        }
+        let frame = ws_next(&mut socket).await;
+        assert_eq!(frame["type"], "lagged");
+        assert_eq!(frame["missed"], 14);
+        assert_eq!(ws_next(&mut socket).await["type"], "led_display_repaired");
+    }
+
+    /// (method, path, handler) of every route registered in `app`, read from its source
+    fn registered_routes() -> Vec<(String, String, String)> {
+        let source = include_str!("main.rs");
+        let app_source = &source[source.find("\nfn app(").unwrap()..];
+        let app_source = &app_source[..app_source.find("\n}\n").unwrap()];
+        app_source
+            .split(".route(\"")
+            .skip(1)
+            .map(|route| {
+                let (path, handler) = route.split_once('"').unwrap();
+                let (method, handler) =
+                    handler.trim_start_matches([',', ' ']).split_once('(').unwrap();
+                let handler = handler.split(')').next().unwrap();
+                (method.to_string(), path.to_string(), handler.to_string())
+            })
+            .collect()
+    }
+
+    /// Query parameters a handler reads, found in its signature: `channel`
+    /// for the channel extractors, and the fields of any `Query` struct
+    fn accepted_query(handler: &str) -> Vec<String> {
+        let (source, name) = match handler.strip_prefix("ws::") {
+            Some(name) => (include_str!("ws.rs"), name),
+            None => (include_str!("main.rs"), handler),
+        };
+        let signature = &source[source.find(&format!("async fn {}(", name)).unwrap()..];
+        let signature = &signature[..signature.find(" {\n").unwrap()];
+
+        let mut params = Vec::new();
+        if signature.contains("ChannelState") || signature.contains("OpenChannel") {
+            params.push("channel".to_string());
+        }
+        for query in signature.split("Query<").skip(1) {
+            let name = query.split('>').next().unwrap();
+            let fields = &source[source.find(&format!("struct {} {{", name)).unwrap()..];
+            let fields = &fields[..fields.find("\n}").unwrap()];
+            params.extend(
+                fields
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.starts_with("//"))
+                    .filter_map(|line| line.split_once(':'))
+                    .map(|(field, _)| field.to_string()),
+            );
+        }
+        params.sort();
+        params
+    }
+
+    #[tokio::test]
+    async fn test_openapi_describes_every_registered_route() {
+        let registered = registered_routes();
+        let documented: Vec<_> = ROUTE_DOCS
+            .iter()
+            .map(|route| (route.method.to_string(), route.path.to_string()))
+            .collect();
+        let routes: Vec<_> = registered
+            .iter()
+            .map(|(method, path, _)| (method.clone(), path.clone()))
+            .collect();
+        assert_eq!(routes, documented, "routes in app() and ROUTE_DOCS differ");
+
+        let request = axum::http::Request::get("/api/openapi.json")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = send(Arc::new(AppState::new(DEFAULT_HISTORY_CAPACITY)), request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let paths = spec["paths"].as_object().unwrap();
+        assert_eq!(paths.len(), 42);
+        // Each route documents exactly the query parameters its handler reads
+        for (method, path, handler) in &registered {
+            let route = ROUTE_DOCS.iter().find(|route| route.path == path).unwrap();
+            let parameters = paths[&route.openapi_path()][method]["parameters"].as_array();
+            let mut documented: Vec<_> = parameters
+                .into_iter()
+                .flatten()
+                .filter(|parameter| parameter["in"] == "query")
+                .map(|parameter| parameter["name"].as_str().unwrap().to_string())
+                .collect();
+            documented.sort();
+            assert_eq!(documented, accepted_query(handler), "query of {} {}", method, path);
+        }
+        let events = &paths["/events"]["get"]["parameters"];
+        assert_eq!((&events[0]["name"], &events[1]["name"]), (&"channel".into(), &"types".into()));
+        let cancel = &paths["/api/events/schedule/{id}"]["delete"];
+        assert_eq!(cancel["parameters"][1]["name"], "id");
+        assert_eq!(cancel["parameters"][1]["in"], "path");
+        let compromise = &paths["/api/scada/compromise"]["post"];
+        assert_eq!(
+            compromise["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ScadaCompromisedRequest"
+        );
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(schemas["ScadaCompromisedRequest"]["required"], serde_json::json!(["team"]));
+        assert_eq!(
+            schemas["GameEvent"]["oneOf"].as_array().unwrap().len(),
+            EventTypeRegistry::all().len()
+        );
+        // Every schema referred to is defined
+        for reference in body
+            .split(|&b| b == b'"')
+            .filter_map(|part| part.strip_prefix(b"#/components/schemas/"))
+        {
+            let name = std::str::from_utf8(reference).unwrap();
+            assert!(schemas.get(name).is_some(), "no schema for {}", name);
+        }
+    }
+
+    #[test]
+    fn test_openapi_event_request_samples_parse() {
+        let spec = openapi_spec();
+
+        /// A value matching a schema, with every optional field filled in
+        fn sample(schema: &serde_json::Value, spec: &serde_json::Value) -> serde_json::Value {
+            if let Some(reference) = schema["$ref"].as_str() {
+                let name = reference.trim_start_matches("#/components/schemas/");
+                return sample(&spec["components"]["schemas"][name], spec);
+            }
+            if let Some(values) = schema["enum"].as_array() {
+                return values[0].clone();
+            }
+            match schema["type"].as_str() {
+                Some("object") => schema["properties"]
+                    .as_object()
+                    .unwrap()
+                    .iter()
+                    .map(|(name, field)| (name.clone(), sample(field, spec)))
+                    .collect(),
+                Some("string") => serde_json::json!("Red Team"),
+                Some("integer") => serde_json::json!(1),
+                Some("number") => serde_json::json!(0.5),
+                Some("boolean") => serde_json::json!(true),
+                _ => serde_json::json!([]),
+            }
+        }
+
+        let event_requests = spec["components"]["schemas"]["EventRequest"]["oneOf"]
+            .as_array()
+            .unwrap();
+        assert_eq!(event_requests.len(), EVENT_REQUEST_BODIES.len());
+        for schema in event_requests {
+            let value = sample(schema, &spec);
+            let parsed = serde_json::from_value::<EventRequest>(value.clone());
+            assert!(parsed.is_ok(), "{} does not parse: {:?}", value, parsed);
+        }
+    }
 }
@@ -0,0 +1,238 @@
+//! Prometheus metrics
+//!
+//! Counters and gauges served at GET /metrics in the Prometheus text format,
+//! for dashboards tracking event frequencies and connected clients during an
+//! exercise:
+//! - `city_events_total{type}`: events broadcast, by variant
+//! - `city_sse_clients`: connected SSE clients
+//! - `city_broadcast_errors_total`: events that could not be sent
+//! - `city_http_requests_total{method,path,status}`: HTTP requests served
+//! - `city_rejected_requests_total`: requests answered with a 4xx status
+//!
+//! The text format is simple enough to write by hand, so the counters are
+//! plain atomics rather than a metrics library.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use city_events::EventTypeRegistry;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio_stream::Stream;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Labels of `city_http_requests_total`: method, matched route and status
+type RequestLabels = (String, String, u16);
+
+/// The server's metrics
+pub struct Metrics {
+    /// One counter per `GameEvent` variant, created up front so counting
+    /// never takes a lock
+    events_total: BTreeMap<&'static str, AtomicU64>,
+    sse_clients: AtomicU64,
+    broadcast_errors_total: AtomicU64,
+    http_requests_total: Mutex<BTreeMap<RequestLabels, u64>>,
+    rejected_requests_total: AtomicU64,
+}
+
+impl Metrics {
+    /// Creates the metrics, all at zero
+    pub fn new() -> Self {
+        Self {
+            events_total: EventTypeRegistry::all()
+                .iter()
+                .map(|info| (info.name, AtomicU64::new(0)))
+                .collect(),
+            sse_clients: AtomicU64::new(0),
+            broadcast_errors_total: AtomicU64::new(0),
+            http_requests_total: Mutex::new(BTreeMap::new()),
+            rejected_requests_total: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a broadcast event
+    ///
+    /// # Arguments
+    /// * `event_type` - Variant name, as in `GameEvent::event_name`
+    pub fn record_event(&self, event_type: &str) {
+        if let Some(counter) = self.events_total.get(event_type) {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Counts an event that could not be sent
+    pub fn record_broadcast_error(&self) {
+        self.broadcast_errors_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a served request
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method
+    /// * `path` - Matched route, or `unmatched`
+    /// * `status` - Response status code
+    fn record_request(&self, method: String, path: String, status: u16) {
+        *self
+            .http_requests_total
+            .lock()
+            .unwrap()
+            .entry((method, path, status))
+            .or_default() += 1;
+        if (400..500).contains(&status) {
+            self.rejected_requests_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Encodes every metric in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+
+        family(&mut text, "city_events_total", "counter", "Events broadcast to SSE clients");
+        for (event_type, counter) in &self.events_total {
+            let count = counter.load(Ordering::Relaxed);
+            let _ = writeln!(text, "city_events_total{{type=\"{}\"}} {}", event_type, count);
+        }
+
+        family(&mut text, "city_sse_clients", "gauge", "Connected SSE clients");
+        let _ = writeln!(text, "city_sse_clients {}", self.sse_clients.load(Ordering::Relaxed));
+
+        family(
+            &mut text,
+            "city_broadcast_errors_total",
+            "counter",
+            "Events that could not be sent to any SSE client",
+        );
+        let errors = self.broadcast_errors_total.load(Ordering::Relaxed);
+        let _ = writeln!(text, "city_broadcast_errors_total {}", errors);
+
+        family(&mut text, "city_http_requests_total", "counter", "HTTP requests served");
+        for ((method, path, status), count) in self.http_requests_total.lock().unwrap().iter() {
+            let _ = writeln!(
+                text,
+                "city_http_requests_total{{method=\"{}\",path=\"{}\",status=\"{}\"}} {}",
+                escape(method),
+                escape(path),
+                status,
+                count
+            );
+        }
+
+        family(
+            &mut text,
+            "city_rejected_requests_total",
+            "counter",
+            "HTTP requests rejected with a 4xx status",
+        );
+        let rejected = self.rejected_requests_total.load(Ordering::Relaxed);
+        let _ = writeln!(text, "city_rejected_requests_total {}", rejected);
+
+        text
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes the `# HELP` and `# TYPE` lines that start a metric family
+fn family(text: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+}
+
+/// Escapes a label value as the text format requires
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// SSE stream that counts as a connected client until it is dropped
+///
+/// axum drops the stream when the client disconnects, including in the middle
+/// of the stream, so `city_sse_clients` never counts clients that are gone.
+pub struct TrackedStream<S> {
+    inner: S,
+    metrics: Arc<Metrics>,
+}
+
+impl<S> TrackedStream<S> {
+    /// Wraps a client's stream, counting the client as connected
+    pub fn new(inner: S, metrics: Arc<Metrics>) -> Self {
+        metrics.sse_clients.fetch_add(1, Ordering::Relaxed);
+        Self { inner, metrics }
+    }
+}
+
+impl<S: Stream + Unpin> Stream for TrackedStream<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        Pin::new(&mut self.inner).poll_next(cx)
+    }
+}
+
+impl<S> Drop for TrackedStream<S> {
+    fn drop(&mut self) {
+        self.metrics.sse_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Middleware counting every request by method, route and status
+///
+/// Requests are labeled with the route they matched (e.g.
+/// `/api/events/schedule/:id`) rather than the raw path, which keeps the
+/// number of series bounded; requests matching no route count as `unmatched`.
+pub async fn track_requests(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+
+    let response = next.run(request).await;
+    metrics.record_request(method, path, response.status().as_u16());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_exposes_counters() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.record_event("BarrierBroken");
+        metrics.record_event("BarrierBroken");
+        metrics.record_broadcast_error();
+        let streams: Vec<_> = (0..3)
+            .map(|_| TrackedStream::new(tokio_stream::empty::<()>(), metrics.clone()))
+            .collect();
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE city_events_total counter"));
+        assert!(text.contains("city_events_total{type=\"BarrierBroken\"} 2"));
+        assert!(text.contains("city_events_total{type=\"LedDisplayBroken\"} 0"));
+        assert!(text.contains("city_broadcast_errors_total 1"));
+        assert!(text.contains("city_sse_clients 3"));
+
+        drop(streams);
+        assert!(metrics.render().contains("city_sse_clients 0"));
+    }
+}
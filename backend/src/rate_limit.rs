@@ -0,0 +1,230 @@
+//! Per-IP rate limiting
+//!
+//! Token-bucket limiter applied to the POST /api/* endpoints. Each client IP
+//! gets a bucket holding up to `burst` tokens that refills at `rate` tokens per
+//! second; a request spends one token, and requests arriving at an empty
+//! bucket are rejected with 429 Too Many Requests and a Retry-After header.
+//!
+//! Behind a reverse proxy every request comes from the proxy's address, so
+//! the limiter can be told to trust the `X-Forwarded-For` header the proxy
+//! adds instead.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default sustained request rate per IP (requests per second)
+pub const DEFAULT_RATE_LIMIT_PER_SEC: u32 = 10;
+
+/// Default burst size as a multiple of the per-second rate
+const BURST_FACTOR: u32 = 2;
+
+/// Number of tracked IPs above which idle buckets are dropped
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Token bucket for a single client
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Per-IP token-bucket rate limiter
+pub struct RateLimiter {
+    /// Tokens added per second
+    rate: f64,
+
+    /// Maximum tokens a bucket can hold
+    burst: f64,
+
+    /// Whether clients are identified by `X-Forwarded-For` rather than the
+    /// address they connect from
+    trust_forwarded_for: bool,
+
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `per_sec` requests per second per IP, with a
+    /// burst of twice that
+    pub fn new(per_sec: u32) -> Self {
+        let per_sec = per_sec.max(1);
+        Self {
+            rate: f64::from(per_sec),
+            burst: f64::from(per_sec * BURST_FACTOR),
+            trust_forwarded_for: false,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the most requests a client can make at once after being idle
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = f64::from(burst.max(1));
+        self
+    }
+
+    /// Identifies clients by the `X-Forwarded-For` header when `trust` is
+    /// set, for servers behind a reverse proxy
+    ///
+    /// Only enable this behind a proxy that sets the header: otherwise
+    /// clients can pick any address to be limited as.
+    pub fn with_trusted_proxy(mut self, trust: bool) -> Self {
+        self.trust_forwarded_for = trust;
+        self
+    }
+
+    /// Address a request is limited as
+    ///
+    /// # Arguments
+    /// * `headers` - The request's headers
+    /// * `peer` - Address the request was received from
+    ///
+    /// # Returns
+    /// The last address in `X-Forwarded-For`, which the proxy in front of the
+    /// server added, if proxies are trusted and the header holds a valid
+    /// address; `peer` otherwise
+    pub fn client_ip(&self, headers: &HeaderMap, peer: IpAddr) -> IpAddr {
+        if !self.trust_forwarded_for {
+            return peer;
+        }
+        headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .next_back()
+            .and_then(|ip| ip.trim().parse().ok())
+            .unwrap_or(peer)
+    }
+
+    /// Spends a token for `ip` at time `now`
+    ///
+    /// # Returns
+    /// `Ok(())` if the request is allowed, or `Err` with the time until the
+    /// next token becomes available
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            // Buckets that have refilled completely behave like new ones
+            buckets.retain(|_, bucket| self.refilled(bucket, now) < self.burst);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    /// Token count of `bucket` after refilling up to `now`
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        (bucket.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst)
+    }
+}
+
+/// Middleware rejecting requests from IPs that exceeded their rate limit
+///
+/// Requires the server to be started with connect info
+/// (`into_make_service_with_connect_info::<SocketAddr>()`).
+pub async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let ip = limiter.client_ip(request.headers(), addr.ip());
+    match limiter.check(ip, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            // Retry-After is in whole seconds; round up so clients don't retry early
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                "Rate limit exceeded",
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+    const OTHER: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn test_burst_then_reject() {
+        let limiter = RateLimiter::new(30);
+        let now = Instant::now();
+
+        for _ in 0..60 {
+            assert!(limiter.check(CLIENT, now).is_ok());
+        }
+        let wait = limiter.check(CLIENT, now).unwrap_err();
+        assert!(wait > Duration::ZERO && wait <= Duration::from_millis(34));
+
+        // Other clients have their own bucket
+        assert!(limiter.check(OTHER, now).is_ok());
+    }
+
+    #[test]
+    fn test_burst_can_be_set_apart_from_rate() {
+        let limiter = RateLimiter::new(10).with_burst(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check(CLIENT, now).is_ok());
+        }
+        let wait = limiter.check(CLIENT, now).unwrap_err();
+        assert!(wait > Duration::from_millis(90) && wait <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_forwarded_for_is_used_only_when_trusted() {
+        let mut headers = HeaderMap::new();
+        headers.append("x-forwarded-for", "203.0.113.9, 10.0.0.2".parse().unwrap());
+
+        assert_eq!(RateLimiter::new(10).client_ip(&headers, CLIENT), CLIENT);
+        let behind_proxy = RateLimiter::new(10).with_trusted_proxy(true);
+        assert_eq!(behind_proxy.client_ip(&headers, CLIENT), OTHER);
+        assert_eq!(behind_proxy.client_ip(&HeaderMap::new(), CLIENT), CLIENT);
+
+        headers.insert("x-forwarded-for", "not an address".parse().unwrap());
+        assert_eq!(behind_proxy.client_ip(&headers, CLIENT), CLIENT);
+    }
+
+    #[test]
+    fn test_tokens_refill_at_rate() {
+        let limiter = RateLimiter::new(30);
+        let start = Instant::now();
+        for _ in 0..60 {
+            limiter.check(CLIENT, start).unwrap();
+        }
+
+        // One second later, 30 more requests fit but not 31
+        let later = start + Duration::from_secs(1);
+        for _ in 0..30 {
+            assert!(limiter.check(CLIENT, later).is_ok());
+        }
+        assert!(limiter.check(CLIENT, later).is_err());
+    }
+}
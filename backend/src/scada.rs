@@ -0,0 +1,300 @@
+//! SCADA building states
+//!
+//! Each channel tracks whether the SCADA system of every building is working
+//! or compromised, and by which team since when, so the SCADA endpoints can
+//! turn down requests that don't fit the current state: compromising a
+//! building a team already holds, or restoring one nobody compromised, is
+//! answered with 409 Conflict instead of a broadcast that would confuse the
+//! scoreboard.
+//!
+//! Requests without a `building_id` cover every building (1 to
+//! `MAX_BUILDING_ID`) and report a result for each. If every building
+//! changes, one event without a `building_id` is broadcast as before;
+//! otherwise one event for each building that changed. Events arriving any
+//! other way (POST /api/events, WebSocket commands, the event log) are
+//! applied without these checks.
+
+use crate::api::{ApiError, MAX_BUILDING_ID};
+use axum::Json;
+use city_events::{GameEvent, ScadaCompromisedRequest};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// State of one building's SCADA system
+///
+/// Serialized as `{"status": "working"}` or
+/// `{"status": "compromised", "team": "Red Team", "since": 1700000000000}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ScadaStatus {
+    Working,
+    Compromised {
+        /// Team holding the building
+        team: String,
+
+        /// When the building was compromised, in milliseconds since the Unix epoch
+        since: u64,
+    },
+}
+
+/// Result for one building of a SCADA request
+#[derive(Debug, Serialize)]
+pub struct BuildingResult {
+    pub building_id: u32,
+
+    /// Whether the building changed state
+    pub ok: bool,
+
+    /// Why the building could not change state
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Team holding the building, if it is compromised
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holder: Option<String>,
+}
+
+/// Body returned by POST /api/scada/compromise and /api/scada/restore
+#[derive(Debug, Serialize)]
+pub struct ScadaResponse {
+    pub ok: bool,
+
+    pub message: &'static str,
+
+    /// Name of the broadcast event type (e.g. "ScadaCompromised")
+    pub event: &'static str,
+
+    /// One result per building the request covers, by building ID
+    pub results: Vec<BuildingResult>,
+}
+
+/// Events a SCADA request broadcasts, with the result for each building
+#[derive(Debug)]
+pub struct ScadaChange {
+    /// Events to apply and broadcast, in order
+    events: Vec<GameEvent>,
+
+    results: Vec<BuildingResult>,
+}
+
+impl ScadaChange {
+    /// Splits the change into its events and the success response naming
+    /// the broadcast event type
+    pub fn into_parts(self) -> (Vec<GameEvent>, Json<ScadaResponse>) {
+        let response = ScadaResponse {
+            ok: true,
+            message: "Event triggered",
+            event: self.events[0].event_name(),
+            results: self.results,
+        };
+        (self.events, Json(response))
+    }
+}
+
+/// IDs of the buildings a request or event covers
+fn covered(building_id: Option<u32>) -> RangeInclusive<u32> {
+    match building_id {
+        Some(id) => id..=id,
+        None => 1..=MAX_BUILDING_ID,
+    }
+}
+
+/// SCADA state of every building
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScadaBoard {
+    buildings: HashMap<u32, ScadaStatus>,
+}
+
+impl Default for ScadaBoard {
+    /// Every building working
+    fn default() -> Self {
+        Self {
+            buildings: covered(None).map(|id| (id, ScadaStatus::Working)).collect(),
+        }
+    }
+}
+
+impl ScadaBoard {
+    /// State of every building, by building ID
+    pub fn buildings(&self) -> &HashMap<u32, ScadaStatus> {
+        &self.buildings
+    }
+
+    /// Updates the buildings an event changes
+    ///
+    /// # Arguments
+    /// * `event` - Event being broadcast
+    /// * `timestamp` - When it was broadcast, in milliseconds since the Unix epoch
+    pub fn apply(&mut self, event: &GameEvent, timestamp: u64) {
+        match event {
+            GameEvent::ScadaCompromised { building_id, team, .. } => {
+                for id in covered(*building_id) {
+                    let status = ScadaStatus::Compromised {
+                        team: team.clone(),
+                        since: timestamp,
+                    };
+                    self.buildings.insert(id, status);
+                }
+            }
+            GameEvent::ScadaRestored { building_id } => {
+                for id in covered(*building_id) {
+                    self.buildings.insert(id, ScadaStatus::Working);
+                }
+            }
+            GameEvent::GameReset => *self = Self::default(),
+            _ => {}
+        }
+    }
+
+    /// Checks a compromise request against the buildings' states
+    ///
+    /// # Returns
+    /// The events to broadcast, or 409 Conflict naming the holder if the
+    /// building (or every building) is already compromised
+    pub fn compromise(&self, req: ScadaCompromisedRequest) -> Result<ScadaChange, ApiError> {
+        let ScadaCompromisedRequest {
+            building_id,
+            team,
+            message,
+        } = req;
+        self.change(building_id, true, |building_id| GameEvent::ScadaCompromised {
+            building_id,
+            team: team.clone(),
+            message: message.clone(),
+        })
+    }
+
+    /// Checks a restore request against the buildings' states
+    ///
+    /// # Returns
+    /// The events to broadcast, or 409 Conflict if the building (or every
+    /// building) is working
+    pub fn restore(&self, building_id: Option<u32>) -> Result<ScadaChange, ApiError> {
+        self.change(building_id, false, |building_id| GameEvent::ScadaRestored { building_id })
+    }
+
+    /// Checks which covered buildings can change state and builds their events
+    ///
+    /// # Arguments
+    /// * `building_id` - Building the request names, or None for every building
+    /// * `compromise` - Whether the buildings are to be compromised or restored
+    /// * `event` - Builds the event for one building, or for every building
+    fn change(
+        &self,
+        building_id: Option<u32>,
+        compromise: bool,
+        event: impl Fn(Option<u32>) -> GameEvent,
+    ) -> Result<ScadaChange, ApiError> {
+        let results: Vec<_> = covered(building_id).map(|id| self.check(id, compromise)).collect();
+        let changed: Vec<_> = results.iter().filter(|r| r.ok).map(|r| r.building_id).collect();
+
+        if changed.is_empty() {
+            return Err(match (building_id, results.into_iter().next()) {
+                (Some(_), Some(result)) => {
+                    let error = ApiError::conflict(result.error.unwrap_or_default());
+                    match result.holder {
+                        Some(holder) => error.with_holder(holder),
+                        None => error,
+                    }
+                }
+                _ if compromise => ApiError::conflict("every building is already compromised"),
+                _ => ApiError::conflict("no building is compromised"),
+            });
+        }
+
+        let events = if changed.len() == results.len() {
+            vec![event(building_id)]
+        } else {
+            changed.into_iter().map(|id| event(Some(id))).collect()
+        };
+        Ok(ScadaChange { events, results })
+    }
+
+    /// Checks whether one building can be compromised or restored
+    fn check(&self, building_id: u32, compromise: bool) -> BuildingResult {
+        let conflict = |error: String, holder: Option<&String>| BuildingResult {
+            building_id,
+            ok: false,
+            error: Some(error),
+            holder: holder.cloned(),
+        };
+        match (self.buildings.get(&building_id), compromise) {
+            (Some(ScadaStatus::Compromised { team, .. }), true) => conflict(
+                format!("building {} is already compromised by {}", building_id, team),
+                Some(team),
+            ),
+            (Some(ScadaStatus::Working) | None, false) => {
+                conflict(format!("building {} is not compromised", building_id), None)
+            }
+            _ => BuildingResult {
+                building_id,
+                ok: true,
+                error: None,
+                holder: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compromise_request(building_id: Option<u32>, team: &str) -> ScadaCompromisedRequest {
+        ScadaCompromisedRequest {
+            building_id,
+            team: team.to_string(),
+            message: None,
+        }
+    }
+
+    #[test]
+    fn test_all_buildings_fan_out_to_the_ones_that_can_change() {
+        let mut board = ScadaBoard::default();
+
+        // Nothing held yet, so one event covers every building
+        let change = board.compromise(compromise_request(None, "Red Team")).unwrap();
+        assert_eq!(change.events.len(), 1);
+        assert!(change.results.iter().all(|r| r.ok));
+        board.apply(&change.events[0], 1000);
+        assert!(board.buildings().values().all(|s| matches!(s, ScadaStatus::Compromised { .. })));
+
+        // Restoring two buildings leaves the rest held
+        for id in [3, 7] {
+            let change = board.restore(Some(id)).unwrap();
+            board.apply(&change.events[0], 2000);
+        }
+
+        // Only the two restored buildings change hands, one event each
+        let change = board.compromise(compromise_request(None, "Blue Team")).unwrap();
+        assert_eq!(
+            change.events,
+            [3, 7].map(|id| GameEvent::ScadaCompromised {
+                building_id: Some(id),
+                team: "Blue Team".to_string(),
+                message: None,
+            })
+        );
+        assert_eq!(change.results.len(), MAX_BUILDING_ID as usize);
+        let held = &change.results[0];
+        assert!(!held.ok);
+        assert_eq!(held.holder.as_deref(), Some("Red Team"));
+        for event in &change.events {
+            board.apply(event, 3000);
+        }
+        assert_eq!(
+            board.buildings()[&3],
+            ScadaStatus::Compromised {
+                team: "Blue Team".to_string(),
+                since: 3000,
+            }
+        );
+
+        // With every building held, there is nothing left to compromise
+        assert!(board.compromise(compromise_request(None, "Blue Team")).is_err());
+        board.apply(&GameEvent::GameReset, 4000);
+        assert_eq!(board, ScadaBoard::default());
+        assert!(board.restore(None).is_err());
+    }
+}
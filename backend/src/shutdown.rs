@@ -0,0 +1,126 @@
+//! Graceful shutdown
+//!
+//! On SIGINT (Ctrl-C) or SIGTERM the server stops accepting connections and
+//! tells every connected dashboard it is going away:
+//! - Each channel broadcasts `ConnectionStatus { connected: false }` with
+//!   the error "server shutting down"
+//! - SSE streams end after that event, and WebSockets close after sending it
+//! - The server waits up to `SHUTDOWN_GRACE_PERIOD` for the streams to flush,
+//!   then exits whether or not every connection has closed
+
+use crate::AppState;
+use axum::Router;
+use city_events::GameEvent;
+use std::future::{Future, IntoFuture};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+/// How long open connections get to receive the shutdown event and close
+pub const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Error sent with the final `ConnectionStatus` event
+const SHUTDOWN_MESSAGE: &str = "server shutting down";
+
+/// The last event every client receives before the server exits
+pub fn shutdown_event() -> GameEvent {
+    GameEvent::ConnectionStatus {
+        connected: false,
+        error: Some(SHUTDOWN_MESSAGE.to_string()),
+    }
+}
+
+/// Checks if an event is the one sent on shutdown, after which streams end
+pub fn is_shutdown_event(event: &GameEvent) -> bool {
+    matches!(
+        event,
+        GameEvent::ConnectionStatus { connected: false, error: Some(error) }
+            if error == SHUTDOWN_MESSAGE
+    )
+}
+
+/// Waits for Ctrl-C, or SIGTERM on Unix
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Could not listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!("Could not listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl-C"),
+        _ = terminate => info!("Received SIGTERM"),
+    }
+}
+
+/// Serves the router until `shutdown` completes, then shuts down gracefully
+///
+/// # Arguments
+/// * `listener` - Bound listener to accept connections on
+/// * `app` - Application router
+/// * `state` - Shared application state, whose channels announce the shutdown
+/// * `shutdown` - Future completing when the server should stop, e.g. `signal()`
+///
+/// # Returns
+/// Once every connection has closed, or `SHUTDOWN_GRACE_PERIOD` after
+/// `shutdown` completed if some are still open
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    state: Arc<AppState>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let (started_tx, started_rx) = oneshot::channel();
+    let graceful = async move {
+        shutdown.await;
+        info!("Shutting down: notifying clients and waiting for streams to flush");
+        for channel in state.channels.all() {
+            channel.announce_shutdown();
+        }
+        let _ = started_tx.send(());
+    };
+
+    // Connect info provides the client IP for rate limiting
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(graceful)
+    .into_future();
+    tokio::pin!(server);
+
+    tokio::select! {
+        result = &mut server => return result,
+        _ = started_rx => {}
+    }
+
+    match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, server).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!(
+                "Connections still open after {} seconds, closing them",
+                SHUTDOWN_GRACE_PERIOD.as_secs()
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! WebSocket endpoint
+//!
+//! GET /ws mirrors the SSE stream for tools that speak WebSocket but not SSE,
+//! and takes commands in the other direction:
+//! - Server to client: a state snapshot, then every `GameEvent` as a JSON
+//!   text frame, and `{"type": "lagged", "missed": n}` when the client fell
+//!   so far behind that events were dropped
+//! - Client to server: `{"command": "barrier_break", "payload": {...}}`,
+//!   where the commands and payloads are those of POST /api/events/batch
+//!   elements; each command is answered with an `ack` or `error` frame
+//!
+//! Commands go through the same validation, API key check and per-IP rate
+//! limit as the POST endpoints. When the server shuts down, the socket is
+//! closed after the final `connection_status` frame. The API key is read from the upgrade
+//! request's `Authorization` header, and the channel from its `channel`
+//! query parameter.
+
+use crate::api::{self, ApiError, ApiErrorBody, ApiResponse};
+use crate::auth::{self, AuthError};
+use crate::channels::OpenChannel;
+use crate::clients::{ClientInfo, ClientRegistry};
+use crate::rate_limit::RateLimiter;
+use crate::shutdown;
+use crate::{AppState, Channel};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, State,
+    },
+    http::HeaderMap,
+    response::Response,
+    Extension,
+};
+use axum_extra::TypedHeader;
+use city_events::{EventRequest, GameEvent};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// Command frame sent by a client
+#[derive(Debug, Deserialize)]
+struct Command {
+    /// Event request type, e.g. "barrier_break"
+    command: String,
+
+    /// Request fields; may be left out for commands without any
+    #[serde(default)]
+    payload: Option<Value>,
+}
+
+/// Frames sent in reply to a command, or about the connection itself
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Reply {
+    /// The command's event was broadcast
+    Ack {
+        command: String,
+        #[serde(flatten)]
+        response: ApiResponse,
+    },
+
+    /// The command was rejected; `command` is missing if the frame could
+    /// not be read as a command at all
+    Error {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        command: Option<String>,
+        #[serde(flatten)]
+        error: ApiErrorBody,
+    },
+
+    /// The client fell behind and `missed` events were dropped
+    Lagged { missed: u64 },
+}
+
+impl Reply {
+    fn error(command: Option<String>, error: impl Into<ApiError>) -> Self {
+        Reply::Error {
+            command,
+            error: error.into().into(),
+        }
+    }
+}
+
+/// Per-connection context for handling commands
+struct Connection {
+    state: Arc<AppState>,
+    channel: Arc<Channel>,
+    limiter: Arc<RateLimiter>,
+    ip: Option<IpAddr>,
+
+    /// Name of the key the upgrade request carried, or why it was not accepted
+    authorization: Result<Option<String>, AuthError>,
+}
+
+/// GET /ws
+pub async fn ws_handler(
+    State(state): State<Arc<AppState>>,
+    OpenChannel(channel): OpenChannel,
+    Extension(limiter): Extension<Arc<RateLimiter>>,
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    user_agent: Option<TypedHeader<headers::UserAgent>>,
+) -> Response {
+    let ip = connect_info.map(|ConnectInfo(addr)| limiter.client_ip(&headers, addr.ip()));
+    let connection = Connection {
+        authorization: auth::authorize(&state.api_keys, &headers),
+        state,
+        channel,
+        limiter,
+        ip,
+    };
+    let user_agent = user_agent.map(|TypedHeader(agent)| agent.to_string());
+    ws.on_upgrade(move |socket| run(socket, connection, user_agent))
+}
+
+/// Serves one WebSocket connection until either side closes it
+async fn run(mut socket: WebSocket, connection: Connection, user_agent: Option<String>) {
+    // Subscribe while holding the game state read lock, so the snapshot
+    // matches the live events that follow it
+    let channel = &connection.channel;
+    let game = channel.game.read().await;
+    let (_, mut rx) = channel.subscribe_with_replay(None);
+    let snapshot = GameEvent::StateSnapshot {
+        state: Box::new(game.clone()),
+    };
+    drop(game);
+
+    let client = register(&connection.state.clients, connection.ip, user_agent);
+    info!("New WebSocket client {} connected", client.id());
+
+    if send_json(&mut socket, &snapshot).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            received = rx.recv() => {
+                let sent = match received {
+                    Ok(sequenced) if shutdown::is_shutdown_event(&sequenced.event) => {
+                        let _ = send_json(&mut socket, &sequenced.event).await;
+                        let _ = socket.send(Message::Close(None)).await;
+                        break;
+                    }
+                    Ok(sequenced) => send_json(&mut socket, &sequenced.event).await,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("WebSocket client {} missed {} events", client.id(), missed);
+                        send_json(&mut socket, &Reply::Lagged { missed }).await
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if sent.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                let reply = match message {
+                    Some(Ok(Message::Text(text))) => connection.handle_command(&text).await,
+                    Some(Ok(Message::Binary(_))) => Reply::error(
+                        None,
+                        ApiError::bad_request("Commands must be sent as text frames"),
+                    ),
+                    // Pings are answered by axum itself
+                    Some(Ok(Message::Ping(_) | Message::Pong(_))) => continue,
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                };
+                if send_json(&mut socket, &reply).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Validates a command frame, then applies and broadcasts its event
+    async fn handle_command(&self, text: &str) -> Reply {
+        let command: Command = match serde_json::from_str(text) {
+            Ok(command) => command,
+            Err(e) => {
+                return Reply::error(None, ApiError::bad_request(format!("Invalid command: {}", e)))
+            }
+        };
+
+        if let Some(ip) = self.ip
+            && self.limiter.check(ip, Instant::now()).is_err()
+        {
+            let error = ApiError::too_many_requests("Rate limit exceeded");
+            return Reply::error(Some(command.command), error);
+        }
+        let key_name = match &self.authorization {
+            Ok(key_name) => key_name.clone(),
+            Err(error) => return Reply::error(Some(command.command), *error),
+        };
+        let request = match parse_request(&command) {
+            Ok(request) => request,
+            Err(error) => return Reply::error(Some(command.command), error),
+        };
+
+        let response = auth::with_key_name(key_name, self.channel.trigger(request.into())).await;
+        Reply::Ack {
+            command: command.command,
+            response: response.0,
+        }
+    }
+}
+
+/// Reads a command as the batch element it stands for
+///
+/// Errors in the payload name the field as `payload.<field>`, and an unknown
+/// command names `command`.
+fn parse_request(command: &Command) -> Result<EventRequest, ApiError> {
+    let mut request = match &command.payload {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(fields)) => fields.clone(),
+        Some(_) => return Err(ApiError::invalid("payload", "payload must be an object")),
+    };
+    request.insert("type".to_string(), Value::String(command.command.clone()));
+
+    // The command itself is the only field that can make "type" invalid
+    api::parse_validated(Value::Object(request)).map_err(|error| {
+        if error.field() == Some("type") {
+            ApiError::invalid("command", format!("unknown command `{}`", command.command))
+        } else {
+            error.within("payload")
+        }
+    })
+}
+
+/// Registers the connection in the client list shown by GET /api/clients
+fn register(
+    clients: &ClientRegistry,
+    ip: Option<IpAddr>,
+    user_agent: Option<String>,
+) -> crate::clients::ClientGuard {
+    clients.register(ClientInfo {
+        connected_at: Instant::now(),
+        ip,
+        user_agent,
+    })
+}
+
+/// Sends a value as a JSON text frame
+async fn send_json(socket: &mut WebSocket, value: &impl Serialize) -> Result<(), axum::Error> {
+    match serde_json::to_string(value) {
+        Ok(json) => socket.send(Message::Text(json)).await,
+        Err(e) => {
+            warn!("Failed to serialize WebSocket frame: {}", e);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,664 @@
+//! Event types for SSE communication
+//!
+//! `GameEvent` is the wire format of the SSE stream: internally tagged JSON
+//! such as `{"type": "barrier_broken", "team": "Red Team"}`. Existing scripts
+//! and dashboards depend on this format, so renaming a variant or field is a
+//! breaking change.
+
+use crate::state::GameState;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashSet;
+
+// ============================================================================
+// Event Type Registry
+// ============================================================================
+
+/// Describes one field of an event type
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FieldInfo {
+    /// Field name as it appears in the JSON payload
+    pub name: &'static str,
+
+    /// Rust type of the field (e.g. "String", "Option<u32>")
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+}
+
+/// Describes one `GameEvent` variant
+#[derive(Debug, Clone, Copy)]
+pub struct EventTypeInfo {
+    /// Variant name (e.g. "BarrierBroken")
+    pub name: &'static str,
+
+    /// Description taken from the variant's doc comment
+    pub description: &'static str,
+
+    /// Fields carried by the event
+    pub fields: &'static [FieldInfo],
+}
+
+impl EventTypeInfo {
+    /// Returns the `type` tag used for this event in JSON payloads
+    /// (e.g. "barrier_broken")
+    pub fn wire_name(&self) -> String {
+        to_snake_case(self.name)
+    }
+}
+
+impl Serialize for EventTypeInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("EventTypeInfo", 4)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("type", &self.wire_name())?;
+        state.serialize_field("description", self.description.trim())?;
+        state.serialize_field("fields", self.fields)?;
+        state.end()
+    }
+}
+
+/// Runtime introspection over every `GameEvent` variant
+pub struct EventTypeRegistry;
+
+impl EventTypeRegistry {
+    /// Returns metadata for all event types
+    pub fn all() -> &'static [EventTypeInfo] {
+        EVENT_TYPES
+    }
+
+    /// Looks up an event type by variant name ("BarrierBroken") or
+    /// JSON type tag ("barrier_broken")
+    pub fn get(name: &str) -> Option<&'static EventTypeInfo> {
+        EVENT_TYPES
+            .iter()
+            .find(|info| info.name == name || info.wire_name() == name)
+    }
+
+    /// Returns true if `name` is a known event type (variant name or JSON type tag)
+    pub fn is_valid_event_type(name: &str) -> bool {
+        Self::get(name).is_some()
+    }
+}
+
+/// Subscription filter restricting which event types an SSE client receives
+///
+/// Connection status, heartbeat and state snapshot events always pass so
+/// clients can track their connection and start from the current state.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// Allowed variant names, or None to allow every event
+    allowed: Option<HashSet<&'static str>>,
+}
+
+impl EventFilter {
+    /// Parses a comma-separated list of event types
+    ///
+    /// Accepts variant names ("BarrierBroken") or JSON type tags ("barrier_broken").
+    ///
+    /// # Returns
+    /// The filter, or the list of unknown event type names
+    pub fn parse(spec: &str) -> Result<Self, Vec<String>> {
+        let mut allowed = HashSet::new();
+        let mut invalid = Vec::new();
+
+        for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            if !EventTypeRegistry::is_valid_event_type(name) {
+                invalid.push(name.to_string());
+            } else if let Some(info) = EventTypeRegistry::get(name) {
+                allowed.insert(info.name);
+            }
+        }
+
+        if invalid.is_empty() {
+            Ok(Self {
+                allowed: Some(allowed),
+            })
+        } else {
+            Err(invalid)
+        }
+    }
+
+    /// Returns true if the event should be delivered to the client
+    pub fn matches(&self, event: &GameEvent) -> bool {
+        match &self.allowed {
+            Some(allowed) => {
+                matches!(
+                    event,
+                    GameEvent::ConnectionStatus { .. }
+                        | GameEvent::Heartbeat { .. }
+                        | GameEvent::StateSnapshot { .. }
+                ) || allowed.contains(event.event_name())
+            }
+            None => true,
+        }
+    }
+}
+
+/// Converts a PascalCase variant name to its snake_case JSON tag
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Defines the `GameEvent` enum and generates `EVENT_TYPES` from it
+///
+/// Every variant must have a doc comment, which becomes its description
+/// in the registry. Adding a variant to the enum automatically registers it.
+macro_rules! register_event_types {
+    (
+        $(#[$enum_meta:meta])*
+        pub enum $enum_name:ident {
+            $(
+                $(#[doc = $doc:literal])+
+                $variant:ident $({
+                    $(
+                        $(#[$field_meta:meta])*
+                        $field:ident : $field_ty:ty
+                    ),* $(,)?
+                })?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$enum_meta])*
+        pub enum $enum_name {
+            $(
+                $(#[doc = $doc])+
+                $variant $({
+                    $(
+                        $(#[$field_meta])*
+                        $field: $field_ty
+                    ),*
+                })?
+            ),*
+        }
+
+        impl $enum_name {
+            /// Returns the variant name of this event (e.g. "BarrierBroken")
+            pub fn event_name(&self) -> &'static str {
+                match self {
+                    $(Self::$variant { .. } => stringify!($variant)),*
+                }
+            }
+        }
+
+        /// Metadata for every `GameEvent` variant, generated by `register_event_types!`
+        pub static EVENT_TYPES: &[EventTypeInfo] = &[
+            $(
+                EventTypeInfo {
+                    name: stringify!($variant),
+                    description: concat!($($doc),+),
+                    fields: &[
+                        $($(
+                            FieldInfo {
+                                name: stringify!($field),
+                                ty: stringify!($field_ty),
+                            }
+                        ),*)?
+                    ],
+                }
+            ),*
+        ];
+    };
+}
+
+// ============================================================================
+// Game Events
+// ============================================================================
+
+register_event_types! {
+    /// Game events that can be triggered by API and sent via SSE
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum GameEvent {
+        /// Barrier gate broken by a team
+        BarrierBroken {
+            team: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            message: Option<String>,
+        },
+
+        /// Barrier gate repaired/reset
+        BarrierRepaired {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            team: Option<String>,
+        },
+
+        /// LED display broken or damaged
+        LedDisplayBroken {
+            team: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            message: Option<String>,
+        },
+
+        /// LED display repaired
+        LedDisplayRepaired,
+
+        /// SCADA system compromised
+        ScadaCompromised {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            building_id: Option<u32>,
+            team: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            message: Option<String>,
+        },
+
+        /// SCADA system restored
+        ScadaRestored {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            building_id: Option<u32>,
+        },
+
+        /// Building set on fire, burning at `intensity` (0.0 to 1.0)
+        BuildingFire { building_id: u32, intensity: f32 },
+
+        /// Fire at a building put out
+        BuildingFireExtinguished { building_id: u32 },
+
+        /// Power cut in a district, turning its traffic lights and windows dark
+        PowerOutage { district: String },
+
+        /// Power back on in a district
+        PowerRestored { district: String },
+
+        /// Road closed to traffic (`blocked`) or reopened; roads 0-2 run
+        /// vertically from left to right, 3-4 horizontally from top to bottom
+        RoadClosure { road_id: usize, blocked: bool },
+
+        /// Weather changed; rain falls at `rain_intensity` (0.0 = dry, 1.0 =
+        /// downpour)
+        WeatherChange { rain_intensity: f32 },
+
+        /// Lights along vertical roads timed so cars driving down at `speed`
+        /// pixels per second (their usual speed if unset) meet green lights
+        GreenWave {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            speed: Option<f32>,
+        },
+
+        /// Emergency traffic stop activated
+        EmergencyStop { reason: String },
+
+        /// Emergency stop deactivated
+        EmergencyStopDeactivated,
+
+        /// Danger mode activated
+        DangerModeActivated { reason: String },
+
+        /// Danger mode deactivated
+        DangerModeDeactivated,
+
+        /// Whole city reset to its initial state (e.g. between rounds)
+        GameReset,
+
+        /// Dashboards save their simulation (cars, traffic lights, parked
+        /// cars) to their save file
+        CitySave,
+
+        /// Dashboards reload their simulation from their save file
+        CityLoad,
+
+        /// Team score changed by `delta`, leaving it at `total`
+        TeamScoreUpdate {
+            team: String,
+            delta: i32,
+            total: i32,
+        },
+
+        /// Round countdown tick, or the countdown stopped early when `running`
+        /// is false
+        TimerUpdate { remaining_secs: u32, running: bool },
+
+        /// Round countdown reached zero
+        TimerExpired,
+
+        /// Custom log message
+        LogMessage { level: LogLevel, message: String },
+
+        /// Server connection status change
+        ConnectionStatus {
+            connected: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<String>,
+        },
+
+        /// Sign of life sent periodically while clients are connected, so a
+        /// quiet stream can be told from a broken one; `seq` counts up by one
+        /// with every heartbeat
+        Heartbeat {
+            uptime_secs: u64,
+            connected_clients: usize,
+            seq: u64,
+        },
+
+        /// Current city state, sent to newly connected clients
+        StateSnapshot { state: Box<GameState> },
+    }
+}
+
+/// Log severity level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_contains_all_event_types() {
+        let expected = [
+            "BarrierBroken",
+            "BarrierRepaired",
+            "LedDisplayBroken",
+            "LedDisplayRepaired",
+            "ScadaCompromised",
+            "ScadaRestored",
+            "BuildingFire",
+            "BuildingFireExtinguished",
+            "PowerOutage",
+            "PowerRestored",
+            "RoadClosure",
+            "WeatherChange",
+            "GreenWave",
+            "EmergencyStop",
+            "EmergencyStopDeactivated",
+            "DangerModeActivated",
+            "DangerModeDeactivated",
+            "GameReset",
+            "CitySave",
+            "CityLoad",
+            "TeamScoreUpdate",
+            "TimerUpdate",
+            "TimerExpired",
+            "LogMessage",
+            "ConnectionStatus",
+            "Heartbeat",
+            "StateSnapshot",
+        ];
+
+        assert_eq!(EventTypeRegistry::all().len(), expected.len());
+        for name in expected {
+            assert!(
+                EventTypeRegistry::is_valid_event_type(name),
+                "missing event type {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_registry_accepts_json_type_tags() {
+        assert!(EventTypeRegistry::is_valid_event_type("barrier_broken"));
+        assert!(EventTypeRegistry::is_valid_event_type("emergency_stop_deactivated"));
+        assert!(!EventTypeRegistry::is_valid_event_type("not_an_event"));
+    }
+
+    #[test]
+    fn test_wire_names_match_serde_tags() {
+        let event = GameEvent::ScadaCompromised {
+            building_id: Some(3),
+            team: "Red Team".to_string(),
+            message: None,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        let info = EventTypeRegistry::get("ScadaCompromised").unwrap();
+        assert_eq!(json["type"], info.wire_name());
+    }
+
+    #[test]
+    fn test_event_filter_validates_names() {
+        let filter = EventFilter::parse("barrier_broken, ScadaCompromised").unwrap();
+        assert!(filter.matches(&GameEvent::BarrierBroken {
+            team: "Red Team".to_string(),
+            message: None,
+        }));
+        assert!(!filter.matches(&GameEvent::LedDisplayRepaired));
+        assert!(filter.matches(&GameEvent::Heartbeat {
+            uptime_secs: 10,
+            connected_clients: 1,
+            seq: 1,
+        }));
+
+        let invalid = EventFilter::parse("barrier_broken,bogus").unwrap_err();
+        assert_eq!(invalid, ["bogus"]);
+    }
+
+    #[test]
+    fn test_registry_describes_fields() {
+        let info = EventTypeRegistry::get("BarrierBroken").unwrap();
+        assert_eq!(info.description.trim(), "Barrier gate broken by a team");
+
+        let fields: Vec<_> = info.fields.iter().map(|f| (f.name, f.ty)).collect();
+        assert_eq!(fields, [("team", "String"), ("message", "Option<String>")]);
+
+        let unit = EventTypeRegistry::get("LedDisplayRepaired").unwrap();
+        assert!(unit.fields.is_empty());
+    }
+
+    /// One instance of every variant with its exact wire JSON
+    fn wire_samples() -> Vec<(GameEvent, serde_json::Value)> {
+        use serde_json::json;
+
+        let state = GameState {
+            barrier_broken: true,
+            barrier_broken_by: Some("Red Team".to_string()),
+            led_broken: Some("pwned".to_string()),
+            scada_compromised_buildings: [(5, true)].into(),
+            scada_compromised_by: [(5, "Red Team".to_string())].into(),
+            buildings_on_fire: [(3, 0.5)].into(),
+            districts_without_power: ["west".to_string()].into(),
+            closed_roads: [3].into(),
+            team_scores: [("Red Team".to_string(), 350)].into(),
+            timer_remaining_secs: Some(42),
+            rain_intensity: 0.75,
+            ..GameState::default()
+        };
+
+        vec![
+            (
+                GameEvent::BarrierBroken {
+                    team: "Red Team".to_string(),
+                    message: Some("Gate down".to_string()),
+                },
+                json!({"type": "barrier_broken", "team": "Red Team", "message": "Gate down"}),
+            ),
+            (
+                GameEvent::BarrierRepaired { team: None },
+                json!({"type": "barrier_repaired"}),
+            ),
+            (
+                GameEvent::LedDisplayBroken {
+                    team: "Blue Team".to_string(),
+                    message: None,
+                },
+                json!({"type": "led_display_broken", "team": "Blue Team"}),
+            ),
+            (
+                GameEvent::LedDisplayRepaired,
+                json!({"type": "led_display_repaired"}),
+            ),
+            (
+                GameEvent::ScadaCompromised {
+                    building_id: Some(7),
+                    team: "Red Team".to_string(),
+                    message: None,
+                },
+                json!({"type": "scada_compromised", "building_id": 7, "team": "Red Team"}),
+            ),
+            (
+                GameEvent::ScadaRestored { building_id: None },
+                json!({"type": "scada_restored"}),
+            ),
+            (
+                GameEvent::BuildingFire {
+                    building_id: 3,
+                    intensity: 0.75,
+                },
+                json!({"type": "building_fire", "building_id": 3, "intensity": 0.75}),
+            ),
+            (
+                GameEvent::BuildingFireExtinguished { building_id: 3 },
+                json!({"type": "building_fire_extinguished", "building_id": 3}),
+            ),
+            (
+                GameEvent::PowerOutage {
+                    district: "west".to_string(),
+                },
+                json!({"type": "power_outage", "district": "west"}),
+            ),
+            (
+                GameEvent::PowerRestored {
+                    district: "west".to_string(),
+                },
+                json!({"type": "power_restored", "district": "west"}),
+            ),
+            (
+                GameEvent::RoadClosure {
+                    road_id: 3,
+                    blocked: true,
+                },
+                json!({"type": "road_closure", "road_id": 3, "blocked": true}),
+            ),
+            (
+                GameEvent::WeatherChange {
+                    rain_intensity: 0.5,
+                },
+                json!({"type": "weather_change", "rain_intensity": 0.5}),
+            ),
+            (
+                GameEvent::GreenWave { speed: Some(60.0) },
+                json!({"type": "green_wave", "speed": 60.0}),
+            ),
+            (
+                GameEvent::EmergencyStop {
+                    reason: "Drill".to_string(),
+                },
+                json!({"type": "emergency_stop", "reason": "Drill"}),
+            ),
+            (
+                GameEvent::EmergencyStopDeactivated,
+                json!({"type": "emergency_stop_deactivated"}),
+            ),
+            (
+                GameEvent::DangerModeActivated {
+                    reason: "Breach".to_string(),
+                },
+                json!({"type": "danger_mode_activated", "reason": "Breach"}),
+            ),
+            (
+                GameEvent::DangerModeDeactivated,
+                json!({"type": "danger_mode_deactivated"}),
+            ),
+            (GameEvent::GameReset, json!({"type": "game_reset"})),
+            (GameEvent::CitySave, json!({"type": "city_save"})),
+            (GameEvent::CityLoad, json!({"type": "city_load"})),
+            (
+                GameEvent::TeamScoreUpdate {
+                    team: "Red Team".to_string(),
+                    delta: -25,
+                    total: 350,
+                },
+                json!({"type": "team_score_update", "team": "Red Team", "delta": -25, "total": 350}),
+            ),
+            (
+                GameEvent::TimerUpdate {
+                    remaining_secs: 599,
+                    running: true,
+                },
+                json!({"type": "timer_update", "remaining_secs": 599, "running": true}),
+            ),
+            (GameEvent::TimerExpired, json!({"type": "timer_expired"})),
+            (
+                GameEvent::LogMessage {
+                    level: LogLevel::Critical,
+                    message: "Intrusion".to_string(),
+                },
+                json!({"type": "log_message", "level": "critical", "message": "Intrusion"}),
+            ),
+            (
+                GameEvent::ConnectionStatus {
+                    connected: false,
+                    error: Some("timeout".to_string()),
+                },
+                json!({"type": "connection_status", "connected": false, "error": "timeout"}),
+            ),
+            (
+                GameEvent::Heartbeat {
+                    uptime_secs: 120,
+                    connected_clients: 3,
+                    seq: 12,
+                },
+                json!({"type": "heartbeat", "uptime_secs": 120, "connected_clients": 3, "seq": 12}),
+            ),
+            (
+                GameEvent::StateSnapshot {
+                    state: Box::new(state),
+                },
+                json!({
+                    "type": "state_snapshot",
+                    "state": {
+                        "barrier_broken": true,
+                        "barrier_broken_by": "Red Team",
+                        "led_broken": "pwned",
+                        "danger_mode_active": false,
+                        "emergency_stop_active": false,
+                        "scada_compromised_buildings": {"5": true},
+                        "scada_compromised_by": {"5": "Red Team"},
+                        "buildings_on_fire": {"3": 0.5},
+                        "districts_without_power": ["west"],
+                        "closed_roads": [3],
+                        "team_scores": {"Red Team": 350},
+                        "timer_remaining_secs": 42,
+                        "rain_intensity": 0.75
+                    }
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_every_variant_round_trips_through_wire_json() {
+        let samples = wire_samples();
+        let covered: HashSet<_> = samples.iter().map(|(event, _)| event.event_name()).collect();
+        for info in EventTypeRegistry::all() {
+            assert!(covered.contains(info.name), "no wire sample for {}", info.name);
+        }
+
+        for (event, wire) in samples {
+            assert_eq!(serde_json::to_value(&event).unwrap(), wire);
+            let parsed: GameEvent = serde_json::from_value(wire.clone()).unwrap();
+            assert_eq!(parsed, event, "{}", wire);
+        }
+    }
+
+    #[test]
+    fn test_optional_building_id_round_trips() {
+        for building_id in [None, Some(1), Some(12)] {
+            let event = GameEvent::ScadaRestored { building_id };
+            let json = serde_json::to_string(&event).unwrap();
+            assert_eq!(json.contains("building_id"), building_id.is_some(), "{}", json);
+            assert_eq!(serde_json::from_str::<GameEvent>(&json).unwrap(), event);
+        }
+
+        // Clients may also send an explicit null
+        let event: GameEvent =
+            serde_json::from_str(r#"{"type": "scada_restored", "building_id": null}"#).unwrap();
+        assert_eq!(event, GameEvent::ScadaRestored { building_id: None });
+    }
+}
@@ -0,0 +1,17 @@
+//! Event types shared by the City Dashboard backend and frontend
+//!
+//! The backend serializes these types onto the SSE stream and parses the
+//! API request bodies with them; the frontend deserializes the same types
+//! from the stream. Keeping a single definition means the two sides can't
+//! drift apart.
+//!
+//! The crate only depends on serde, so it builds for native and WASM
+//! targets alike.
+
+mod events;
+mod requests;
+mod state;
+
+pub use events::*;
+pub use requests::*;
+pub use state::{GameState, MAX_BUILDING_ID};
@@ -0,0 +1,276 @@
+//! API request bodies
+//!
+//! One struct per POST /api/* endpoint, plus the tagged `EventRequest` used
+//! by batch and scheduled events, and their conversions into `GameEvent`s.
+
+use crate::events::{GameEvent, LogLevel};
+use serde::Deserialize;
+
+/// Request body for triggering barrier broken event
+#[derive(Debug, Deserialize)]
+pub struct BarrierBrokenRequest {
+    pub team: String,
+    pub message: Option<String>,
+}
+
+/// Request body for triggering barrier repaired event
+#[derive(Debug, Deserialize)]
+pub struct BarrierRepairedRequest {
+    pub team: Option<String>,
+}
+
+/// Request body for LED display events
+#[derive(Debug, Deserialize)]
+pub struct LedDisplayBrokenRequest {
+    pub team: String,
+    pub message: Option<String>,
+}
+
+/// Request body for SCADA events
+#[derive(Debug, Deserialize)]
+pub struct ScadaCompromisedRequest {
+    pub building_id: Option<u32>,
+    pub team: String,
+    pub message: Option<String>,
+}
+
+/// Request body for SCADA restored
+#[derive(Debug, Deserialize)]
+pub struct ScadaRestoredRequest {
+    pub building_id: Option<u32>,
+}
+
+/// Request body for setting a building on fire
+#[derive(Debug, Deserialize)]
+pub struct BuildingFireRequest {
+    pub building_id: u32,
+
+    /// Fire intensity from 0.0 to 1.0 (default 1.0)
+    pub intensity: Option<f32>,
+}
+
+/// Request body for putting out a building fire
+#[derive(Debug, Deserialize)]
+pub struct BuildingFireExtinguishRequest {
+    pub building_id: u32,
+}
+
+/// Request body for power outage and restore events
+#[derive(Debug, Deserialize)]
+pub struct PowerRequest {
+    pub district: String,
+}
+
+/// Request body for closing and reopening a road
+#[derive(Debug, Deserialize)]
+pub struct RoadRequest {
+    pub road_id: usize,
+}
+
+/// Request body for changing the weather
+#[derive(Debug, Deserialize)]
+pub struct WeatherChangeRequest {
+    /// Rain intensity from 0.0 (dry) to 1.0 (downpour)
+    pub rain_intensity: f32,
+}
+
+/// Request body for starting a green wave
+#[derive(Debug, Deserialize)]
+pub struct GreenWaveRequest {
+    /// Speed in pixels per second the lights are timed for (default: the
+    /// usual car speed)
+    pub speed: Option<f32>,
+}
+
+/// Request body for emergency stop
+#[derive(Debug, Deserialize)]
+pub struct EmergencyStopRequest {
+    pub reason: String,
+}
+
+/// Request body for danger mode
+#[derive(Debug, Deserialize)]
+pub struct DangerModeRequest {
+    pub reason: String,
+}
+
+/// Request body for custom log message
+#[derive(Debug, Deserialize)]
+pub struct LogMessageRequest {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Request body for changing a team's score
+#[derive(Debug, Deserialize)]
+pub struct TeamScoreUpdateRequest {
+    pub team: String,
+
+    /// Points to add (negative to subtract)
+    pub delta: i32,
+}
+
+/// Request body for starting the round countdown
+#[derive(Debug, Deserialize)]
+pub struct TimerStartRequest {
+    /// Length of the round in seconds
+    pub duration_secs: u32,
+}
+
+/// One event in a POST /api/events/batch request
+///
+/// Tagged with the name of the matching endpoint, e.g.
+/// `{"type": "barrier_break", "team": "Red Team"}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventRequest {
+    BarrierBreak(BarrierBrokenRequest),
+    BarrierRepair(BarrierRepairedRequest),
+    LedBreak(LedDisplayBrokenRequest),
+    LedRepair,
+    ScadaCompromise(ScadaCompromisedRequest),
+    ScadaRestore(ScadaRestoredRequest),
+    BuildingFire(BuildingFireRequest),
+    BuildingFireExtinguish(BuildingFireExtinguishRequest),
+    PowerOutage(PowerRequest),
+    PowerRestore(PowerRequest),
+    RoadClose(RoadRequest),
+    RoadOpen(RoadRequest),
+    WeatherChange(WeatherChangeRequest),
+    GreenWave(GreenWaveRequest),
+    EmergencyStart(EmergencyStopRequest),
+    EmergencyStop,
+    DangerActivate(DangerModeRequest),
+    DangerDeactivate,
+    Log(LogMessageRequest),
+}
+
+impl From<BarrierBrokenRequest> for GameEvent {
+    fn from(req: BarrierBrokenRequest) -> Self {
+        GameEvent::BarrierBroken {
+            team: req.team,
+            message: req.message,
+        }
+    }
+}
+
+impl From<BarrierRepairedRequest> for GameEvent {
+    fn from(req: BarrierRepairedRequest) -> Self {
+        GameEvent::BarrierRepaired { team: req.team }
+    }
+}
+
+impl From<LedDisplayBrokenRequest> for GameEvent {
+    fn from(req: LedDisplayBrokenRequest) -> Self {
+        GameEvent::LedDisplayBroken {
+            team: req.team,
+            message: req.message,
+        }
+    }
+}
+
+impl From<ScadaCompromisedRequest> for GameEvent {
+    fn from(req: ScadaCompromisedRequest) -> Self {
+        GameEvent::ScadaCompromised {
+            building_id: req.building_id,
+            team: req.team,
+            message: req.message,
+        }
+    }
+}
+
+impl From<ScadaRestoredRequest> for GameEvent {
+    fn from(req: ScadaRestoredRequest) -> Self {
+        GameEvent::ScadaRestored {
+            building_id: req.building_id,
+        }
+    }
+}
+
+impl From<BuildingFireRequest> for GameEvent {
+    fn from(req: BuildingFireRequest) -> Self {
+        GameEvent::BuildingFire {
+            building_id: req.building_id,
+            intensity: req.intensity.unwrap_or(1.0),
+        }
+    }
+}
+
+impl From<BuildingFireExtinguishRequest> for GameEvent {
+    fn from(req: BuildingFireExtinguishRequest) -> Self {
+        GameEvent::BuildingFireExtinguished {
+            building_id: req.building_id,
+        }
+    }
+}
+
+impl From<WeatherChangeRequest> for GameEvent {
+    fn from(req: WeatherChangeRequest) -> Self {
+        GameEvent::WeatherChange {
+            rain_intensity: req.rain_intensity,
+        }
+    }
+}
+
+impl From<GreenWaveRequest> for GameEvent {
+    fn from(req: GreenWaveRequest) -> Self {
+        GameEvent::GreenWave { speed: req.speed }
+    }
+}
+
+impl From<EmergencyStopRequest> for GameEvent {
+    fn from(req: EmergencyStopRequest) -> Self {
+        GameEvent::EmergencyStop { reason: req.reason }
+    }
+}
+
+impl From<DangerModeRequest> for GameEvent {
+    fn from(req: DangerModeRequest) -> Self {
+        GameEvent::DangerModeActivated { reason: req.reason }
+    }
+}
+
+impl From<LogMessageRequest> for GameEvent {
+    fn from(req: LogMessageRequest) -> Self {
+        GameEvent::LogMessage {
+            level: req.level,
+            message: req.message,
+        }
+    }
+}
+
+impl From<EventRequest> for GameEvent {
+    fn from(req: EventRequest) -> Self {
+        match req {
+            EventRequest::BarrierBreak(req) => req.into(),
+            EventRequest::BarrierRepair(req) => req.into(),
+            EventRequest::LedBreak(req) => req.into(),
+            EventRequest::LedRepair => GameEvent::LedDisplayRepaired,
+            EventRequest::ScadaCompromise(req) => req.into(),
+            EventRequest::ScadaRestore(req) => req.into(),
+            EventRequest::BuildingFire(req) => req.into(),
+            EventRequest::BuildingFireExtinguish(req) => req.into(),
+            EventRequest::PowerOutage(req) => GameEvent::PowerOutage {
+                district: req.district,
+            },
+            EventRequest::PowerRestore(req) => GameEvent::PowerRestored {
+                district: req.district,
+            },
+            EventRequest::RoadClose(req) => GameEvent::RoadClosure {
+                road_id: req.road_id,
+                blocked: true,
+            },
+            EventRequest::RoadOpen(req) => GameEvent::RoadClosure {
+                road_id: req.road_id,
+                blocked: false,
+            },
+            EventRequest::WeatherChange(req) => req.into(),
+            EventRequest::GreenWave(req) => req.into(),
+            EventRequest::EmergencyStart(req) => req.into(),
+            EventRequest::EmergencyStop => GameEvent::EmergencyStopDeactivated,
+            EventRequest::DangerActivate(req) => req.into(),
+            EventRequest::DangerDeactivate => GameEvent::DangerModeDeactivated,
+            EventRequest::Log(req) => req.into(),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Current game state
+//!
+//! Tracks what the city currently looks like so that clients connecting
+//! after the fact can catch up. The backend updates it alongside every
+//! broadcast and sends it in `StateSnapshot` events.
+
+use crate::events::GameEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Highest building ID the dashboard knows about (city blocks are 1-12)
+///
+/// SCADA events without a `building_id` cover every building from 1 to here.
+pub const MAX_BUILDING_ID: u32 = 12;
+
+/// Snapshot of the game's current state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameState {
+    /// Whether the barrier gate is currently broken
+    pub barrier_broken: bool,
+
+    /// Team that broke the barrier gate, while it is broken
+    #[serde(default)]
+    pub barrier_broken_by: Option<String>,
+
+    /// Message shown by the broken LED display, or None while it works
+    pub led_broken: Option<String>,
+
+    /// Whether danger mode is active
+    pub danger_mode_active: bool,
+
+    /// Whether the emergency traffic stop is active
+    pub emergency_stop_active: bool,
+
+    /// SCADA compromised state per building ID
+    #[serde(deserialize_with = "deserialize_building_map")]
+    pub scada_compromised_buildings: HashMap<u32, bool>,
+
+    /// Team holding each compromised building, by building ID
+    #[serde(default, deserialize_with = "deserialize_building_map")]
+    pub scada_compromised_by: HashMap<u32, String>,
+
+    /// Fire intensity per burning building ID
+    #[serde(default, deserialize_with = "deserialize_building_map")]
+    pub buildings_on_fire: HashMap<u32, f32>,
+
+    /// Districts currently without power
+    #[serde(default)]
+    pub districts_without_power: HashSet<String>,
+
+    /// IDs of the roads currently closed to traffic
+    #[serde(default)]
+    pub closed_roads: HashSet<usize>,
+
+    /// Current score per team name
+    #[serde(default)]
+    pub team_scores: HashMap<String, i32>,
+
+    /// Seconds left on the round countdown, or None while no countdown runs
+    #[serde(default)]
+    pub timer_remaining_secs: Option<u32>,
+
+    /// Current rain intensity (0.0 = dry)
+    #[serde(default)]
+    pub rain_intensity: f32,
+}
+
+/// Deserializes a JSON object keyed by building ID
+///
+/// Inside internally tagged events serde can't parse numeric map keys from
+/// their string form, so the keys are read as strings and parsed here.
+fn deserialize_building_map<'de, D, V>(deserializer: D) -> Result<HashMap<u32, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let raw = HashMap::<String, V>::deserialize(deserializer)?;
+    raw.into_iter()
+        .map(|(id, value)| {
+            id.parse()
+                .map(|id| (id, value))
+                .map_err(serde::de::Error::custom)
+        })
+        .collect()
+}
+
+impl GameState {
+    /// Updates the state to reflect a broadcast event
+    ///
+    /// Events that don't change the city (log messages, connection status,
+    /// snapshots) leave the state untouched.
+    pub fn apply(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::BarrierBroken { team, .. } => {
+                self.barrier_broken = true;
+                self.barrier_broken_by = Some(team.clone());
+            }
+            GameEvent::BarrierRepaired { .. } => {
+                self.barrier_broken = false;
+                self.barrier_broken_by = None;
+            }
+            GameEvent::LedDisplayBroken { message, .. } => {
+                self.led_broken = Some(message.clone().unwrap_or_default());
+            }
+            GameEvent::LedDisplayRepaired => self.led_broken = None,
+            GameEvent::ScadaCompromised { building_id, team, .. } => {
+                self.set_scada_compromised(*building_id, Some(team));
+            }
+            GameEvent::ScadaRestored { building_id } => {
+                self.set_scada_compromised(*building_id, None);
+            }
+            GameEvent::BuildingFire {
+                building_id,
+                intensity,
+            } => {
+                self.buildings_on_fire.insert(*building_id, *intensity);
+            }
+            GameEvent::BuildingFireExtinguished { building_id } => {
+                self.buildings_on_fire.remove(building_id);
+            }
+            GameEvent::PowerOutage { district } => {
+                self.districts_without_power.insert(district.clone());
+            }
+            GameEvent::PowerRestored { district } => {
+                self.districts_without_power.remove(district);
+            }
+            GameEvent::RoadClosure { road_id, blocked } => {
+                if *blocked {
+                    self.closed_roads.insert(*road_id);
+                } else {
+                    self.closed_roads.remove(road_id);
+                }
+            }
+            GameEvent::WeatherChange { rain_intensity } => self.rain_intensity = *rain_intensity,
+            GameEvent::EmergencyStop { .. } => self.emergency_stop_active = true,
+            GameEvent::EmergencyStopDeactivated => self.emergency_stop_active = false,
+            GameEvent::DangerModeActivated { .. } => self.danger_mode_active = true,
+            GameEvent::DangerModeDeactivated => self.danger_mode_active = false,
+            GameEvent::TeamScoreUpdate { team, total, .. } => {
+                self.team_scores.insert(team.clone(), *total);
+            }
+            GameEvent::TimerUpdate {
+                remaining_secs,
+                running,
+            } => {
+                self.timer_remaining_secs = running.then_some(*remaining_secs);
+            }
+            GameEvent::TimerExpired => self.timer_remaining_secs = None,
+            GameEvent::GameReset => *self = GameState::default(),
+            GameEvent::GreenWave { .. }
+            | GameEvent::CitySave
+            | GameEvent::CityLoad
+            | GameEvent::LogMessage { .. }
+            | GameEvent::ConnectionStatus { .. }
+            | GameEvent::Heartbeat { .. }
+            | GameEvent::StateSnapshot { .. } => {}
+        }
+    }
+
+    /// Current score of a team (0 if it has never scored)
+    pub fn team_score(&self, team: &str) -> i32 {
+        self.team_scores.get(team).copied().unwrap_or(0)
+    }
+
+    /// Builds the event that changes a team's score by `delta`
+    ///
+    /// The new total saturates at the `i32` bounds rather than wrapping.
+    pub fn score_update(&self, team: String, delta: i32) -> GameEvent {
+        let total = self.team_score(&team).saturating_add(delta);
+        GameEvent::TeamScoreUpdate { team, delta, total }
+    }
+
+    /// Marks one building, or every building from 1 to `MAX_BUILDING_ID`
+    /// when no ID is given, as compromised by a team or working again
+    ///
+    /// # Arguments
+    /// * `building_id` - Building to change, or None for all of them
+    /// * `team` - Team compromising the buildings, or None to restore them
+    pub fn set_scada_compromised(&mut self, building_id: Option<u32>, team: Option<&str>) {
+        let ids = match building_id {
+            Some(id) => id..=id,
+            None => 1..=MAX_BUILDING_ID,
+        };
+        for id in ids {
+            self.scada_compromised_buildings.insert(id, team.is_some());
+            match team {
+                Some(team) => self.scada_compromised_by.insert(id, team.to_string()),
+                None => self.scada_compromised_by.remove(&id),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_scada_compromised() {
+        let mut state = GameState::default();
+
+        state.set_scada_compromised(Some(5), Some("Red"));
+        state.set_scada_compromised(Some(7), Some("Blue"));
+        assert_eq!(state.scada_compromised_buildings.get(&5), Some(&true));
+
+        state.set_scada_compromised(Some(5), None);
+        assert_eq!(state.scada_compromised_buildings.get(&5), Some(&false));
+        assert_eq!(state.scada_compromised_buildings.get(&7), Some(&true));
+        assert_eq!(state.scada_compromised_by, [(7, "Blue".to_string())].into());
+
+        state.set_scada_compromised(None, None);
+        assert!(state.scada_compromised_buildings.values().all(|&c| !c));
+        assert!(state.scada_compromised_by.is_empty());
+    }
+
+    #[test]
+    fn test_scada_events_without_building_cover_every_building() {
+        let mut state = GameState::default();
+        state.apply(&GameEvent::ScadaCompromised {
+            building_id: None,
+            team: "Red".to_string(),
+            message: None,
+        });
+        assert_eq!(state.scada_compromised_buildings.len(), MAX_BUILDING_ID as usize);
+        assert!(state.scada_compromised_buildings.values().all(|&c| c));
+        assert_eq!(state.scada_compromised_by.get(&12).map(String::as_str), Some("Red"));
+
+        state.apply(&GameEvent::ScadaRestored { building_id: None });
+        assert!(state.scada_compromised_buildings.values().all(|&c| !c));
+        assert!(state.scada_compromised_by.is_empty());
+    }
+
+    #[test]
+    fn test_barrier_remembers_the_team_that_broke_it() {
+        let mut state = GameState::default();
+        state.apply(&GameEvent::BarrierBroken {
+            team: "Red".to_string(),
+            message: None,
+        });
+        assert_eq!(state.barrier_broken_by.as_deref(), Some("Red"));
+
+        state.apply(&GameEvent::BarrierRepaired { team: None });
+        assert!(!state.barrier_broken && state.barrier_broken_by.is_none());
+    }
+
+    #[test]
+    fn test_building_fires_are_tracked_until_extinguished() {
+        let mut state = GameState::default();
+        state.apply(&GameEvent::BuildingFire {
+            building_id: 2,
+            intensity: 0.4,
+        });
+        state.apply(&GameEvent::BuildingFire {
+            building_id: 9,
+            intensity: 1.0,
+        });
+
+        // Setting a burning building on fire again changes its intensity
+        state.apply(&GameEvent::BuildingFire {
+            building_id: 2,
+            intensity: 0.8,
+        });
+        assert_eq!(state.buildings_on_fire.get(&2), Some(&0.8));
+
+        state.apply(&GameEvent::BuildingFireExtinguished { building_id: 2 });
+        state.apply(&GameEvent::BuildingFireExtinguished { building_id: 5 });
+        assert_eq!(state.buildings_on_fire, [(9, 1.0)].into());
+    }
+
+    #[test]
+    fn test_power_outages_are_tracked_per_district() {
+        let mut state = GameState::default();
+        for district in ["west", "east", "west"] {
+            state.apply(&GameEvent::PowerOutage {
+                district: district.to_string(),
+            });
+        }
+        state.apply(&GameEvent::PowerRestored {
+            district: "west".to_string(),
+        });
+        assert_eq!(state.districts_without_power, ["east".to_string()].into());
+    }
+
+    #[test]
+    fn test_road_closures_are_tracked_per_road() {
+        let mut state = GameState::default();
+        for (road_id, blocked) in [(1, true), (3, true), (1, false), (4, false)] {
+            state.apply(&GameEvent::RoadClosure { road_id, blocked });
+        }
+        assert_eq!(state.closed_roads, [3].into());
+    }
+
+    #[test]
+    fn test_score_updates_accumulate_per_team() {
+        let mut state = GameState::default();
+        for (team, delta) in [("Red", 100), ("Blue", 40), ("Red", -30)] {
+            let event = state.score_update(team.to_string(), delta);
+            state.apply(&event);
+        }
+        assert_eq!(state.team_score("Red"), 70);
+        assert_eq!(state.team_score("Blue"), 40);
+        assert_eq!(state.team_score("Green"), 0);
+
+        match state.score_update("Red".to_string(), i32::MAX) {
+            GameEvent::TeamScoreUpdate { total, .. } => assert_eq!(total, i32::MAX),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_game_reset_restores_initial_state() {
+        let mut state = GameState::default();
+        state.apply(&GameEvent::BarrierBroken {
+            team: "Red".to_string(),
+            message: None,
+        });
+        state.apply(&GameEvent::LedDisplayBroken {
+            team: "Red".to_string(),
+            message: Some("pwned".to_string()),
+        });
+        state.apply(&GameEvent::EmergencyStop {
+            reason: "Drill".to_string(),
+        });
+        state.set_scada_compromised(Some(3), Some("Red"));
+        state.apply(&GameEvent::BuildingFire {
+            building_id: 4,
+            intensity: 1.0,
+        });
+        state.apply(&GameEvent::PowerOutage {
+            district: "east".to_string(),
+        });
+        state.apply(&GameEvent::WeatherChange {
+            rain_intensity: 0.5,
+        });
+        state.apply(&GameEvent::RoadClosure {
+            road_id: 2,
+            blocked: true,
+        });
+        let score = state.score_update("Red".to_string(), 50);
+        state.apply(&score);
+
+        state.apply(&GameEvent::GameReset);
+        assert_eq!(state, GameState::default());
+    }
+}
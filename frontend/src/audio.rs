@@ -0,0 +1,338 @@
+//! Sound effects and ambient audio
+//!
+//! This module handles:
+//! - Loading the sound files at startup, tolerating missing ones
+//! - Mapping game events to the sound cue they trigger
+//! - The danger mode alarm loop and one-shot effects
+//! - Ambient traffic noise that swells with the number of cars
+//! - The master mute toggle
+//!
+//! Sounds are read from the directory set in the `audio` section of the
+//! configuration file (`assets/sounds` by default):
+//!
+//! | File          | Played when                          |
+//! |---------------|--------------------------------------|
+//! | `alarm.ogg`   | Looped while danger mode is active   |
+//! | `klaxon.ogg`  | Once when the emergency stop engages |
+//! | `crash.ogg`   | Once when the barrier is broken      |
+//! | `blip.ogg`    | Once when a SCADA system is hacked   |
+//! | `traffic.ogg` | Looped all the time, louder with cars|
+//!
+//! A missing file only silences its cue. Sound is played only in builds
+//! with the `audio` feature; without it every cue is silent.
+
+use crate::events::GameEvent;
+use macroquad::audio::{
+    load_sound, play_sound, play_sound_once, set_sound_volume, stop_sound, PlaySoundParams,
+    Sound,
+};
+use serde::Deserialize;
+
+// ============================================================================
+// Audio Constants
+// ============================================================================
+
+/// Number of cars at which the traffic noise reaches full volume
+const TRAFFIC_FULL_VOLUME_CARS: usize = 30;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Where the sounds are loaded from and how loud each kind plays
+///
+/// Volumes run from 0.0 (silent) to 1.0 (full); each category is scaled by
+/// `master`. Fields left out of the configuration file keep their defaults.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AudioConfig {
+    /// Directory holding the sound files
+    pub directory: String,
+
+    /// Volume every sound is scaled by
+    pub master: f32,
+
+    /// Volume of the danger mode alarm
+    pub alarm: f32,
+
+    /// Volume of the klaxon, crash and alert blip
+    pub effects: f32,
+
+    /// Volume of the traffic noise with the city full of cars
+    pub ambient: f32,
+}
+
+impl Default for AudioConfig {
+    /// Sounds from `assets/sounds`, with the traffic noise kept low under
+    /// the alarms
+    fn default() -> Self {
+        Self {
+            directory: "assets/sounds".to_string(),
+            master: 0.8,
+            alarm: 0.7,
+            effects: 1.0,
+            ambient: 0.3,
+        }
+    }
+}
+
+// ============================================================================
+// Cues
+// ============================================================================
+
+/// A sound triggered by something happening in the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    /// Start looping the alarm, unless it already plays
+    StartAlarm,
+    /// Stop the alarm loop
+    StopAlarm,
+    /// Sound the klaxon once
+    Klaxon,
+    /// Play the crash once
+    Crash,
+    /// Play the alert blip once
+    Blip,
+}
+
+/// Gets the sound cue a game event triggers
+///
+/// Events from the server and the equivalent keyboard controls both go
+/// through here, so they always sound the same.
+///
+/// # Arguments
+/// * `event` - Event that happened
+///
+/// # Returns
+/// The cue to play, or `None` if the event is silent
+pub fn cue_for_event(event: &GameEvent) -> Option<Cue> {
+    match event {
+        GameEvent::DangerModeActivated { .. } => Some(Cue::StartAlarm),
+        GameEvent::DangerModeDeactivated | GameEvent::GameReset => Some(Cue::StopAlarm),
+        GameEvent::StateSnapshot { state } if state.danger_mode_active => Some(Cue::StartAlarm),
+        GameEvent::StateSnapshot { .. } => Some(Cue::StopAlarm),
+        GameEvent::EmergencyStop { .. } => Some(Cue::Klaxon),
+        GameEvent::BarrierBroken { .. } => Some(Cue::Crash),
+        GameEvent::ScadaCompromised { .. } => Some(Cue::Blip),
+        _ => None,
+    }
+}
+
+/// Volume of the traffic noise relative to the ambient volume
+///
+/// # Arguments
+/// * `car_count` - Number of cars in the city
+///
+/// # Returns
+/// 0.0 with no cars, rising linearly to 1.0 at `TRAFFIC_FULL_VOLUME_CARS`
+fn traffic_level(car_count: usize) -> f32 {
+    (car_count as f32 / TRAFFIC_FULL_VOLUME_CARS as f32).min(1.0)
+}
+
+// ============================================================================
+// Audio Player
+// ============================================================================
+
+/// Plays the loaded sounds in response to cues
+pub struct AudioPlayer {
+    config: AudioConfig,
+    alarm: Option<Sound>,
+    klaxon: Option<Sound>,
+    crash: Option<Sound>,
+    blip: Option<Sound>,
+    traffic: Option<Sound>,
+
+    /// Whether the alarm loop is playing
+    alarm_on: bool,
+
+    /// Traffic noise level last set, relative to the ambient volume
+    traffic_level: f32,
+
+    muted: bool,
+}
+
+impl AudioPlayer {
+    /// Loads the sounds and starts the (silent) traffic noise loop
+    ///
+    /// # Arguments
+    /// * `config` - Sound directory and volumes
+    ///
+    /// # Returns
+    /// The player and the names of the sound files that could not be loaded
+    pub async fn load(config: AudioConfig) -> (Self, Vec<String>) {
+        let mut missing = Vec::new();
+        let mut sounds = Vec::new();
+        for name in ["alarm.ogg", "klaxon.ogg", "crash.ogg", "blip.ogg", "traffic.ogg"] {
+            let path = format!("{}/{}", config.directory, name);
+            match load_sound(&path).await {
+                Ok(sound) => sounds.push(Some(sound)),
+                Err(_) => {
+                    missing.push(path);
+                    sounds.push(None);
+                }
+            }
+        }
+        let mut sounds = sounds.into_iter();
+        let mut next = move || sounds.next().flatten();
+
+        let player = Self {
+            alarm: next(),
+            klaxon: next(),
+            crash: next(),
+            blip: next(),
+            traffic: next(),
+            config,
+            alarm_on: false,
+            traffic_level: 0.0,
+            muted: false,
+        };
+        if let Some(traffic) = &player.traffic {
+            play_sound(
+                traffic,
+                PlaySoundParams {
+                    looped: true,
+                    volume: 0.0,
+                },
+            );
+        }
+        (player, missing)
+    }
+
+    /// Plays whatever sound a game event triggers
+    ///
+    /// # Arguments
+    /// * `event` - Event from the server, or the one a keyboard control
+    ///   stands in for
+    pub fn handle_event(&mut self, event: &GameEvent) {
+        if let Some(cue) = cue_for_event(event) {
+            self.play(cue);
+        }
+    }
+
+    /// Plays a cue
+    ///
+    /// # Arguments
+    /// * `cue` - Cue to play
+    fn play(&mut self, cue: Cue) {
+        let one_shot = match cue {
+            Cue::StartAlarm => {
+                if !self.alarm_on {
+                    self.alarm_on = true;
+                    if let Some(alarm) = &self.alarm {
+                        play_sound(
+                            alarm,
+                            PlaySoundParams {
+                                looped: true,
+                                volume: self.volume(self.config.alarm),
+                            },
+                        );
+                    }
+                }
+                return;
+            }
+            Cue::StopAlarm => {
+                if self.alarm_on {
+                    self.alarm_on = false;
+                    if let Some(alarm) = &self.alarm {
+                        stop_sound(alarm);
+                    }
+                }
+                return;
+            }
+            Cue::Klaxon => &self.klaxon,
+            Cue::Crash => &self.crash,
+            Cue::Blip => &self.blip,
+        };
+        if let (Some(sound), false) = (one_shot, self.muted) {
+            set_sound_volume(sound, self.volume(self.config.effects));
+            play_sound_once(sound);
+        }
+    }
+
+    /// Adjusts the traffic noise to the number of cars
+    ///
+    /// # Arguments
+    /// * `car_count` - Number of cars in the city
+    pub fn set_traffic(&mut self, car_count: usize) {
+        let level = traffic_level(car_count);
+        if level != self.traffic_level {
+            self.traffic_level = level;
+            self.apply_loop_volumes();
+        }
+    }
+
+    /// Mutes or unmutes every sound
+    ///
+    /// # Returns
+    /// `true` if sound is now muted
+    pub fn toggle_mute(&mut self) -> bool {
+        self.muted = !self.muted;
+        self.apply_loop_volumes();
+        self.muted
+    }
+
+    /// Volume of a category after the master volume and mute
+    fn volume(&self, category: f32) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            (category * self.config.master).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Sets the volume of the looping sounds, which keep playing while muted
+    fn apply_loop_volumes(&self) {
+        if let Some(traffic) = &self.traffic {
+            set_sound_volume(traffic, self.volume(self.config.ambient * self.traffic_level));
+        }
+        if let (Some(alarm), true) = (&self.alarm, self.alarm_on) {
+            set_sound_volume(alarm, self.volume(self.config.alarm));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GameState;
+
+    #[test]
+    fn test_events_map_to_cues_and_traffic_noise_follows_cars() {
+        let danger = GameEvent::DangerModeActivated {
+            reason: "Breach".to_string(),
+        };
+        assert_eq!(cue_for_event(&danger), Some(Cue::StartAlarm));
+        assert_eq!(cue_for_event(&GameEvent::DangerModeDeactivated), Some(Cue::StopAlarm));
+        assert_eq!(cue_for_event(&GameEvent::GameReset), Some(Cue::StopAlarm));
+        let stop = GameEvent::EmergencyStop {
+            reason: "Drill".to_string(),
+        };
+        assert_eq!(cue_for_event(&stop), Some(Cue::Klaxon));
+        let barrier = GameEvent::BarrierBroken {
+            team: "Red Team".to_string(),
+            message: None,
+        };
+        assert_eq!(cue_for_event(&barrier), Some(Cue::Crash));
+        let scada = GameEvent::ScadaCompromised {
+            building_id: None,
+            team: "Red Team".to_string(),
+            message: None,
+        };
+        assert_eq!(cue_for_event(&scada), Some(Cue::Blip));
+        assert_eq!(cue_for_event(&GameEvent::LedDisplayRepaired), None);
+
+        // A snapshot brings the alarm in line with the server's danger mode
+        let state = GameState {
+            danger_mode_active: true,
+            ..GameState::default()
+        };
+        let snapshot = GameEvent::StateSnapshot {
+            state: Box::new(state),
+        };
+        assert_eq!(cue_for_event(&snapshot), Some(Cue::StartAlarm));
+
+        assert_eq!(traffic_level(0), 0.0);
+        assert_eq!(traffic_level(TRAFFIC_FULL_VOLUME_CARS / 2), 0.5);
+        assert_eq!(traffic_level(TRAFFIC_FULL_VOLUME_CARS * 3), 1.0);
+    }
+}
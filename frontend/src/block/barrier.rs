@@ -0,0 +1,273 @@
+//! Barrier block object implementation
+//!
+//! Provides a boom barrier that closes off the road running along the right
+//! edge of its block. The arm sweeps up and down when the barrier is toggled,
+//! and cars on the road stop in front of it until it is fully open. While a
+//! team has broken it, the arm's red stripes take that team's color.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use crate::constants::visual::ROAD_WIDTH;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Barrier Rendering Constants
+// ============================================================================
+
+/// Time in seconds for the arm to sweep between closed and open
+const BARRIER_SWING_DURATION: f32 = 1.0;
+
+/// Arm angle when fully open (vertical)
+const BARRIER_OPEN_ANGLE: f32 = std::f32::consts::FRAC_PI_2;
+
+/// Post size in pixels
+const POST_WIDTH: f32 = 6.0;
+const POST_HEIGHT: f32 = 15.0;
+
+/// Arm thickness in pixels
+const ARM_THICKNESS: f32 = 4.0;
+
+/// Number of alternating red/white stripes on the arm
+const ARM_STRIPE_COUNT: usize = 4;
+
+/// Counterweight size and distance behind the pivot in pixels
+const COUNTERWEIGHT_SIZE: f32 = 6.0;
+const COUNTERWEIGHT_OFFSET: f32 = 10.0;
+
+const POST_COLOR: Color = Color::new(0.3, 0.3, 0.3, 1.0);
+const ARM_RED: Color = Color::new(0.95, 0.1, 0.1, 1.0);
+const ARM_WHITE: Color = Color::new(0.95, 0.95, 0.95, 1.0);
+const COUNTERWEIGHT_COLOR: Color = Color::new(0.7, 0.1, 0.1, 1.0);
+
+// ============================================================================
+// Barrier State
+// ============================================================================
+
+/// Position of a barrier's arm
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarrierState {
+    /// Raised, letting traffic through
+    Open,
+    /// Lowered across the road
+    Closed,
+    /// Rising, with the progress of the swing (0.0-1.0)
+    Opening(f32),
+    /// Lowering, with the progress of the swing (0.0-1.0)
+    Closing(f32),
+}
+
+impl BarrierState {
+    /// How far the arm is raised (0.0 = closed/horizontal, 1.0 = open/vertical)
+    pub fn openness(self) -> f32 {
+        match self {
+            BarrierState::Open => 1.0,
+            BarrierState::Closed => 0.0,
+            BarrierState::Opening(progress) => progress,
+            BarrierState::Closing(progress) => 1.0 - progress,
+        }
+    }
+
+    /// State after starting to move toward open or closed
+    ///
+    /// An arm already moving the other way turns around where it is.
+    pub(super) fn toward(self, open: bool) -> Self {
+        match (self, open) {
+            (BarrierState::Closed, true) => BarrierState::Opening(0.0),
+            (BarrierState::Closing(progress), true) => BarrierState::Opening(1.0 - progress),
+            (BarrierState::Open, false) => BarrierState::Closing(0.0),
+            (BarrierState::Opening(progress), false) => BarrierState::Closing(1.0 - progress),
+            (state, _) => state,
+        }
+    }
+
+    /// State after the arm has swung for `step` of a full swing
+    pub(super) fn advance(self, step: f32) -> Self {
+        match self {
+            BarrierState::Opening(progress) if progress + step >= 1.0 => BarrierState::Open,
+            BarrierState::Opening(progress) => BarrierState::Opening(progress + step),
+            BarrierState::Closing(progress) if progress + step >= 1.0 => BarrierState::Closed,
+            BarrierState::Closing(progress) => BarrierState::Closing(progress + step),
+            state => state,
+        }
+    }
+}
+
+// ============================================================================
+// Barrier Object Implementation
+// ============================================================================
+
+/// A boom barrier across the vertical road on the right edge of its block
+///
+/// The post stands at the block's right edge and the arm spans the road.
+pub struct Barrier {
+    /// Vertical offset as percentage of block height (0.0 = top edge, 1.0 = bottom edge)
+    pub y_offset_percent: f32,
+
+    /// Position of the arm; changed with `set_open` so it animates
+    pub state: BarrierState,
+
+    /// Color of the team that broke the barrier, shown on the arm until repaired
+    pub broken_by: Option<Color>,
+}
+
+impl Barrier {
+    /// Creates a closed barrier
+    ///
+    /// # Arguments
+    /// * `y_offset_percent` - Position along the block's right edge (0.0-1.0)
+    pub fn new(y_offset_percent: f32) -> Self {
+        Self {
+            y_offset_percent,
+            state: BarrierState::Closed,
+            broken_by: None,
+        }
+    }
+
+    /// Sets the target state; the arm animates toward it in `update`
+    pub fn set_open(&mut self, open: bool) {
+        self.state = self.state.toward(open);
+    }
+
+    /// Returns true unless the arm is fully raised
+    ///
+    /// Cars wait while the arm is moving in either direction.
+    pub fn blocks_traffic(&self) -> bool {
+        self.state != BarrierState::Open
+    }
+
+    /// Point on the road center line where traffic must stop, in pixels
+    ///
+    /// # Arguments
+    /// * `block` - The block containing this barrier
+    pub fn stop_point(&self, block: &Block) -> (f32, f32) {
+        (
+            block.x() + block.width() + ROAD_WIDTH / 2.0,
+            block.y() + self.y_offset_percent * block.height(),
+        )
+    }
+
+    /// Current arm angle in radians (0 = closed, π/2 = open)
+    fn arm_angle(&self) -> f32 {
+        // Ease in and out so the arm starts and stops smoothly
+        let t = self.state.openness();
+        let eased = t * t * (3.0 - 2.0 * t);
+        eased * BARRIER_OPEN_ANGLE
+    }
+}
+
+impl BlockObject for Barrier {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.state = self.state.advance(dt / BARRIER_SWING_DURATION);
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let pivot_x = block.x() + block.width();
+        let pivot_y = block.y() + self.y_offset_percent * block.height() - POST_HEIGHT;
+
+        // Post (vertical support at the roadside)
+        draw_rectangle(
+            pivot_x - POST_WIDTH,
+            pivot_y,
+            POST_WIDTH,
+            POST_HEIGHT,
+            context.shade(POST_COLOR),
+        );
+
+        // Arm spans the road when closed and points straight up when open
+        let angle = self.arm_angle();
+        let (dir_x, dir_y) = (angle.cos(), -angle.sin());
+        let (perp_x, perp_y) = (-dir_y * ARM_THICKNESS / 2.0, dir_x * ARM_THICKNESS / 2.0);
+        let arm_x = pivot_x - POST_WIDTH / 2.0;
+        let stripe_length = ROAD_WIDTH / ARM_STRIPE_COUNT as f32;
+        let stripe_color = self.broken_by.unwrap_or(ARM_RED);
+
+        for i in 0..ARM_STRIPE_COUNT {
+            let start = i as f32 * stripe_length;
+            let end = start + stripe_length;
+            let color = context.shade(if i % 2 == 0 { stripe_color } else { ARM_WHITE });
+
+            let (x1, y1) = (arm_x + start * dir_x, pivot_y + start * dir_y);
+            let (x2, y2) = (arm_x + end * dir_x, pivot_y + end * dir_y);
+
+            draw_triangle(
+                vec2(x1 + perp_x, y1 + perp_y),
+                vec2(x1 - perp_x, y1 - perp_y),
+                vec2(x2 + perp_x, y2 + perp_y),
+                color,
+            );
+            draw_triangle(
+                vec2(x2 + perp_x, y2 + perp_y),
+                vec2(x2 - perp_x, y2 - perp_y),
+                vec2(x1 - perp_x, y1 - perp_y),
+                color,
+            );
+        }
+
+        // Counterweight behind the pivot, opposite the arm
+        draw_rectangle(
+            arm_x - COUNTERWEIGHT_OFFSET * dir_x - COUNTERWEIGHT_SIZE / 2.0,
+            pivot_y - COUNTERWEIGHT_OFFSET * dir_y - COUNTERWEIGHT_SIZE / 2.0,
+            COUNTERWEIGHT_SIZE,
+            COUNTERWEIGHT_SIZE,
+            context.shade(COUNTERWEIGHT_COLOR),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_barrier_opens_over_swing_duration() {
+        let mut barrier = Barrier::new(0.5);
+        assert!(barrier.blocks_traffic());
+
+        barrier.set_open(true);
+        barrier.update(BARRIER_SWING_DURATION / 2.0);
+        assert_eq!(barrier.state, BarrierState::Opening(0.5));
+        assert!(barrier.blocks_traffic(), "half-open arm still blocks traffic");
+
+        barrier.update(BARRIER_SWING_DURATION / 2.0);
+        assert_eq!(barrier.state, BarrierState::Open);
+        assert!(!barrier.blocks_traffic());
+        assert!((barrier.arm_angle() - BARRIER_OPEN_ANGLE).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_closing_barrier_blocks_immediately() {
+        let mut barrier = Barrier::new(0.5);
+        barrier.set_open(true);
+        barrier.update(BARRIER_SWING_DURATION);
+
+        barrier.set_open(false);
+        assert!(barrier.blocks_traffic());
+
+        barrier.update(BARRIER_SWING_DURATION);
+        assert_eq!(barrier.state, BarrierState::Closed);
+        assert_eq!(barrier.arm_angle(), 0.0);
+    }
+
+    #[test]
+    fn test_reversing_midway_turns_arm_around_in_place() {
+        let mut barrier = Barrier::new(0.5);
+        barrier.set_open(true);
+        barrier.update(BARRIER_SWING_DURATION * 0.75);
+        let angle = barrier.arm_angle();
+
+        barrier.set_open(false);
+        assert_eq!(barrier.state, BarrierState::Closing(0.25));
+        assert!((barrier.arm_angle() - angle).abs() < 1e-6);
+
+        // Asking for the state it is already heading to changes nothing
+        barrier.set_open(false);
+        assert_eq!(barrier.state, BarrierState::Closing(0.25));
+    }
+}
@@ -2,10 +2,18 @@
 //!
 //! Provides a 3D building that can be placed in city blocks with
 //! isometric rendering showing front, side, and top faces.
+//!
+//! Buildings with SCADA control show the state of their system: a pulsing
+//! border in the attacking team's color (red if unknown) and a blinking LED
+//! warning sign while compromised, a gray
+//! border while offline, and a fading green flash once restored.
 
 use crate::block::{Block, BlockObject, RenderContext};
-use crate::rendering::draw_rounded_rectangle;
+use crate::rendering::led_display::draw_led_char;
+use crate::rendering::{darken_color, draw_rounded_rectangle, lighten_color};
+use crate::team::Team;
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Building Rendering Constants
@@ -28,42 +36,94 @@ const BUILDING_TOP_LIGHTEN: f32 = 0.1;
 /// Corner radius for building top (in pixels)
 pub const BUILDING_CORNER_RADIUS: f32 = 8.0;
 
+/// Red tint blended into a compromised building's faces (pulses between these)
+const SCADA_TINT_MIN: f32 = 0.35;
+const SCADA_TINT_MAX: f32 = 0.75;
+
+/// Pulse frequency of the compromised tint (Hz)
+const SCADA_PULSE_FREQUENCY: f64 = 1.0;
+
+/// Width of the border drawn around the top face for the SCADA state (pixels)
+const SCADA_BORDER_WIDTH: f32 = 2.0;
+
+/// Border color of a building whose SCADA system is offline
+const SCADA_OFFLINE_COLOR: Color = Color::new(0.55, 0.55, 0.55, 0.9);
+
+/// Color a restored building flashes before fading back to normal
+const SCADA_RESTORED_COLOR: Color = Color::new(0.1, 1.0, 0.3, 1.0);
+
+/// Seconds the green flash of a restored building takes to fade out
+const SCADA_RESTORE_FLASH_DURATION: f32 = 1.0;
+
+/// Blink frequency of the warning icon above a compromised building (Hz)
+const WARNING_BLINK_FREQUENCY: f64 = 2.0;
+
+/// Size and spacing of the LED dots of the warning icon (pixels)
+const WARNING_DOT_SIZE: f32 = 2.0;
+const WARNING_DOT_PITCH: f32 = 2.5;
+
+/// Padding around the warning icon's dots and gap above the building top (pixels)
+const WARNING_ICON_PADDING: f32 = 3.0;
+const WARNING_ICON_GAP: f32 = 6.0;
+
+/// Warning icon dot and backing plate colors
+const WARNING_DOT_COLOR: Color = Color::new(1.0, 0.15, 0.1, 1.0);
+const WARNING_PLATE_COLOR: Color = Color::new(0.08, 0.08, 0.08, 0.9);
+
+/// Window size and horizontal spacing on the front face (pixels)
+const WINDOW_SIZE: f32 = 4.0;
+const WINDOW_SPACING: f32 = 9.0;
+
+/// Gap between the front face's side edges and the outer windows (pixels)
+const WINDOW_MARGIN: f32 = 5.0;
+
+/// Heights of the window rows, as fractions of the front face height
+const WINDOW_ROWS: [f32; 2] = [0.3, 0.7];
+
+/// Window colors with the lights on, in daylight, and with the lights off
+const WINDOW_LIT_COLOR: Color = Color::new(1.0, 0.88, 0.5, 1.0);
+const WINDOW_DAY_COLOR: Color = Color::new(0.55, 0.7, 0.85, 1.0);
+const WINDOW_DARK_COLOR: Color = Color::new(0.12, 0.14, 0.18, 1.0);
+
+/// Fraction of the windows that have gone dark by dawn
+const LATE_NIGHT_DARK_WINDOWS: f64 = 0.6;
+
 // ============================================================================
 // Color Manipulation Helpers
 // ============================================================================
 
-/// Darkens a color by a specified amount, clamping to prevent negative values
+/// Blends a color toward another
 ///
 /// # Arguments
 /// * `color` - The original color
-/// * `amount` - Amount to subtract from RGB channels (0.0-1.0)
+/// * `target` - Color to blend toward, e.g. RED
+/// * `amount` - Blend factor (0.0 = original color, 1.0 = `target`)
 ///
 /// # Returns
-/// A new color with darkened RGB values, alpha channel unchanged
-fn darken_color(color: Color, amount: f32) -> Color {
+/// The tinted color, alpha channel unchanged
+fn tint(color: Color, target: Color, amount: f32) -> Color {
     Color::new(
-        (color.r - amount).max(0.0),
-        (color.g - amount).max(0.0),
-        (color.b - amount).max(0.0),
+        color.r + (target.r - color.r) * amount,
+        color.g + (target.g - color.g) * amount,
+        color.b + (target.b - color.b) * amount,
         color.a,
     )
 }
 
-/// Lightens a color by a specified amount, clamping to prevent values > 1.0
-///
-/// # Arguments
-/// * `color` - The original color
-/// * `amount` - Amount to add to RGB channels (0.0-1.0)
-///
-/// # Returns
-/// A new color with lightened RGB values, alpha channel unchanged
-fn lighten_color(color: Color, amount: f32) -> Color {
-    Color::new(
-        (color.r + amount).min(1.0),
-        (color.g + amount).min(1.0),
-        (color.b + amount).min(1.0),
-        color.a,
-    )
+// ============================================================================
+// SCADA State
+// ============================================================================
+
+/// State of a building's SCADA system
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScadaState {
+    /// Working normally
+    #[default]
+    Normal,
+    /// Taken over by an attacker
+    Compromised,
+    /// Not responding, neither working nor compromised
+    Offline,
 }
 
 // ============================================================================
@@ -100,8 +160,20 @@ pub struct Building {
     /// Whether this building has SCADA control
     pub has_scada: bool,
 
-    /// Whether the SCADA system is broken (only relevant if has_scada is true)
-    pub scada_broken: bool,
+    /// State of the SCADA system (only relevant if has_scada is true)
+    pub scada_state: ScadaState,
+
+    /// Seconds left of the green flash after the SCADA system was restored
+    restore_flash: f32,
+
+    /// Team that compromised the SCADA system, if known
+    pub compromised_by: Option<Team>,
+
+    /// Whether the building has power (windows are dark during an outage)
+    pub powered: bool,
+
+    /// Whether the lights behind the windows are on, as they are at night
+    pub windows_lit: bool,
 }
 
 impl Building {
@@ -133,7 +205,11 @@ impl Building {
             corner_radius,
             color,
             has_scada: false,
-            scada_broken: false,
+            scada_state: ScadaState::Normal,
+            restore_flash: 0.0,
+            compromised_by: None,
+            powered: true,
+            windows_lit: false,
         }
     }
 
@@ -143,14 +219,67 @@ impl Building {
         self
     }
 
-    /// Sets the SCADA broken state
-    pub fn set_scada_broken(&mut self, broken: bool) {
-        self.scada_broken = broken;
+    /// Sets the state of the SCADA system
+    ///
+    /// Leaving the compromised state clears the compromising team, and a
+    /// compromised system restored to normal flashes green.
+    pub fn set_scada_state(&mut self, state: ScadaState) {
+        if self.scada_state == ScadaState::Compromised && state == ScadaState::Normal {
+            self.restore_flash = SCADA_RESTORE_FLASH_DURATION;
+        }
+        if state == ScadaState::Compromised {
+            self.restore_flash = 0.0;
+        } else {
+            self.compromised_by = None;
+        }
+        self.scada_state = state;
+    }
+
+    /// Sets the SCADA system compromised, or back to normal
+    pub fn set_scada_compromised(&mut self, compromised: bool) {
+        self.set_scada_state(if compromised {
+            ScadaState::Compromised
+        } else {
+            ScadaState::Normal
+        });
+    }
+
+    /// Marks the SCADA system as compromised by a team
+    pub fn compromise_scada(&mut self, team: &Team) {
+        self.set_scada_state(ScadaState::Compromised);
+        self.compromised_by = Some(team.clone());
+    }
+
+    /// Gets whether SCADA is compromised
+    pub fn is_scada_compromised(&self) -> bool {
+        self.has_scada && self.scada_state == ScadaState::Compromised
     }
 
-    /// Gets whether SCADA is broken
-    pub fn is_scada_broken(&self) -> bool {
-        self.has_scada && self.scada_broken
+    /// Strength of the green flash after a restore, from 1.0 right after it
+    /// down to 0.0 once faded
+    fn restore_flash_amount(&self) -> f32 {
+        if !self.has_scada {
+            return 0.0;
+        }
+        (self.restore_flash / SCADA_RESTORE_FLASH_DURATION).clamp(0.0, 1.0)
+    }
+
+    /// Turns the building's power off or back on
+    ///
+    /// A working SCADA system goes offline without power and comes back
+    /// when it returns. A compromised one stays compromised.
+    pub fn set_powered(&mut self, powered: bool) {
+        self.powered = powered;
+        match (powered, self.scada_state) {
+            (false, ScadaState::Normal) => self.set_scada_state(ScadaState::Offline),
+            (true, ScadaState::Offline) => self.set_scada_state(ScadaState::Normal),
+            _ => {}
+        }
+    }
+
+    /// Switches the lights behind the windows on or off
+    pub fn set_windows_lit(&mut self, lit: bool) {
+        self.windows_lit = lit;
     }
 
     /// Creates a Building object using the builder pattern
@@ -200,8 +329,8 @@ impl Building {
     }
 
     /// Renders the front face of the building
-    fn render_front_face(&self, params: &RenderParams, time: f64) {
-        let color = self.get_face_color_with_scada(BuildingFace::Front, time);
+    fn render_front_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color_with_scada(BuildingFace::Front, context);
 
         // Lower triangle
         draw_triangle(
@@ -238,9 +367,63 @@ impl Building {
         );
     }
 
+    /// Checks if a window's light is on
+    ///
+    /// All windows of a powered building light up at dusk; after midnight they go dark one by one
+    /// until `LATE_NIGHT_DARK_WINDOWS` of them are out by dawn.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the window on the front face
+    /// * `time_of_day` - Point of the day/night cycle (0.0 = midnight, 0.5 = noon)
+    fn window_lit(&self, index: usize, time_of_day: f64) -> bool {
+        if !self.windows_lit || !self.powered {
+            return false;
+        }
+        let bedtime = (index * 37 % 100) as f64 / 100.0;
+        time_of_day >= 0.25 || bedtime >= time_of_day * 4.0 * LATE_NIGHT_DARK_WINDOWS
+    }
+
+    /// Draws rows of windows on the front face
+    ///
+    /// Windows are lit while `windows_lit` is set and the building has power,
+    /// and dark during a power outage at any time of day. The front face leans
+    /// with the isometric projection, so each row is shifted left in
+    /// proportion to its height.
+    fn render_windows(&self, params: &RenderParams, context: &RenderContext) {
+        let unlit_color = if self.windows_lit || !self.powered {
+            WINDOW_DARK_COLOR
+        } else {
+            context.shade(WINDOW_DAY_COLOR)
+        };
+        let usable_width = params.width - 2.0 * WINDOW_MARGIN;
+        if usable_width < WINDOW_SIZE {
+            return;
+        }
+        let columns = ((usable_width - WINDOW_SIZE) / WINDOW_SPACING) as usize + 1;
+        let row_width = (columns - 1) as f32 * WINDOW_SPACING + WINDOW_SIZE;
+
+        let base_y = params.y + params.depth;
+        let face_height = params.y - params.y_top;
+        let lean = params.x - params.x_top;
+        for (row, fraction) in WINDOW_ROWS.into_iter().enumerate() {
+            let row_x = params.x - lean * fraction + (params.width - row_width) / 2.0;
+            let row_y = base_y - face_height * fraction - WINDOW_SIZE / 2.0;
+            for column in 0..columns {
+                let lit = self.window_lit(row * columns + column, context.time_of_day);
+                draw_rectangle(
+                    row_x + column as f32 * WINDOW_SPACING,
+                    row_y,
+                    WINDOW_SIZE,
+                    WINDOW_SIZE,
+                    if lit { WINDOW_LIT_COLOR } else { unlit_color },
+                );
+            }
+        }
+    }
+
     /// Renders the right side face of the building
-    fn render_side_face(&self, params: &RenderParams, time: f64) {
-        let color = self.get_face_color_with_scada(BuildingFace::Side, time);
+    fn render_side_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color_with_scada(BuildingFace::Side, context);
 
         // Back triangle
         draw_triangle(
@@ -278,8 +461,8 @@ impl Building {
     }
 
     /// Renders the top face of the building
-    fn render_top_face(&self, params: &RenderParams, time: f64) {
-        let color = self.get_face_color_with_scada(BuildingFace::Top, time);
+    fn render_top_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color_with_scada(BuildingFace::Top, context);
         draw_rounded_rectangle(
             params.x_top,
             params.y_top,
@@ -290,40 +473,120 @@ impl Building {
         );
     }
 
-    /// Gets the color for a face when SCADA is broken (flashing between original and red)
-    fn get_face_color_with_scada(&self, face: BuildingFace, time: f64) -> Color {
-        // DEBUG: Check if SCADA is broken
-        let is_broken = self.is_scada_broken();
+    /// Current strength of the pulse of a compromised building (0.0 to 1.0)
+    fn scada_pulse(time: f64) -> f32 {
+        let phase = time * SCADA_PULSE_FREQUENCY * std::f64::consts::TAU;
+        (phase.sin() * 0.5 + 0.5) as f32
+    }
+
+    /// Gets the color for a face, tinted red while SCADA is compromised
+    ///
+    /// The tint pulses over time so compromised buildings stand out. Just
+    /// after a restore the faces are tinted green instead, fading back to
+    /// their own color. The result is shaded for the ambient light.
+    fn get_face_color_with_scada(&self, face: BuildingFace, context: &RenderContext) -> Color {
+        let color = self.get_face_color(face);
+        if self.is_scada_compromised() {
+            let pulse = Self::scada_pulse(context.time);
+            let amount = SCADA_TINT_MIN + (SCADA_TINT_MAX - SCADA_TINT_MIN) * pulse;
+            return context.shade(tint(color, RED, amount));
+        }
+
+        let flash = self.restore_flash_amount();
+        context.shade(tint(color, SCADA_RESTORED_COLOR, flash * SCADA_TINT_MAX))
+    }
 
-        if !is_broken {
-            return self.get_face_color(face);
+    /// Draws a border around the top face showing the SCADA state
+    ///
+    /// Compromised buildings get a pulsing border in the attacking team's
+    /// color (red if the team is unknown), offline ones a gray one, and
+    /// restored ones a green border fading out with their flash.
+    fn render_scada_border(&self, params: &RenderParams, time: f64) {
+        if !self.has_scada {
+            return;
         }
 
-        // Flash at 1 Hz (1 flash per second) - slower and more visible
-        let flash_frequency = 1.0;
-        let flash_value = (time * flash_frequency * std::f64::consts::PI * 2.0).sin();
-
-        // When flash_value > 0, show red; when < 0, show original color
-        if flash_value > 0.0 {
-            // Bright red color, but keep the same shading for different faces
-            let base_red = Color::new(1.0, 0.0, 0.0, 1.0);
-            match face {
-                BuildingFace::Front => base_red,
-                BuildingFace::Side => darken_color(base_red, BUILDING_SIDE_DARKEN),
-                BuildingFace::Top => lighten_color(base_red, BUILDING_TOP_LIGHTEN),
+        let color = match self.scada_state {
+            ScadaState::Compromised => {
+                let alpha = 0.4 + 0.6 * Self::scada_pulse(time);
+                let color = self
+                    .compromised_by
+                    .as_ref()
+                    .map_or(Color::new(1.0, 0.1, 0.05, 1.0), |team| team.color);
+                Color { a: alpha, ..color }
             }
-        } else {
-            // Original color
-            self.get_face_color(face)
+            ScadaState::Offline => SCADA_OFFLINE_COLOR,
+            ScadaState::Normal => {
+                let flash = self.restore_flash_amount();
+                if flash <= 0.0 {
+                    return;
+                }
+                Color::new(
+                    SCADA_RESTORED_COLOR.r,
+                    SCADA_RESTORED_COLOR.g,
+                    SCADA_RESTORED_COLOR.b,
+                    flash,
+                )
+            }
+        };
+
+        draw_rectangle_lines(
+            params.x_top,
+            params.y_top,
+            params.width,
+            params.depth,
+            SCADA_BORDER_WIDTH,
+            color,
+        );
+    }
+
+    /// Draws a blinking LED exclamation mark above the building while SCADA
+    /// is compromised
+    fn render_warning_icon(&self, params: &RenderParams, time: f64) {
+        use crate::constants::led::{LED_CHAR_HEIGHT, LED_CHAR_WIDTH};
+
+        if !self.is_scada_compromised() {
+            return;
+        }
+
+        // Visible for the first half of each blink period
+        if (time * WARNING_BLINK_FREQUENCY).fract() >= 0.5 {
+            return;
         }
+
+        let dots_width = (LED_CHAR_WIDTH - 1) as f32 * WARNING_DOT_PITCH + WARNING_DOT_SIZE;
+        let dots_height = (LED_CHAR_HEIGHT - 1) as f32 * WARNING_DOT_PITCH + WARNING_DOT_SIZE;
+        let plate_width = dots_width + WARNING_ICON_PADDING * 2.0;
+        let plate_height = dots_height + WARNING_ICON_PADDING * 2.0;
+        let plate_x = params.x_top + (params.width - plate_width) / 2.0;
+        let plate_y = params.y_top - WARNING_ICON_GAP - plate_height;
+
+        draw_rectangle(plate_x, plate_y, plate_width, plate_height, WARNING_PLATE_COLOR);
+        draw_rectangle_lines(plate_x, plate_y, plate_width, plate_height, 1.0, WARNING_DOT_COLOR);
+        draw_led_char(
+            '!',
+            plate_x + WARNING_ICON_PADDING,
+            plate_y + WARNING_ICON_PADDING,
+            WARNING_DOT_SIZE,
+            WARNING_DOT_PITCH,
+            WARNING_DOT_COLOR,
+        );
     }
 }
 
 impl BlockObject for Building {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
 
+    fn update(&mut self, dt: f32) {
+        self.restore_flash = (self.restore_flash - dt).max(0.0);
+    }
+
     fn render(&self, block: &Block, context: &RenderContext) {
         // Get block position and size in pixels
         let block_x = block.x();
@@ -352,10 +615,13 @@ impl BlockObject for Building {
             depth,
         };
 
-        // Render all three visible faces (with SCADA flashing if broken)
-        self.render_front_face(&params, context.time);
-        self.render_side_face(&params, context.time);
-        self.render_top_face(&params, context.time);
+        // Render all three visible faces (red-tinted if SCADA is compromised)
+        self.render_front_face(&params, context);
+        self.render_windows(&params, context);
+        self.render_side_face(&params, context);
+        self.render_top_face(&params, context);
+        self.render_scada_border(&params, context.time);
+        self.render_warning_icon(&params, context.time);
     }
 }
 
@@ -397,7 +663,7 @@ pub struct BuildingBuilder {
     corner_radius: Option<f32>,
     color: Option<Color>,
     has_scada: Option<bool>,
-    scada_broken: Option<bool>,
+    scada_state: Option<ScadaState>,
 }
 
 impl BuildingBuilder {
@@ -412,7 +678,7 @@ impl BuildingBuilder {
             corner_radius: None,
             color: None,
             has_scada: None,
-            scada_broken: None,
+            scada_state: None,
         }
     }
 
@@ -471,9 +737,19 @@ impl BuildingBuilder {
         self
     }
 
-    /// Sets whether the SCADA is broken
-    pub fn scada_broken(mut self, broken: bool) -> Self {
-        self.scada_broken = Some(broken);
+    /// Sets whether the SCADA is compromised
+    pub fn scada_compromised(mut self, compromised: bool) -> Self {
+        self.scada_state = Some(if compromised {
+            ScadaState::Compromised
+        } else {
+            ScadaState::Normal
+        });
+        self
+    }
+
+    /// Sets the state of the SCADA system
+    pub fn scada_state(mut self, state: ScadaState) -> Self {
+        self.scada_state = Some(state);
         self
     }
 
@@ -488,7 +764,7 @@ impl BuildingBuilder {
     /// - corner_radius: 8.0 (8 pixel corner radius)
     /// - color: Gray (0.6, 0.6, 0.6, 1.0)
     /// - has_scada: false
-    /// - scada_broken: false
+    /// - scada_state: Normal
     pub fn build(self) -> Building {
         Building {
             x_offset_percent: self.x_offset_percent.unwrap_or(0.0),
@@ -499,7 +775,57 @@ impl BuildingBuilder {
             corner_radius: self.corner_radius.unwrap_or(BUILDING_CORNER_RADIUS),
             color: self.color.unwrap_or(Color::new(0.6, 0.6, 0.6, 1.0)),
             has_scada: self.has_scada.unwrap_or(false),
-            scada_broken: self.scada_broken.unwrap_or(false),
+            scada_state: self.scada_state.unwrap_or_default(),
+            restore_flash: 0.0,
+            compromised_by: None,
+            powered: true,
+            windows_lit: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restored_scada_flashes_green_then_fades() {
+        let mut building = Building::builder().has_scada(true).build();
+        building.compromise_scada(crate::team::TeamRegistry::new().get_or_register("Red Team"));
+        assert!(building.is_scada_compromised());
+        assert_eq!(building.restore_flash_amount(), 0.0);
+
+        building.set_scada_state(ScadaState::Normal);
+        assert_eq!(building.compromised_by, None);
+        assert_eq!(building.restore_flash_amount(), 1.0);
+
+        building.update(SCADA_RESTORE_FLASH_DURATION / 2.0);
+        assert!((building.restore_flash_amount() - 0.5).abs() < 1e-6);
+        building.update(SCADA_RESTORE_FLASH_DURATION);
+        assert_eq!(building.restore_flash_amount(), 0.0);
+
+        // Only a compromised system flashes when it comes back
+        building.set_powered(false);
+        assert_eq!(building.scada_state, ScadaState::Offline);
+        building.set_powered(true);
+        assert_eq!(building.scada_state, ScadaState::Normal);
+        assert_eq!(building.restore_flash_amount(), 0.0);
+    }
+
+    #[test]
+    fn test_windows_go_dark_after_midnight() {
+        let mut building = Building::builder().build();
+        assert!(!building.window_lit(0, 0.9));
+
+        building.set_windows_lit(true);
+        let lit = |building: &Building, time_of_day| {
+            (0..100).filter(|&index| building.window_lit(index, time_of_day)).count()
+        };
+        assert_eq!(lit(&building, 0.9), 100);
+        assert_eq!(lit(&building, 0.0), 100);
+        assert_eq!(lit(&building, 0.125), 70);
+
+        building.set_powered(false);
+        assert_eq!(lit(&building, 0.9), 0);
+    }
+}
@@ -0,0 +1,600 @@
+//! Guarded compound block object implementation
+//!
+//! Provides a fenced compound with a sliding gate on its bottom side, facing
+//! the road below the block, a guard booth beside the gate and a row of
+//! parking spots inside. Visiting cars stop at the gate, are checked by the
+//! guard while the gate slides open, drive in and park. After a while they
+//! drive back out and join the road in front of the gate.
+//!
+//! The compound takes cars off the road while they are inside and puts them
+//! back when they leave, so the traffic simulation never sees parked cars.
+
+use super::barrier::BarrierState;
+use crate::block::{Block, BlockObject, Fence, RenderContext};
+use crate::car::{arrived_at_gate, join_road, lane_at_gate, Gate};
+use crate::constants::vehicle::QUEUED_SPEED;
+use crate::constants::visual::ROAD_WIDTH;
+use crate::models::{Car, CarLocation, Direction};
+use crate::rendering::draw_car;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Compound Layout Constants
+// ============================================================================
+
+/// Gate opening along the bottom fence, as percentages of block width
+const GATE_START: f32 = 0.40;
+const GATE_END: f32 = 0.60;
+
+/// Center of the gate, where cars drive in and out
+const GATE_X: f32 = (GATE_START + GATE_END) / 2.0;
+
+/// Fence line offset and depth as percentages of block size
+const FENCE_INSET: f32 = 0.10;
+const FENCE_DEPTH: f32 = 0.01;
+
+/// Fence height in pixels
+const FENCE_HEIGHT: f32 = 6.0;
+
+/// Parking spot centers as percentages of block width; the compound takes
+/// at most this many cars
+const PARKING_SPOTS: [f32; 4] = [0.16, 0.27, 0.73, 0.84];
+
+/// Width of a parking spot as a percentage of block width
+const SPOT_WIDTH: f32 = 0.11;
+
+/// Vertical position of the parking spots' centers, and half their length,
+/// as percentages of block height
+const PARKING_ROW: f32 = 0.76;
+const SPOT_HALF_LENGTH: f32 = 0.08;
+
+/// Vertical position of the lane cars drive along between the gate and the
+/// parking spots, as a percentage of block height
+const AISLE: f32 = 0.63;
+
+/// Point just outside the gate where leaving cars wait for a gap in traffic,
+/// as a percentage of block height
+const DRIVEWAY: f32 = 0.97;
+
+/// Guard booth position and size as percentages of block size
+const BOOTH_X: f32 = 0.33;
+const BOOTH_Y: f32 = 0.78;
+const BOOTH_WIDTH: f32 = 0.06;
+const BOOTH_DEPTH: f32 = 0.10;
+
+/// Speed of cars inside the compound (pixels per second)
+const COMPOUND_SPEED: f32 = 25.0;
+
+/// Time in seconds for the gate to slide between closed and open
+const GATE_SLIDE_DURATION: f32 = 1.5;
+
+/// Default time in seconds the guard checks each arriving car
+const DEFAULT_CHECK_TIME: f32 = 2.0;
+
+/// Shortest and longest time in seconds a visitor stays parked
+const MIN_PARK_TIME: f32 = 10.0;
+const MAX_PARK_TIME: f32 = 25.0;
+
+/// Distance in pixels a moving car must be from the gate for a leaving car
+/// to pull out in front of it
+const EXIT_CLEARANCE: f32 = 80.0;
+
+/// Space in pixels kept between a leaving car and cars standing in its lane
+const EXIT_MARGIN: f32 = 4.0;
+
+/// Gate height in pixels and spacing of its vertical bars
+const GATE_HEIGHT: f32 = 8.0;
+const GATE_BAR_SPACING: f32 = 4.0;
+
+const GATE_COLOR: Color = Color::new(0.85, 0.7, 0.1, 1.0);
+const BOOTH_COLOR: Color = Color::new(0.75, 0.75, 0.7, 1.0);
+const BOOTH_ROOF_COLOR: Color = Color::new(0.3, 0.35, 0.45, 1.0);
+const BOOTH_WINDOW_COLOR: Color = Color::new(0.55, 0.75, 0.9, 1.0);
+const BOOTH_WINDOW_LIT_COLOR: Color = Color::new(1.0, 0.9, 0.5, 1.0);
+const SPOT_LINE_COLOR: Color = Color::new(0.9, 0.9, 0.9, 0.8);
+
+// ============================================================================
+// Visitors
+// ============================================================================
+
+/// What a car inside the compound is doing
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Stay {
+    /// Driving from the gate to its parking spot
+    Entering,
+    /// Parked, with the seconds left until it leaves
+    Parked(f32),
+    /// Driving from its spot to just outside the gate
+    Leaving,
+    /// Pulling out onto the road, to drive off in the given direction
+    Exiting(Direction),
+}
+
+/// A car inside the compound
+struct Visitor {
+    car: Car,
+
+    /// Index into `PARKING_SPOTS`
+    spot: usize,
+
+    /// Points still to drive through, as percentages of block size
+    path: Vec<Vec2>,
+
+    stay: Stay,
+}
+
+impl Visitor {
+    /// Moves the car along its path for one frame
+    ///
+    /// Cars drive straight from point to point, facing the way they move.
+    fn drive(&mut self, area: Rect, dt: f32) {
+        let Some(&next) = self.path.first() else {
+            self.car.velocity = 0.0;
+            return;
+        };
+
+        let target = area.point() + next * area.size();
+        let position = vec2(self.car.x(), self.car.y());
+        let offset = target - position;
+        let step = COMPOUND_SPEED * dt;
+
+        if offset.length() <= step {
+            self.car.set_x(target.x);
+            self.car.set_y(target.y);
+            self.path.remove(0);
+        } else {
+            let moved = position + offset.normalize() * step;
+            self.car.set_x(moved.x);
+            self.car.set_y(moved.y);
+        }
+
+        if offset.x.abs() > offset.y.abs() {
+            self.car.direction = if offset.x > 0.0 { Direction::Right } else { Direction::Left };
+        } else if offset.y != 0.0 {
+            self.car.direction = if offset.y > 0.0 { Direction::Down } else { Direction::Up };
+        }
+        self.car.velocity = COMPOUND_SPEED;
+    }
+}
+
+/// A car being checked at the gate
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Check {
+    car_id: usize,
+
+    /// Seconds until the guard is done
+    remaining: f32,
+}
+
+// ============================================================================
+// Guarded Compound Object Implementation
+// ============================================================================
+
+/// A fenced compound with a guarded gate and parking spots for visitors
+///
+/// The gate is in the middle of the bottom fence and opens onto the road
+/// below the block. The compound lets visitors in through `serve`, which the
+/// city calls every frame after moving the cars on the roads.
+pub struct GuardedCompound {
+    /// Fence around the compound, with a gap for the gate
+    fences: Vec<Fence>,
+
+    /// Position of the sliding gate; `Open` is fully slid aside
+    gate: BarrierState,
+
+    /// Number of cars the compound takes at once (1 to `PARKING_SPOTS.len()`)
+    pub capacity: usize,
+
+    /// Time in seconds the guard checks each arriving car before letting
+    /// it in
+    pub check_time: f32,
+
+    visitors: Vec<Visitor>,
+    check: Option<Check>,
+}
+
+impl GuardedCompound {
+    /// Creates an empty compound with a closed gate
+    ///
+    /// # Arguments
+    /// * `fence_color` - Color of the fence around the compound
+    pub fn new(fence_color: Color) -> Self {
+        let far = 1.0 - FENCE_INSET - FENCE_DEPTH;
+        let side_length = far - FENCE_INSET - FENCE_DEPTH;
+        let fence = |x, y, width, depth| Fence::new(x, y, width, depth, FENCE_HEIGHT, fence_color);
+
+        Self {
+            fences: vec![
+                // Top, left and right sides
+                fence(FENCE_INSET, FENCE_INSET, far + FENCE_DEPTH - FENCE_INSET, FENCE_DEPTH),
+                fence(FENCE_INSET, FENCE_INSET + FENCE_DEPTH, FENCE_DEPTH, side_length),
+                fence(far, FENCE_INSET + FENCE_DEPTH, FENCE_DEPTH, side_length),
+                // Bottom side, on either side of the gate
+                fence(FENCE_INSET, far, GATE_START - FENCE_INSET, FENCE_DEPTH),
+                fence(GATE_END, far, far + FENCE_DEPTH - GATE_END, FENCE_DEPTH),
+            ],
+            gate: BarrierState::Closed,
+            capacity: PARKING_SPOTS.len(),
+            check_time: DEFAULT_CHECK_TIME,
+            visitors: Vec::new(),
+            check: None,
+        }
+    }
+
+    /// Sets how many cars the compound takes at once, at most one per
+    /// parking spot
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.clamp(1, PARKING_SPOTS.len());
+        self
+    }
+
+    /// Sets how long the guard checks each arriving car, in seconds
+    pub fn with_check_time(mut self, seconds: f32) -> Self {
+        self.check_time = seconds.max(0.0);
+        self
+    }
+
+    /// Returns true if arriving cars must wait: every spot is taken, or a
+    /// car is on its way out through the gate
+    pub fn is_full(&self) -> bool {
+        self.visitors.len() >= self.capacity
+            || self
+                .visitors
+                .iter()
+                .any(|visitor| matches!(visitor.stay, Stay::Leaving | Stay::Exiting(_)))
+    }
+
+    /// The compound's gate, as seen by cars on the road
+    ///
+    /// # Arguments
+    /// * `block` - The block containing this compound
+    ///
+    /// # Returns
+    /// The gate, or None if no road runs along the bottom of the block
+    pub fn gate(&self, block: &Block) -> Option<Gate> {
+        let road_index = block.get_adjacent_road(Direction::Down)?;
+        Some(Gate {
+            block_id: block.id,
+            road_index,
+            position: vec2(
+                block.x() + GATE_X * block.width(),
+                block.y() + block.height() + ROAD_WIDTH / 2.0,
+            ),
+            inward: Direction::Up,
+            full: self.is_full(),
+            admitting: self.check.map(|check| check.car_id),
+        })
+    }
+
+    /// Lets cars in and out of the compound for one frame
+    ///
+    /// Checks the car waiting at the gate and takes it off the road once the
+    /// gate is open, drives the cars inside, and puts cars done visiting back
+    /// on the road when their lane is clear.
+    ///
+    /// # Arguments
+    /// * `gate` - This compound's gate, from `gate`
+    /// * `area` - The block's area in pixels
+    /// * `cars` - The cars on the roads
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn serve(&mut self, gate: &Gate, area: Rect, cars: &mut Vec<Car>, dt: f32) {
+        self.admit(gate, area, cars, dt);
+
+        // Parked cars whose time is up leave one at a time, while nobody else
+        // uses the gate
+        let gate_idle = self.check.is_none()
+            && self.visitors.iter().all(|visitor| matches!(visitor.stay, Stay::Parked(_)));
+        for visitor in &mut self.visitors {
+            if let Stay::Parked(remaining) = &mut visitor.stay {
+                *remaining -= dt;
+            }
+        }
+        if gate_idle
+            && let Some(visitor) = self
+                .visitors
+                .iter_mut()
+                .find(|visitor| matches!(visitor.stay, Stay::Parked(remaining) if remaining <= 0.0))
+        {
+            let spot_x = PARKING_SPOTS[visitor.spot];
+            visitor.path = vec![vec2(spot_x, AISLE), vec2(GATE_X, AISLE), vec2(GATE_X, DRIVEWAY)];
+            visitor.stay = Stay::Leaving;
+        }
+
+        for visitor in &mut self.visitors {
+            visitor.drive(area, dt);
+        }
+
+        self.depart(gate, area, cars);
+
+        // The gate stays open while a car is checked or drives through it
+        let busy = self.check.is_some()
+            || self.visitors.iter().any(|visitor| !matches!(visitor.stay, Stay::Parked(_)));
+        self.gate = self.gate.toward(busy);
+    }
+
+    /// Checks the car at the gate and takes it in once it may enter
+    fn admit(&mut self, gate: &Gate, area: Rect, cars: &mut Vec<Car>, dt: f32) {
+        let Some(check) = &mut self.check else {
+            if !self.is_full()
+                && let Some(car) = cars.iter().find(|car| arrived_at_gate(car, gate))
+            {
+                self.check = Some(Check {
+                    car_id: car.id,
+                    remaining: self.check_time,
+                });
+            }
+            return;
+        };
+
+        check.remaining -= dt;
+        if check.remaining > 0.0 || self.gate != BarrierState::Open {
+            return;
+        }
+        let car_id = check.car_id;
+        self.check = None;
+
+        // The car may have been removed from the road meanwhile
+        let Some(index) = cars.iter().position(|car| car.id == car_id) else {
+            return;
+        };
+        let mut car = cars.remove(index);
+        car.location = CarLocation::InBlock { block_id: gate.block_id };
+        car.pull_over = 0.0;
+        car.next_turn = None;
+
+        let spot = (0..self.capacity)
+            .find(|&spot| self.visitors.iter().all(|visitor| visitor.spot != spot))
+            .unwrap_or(0);
+        let spot_x = PARKING_SPOTS[spot];
+        let lane_y = (car.y() - area.y) / area.h;
+        self.visitors.push(Visitor {
+            car,
+            spot,
+            path: vec![
+                vec2(GATE_X, lane_y),
+                vec2(GATE_X, AISLE),
+                vec2(spot_x, AISLE),
+                vec2(spot_x, PARKING_ROW),
+            ],
+            stay: Stay::Entering,
+        });
+    }
+
+    /// Parks cars that reached their spot and moves leaving cars onto the road
+    fn depart(&mut self, gate: &Gate, area: Rect, cars: &mut Vec<Car>) {
+        let mut index = 0;
+        while index < self.visitors.len() {
+            let visitor = &mut self.visitors[index];
+            if !visitor.path.is_empty() {
+                index += 1;
+                continue;
+            }
+
+            match visitor.stay {
+                Stay::Entering => {
+                    visitor.stay = Stay::Parked(rand::gen_range(MIN_PARK_TIME, MAX_PARK_TIME));
+                }
+                Stay::Leaving if self.gate == BarrierState::Open => {
+                    // Pull out toward whichever side traffic leaves room on
+                    let mut directions = [Direction::Left, Direction::Right];
+                    if rand::gen_range(0, 2) == 0 {
+                        directions.reverse();
+                    }
+                    if let Some(&direction) = directions
+                        .iter()
+                        .find(|&&direction| lane_clear(gate, direction, &visitor.car, cars))
+                    {
+                        let lane = lane_at_gate(gate, direction, visitor.car.traffic_rule);
+                        let lane = (lane - area.point()) / area.size();
+                        visitor.path.push(lane);
+                        visitor.stay = Stay::Exiting(direction);
+                    }
+                }
+                Stay::Exiting(direction) => {
+                    let mut visitor = self.visitors.remove(index);
+                    join_road(&mut visitor.car, gate, direction);
+                    cars.push(visitor.car);
+                    continue;
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Checks if a car leaving through a gate can pull into a lane
+///
+/// The lane must have no car where the leaving car would stand, and no
+/// moving car about to reach that point.
+///
+/// # Arguments
+/// * `gate` - The gate the car leaves through
+/// * `direction` - Direction the car would drive off in
+/// * `leaving` - The car leaving
+/// * `cars` - The cars on the roads
+fn lane_clear(gate: &Gate, direction: Direction, leaving: &Car, cars: &[Car]) -> bool {
+    let lane = lane_at_gate(gate, direction, leaving.traffic_rule);
+    let (dx, dy) = direction.to_vector();
+    let forward = vec2(dx, dy);
+
+    cars.iter().filter(|car| car.direction == direction).all(|car| {
+        let offset = lane - vec2(car.x(), car.y());
+        if offset.perp_dot(forward).abs() >= ROAD_WIDTH / 4.0 {
+            return true;
+        }
+        let ahead = offset.dot(forward);
+        let overlap = (car.length() + leaving.length()) / 2.0 + EXIT_MARGIN;
+        let approaching = car.velocity >= QUEUED_SPEED && ahead > 0.0 && ahead < EXIT_CLEARANCE;
+        ahead.abs() >= overlap && !approaching
+    })
+}
+
+impl BlockObject for GuardedCompound {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.gate = self.gate.advance(dt / GATE_SLIDE_DURATION);
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let (bx, by, bw, bh) = (block.x(), block.y(), block.width(), block.height());
+
+        // Parking spot markings
+        let line_color = context.shade(SPOT_LINE_COLOR);
+        for &spot_x in &PARKING_SPOTS[..self.capacity] {
+            for edge in [spot_x - SPOT_WIDTH / 2.0, spot_x + SPOT_WIDTH / 2.0] {
+                draw_line(
+                    bx + edge * bw,
+                    by + (PARKING_ROW - SPOT_HALF_LENGTH) * bh,
+                    bx + edge * bw,
+                    by + (PARKING_ROW + SPOT_HALF_LENGTH) * bh,
+                    1.0,
+                    line_color,
+                );
+            }
+        }
+
+        for visitor in &self.visitors {
+            draw_car(&visitor.car, context.time);
+        }
+
+        for fence in &self.fences {
+            fence.render(block, context);
+        }
+
+        // Gate panel, sliding to the left behind the fence as it opens
+        let gate_x = bx + GATE_START * bw;
+        let gate_width = (GATE_END - GATE_START) * bw * (1.0 - self.gate.openness());
+        let gate_bottom = by + (1.0 - FENCE_INSET) * bh;
+        let gate_color = context.shade(GATE_COLOR);
+        if gate_width > 0.0 {
+            draw_rectangle(gate_x, gate_bottom - GATE_HEIGHT, gate_width, 2.0, gate_color);
+            draw_rectangle(gate_x, gate_bottom - 2.0, gate_width, 2.0, gate_color);
+            let mut bar_x = gate_x + gate_width;
+            while bar_x > gate_x {
+                draw_line(bar_x, gate_bottom - GATE_HEIGHT, bar_x, gate_bottom, 1.0, gate_color);
+                bar_x -= GATE_BAR_SPACING;
+            }
+        }
+
+        // Guard booth beside the gate, with its window lit at night
+        let (booth_x, booth_y) = (bx + BOOTH_X * bw, by + BOOTH_Y * bh);
+        let (booth_width, booth_depth) = (BOOTH_WIDTH * bw, BOOTH_DEPTH * bh);
+        draw_rectangle(booth_x, booth_y, booth_width, booth_depth, context.shade(BOOTH_COLOR));
+        draw_rectangle(
+            booth_x - 1.0,
+            booth_y - 1.0,
+            booth_width + 2.0,
+            booth_depth / 3.0,
+            context.shade(BOOTH_ROOF_COLOR),
+        );
+        let window_color = if context.ambient < 0.5 {
+            BOOTH_WINDOW_LIT_COLOR
+        } else {
+            context.shade(BOOTH_WINDOW_COLOR)
+        };
+        draw_rectangle(
+            booth_x + booth_width - 3.0,
+            booth_y + booth_depth / 2.0,
+            2.0,
+            booth_depth / 3.0,
+            window_color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TrafficRule, VehicleStyle, VehicleType};
+
+    /// Compound in a block like block 8 on the 800x600 test screen, with the
+    /// horizontal road 4 below it, and its gate
+    fn compound_block() -> Block {
+        let mut block = Block::new(430.0 / 800.0, 180.0 / 600.0, 220.0 / 800.0, 240.0 / 600.0, 8);
+        block.connect_road(Direction::Down, 4);
+        block
+    }
+
+    /// Visitor for block 8 standing at `gate`
+    fn visitor_at(gate: &Gate) -> Car {
+        let lane = lane_at_gate(gate, Direction::Left, TrafficRule::LeftHand);
+        Car {
+            id: 7,
+            x_percent: lane.x / 800.0,
+            y_percent: lane.y / 600.0,
+            direction: Direction::Left,
+            velocity: 0.0,
+            target_velocity: 0.0,
+            vehicle_type: VehicleType::Car,
+            style: VehicleStyle::Sedan,
+            color: RED,
+            road_index: 4,
+            next_turn: None,
+            just_turned: false,
+            in_intersection: false,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
+            location: CarLocation::OnRoad { road_id: 4 },
+            destination: Some(8),
+            route: Vec::new(),
+            traffic_rule: TrafficRule::LeftHand,
+        }
+    }
+
+    /// Runs the compound for `seconds` at 60 frames per second
+    fn run(compound: &mut GuardedCompound, block: &Block, cars: &mut Vec<Car>, seconds: f32) {
+        let area = Rect::new(block.x(), block.y(), block.width(), block.height());
+        for _ in 0..(seconds * 60.0) as usize {
+            compound.update(1.0 / 60.0);
+            let gate = compound.gate(block).unwrap();
+            compound.serve(&gate, area, cars, 1.0 / 60.0);
+        }
+    }
+
+    #[test]
+    fn test_visitor_is_checked_parks_and_leaves() {
+        let block = compound_block();
+        let area = Rect::new(block.x(), block.y(), block.width(), block.height());
+        let mut compound = GuardedCompound::new(BROWN).with_capacity(1).with_check_time(3.0);
+        let gate = compound.gate(&block).unwrap();
+        assert_eq!(gate.position, vec2(540.0, 450.0));
+        let mut cars = vec![visitor_at(&gate)];
+
+        // Held at the gate while the guard checks it
+        run(&mut compound, &block, &mut cars, 2.5);
+        assert_eq!(cars.len(), 1);
+        assert_eq!(compound.gate(&block).unwrap().admitting, Some(7));
+        assert!(compound.gate.openness() > 0.0, "gate opens during the check");
+
+        // Let in and driven to the one parking spot, filling the compound
+        run(&mut compound, &block, &mut cars, 10.0);
+        assert!(cars.is_empty());
+        assert!(compound.is_full());
+        let parked = &compound.visitors[0];
+        assert!(matches!(parked.stay, Stay::Parked(_)));
+        assert!(matches!(parked.car.location, CarLocation::InBlock { block_id: 8 }));
+        let spot = area.point() + vec2(PARKING_SPOTS[0], PARKING_ROW) * area.size();
+        assert!(vec2(parked.car.x(), parked.car.y()).distance(spot) < 0.01);
+
+        // Done visiting, it drives back out onto the road, just passing through
+        compound.visitors[0].stay = Stay::Parked(0.0);
+        run(&mut compound, &block, &mut cars, 15.0);
+        assert!(compound.visitors.is_empty());
+        assert!(!compound.is_full());
+        assert_eq!(compound.gate, BarrierState::Closed);
+        let car = &cars[0];
+        assert_eq!(car.destination, None);
+        assert!(matches!(car.location, CarLocation::OnRoad { road_id: 4 }));
+        let lane = lane_at_gate(&gate, car.direction, car.traffic_rule);
+        assert!(vec2(car.x(), car.y()).distance(lane) < 0.01);
+    }
+}
@@ -0,0 +1,124 @@
+//! Traffic cone block object implementation
+//!
+//! Provides the orange traffic cones lining a closed road. The cones stand
+//! in a row along the block edge facing the road, and remember which road
+//! they close so they can be cleared when it reopens.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use crate::models::Direction;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Cone Rendering Constants
+// ============================================================================
+
+/// Cone size in pixels
+const CONE_WIDTH: f32 = 7.0;
+const CONE_HEIGHT: f32 = 10.0;
+
+/// Distance between neighboring cones in pixels
+const CONE_SPACING: f32 = 30.0;
+
+/// Distance of the cone centers from the block edge in pixels
+const CONE_INSET: f32 = 6.0;
+
+const CONE_ORANGE: Color = Color::new(1.0, 0.5, 0.05, 1.0);
+const CONE_STRIPE: Color = Color::new(0.95, 0.95, 0.95, 1.0);
+const CONE_BASE: Color = Color::new(0.25, 0.25, 0.25, 1.0);
+
+// ============================================================================
+// Cone Object Implementation
+// ============================================================================
+
+/// A traffic cone at the edge of a block, closing the road beside it
+pub struct TrafficCone {
+    /// Horizontal offset of the cone's base as percentage of block width
+    pub x_offset_percent: f32,
+
+    /// Vertical offset of the cone's base as percentage of block height
+    pub y_offset_percent: f32,
+
+    /// ID of the road the cone closes
+    pub road_id: usize,
+}
+
+impl TrafficCone {
+    /// Creates a row of cones along one side of a block, evenly spaced
+    ///
+    /// # Arguments
+    /// * `block` - Block to place the cones in
+    /// * `side` - Side of the block the closed road runs along
+    /// * `road_id` - ID of the closed road
+    ///
+    /// # Returns
+    /// At least one cone, more the longer the side is
+    pub fn row_along(block: &Block, side: Direction, road_id: usize) -> Vec<Self> {
+        let vertical_side = matches!(side, Direction::Left | Direction::Right);
+        let (length, depth) = if vertical_side {
+            (block.height(), block.width())
+        } else {
+            (block.width(), block.height())
+        };
+        let inset = (CONE_INSET / depth).min(0.5);
+        let across = match side {
+            Direction::Left | Direction::Up => inset,
+            Direction::Right | Direction::Down => 1.0 - inset,
+        };
+
+        let count = ((length / CONE_SPACING) as usize).max(1);
+        (0..count)
+            .map(|i| {
+                let along = (i as f32 + 0.5) / count as f32;
+                let (x_offset_percent, y_offset_percent) =
+                    if vertical_side { (across, along) } else { (along, across) };
+                Self {
+                    x_offset_percent,
+                    y_offset_percent,
+                    road_id,
+                }
+            })
+            .collect()
+    }
+}
+
+impl BlockObject for TrafficCone {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let x = block.x() + self.x_offset_percent * block.width();
+        let base_y = block.y() + self.y_offset_percent * block.height() + CONE_HEIGHT / 2.0;
+        let half_width = CONE_WIDTH / 2.0;
+
+        draw_rectangle(
+            x - half_width - 1.0,
+            base_y - 1.5,
+            CONE_WIDTH + 2.0,
+            2.0,
+            context.shade(CONE_BASE),
+        );
+        draw_triangle(
+            vec2(x - half_width, base_y - 1.5),
+            vec2(x + half_width, base_y - 1.5),
+            vec2(x, base_y - CONE_HEIGHT),
+            context.shade(CONE_ORANGE),
+        );
+
+        // Reflective band around the middle of the cone
+        let band_y = base_y - CONE_HEIGHT * 0.55;
+        let band_half_width = half_width * 0.5;
+        draw_line(
+            x - band_half_width,
+            band_y,
+            x + band_half_width,
+            band_y,
+            1.5,
+            context.shade(CONE_STRIPE),
+        );
+    }
+}
@@ -3,8 +3,8 @@
 //! Provides an isometric 3D fence that can be placed around areas in blocks.
 
 use crate::block::{Block, BlockObject, RenderContext};
+use crate::rendering::{darken_color, lighten_color};
 use macroquad::prelude::*;
-use std::cell::RefCell;
 
 // ============================================================================
 // Fence Rendering Constants
@@ -25,30 +25,6 @@ const FENCE_TOP_LIGHTEN: f32 = 0.1;
 /// Default fence color (brown)
 const DEFAULT_FENCE_COLOR: Color = Color::new(0.4, 0.3, 0.2, 1.0);
 
-// ============================================================================
-// Color Manipulation Helpers
-// ============================================================================
-
-/// Darkens a color by a specified amount, clamping to prevent negative values
-fn darken_color(color: Color, amount: f32) -> Color {
-    Color::new(
-        (color.r - amount).max(0.0),
-        (color.g - amount).max(0.0),
-        (color.b - amount).max(0.0),
-        color.a,
-    )
-}
-
-/// Lightens a color by a specified amount, clamping to prevent values > 1.0
-fn lighten_color(color: Color, amount: f32) -> Color {
-    Color::new(
-        (color.r + amount).min(1.0),
-        (color.g + amount).min(1.0),
-        (color.b + amount).min(1.0),
-        color.a,
-    )
-}
-
 // ============================================================================
 // Fence Object Implementation
 // ============================================================================
@@ -74,16 +50,6 @@ pub struct Fence {
 
     /// Fence color
     pub color: Color,
-
-    /// Whether this fence has a barrier gate
-    pub has_barrier: bool,
-
-    /// Position of barrier gate along the fence (0.0 = start, 1.0 = end)
-    pub barrier_position: f32,
-
-    /// Current animated angle for barrier boom arm (0° = closed/horizontal, 85° = open/vertical)
-    /// Uses RefCell for interior mutability during rendering
-    current_angle: RefCell<f32>,
 }
 
 impl Fence {
@@ -103,19 +69,9 @@ impl Fence {
             depth_percent,
             height_pixels,
             color,
-            has_barrier: false,
-            barrier_position: 0.5, // Default to center
-            current_angle: RefCell::new(0.0), // Start closed
         }
     }
 
-    /// Adds an animated barrier gate to this fence
-    pub fn with_barrier(mut self, position: f32) -> Self {
-        self.has_barrier = true;
-        self.barrier_position = position;
-        self
-    }
-
     /// Creates a Fence object using the builder pattern
     pub fn builder() -> FenceBuilder {
         FenceBuilder::new()
@@ -129,18 +85,18 @@ impl Fence {
         )
     }
 
-    /// Gets the color for a specific face of the fence
-    fn get_face_color(&self, face: FenceFace) -> Color {
-        match face {
+    /// Gets the color for a specific face of the fence, shaded for the ambient light
+    fn get_face_color(&self, face: FenceFace, context: &RenderContext) -> Color {
+        context.shade(match face {
             FenceFace::Front => self.color,
             FenceFace::Side => darken_color(self.color, FENCE_SIDE_DARKEN),
             FenceFace::Top => lighten_color(self.color, FENCE_TOP_LIGHTEN),
-        }
+        })
     }
 
     /// Renders the front face of the fence
-    fn render_front_face(&self, params: &RenderParams) {
-        let color = self.get_face_color(FenceFace::Front);
+    fn render_front_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color(FenceFace::Front, context);
 
         // Lower triangle
         draw_triangle(
@@ -178,8 +134,8 @@ impl Fence {
     }
 
     /// Renders the right side face of the fence
-    fn render_side_face(&self, params: &RenderParams) {
-        let color = self.get_face_color(FenceFace::Side);
+    fn render_side_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color(FenceFace::Side, context);
 
         // Back triangle
         draw_triangle(
@@ -217,8 +173,8 @@ impl Fence {
     }
 
     /// Renders the top face of the fence
-    fn render_top_face(&self, params: &RenderParams) {
-        let color = self.get_face_color(FenceFace::Top);
+    fn render_top_face(&self, params: &RenderParams, context: &RenderContext) {
+        let color = self.get_face_color(FenceFace::Top, context);
         draw_rectangle(
             params.x_top,
             params.y_top,
@@ -227,130 +183,13 @@ impl Fence {
             color,
         );
     }
-
-    /// Renders an animated barrier gate (boom gate style)
-    fn render_barrier(&self, params: &RenderParams, context: &RenderContext) {
-        if !self.has_barrier {
-            return;
-        }
-
-        // Calculate barrier position along the fence - position at the START of the gap
-        // So the boom arm can extend across the entire gap
-        let barrier_x = params.x; // Start of the fence/gap
-        let barrier_x_top = params.x_top;
-
-        // Target angle based on barrier state
-        let target_angle = if context.barrier_open {
-            85.0_f32.to_radians() // Open = vertical (85 degrees)
-        } else {
-            0.0 // Closed = horizontal (0 degrees)
-        };
-
-        // Smooth animation toward target with slower speed
-        let mut current_angle = self.current_angle.borrow_mut();
-        let transition_speed = 0.4; // Radians per second (slower = takes longer)
-        let delta = get_frame_time();
-
-        // Move current angle toward target
-        let angle_diff = target_angle - *current_angle;
-        if angle_diff.abs() > 0.001 {
-            // Smooth interpolation
-            *current_angle += angle_diff.signum() * transition_speed * delta;
-
-            // Clamp to target if we're very close
-            if (*current_angle - target_angle).abs() < transition_speed * delta {
-                *current_angle = target_angle;
-            }
-        }
-
-        let rotation_angle = *current_angle;
-
-        // Post dimensions
-        let post_width = 6.0;
-        let post_height = 15.0;
-        let post_color = Color::new(0.3, 0.3, 0.3, 1.0); // Dark gray
-
-        // Calculate post position (rises from fence)
-        let post_x_offset = post_height * ISOMETRIC_X_FACTOR;
-        let post_y_offset = post_height * ISOMETRIC_Y_FACTOR;
-        let post_x = barrier_x_top - post_x_offset;
-        let post_y = params.y_top - post_y_offset;
-
-        // Draw post (vertical support)
-        draw_rectangle(
-            post_x,
-            params.y_top - post_height,
-            post_width,
-            post_height,
-            post_color,
-        );
-
-        // Boom arm dimensions - should span the entire gap (from fence end to fence end)
-        // The gap is defined by the fence width, so boom should match it
-        let boom_length = params.width; // Full width of the gap area
-        let boom_thickness = 4.0;
-
-        // Calculate boom arm end position based on rotation
-        let boom_end_x = boom_length * rotation_angle.cos();
-        let boom_end_y = -boom_length * rotation_angle.sin();
-
-        // Draw boom arm with red and white stripes
-        let stripe_count = 3; // 3 stripes for the gap-spanning boom
-        let stripe_length = boom_length / stripe_count as f32;
-
-        for i in 0..stripe_count {
-            let stripe_start = i as f32 * stripe_length;
-            let stripe_end = (i + 1) as f32 * stripe_length;
-
-            // Alternate colors
-            let color = if i % 2 == 0 {
-                Color::new(0.95, 0.1, 0.1, 1.0) // Red
-            } else {
-                Color::new(0.95, 0.95, 0.95, 1.0) // White
-            };
-
-            // Calculate stripe positions
-            let x1 = post_x + post_width / 2.0 + stripe_start * rotation_angle.cos();
-            let y1 = post_y - stripe_start * rotation_angle.sin();
-            let x2 = post_x + post_width / 2.0 + stripe_end * rotation_angle.cos();
-            let y2 = post_y - stripe_end * rotation_angle.sin();
-
-            // Draw stripe as thick line
-            let perpendicular_x = -rotation_angle.sin() * boom_thickness / 2.0;
-            let perpendicular_y = -rotation_angle.cos() * boom_thickness / 2.0;
-
-            // Draw rectangle for stripe
-            draw_triangle(
-                Vec2 { x: x1 + perpendicular_x, y: y1 + perpendicular_y },
-                Vec2 { x: x1 - perpendicular_x, y: y1 - perpendicular_y },
-                Vec2 { x: x2 + perpendicular_x, y: y2 + perpendicular_y },
-                color,
-            );
-            draw_triangle(
-                Vec2 { x: x2 + perpendicular_x, y: y2 + perpendicular_y },
-                Vec2 { x: x2 - perpendicular_x, y: y2 - perpendicular_y },
-                Vec2 { x: x1 - perpendicular_x, y: y1 - perpendicular_y },
-                color,
-            );
-        }
-
-        // Draw counterweight on the back side (small red box) - opposite direction of boom
-        let counterweight_size = 6.0;
-        let counterweight_offset = 10.0; // Distance from post center
-        let counterweight_x = post_x + post_width / 2.0 - counterweight_offset * rotation_angle.cos();
-        let counterweight_y = post_y + counterweight_offset * rotation_angle.sin();
-
-        draw_rectangle(
-            counterweight_x - counterweight_size / 2.0,
-            counterweight_y - counterweight_size / 2.0,
-            counterweight_size,
-            counterweight_size,
-            Color::new(0.7, 0.1, 0.1, 1.0),
-        );
-    }
 }
 
 impl BlockObject for Fence {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
@@ -384,12 +223,9 @@ impl BlockObject for Fence {
         };
 
         // Render all three visible faces
-        self.render_front_face(&params);
-        self.render_side_face(&params);
-        self.render_top_face(&params);
-
-        // Render barrier if present
-        self.render_barrier(&params, context);
+        self.render_front_face(&params, context);
+        self.render_side_face(&params, context);
+        self.render_top_face(&params, context);
     }
 }
 
@@ -503,9 +339,6 @@ impl FenceBuilder {
     /// - depth_percent: 0.5 (50% of block height)
     /// - height_pixels: 8.0 (8 pixels tall)
     /// - color: Brown (0.4, 0.3, 0.2, 1.0)
-    /// - has_barrier: false
-    /// - barrier_position: 0.5
-    /// - current_angle: 0.0 (closed)
     pub fn build(self) -> Fence {
         Fence {
             x_offset_percent: self.x_offset_percent.unwrap_or(0.0),
@@ -514,9 +347,6 @@ impl FenceBuilder {
             depth_percent: self.depth_percent.unwrap_or(0.5),
             height_pixels: self.height_pixels.unwrap_or(8.0),
             color: self.color.unwrap_or(DEFAULT_FENCE_COLOR),
-            has_barrier: false,
-            barrier_position: 0.5,
-            current_angle: RefCell::new(0.0),
         }
     }
 }
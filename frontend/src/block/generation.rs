@@ -2,18 +2,25 @@
 //!
 //! Provides functions for generating the city grid of blocks.
 
-use crate::block::{Block, Building, Fence, Grass};
+use crate::block::{
+    Barrier, Block, Building, Grass, GuardedCompound, ParkingLot, Sidewalk, StreetLamp, Tree,
+};
 use crate::constants::{
     road_network::{HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS},
     visual::ROAD_WIDTH,
 };
-use macroquad::prelude::*;
+use crate::models::Direction;
+use crate::screen::{screen_height, screen_width};
+use macroquad::prelude::Color;
 
 /// Generates all grass blocks for the city grid
 ///
 /// Creates a 4×3 grid of blocks (12 total) in the spaces between roads.
-/// Each block contains a Grass object as the base. Some blocks may have
-/// additional objects (like Buildings) placed on top of the grass.
+/// Each block contains a Grass object as the base, edged with sidewalks on
+/// the sides that face a road; the block is connected to those roads. Some
+/// blocks may have additional objects (like Buildings) placed on top of the
+/// grass, blocks 3 and 4 are parks planted with trees, and every block has a
+/// street lamp at each corner.
 ///
 /// # Returns
 /// Vector of Block instances, each containing at least a Grass object
@@ -64,8 +71,26 @@ pub fn generate_grass_blocks() -> Vec<Block> {
                 block_id,
             );
 
-            // Add grass to all blocks as the base
+            // Roads between the columns are vertical roads 0-2, roads between
+            // the rows horizontal roads 3-4
+            let (column, row) = (i / 2, j / 2);
+            let horizontal_road_ids = VERTICAL_ROAD_POSITIONS.len();
+            if column > 0 {
+                block.connect_road(Direction::Left, column - 1);
+            }
+            if column < VERTICAL_ROAD_POSITIONS.len() {
+                block.connect_road(Direction::Right, column);
+            }
+            if row > 0 {
+                block.connect_road(Direction::Up, horizontal_road_ids + row - 1);
+            }
+            if row < HORIZONTAL_ROAD_POSITIONS.len() {
+                block.connect_road(Direction::Down, horizontal_road_ids + row);
+            }
+
+            // Add grass to all blocks as the base, then the sidewalks
             block.add_object(Box::new(Grass::fill()));
+            block.add_object(Box::new(Sidewalk));
 
             // Block 1 - top left corner
             if block_id == 1 {
@@ -193,69 +218,16 @@ pub fn generate_grass_blocks() -> Vec<Block> {
 
             // Block 8 is second row, third column - add a building in the middle
             if block_id == 8 {
-                // Add a fence on the top side with offsets from edges
-                block.add_object(Box::new(Fence::new(
-                    0.10,           // x_offset: 10% from left edge
-                    0.10,           // y_offset: 10% from top edge
-                    0.80,           // width: 80% of block width (leaves 10% at left, 10% at right)
-                    0.01,           // depth: 1% of block height
-                    6.0,            // height: 6 pixels tall
-                    building_color, // Brown fence
-                )));
-
-                // Add a fence on the left side with offsets from edges
-                block.add_object(Box::new(Fence::new(
-                    0.10, // x_offset: 10% from left edge
-                    0.11, // y_offset: 11% from top edge (starts where top fence ends)
-                    0.01, // width: 1% of block width
-                    0.78, // depth: 78% (from 11% to 89%)
-                    6.0,  // height: 6 pixels tall
-                    building_color,
-                )));
-
-                // Add a fence on the right side with offsets from edges
-                block.add_object(Box::new(Fence::new(
-                    0.89, // x_offset: 89% from left edge (leaves 10% + 1% width to reach right edge)
-                    0.11, // y_offset: 11% from top edge (starts where top fence ends)
-                    0.01, // width: 1% of block width
-                    0.78, // depth: 78% (from 11% to 89%)
-                    6.0,  // height: 6 pixels tall
-                    building_color,
-                )));
-
-                // Add a fence on the bottom side - LEFT part (before barrier gap)
-                block.add_object(Box::new(Fence::new(
-                    0.10, // x_offset: 10% from left edge
-                    0.89, // y_offset: 89% from top edge
-                    0.35, // width: 35% of block width (leaves gap for barrier)
-                    0.01, // depth: 1% of block height
-                    6.0,  // height: 6 pixels tall
-                    building_color,
-                )));
-
-                // Add a fence on the bottom side - RIGHT part (after barrier gap)
-                block.add_object(Box::new(Fence::new(
-                    0.55, // x_offset: 55% from left edge (after gap)
-                    0.89, // y_offset: 89% from top edge
-                    0.35, // width: 35% of block width (leaves 10% at right)
-                    0.01, // depth: 1% of block height
-                    6.0,  // height: 6 pixels tall
-                    building_color,
-                )));
-
-                // Add barrier in the gap - boom arm spans from left to right fence
+                // Fenced compound with a guarded gate facing the road below
                 block.add_object(Box::new(
-                    Fence::new(
-                        0.45, // x_offset: 45% from left edge (gap start)
-                        0.89, // y_offset: 89% from top edge
-                        0.10, // width: 10% gap - boom arm will span this entire width
-                        0.01, // depth: 1% of block height
-                        6.0,  // height: 6 pixels tall (invisible, just for barrier mount)
-                        Color::new(0.0, 0.0, 0.0, 0.0), // Transparent fence
-                    )
-                    .with_barrier(0.0), // Barrier post at left edge, boom spans to right edge
+                    GuardedCompound::new(building_color)
+                        .with_capacity(4)      // one car on each parking spot
+                        .with_check_time(2.0), // guard checks each car for 2 seconds
                 ));
 
+                // Add barrier across the road on the right side of the block
+                block.add_object(Box::new(Barrier::new(0.5)));
+
                 // Add building in the center of the block with SCADA control
                 // Positioned at 25% offset, sized to 50% of block dimensions
                 block.add_object(Box::new(
@@ -283,6 +255,15 @@ pub fn generate_grass_blocks() -> Vec<Block> {
                     7.0,  // corner_radius: 7 pixels
                     building_color,
                 )));
+
+                // Parking lot in front of the building
+                block.add_object(Box::new(ParkingLot::new(
+                    0.10, // x_offset: 10% from left
+                    0.76, // y_offset: 76% from top
+                    0.80, // width: 80% of block width
+                    0.18, // height: 18% of block height
+                    8,    // capacity: 8 stalls
+                )));
             }
 
             // Block 9 - bottom row, third column
@@ -311,6 +292,18 @@ pub fn generate_grass_blocks() -> Vec<Block> {
                 )));
             }
 
+            // Blocks 3 and 4 - parks, bottom left and top of the second column
+            if block_id == 3 || block_id == 4 {
+                for tree in Tree::grove(4, 3) {
+                    block.add_object(Box::new(tree));
+                }
+            }
+
+            // Street lamps at every corner, where the block meets the roads
+            for lamp in StreetLamp::corners(&block) {
+                block.add_object(Box::new(lamp));
+            }
+
             blocks.push(block);
             block_id += 1;
         }
@@ -81,11 +81,15 @@ impl Grass {
 }
 
 impl BlockObject for Grass {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
 
-    fn render(&self, block: &Block, _context: &RenderContext) {
+    fn render(&self, block: &Block, context: &RenderContext) {
         // Get block position and size in pixels
         let block_x = block.x();
         let block_y = block.y();
@@ -99,7 +103,8 @@ impl BlockObject for Grass {
         let height = self.height_percent * block_height;
 
         // Draw flat grass rectangle with rounded corners (no depth effects)
-        draw_rounded_rectangle(x, y, width, height, BLOCK_CORNER_RADIUS, GRASS_COLOR);
+        let color = context.shade(GRASS_COLOR);
+        draw_rounded_rectangle(x, y, width, height, BLOCK_CORNER_RADIUS, color);
     }
 }
 
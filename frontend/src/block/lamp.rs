@@ -0,0 +1,161 @@
+//! Street lamp block object implementation
+//!
+//! Provides the street lamps standing at the corners of each block. A lamp
+//! is a dark pole with a round head, unlit while the sun is up; once it has
+//! set, the head glows yellow and casts a soft pool of light around it.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use crate::constants::day_night::GLOW_ALPHA;
+use crate::rendering::draw_circle_glow;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Lamp Rendering Constants
+// ============================================================================
+
+/// Pole size in pixels
+const POLE_WIDTH: f32 = 2.0;
+const POLE_HEIGHT: f32 = 14.0;
+
+/// Radius of the lamp head in pixels
+const HEAD_RADIUS: f32 = 3.0;
+
+/// How far the light spreads past the lamp head, in pixels
+const LIGHT_SPREAD: f32 = 22.0;
+
+/// Distance of a corner lamp from both edges of its block, in pixels
+const CORNER_INSET: f32 = 8.0;
+
+const POLE_COLOR: Color = Color::new(0.2, 0.2, 0.22, 1.0);
+const HEAD_UNLIT_COLOR: Color = Color::new(0.35, 0.35, 0.38, 1.0);
+const HEAD_LIT_COLOR: Color = Color::new(1.0, 0.9, 0.45, 1.0);
+const LIGHT_COLOR: Color = Color::new(1.0, 0.85, 0.3, 1.0);
+
+// ============================================================================
+// Street Lamp Object Implementation
+// ============================================================================
+
+/// A street lamp, lit from sunset to sunrise
+pub struct StreetLamp {
+    /// Horizontal offset of the pole's foot as percentage of block width
+    pub x_offset_percent: f32,
+
+    /// Vertical offset of the pole's foot as percentage of block height
+    pub y_offset_percent: f32,
+}
+
+impl StreetLamp {
+    /// Creates a lamp at a position within its block
+    ///
+    /// # Arguments
+    /// * `x_offset_percent` - Position across the block (0.0-1.0)
+    /// * `y_offset_percent` - Position down the block (0.0-1.0)
+    pub fn new(x_offset_percent: f32, y_offset_percent: f32) -> Self {
+        Self {
+            x_offset_percent,
+            y_offset_percent,
+        }
+    }
+
+    /// Creates a lamp at each corner of a block, just inside its edges
+    ///
+    /// # Arguments
+    /// * `block` - Block to place the lamps in
+    pub fn corners(block: &Block) -> [Self; 4] {
+        let inset_x = (CORNER_INSET / block.width()).min(0.5);
+        let inset_y = (CORNER_INSET / block.height()).min(0.5);
+        let (left, right) = (inset_x, 1.0 - inset_x);
+        let (top, bottom) = (inset_y, 1.0 - inset_y);
+        [
+            Self::new(left, top),
+            Self::new(right, top),
+            Self::new(left, bottom),
+            Self::new(right, bottom),
+        ]
+    }
+}
+
+/// Checks if lamps are on at a point of the day/night cycle
+///
+/// Lamps are on while the sun is below the horizon: from 6 pm (0.75) until
+/// 6 am (0.25).
+///
+/// # Arguments
+/// * `time_of_day` - Point of the day/night cycle (0.0 = midnight, 0.5 = noon)
+fn is_lit(time_of_day: f64) -> bool {
+    !(0.25..=0.75).contains(&time_of_day)
+}
+
+impl BlockObject for StreetLamp {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let x = block.x() + self.x_offset_percent * block.width();
+        let foot_y = block.y() + self.y_offset_percent * block.height();
+        let head_y = foot_y - POLE_HEIGHT;
+        let lit = is_lit(context.time_of_day);
+
+        // Pool of light on the ground first, so the pole stands in it
+        if lit {
+            let strength = GLOW_ALPHA * (1.0 - context.ambient).max(0.5);
+            draw_circle_glow(
+                x,
+                foot_y,
+                HEAD_RADIUS,
+                LIGHT_SPREAD,
+                Color { a: strength, ..LIGHT_COLOR },
+            );
+        }
+
+        draw_rectangle(
+            x - POLE_WIDTH / 2.0,
+            head_y,
+            POLE_WIDTH,
+            POLE_HEIGHT,
+            context.shade(POLE_COLOR),
+        );
+
+        if lit {
+            draw_circle_glow(
+                x,
+                head_y,
+                HEAD_RADIUS,
+                HEAD_RADIUS * 2.0,
+                Color { a: GLOW_ALPHA, ..LIGHT_COLOR },
+            );
+            draw_circle(x, head_y, HEAD_RADIUS, HEAD_LIT_COLOR);
+        } else {
+            draw_circle(x, head_y, HEAD_RADIUS, context.shade(HEAD_UNLIT_COLOR));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lamps_stand_inside_every_corner_and_light_at_night() {
+        let block = Block::new(0.1, 0.1, 0.2, 0.2, 1);
+        let lamps = StreetLamp::corners(&block);
+        for lamp in &lamps {
+            let (x, y) = (lamp.x_offset_percent, lamp.y_offset_percent);
+            assert!((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y));
+            assert!(x.min(1.0 - x) < 0.1 && y.min(1.0 - y) < 0.1, "{} {}", x, y);
+        }
+        assert_eq!(lamps.iter().filter(|lamp| lamp.x_offset_percent < 0.5).count(), 2);
+        assert_eq!(lamps.iter().filter(|lamp| lamp.y_offset_percent < 0.5).count(), 2);
+
+        // Midnight and late evening are lit; morning, noon and afternoon not
+        let times = [(0.0, true), (0.9, true), (0.3, false), (0.5, false), (0.7, false)];
+        for (time_of_day, lit) in times {
+            assert_eq!(is_lit(time_of_day), lit, "{}", time_of_day);
+        }
+    }
+}
@@ -6,19 +6,35 @@
 //! Architecture:
 //! - Block: A positioned container that holds BlockObjects
 //! - BlockObject: Trait for things that can be rendered (Grass, Building, etc.)
-//! - Grass, Building, etc.: Concrete implementations of BlockObject
+//! - Grass, Building, Tree, etc.: Concrete implementations of BlockObject
 
+mod barrier;
 mod building;
+mod compound;
+mod cone;
 mod fence;
 mod generation;
 mod grass;
-
-pub use building::{Building, BuildingBuilder, BUILDING_CORNER_RADIUS};
+mod lamp;
+mod parking_lot;
+mod sidewalk;
+mod tree;
+
+pub use barrier::Barrier;
+pub use building::{Building, BuildingBuilder, ScadaState, BUILDING_CORNER_RADIUS};
+pub use compound::GuardedCompound;
+pub use cone::TrafficCone;
 pub use fence::{Fence, FenceBuilder};
 pub use generation::generate_grass_blocks;
 pub use grass::{Grass, GrassBuilder};
+pub use lamp::StreetLamp;
+pub use parking_lot::ParkingLot;
+pub use sidewalk::Sidewalk;
+pub use tree::Tree;
 
 use crate::models::Direction;
+use crate::screen::{screen_height, screen_width};
+use crate::time_of_day::TimeOfDay;
 use macroquad::prelude::*;
 use std::collections::HashMap;
 
@@ -34,16 +50,25 @@ pub struct RenderContext {
     /// Current simulation time
     pub time: f64,
 
-    /// Danger mode active (emergency warning state)
-    pub danger_mode: bool,
+    /// Ambient light level of the day/night cycle (0.0 = night, 1.0 = full daylight)
+    pub ambient: f32,
 
-    /// Barrier gate state (true = open, false = closed)
-    pub barrier_open: bool,
+    /// Point of the day/night cycle (0.0 = midnight, 0.5 = noon)
+    pub time_of_day: f64,
 }
 
 impl RenderContext {
-    pub fn new(time: f64, danger_mode: bool, barrier_open: bool) -> Self {
-        Self { time, danger_mode, barrier_open }
+    pub fn new(time: f64, lighting: &TimeOfDay) -> Self {
+        Self {
+            time,
+            ambient: lighting.ambient(),
+            time_of_day: lighting.phase(),
+        }
+    }
+
+    /// Shades a color for the ambient light, so every object darkens alike at night
+    pub fn shade(&self, color: Color) -> Color {
+        crate::rendering::shade(color, self.ambient)
     }
 }
 
@@ -63,6 +88,14 @@ pub trait BlockObject {
     /// * `context` - Rendering context with global state
     fn render(&self, block: &Block, context: &RenderContext);
 
+    /// Advances any animation state by `dt` seconds
+    ///
+    /// Static objects can rely on the default no-op implementation.
+    fn update(&mut self, _dt: f32) {}
+
+    /// Enables downcasting to concrete types
+    fn as_any(&self) -> &dyn std::any::Any;
+
     /// Enables downcasting to concrete types (mutable)
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
 }
@@ -185,13 +218,23 @@ impl Block {
     /// Renders all objects contained in this block
     ///
     /// # Arguments
-    /// * `context` - Rendering context with global state (time, danger_mode, etc.)
+    /// * `context` - Rendering context with global state (time, lighting, etc.)
     pub fn render(&self, context: &RenderContext) {
         for obj in &self.objects {
             obj.render(self, context);
         }
     }
 
+    /// Advances the animation state of all objects in this block
+    ///
+    /// # Arguments
+    /// * `dt` - Time elapsed since the last update, in seconds
+    pub fn update(&mut self, dt: f32) {
+        for obj in &mut self.objects {
+            obj.update(dt);
+        }
+    }
+
     /// Adds an object to this block
     ///
     /// # Arguments
@@ -0,0 +1,176 @@
+//! Parking lot block object implementation
+//!
+//! Provides an open-air parking lot: gray asphalt with a row of stalls
+//! painted across it. Cars that reach a destination near the lot are taken
+//! off the road and parked in it, filling its stalls in a random order.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use crate::constants::rendering::CAR_COLORS;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Parking Lot Rendering Constants
+// ============================================================================
+
+const ASPHALT_COLOR: Color = Color::new(0.35, 0.35, 0.37, 1.0);
+const MARKING_COLOR: Color = Color::new(0.95, 0.95, 0.95, 1.0);
+
+/// Width of the painted stall dividers in pixels
+const MARKING_WIDTH: f32 = 1.0;
+
+/// Gap between a parked car and the edges of its stall in pixels
+const STALL_PADDING: f32 = 2.0;
+
+// ============================================================================
+// Parking Lot Object Implementation
+// ============================================================================
+
+/// A parking lot with a row of painted stalls
+pub struct ParkingLot {
+    /// Horizontal offset as percentage of block width (0.0 = left edge, 1.0 = right edge)
+    pub x_offset_percent: f32,
+
+    /// Vertical offset as percentage of block height (0.0 = top edge, 1.0 = bottom edge)
+    pub y_offset_percent: f32,
+
+    /// Width as percentage of block width (0.0-1.0)
+    pub width_percent: f32,
+
+    /// Height as percentage of block height (0.0-1.0)
+    pub height_percent: f32,
+
+    /// Number of stalls
+    pub capacity: usize,
+
+    /// Number of stalls with a car parked in them
+    pub occupied: usize,
+
+    /// Order the stalls fill up in, shuffled when the lot is created
+    stall_order: Vec<usize>,
+}
+
+impl ParkingLot {
+    /// Creates an empty parking lot
+    ///
+    /// # Arguments
+    /// * `x_offset_percent` - X offset as percentage of block width (0.0-1.0)
+    /// * `y_offset_percent` - Y offset as percentage of block height (0.0-1.0)
+    /// * `width_percent` - Width as percentage of block width (0.0-1.0)
+    /// * `height_percent` - Height as percentage of block height (0.0-1.0)
+    /// * `capacity` - Number of stalls
+    pub fn new(
+        x_offset_percent: f32,
+        y_offset_percent: f32,
+        width_percent: f32,
+        height_percent: f32,
+        capacity: usize,
+    ) -> Self {
+        let mut stall_order: Vec<usize> = (0..capacity).collect();
+        for i in (1..capacity).rev() {
+            stall_order.swap(i, rand::gen_range(0, i + 1));
+        }
+        Self {
+            x_offset_percent,
+            y_offset_percent,
+            width_percent,
+            height_percent,
+            capacity,
+            occupied: 0,
+            stall_order,
+        }
+    }
+
+    /// Parks a car in a free stall
+    ///
+    /// # Returns
+    /// `true` if the car was parked, `false` if the lot is full
+    pub fn park(&mut self) -> bool {
+        if self.occupied >= self.capacity {
+            return false;
+        }
+        self.occupied += 1;
+        true
+    }
+
+    /// The lot's area in pixels
+    ///
+    /// # Arguments
+    /// * `block` - Block containing the lot
+    pub fn area(&self, block: &Block) -> Rect {
+        Rect::new(
+            block.x() + self.x_offset_percent * block.width(),
+            block.y() + self.y_offset_percent * block.height(),
+            self.width_percent * block.width(),
+            self.height_percent * block.height(),
+        )
+    }
+
+    /// Stalls with a car parked in them, in the order they filled up
+    fn occupied_stalls(&self) -> &[usize] {
+        &self.stall_order[..self.occupied.min(self.capacity)]
+    }
+}
+
+impl BlockObject for ParkingLot {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let area = self.area(block);
+        draw_rectangle(area.x, area.y, area.w, area.h, context.shade(ASPHALT_COLOR));
+        if self.capacity == 0 {
+            return;
+        }
+
+        // Dividers between the stalls, open toward the bottom where cars drive in
+        let stall_width = area.w / self.capacity as f32;
+        let marking_color = context.shade(MARKING_COLOR);
+        for i in 0..=self.capacity {
+            let x = area.x + i as f32 * stall_width;
+            draw_line(x, area.y, x, area.y + area.h * 0.8, MARKING_WIDTH, marking_color);
+        }
+
+        // A car silhouette, nose in, in every occupied stall
+        for &stall in self.occupied_stalls() {
+            let x = area.x + stall as f32 * stall_width + STALL_PADDING;
+            let width = (stall_width - STALL_PADDING * 2.0).max(1.0);
+            let height = (area.h * 0.8 - STALL_PADDING * 2.0).max(1.0);
+            let color = CAR_COLORS[stall % CAR_COLORS.len()];
+            draw_rectangle(x, area.y + STALL_PADDING, width, height, context.shade(color));
+            // Windshield
+            draw_rectangle(
+                x + width * 0.15,
+                area.y + STALL_PADDING + height * 0.2,
+                width * 0.7,
+                height * 0.2,
+                context.shade(Color::new(0.2, 0.25, 0.3, 1.0)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cars_fill_distinct_stalls_until_the_lot_is_full() {
+        let mut lot = ParkingLot::new(0.1, 0.7, 0.8, 0.2, 6);
+        assert!(lot.occupied_stalls().is_empty());
+
+        for _ in 0..6 {
+            assert!(lot.park());
+        }
+        assert!(!lot.park());
+        assert_eq!(lot.occupied, 6);
+
+        let mut stalls = lot.occupied_stalls().to_vec();
+        stalls.sort_unstable();
+        assert_eq!(stalls, [0, 1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Sidewalk block object implementation
+//!
+//! Provides the cement strips edging a block where it meets a road. Only
+//! the sides listed in the block's `adjacent_roads` get a strip, so a block
+//! at the edge of the city has no sidewalk facing the screen edge.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use crate::models::Direction;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Sidewalk Rendering Constants
+// ============================================================================
+
+/// Sidewalk width as a fraction of the block's width (left and right sides)
+/// or height (top and bottom sides)
+const SIDEWALK_WIDTH_PERCENT: f32 = 0.04;
+
+const SIDEWALK_COLOR: Color = Color::new(0.72, 0.72, 0.7, 1.0);
+
+/// Joint lines between the sidewalk's slabs
+const SLAB_JOINT_COLOR: Color = Color::new(0.6, 0.6, 0.58, 1.0);
+
+/// Length of a sidewalk slab in pixels
+const SLAB_LENGTH: f32 = 16.0;
+
+// ============================================================================
+// Sidewalk Object Implementation
+// ============================================================================
+
+/// Sidewalks along every side of a block that faces a road
+pub struct Sidewalk;
+
+/// Strip of sidewalk along one side of a block
+///
+/// # Arguments
+/// * `block` - Block the sidewalk edges
+/// * `side` - Side of the block the road is on
+///
+/// # Returns
+/// The strip as a rectangle in pixels
+fn strip(block: &Block, side: Direction) -> Rect {
+    let (x, y, width, height) = (block.x(), block.y(), block.width(), block.height());
+    let across_x = width * SIDEWALK_WIDTH_PERCENT;
+    let across_y = height * SIDEWALK_WIDTH_PERCENT;
+    match side {
+        Direction::Left => Rect::new(x, y, across_x, height),
+        Direction::Right => Rect::new(x + width - across_x, y, across_x, height),
+        Direction::Up => Rect::new(x, y, width, across_y),
+        Direction::Down => Rect::new(x, y + height - across_y, width, across_y),
+    }
+}
+
+impl BlockObject for Sidewalk {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        for &side in block.adjacent_roads.keys() {
+            let rect = strip(block, side);
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, context.shade(SIDEWALK_COLOR));
+
+            // Joints across the strip between its slabs
+            let joint_color = context.shade(SLAB_JOINT_COLOR);
+            if side.is_vertical() {
+                let mut x = rect.x + SLAB_LENGTH;
+                while x < rect.right() {
+                    draw_line(x, rect.y, x, rect.bottom(), 1.0, joint_color);
+                    x += SLAB_LENGTH;
+                }
+            } else {
+                let mut y = rect.y + SLAB_LENGTH;
+                while y < rect.bottom() {
+                    draw_line(rect.x, y, rect.right(), y, 1.0, joint_color);
+                    y += SLAB_LENGTH;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_hug_the_sides_facing_roads() {
+        let block = Block::new(0.1, 0.1, 0.25, 0.5, 1);
+        let (x, y, width, height) = (block.x(), block.y(), block.width(), block.height());
+
+        let left = strip(&block, Direction::Left);
+        assert_eq!((left.x, left.y, left.h), (x, y, height));
+        assert!((left.w - width * 0.04).abs() < 1e-4);
+
+        let bottom = strip(&block, Direction::Down);
+        assert!((bottom.bottom() - (y + height)).abs() < 1e-4);
+        assert_eq!(bottom.w, width);
+        assert!((bottom.h - height * 0.04).abs() < 1e-4);
+
+        let right = strip(&block, Direction::Right);
+        assert!((right.right() - (x + width)).abs() < 1e-4);
+    }
+}
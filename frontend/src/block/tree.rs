@@ -0,0 +1,181 @@
+//! Tree block object implementation
+//!
+//! Provides the trees planted in park blocks. A tree is a brown trunk under
+//! a round green canopy, with its shadow cast on the grass beside it. The
+//! canopy sways gently in the wind, each tree a little out of step with its
+//! neighbors.
+
+use crate::block::{Block, BlockObject, RenderContext};
+use macroquad::prelude::*;
+
+// ============================================================================
+// Tree Rendering Constants
+// ============================================================================
+
+/// Isometric projection factor for shadow X offset (cos(30°) ≈ 0.866)
+const ISOMETRIC_X_FACTOR: f32 = 0.866;
+
+/// Isometric projection factor for shadow Y offset (sin(30°) = 0.5)
+const ISOMETRIC_Y_FACTOR: f32 = 0.5;
+
+/// Trunk width in pixels
+const TRUNK_WIDTH: f32 = 4.0;
+
+/// Furthest the canopy sways to either side, in pixels
+const SWAY_AMPLITUDE: f32 = 1.5;
+
+/// Sway speed in radians per second
+const SWAY_SPEED: f64 = 1.3;
+
+/// Largest random shift of a park tree from its grid spot, as a fraction of
+/// the spacing between trees
+const GROVE_JITTER: f32 = 0.25;
+
+const TRUNK_COLOR: Color = Color::new(0.4, 0.26, 0.13, 1.0);
+const CANOPY_COLOR: Color = Color::new(0.18, 0.55, 0.22, 1.0);
+const CANOPY_HIGHLIGHT_COLOR: Color = Color::new(0.3, 0.68, 0.3, 1.0);
+const SHADOW_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.25);
+
+// ============================================================================
+// Tree Object Implementation
+// ============================================================================
+
+/// A tree standing in a block
+pub struct Tree {
+    /// Horizontal offset of the trunk's foot as percentage of block width
+    pub x_offset_percent: f32,
+
+    /// Vertical offset of the trunk's foot as percentage of block height
+    pub y_offset_percent: f32,
+
+    /// Height of the trunk in pixels, from the ground to the canopy center
+    pub height_pixels: f32,
+
+    /// Canopy radius as percentage of block width
+    pub canopy_radius_percent: f32,
+}
+
+impl Tree {
+    /// Creates a tree at a position within its block
+    ///
+    /// # Arguments
+    /// * `x_offset_percent` - Position across the block (0.0-1.0)
+    /// * `y_offset_percent` - Position down the block (0.0-1.0)
+    /// * `height_pixels` - Trunk height in pixels
+    /// * `canopy_radius_percent` - Canopy radius as percentage of block width
+    pub fn new(
+        x_offset_percent: f32,
+        y_offset_percent: f32,
+        height_pixels: f32,
+        canopy_radius_percent: f32,
+    ) -> Self {
+        Self {
+            x_offset_percent,
+            y_offset_percent,
+            height_pixels,
+            canopy_radius_percent,
+        }
+    }
+
+    /// Plants a park: a grid of trees, each shifted a little off its spot
+    /// and given a slightly different size so the rows don't look planted
+    ///
+    /// # Arguments
+    /// * `columns` - Trees across the block
+    /// * `rows` - Trees down the block
+    ///
+    /// # Returns
+    /// The trees, back rows first so nearer trees are drawn over them
+    pub fn grove(columns: usize, rows: usize) -> Vec<Self> {
+        let spacing_x = 1.0 / columns as f32;
+        let spacing_y = 1.0 / rows as f32;
+        let jitter = |spacing: f32| rand::gen_range(-GROVE_JITTER, GROVE_JITTER) * spacing;
+
+        let mut trees = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            for column in 0..columns {
+                trees.push(Self::new(
+                    (column as f32 + 0.5) * spacing_x + jitter(spacing_x),
+                    (row as f32 + 0.5) * spacing_y + jitter(spacing_y),
+                    rand::gen_range(12.0, 18.0),
+                    rand::gen_range(0.05, 0.07),
+                ));
+            }
+        }
+        trees
+    }
+}
+
+/// Sideways shift of a tree's canopy in the wind, in pixels
+///
+/// # Arguments
+/// * `time` - Current simulation time in seconds
+/// * `phase` - Offset into the sway, so neighboring trees differ
+fn sway(time: f64, phase: f32) -> f32 {
+    (time * SWAY_SPEED + phase as f64).sin() as f32 * SWAY_AMPLITUDE
+}
+
+impl BlockObject for Tree {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+
+    fn render(&self, block: &Block, context: &RenderContext) {
+        let x = block.x() + self.x_offset_percent * block.width();
+        let foot_y = block.y() + self.y_offset_percent * block.height();
+        let radius = self.canopy_radius_percent * block.width();
+
+        // Shadow falls away from the light, opposite the way buildings lean
+        draw_ellipse(
+            x + self.height_pixels * ISOMETRIC_X_FACTOR * 0.5,
+            foot_y + self.height_pixels * ISOMETRIC_Y_FACTOR * 0.2,
+            radius * 1.1,
+            radius * 0.45,
+            0.0,
+            SHADOW_COLOR,
+        );
+
+        draw_rectangle(
+            x - TRUNK_WIDTH / 2.0,
+            foot_y - self.height_pixels,
+            TRUNK_WIDTH,
+            self.height_pixels,
+            context.shade(TRUNK_COLOR),
+        );
+
+        let canopy_x = x + sway(context.time, self.x_offset_percent * 7.0);
+        let canopy_y = foot_y - self.height_pixels;
+        draw_circle(canopy_x, canopy_y, radius, context.shade(CANOPY_COLOR));
+        draw_circle(
+            canopy_x - radius * 0.3,
+            canopy_y - radius * 0.3,
+            radius * 0.45,
+            context.shade(CANOPY_HIGHLIGHT_COLOR),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grove_fills_its_block_and_sways_within_bounds() {
+        let trees = Tree::grove(4, 3);
+        assert_eq!(trees.len(), 12);
+        for tree in &trees {
+            let (x, y) = (tree.x_offset_percent, tree.y_offset_percent);
+            assert!((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y), "{} {}", x, y);
+        }
+        // Back rows first, so nearer trees are drawn over them
+        assert!(trees.windows(5).all(|w| w[0].y_offset_percent < w[4].y_offset_percent));
+
+        let shifts: Vec<f32> = (0..100).map(|step| sway(step as f64 * 0.1, 0.0)).collect();
+        assert!(shifts.iter().all(|shift| shift.abs() <= SWAY_AMPLITUDE));
+        assert!(shifts.iter().any(|&shift| shift > 1.0) && shifts.iter().any(|&shift| shift < -1.0));
+    }
+}
@@ -0,0 +1,193 @@
+//! Camera zoom and pan for the city view
+//!
+//! This module handles:
+//! - Camera: Zoom level and offset of the view over the city
+//! - Zooming with the mouse wheel, toward the point under the cursor
+//! - Panning by dragging with the middle mouse button
+//! - Keeping the view inside the city, which fills the screen at zoom 1.0
+//! - Converting between screen positions and city positions
+//!
+//! The city is laid out in screen pixels as usual; the camera only changes
+//! how that layout is drawn. It is applied with `set_camera` before the city
+//! is rendered, and the UI overlays are drawn after `set_default_camera`.
+
+use crate::constants::camera::*;
+use crate::screen::{screen_height, screen_width};
+use macroquad::prelude::*;
+
+/// View over the city
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// Magnification, from `MIN_ZOOM` (whole city) to `MAX_ZOOM`
+    pub zoom: f32,
+
+    /// Horizontal distance in pixels from the city center to the view center
+    pub offset_x: f32,
+
+    /// Vertical distance in pixels from the city center to the view center
+    pub offset_y: f32,
+
+    /// Mouse position of an ongoing middle-button drag, as of the last frame
+    drag_from: Option<Vec2>,
+}
+
+impl Camera {
+    /// Creates a camera showing the whole city
+    pub fn new() -> Self {
+        Self {
+            zoom: MIN_ZOOM,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            drag_from: None,
+        }
+    }
+
+    /// Handles the camera controls
+    ///
+    /// The mouse wheel zooms in and out around the cursor, and dragging
+    /// with the middle mouse button pans the view.
+    ///
+    /// # Arguments
+    /// * `over_ui` - If true, the mouse is over a UI panel that uses the
+    ///   wheel itself, so the wheel doesn't zoom
+    pub fn handle_input(&mut self, over_ui: bool) {
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse = vec2(mouse_x, mouse_y);
+
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0.0 && !over_ui {
+            let factor = if wheel > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+            self.zoom_at(self.zoom * factor, mouse);
+        }
+
+        if is_mouse_button_down(MouseButton::Middle) {
+            if let Some(from) = self.drag_from {
+                self.pan(from - mouse);
+            }
+            self.drag_from = Some(mouse);
+        } else {
+            self.drag_from = None;
+        }
+
+        // The limits depend on the screen size, which may have changed
+        self.clamp_offset();
+    }
+
+    /// Changes the zoom, keeping the city point under `anchor` in place
+    ///
+    /// # Arguments
+    /// * `zoom` - New zoom level, clamped to `MIN_ZOOM`..=`MAX_ZOOM`
+    /// * `anchor` - Screen position that stays over the same city point
+    pub fn zoom_at(&mut self, zoom: f32, anchor: Vec2) {
+        let before = self.screen_to_world(anchor);
+        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let after = self.screen_to_world(anchor);
+
+        self.offset_x += before.x - after.x;
+        self.offset_y += before.y - after.y;
+        self.clamp_offset();
+    }
+
+    /// Moves the view
+    ///
+    /// # Arguments
+    /// * `screen_delta` - Distance in screen pixels; the view moves less the
+    ///   further it is zoomed in, so the city follows the mouse
+    pub fn pan(&mut self, screen_delta: Vec2) {
+        self.offset_x += screen_delta.x / self.zoom;
+        self.offset_y += screen_delta.y / self.zoom;
+        self.clamp_offset();
+    }
+
+    /// Limits the offset so the view never shows anything outside the city
+    fn clamp_offset(&mut self) {
+        let max_x = screen_width() / 2.0 * (1.0 - 1.0 / self.zoom);
+        let max_y = screen_height() / 2.0 * (1.0 - 1.0 / self.zoom);
+        self.offset_x = self.offset_x.clamp(-max_x, max_x);
+        self.offset_y = self.offset_y.clamp(-max_y, max_y);
+    }
+
+    /// City position at the center of the view
+    fn center(&self) -> Vec2 {
+        vec2(
+            screen_width() / 2.0 + self.offset_x,
+            screen_height() / 2.0 + self.offset_y,
+        )
+    }
+
+    /// Converts a screen position to the city position drawn there
+    ///
+    /// # Arguments
+    /// * `point` - Position in screen pixels, e.g. the mouse position
+    ///
+    /// # Returns
+    /// The position in the city's pixel layout
+    pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+        let screen_center = vec2(screen_width() / 2.0, screen_height() / 2.0);
+        self.center() + (point - screen_center) / self.zoom
+    }
+
+    /// City position under the mouse cursor
+    pub fn mouse_world_position(&self) -> (f32, f32) {
+        let (mouse_x, mouse_y) = mouse_position();
+        self.screen_to_world(vec2(mouse_x, mouse_y)).into()
+    }
+
+    /// Macroquad camera drawing the city through this view
+    pub fn to_camera2d(&self) -> Camera2D {
+        Camera2D {
+            target: self.center(),
+            // Negative Y keeps the Y axis pointing down, as on screen
+            zoom: vec2(
+                2.0 * self.zoom / screen_width(),
+                -2.0 * self.zoom / screen_height(),
+            ),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_keeps_point_under_cursor_and_is_clamped() {
+        let mut camera = Camera::new();
+        assert_eq!(camera.screen_to_world(vec2(200.0, 150.0)), vec2(200.0, 150.0));
+
+        camera.zoom_at(2.0, vec2(200.0, 150.0));
+        assert_eq!(camera.zoom, 2.0);
+        assert_eq!(camera.screen_to_world(vec2(200.0, 150.0)), vec2(200.0, 150.0));
+        // The screen center now shows a point between the cursor and the city center
+        assert_eq!(camera.screen_to_world(vec2(400.0, 300.0)), vec2(300.0, 225.0));
+
+        camera.zoom_at(10.0, vec2(400.0, 300.0));
+        assert_eq!(camera.zoom, MAX_ZOOM);
+        camera.zoom_at(0.1, vec2(400.0, 300.0));
+        assert_eq!(camera.zoom, MIN_ZOOM);
+        assert_eq!((camera.offset_x, camera.offset_y), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pan_stays_inside_the_city() {
+        let mut camera = Camera::new();
+        camera.pan(vec2(100.0, 100.0));
+        assert_eq!((camera.offset_x, camera.offset_y), (0.0, 0.0));
+
+        camera.zoom_at(2.0, vec2(400.0, 300.0));
+        camera.pan(vec2(100.0, -40.0));
+        assert_eq!((camera.offset_x, camera.offset_y), (50.0, -20.0));
+
+        // At 2x the view is half the city, so its center stays in the middle half
+        camera.pan(vec2(10_000.0, 10_000.0));
+        assert_eq!((camera.offset_x, camera.offset_y), (200.0, 150.0));
+        assert_eq!(camera.screen_to_world(vec2(800.0, 600.0)), vec2(800.0, 600.0));
+    }
+}
@@ -3,91 +3,350 @@
 //! This module handles:
 //! - Car movement and physics
 //! - Traffic light compliance
-//! - Collision avoidance
-//! - Intersection navigation and turning
+//! - Collision avoidance, checking only nearby cars through a spatial grid
+//! - Intersection navigation and turning, signalling turns ahead
+//! - Driving around roundabouts, giving way to the cars circling them
+//! - Yielding to emergency vehicles
+//! - Stopping for pedestrians on crosswalks
+//! - Routing visiting cars to the gate of their destination
+//! - Following planned routes through the intersections, turn by turn
+//! - Despawning or wrapping around at the screen edges
 //!
-//! Cars follow left-hand traffic rules with proper lane discipline.
+//! Cars keep to the lanes of the traffic rule they were spawned under. Turning
+//! cars follow a quarter-circle arc from their entry lane to the exit lane.
+//! At roundabouts every car, turning or not, drives around the island from
+//! where its lane meets the circle to where its exit lane leaves it.
+//! Emergency vehicles ignore traffic lights and turn the lights ahead of them
+//! green, and cars ahead of one in its lane pull over to the road edge and
+//! wait for it to pass. Cars visiting a guarded compound turn toward its gate
+//! and stop there to be let in, pulling over if it is full.
 
+use crate::constants::rendering::CROSSWALK_DISTANCE;
 use crate::constants::vehicle::*;
 use crate::constants::visual::ROAD_WIDTH;
 use crate::intersection::Intersection;
-use crate::models::{Car, Direction};
+use crate::models::{
+    Car, CarLocation, CrossingPath, Direction, Pedestrian, RoundaboutPath, TrafficRule, TurnPath,
+    TurnProgress, TurnSignalState, VehicleStyle, VehicleType,
+};
+use crate::road::{Orientation, Road};
+use crate::spatial_grid::SpatialGrid;
+use crate::traffic_light::LightState;
+use crate::screen::{screen_height, screen_width};
 use macroquad::prelude::*;
+use serde::Deserialize;
+
+// ============================================================================
+// Nearby Cars
+// ============================================================================
+
+/// All cars, indexed by position for finding the ones near a point
+///
+/// Built once per frame by `update_cars`, so each car's collision checks
+/// look only at the cars in the grid cells around it instead of at every car.
+pub struct Traffic<'a> {
+    cars: &'a [Car],
+
+    /// Grid over the car positions; None checks every car instead, as a
+    /// reference to compare the grid against
+    grid: Option<SpatialGrid>,
+}
+
+impl<'a> Traffic<'a> {
+    /// Indexes cars by position
+    pub fn new(cars: &'a [Car]) -> Self {
+        let positions = cars.iter().map(|car| vec2(car.x(), car.y()));
+        Self {
+            cars,
+            grid: Some(SpatialGrid::new(COLLISION_CELL_SIZE, positions)),
+        }
+    }
+
+    /// Wraps cars without an index, so every query checks every car
+    #[cfg(test)]
+    fn brute_force(cars: &'a [Car]) -> Self {
+        Self { cars, grid: None }
+    }
+
+    /// Cars that may be within `radius` of `point`, and possibly some
+    /// further away
+    ///
+    /// # Arguments
+    /// * `point` - Position to search around, in pixels
+    /// * `radius` - Search distance in pixels
+    fn near(&self, point: Vec2, radius: f32) -> impl Iterator<Item = &'a Car> + '_ {
+        let indices: Box<dyn Iterator<Item = usize>> = match &self.grid {
+            Some(grid) => Box::new(grid.query(point, radius)),
+            None => Box::new(0..self.cars.len()),
+        };
+        let cars = self.cars;
+        indices.map(move |index| &cars[index])
+    }
+}
+
+// ============================================================================
+// Stops
+// ============================================================================
+
+/// Entrance of a guarded compound, where visiting cars leave the road
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gate {
+    /// Block the gate leads into; cars with it as their destination stop here
+    pub block_id: usize,
+
+    /// Road running past the gate
+    pub road_index: usize,
+
+    /// Point on the road center line in front of the gate (pixels)
+    pub position: Vec2,
+
+    /// Direction from the road through the gate into the block
+    pub inward: Direction,
+
+    /// Whether the compound can't take a car now (every spot is taken or a
+    /// car is leaving), so arriving cars pull over and wait `GATE_QUEUE_GAP`
+    /// before the gate
+    pub full: bool,
+
+    /// Car being checked at the gate; others arriving meanwhile wait
+    pub admitting: Option<usize>,
+}
+
+/// Where cars stop besides traffic lights, crosswalks and other cars
+#[derive(Clone, Debug, Default)]
+pub struct Stops {
+    /// Stop points of barriers blocking traffic (pixels)
+    pub barriers: Vec<(f32, f32)>,
+
+    /// Gates of guarded compounds, where visiting cars stop to be let in
+    pub gates: Vec<Gate>,
+
+    /// IDs of the roads closed to traffic
+    pub closed_roads: Vec<usize>,
+}
+
+impl Stops {
+    /// Gate of the block a car is visiting, if it has one
+    fn gate_for(&self, car: &Car) -> Option<&Gate> {
+        let destination = car.destination?;
+        self.gates.iter().find(|gate| gate.block_id == destination)
+    }
+
+    /// Whether a road through an intersection is closed to a car
+    ///
+    /// Cars already on the closed road may drive on, so it empties out.
+    fn closed_at(&self, car: &Car, intersection: &Intersection) -> bool {
+        intersection
+            .connected_roads
+            .values()
+            .any(|road| *road != car.road_index && self.closed_roads.contains(road))
+    }
+}
 
 // ============================================================================
 // Traffic Control & Collision Detection
 // ============================================================================
 
+/// Distance a car travelling at `speed` needs to come to a stop (pixels)
+fn braking_distance(speed: f32) -> f32 {
+    speed * speed / (2.0 * DECELERATION)
+}
+
+/// Highest speed from which a car can still stop within `gap` pixels
+///
+/// Following this speed as the gap shrinks decelerates the car at exactly
+/// `DECELERATION`, bringing it to rest at the end of the gap.
+fn stopping_speed(gap: f32) -> f32 {
+    (2.0 * DECELERATION * gap.max(0.0)).sqrt()
+}
+
+/// Returns how far a car can travel before it must be stopped for a stop point
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `stop_x` - X position of the stop point (pixels)
+/// * `stop_y` - Y position of the stop point (pixels)
+///
+/// # Returns
+/// The remaining distance to the stop line (negative once past it), or `None`
+/// if the stop point is not ahead of the car in its lane or is further away
+/// than the car's braking distance
+///
+/// # Rules
+/// - Cars come to rest `STOP_LINE_DISTANCE` pixels before the stop point
+/// - If too close (< 30 pixels), the car continues through
+fn distance_to_stop_line(car: &Car, stop_x: f32, stop_y: f32) -> Option<f32> {
+    lane_gap(car, stop_x, stop_y).filter(|&gap| gap <= braking_distance(car.velocity))
+}
+
+/// Returns the remaining distance to a stop line ahead in the car's lane,
+/// however far away it is
+///
+/// See `distance_to_stop_line` for the rules.
+fn lane_gap(car: &Car, stop_x: f32, stop_y: f32) -> Option<f32> {
+    let car_x = car.x();
+    let car_y = car.y();
+
+    let (lane_offset, distance) = match car.direction {
+        Direction::Down => (car_x - stop_x, stop_y - car_y),
+        Direction::Up => (car_x - stop_x, car_y - stop_y),
+        Direction::Right => (car_y - stop_y, stop_x - car_x),
+        Direction::Left => (car_y - stop_y, car_x - stop_x),
+    };
+
+    if lane_offset.abs() >= LANE_TOLERANCE || distance <= STOP_DISTANCE_MIN {
+        return None;
+    }
+
+    Some(distance - STOP_LINE_DISTANCE)
+}
+
 /// Checks if a car should stop for a traffic light at an intersection
 ///
 /// # Arguments
 /// * `car` - The car to check
 /// * `intersection_x` - X position of intersection center (pixels)
 /// * `intersection_y` - Y position of intersection center (pixels)
-/// * `light_state` - Traffic light state (0=red, 1=yellow, 2=green)
+/// * `light_state` - Traffic light state for the car's direction of travel
 ///
 /// # Returns
-/// `true` if car should stop, `false` if it can proceed
+/// The distance left to the stop line if the car must stop, `None` if it
+/// can proceed
 ///
 /// # Safety Rules
 /// - Cars already in intersection MUST continue (never stop mid-crossing)
-/// - Stop only if 30-80 pixels from intersection
+/// - Stop only if the stop line is within braking distance
 /// - Stop on red or yellow lights only
+/// - Emergency vehicles never stop for lights
 fn check_traffic_light_at_intersection(
     car: &Car,
     intersection_x: f32,
     intersection_y: f32,
-    light_state: u8,
-) -> bool {
+    light_state: LightState,
+) -> Option<f32> {
     // CRITICAL: Never stop a car that's already in the intersection
-    if car.in_intersection {
-        return false; // Cars in intersection must continue through
+    if car.in_intersection || car.vehicle_type == VehicleType::EmergencyVehicle {
+        return None; // Cars in intersection must continue through
     }
 
-    let stop_distance_min = STOP_DISTANCE_MIN;
-    let stop_distance_max = STOP_DISTANCE_MAX;
-    let lane_tolerance = LANE_TOLERANCE;
+    // Stop on red or yellow lights only
+    match light_state {
+        LightState::Red(_) | LightState::Yellow(_) => {
+            distance_to_stop_line(car, intersection_x, intersection_y)
+        }
+        LightState::Green(_) => None,
+    }
+}
 
-    let car_x = car.x();
-    let car_y = car.y();
+/// Checks if a car should stop for a closed barrier
+///
+/// Barriers are treated like a red light at their stop point: cars in
+/// either lane stop `STOP_LINE_DISTANCE` pixels before the arm.
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `barriers` - Stop points of all barriers currently blocking traffic
+///
+/// # Returns
+/// The distance left to the nearest barrier's stop line, if one is ahead
+fn check_barriers(car: &Car, barriers: &[(f32, f32)]) -> Option<f32> {
+    barriers
+        .iter()
+        .filter_map(|&(barrier_x, barrier_y)| distance_to_stop_line(car, barrier_x, barrier_y))
+        .reduce(f32::min)
+}
 
-    match car.direction {
-        Direction::Down => {
-            if (car_x - intersection_x).abs() < lane_tolerance && intersection_y > car_y {
-                let distance = intersection_y - car_y;
-                // Only stop if far enough away and light is red/yellow
-                // If too close (< stop_distance_min), continue through
-                if distance > stop_distance_min && distance < stop_distance_max {
-                    return light_state == 0 || light_state == 1; // Stop on red or yellow
-                }
-            }
-        }
-        Direction::Up => {
-            if (car_x - intersection_x).abs() < lane_tolerance && intersection_y < car_y {
-                let distance = car_y - intersection_y;
-                if distance > stop_distance_min && distance < stop_distance_max {
-                    return light_state == 0 || light_state == 1;
-                }
-            }
-        }
-        Direction::Right => {
-            if (car_y - intersection_y).abs() < lane_tolerance && intersection_x > car_x {
-                let distance = intersection_x - car_x;
-                if distance > stop_distance_min && distance < stop_distance_max {
-                    return light_state == 0 || light_state == 1;
-                }
-            }
-        }
-        Direction::Left => {
-            if (car_y - intersection_y).abs() < lane_tolerance && intersection_x < car_x {
-                let distance = car_x - intersection_x;
-                if distance > stop_distance_min && distance < stop_distance_max {
-                    return light_state == 0 || light_state == 1;
-                }
-            }
-        }
+/// Returns how far a car on a gate's road is from the gate
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `gate` - The gate to check
+///
+/// # Returns
+/// The distance along the road to the point in front of the gate (negative
+/// once past it), or `None` if the car is not driving along the gate's road
+/// toward the gate. Cars pulled over to the road edge still count.
+fn distance_to_gate(car: &Car, gate: &Gate) -> Option<f32> {
+    if car.direction.is_vertical() == gate.inward.is_vertical() {
+        return None;
     }
-    false
+
+    let (dx, dy) = car.direction.to_vector();
+    let offset = gate.position - vec2(car.x(), car.y());
+    let ahead = offset.dot(vec2(dx, dy));
+    let beside = offset.perp_dot(vec2(dx, dy)).abs();
+    (beside < ROAD_WIDTH / 2.0 && ahead > -GATE_REACH).then_some(ahead)
+}
+
+/// Checks if a visiting car is standing at the gate of its destination
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `gate` - The gate to check
+///
+/// # Returns
+/// `true` if the car visits the gate's block and has stopped in front of it
+pub fn arrived_at_gate(car: &Car, gate: &Gate) -> bool {
+    car.destination == Some(gate.block_id)
+        && car.velocity < QUEUED_SPEED
+        && distance_to_gate(car, gate).is_some_and(|distance| distance.abs() < GATE_REACH)
+}
+
+/// Distance before a gate a visiting car drives up to (pixels)
+///
+/// Cars stop at the gate itself unless the compound is full; then they wait
+/// further back, leaving the road in front of the gate to cars leaving.
+fn gate_stop_distance(car: &Car, gate: &Gate) -> Option<f32> {
+    let distance = distance_to_gate(car, gate)?;
+    Some(if gate.full { distance - GATE_QUEUE_GAP } else { distance })
+}
+
+/// Checks if a visiting car must wait beside the road for its gate
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `stops` - Stops including the gates of guarded compounds
+///
+/// # Returns
+/// `true` if the car has stopped by a gate that can't let it in yet
+fn waiting_at_gate(car: &Car, stops: &Stops) -> bool {
+    stops.gate_for(car).is_some_and(|gate| {
+        let held = gate.full || gate.admitting.is_some_and(|id| id != car.id);
+        held && car.velocity < QUEUED_SPEED
+            && distance_to_gate(car, gate)
+                .is_some_and(|distance| distance < GATE_QUEUE_GAP + GATE_REACH)
+    })
+}
+
+/// Checks if a car should stop for pedestrians on a crosswalk
+///
+/// A pedestrian on a crosswalk across the car's road is a stop whatever the
+/// light shows, also for a crosswalk on the far side of an intersection the
+/// car is already in. The stop point lies `CROSSWALK_DISTANCE` past the
+/// crosswalk, so the car comes to rest as far before the crosswalk as it does
+/// before the near crosswalk at a red light.
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `pedestrians` - All pedestrians in the simulation
+///
+/// # Returns
+/// The distance left to the nearest stop line for a crosswalk, if one is ahead
+fn check_pedestrians(car: &Car, pedestrians: &[Pedestrian]) -> Option<f32> {
+    let (dx, dy) = car.direction.to_vector();
+    pedestrians
+        .iter()
+        // Only pedestrians walking across the car's road
+        .filter(|pedestrian| pedestrian.direction.is_vertical() != car.direction.is_vertical())
+        .filter_map(|pedestrian| pedestrian.crossing)
+        .filter_map(|crosswalk| {
+            distance_to_stop_line(
+                car,
+                crosswalk.x + dx * CROSSWALK_DISTANCE,
+                crosswalk.y + dy * CROSSWALK_DISTANCE,
+            )
+        })
+        .reduce(f32::min)
 }
 
 /// Checks if another car is currently occupying an intersection
@@ -99,7 +358,7 @@ fn check_traffic_light_at_intersection(
 /// * `car` - The car checking to enter
 /// * `intersection_x` - X position of intersection center
 /// * `intersection_y` - Y position of intersection center
-/// * `other_cars` - All other cars in the simulation
+/// * `traffic` - All cars in the simulation
 ///
 /// # Returns
 /// `true` if intersection is occupied by another car
@@ -107,12 +366,13 @@ fn check_intersection_occupied(
     car: &Car,
     intersection_x: f32,
     intersection_y: f32,
-    other_cars: &[Car],
+    traffic: &Traffic,
 ) -> bool {
     // Check if another car is already in this intersection
     let intersection_radius = INTERSECTION_RADIUS;
+    let center = vec2(intersection_x, intersection_y);
 
-    for other in other_cars {
+    for other in traffic.near(center, intersection_radius) {
         // Skip self
         if std::ptr::eq(car as *const Car, other as *const Car) {
             continue;
@@ -132,30 +392,60 @@ fn check_intersection_occupied(
     false
 }
 
-/// Checks if car is too close to another vehicle (collision avoidance)
+/// Distance between the centers of a car and the car ahead of it at which
+/// the car must stop following closer
+///
+/// Half of each car's length keeps their bodies from overlapping, and
+/// `FOLLOWING_GAP` is left between the bumpers, so a car behind a bus waits
+/// further back than one behind a sedan.
+///
+/// # Arguments
+/// * `car` - The following car
+/// * `leader` - The car ahead
+fn safe_following_distance(car: &Car, leader: &Car) -> f32 {
+    (car.length() + leader.length()) / 2.0 + FOLLOWING_GAP
+}
+
+/// Furthest a car ahead can be and still slow this car down (pixels)
+///
+/// Beyond its braking distance plus the safe following distance behind the
+/// longest vehicle, the car can always keep its top speed, so only cars
+/// within this reach need checking. A pixel is added for rounding.
+fn following_reach(car: &Car) -> f32 {
+    let longest = VehicleStyle::ALL
+        .iter()
+        .map(|style| style.size().0)
+        .fold(0.0, f32::max);
+    braking_distance(car.max_speed()) + (car.length() + longest) / 2.0 + FOLLOWING_GAP + 1.0
+}
+
+/// Returns the highest speed that keeps a safe distance to the car ahead
 ///
 /// Implements basic following distance and prevents rear-end collisions.
-/// Cars maintain a 50-pixel safe following distance.
+/// Cars keep a `FOLLOWING_GAP` between their bumpers and the car ahead; the
+/// allowed speed accounts for the leader's own speed, so a car closing on a
+/// slower car slows to match it rather than stopping dead.
 ///
 /// # Arguments
 /// * `car` - The car to check
-/// * `other_cars` - All other cars to check against
+/// * `traffic` - All cars to check against
+///
+/// Emergency vehicles don't queue behind cars pulling over for them.
 ///
 /// # Returns
-/// `true` if car should stop to avoid collision
-fn check_car_collision(car: &Car, other_cars: &[Car]) -> bool {
-    // Don't stop if car is in intersection - must complete crossing
+/// Speed limit in pixels per second (the car's top speed if the road ahead
+/// is clear)
+fn following_speed(car: &Car, traffic: &Traffic) -> f32 {
+    // Don't slow down if car is in intersection - must complete crossing
     if car.in_intersection {
-        return false;
+        return car.max_speed();
     }
 
-    // Minimum safe following distance in pixels
-    let safe_distance = SAFE_FOLLOWING_DISTANCE;
-
     let car_x = car.x();
     let car_y = car.y();
+    let mut max_speed = car.max_speed();
 
-    for other in other_cars {
+    for other in traffic.near(vec2(car_x, car_y), following_reach(car)) {
         // Skip self comparison
         if std::ptr::eq(car as *const Car, other as *const Car) {
             continue;
@@ -167,6 +457,21 @@ fn check_car_collision(car: &Car, other_cars: &[Car]) -> bool {
             continue;
         }
 
+        // Cars pulling over leave room for an emergency vehicle to pass
+        if car.vehicle_type == VehicleType::EmergencyVehicle && other.pull_over > 0.0 {
+            continue;
+        }
+
+        // Cars waiting beside the road for a compound to take them are passed,
+        // except by cars queueing for the same compound
+        if other.pull_over > 0.0
+            && car.pull_over == 0.0
+            && other.destination.is_some()
+            && other.destination != car.destination
+        {
+            continue;
+        }
+
         let other_x = other.x();
         let other_y = other.y();
 
@@ -203,45 +508,119 @@ fn check_car_collision(car: &Car, other_cars: &[Car]) -> bool {
                 }
             };
 
-            if distance > 0.0 && distance < safe_distance {
-                return true; // Too close to another car
+            if distance > 0.0 && distance < f32::MAX {
+                // Braking from this speed ends no closer than the safe distance,
+                // even if the car ahead brakes to a stop as well
+                let gap = distance - safe_following_distance(car, other);
+                let speed = (other.velocity.powi(2) + 2.0 * DECELERATION * gap).max(0.0).sqrt();
+                max_speed = max_speed.min(speed);
             }
         }
+    }
 
-        // Check cars going in opposite directions (avoid head-on collisions)
-        let is_opposite = match car.direction {
-            Direction::Down => other.direction == Direction::Up,
-            Direction::Up => other.direction == Direction::Down,
-            Direction::Right => other.direction == Direction::Left,
-            Direction::Left => other.direction == Direction::Right,
-        };
+    max_speed
+}
 
-        if is_opposite {
-            // Check if cars are on the same road and close to each other
-            let (on_same_road, distance) = match car.direction {
-                Direction::Down | Direction::Up => {
-                    // Check if on same vertical road
-                    let on_same = (car_x - other_x).abs() < ROAD_WIDTH / 2.0;
-                    let dist = (car_y - other_y).abs();
-                    (on_same, dist)
-                }
-                Direction::Right | Direction::Left => {
-                    // Check if on same horizontal road
-                    let on_same = (car_y - other_y).abs() < ROAD_WIDTH / 2.0;
-                    let dist = (car_x - other_x).abs();
-                    (on_same, dist)
-                }
-            };
+/// Checks if a car must pull over for an emergency vehicle behind it
+///
+/// Civilian cars yield to emergency vehicles travelling the same way in
+/// their lane within `EMERGENCY_YIELD_DISTANCE`, until the vehicle has
+/// completely passed them. Cars crossing an intersection finish crossing
+/// first.
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `traffic` - All cars, including any emergency vehicles
+///
+/// # Returns
+/// `true` if the car should pull over and stop
+fn yielding_to_emergency(car: &Car, traffic: &Traffic) -> bool {
+    let emergency = car.vehicle_type == VehicleType::EmergencyVehicle;
+    if emergency || car.in_intersection || car.turn.is_some() {
+        return false;
+    }
 
-            if on_same_road && distance < safe_distance {
-                // Cars need to stay on their side of the road
-                // Shift to the right side of the road (relative to direction)
-                return false; // Don't stop, but we'll handle lane separation differently
-            }
+    let (dx, dy) = car.direction.to_vector();
+    let forward = vec2(dx, dy);
+
+    let reach = EMERGENCY_YIELD_DISTANCE + LANE_TOLERANCE;
+    traffic.near(vec2(car.x(), car.y()), reach).any(|other| {
+        if other.vehicle_type != VehicleType::EmergencyVehicle || other.direction != car.direction {
+            return false;
         }
+        // How far this car is ahead of the emergency vehicle, and beside it
+        let offset = vec2(car.x() - other.x(), car.y() - other.y());
+        let ahead = offset.dot(forward);
+        let beside = offset.perp_dot(forward).abs();
+        // Until the emergency vehicle's rear has passed this car's front
+        let passed = -(car.length() + other.length()) / 2.0;
+        beside < LANE_TOLERANCE && ahead > passed && ahead <= EMERGENCY_YIELD_DISTANCE
+    })
+}
+
+/// Checks if an emergency vehicle should turn an intersection's light green
+///
+/// Emergency vehicles preempt the light of an intersection ahead of them on
+/// their road once within `EMERGENCY_PREEMPT_DISTANCE`, so cross traffic is
+/// stopped by the time they arrive.
+///
+/// # Arguments
+/// * `car` - The vehicle to check
+/// * `intersection` - The intersection to check
+///
+/// # Returns
+/// `true` if `car` is an emergency vehicle approaching `intersection`
+pub fn preempts_light(car: &Car, intersection: &Intersection) -> bool {
+    if car.vehicle_type != VehicleType::EmergencyVehicle || car.turn.is_some() {
+        return false;
     }
 
-    false
+    let (dx, dy) = car.direction.to_vector();
+    let offset = vec2(intersection.x() - car.x(), intersection.y() - car.y());
+    let ahead = offset.dot(vec2(dx, dy));
+    let beside = offset.perp_dot(vec2(dx, dy)).abs();
+    beside < LANE_TOLERANCE && ahead > 0.0 && ahead <= EMERGENCY_PREEMPT_DISTANCE
+}
+
+/// Checks if a car is stopped in the queue before an intersection
+///
+/// Cars count as queued while standing (below `QUEUED_SPEED`) in a lane
+/// leading into the intersection, no further than `STOP_DISTANCE_MAX` before
+/// its center. Cars inside it or turning are crossing, not queued.
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `intersection` - The intersection to check
+///
+/// # Returns
+/// `true` if `car` is waiting to enter `intersection`
+pub fn is_queued_at(car: &Car, intersection: &Intersection) -> bool {
+    if car.velocity >= QUEUED_SPEED || car.in_intersection || car.turn.is_some() {
+        return false;
+    }
+
+    let (dx, dy) = car.direction.to_vector();
+    let offset = vec2(intersection.x() - car.x(), intersection.y() - car.y());
+    let ahead = offset.dot(vec2(dx, dy));
+    let beside = offset.perp_dot(vec2(dx, dy)).abs();
+    beside < LANE_TOLERANCE && ahead > 0.0 && ahead <= STOP_DISTANCE_MAX
+}
+
+/// Returns how far a car driving up to an intersection is from its stop line
+///
+/// # Arguments
+/// * `car` - The car to check
+/// * `intersection` - The intersection to check
+///
+/// # Returns
+/// The remaining distance to the stop line (negative once past it), or `None`
+/// if the car is not in a lane leading into the intersection. Cars inside it
+/// or turning are crossing, not approaching.
+pub fn stop_line_gap(car: &Car, intersection: &Intersection) -> Option<f32> {
+    if car.in_intersection || car.turn.is_some() {
+        return None;
+    }
+    lane_gap(car, intersection.x(), intersection.y())
 }
 
 // ============================================================================
@@ -281,86 +660,458 @@ fn plan_next_turn(current_direction: Direction) -> Option<Direction> {
     }
 }
 
-/// Handles car turning at intersection center
+/// Returns the point on a direction's lane `along` pixels past an
+/// intersection center (negative = before it)
+fn lane_point(center: Vec2, direction: Direction, along: f32, rule: TrafficRule) -> Vec2 {
+    let (dx, dy) = direction.to_vector();
+    center + vec2(dx, dy) * along + rule.lane_offset(direction)
+}
+
+/// Builds the arc a car follows to turn from one lane onto another
+///
+/// The arc starts `TURN_ENTRY_DISTANCE` before the intersection center on
+/// the entry lane and ends as far past it on the exit lane. Both ends are
+/// equally far from the corner where the two lanes cross, so the arc is a
+/// quarter circle.
+///
+/// # Arguments
+/// * `intersection_center` - Intersection center in pixels
+/// * `from` - Direction of travel on the entry lane
+/// * `to` - Direction of travel on the exit lane (perpendicular to `from`)
+/// * `rule` - Side of the road both lanes are on
+fn turn_path(
+    intersection_center: Vec2,
+    from: Direction,
+    to: Direction,
+    rule: TrafficRule,
+) -> TurnPath {
+    let start = lane_point(intersection_center, from, -TURN_ENTRY_DISTANCE, rule);
+    let end = lane_point(intersection_center, to, TURN_ENTRY_DISTANCE, rule);
+    let corner = intersection_center + rule.lane_offset(from) + rule.lane_offset(to);
+    TurnPath::new(start, corner, end, to)
+}
+
+/// Distance from a roundabout's center at which a lane meets the circle
+/// cars drive around it (pixels)
+///
+/// # Arguments
+/// * `radius` - Radius of the circle in pixels
+fn roundabout_reach(radius: f32) -> f32 {
+    (radius * radius - LANE_OFFSET * LANE_OFFSET).max(0.0).sqrt()
+}
+
+/// Returns where a lane enters a roundabout and which way cars circle it
+///
+/// Cars circle the way the entering car is already heading: along the
+/// circle's tangent at the entry point that points forward.
+///
+/// # Arguments
+/// * `radius` - Radius of the circle in pixels
+/// * `direction` - Direction of travel on the entry lane
+/// * `rule` - Side of the road the lane is on
+///
+/// # Returns
+/// The entry point relative to the roundabout center, and the sense cars
+/// circle in (1.0 = increasing angle, clockwise on screen; -1.0 = the other
+/// way)
+fn roundabout_entry(radius: f32, direction: Direction, rule: TrafficRule) -> (Vec2, f32) {
+    let entry = lane_point(Vec2::ZERO, direction, -roundabout_reach(radius), rule);
+    let (dx, dy) = direction.to_vector();
+    (entry, entry.perp_dot(vec2(dx, dy)).signum())
+}
+
+/// Builds the path a car follows around a roundabout
+///
+/// The path runs along the circle, the way traffic circles, from where the
+/// entry lane meets it to where the exit lane leaves it, with a point every
+/// `ROUNDABOUT_STEP`.
+///
+/// # Arguments
+/// * `center` - Roundabout center in pixels
+/// * `radius` - Radius of the circle cars drive along
+/// * `from` - Direction of travel on the entry lane
+/// * `to` - Direction of travel on the exit lane (any but back the way the
+///   car came)
+/// * `rule` - Side of the road both lanes are on
+fn roundabout_path(
+    center: Vec2,
+    radius: f32,
+    from: Direction,
+    to: Direction,
+    rule: TrafficRule,
+) -> RoundaboutPath {
+    let (entry, sense) = roundabout_entry(radius, from, rule);
+    let exit = lane_point(Vec2::ZERO, to, roundabout_reach(radius), rule);
+    let start_angle = entry.to_angle();
+    let sweep = ((exit.to_angle() - start_angle) * sense).rem_euclid(std::f32::consts::TAU);
+    let steps = (sweep / ROUNDABOUT_STEP).ceil().max(1.0) as usize;
+
+    let path = (0..=steps)
+        .map(|step| {
+            let angle = start_angle + sense * sweep * step as f32 / steps as f32;
+            (center + Vec2::from_angle(angle) * radius).into()
+        })
+        .collect();
+    RoundaboutPath {
+        path,
+        exit_direction: to,
+    }
+}
+
+/// Checks if a car about to enter a roundabout must give way
+///
+/// Cars circling toward the car's entry point, no more than
+/// `ROUNDABOUT_GIVE_WAY_ANGLE` before reaching it, have the right of way.
+///
+/// # Arguments
+/// * `car` - The car waiting to enter
+/// * `intersection` - The roundabout
+/// * `radius` - Radius of the circle cars drive along
+/// * `traffic` - All cars in the simulation
+fn must_give_way(car: &Car, intersection: &Intersection, radius: f32, traffic: &Traffic) -> bool {
+    let center = vec2(intersection.x(), intersection.y());
+    let (entry, sense) = roundabout_entry(radius, car.direction, car.traffic_rule);
+    let reach = radius + LANE_TOLERANCE;
+
+    traffic.near(center, reach).any(|other| {
+        let circling = matches!(
+            &other.turn,
+            Some(TurnProgress { path: CrossingPath::Roundabout(_), .. })
+        );
+        let offset = vec2(other.x(), other.y()) - center;
+        // Angle the other car still circles before it passes the entry
+        let to_entry = ((entry.to_angle() - offset.to_angle()) * sense)
+            .rem_euclid(std::f32::consts::TAU);
+        circling && offset.length() < reach && to_entry <= ROUNDABOUT_GIVE_WAY_ANGLE
+    })
+}
+
+/// Starts the planned turn when the car reaches the start of the turn arc
 ///
-/// Executes the planned turn when the car reaches the intersection center,
-/// adjusts the car's position to the correct lane for the new direction,
-/// and plans the next turn.
+/// At a roundabout every car starts its path around the island where its
+/// lane meets the circle, and one without a planned turn leaves it straight
+/// ahead.
 ///
 /// # Arguments
 /// * `car` - The car to potentially turn
 /// * `intersection` - The intersection where turning might occur
-/// * `at_intersection_center` - Whether the car is at the intersection center
 ///
 /// # Returns
-/// `true` if a turn was executed, `false` otherwise
-fn handle_car_turn(car: &mut Car, intersection: &Intersection, at_intersection_center: bool) -> bool {
-    if at_intersection_center && car.next_turn.is_some() && !car.just_turned {
-        // Execute the turn
-        let new_direction = car.next_turn.unwrap();
-        car.direction = new_direction;
-
-        // Adjust position to new lane (left-hand traffic)
-        match new_direction {
-            Direction::Down => {
-                car.x_percent = intersection.x_percent - (LANE_OFFSET / screen_width());
-                car.y_percent = intersection.y_percent;
-            }
-            Direction::Up => {
-                car.x_percent = intersection.x_percent + (LANE_OFFSET / screen_width());
-                car.y_percent = intersection.y_percent;
-            }
-            Direction::Right => {
-                car.x_percent = intersection.x_percent;
-                car.y_percent = intersection.y_percent + (LANE_OFFSET / screen_height());
-            }
-            Direction::Left => {
-                car.x_percent = intersection.x_percent;
-                car.y_percent = intersection.y_percent - (LANE_OFFSET / screen_height());
+/// `true` if the car started turning, `false` otherwise
+fn start_car_turn(car: &mut Car, intersection: &Intersection) -> bool {
+    if car.turn.is_some() || car.just_turned {
+        return false;
+    }
+    let radius = intersection.roundabout_radius();
+    let Some(exit_direction) = car.next_turn.or(radius.map(|_| car.direction)) else {
+        return false;
+    };
+    let entry_distance = radius.map_or(TURN_ENTRY_DISTANCE, roundabout_reach);
+
+    // Distance to the intersection center ahead of the car and to the side
+    let (dx, dy) = car.direction.to_vector();
+    let to_center = vec2(intersection.x() - car.x(), intersection.y() - car.y());
+    let ahead = to_center.dot(vec2(dx, dy));
+    let beside = to_center.perp_dot(vec2(dx, dy)).abs();
+
+    let at_turn_start = beside < 15.0 && ahead <= entry_distance && ahead > entry_distance - 10.0;
+    if !at_turn_start {
+        return false;
+    }
+
+    let center = vec2(intersection.x(), intersection.y());
+    let path: CrossingPath = match radius {
+        Some(radius) => {
+            roundabout_path(center, radius, car.direction, exit_direction, car.traffic_rule).into()
+        }
+        None => turn_path(center, car.direction, exit_direction, car.traffic_rule).into(),
+    };
+    let start = path.position_at(0.0);
+    car.set_x(start.x);
+    car.set_y(start.y);
+    car.pull_over = 0.0;
+    car.turn = Some(TurnProgress { path, t: 0.0 });
+    car.in_intersection = true;
+    true
+}
+
+/// Turns a car's turn signal on as it nears the intersection where it turns
+///
+/// The signal comes on within `TURN_SIGNAL_DISTANCE` before the center of
+/// the next intersection ahead in the car's lane, and stays on while the car
+/// turns.
+///
+/// # Arguments
+/// * `car` - The car to update
+/// * `intersections` - All intersections in the simulation
+fn update_turn_signal(car: &mut Car, intersections: &[Intersection]) {
+    if car.turn.is_some() {
+        return;
+    }
+
+    let (dx, dy) = car.direction.to_vector();
+    let forward = vec2(dx, dy);
+    let position = vec2(car.x(), car.y());
+    let nearing_intersection = intersections.iter().any(|intersection| {
+        let offset = vec2(intersection.x(), intersection.y()) - position;
+        let ahead = offset.dot(forward);
+        offset.perp_dot(forward).abs() < LANE_TOLERANCE
+            && ahead > 0.0
+            && ahead <= TURN_SIGNAL_DISTANCE
+    });
+
+    car.turn_signal = car
+        .next_turn
+        .filter(|_| nearing_intersection)
+        .and_then(|exit| TurnSignalState::for_turn(car.direction, exit));
+}
+
+/// Moves a turning car along its path, finishing the turn at the path's end
+///
+/// On finishing, the car drives on in the exit direction with its turn
+/// signal off and plans its next turn.
+///
+/// # Arguments
+/// * `car` - The turning car
+/// * `turn` - The car's turn progress
+/// * `dt` - Delta time (frame duration in seconds)
+fn advance_car_turn(car: &mut Car, mut turn: TurnProgress, dt: f32) {
+    turn.t += car.velocity * dt / turn.path.length();
+    let position = turn.path.position_at(turn.t);
+    car.set_x(position.x);
+    car.set_y(position.y);
+
+    if turn.t >= 1.0 {
+        car.direction = turn.path.exit_direction();
+        car.turn_signal = None;
+        car.next_turn = plan_next_turn(car.direction);
+        car.just_turned = true;
+        car.turn = None;
+    } else {
+        car.turn = Some(turn);
+    }
+}
+
+/// Direction along one axis, toward the side `amount` points to
+///
+/// # Arguments
+/// * `vertical` - true for Down or Up, false for Right or Left
+/// * `amount` - Signed distance along the axis (positive = Down or Right)
+fn toward(vertical: bool, amount: f32) -> Direction {
+    match (vertical, amount > 0.0) {
+        (true, true) => Direction::Down,
+        (true, false) => Direction::Up,
+        (false, true) => Direction::Right,
+        (false, false) => Direction::Left,
+    }
+}
+
+/// Picks the turn that brings a visiting car closer to its gate
+///
+/// Cars drive straight along the gate's road toward the gate. Elsewhere they
+/// turn onto the gate's road where they cross it, heading for the gate, or
+/// turn toward it at the next intersection if they are heading away. Cars
+/// that passed the gate circle the compound's block to come back.
+///
+/// # Arguments
+/// * `car` - The visiting car
+/// * `intersections` - All intersections, to find the next one ahead
+/// * `gate` - Gate of the car's destination
+///
+/// # Returns
+/// The turn to take at the next intersection (None = go straight)
+fn route_turn(car: &Car, intersections: &[Intersection], gate: &Gate) -> Option<Direction> {
+    let (dx, dy) = car.direction.to_vector();
+    let forward = vec2(dx, dy);
+    let position = vec2(car.x(), car.y());
+    let to_gate = gate.position - position;
+    let road_vertical = !gate.inward.is_vertical();
+    // Distance to the gate across its road, and along it
+    let (across, along) = if road_vertical {
+        (to_gate.x, to_gate.y)
+    } else {
+        (to_gate.y, to_gate.x)
+    };
+    let heading_to_gate = to_gate.dot(forward) > 0.0;
+
+    if car.direction.is_vertical() == road_vertical {
+        // Parallel to the gate's road
+        if across.abs() < ROAD_WIDTH / 2.0 {
+            return if heading_to_gate { None } else { Some(gate.inward) };
+        }
+        return Some(toward(!road_vertical, across));
+    }
+
+    // Crossing the gate's road: turn onto it at the intersection where they meet
+    let next_intersection = intersections
+        .iter()
+        .map(|intersection| vec2(intersection.x(), intersection.y()) - position)
+        .filter(|offset| {
+            offset.perp_dot(forward).abs() < LANE_TOLERANCE
+                && offset.dot(forward) > TURN_ENTRY_DISTANCE - 10.0
+        })
+        .min_by(|a, b| a.dot(forward).total_cmp(&b.dot(forward)))?;
+    let on_gate_road = (next_intersection - to_gate).dot(forward).abs() < ROAD_WIDTH / 2.0;
+    (on_gate_road || !heading_to_gate).then(|| toward(road_vertical, along))
+}
+
+/// Sets up a routed car's turn at the next intersection on its route
+///
+/// Intersections the car has driven through are dropped from the front of
+/// the route. The turn is the direction from the next intersection to the
+/// one after it; at the last one the car goes straight, and once it has
+/// passed that too it goes back to turning at random.
+///
+/// # Arguments
+/// * `car` - The routed car, not in the middle of a turn
+/// * `intersections` - All intersections, to find the ones on the route
+fn follow_route(car: &mut Car, intersections: &[Intersection]) {
+    let center = |id: usize| {
+        intersections
+            .iter()
+            .find(|intersection| intersection.id == id)
+            .map(|intersection| vec2(intersection.x(), intersection.y()))
+    };
+    let (dx, dy) = car.direction.to_vector();
+    let forward = vec2(dx, dy);
+    let position = vec2(car.x(), car.y());
+
+    while let Some(&next) = car.route.first() {
+        match center(next) {
+            Some(point) if (point - position).dot(forward) >= 0.0 => break,
+            _ => {
+                car.route.remove(0);
             }
         }
+    }
+
+    car.next_turn = match car.route.as_slice() {
+        [] => plan_next_turn(car.direction),
+        [next, after, ..] => match (center(*next), center(*after)) {
+            (Some(from), Some(to)) => {
+                let offset = to - from;
+                let vertical = offset.y.abs() > offset.x.abs();
+                let exit = toward(vertical, if vertical { offset.y } else { offset.x });
+                (exit != car.direction).then_some(exit)
+            }
+            _ => None,
+        },
+        [_] => None,
+    };
+}
 
-        // Plan next turn
-        car.next_turn = plan_next_turn(new_direction);
+/// Point in the lane for `direction` in front of a gate (pixels)
+///
+/// # Arguments
+/// * `gate` - The gate
+/// * `direction` - Direction of travel along the gate's road
+/// * `rule` - Side of the road the car keeps to
+pub fn lane_at_gate(gate: &Gate, direction: Direction, rule: TrafficRule) -> Vec2 {
+    gate.position + rule.lane_offset(direction)
+}
 
-        // Mark that we just turned
-        car.just_turned = true;
-        true
-    } else {
-        false
-    }
+/// Puts a car leaving a compound onto the road in front of its gate
+///
+/// The car starts standing in the lane for its direction, just passing
+/// through, and goes straight at the next intersection.
+///
+/// # Arguments
+/// * `car` - The car leaving
+/// * `gate` - Gate the car leaves through
+/// * `direction` - Direction to drive off in, along the gate's road
+pub fn join_road(car: &mut Car, gate: &Gate, direction: Direction) {
+    let lane = lane_at_gate(gate, direction, car.traffic_rule);
+    car.set_x(lane.x);
+    car.set_y(lane.y);
+    car.direction = direction;
+    car.velocity = 0.0;
+    car.target_velocity = 0.0;
+    car.road_index = gate.road_index;
+    car.location = CarLocation::OnRoad { road_id: gate.road_index };
+    car.destination = None;
+    car.next_turn = None;
+    car.just_turned = false;
+    car.in_intersection = false;
+    car.turn = None;
+    car.pull_over = 0.0;
 }
 
-/// Moves the car based on its direction and speed
+/// Moves the car based on its direction and velocity
 ///
 /// Updates the car's position based on its current direction of travel
 /// and the frame delta time. Movement is calculated as percentage of
-/// screen dimensions for responsive scaling.
+/// screen dimensions for responsive scaling. Turning cars move along their
+/// turn arc instead.
 ///
 /// # Arguments
 /// * `car` - The car to move
 /// * `dt` - Delta time (frame duration in seconds)
 fn move_car(car: &mut Car, dt: f32) {
+    if let Some(turn) = car.turn.take() {
+        advance_car_turn(car, turn, dt);
+        return;
+    }
+
     match car.direction {
         Direction::Down => {
-            let speed_percent = CAR_SPEED * dt / screen_height();
+            let speed_percent = car.velocity * dt / screen_height();
             car.y_percent += speed_percent;
         }
         Direction::Up => {
-            let speed_percent = CAR_SPEED * dt / screen_height();
+            let speed_percent = car.velocity * dt / screen_height();
             car.y_percent -= speed_percent;
         }
         Direction::Right => {
-            let speed_percent = CAR_SPEED * dt / screen_width();
+            let speed_percent = car.velocity * dt / screen_width();
             car.x_percent += speed_percent;
         }
         Direction::Left => {
-            let speed_percent = CAR_SPEED * dt / screen_width();
+            let speed_percent = car.velocity * dt / screen_width();
             car.x_percent -= speed_percent;
         }
     }
 }
 
+/// Moves a car toward the road edge while it yields to an emergency vehicle,
+/// and back into its lane afterwards
+///
+/// # Arguments
+/// * `car` - The car to update
+/// * `yielding` - Whether the car is currently yielding
+/// * `dt` - Delta time (frame duration in seconds)
+fn update_pull_over(car: &mut Car, yielding: bool, dt: f32) {
+    let target = if yielding { PULL_OVER_OFFSET } else { 0.0 };
+    let max_step = PULL_OVER_SPEED * dt;
+    let step = (target - car.pull_over).clamp(-max_step, max_step);
+    if step == 0.0 {
+        return;
+    }
+
+    car.pull_over += step;
+    let toward_edge = car.traffic_rule.lane_offset(car.direction).normalize() * step;
+    car.set_x(car.x() + toward_edge.x);
+    car.set_y(car.y() + toward_edge.y);
+}
+
+/// Brings the car's velocity toward its `target_velocity` for one frame
+///
+/// Cars accelerate at `ACCELERATION` and slow down to the target at once.
+/// Because stop constraints are expressed through `stopping_speed`, a car
+/// that notices a stop at its braking distance ends up decelerating at
+/// `DECELERATION`; harder braking only happens when a stop appears closer
+/// than that (e.g. a light turning yellow just ahead).
+///
+/// # Arguments
+/// * `car` - The car to update
+/// * `dt` - Delta time (frame duration in seconds)
+fn update_car_velocity(car: &mut Car, dt: f32) {
+    car.velocity = if car.target_velocity < car.velocity {
+        car.target_velocity
+    } else {
+        (car.velocity + ACCELERATION * dt).min(car.target_velocity)
+    };
+}
+
 /// Checks if a car is still on screen
 ///
 /// Cars are kept slightly off-screen (0.1 buffer) to allow smooth
@@ -375,12 +1126,70 @@ fn is_car_on_screen(car: &Car) -> bool {
     car.x_percent > -0.1 && car.x_percent < 1.1 && car.y_percent > -0.1 && car.y_percent < 1.1
 }
 
+/// What happens to cars that drive off the edge of the screen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeBehavior {
+    /// Cars are removed, and the spawner keeps traffic coming
+    Despawn,
+
+    /// Cars on a road that continues re-enter from the opposite edge
+    #[default]
+    Wrap,
+}
+
+/// Moves a car that drove off the screen back to the opposite edge
+///
+/// The car re-enters on the same road and lane, keeping its color, speed and
+/// planned turn. Only civilian cars driving straight along one of `roads`
+/// wrap; emergency vehicles, which are dispatched for a single run, and cars
+/// off the road network are left to despawn.
+///
+/// # Arguments
+/// * `car` - The car to wrap
+/// * `roads` - Roads of the city
+///
+/// # Returns
+/// `true` if the car was moved
+pub fn wrap_at_edge<'a>(car: &mut Car, roads: impl IntoIterator<Item = &'a Road>) -> bool {
+    if is_car_on_screen(car) || car.vehicle_type != VehicleType::Car || car.turn.is_some() {
+        return false;
+    }
+
+    let (orientation, across) = if car.direction.is_vertical() {
+        (Orientation::Vertical, car.x())
+    } else {
+        (Orientation::Horizontal, car.y())
+    };
+    let Some(road) = roads.into_iter().find(|road| {
+        let center = match road.orientation {
+            Orientation::Vertical => road.position_percent * screen_width(),
+            Orientation::Horizontal => road.position_percent * screen_height(),
+        };
+        road.orientation == orientation && (across - center).abs() < LANE_TOLERANCE
+    }) else {
+        return false;
+    };
+
+    // Just off screen on the far side, where spawned cars start
+    match car.direction {
+        Direction::Down => car.y_percent = -0.05,
+        Direction::Up => car.y_percent = 1.05,
+        Direction::Right => car.x_percent = -0.05,
+        Direction::Left => car.x_percent = 1.05,
+    }
+    car.road_index = road.index;
+    car.location = CarLocation::OnRoad { road_id: road.index };
+    car.just_turned = false;
+    car.in_intersection = false;
+    true
+}
+
 /// Updates car state at intersections and handles turning
 ///
 /// Checks all intersections to:
 /// - Update car's intersection state (in_intersection flag)
-/// - Check if car is approaching intersection center
-/// - Handle turning if at intersection center
+/// - Start the planned turn when the car reaches the start of the turn arc
 ///
 /// # Arguments
 /// * `car` - The car to update
@@ -407,89 +1216,123 @@ fn update_car_at_intersection(car: &mut Car, intersections: &[Intersection]) ->
             car.in_intersection = true;
         }
 
-        // Check for turning at intersection center
-        let at_intersection_center = match car.direction {
-            Direction::Down => (car_x - int_x).abs() < 15.0 && (car_y - int_y).abs() < 10.0,
-            Direction::Up => (car_x - int_x).abs() < 15.0 && (car_y - int_y).abs() < 10.0,
-            Direction::Right => (car_y - int_y).abs() < 15.0 && (car_x - int_x).abs() < 10.0,
-            Direction::Left => (car_y - int_y).abs() < 15.0 && (car_x - int_x).abs() < 10.0,
-        };
-
-        if handle_car_turn(car, intersection, at_intersection_center) {
-            return (at_any_intersection, true); // Turned at this intersection
+        if start_car_turn(car, intersection) {
+            return (true, true); // Started turning at this intersection
         }
     }
 
     (at_any_intersection, false)
 }
 
-/// Determines if a car should stop based on all conditions
+/// Determines how fast a car may drive based on all conditions
 ///
 /// Checks multiple stop conditions:
-/// - Traffic lights at intersections
+/// - Closed barriers
+/// - The gate of the compound a visiting car drives to
+/// - Pedestrians on crosswalks (even on green)
+/// - Traffic lights at intersections (except for emergency vehicles)
+/// - All-way stops until it is the car's turn (except for emergency vehicles)
+/// - Cars circling a roundabout (except for emergency vehicles)
 /// - Occupied intersections (prevent gridlock)
 /// - Collision avoidance with other cars
 ///
+/// Each stop within braking distance limits the speed to one the car can
+/// still stop from before its stop line.
+///
 /// # Arguments
 /// * `car` - The car to check
 /// * `intersections` - All intersections with traffic lights
-/// * `other_cars` - All other cars for collision checking
+/// * `stops` - Barriers blocking traffic and gates of guarded compounds
+/// * `pedestrians` - All pedestrians, for occupied crosswalks
+/// * `traffic` - All cars for collision checking
 /// * `all_lights_red` - Emergency mode (all lights red)
 ///
 /// # Returns
-/// `true` if car should stop, `false` if car can proceed
-fn should_car_stop(
+/// Target speed in pixels per second (0.0 = stop now, the car's top speed =
+/// road is clear)
+fn target_speed(
     car: &Car,
     intersections: &[Intersection],
-    other_cars: &[Car],
+    stops: &Stops,
+    pedestrians: &[Pedestrian],
+    traffic: &Traffic,
     all_lights_red: bool,
-) -> bool {
-    let car_x = car.x();
-    let car_y = car.y();
+) -> f32 {
+    let mut target = car.max_speed();
+
+    if let Some(gap) = check_barriers(car, &stops.barriers) {
+        target = target.min(stopping_speed(gap));
+    }
+
+    if let Some(gap) = stops.gate_for(car).and_then(|gate| gate_stop_distance(car, gate)) {
+        target = target.min(stopping_speed(gap));
+    }
+
+    if let Some(gap) = check_pedestrians(car, pedestrians) {
+        target = target.min(stopping_speed(gap));
+    }
 
     // Check all intersections for stop conditions
     for intersection in intersections {
         let int_x = intersection.x();
         let int_y = intersection.y();
 
-        // Get traffic light state
+        // Get traffic light state (None = intersection has no signal)
         let light_state = if all_lights_red {
-            0 // All lights red
+            Some(LightState::default_red())
         } else {
             intersection.get_light_state_for_direction(car.direction)
         };
 
         // Check if we should stop for traffic light
-        if check_traffic_light_at_intersection(car, int_x, int_y, light_state) {
-            return true;
+        if let Some(light_state) = light_state
+            && let Some(gap) = check_traffic_light_at_intersection(car, int_x, int_y, light_state)
+        {
+            target = target.min(stopping_speed(gap));
         }
 
-        // Check if intersection is occupied (before entering)
-        if !car.in_intersection {
-            let approaching_intersection = match car.direction {
-                Direction::Down => {
-                    (car_x - int_x).abs() < 20.0 && int_y > car_y && (int_y - car_y) < 50.0
-                }
-                Direction::Up => {
-                    (car_x - int_x).abs() < 20.0 && int_y < car_y && (car_y - int_y) < 50.0
-                }
-                Direction::Right => {
-                    (car_y - int_y).abs() < 20.0 && int_x > car_x && (int_x - car_x) < 50.0
-                }
-                Direction::Left => {
-                    (car_y - int_y).abs() < 20.0 && int_x < car_x && (car_x - int_x) < 50.0
-                }
-            };
+        // Wait at an all-way stop until the cars that came first have gone
+        if intersection.is_all_way_stop()
+            && car.vehicle_type != VehicleType::EmergencyVehicle
+            && !car.in_intersection
+            && !intersection.may_proceed(car.id)
+            && let Some(gap) = distance_to_stop_line(car, int_x, int_y)
+        {
+            target = target.min(stopping_speed(gap));
+        }
 
-            if approaching_intersection && check_intersection_occupied(car, int_x, int_y, other_cars)
-            {
-                return true;
-            }
+        // A closed road is a red light that never turns green
+        if car.vehicle_type != VehicleType::EmergencyVehicle
+            && !car.in_intersection
+            && stops.closed_at(car, intersection)
+            && let Some(gap) = distance_to_stop_line(car, int_x, int_y)
+        {
+            target = target.min(stopping_speed(gap));
+        }
+
+        // Give way to the cars circling a roundabout toward the entry
+        if let Some(radius) = intersection.roundabout_radius()
+            && car.vehicle_type != VehicleType::EmergencyVehicle
+            && !car.in_intersection
+            && let Some(gap) = distance_to_stop_line(car, int_x, int_y)
+            && must_give_way(car, intersection, radius, traffic)
+        {
+            target = target.min(stopping_speed(gap));
+        }
+
+        // Check if intersection is occupied (before entering); roundabouts
+        // take several cars at once
+        if intersection.roundabout_radius().is_none()
+            && !car.in_intersection
+            && let Some(gap) = distance_to_stop_line(car, int_x, int_y)
+            && check_intersection_occupied(car, int_x, int_y, traffic)
+        {
+            target = target.min(stopping_speed(gap));
         }
     }
 
     // Check for collision with other cars
-    check_car_collision(car, other_cars)
+    target.min(following_speed(car, traffic))
 }
 
 // ============================================================================
@@ -501,12 +1344,16 @@ fn should_car_stop(
 /// This allows us to separate decision-making (which needs to read all cars)
 /// from position updates (which needs to write to cars), eliminating the
 /// need to clone the entire cars vector.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 struct CarDecision {
-    /// Whether the car should stop this frame
-    should_stop: bool,
-    /// Whether the car is at any intersection
+    /// Speed the car should drive at this frame (pixels per second)
+    target_speed: f32,
+    /// Whether the car is at any intersection (always true while turning)
     at_any_intersection: bool,
+    /// Whether the car is pulling over for an emergency vehicle
+    yielding: bool,
+    /// Whether the car is pulled over, waiting for a compound to take it
+    waiting: bool,
     /// Whether the car is still on screen (false = should be removed)
     is_on_screen: bool,
 }
@@ -518,25 +1365,30 @@ struct CarDecision {
 ///
 /// # Arguments
 /// * `car` - The car to calculate decisions for
-/// * `all_cars` - All cars (for collision checking)
+/// * `traffic` - All cars (for collision checking)
 /// * `intersections` - All intersections with traffic lights
+/// * `stops` - Barriers blocking traffic and gates of guarded compounds
+/// * `pedestrians` - All pedestrians, for occupied crosswalks
 /// * `all_lights_red` - Emergency mode flag
 ///
 /// # Returns
 /// CarDecision containing what the car should do this frame
 fn calculate_car_decision(
     car: &Car,
-    all_cars: &[Car],
+    traffic: &Traffic,
     intersections: &[Intersection],
+    stops: &Stops,
+    pedestrians: &[Pedestrian],
     all_lights_red: bool,
 ) -> CarDecision {
-    // Check stop conditions (traffic lights, collisions, etc.)
-    let should_stop = should_car_stop(car, intersections, all_cars, all_lights_red);
+    // Check stop conditions (barriers, pedestrians, traffic lights, collisions, etc.)
+    let target_speed =
+        target_speed(car, intersections, stops, pedestrians, traffic, all_lights_red);
 
     // Check if car is at any intersection
     let car_x = car.x();
     let car_y = car.y();
-    let mut at_any_intersection = false;
+    let mut at_any_intersection = car.turn.is_some();
 
     for intersection in intersections {
         let int_x = intersection.x();
@@ -554,44 +1406,84 @@ fn calculate_car_decision(
     let is_on_screen = is_car_on_screen(car);
 
     CarDecision {
-        should_stop,
+        target_speed,
         at_any_intersection,
+        yielding: yielding_to_emergency(car, traffic),
+        waiting: waiting_at_gate(car, stops),
         is_on_screen,
     }
 }
 
+/// Per-frame counters reported by `update_cars`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CarUpdateStats {
+    /// Cars standing still this frame (lights, traffic, emergency mode)
+    pub stopped: usize,
+
+    /// Cars inside an intersection
+    pub in_intersection: usize,
+
+    /// Cars removed this frame for leaving the screen
+    pub despawned: usize,
+}
+
 /// Updates all cars' positions and behaviors for one frame
 ///
 /// This is the main simulation loop that handles:
-/// - Traffic light compliance
+/// - Braking and acceleration
+/// - Traffic light and barrier compliance
+/// - Stopping for pedestrians on crosswalks
 /// - Collision avoidance
 /// - Intersection navigation and turning
+/// - Routing visiting cars to their compound's gate
+/// - Pulling over for emergency vehicles and full compounds
 /// - Car removal when off-screen
 ///
 /// Uses a two-pass approach to avoid cloning the cars vector:
-/// 1. Read-only pass: Calculate decisions for all cars
+/// 1. Read-only pass: Index the cars in a spatial grid and calculate
+///    decisions for all cars, each checking only the cars near it
 /// 2. Write pass: Apply decisions and update car positions
 ///
 /// # Arguments
 /// * `cars` - Mutable vector of all cars
 /// * `intersections` - All intersections with traffic lights
+/// * `stops` - Barriers blocking traffic and gates of guarded compounds
+/// * `pedestrians` - All pedestrians, for occupied crosswalks
 /// * `dt` - Delta time (frame duration in seconds)
 /// * `all_lights_red` - Emergency mode flag (stops all traffic)
+/// * `speed_factor` - Fraction of their usual speed cars drive at (lower in rain)
+///
+/// # Returns
+/// Counters for stopped, in-intersection, and despawned cars this frame
 pub fn update_cars(
     cars: &mut Vec<Car>,
     intersections: &[Intersection],
+    stops: &Stops,
+    pedestrians: &[Pedestrian],
     dt: f32,
     all_lights_red: bool,
-) {
+    speed_factor: f32,
+) -> CarUpdateStats {
     // ========================================================================
     // PASS 1: Calculate decisions (read-only, no clone needed!)
     // ========================================================================
     //
     // We collect all decisions first using only immutable references.
-    // This eliminates the need to clone the entire cars vector.
+    // This eliminates the need to clone the entire cars vector, and the grid
+    // keeps each car's checks to the cars around it.
+    let traffic = Traffic::new(cars);
     let decisions: Vec<CarDecision> = cars
         .iter()
-        .map(|car| calculate_car_decision(car, cars, intersections, all_lights_red))
+        .map(|car| {
+            calculate_car_decision(
+                car,
+                &traffic,
+                intersections,
+                stops,
+                pedestrians,
+                all_lights_red,
+            )
+        })
         .collect();
 
     // ========================================================================
@@ -599,26 +1491,789 @@ pub fn update_cars(
     // ========================================================================
     //
     // Now we can safely mutate each car based on its pre-calculated decision.
+    let mut stats = CarUpdateStats::default();
     let mut car_index = 0;
     cars.retain_mut(|car| {
         let decision = &decisions[car_index];
         car_index += 1;
 
+        // Routed cars turn where their route does, visitors without one
+        // toward their destination's gate
+        if !car.route.is_empty() && car.turn.is_none() {
+            follow_route(car, intersections);
+        } else if let Some(gate) = stops.gate_for(car)
+            && car.turn.is_none()
+        {
+            car.next_turn = route_turn(car, intersections, gate);
+        }
+
+        update_turn_signal(car, intersections);
+
         // Update intersection state and handle turning
         let (_at_any_intersection, _turned) = update_car_at_intersection(car, intersections);
 
         // Reset flags when leaving all intersections
-        if !decision.at_any_intersection {
+        if !decision.at_any_intersection && car.turn.is_none() {
             car.just_turned = false;
             car.in_intersection = false;
         }
 
-        // Move car if not stopped
-        if !decision.should_stop {
-            move_car(car, dt);
-        }
+        // Yielding cars brake to a stop at the road edge
+        let target_speed = decision.target_speed * speed_factor;
+        car.target_velocity = if decision.yielding {
+            target_speed.min((car.velocity - DECELERATION * dt).max(0.0))
+        } else {
+            target_speed
+        };
+        update_pull_over(car, decision.yielding || decision.waiting, dt);
+
+        // Brake or accelerate, then move at the new speed
+        update_car_velocity(car, dt);
+        move_car(car, dt);
 
         // Keep car only if still on screen
-        decision.is_on_screen
+        if !decision.is_on_screen {
+            stats.despawned += 1;
+            return false;
+        }
+
+        if car.velocity <= 0.0 {
+            stats.stopped += 1;
+        }
+        if decision.at_any_intersection {
+            stats.in_intersection += 1;
+        }
+        true
     });
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CarLocation, VehicleStyle};
+    use crate::traffic_light::IntersectionTrafficLight;
+
+    /// Intersection at the center of the 800x600 test screen
+    fn intersection_with_vertical_state(state: Option<LightState>) -> Intersection {
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        if let Some(state) = state {
+            let mut light = IntersectionTrafficLight::new(0.5, 0.5, 0, true);
+            light.vertical_state = state;
+            intersection.set_light(light);
+        }
+        intersection
+    }
+
+    /// Car heading down toward the test intersection, `distance` pixels away
+    fn car_approaching(distance: f32) -> Car {
+        Car {
+            id: 0,
+            x_percent: (400.0 - LANE_OFFSET) / 800.0,
+            y_percent: (300.0 - distance) / 600.0,
+            direction: Direction::Down,
+            velocity: CAR_SPEED,
+            target_velocity: CAR_SPEED,
+            vehicle_type: VehicleType::Car,
+            style: VehicleStyle::Sedan,
+            color: RED,
+            road_index: 1,
+            next_turn: None,
+            just_turned: false,
+            in_intersection: false,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
+            location: CarLocation::OnRoad { road_id: 1 },
+            destination: None,
+            route: Vec::new(),
+            traffic_rule: TrafficRule::LeftHand,
+        }
+    }
+
+    fn stops(state: Option<LightState>, car: &Car, all_lights_red: bool) -> bool {
+        let intersections = [intersection_with_vertical_state(state)];
+        let cars = std::slice::from_ref(car);
+        let traffic = Traffic::new(cars);
+        target_speed(car, &intersections, &Stops::default(), &[], &traffic, all_lights_red) <= 0.0
+    }
+
+    #[test]
+    fn test_car_stops_on_red() {
+        let car = car_approaching(60.0);
+        assert!(stops(Some(LightState::default_red()), &car, false));
+    }
+
+    #[test]
+    fn test_car_stops_on_yellow() {
+        let car = car_approaching(60.0);
+        assert!(stops(Some(LightState::default_yellow()), &car, false));
+    }
+
+    #[test]
+    fn test_car_proceeds_on_green() {
+        let car = car_approaching(60.0);
+        assert!(!stops(Some(LightState::default_green()), &car, false));
+    }
+
+    #[test]
+    fn test_car_proceeds_without_light() {
+        let car = car_approaching(60.0);
+        assert!(!stops(None, &car, false));
+    }
+
+    #[test]
+    fn test_all_lights_red_stops_car_on_green() {
+        let car = car_approaching(60.0);
+        assert!(stops(Some(LightState::default_green()), &car, true));
+    }
+
+    #[test]
+    fn test_car_too_close_to_stop_continues() {
+        let car = car_approaching(STOP_DISTANCE_MIN - 5.0);
+        assert!(
+            check_traffic_light_at_intersection(&car, 400.0, 300.0, LightState::default_yellow())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_car_in_intersection_never_stops() {
+        let mut car = car_approaching(60.0);
+        car.in_intersection = true;
+        assert!(
+            check_traffic_light_at_intersection(&car, 400.0, 300.0, LightState::default_red())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cross_traffic_uses_its_own_light() {
+        // Vertical red / horizontal green: a car heading right proceeds
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        intersection.set_light(IntersectionTrafficLight::new(0.5, 0.5, 0, false));
+
+        let mut car = car_approaching(60.0);
+        car.direction = Direction::Right;
+        car.x_percent = (400.0 - 60.0) / 800.0;
+        car.y_percent = (300.0 + LANE_OFFSET) / 600.0;
+
+        let intersections = [intersection];
+        let traffic = Traffic::new(std::slice::from_ref(&car));
+        let target = target_speed(&car, &intersections, &Stops::default(), &[], &traffic, false);
+        assert_eq!(target, CAR_SPEED);
+    }
+
+    #[test]
+    fn test_car_yields_to_pedestrian_in_crosswalk() {
+        let car = car_approaching(60.0);
+        let intersections = [intersection_with_vertical_state(Some(LightState::default_green()))];
+        let cars = std::slice::from_ref(&car);
+
+        // Someone crossing the car's road north of the intersection, on green
+        let crosswalk = vec2(400.0, 300.0 - CROSSWALK_DISTANCE);
+        let mut pedestrian = Pedestrian::new(390.0, crosswalk.y, Direction::Right, RED);
+        pedestrian.crossing = Some(crosswalk);
+        let pedestrians = [pedestrian.clone()];
+        let traffic = Traffic::new(cars);
+        let stops = Stops::default();
+        assert_eq!(target_speed(&car, &intersections, &stops, &pedestrians, &traffic, false), 0.0);
+
+        // Pedestrians on the sidewalk, or crossing the other road, don't stop it
+        let mut parallel = pedestrian.clone();
+        parallel.direction = Direction::Down;
+        pedestrian.crossing = None;
+        assert_eq!(
+            target_speed(&car, &intersections, &stops, &[pedestrian, parallel], &traffic, false),
+            CAR_SPEED
+        );
+    }
+
+    #[test]
+    fn test_car_stops_before_closed_barrier() {
+        // Barrier across the road 60 pixels ahead, no intersection involved
+        let car = car_approaching(60.0);
+        let barrier = (400.0, 300.0);
+        let traffic = Traffic::new(std::slice::from_ref(&car));
+        let closed = Stops { barriers: vec![barrier], ..Stops::default() };
+        assert_eq!(target_speed(&car, &[], &closed, &[], &traffic, false), 0.0);
+        assert_eq!(target_speed(&car, &[], &Stops::default(), &[], &traffic, false), CAR_SPEED);
+    }
+
+    #[test]
+    fn test_car_waits_at_intersection_with_closed_road() {
+        // The car drives down road 1 toward its crossing with road 3
+        let mut intersection = intersection_with_vertical_state(None);
+        for (direction, road) in [(Direction::Up, 1), (Direction::Left, 3)] {
+            intersection.connect_road(direction, road);
+        }
+        let intersections = [intersection];
+        let car = car_approaching(60.0);
+        let traffic = Traffic::new(std::slice::from_ref(&car));
+
+        let crossing_closed = Stops { closed_roads: vec![3], ..Stops::default() };
+        assert_eq!(target_speed(&car, &intersections, &crossing_closed, &[], &traffic, false), 0.0);
+
+        // Cars on the closed road itself drive off it
+        let own_closed = Stops { closed_roads: vec![1], ..Stops::default() };
+        assert_eq!(
+            target_speed(&car, &intersections, &own_closed, &[], &traffic, false),
+            CAR_SPEED
+        );
+    }
+
+    /// Drives a car heading down through the test intersection with a planned
+    /// turn, checking every turning frame, until the turn is complete
+    fn drive_through_turn(exit_direction: Direction) -> Car {
+        use crate::constants::visual::ROAD_WIDTH;
+        use std::f32::consts::PI;
+
+        let intersections = [intersection_with_vertical_state(None)];
+        let mut car = car_approaching(100.0);
+        car.next_turn = Some(exit_direction);
+        let mut cars = vec![car];
+        let mut turning_frames = 0;
+        let mut last_heading = cars[0].heading();
+
+        for _ in 0..600 {
+            update_cars(&mut cars, &intersections, &Stops::default(), &[], 1.0 / 60.0, false, 1.0);
+            let car = &cars[0];
+            if car.turn.is_none() {
+                if turning_frames > 0 {
+                    break;
+                }
+                continue;
+            }
+
+            turning_frames += 1;
+            assert!(car.in_intersection, "turning car must occupy the intersection");
+            assert!((car.x() - 400.0).abs() <= ROAD_WIDTH / 2.0 + 0.01, "x = {}", car.x());
+            assert!((car.y() - 300.0).abs() <= ROAD_WIDTH / 2.0 + 0.01, "y = {}", car.y());
+            // Heading changes a little each frame instead of snapping
+            let turned = (car.heading() - last_heading + PI).rem_euclid(2.0 * PI) - PI;
+            let turned = turned.abs();
+            assert!(turned < 0.1, "heading jumped by {} rad", turned);
+            last_heading = car.heading();
+        }
+
+        assert!(turning_frames > 10, "turn took {} frames", turning_frames);
+        cars.remove(0)
+    }
+
+    #[test]
+    fn test_turning_car_follows_arc_into_exit_lane() {
+        // A car heading down turning towards the right of the screen
+        let car = drive_through_turn(Direction::Right);
+        assert_eq!(car.direction, Direction::Right);
+        assert!((car.y() - (300.0 + LANE_OFFSET)).abs() < 0.01, "y = {}", car.y());
+        assert!(car.x() >= 400.0 + TURN_ENTRY_DISTANCE - 0.01);
+        assert!(car.just_turned);
+
+        // And towards the left, across the other lanes
+        let car = drive_through_turn(Direction::Left);
+        assert_eq!(car.direction, Direction::Left);
+        assert!((car.y() - (300.0 - LANE_OFFSET)).abs() < 0.01, "y = {}", car.y());
+        assert!(car.x() <= 400.0 - TURN_ENTRY_DISTANCE + 0.01);
+    }
+
+    #[test]
+    fn test_turn_signal_blinks_from_near_the_intersection_until_the_turn_is_done() {
+        let intersections = [intersection_with_vertical_state(None)];
+        let stops = Stops::default();
+        // Heading down, so turning right of the screen is the driver's left
+        let mut car = car_approaching(100.0);
+        car.next_turn = Some(Direction::Right);
+        let mut cars = vec![car];
+
+        update_cars(&mut cars, &intersections, &stops, &[], 1.0 / 60.0, false, 1.0);
+        assert_eq!(cars[0].turn_signal, None);
+
+        let mut signalled_turning = false;
+        while !cars[0].just_turned {
+            // The signal follows where the car was at the start of the frame
+            let y = cars[0].y();
+            update_cars(&mut cars, &intersections, &stops, &[], 1.0 / 60.0, false, 1.0);
+            let car = &cars[0];
+            if !car.just_turned && 300.0 - y <= TURN_SIGNAL_DISTANCE {
+                assert_eq!(car.turn_signal, Some(TurnSignalState::Left), "y = {}", y);
+                signalled_turning |= car.turn.is_some();
+            }
+        }
+        assert!(signalled_turning);
+        assert_eq!(cars[0].turn_signal, None);
+    }
+
+    /// Roundabout at the center of the 800x600 test screen
+    fn roundabout() -> Intersection {
+        use crate::intersection::IntersectionType;
+
+        Intersection::builder()
+            .intersection_type(IntersectionType::Roundabout { radius: 30.0 })
+            .build()
+    }
+
+    #[test]
+    fn test_cars_circle_roundabout_into_their_exit_lane() {
+        let intersections = [roundabout()];
+        let mut circling_frames = Vec::new();
+
+        // Off to the left of the screen, straight on, and off to the right
+        for (exit, lane) in [
+            (Direction::Left, 300.0 - LANE_OFFSET),
+            (Direction::Down, 400.0 - LANE_OFFSET),
+            (Direction::Right, 300.0 + LANE_OFFSET),
+        ] {
+            let mut car = car_approaching(100.0);
+            car.next_turn = (exit != Direction::Down).then_some(exit);
+            let mut cars = vec![car];
+            let mut frames = 0;
+            let stops = Stops::default();
+            for _ in 0..600 {
+                update_cars(&mut cars, &intersections, &stops, &[], 1.0 / 60.0, false, 1.0);
+                let car = &cars[0];
+                if car.turn.is_none() {
+                    if frames > 0 {
+                        break;
+                    }
+                    continue;
+                }
+                frames += 1;
+                let from_center = vec2(car.x(), car.y()).distance(vec2(400.0, 300.0));
+                assert!((from_center - 30.0).abs() < 0.5, "{} px from the center", from_center);
+            }
+
+            let car = &cars[0];
+            assert_eq!(car.direction, exit);
+            let across = if exit.is_vertical() { car.x() } else { car.y() };
+            assert!((across - lane).abs() < 0.01, "{:?} exit at {}", exit, across);
+            circling_frames.push(frames);
+        }
+
+        // The island is east of the car, on its left, so heading right of the
+        // screen takes longest
+        assert!(circling_frames.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", circling_frames);
+    }
+
+    #[test]
+    fn test_car_gives_way_to_cars_circling_toward_its_entry() {
+        let intersections = [roundabout()];
+        let waiting = car_approaching(65.0);
+        // Entered from the right of the screen, heading for the left
+        let path = roundabout_path(
+            vec2(400.0, 300.0),
+            30.0,
+            Direction::Left,
+            Direction::Left,
+            TrafficRule::LeftHand,
+        );
+
+        for (t, gives_way) in [(0.05, true), (0.9, false)] {
+            let mut circling = car_approaching(0.0);
+            circling.direction = Direction::Left;
+            circling.in_intersection = true;
+            let position = path.position_at(t);
+            circling.set_x(position.x);
+            circling.set_y(position.y);
+            circling.turn = Some(TurnProgress { path: path.clone().into(), t });
+
+            let cars = [waiting.clone(), circling];
+            let traffic = Traffic::new(&cars);
+            let stops = Stops::default();
+            let target = target_speed(&cars[0], &intersections, &stops, &[], &traffic, false);
+            assert_eq!(target <= 0.0, gives_way, "circling car at t = {}", t);
+        }
+    }
+
+    /// Runs `update_cars` for `frames` 60 FPS frames, recording each car's speed
+    fn simulate(
+        cars: &mut Vec<Car>,
+        intersections: &[Intersection],
+        frames: usize,
+        mut on_frame: impl FnMut(&[Car]),
+    ) {
+        for _ in 0..frames {
+            update_cars(cars, intersections, &Stops::default(), &[], 1.0 / 60.0, false, 1.0);
+            on_frame(cars);
+        }
+    }
+
+    #[test]
+    fn test_car_brakes_smoothly_and_stops_before_red() {
+        let intersections = [intersection_with_vertical_state(Some(LightState::default_red()))];
+        let mut cars = vec![car_approaching(200.0)];
+        let mut last_speed = CAR_SPEED;
+        let mut braking_frames = 0;
+
+        simulate(&mut cars, &intersections, 600, |cars| {
+            let car = &cars[0];
+            assert!(car.velocity <= last_speed, "speed must never increase on approach");
+            assert!(!car.in_intersection, "car entered the intersection on red");
+            assert!(300.0 - car.y() > STOP_LINE_DISTANCE - 0.5, "car passed the stop line");
+            if car.velocity > 0.0 && car.velocity < CAR_SPEED {
+                braking_frames += 1;
+            }
+            last_speed = car.velocity;
+        });
+
+        assert_eq!(cars[0].velocity, 0.0);
+        assert_eq!(cars[0].target_velocity, 0.0);
+        // Coming to a stop takes about CAR_SPEED / DECELERATION seconds, not one frame
+        let expected_frames = CAR_SPEED / DECELERATION * 60.0;
+        assert!(braking_frames as f32 > expected_frames * 0.8, "braked in {} frames", braking_frames);
+    }
+
+    #[test]
+    fn test_car_accelerates_back_to_full_speed() {
+        let intersections = [intersection_with_vertical_state(Some(LightState::default_green()))];
+        let mut car = car_approaching(250.0);
+        car.velocity = 0.0;
+        let mut cars = vec![car];
+        let mut last_speed = 0.0;
+
+        simulate(&mut cars, &intersections, 90, |cars| {
+            assert!(cars[0].velocity >= last_speed, "speed must never decrease when clear");
+            last_speed = cars[0].velocity;
+        });
+
+        assert_eq!(cars[0].velocity, CAR_SPEED);
+    }
+
+    #[test]
+    fn test_car_matches_speed_of_slower_car_ahead() {
+        let mut follower = car_approaching(250.0);
+        let mut leader = car_approaching(250.0 - FOLLOWING_GAP - CAR_HEIGHT);
+        leader.velocity = 20.0;
+        let cars = [follower.clone(), leader.clone()];
+
+        // At the safe distance the follower matches the leader instead of stopping
+        let speed = following_speed(&cars[0], &Traffic::new(&cars));
+        assert!((speed - 20.0).abs() < 1e-3, "expected 20.0, got {}", speed);
+
+        // Further back it may close the gap, but a stopped car ahead demands more braking
+        follower.y_percent -= 10.0 / 600.0;
+        let cars = [follower.clone(), leader.clone()];
+        let behind_moving = following_speed(&cars[0], &Traffic::new(&cars));
+        leader.velocity = 0.0;
+        let cars = [follower, leader];
+        let behind_stopped = following_speed(&cars[0], &Traffic::new(&cars));
+        assert!(behind_moving > 20.0 && behind_moving <= CAR_SPEED);
+        assert!(behind_stopped < behind_moving);
+    }
+
+    #[test]
+    fn test_following_distance_uses_both_vehicle_lengths() {
+        let sedan = car_approaching(250.0);
+        let bus = Car {
+            style: VehicleStyle::Bus,
+            ..car_approaching(250.0)
+        };
+        let (sedan_length, bus_length) = (sedan.length(), bus.length());
+        assert!(bus_length > sedan_length);
+
+        assert_eq!(safe_following_distance(&sedan, &sedan), sedan_length + FOLLOWING_GAP);
+        assert_eq!(
+            safe_following_distance(&sedan, &bus),
+            (sedan_length + bus_length) / 2.0 + FOLLOWING_GAP
+        );
+        assert_eq!(safe_following_distance(&bus, &sedan), safe_following_distance(&sedan, &bus));
+
+        // A distance that is safe behind a sedan puts a stopped bus's rear
+        // bumper inside the gap, so the follower has to stop
+        let mut leader = Car {
+            velocity: 0.0,
+            ..bus.clone()
+        };
+        leader.y_percent += (sedan_length + FOLLOWING_GAP) / 600.0;
+        let cars = [sedan.clone(), leader];
+        assert_eq!(following_speed(&cars[0], &Traffic::new(&cars)), 0.0);
+    }
+
+    #[test]
+    fn test_mixed_queue_at_red_light_keeps_bumpers_apart() {
+        let intersections = [intersection_with_vertical_state(Some(LightState::default_red()))];
+        let bus = Car {
+            style: VehicleStyle::Bus,
+            ..car_approaching(170.0)
+        };
+        let truck = Car {
+            style: VehicleStyle::Truck,
+            ..car_approaching(330.0)
+        };
+        let mut cars = vec![car_approaching(100.0), bus, truck, car_approaching(420.0)];
+
+        simulate(&mut cars, &intersections, 900, |_| {});
+
+        for pair in cars.windows(2) {
+            let (leader, follower) = (&pair[0], &pair[1]);
+            assert_eq!(follower.velocity, 0.0);
+            let leader_rear = leader.y() - leader.length() / 2.0;
+            let follower_front = follower.y() + follower.length() / 2.0;
+            let bumper_gap = leader_rear - follower_front;
+            assert!(
+                bumper_gap > FOLLOWING_GAP - 0.5,
+                "{:?} behind {:?}: gap {}",
+                follower.style,
+                leader.style,
+                bumper_gap
+            );
+        }
+    }
+
+    /// Emergency vehicle version of `car_approaching`, at full speed
+    fn emergency_approaching(distance: f32) -> Car {
+        Car {
+            vehicle_type: VehicleType::EmergencyVehicle,
+            velocity: VehicleType::EmergencyVehicle.max_speed(),
+            target_velocity: VehicleType::EmergencyVehicle.max_speed(),
+            ..car_approaching(distance)
+        }
+    }
+
+    #[test]
+    fn test_emergency_car_runs_red_light_that_stops_civilian() {
+        let intersections = [intersection_with_vertical_state(Some(LightState::default_red()))];
+
+        let mut civilian = vec![car_approaching(200.0)];
+        simulate(&mut civilian, &intersections, 180, |_| {});
+        assert_eq!(civilian[0].velocity, 0.0);
+        assert!(300.0 - civilian[0].y() > STOP_LINE_DISTANCE - 0.5);
+
+        let mut emergency = vec![emergency_approaching(200.0)];
+        simulate(&mut emergency, &intersections, 240, |cars| {
+            assert_eq!(cars[0].velocity, VehicleType::EmergencyVehicle.max_speed());
+        });
+        assert!(emergency[0].y() > 300.0 + INTERSECTION_RADIUS, "y = {}", emergency[0].y());
+    }
+
+    #[test]
+    fn test_emergency_car_waits_for_occupied_intersection() {
+        let car = emergency_approaching(60.0);
+        let mut crossing = car_approaching(0.0);
+        crossing.direction = Direction::Right;
+        crossing.in_intersection = true;
+
+        let cars = [car.clone(), crossing];
+        let intersections = [intersection_with_vertical_state(None)];
+        let traffic = Traffic::new(&cars);
+        let target = target_speed(&car, &intersections, &Stops::default(), &[], &traffic, false);
+        assert_eq!(target, 0.0);
+    }
+
+    #[test]
+    fn test_civilian_pulls_over_until_emergency_car_passes() {
+        let mut cars = vec![car_approaching(150.0), emergency_approaching(250.0)];
+        let lane_x = 400.0 - LANE_OFFSET;
+        let mut civilian_stopped = false;
+        let mut max_pull_over: f32 = 0.0;
+
+        simulate(&mut cars, &[], 240, |cars| {
+            let civilian = cars.iter().find(|c| c.vehicle_type == VehicleType::Car).unwrap();
+            let emergency = cars
+                .iter()
+                .find(|c| c.vehicle_type == VehicleType::EmergencyVehicle)
+                .unwrap();
+            civilian_stopped |= civilian.velocity == 0.0;
+            max_pull_over = max_pull_over.max(civilian.pull_over);
+            // Never slowed down by the car pulling over ahead of it
+            assert_eq!(emergency.velocity, VehicleType::EmergencyVehicle.max_speed());
+        });
+
+        assert!(civilian_stopped, "civilian never stopped");
+        assert_eq!(max_pull_over, PULL_OVER_OFFSET);
+
+        // Once the emergency car is past, the civilian is back in its lane
+        let civilian = cars.iter().find(|c| c.vehicle_type == VehicleType::Car).unwrap();
+        let emergency = cars
+            .iter()
+            .find(|c| c.vehicle_type == VehicleType::EmergencyVehicle)
+            .unwrap();
+        assert!(emergency.y() > civilian.y() + CAR_HEIGHT);
+        assert_eq!(civilian.pull_over, 0.0);
+        assert!((civilian.x() - lane_x).abs() < 0.01, "x = {}", civilian.x());
+        assert!(civilian.velocity > 0.0);
+    }
+
+    /// Gate on the horizontal road at y = 450, 100 pixels left of the center
+    fn test_gate(full: bool) -> Gate {
+        Gate {
+            block_id: 8,
+            road_index: 4,
+            position: vec2(300.0, 450.0),
+            inward: Direction::Up,
+            full,
+            admitting: None,
+        }
+    }
+
+    #[test]
+    fn test_visitor_turns_toward_its_gate() {
+        let gate = test_gate(false);
+        let intersections = [Intersection::new(0.5, 0.5, 0), Intersection::new(0.5, 0.75, 1)];
+
+        // Heading down toward the gate's road: straight on until it reaches it
+        let mut car = car_approaching(150.0);
+        car.destination = Some(8);
+        assert_eq!(route_turn(&car, &intersections, &gate), None);
+        car.set_y(400.0);
+        assert_eq!(route_turn(&car, &intersections, &gate), Some(Direction::Left));
+
+        // On the gate's road: on toward the gate, or back around once past it
+        car.direction = Direction::Left;
+        car.set_x(350.0);
+        car.set_y(450.0 - LANE_OFFSET);
+        assert_eq!(route_turn(&car, &intersections, &gate), None);
+        car.direction = Direction::Right;
+        car.set_y(450.0 + LANE_OFFSET);
+        assert_eq!(route_turn(&car, &intersections, &gate), Some(Direction::Up));
+    }
+
+    #[test]
+    fn test_visitor_waits_back_from_full_gate() {
+        let mut car = car_approaching(0.0);
+        car.direction = Direction::Left;
+        car.set_x(600.0);
+        car.set_y(450.0 - LANE_OFFSET);
+        car.destination = Some(8);
+        let mut cars = vec![car];
+
+        let mut stops = Stops { gates: vec![test_gate(true)], ..Stops::default() };
+        for _ in 0..600 {
+            update_cars(&mut cars, &[], &stops, &[], 1.0 / 60.0, false, 1.0);
+        }
+        // Stopped short of the gate, pulled over for through traffic
+        assert!((cars[0].x() - (300.0 + GATE_QUEUE_GAP)).abs() < 3.0, "x = {}", cars[0].x());
+        assert_eq!(cars[0].pull_over, PULL_OVER_OFFSET);
+        assert!(!arrived_at_gate(&cars[0], &stops.gates[0]));
+
+        // Once there is room it drives up to the gate to be let in
+        stops.gates[0].full = false;
+        for _ in 0..300 {
+            update_cars(&mut cars, &[], &stops, &[], 1.0 / 60.0, false, 1.0);
+        }
+        assert_eq!(cars[0].pull_over, 0.0);
+        assert!(arrived_at_gate(&cars[0], &stops.gates[0]), "x = {}", cars[0].x());
+    }
+
+    /// Small xorshift generator, so random scenes repeat for a given seed
+    struct SceneRng(u64);
+
+    impl SceneRng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Uniform float in `low..high`
+        fn range(&mut self, low: f32, high: f32) -> f32 {
+            low + (self.next() % 10_000) as f32 / 10_000.0 * (high - low)
+        }
+
+        fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+            items[self.next() as usize % items.len()]
+        }
+    }
+
+    /// `count` cars crowded onto the lanes of a 3x3 road grid on the 800x600
+    /// test screen, with intersections where the roads cross
+    fn random_scene(rng: &mut SceneRng, count: usize) -> (Vec<Car>, Vec<Intersection>) {
+        const ROADS: [f32; 3] = [0.25, 0.5, 0.75];
+        let mut intersections = Vec::new();
+        for &x in &ROADS {
+            for &y in &ROADS {
+                intersections.push(Intersection::new(x, y, intersections.len()));
+            }
+        }
+
+        let cars = (0..count)
+            .map(|id| {
+                let direction =
+                    rng.pick(&[Direction::Down, Direction::Up, Direction::Right, Direction::Left]);
+                let side = match direction {
+                    Direction::Down | Direction::Left => -LANE_OFFSET,
+                    Direction::Up | Direction::Right => LANE_OFFSET,
+                };
+                let lane = rng.pick(&ROADS);
+                let (x, y) = match direction {
+                    Direction::Down | Direction::Up => {
+                        (lane * 800.0 + side + rng.range(-4.0, 4.0), rng.range(0.0, 600.0))
+                    }
+                    Direction::Right | Direction::Left => {
+                        (rng.range(0.0, 800.0), lane * 600.0 + side + rng.range(-4.0, 4.0))
+                    }
+                };
+                let vehicle_type = rng.pick(&[VehicleType::Car, VehicleType::EmergencyVehicle]);
+                Car {
+                    id,
+                    x_percent: x / 800.0,
+                    y_percent: y / 600.0,
+                    direction,
+                    velocity: rng.range(0.0, vehicle_type.max_speed()),
+                    vehicle_type,
+                    style: rng.pick(&VehicleStyle::ALL),
+                    in_intersection: rng.next().is_multiple_of(4),
+                    ..car_approaching(0.0)
+                }
+            })
+            .collect();
+        (cars, intersections)
+    }
+
+    #[test]
+    fn test_grid_decisions_match_checking_every_car() {
+        let mut rng = SceneRng(0x2545_f491_4f6c_dd1d);
+        let mut slowed = 0;
+
+        for _ in 0..50 {
+            let (cars, intersections) = random_scene(&mut rng, 80);
+            let all_lights_red = rng.next().is_multiple_of(2);
+            let grid = Traffic::new(&cars);
+            let brute_force = Traffic::brute_force(&cars);
+
+            for car in &cars {
+                let decide = |traffic| {
+                    calculate_car_decision(
+                        car,
+                        traffic,
+                        &intersections,
+                        &Stops::default(),
+                        &[],
+                        all_lights_red,
+                    )
+                };
+                let expected = decide(&brute_force);
+                assert_eq!(decide(&grid), expected, "car {}", car.id);
+                if expected.target_speed < car.max_speed() {
+                    slowed += 1;
+                }
+            }
+        }
+
+        // The scenes are crowded enough for the nearby cars to matter
+        assert!(slowed > 1000, "only {} cars slowed down", slowed);
+    }
+
+    /// Compares the grid with checking every car for a crowded city. Run with
+    /// `cargo test --release -- --ignored --nocapture` for meaningful timings.
+    #[test]
+    #[ignore]
+    fn bench_update_500_cars() {
+        let (cars, intersections) = random_scene(&mut SceneRng(42), 500);
+        let stops = Stops::default();
+        let time = |traffic: &Traffic| {
+            let start = std::time::Instant::now();
+            for _ in 0..20 {
+                for car in &cars {
+                    calculate_car_decision(car, traffic, &intersections, &stops, &[], false);
+                }
+            }
+            start.elapsed() / 20
+        };
+
+        let grid = time(&Traffic::new(&cars));
+        let brute_force = time(&Traffic::brute_force(&cars));
+        eprintln!("500 cars per frame: grid {:?}, every car {:?}", grid, brute_force);
+    }
 }
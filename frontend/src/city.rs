@@ -3,19 +3,56 @@
 //! This module defines the City structure that contains all city elements:
 //! - Roads: The road network
 //! - Blocks: Areas between roads that hold objects
-//! - Intersections: Road crossings with traffic lights
+//! - Intersections: Road crossings with traffic lights or stop signs
 //! - Cars: Vehicles moving through the city
+//! - Pedestrians: People walking along blocks and across crosswalks
+//!
+//! It also collects the simulation state into a snapshot for the save file,
+//! and restores it from one.
 //!
 //! The City acts as the main container and coordinator for all city elements.
 
-use crate::block::Block;
+use crate::block::{Block, GuardedCompound, ScadaState};
+use crate::car::{CarUpdateStats, EdgeBehavior, Gate, Stops};
 use crate::constants::visual::ROAD_WIDTH;
-use crate::intersection::Intersection;
-use crate::models::Car;
-use crate::road::Road;
-use crate::spawner::CarSpawner;
+use crate::effects::{BuildingFire, ParticleBurst};
+use crate::intersection::{Intersection, LightOverride};
+use crate::led_display_object::{LEDAlert, LEDDisplay, LEDDisplayMode, LEDPriority, LedMessage};
+use crate::models::{Car, Direction, Pedestrian, TrafficRule};
+use crate::pedestrian::PedestrianSpawner;
+use crate::rendering::{MinimapIntersection, MinimapState};
+use crate::road::{Orientation, Road};
+use crate::snapshot::{CitySnapshot, Controls};
+use crate::spawner::{CarSpawner, SpawnConfig};
+use crate::time_of_day::TimeOfDay;
 use std::collections::HashMap;
 
+// ============================================================================
+// Simulation Statistics
+// ============================================================================
+
+/// Simulation statistics for a single frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulationStats {
+    /// Cars in the city after the update
+    pub total_cars: usize,
+
+    /// Cars held in place (lights, traffic, emergency mode)
+    pub cars_stopped: usize,
+
+    /// Cars inside an intersection
+    pub cars_in_intersections: usize,
+
+    /// Cars spawned this frame
+    pub cars_spawned: usize,
+
+    /// Cars removed this frame for leaving the screen
+    pub cars_despawned: usize,
+
+    /// Pedestrians in the city after the update
+    pub total_pedestrians: usize,
+}
+
 // ============================================================================
 // City Model
 // ============================================================================
@@ -40,6 +77,33 @@ pub struct City {
 
     /// Car spawner that manages spawning new cars at regular intervals
     car_spawner: CarSpawner,
+
+    /// What happens to cars driving off the screen
+    edge_behavior: EdgeBehavior,
+
+    /// Fraction of their usual speed cars drive at (lower in rain)
+    speed_factor: f32,
+
+    /// Whether green durations follow the queues at each intersection
+    adaptive_lights: bool,
+
+    /// Side of the road new cars keep to
+    traffic_rule: TrafficRule,
+
+    /// All pedestrians walking through the city
+    pub pedestrians: Vec<Pedestrian>,
+
+    /// Pedestrian spawner that adds pedestrians at regular intervals
+    pedestrian_spawner: PedestrianSpawner,
+
+    /// Active particle bursts (SCADA event effects)
+    pub particle_bursts: Vec<ParticleBurst>,
+
+    /// Burning buildings indexed by block ID
+    pub fires: HashMap<usize, BuildingFire>,
+
+    /// Statistics from the most recent update
+    stats: SimulationStats,
 }
 
 impl City {
@@ -48,17 +112,122 @@ impl City {
     /// # Returns
     /// A new City instance with no roads, blocks, intersections, or cars
     pub fn new() -> Self {
-        use crate::constants::vehicle::CAR_SPAWN_INTERVAL;
-
         Self {
             roads: HashMap::new(),
             blocks: HashMap::new(),
             intersections: HashMap::new(),
             cars: Vec::new(),
-            car_spawner: CarSpawner::new(CAR_SPAWN_INTERVAL),
+            car_spawner: CarSpawner::default(),
+            edge_behavior: EdgeBehavior::default(),
+            speed_factor: 1.0,
+            adaptive_lights: false,
+            traffic_rule: TrafficRule::default(),
+            pedestrians: Vec::new(),
+            pedestrian_spawner: PedestrianSpawner::default(),
+            particle_bursts: Vec::new(),
+            fires: HashMap::new(),
+            stats: SimulationStats::default(),
+        }
+    }
+
+    /// Sets what happens to cars driving off the screen
+    ///
+    /// # Arguments
+    /// * `edge_behavior` - Despawn them, or wrap them around to the opposite edge
+    pub fn set_edge_behavior(&mut self, edge_behavior: EdgeBehavior) {
+        self.edge_behavior = edge_behavior;
+    }
+
+    /// Slows all traffic down, e.g. for rain
+    ///
+    /// # Arguments
+    /// * `speed_factor` - Fraction of their usual speed cars drive at (1.0 = normal)
+    pub fn set_speed_factor(&mut self, speed_factor: f32) {
+        self.speed_factor = speed_factor;
+    }
+
+    /// Turns adaptive light timing on or off at every intersection
+    ///
+    /// With adaptive timing, the direction with more queued cars gets a
+    /// longer green and the other a shorter one, recalculated every cycle.
+    pub fn set_adaptive_lights(&mut self, adaptive: bool) {
+        self.adaptive_lights = adaptive;
+        for intersection in self.intersections.values_mut() {
+            intersection.set_adaptive(adaptive);
+        }
+    }
+
+    /// Times the lights along vertical roads into a green wave
+    ///
+    /// # Arguments
+    /// * `speed` - Speed in pixels per second the wave is timed for, or None
+    ///   for the speed cars currently drive at
+    ///
+    /// # Returns
+    /// The speed the wave was timed for
+    pub fn start_green_wave(&mut self, speed: Option<f32>) -> f32 {
+        use crate::constants::vehicle::CAR_SPEED;
+        use crate::traffic_light::GreenWaveController;
+
+        let speed = speed.unwrap_or(CAR_SPEED * self.speed_factor);
+        GreenWaveController::apply(self.intersections.values_mut(), speed);
+        speed
+    }
+
+    /// Checks if adaptive light timing is on
+    pub fn adaptive_lights(&self) -> bool {
+        self.adaptive_lights
+    }
+
+    /// Switches which side of the road traffic keeps to
+    ///
+    /// Cars already on the road can't change lanes mid-drive, so they are
+    /// cleared and new ones spawn in the lanes for the new rule.
+    ///
+    /// # Arguments
+    /// * `rule` - Left-hand or right-hand traffic
+    ///
+    /// # Returns
+    /// Number of cars cleared; none if the rule was already in effect
+    pub fn set_traffic_rule(&mut self, rule: TrafficRule) -> usize {
+        if rule == self.traffic_rule {
+            return 0;
+        }
+        self.traffic_rule = rule;
+        let cleared = self.cars.len();
+        self.cars.clear();
+        cleared
+    }
+
+    /// Side of the road traffic keeps to
+    pub fn traffic_rule(&self) -> TrafficRule {
+        self.traffic_rule
+    }
+
+    /// Replaces the car spawner's interval, car cap and road weights
+    ///
+    /// # Arguments
+    /// * `config` - New spawner settings
+    pub fn set_spawn_config(&mut self, config: SpawnConfig) {
+        self.car_spawner = CarSpawner::new(config);
+        for road in self.roads.values().filter(|road| road.closed) {
+            self.car_spawner.set_road_closed(road.index, true);
         }
     }
 
+    /// Changes the time between car spawns, to throttle traffic
+    ///
+    /// # Arguments
+    /// * `interval` - Time between spawns in seconds
+    pub fn set_car_spawn_rate(&mut self, interval: f32) {
+        self.car_spawner.set_spawn_rate(interval);
+    }
+
+    /// Time between car spawns in seconds
+    pub fn car_spawn_rate(&self) -> f32 {
+        self.car_spawner.spawn_rate()
+    }
+
     /// Creates a new city using the builder pattern
     ///
     /// # Example
@@ -105,65 +274,771 @@ impl City {
         self.cars.push(car);
     }
 
-    /// Toggles SCADA broken state for a specific building by block ID
+    /// Applies `f` to every SCADA-enabled building in the given block, or in
+    /// every block when no ID is given
+    fn for_each_scada_building(
+        &mut self,
+        block_id: Option<usize>,
+        mut f: impl FnMut(&mut crate::block::Building),
+    ) {
+        for block in self.blocks.values_mut() {
+            if block_id.is_some_and(|id| id != block.id) {
+                continue;
+            }
+            for obj in &mut block.objects {
+                if let Some(building) = obj.as_any_mut().downcast_mut::<crate::block::Building>()
+                    && building.has_scada
+                {
+                    f(building);
+                }
+            }
+        }
+    }
+
+    /// Toggles SCADA compromised state for a specific building by block ID
     ///
     /// # Arguments
     /// * `block_id` - The ID of the block containing the building
-    pub fn toggle_scada_broken(&mut self, block_id: usize) {
-        if let Some(block) = self.blocks.get_mut(&block_id) {
-            // Try to find and toggle any Building objects in this block
+    pub fn toggle_scada(&mut self, block_id: usize) {
+        self.for_each_scada_building(Some(block_id), |building| {
+            building.set_scada_compromised(!building.is_scada_compromised());
+        });
+    }
+
+    /// Sets SCADA compromised state for one building, or for all of them
+    ///
+    /// Mirrors the server's SCADA events, where a missing building ID means
+    /// every building.
+    ///
+    /// # Arguments
+    /// * `building_id` - The ID of the block containing the building (None = all)
+    /// * `compromised` - Whether the SCADA should be compromised
+    pub fn set_scada(&mut self, building_id: Option<usize>, compromised: bool) {
+        self.for_each_scada_building(building_id, |building| {
+            building.set_scada_compromised(compromised);
+        });
+    }
+
+    /// Sets the SCADA state of one building
+    ///
+    /// Restoring a compromised building to `ScadaState::Normal` plays its
+    /// green flash.
+    ///
+    /// # Arguments
+    /// * `building_id` - The ID of the block containing the building
+    /// * `state` - The new state of its SCADA system
+    pub fn set_building_scada_state(&mut self, building_id: usize, state: ScadaState) {
+        self.for_each_scada_building(Some(building_id), |building| {
+            building.set_scada_state(state);
+        });
+    }
+
+    /// Marks SCADA as compromised by a team for one building, or for all of them
+    ///
+    /// # Arguments
+    /// * `building_id` - The ID of the block containing the building (None = all)
+    /// * `team` - Team that compromised the system
+    pub fn compromise_scada(&mut self, building_id: Option<usize>, team: &crate::team::Team) {
+        self.for_each_scada_building(building_id, |building| {
+            building.compromise_scada(team);
+        });
+    }
+
+    /// Toggles SCADA compromised state for ALL buildings with SCADA in the city
+    pub fn toggle_all_scada(&mut self) {
+        self.for_each_scada_building(None, |building| {
+            building.set_scada_compromised(!building.is_scada_compromised());
+        });
+    }
+
+    /// Resets all SCADA systems to working state (not compromised)
+    pub fn reset_all_scada(&mut self) {
+        self.set_scada(None, false);
+    }
+
+    /// Sets or clears the light override of one intersection
+    ///
+    /// # Arguments
+    /// * `id` - Intersection ID
+    /// * `override_state` - Forced light state, or None for automatic operation
+    pub fn set_intersection_override(&mut self, id: usize, override_state: Option<LightOverride>) {
+        if let Some(intersection) = self.intersections.get_mut(&id) {
+            intersection.set_override(override_state);
+        }
+    }
+
+    /// Returns every intersection to automatic light operation
+    pub fn clear_intersection_overrides(&mut self) {
+        for intersection in self.intersections.values_mut() {
+            intersection.set_override(None);
+        }
+    }
+
+    /// Turns the power in a district off or back on
+    ///
+    /// Affects the traffic lights of the district's intersections and the
+    /// buildings in blocks whose center lies in the district.
+    ///
+    /// # Arguments
+    /// * `district` - Name of the power district (e.g. "central")
+    /// * `powered` - False for a power outage
+    ///
+    /// # Returns
+    /// `true` if the district contains any intersection or block
+    pub fn set_district_power(&mut self, district: &str, powered: bool) -> bool {
+        use crate::intersection::district_at;
+
+        let mut found = false;
+        for intersection in self.intersections.values_mut() {
+            if intersection.district.as_deref() == Some(district) {
+                intersection.set_powered(powered);
+                found = true;
+            }
+        }
+        for block in self.blocks.values_mut() {
+            if district_at(block.x_percent + block.width_percent / 2.0) != district {
+                continue;
+            }
+            found = true;
             for obj in &mut block.objects {
-                // Use downcast to check if this is a Building
                 if let Some(building) = obj.as_any_mut().downcast_mut::<crate::block::Building>() {
-                    if building.has_scada {
-                        building.set_scada_broken(!building.scada_broken);
-                    }
+                    building.set_powered(powered);
                 }
             }
         }
+        found
     }
 
-    /// Sets SCADA broken state for a specific building by block ID
+    /// Switches the window lights of every building on or off
     ///
     /// # Arguments
-    /// * `block_id` - The ID of the block containing the building
-    /// * `broken` - Whether the SCADA should be broken
-    pub fn set_scada_broken(&mut self, block_id: usize, broken: bool) {
-        if let Some(block) = self.blocks.get_mut(&block_id) {
+    /// * `lit` - True at night
+    pub fn set_windows_lit(&mut self, lit: bool) {
+        for block in self.blocks.values_mut() {
             for obj in &mut block.objects {
                 if let Some(building) = obj.as_any_mut().downcast_mut::<crate::block::Building>() {
-                    if building.has_scada {
-                        building.set_scada_broken(broken);
-                    }
+                    building.set_windows_lit(lit);
                 }
             }
         }
     }
 
-    /// Toggles SCADA broken state for ALL buildings with SCADA in the city
-    pub fn toggle_all_scada(&mut self) {
+    /// Turns the power back on in every district
+    pub fn restore_all_power(&mut self) {
+        for intersection in self.intersections.values_mut() {
+            intersection.set_powered(true);
+        }
         for block in self.blocks.values_mut() {
             for obj in &mut block.objects {
                 if let Some(building) = obj.as_any_mut().downcast_mut::<crate::block::Building>() {
-                    if building.has_scada {
-                        building.set_scada_broken(!building.scada_broken);
-                    }
+                    building.set_powered(true);
                 }
             }
         }
     }
 
-    /// Resets all SCADA systems to working state (not broken)
-    pub fn reset_all_scada(&mut self) {
+    /// Closes a road to traffic or reopens it
+    ///
+    /// A closed road gets no new cars and is lined with traffic cones in the
+    /// blocks along it; cars on the roads it crosses wait at those
+    /// intersections until it reopens.
+    ///
+    /// # Arguments
+    /// * `road_id` - ID of the road (0-2 vertical, 3-4 horizontal)
+    /// * `closed` - True to close the road
+    ///
+    /// # Returns
+    /// `true` if the city has the road
+    pub fn set_road_closed(&mut self, road_id: usize, closed: bool) -> bool {
+        use crate::block::TrafficCone;
+
+        let Some(road) = self.roads.get_mut(&road_id) else {
+            return false;
+        };
+        road.closed = closed;
+        self.car_spawner.set_road_closed(road_id, closed);
+
+        for block_id in &road.adjacent_block_ids {
+            let Some(block) = self.blocks.get_mut(block_id) else {
+                continue;
+            };
+            block.objects.retain(|obj| {
+                obj.as_any()
+                    .downcast_ref::<TrafficCone>()
+                    .is_none_or(|cone| cone.road_id != road_id)
+            });
+            let side = block
+                .adjacent_roads
+                .iter()
+                .find(|&(_, &id)| id == road_id)
+                .map(|(&side, _)| side);
+            if let Some(side) = side.filter(|_| closed) {
+                for cone in TrafficCone::row_along(block, side, road_id) {
+                    block.add_object(Box::new(cone));
+                }
+            }
+        }
+        true
+    }
+
+    /// Reopens every closed road
+    pub fn reopen_all_roads(&mut self) {
+        let closed: Vec<usize> = self
+            .roads
+            .values()
+            .filter(|road| road.closed)
+            .map(|road| road.index)
+            .collect();
+        for road_id in closed {
+            self.set_road_closed(road_id, false);
+        }
+    }
+
+    /// Advances the light override of the intersection at a screen position
+    /// through Auto → AllRed → AllGreen → Auto
+    ///
+    /// # Arguments
+    /// * `x` - X coordinate in pixels
+    /// * `y` - Y coordinate in pixels
+    ///
+    /// # Returns
+    /// The intersection ID and its new override, or None if no intersection is there
+    pub fn cycle_intersection_override_at(
+        &mut self,
+        x: f32,
+        y: f32,
+    ) -> Option<(usize, Option<LightOverride>)> {
+        let id = self.find_intersection_at_position(x, y)?;
+        let intersection = self.intersections.get_mut(&id)?;
+        let next = LightOverride::cycle(intersection.override_state);
+        intersection.set_override(next);
+        Some((id, next))
+    }
+
+    /// Records which roads border each block, on both the blocks and the roads
+    ///
+    /// Call after adding or regenerating blocks; any previous links are replaced.
+    pub fn link_blocks_to_roads(&mut self) {
+        for road in self.roads.values_mut() {
+            road.adjacent_block_ids.clear();
+        }
+
+        let mut block_ids: Vec<usize> = self.blocks.keys().copied().collect();
+        block_ids.sort_unstable();
+
+        for block_id in block_ids {
+            let block = self.blocks.get_mut(&block_id).unwrap();
+            block.adjacent_roads.clear();
+            for road in self.roads.values_mut() {
+                if let Some(side) = road.side_of_block(block) {
+                    block.connect_road(side, road.index);
+                    road.adjacent_block_ids.push(block_id);
+                }
+            }
+        }
+    }
+
+    /// Opens or closes every barrier in the city
+    ///
+    /// The arms animate toward the new state on subsequent updates.
+    ///
+    /// # Arguments
+    /// * `open` - true to raise the barriers, false to lower them
+    pub fn set_barrier_state(&mut self, open: bool) {
         for block in self.blocks.values_mut() {
             for obj in &mut block.objects {
-                if let Some(building) = obj.as_any_mut().downcast_mut::<crate::block::Building>() {
-                    if building.has_scada {
-                        building.set_scada_broken(false);
-                    }
+                if let Some(barrier) = obj.as_any_mut().downcast_mut::<crate::block::Barrier>() {
+                    barrier.set_open(open);
+                }
+            }
+        }
+    }
+
+    /// Marks every barrier as broken by a team, or as repaired
+    ///
+    /// # Arguments
+    /// * `team` - Team that broke the barriers, or None once repaired
+    pub fn set_barrier_broken_by(&mut self, team: Option<&crate::team::Team>) {
+        for block in self.blocks.values_mut() {
+            for obj in &mut block.objects {
+                if let Some(barrier) = obj.as_any_mut().downcast_mut::<crate::block::Barrier>() {
+                    barrier.broken_by = team.map(|team| team.color);
+                }
+            }
+        }
+    }
+
+    /// Every barrier in the city, with the block it stands in
+    fn barriers(&self) -> impl Iterator<Item = (&Block, &crate::block::Barrier)> {
+        self.blocks.values().flat_map(|block| {
+            block
+                .objects
+                .iter()
+                .filter_map(|obj| obj.as_any().downcast_ref::<crate::block::Barrier>())
+                .map(move |barrier| (block, barrier))
+        })
+    }
+
+    /// Returns the stop points (in pixels) of all barriers blocking traffic
+    pub fn barrier_stop_points(&self) -> Vec<(f32, f32)> {
+        self.barriers()
+            .filter(|(_, barrier)| barrier.blocks_traffic())
+            .map(|(block, barrier)| barrier.stop_point(block))
+            .collect()
+    }
+
+    /// Returns the stop points (in pixels) of all barriers, open or closed
+    pub fn barrier_positions(&self) -> Vec<(f32, f32)> {
+        self.barriers()
+            .map(|(block, barrier)| barrier.stop_point(block))
+            .collect()
+    }
+
+    /// Returns the gates of all guarded compounds, with their current state
+    pub fn gates(&self) -> Vec<Gate> {
+        let mut gates = Vec::new();
+        for block in self.blocks.values() {
+            for obj in &block.objects {
+                if let Some(compound) = obj.as_any().downcast_ref::<GuardedCompound>()
+                    && let Some(gate) = compound.gate(block)
+                {
+                    gates.push(gate);
+                }
+            }
+        }
+        gates
+    }
+
+    /// Returns the IDs of all blocks containing a guarded compound
+    pub fn compound_block_ids(&self) -> Vec<usize> {
+        self.gates().iter().map(|gate| gate.block_id).collect()
+    }
+
+    /// Returns the IDs of all blocks containing a parking lot
+    pub fn parking_lot_block_ids(&self) -> Vec<usize> {
+        use crate::block::ParkingLot;
+
+        self.blocks
+            .values()
+            .filter(|block| block.objects.iter().any(|obj| obj.as_any().is::<ParkingLot>()))
+            .map(|block| block.id)
+            .collect()
+    }
+
+    /// Parks a car in the parking lot nearest a position
+    ///
+    /// # Arguments
+    /// * `x` - X coordinate in pixels
+    /// * `y` - Y coordinate in pixels
+    ///
+    /// # Returns
+    /// `true` if the car was parked, `false` if the city has no parking lot
+    /// or the nearest one is full
+    pub fn park_car_near(&mut self, x: f32, y: f32) -> bool {
+        use crate::block::ParkingLot;
+        use macroquad::prelude::vec2;
+
+        let point = vec2(x, y);
+        self.blocks
+            .values_mut()
+            .flat_map(|block| {
+                let area = (block.x(), block.y(), block.width(), block.height());
+                block.objects.iter_mut().map(move |obj| (area, obj))
+            })
+            .filter_map(|((x, y, width, height), obj)| {
+                let lot = obj.as_any_mut().downcast_mut::<ParkingLot>()?;
+                let center = vec2(
+                    x + (lot.x_offset_percent + lot.width_percent / 2.0) * width,
+                    y + (lot.y_offset_percent + lot.height_percent / 2.0) * height,
+                );
+                Some((center.distance(point), lot))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .is_some_and(|(_, lot)| lot.park())
+    }
+
+    /// Takes cars that reached a block with a parking lot off the road and
+    /// parks them in the nearest lot
+    ///
+    /// A car has reached its block once it has driven its whole route. Cars
+    /// finding the lot full drive on with no destination.
+    ///
+    /// # Returns
+    /// Number of cars parked
+    pub fn park_arrived_cars(&mut self) -> usize {
+        let lots = self.parking_lot_block_ids();
+        let arrived: Vec<(usize, f32, f32)> = self
+            .cars
+            .iter()
+            .filter(|car| {
+                car.route.is_empty() && car.destination.is_some_and(|id| lots.contains(&id))
+            })
+            .map(|car| (car.id, car.x(), car.y()))
+            .collect();
+
+        let mut parked = 0;
+        for (car_id, x, y) in arrived {
+            if self.park_car_near(x, y) {
+                self.cars.retain(|car| car.id != car_id);
+                parked += 1;
+            } else if let Some(car) = self.cars.iter_mut().find(|car| car.id == car_id) {
+                car.destination = None;
+            }
+        }
+        parked
+    }
+
+    /// Returns everywhere cars stop besides lights, crosswalks and other cars
+    pub fn stops(&self) -> Stops {
+        Stops {
+            barriers: self.barrier_stop_points(),
+            gates: self.gates(),
+            closed_roads: self
+                .roads
+                .values()
+                .filter(|road| road.closed)
+                .map(|road| road.index)
+                .collect(),
+        }
+    }
+
+    /// Applies `f` to every LED display in the given block, or in every
+    /// block when no ID is given
+    ///
+    /// # Returns
+    /// `true` if at least one LED display was found
+    fn for_each_led_display(
+        &mut self,
+        block_id: Option<usize>,
+        mut f: impl FnMut(&mut LEDDisplay),
+    ) -> bool {
+        let mut found = false;
+        for block in self.blocks.values_mut() {
+            if block_id.is_some_and(|id| id != block.id) {
+                continue;
+            }
+            for obj in &mut block.objects {
+                if let Some(led) = obj.as_any_mut().downcast_mut::<LEDDisplay>() {
+                    f(led);
+                    found = true;
                 }
             }
         }
+        found
+    }
+
+    /// Changes the text shown by the LED displays in a block
+    ///
+    /// # Arguments
+    /// * `block_id` - The ID of the block containing the display
+    /// * `text` - New text to show
+    ///
+    /// # Returns
+    /// `true` if the block contains an LED display
+    pub fn set_led_text(&mut self, block_id: usize, text: &str) -> bool {
+        self.for_each_led_display(Some(block_id), |led| led.set_text(text))
+    }
+
+    /// Changes the display mode of the LED displays in a block
+    ///
+    /// # Arguments
+    /// * `block_id` - The ID of the block containing the display
+    /// * `mode` - New display mode (e.g. `Broken` while the sign is hacked)
+    ///
+    /// # Returns
+    /// `true` if the block contains an LED display
+    pub fn set_led_mode(&mut self, block_id: usize, mode: LEDDisplayMode) -> bool {
+        self.for_each_led_display(Some(block_id), |led| led.mode = mode.clone())
+    }
+
+    /// Raises an alert on every LED display in the city
+    ///
+    /// See `LEDDisplay::enqueue_alert` for how alerts are shown.
+    ///
+    /// # Arguments
+    /// * `text` - Text to show
+    /// * `priority` - Urgency; more urgent alerts preempt less urgent ones
+    /// * `duration` - Seconds until the alert expires, or None to keep it
+    ///   until `led_remove` is called
+    ///
+    /// # Returns
+    /// `true` if the city has an LED display
+    pub fn led_enqueue(
+        &mut self,
+        text: impl Into<String>,
+        priority: LEDPriority,
+        duration: Option<f32>,
+    ) -> bool {
+        let text = text.into();
+        self.for_each_led_display(None, |led| led.enqueue_alert(text.clone(), priority, duration))
+    }
+
+    /// Queues a message on every LED display in the city, shown once after
+    /// the messages queued before it
+    ///
+    /// # Returns
+    /// `true` if the city has an LED display
+    pub fn led_enqueue_message(&mut self, msg: LedMessage) -> bool {
+        self.for_each_led_display(None, |led| led.enqueue(msg.clone()))
+    }
+
+    /// Removes an alert from every LED display in the city
+    pub fn led_remove(&mut self, text: &str) {
+        self.for_each_led_display(None, |led| {
+            led.remove_alert(text);
+        });
+    }
+
+    /// Removes every alert and queued message from the LED displays in the city
+    pub fn led_clear_queue(&mut self) {
+        self.for_each_led_display(None, |led| led.clear_queue());
+    }
+
+    /// Gets the city's first LED display, if any
+    fn first_led_display(&self) -> Option<&LEDDisplay> {
+        self.blocks
+            .values()
+            .flat_map(|block| &block.objects)
+            .find_map(|obj| obj.as_any().downcast_ref::<LEDDisplay>())
+    }
+
+    /// Gets the alerts raised on the city's first LED display
+    ///
+    /// Lets the alerts be carried over when the display is recreated.
+    pub fn led_queue(&self) -> Vec<LEDAlert> {
+        self.first_led_display()
+            .map_or_else(Vec::new, |led| led.alerts().to_vec())
+    }
+
+    /// Gets the messages queued on the city's first LED display, the one
+    /// being shown first
+    pub fn led_messages(&self) -> Vec<LedMessage> {
+        self.first_led_display()
+            .map_or_else(Vec::new, |led| led.messages().iter().cloned().collect())
+    }
+
+    /// Gets whether an alert with this text is raised on any LED display
+    pub fn led_has_message(&self, text: &str) -> bool {
+        self.blocks.values().flat_map(|block| &block.objects).any(|obj| {
+            obj.as_any()
+                .downcast_ref::<LEDDisplay>()
+                .is_some_and(|led| led.has_alert(text))
+        })
+    }
+
+    /// Returns true if the block contains a building with a SCADA system
+    ///
+    /// # Arguments
+    /// * `block_id` - The ID of the block to check
+    pub fn block_has_scada(&self, block_id: usize) -> bool {
+        self.blocks.get(&block_id).is_some_and(|block| {
+            block.objects.iter().any(|obj| {
+                obj.as_any()
+                    .downcast_ref::<crate::block::Building>()
+                    .is_some_and(|building| building.has_scada)
+            })
+        })
+    }
+
+    /// Returns the IDs of all blocks containing a SCADA building
+    pub fn scada_block_ids(&self) -> Vec<usize> {
+        self.blocks
+            .keys()
+            .copied()
+            .filter(|&id| self.block_has_scada(id))
+            .collect()
+    }
+
+    /// Computes the center of a block in pixel coordinates
+    ///
+    /// # Arguments
+    /// * `block_id` - The ID of the block
+    ///
+    /// # Returns
+    /// Optional (x, y) pixel position of the block center if the block exists
+    pub fn building_world_position(&self, block_id: usize) -> Option<(f32, f32)> {
+        self.blocks.get(&block_id).map(|block| {
+            (
+                block.x() + block.width() / 2.0,
+                block.y() + block.height() / 2.0,
+            )
+        })
+    }
+
+    /// Collects what the minimap shows, as fractions of the screen
+    ///
+    /// Roads, blocks and intersections are listed in ID order. The LED
+    /// display block is left out, as it is not drawn as grass either.
+    ///
+    /// # Arguments
+    /// * `all_lights_red` - If true, every light is shown red, as the
+    ///   emergency stop mode draws them
+    pub fn minimap_state(&self, all_lights_red: bool) -> MinimapState {
+        use crate::block::Building;
+        use crate::traffic_light::LightState;
+        use macroquad::math::{vec2, Rect};
+
+        let mut road_ids: Vec<_> = self.roads.keys().copied().collect();
+        road_ids.sort_unstable();
+        let mut block_ids: Vec<_> = self.blocks.keys().copied().filter(|&id| id != 0).collect();
+        block_ids.sort_unstable();
+        let blocks: Vec<_> = block_ids.iter().map(|id| &self.blocks[id]).collect();
+
+        let compromised_buildings = blocks
+            .iter()
+            .flat_map(|block| {
+                block
+                    .objects
+                    .iter()
+                    .filter_map(|obj| obj.as_any().downcast_ref::<Building>())
+                    .filter(|building| building.is_scada_compromised())
+                    .map(|building| {
+                        vec2(
+                            block.x_percent
+                                + (building.x_offset_percent + building.width_percent / 2.0)
+                                    * block.width_percent,
+                            block.y_percent
+                                + (building.y_offset_percent + building.depth_percent / 2.0)
+                                    * block.height_percent,
+                        )
+                    })
+            })
+            .collect();
+
+        let mut intersection_ids: Vec<_> = self
+            .intersections
+            .values()
+            .filter(|intersection| intersection.has_light())
+            .map(|intersection| intersection.id)
+            .collect();
+        intersection_ids.sort_unstable();
+        let intersections = intersection_ids
+            .iter()
+            .map(|id| {
+                let intersection = &self.intersections[id];
+                let light = |direction| {
+                    if all_lights_red {
+                        Some(LightState::default_red())
+                    } else {
+                        intersection.get_light_state_for_direction(direction)
+                    }
+                };
+                MinimapIntersection {
+                    position: vec2(intersection.x_percent, intersection.y_percent),
+                    vertical: light(Direction::Down),
+                    horizontal: light(Direction::Right),
+                }
+            })
+            .collect();
+
+        MinimapState {
+            roads: road_ids
+                .iter()
+                .map(|id| (self.roads[id].orientation, self.roads[id].position_percent))
+                .collect(),
+            blocks: blocks
+                .iter()
+                .map(|block| {
+                    Rect::new(
+                        block.x_percent,
+                        block.y_percent,
+                        block.width_percent,
+                        block.height_percent,
+                    )
+                })
+                .collect(),
+            cars: self
+                .cars
+                .iter()
+                .map(|car| (vec2(car.x_percent, car.y_percent), car.color))
+                .collect(),
+            compromised_buildings,
+            intersections,
+        }
+    }
+
+    // ========================================================================
+    // Particle Effects
+    // ========================================================================
+
+    /// Starts a particle burst at a pixel position
+    ///
+    /// # Arguments
+    /// * `world_x` - Burst center X position in pixels
+    /// * `world_y` - Burst center Y position in pixels
+    /// * `color` - Particle color
+    /// * `count` - Number of particles to spawn
+    pub fn trigger_particle_burst(
+        &mut self,
+        world_x: f32,
+        world_y: f32,
+        color: macroquad::prelude::Color,
+        count: usize,
+    ) {
+        self.particle_bursts
+            .push(ParticleBurst::new(world_x, world_y, color, count));
+    }
+
+    /// Sets the building in a block on fire, or changes the intensity of a
+    /// fire already burning or still fading out there
+    ///
+    /// # Arguments
+    /// * `block_id` - The ID of the burning block
+    /// * `intensity` - Fire intensity from 0.0 to 1.0
+    ///
+    /// # Returns
+    /// `true` if the block exists
+    pub fn set_building_fire(&mut self, block_id: usize, intensity: f32) -> bool {
+        use crate::constants::effects::FIRE_BASE_OFFSET;
+
+        if let Some(fire) = self.fires.get_mut(&block_id) {
+            fire.set_intensity(intensity);
+            return true;
+        }
+
+        let Some((x, y)) = self.building_world_position(block_id) else {
+            return false;
+        };
+        self.fires
+            .insert(block_id, BuildingFire::new(x, y - FIRE_BASE_OFFSET, intensity));
+        true
+    }
+
+    /// Puts out the fire in a block, which then fades away
+    ///
+    /// # Returns
+    /// `true` if the block was on fire
+    pub fn extinguish_building_fire(&mut self, block_id: usize) -> bool {
+        self.fires.get_mut(&block_id).is_some_and(|fire| fire.extinguish())
+    }
+
+    /// Removes every building fire at once, without fading
+    pub fn clear_building_fires(&mut self) {
+        self.fires.clear();
+    }
+
+    /// Advances all particle bursts and fires, and removes the finished bursts
+    /// and the fires that have faded away
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update_particles(&mut self, dt: f32) {
+        for burst in &mut self.particle_bursts {
+            burst.update(dt);
+        }
+        self.particle_bursts.retain(|burst| burst.alive);
+
+        for fire in self.fires.values_mut() {
+            fire.update(dt);
+        }
+        self.fires.retain(|_, fire| !fire.is_gone());
+    }
+
+    /// Renders all active particle bursts
+    pub fn render_particles(&self) {
+        for burst in &self.particle_bursts {
+            burst.render();
+        }
     }
 
     /// Returns the number of roads in the city
@@ -186,6 +1061,48 @@ impl City {
         self.cars.len()
     }
 
+    /// Returns the statistics from the most recent update
+    pub fn stats(&self) -> SimulationStats {
+        self.stats
+    }
+
+    /// Describes the last update for step mode's per-frame log
+    ///
+    /// # Returns
+    /// One line with the car counts from `stats`, and one with the vertical
+    /// and horizontal light of each intersection by ID (`-` without lights)
+    pub fn step_report(&self) -> [String; 2] {
+        use crate::traffic_light::LightState;
+
+        let letter = |state: LightState| match state {
+            LightState::Red(_) => 'R',
+            LightState::Yellow(_) => 'Y',
+            LightState::Green(_) => 'G',
+        };
+        let mut ids: Vec<_> = self.intersections.keys().copied().collect();
+        ids.sort();
+        let lights: Vec<_> = ids
+            .iter()
+            .map(|id| match &self.intersections[id].light {
+                Some(light) => format!(
+                    "{} {}/{}",
+                    id,
+                    letter(light.vertical_state),
+                    letter(light.horizontal_state)
+                ),
+                None => format!("{} -", id),
+            })
+            .collect();
+
+        [
+            format!(
+                "{} cars, {} stopped, {} in intersections",
+                self.stats.total_cars, self.stats.cars_stopped, self.stats.cars_in_intersections
+            ),
+            format!("Lights (V/H): {}", lights.join(", ")),
+        ]
+    }
+
     /// Renders all blocks in the city
     ///
     /// This will render all objects contained in each block.
@@ -242,6 +1159,17 @@ impl City {
         self.roads.get_mut(&id)
     }
 
+    /// Gets a reference to a car by its ID
+    ///
+    /// # Arguments
+    /// * `id` - The car ID to search for
+    ///
+    /// # Returns
+    /// Optional reference to the car if it is still in the city
+    pub fn get_car(&self, id: usize) -> Option<&Car> {
+        self.cars.iter().find(|car| car.id == id)
+    }
+
     /// Gets a reference to an intersection by its ID
     ///
     /// # Arguments
@@ -274,22 +1202,251 @@ impl City {
         self.blocks.clear();
     }
 
-    /// Clears all intersections from the city
-    pub fn clear_intersections(&mut self) {
-        self.intersections.clear();
-    }
+    /// Clears all intersections from the city
+    pub fn clear_intersections(&mut self) {
+        self.intersections.clear();
+    }
+
+    /// Clears all cars from the city
+    pub fn clear_cars(&mut self) {
+        self.cars.clear();
+    }
+
+    /// Moves the cars back into their lanes after the window is resized
+    ///
+    /// Cars keep their positions as percentages of the screen, but lanes lie
+    /// `LANE_OFFSET` pixels from the road center, so a resize scales every
+    /// car's distance from its road along with the window. Each car is put
+    /// back at the same distance in pixels from the nearest road it drives
+    /// along. Cars that can't be placed again are removed: those halfway
+    /// through a turn, whose arc is in pixels, and those no longer on a road.
+    ///
+    /// # Arguments
+    /// * `old_size` - Screen width and height in pixels before the resize
+    /// * `new_size` - Screen width and height in pixels after the resize
+    ///
+    /// # Returns
+    /// Number of cars removed
+    pub fn handle_resize(&mut self, old_size: (f32, f32), new_size: (f32, f32)) -> usize {
+        let before = self.cars.len();
+        let roads = &self.roads;
+        self.cars.retain_mut(|car| {
+            if car.turn.is_some() {
+                return false;
+            }
+            let (orientation, across, old, new) = if car.direction.is_vertical() {
+                (Orientation::Vertical, &mut car.x_percent, old_size.0, new_size.0)
+            } else {
+                (Orientation::Horizontal, &mut car.y_percent, old_size.1, new_size.1)
+            };
+
+            let distance = |road: &Road| (*across - road.position_percent).abs();
+            let Some(road) = roads
+                .values()
+                .filter(|road| road.orientation == orientation)
+                .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            else {
+                return false;
+            };
+            let offset = (*across - road.position_percent) * old;
+            if offset.abs() > ROAD_WIDTH / 2.0 {
+                return false;
+            }
+
+            *across = road.position_percent + offset / new;
+            car.road_index = road.index;
+            true
+        });
+        before - self.cars.len()
+    }
+
+    /// Clears all pedestrians from the city
+    pub fn clear_pedestrians(&mut self) {
+        self.pedestrians.clear();
+    }
+
+    /// Clears all elements from the city
+    pub fn clear(&mut self) {
+        self.roads.clear();
+        self.blocks.clear();
+        self.intersections.clear();
+        self.cars.clear();
+        self.pedestrians.clear();
+    }
+
+    // ========================================================================
+    // Save and Restore
+    // ========================================================================
+
+    /// Collects the simulation state for the save file
+    ///
+    /// # Arguments
+    /// * `controls` - The operator's switches, saved alongside the city
+    ///
+    /// # Returns
+    /// Everything needed to put the city back as it is now, apart from the
+    /// blocks themselves
+    pub fn to_snapshot(&self, controls: Controls) -> CitySnapshot {
+        use crate::block::{Barrier, Building, ParkingLot};
+        use crate::snapshot::{BuildingScada, SNAPSHOT_VERSION};
+
+        let mut parked_cars = HashMap::new();
+        let mut scada = Vec::new();
+        let mut unpowered_blocks = Vec::new();
+        let mut barrier_broken_by = None;
+        for block in self.blocks.values() {
+            for obj in &block.objects {
+                let obj = obj.as_any();
+                if let Some(lot) = obj.downcast_ref::<ParkingLot>() {
+                    parked_cars.insert(block.id, lot.occupied);
+                } else if let Some(barrier) = obj.downcast_ref::<Barrier>() {
+                    barrier_broken_by = barrier_broken_by.or(barrier.broken_by);
+                } else if let Some(building) = obj.downcast_ref::<Building>() {
+                    if building.has_scada {
+                        scada.push(BuildingScada {
+                            block_id: block.id,
+                            state: building.scada_state,
+                            compromised_by: building.compromised_by.clone(),
+                        });
+                    }
+                    if !building.powered {
+                        unpowered_blocks.push(block.id);
+                    }
+                }
+            }
+        }
+        scada.sort_by_key(|building| building.block_id);
+        unpowered_blocks.sort();
+        unpowered_blocks.dedup();
+
+        let mut intersections: Vec<_> = self.intersections.values().cloned().collect();
+        intersections.sort_by_key(|intersection| intersection.id);
+        let mut roads: Vec<_> = self.roads.values().cloned().collect();
+        roads.sort_by_key(|road| road.index);
+
+        CitySnapshot {
+            version: SNAPSHOT_VERSION,
+            cars: self.cars.clone(),
+            pedestrians: self.pedestrians.clone(),
+            intersections,
+            roads,
+            scada,
+            fires: self.fires.iter().map(|(&id, fire)| (id, fire.intensity)).collect(),
+            unpowered_blocks,
+            led_messages: self.led_queue(),
+            barrier_broken_by,
+            parked_cars,
+            traffic_rule: self.traffic_rule,
+            adaptive_lights: self.adaptive_lights,
+            controls,
+        }
+    }
+
+    /// Puts the simulation back in a saved state
+    ///
+    /// The saved cars, pedestrians and intersections replace the current
+    /// ones. Roads and blocks keep their layout, which is generated from the
+    /// constants; the roads are closed or reopened and the objects in the
+    /// blocks (SCADA buildings, fires, power, the LED display, the barrier
+    /// and parking lots) are set back to their saved state.
+    ///
+    /// # Arguments
+    /// * `snapshot` - State collected by `to_snapshot`, possibly in an
+    ///   earlier session
+    ///
+    /// # Returns
+    /// The operator's switches saved with the city, for the caller to apply
+    pub fn restore_snapshot(&mut self, snapshot: CitySnapshot) -> Controls {
+        self.restore_block_objects(&snapshot);
+
+        for car in &snapshot.cars {
+            Car::reserve_id(car.id);
+        }
+        self.cars = snapshot.cars;
+        self.pedestrians = snapshot.pedestrians;
+        self.intersections = snapshot
+            .intersections
+            .into_iter()
+            .map(|intersection| (intersection.id, intersection))
+            .collect();
+        for road in &snapshot.roads {
+            self.set_road_closed(road.index, road.closed);
+        }
+
+        self.clear_building_fires();
+        for (block_id, intensity) in snapshot.fires {
+            self.set_building_fire(block_id, intensity);
+        }
+        self.traffic_rule = snapshot.traffic_rule;
+        self.adaptive_lights = snapshot.adaptive_lights;
+        snapshot.controls
+    }
+
+    /// Sets the objects in the blocks (parking lots, the barrier, SCADA
+    /// buildings and their power, and the LED queue) back to a saved state
+    fn restore_block_objects(&mut self, snapshot: &CitySnapshot) {
+        use crate::block::{Barrier, Building, ParkingLot};
 
-    /// Clears all cars from the city
-    pub fn clear_cars(&mut self) {
-        self.cars.clear();
+        let scada: HashMap<_, _> =
+            snapshot.scada.iter().map(|building| (building.block_id, building)).collect();
+        for block in self.blocks.values_mut() {
+            let parked = snapshot.parked_cars.get(&block.id).copied().unwrap_or(0);
+            let powered = !snapshot.unpowered_blocks.contains(&block.id);
+            for obj in &mut block.objects {
+                let obj = obj.as_any_mut();
+                if let Some(lot) = obj.downcast_mut::<ParkingLot>() {
+                    lot.occupied = parked.min(lot.capacity);
+                } else if let Some(barrier) = obj.downcast_mut::<Barrier>() {
+                    barrier.broken_by = snapshot.barrier_broken_by;
+                } else if let Some(building) = obj.downcast_mut::<Building>() {
+                    building.set_powered(powered);
+                    if let Some(saved) = scada.get(&block.id).filter(|_| building.has_scada) {
+                        building.scada_state = saved.state;
+                        building.compromised_by = saved.compromised_by.clone();
+                    }
+                }
+            }
+        }
+
+        self.led_clear_queue();
+        for alert in snapshot.led_messages.iter().cloned() {
+            self.led_enqueue(alert.text, alert.priority, alert.remaining);
+        }
     }
 
-    /// Clears all elements from the city
-    pub fn clear(&mut self) {
-        self.roads.clear();
-        self.blocks.clear();
-        self.intersections.clear();
-        self.cars.clear();
+    /// Generates the blocks again after the window is resized, keeping the
+    /// state of the objects in them
+    ///
+    /// The blocks are laid out around roads `ROAD_WIDTH` pixels wide, so
+    /// their percentages change with the window size. The objects in the
+    /// new blocks are set back to their state before the resize, as a saved
+    /// city is restored, and each fire moves to its building's new position
+    /// with its flames, smoke and fade-out kept. Queued LED messages are
+    /// kept too, the shown one starting over.
+    ///
+    /// # Arguments
+    /// * `add_blocks` - Adds the blocks generated for the current window size
+    pub fn regenerate_blocks(&mut self, add_blocks: impl FnOnce(&mut City)) {
+        use crate::constants::effects::FIRE_BASE_OFFSET;
+
+        let snapshot = self.to_snapshot(Controls::default());
+        let led_messages = self.led_messages();
+        self.clear_blocks();
+        add_blocks(self);
+        self.restore_block_objects(&snapshot);
+        for msg in led_messages {
+            self.led_enqueue_message(msg);
+        }
+
+        let mut fires = std::mem::take(&mut self.fires);
+        fires.retain(|&block_id, fire| match self.building_world_position(block_id) {
+            Some((x, y)) => {
+                fire.move_to(x, y - FIRE_BASE_OFFSET);
+                true
+            }
+            None => false,
+        });
+        self.fires = fires;
     }
 
     // ========================================================================
@@ -310,14 +1467,12 @@ impl City {
         for road in self.roads.values() {
             match road.orientation {
                 crate::road::Orientation::Vertical => {
-                    let road_x = road.position_percent * macroquad::prelude::screen_width();
-                    if (x - road_x).abs() <= half_road {
+                    if (x - road.position()).abs() <= half_road {
                         return Some(road.index);
                     }
                 }
                 crate::road::Orientation::Horizontal => {
-                    let road_y = road.position_percent * macroquad::prelude::screen_height();
-                    if (y - road_y).abs() <= half_road {
+                    if (y - road.position()).abs() <= half_road {
                         return Some(road.index);
                     }
                 }
@@ -326,6 +1481,24 @@ impl City {
         None
     }
 
+    /// Finds which car a point is on, if any
+    ///
+    /// Cars drawn later (on top) win where cars overlap.
+    ///
+    /// # Arguments
+    /// * `x` - X coordinate in pixels
+    /// * `y` - Y coordinate in pixels
+    ///
+    /// # Returns
+    /// Optional car ID if the point is inside a car's bounds
+    pub fn find_car_at_position(&self, x: f32, y: f32) -> Option<usize> {
+        self.cars
+            .iter()
+            .rev()
+            .find(|car| car.contains_point(x, y))
+            .map(|car| car.id)
+    }
+
     /// Finds which intersection a point is in, if any
     ///
     /// # Arguments
@@ -375,15 +1548,14 @@ impl City {
     /// the background layer.
     ///
     /// # Arguments
-    /// * `time` - Current time for animations (needed for SCADA flashing and barrier animation)
-    /// * `danger_mode` - Whether danger mode is active
-    /// * `barrier_open` - Whether the barrier gate is in open state
-    pub fn render_environment(&self, time: f64, danger_mode: bool, barrier_open: bool) {
+    /// * `time` - Current time for animations (needed for SCADA flashing)
+    /// * `lighting` - Day/night cycle the scenery is shaded for
+    pub fn render_environment(&self, time: f64, lighting: &TimeOfDay) {
         use crate::block::RenderContext;
         use crate::rendering::{draw_intersection_markings, draw_road_lines};
 
-        // Render grass blocks with time for SCADA animations and barrier control
-        let context = RenderContext::new(time, danger_mode, barrier_open);
+        // Render grass blocks with time for SCADA animations
+        let context = RenderContext::new(time, lighting);
         for block in self.blocks.values() {
             // Only render blocks with grass (not LED display block)
             if block.id != 0 {
@@ -398,54 +1570,101 @@ impl City {
         draw_intersection_markings(&intersections);
     }
 
-    /// Renders dynamic traffic elements (cars and traffic lights)
+    /// Renders dynamic traffic elements (pedestrians, cars and traffic lights)
     ///
     /// Draws moving and interactive elements:
     /// - Traffic lights at all intersections
     /// - All cars with directional sprites
+    /// - All pedestrians
     ///
-    /// Cars are drawn first (background), then traffic lights (foreground).
+    /// Pedestrians and cars are drawn first (background), then traffic
+    /// lights (foreground). After dusk, headlight cones are drawn under the
+    /// cars and lit lamps glow.
     ///
     /// # Arguments
+    /// * `time` - Current simulation time for animations (flashing lights)
     /// * `all_lights_red` - If true, forces all traffic lights to red (emergency mode)
-    pub fn render_traffic(&self, all_lights_red: bool) {
-        use crate::rendering::draw_car;
+    /// * `lighting` - Day/night cycle, for headlights and glowing lamps
+    pub fn render_traffic(&self, time: f64, all_lights_red: bool, lighting: &TimeOfDay) {
+        use crate::constants::day_night::GLOW_ALPHA;
+        use crate::rendering::{draw_car, draw_headlights};
         use crate::traffic_light::draw_traffic_lights;
 
         // Convert HashMap values to Vec for rendering
         let intersections: Vec<_> = self.intersections.values().cloned().collect();
 
+        self.render_pedestrians();
+
+        // Headlights light up the road ahead of every car
+        let darkness = 1.0 - lighting.ambient();
+        if darkness > 0.0 {
+            for car in &self.cars {
+                draw_headlights(car, darkness);
+            }
+        }
+
         // Draw all cars first (behind traffic lights)
         for car in &self.cars {
-            draw_car(car);
+            draw_car(car, time);
         }
 
         // Draw traffic lights on top
-        draw_traffic_lights(&intersections, all_lights_red);
+        draw_traffic_lights(&intersections, all_lights_red, time, GLOW_ALPHA * darkness);
+    }
+
+    /// Renders the number of cars queued on each approach of every
+    /// intersection (debug overlay)
+    pub fn render_queue_lengths(&self) {
+        for intersection in self.intersections.values() {
+            intersection.render_queue_lengths();
+        }
+    }
+
+    /// Outlines what the simulation checks cars against, for the debug overlay
+    ///
+    /// Draws each car's bounding box and the radius around each intersection
+    /// within which a car counts as inside it (the circle itself for
+    /// roundabouts).
+    pub fn render_debug_bounds(&self) {
+        use crate::constants::vehicle::INTERSECTION_RADIUS;
+        use macroquad::prelude::{draw_circle_lines, draw_rectangle_lines, MAGENTA, SKYBLUE};
+
+        for intersection in self.intersections.values() {
+            let radius = intersection.roundabout_radius().unwrap_or(INTERSECTION_RADIUS);
+            draw_circle_lines(intersection.x(), intersection.y(), radius, 1.0, SKYBLUE);
+        }
+        for car in &self.cars {
+            let bounds = car.bounds();
+            draw_rectangle_lines(bounds.x, bounds.y, bounds.w, bounds.h, 1.0, MAGENTA);
+        }
+    }
+
+    /// Renders all pedestrians as small circles with a shadow dot
+    pub fn render_pedestrians(&self) {
+        use crate::rendering::draw_pedestrian;
+
+        for pedestrian in &self.pedestrians {
+            draw_pedestrian(pedestrian);
+        }
     }
 
     /// Renders UI overlays and decorative elements
     ///
     /// Draws overlay elements that appear on top of the environment and traffic:
-    /// - LED display with scrolling text or danger warning
-    /// - Decorative elements (currently empty but kept for future use)
+    /// - LED display with its own text or queued messages
+    /// - Burning buildings
     ///
     /// This should be called last in the rendering pipeline as it draws
     /// the foreground/UI layer.
     ///
     /// # Arguments
     /// * `time` - Current simulation time for animations
-    /// * `danger_mode` - If true, shows "DANGER" on LED display in red
-    /// * `barrier_open` - Whether the barrier gate is in open state
-    pub fn render_overlays(&self, time: f64, danger_mode: bool, barrier_open: bool) {
+    /// * `lighting` - Day/night cycle, for the LED display's glow
+    pub fn render_overlays(&self, time: f64, lighting: &TimeOfDay) {
         use crate::block::RenderContext;
-        use crate::rendering::draw_guarded_building;
-
-        // Note: draw_guarded_building is currently empty but kept for future use
-        draw_guarded_building(time, &self.cars);
 
         // Create render context with current state
-        let context = RenderContext::new(time, danger_mode, barrier_open);
+        let context = RenderContext::new(time, lighting);
 
         // Render only LED display blocks (id 0)
         // Grass blocks are rendered in render_environment
@@ -454,6 +1673,11 @@ impl City {
                 block.render(&context);
             }
         }
+
+        // Burning buildings, drawn over everything else in the city
+        for fire in self.fires.values() {
+            fire.render(time);
+        }
     }
 
     // ========================================================================
@@ -465,42 +1689,300 @@ impl City {
     /// Uses the internal car spawner to add new cars to the city at
     /// configured intervals. Cars spawn at random road edges with random
     /// properties (color, direction, planned turns).
-    pub fn spawn_cars(&mut self) {
-        self.car_spawner.try_spawn(&mut self.cars);
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    ///
+    /// # Returns
+    /// Number of cars spawned
+    pub fn spawn_cars(&mut self, dt: f32) -> usize {
+        use crate::constants::vehicle::MAX_WRAPPING_CARS;
+
+        // Wrapped cars never leave, so stop adding cars once the city is full
+        if self.edge_behavior == EdgeBehavior::Wrap && self.cars.len() >= MAX_WRAPPING_CARS {
+            return 0;
+        }
+        if !self.car_spawner.try_spawn(dt, &mut self.cars, self.traffic_rule) {
+            return 0;
+        }
+
+        // Some cars head for a guarded compound instead of passing through
+        let compounds = self.compound_block_ids();
+        if let Some(car) = self.cars.last_mut() {
+            car.destination = self.car_spawner.pick_destination(car, &compounds);
+        }
+        1
+    }
+
+    /// Dispatches an emergency vehicle from a random road edge
+    ///
+    /// # Returns
+    /// The road index and direction the vehicle drives along
+    pub fn dispatch_emergency_vehicle(&mut self) -> (usize, Direction) {
+        self.car_spawner.spawn_emergency(&mut self.cars, self.traffic_rule)
+    }
+
+    /// Plans a car's route to an intersection
+    ///
+    /// The route starts at the next intersection ahead of the car; from then
+    /// on the car turns where the route does instead of at random.
+    ///
+    /// # Arguments
+    /// * `car_id` - ID of the car
+    /// * `target` - ID of the intersection to drive to
+    ///
+    /// # Returns
+    /// `true` if the car was routed, `false` if it is unknown, turning, past
+    /// the last intersection on its road or can't reach the target
+    pub fn route_car(&mut self, car_id: usize, target: usize) -> bool {
+        use crate::constants::vehicle::LANE_TOLERANCE;
+        use crate::route::plan_route;
+        use macroquad::prelude::vec2;
+
+        let Some(car) = self.cars.iter().find(|car| car.id == car_id) else {
+            return false;
+        };
+        if car.turn.is_some() {
+            return false;
+        }
+        let (dx, dy) = car.direction.to_vector();
+        let forward = vec2(dx, dy);
+        let position = vec2(car.x(), car.y());
+        let next = self
+            .intersections
+            .values()
+            .map(|intersection| {
+                let offset = vec2(intersection.x(), intersection.y()) - position;
+                (intersection.id, offset)
+            })
+            .filter(|(_, offset)| {
+                offset.perp_dot(forward).abs() < LANE_TOLERANCE && offset.dot(forward) >= 0.0
+            })
+            .min_by(|(_, a), (_, b)| a.dot(forward).total_cmp(&b.dot(forward)))
+            .map(|(id, _)| id);
+        let Some(route) = next.and_then(|next| plan_route(&self.roads, next, car.direction, target))
+        else {
+            return false;
+        };
+
+        if let Some(car) = self.cars.iter_mut().find(|car| car.id == car_id) {
+            car.route = route;
+        }
+        true
+    }
+
+    /// Sends a new car from a random open road edge to a block
+    ///
+    /// The car is routed to the intersection nearest the block's center. If
+    /// the block is a guarded compound, the car then visits it; if it has a
+    /// parking lot, the car parks there.
+    ///
+    /// # Arguments
+    /// * `block_id` - ID of the block to drive to
+    ///
+    /// # Returns
+    /// The new car's ID, or None if the block is unknown, every road is
+    /// closed or the block can't be reached
+    pub fn spawn_car_to(&mut self, block_id: usize) -> Option<usize> {
+        use crate::models::VehicleStyle;
+        use crate::spawner::spawn_car;
+        use macroquad::rand::gen_range;
+
+        let (center_x, center_y) = self.blocks.get(&block_id)?.center();
+        let target = self
+            .intersections
+            .values()
+            .min_by(|a, b| {
+                let distance = |i: &Intersection| (i.x() - center_x).hypot(i.y() - center_y);
+                distance(a).total_cmp(&distance(b))
+            })?
+            .id;
+
+        let open_roads: Vec<usize> = self
+            .roads
+            .values()
+            .filter(|road| !road.closed)
+            .map(|road| road.index)
+            .collect();
+        if open_roads.is_empty() {
+            return None;
+        }
+        let road_index = open_roads[gen_range(0, open_roads.len())];
+        spawn_car(&mut self.cars, road_index, VehicleStyle::Sedan, self.traffic_rule);
+
+        let car_id = self.cars.last()?.id;
+        if !self.route_car(car_id, target) {
+            self.cars.pop();
+            return None;
+        }
+        let visits = self.compound_block_ids().contains(&block_id)
+            || self.parking_lot_block_ids().contains(&block_id);
+        if let Some(car) = self.cars.last_mut() {
+            car.destination = visits.then_some(block_id);
+        }
+        Some(car_id)
+    }
+
+    /// Spawns pedestrians at regular intervals, then walks them all
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    /// * `all_lights_red` - Emergency mode flag (pedestrians cross everywhere)
+    ///
+    /// # Returns
+    /// Number of pedestrians that left the screen
+    pub fn update_pedestrians(&mut self, dt: f32, all_lights_red: bool) -> usize {
+        use crate::pedestrian::update_pedestrians;
+
+        self.pedestrian_spawner.try_spawn(dt, &mut self.pedestrians);
+        let intersections: Vec<_> = self.intersections.values().cloned().collect();
+        update_pedestrians(&mut self.pedestrians, &intersections, dt, all_lights_red)
     }
 
     /// Updates all traffic lights for one frame
     ///
     /// Cycles through all intersections and updates their traffic light states
-    /// based on the configured durations (green, yellow, red).
+    /// based on the configured durations (green, yellow, red), then turns the
+    /// lights green for emergency vehicles approaching them.
     ///
     /// # Arguments
     /// * `dt` - Delta time (frame duration in seconds)
     pub fn update_traffic_lights(&mut self, dt: f32) {
+        use crate::car::preempts_light;
+
         for intersection in self.intersections.values_mut() {
             intersection.update_lights(dt);
+            if let Some(car) = self.cars.iter().find(|car| preempts_light(car, intersection)) {
+                intersection.preempt_for_emergency(car.direction);
+            }
         }
     }
 
     /// Updates all cars' positions and behaviors for one frame
     ///
     /// This is the main simulation loop that handles:
-    /// - Traffic light compliance
+    /// - Traffic light, barrier and gate compliance
+    /// - Stopping for pedestrians on crosswalks
     /// - Collision avoidance
     /// - Intersection navigation and turning
-    /// - Car removal when off-screen
+    /// - Wrapping or removing cars that drove off-screen
     ///
     /// # Arguments
     /// * `dt` - Delta time (frame duration in seconds)
     /// * `all_lights_red` - Emergency mode flag (stops all traffic)
-    pub fn update_cars(&mut self, dt: f32, all_lights_red: bool) {
-        use crate::car::update_cars;
+    ///
+    /// # Returns
+    /// Counters for stopped, in-intersection, and despawned cars
+    pub fn update_cars(&mut self, dt: f32, all_lights_red: bool) -> CarUpdateStats {
+        use crate::car::{update_cars, wrap_at_edge};
+
+        // Cars that wrap are back on screen before off-screen cars are removed
+        if self.edge_behavior == EdgeBehavior::Wrap {
+            for car in &mut self.cars {
+                wrap_at_edge(car, self.roads.values());
+            }
+        }
 
         // Convert HashMap to Vec for the car update function
         let intersections: Vec<_> = self.intersections.values().cloned().collect();
+        let stops = self.stops();
 
         // Update all cars using the car module's update function
-        update_cars(&mut self.cars, &intersections, dt, all_lights_red);
+        let stats = update_cars(
+            &mut self.cars,
+            &intersections,
+            &stops,
+            &self.pedestrians,
+            dt,
+            all_lights_red,
+            self.speed_factor,
+        );
+        self.measure_queues();
+        self.update_stop_queues(dt);
+        stats
+    }
+
+    /// Counts the cars queued before each intersection by direction of travel
+    ///
+    /// Intersections with adaptive timing retime their next green phases
+    /// for the new counts.
+    fn measure_queues(&mut self) {
+        use crate::car::is_queued_at;
+
+        for intersection in self.intersections.values_mut() {
+            let mut queue_lengths = HashMap::new();
+            for car in self.cars.iter().filter(|car| is_queued_at(car, intersection)) {
+                *queue_lengths.entry(car.direction).or_insert(0) += 1;
+            }
+            intersection.set_queue_lengths(queue_lengths);
+        }
+    }
+
+    /// Moves the queues at all-way stops along for one frame
+    ///
+    /// Cars join the queue once they stand within `STOP_LINE_REACH` of the
+    /// stop line. Emergency vehicles never wait at stops.
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    fn update_stop_queues(&mut self, dt: f32) {
+        use crate::car::stop_line_gap;
+        use crate::constants::vehicle::{QUEUED_SPEED, STOP_LINE_REACH};
+        use crate::models::VehicleType;
+
+        for intersection in self.intersections.values_mut() {
+            if !intersection.is_all_way_stop() {
+                continue;
+            }
+            let mut standing = Vec::new();
+            let mut approaching = Vec::new();
+            for car in &self.cars {
+                if car.vehicle_type == VehicleType::EmergencyVehicle {
+                    continue;
+                }
+                let Some(gap) = stop_line_gap(car, intersection) else {
+                    continue;
+                };
+                approaching.push(car.id);
+                if car.velocity < QUEUED_SPEED && gap < STOP_LINE_REACH {
+                    standing.push(car.id);
+                }
+            }
+            intersection.update_stop_queue(&standing, &approaching, dt);
+        }
+    }
+
+    /// Lets visiting cars into guarded compounds and back out for one frame
+    ///
+    /// Cars admitted through a gate leave the road for the compound's parking
+    /// spots, and cars done visiting join the road again in front of the gate.
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update_compounds(&mut self, dt: f32) {
+        use macroquad::math::Rect;
+
+        for gate in self.gates() {
+            let Some(block) = self.blocks.get_mut(&gate.block_id) else {
+                continue;
+            };
+            let area = Rect::new(block.x(), block.y(), block.width(), block.height());
+            for obj in &mut block.objects {
+                if let Some(compound) = obj.as_any_mut().downcast_mut::<GuardedCompound>() {
+                    compound.serve(&gate, area, &mut self.cars, dt);
+                }
+            }
+        }
+    }
+
+    /// Advances animated block objects (such as barriers) for one frame
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update_blocks(&mut self, dt: f32) {
+        for block in self.blocks.values_mut() {
+            block.update(dt);
+        }
     }
 
     /// Updates the entire city simulation for one frame
@@ -508,7 +1990,12 @@ impl City {
     /// This is the main update method that orchestrates all simulation updates:
     /// 1. Spawns new cars at regular intervals
     /// 2. Updates all traffic light states
-    /// 3. Updates all car positions and behaviors
+    /// 3. Animates block objects (barriers, gates)
+    /// 4. Spawns and walks pedestrians
+    /// 5. Updates all car positions and behaviors
+    /// 6. Lets visitors in and out of guarded compounds, and parks cars that
+    ///    reached a parking lot
+    /// 7. Advances particle effects
     ///
     /// This method provides a unified interface for updating the entire city
     /// simulation in a single call.
@@ -517,15 +2004,33 @@ impl City {
     /// * `dt` - Delta time (frame duration in seconds)
     /// * `all_lights_red` - Emergency mode flag (stops all traffic)
     ///
+    /// # Returns
+    /// Statistics for this frame (also available afterwards via `stats()`)
+    ///
     /// # Example
     /// ```
     /// city.update(dt, false); // Normal operation
     /// city.update(dt, true);  // Emergency mode - all lights red
     /// ```
-    pub fn update(&mut self, dt: f32, all_lights_red: bool) {
-        self.spawn_cars();
+    pub fn update(&mut self, dt: f32, all_lights_red: bool) -> SimulationStats {
+        let cars_spawned = self.spawn_cars(dt);
         self.update_traffic_lights(dt);
-        self.update_cars(dt, all_lights_red);
+        self.update_blocks(dt);
+        self.update_pedestrians(dt, all_lights_red);
+        let car_stats = self.update_cars(dt, all_lights_red);
+        self.update_compounds(dt);
+        self.park_arrived_cars();
+        self.update_particles(dt);
+
+        self.stats = SimulationStats {
+            total_cars: self.cars.len(),
+            cars_stopped: car_stats.stopped,
+            cars_in_intersections: car_stats.in_intersection,
+            cars_spawned,
+            cars_despawned: car_stats.despawned,
+            total_pedestrians: self.pedestrians.len(),
+        };
+        self.stats
     }
 }
 
@@ -629,14 +2134,957 @@ impl CityBuilder {
     /// # Returns
     /// A new City instance with all added roads, blocks, intersections, and cars
     pub fn build(self) -> City {
-        use crate::constants::vehicle::CAR_SPAWN_INTERVAL;
-
         City {
             roads: self.roads,
             blocks: self.blocks,
             intersections: self.intersections,
             cars: self.cars,
-            car_spawner: CarSpawner::new(CAR_SPAWN_INTERVAL),
+            car_spawner: CarSpawner::default(),
+            edge_behavior: EdgeBehavior::default(),
+            speed_factor: 1.0,
+            adaptive_lights: false,
+            traffic_rule: TrafficRule::default(),
+            pedestrians: Vec::new(),
+            pedestrian_spawner: PedestrianSpawner::default(),
+            particle_bursts: Vec::new(),
+            fires: HashMap::new(),
+            stats: SimulationStats::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::effects::FIRE_FADE_OUT_TIME;
+    use crate::constants::vehicle::{CAR_SPEED, LANE_OFFSET};
+    use crate::models::{VehicleStyle, VehicleType, CarLocation, Direction};
+    use crate::traffic_light::{IntersectionTrafficLight, LightState};
+    use macroquad::prelude::RED;
+
+    /// Intersection at the center of the 800x600 test screen, red for vertical traffic
+    fn red_intersection() -> Intersection {
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        let mut light = IntersectionTrafficLight::new(0.5, 0.5, 0, true);
+        light.vertical_state = LightState::default_red();
+        intersection.set_light(light);
+        intersection
+    }
+
+    fn car_at(x: f32, y: f32, direction: Direction, in_intersection: bool) -> Car {
+        Car {
+            id: 0,
+            x_percent: x / 800.0,
+            y_percent: y / 600.0,
+            direction,
+            velocity: CAR_SPEED,
+            target_velocity: CAR_SPEED,
+            vehicle_type: VehicleType::Car,
+            style: VehicleStyle::Sedan,
+            color: RED,
+            road_index: 1,
+            next_turn: None,
+            just_turned: false,
+            in_intersection,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
+            location: CarLocation::OnRoad { road_id: 1 },
+            destination: None,
+            route: Vec::new(),
+            traffic_rule: TrafficRule::LeftHand,
+        }
+    }
+
+    #[test]
+    fn test_update_cars_reports_counters() {
+        // Waiting at the red light, crossing the intersection, and driving off screen
+        let waiting = car_at(400.0 - LANE_OFFSET, 240.0, Direction::Down, false);
+        let crossing = car_at(380.0, 300.0 + LANE_OFFSET, Direction::Right, true);
+        let leaving = car_at(880.0, 300.0 + LANE_OFFSET, Direction::Right, false);
+
+        let mut city = City::builder()
+            .add_intersection(red_intersection())
+            .add_cars(vec![waiting, crossing, leaving])
+            .build();
+
+        let stats = city.update_cars(0.016, false);
+
+        assert_eq!(
+            stats,
+            CarUpdateStats {
+                stopped: 1,
+                in_intersection: 1,
+                despawned: 1,
+            }
+        );
+        assert_eq!(city.car_count(), 2);
+    }
+
+    #[test]
+    fn test_long_vertical_queue_extends_vertical_green() {
+        use crate::constants::traffic_light::{ADAPTIVE_GREEN_MAX_FACTOR, GREEN_DURATION};
+
+        // Horizontal traffic has the green, four cars wait to go down
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        intersection.set_light(IntersectionTrafficLight::new(0.5, 0.5, 0, false));
+        let queue = [230.0, 180.0, 130.0, 80.0].map(|y| {
+            let mut car = car_at(400.0 - LANE_OFFSET, y, Direction::Down, false);
+            car.velocity = 0.0;
+            car
+        });
+        let mut city = City::builder()
+            .add_intersection(intersection)
+            .add_cars(queue.to_vec())
+            .build();
+        city.set_adaptive_lights(true);
+
+        city.update_cars(0.1, false);
+        let intersection = city.get_intersection(0).unwrap();
+        assert_eq!(intersection.queue_lengths(), &[(Direction::Down, 4)].into());
+        let light = intersection.light.as_ref().unwrap();
+        let extended = GREEN_DURATION * ADAPTIVE_GREEN_MAX_FACTOR;
+        assert_eq!(light.green_duration_for_direction(Direction::Down), extended);
+        assert!(light.green_duration_for_direction(Direction::Left) < GREEN_DURATION);
+
+        // Once horizontal green and yellow are over, vertical gets the longer green
+        while !city.get_intersection(0).unwrap().light.as_ref().unwrap().vertical_state.is_green() {
+            city.update_traffic_lights(0.1);
+            city.update_cars(0.1, false);
+        }
+        let light = city.get_intersection(0).unwrap().light.as_ref().unwrap();
+        assert_eq!(light.vertical_state, LightState::Green(extended));
+
+        // Without adaptive timing the configured green is back
+        city.set_adaptive_lights(false);
+        let light = city.get_intersection(0).unwrap().light.as_ref().unwrap();
+        assert_eq!(light.green_duration_for_direction(Direction::Down), GREEN_DURATION);
+    }
+
+    #[test]
+    fn test_car_wraps_from_bottom_edge_to_top() {
+        use crate::road::Orientation;
+
+        let lane_x = 400.0 - LANE_OFFSET;
+        let mut leaving = car_at(lane_x, 670.0, Direction::Down, false);
+        leaving.next_turn = Some(Direction::Left);
+        let mut city = City::builder()
+            .add_road(Road::new(0.5, Orientation::Vertical, 1))
+            .add_car(leaving)
+            .build();
+
+        let stats = city.update_cars(0.016, false);
+
+        assert_eq!(stats.despawned, 0);
+        let car = &city.cars[0];
+        assert!(car.y() < 0.0, "y = {}", car.y());
+        assert!((car.x() - lane_x).abs() < 0.01, "x = {}", car.x());
+        assert_eq!(car.next_turn, Some(Direction::Left));
+
+        // The old behavior despawns it instead
+        city.cars[0].y_percent = 670.0 / 600.0;
+        city.set_edge_behavior(EdgeBehavior::Despawn);
+        assert_eq!(city.update_cars(0.016, false).despawned, 1);
+    }
+
+    #[test]
+    fn test_emergency_vehicle_turns_light_ahead_green() {
+        let mut ambulance = car_at(400.0 - LANE_OFFSET, 150.0, Direction::Down, false);
+        ambulance.vehicle_type = VehicleType::EmergencyVehicle;
+        let mut city = City::builder()
+            .add_intersection(red_intersection())
+            .add_car(ambulance)
+            .build();
+
+        city.update_traffic_lights(0.016);
+
+        let intersection = city.get_intersection(0).unwrap();
+        assert!(intersection.get_light_state_for_direction(Direction::Down).unwrap().is_green());
+        assert!(intersection.get_light_state_for_direction(Direction::Right).unwrap().is_red());
+
+        // Once past the intersection it no longer holds the light
+        city.cars[0].y_percent = 400.0 / 600.0;
+        city.update_traffic_lights(10.0);
+        let intersection = city.get_intersection(0).unwrap();
+        assert!(!intersection.get_light_state_for_direction(Direction::Down).unwrap().is_green());
+    }
+
+    #[test]
+    fn test_update_cars_counts_emergency_stops() {
+        let mut city = City::builder()
+            .add_intersection(red_intersection())
+            .add_cars(vec![
+                car_at(400.0 - LANE_OFFSET, 240.0, Direction::Down, false),
+                car_at(340.0, 300.0 + LANE_OFFSET, Direction::Right, false),
+            ])
+            .build();
+
+        let stats = city.update_cars(0.016, true);
+
+        assert_eq!(stats.stopped, 2);
+        assert_eq!(stats.despawned, 0);
+    }
+
+    #[test]
+    fn test_closed_barrier_holds_traffic_until_raised() {
+        use crate::block::{Barrier, Block};
+        use crate::constants::visual::ROAD_WIDTH;
+
+        // Block ending at x = 400 with a barrier across the road beside it at y = 300
+        let mut block = Block::new(0.0, 0.0, 0.5, 1.0, 1);
+        block.add_object(Box::new(Barrier::new(0.5)));
+        let car = car_at(400.0 + ROAD_WIDTH / 2.0 - LANE_OFFSET, 240.0, Direction::Down, false);
+
+        let mut city = City::builder().add_block(block).add_cars(vec![car]).build();
+
+        assert_eq!(city.update_cars(0.016, false).stopped, 1);
+
+        city.set_barrier_state(true);
+        city.update_blocks(0.5);
+        assert_eq!(city.update_cars(0.016, false).stopped, 1, "arm still rising");
+
+        city.update_blocks(0.5);
+        assert!(city.barrier_stop_points().is_empty());
+        assert_eq!(city.update_cars(0.016, false).stopped, 0);
+    }
+
+    #[test]
+    fn test_car_waits_at_all_way_stop_before_crossing() {
+        use crate::intersection::IntersectionType;
+
+        // Standing at the stop line, 70 pixels before the center
+        let mut car = car_at(400.0 - LANE_OFFSET, 230.0, Direction::Down, false);
+        car.velocity = 0.0;
+        let stop = Intersection::builder()
+            .intersection_type(IntersectionType::AllWayStop)
+            .build();
+        let mut city = City::builder().add_intersection(stop).add_cars(vec![car]).build();
+
+        for _ in 0..15 {
+            city.update_cars(0.1, false);
+        }
+        assert!(city.cars[0].y() < 231.0, "still waiting after 1.5 seconds");
+
+        for _ in 0..15 {
+            city.update_cars(0.1, false);
+        }
+        assert!(city.cars[0].y() > 240.0, "crossing after 3 seconds");
+    }
+
+    #[test]
+    fn test_right_hand_turn_ends_in_the_right_hand_exit_lane() {
+        // Going down in the right-hand lane, turning right onto the road going left
+        let mut car = car_at(400.0 + LANE_OFFSET, 200.0, Direction::Down, false);
+        car.traffic_rule = TrafficRule::RightHand;
+        car.next_turn = Some(Direction::Left);
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        intersection.set_light(IntersectionTrafficLight::new(0.5, 0.5, 0, true));
+        let mut city = City::builder().add_intersection(intersection).add_car(car).build();
+
+        while city.cars[0].direction == Direction::Down || city.cars[0].turn.is_some() {
+            city.update_cars(0.016, false);
+        }
+
+        let car = &city.cars[0];
+        assert_eq!(car.direction, Direction::Left);
+        assert!((car.y() - (300.0 + LANE_OFFSET)).abs() < 0.01, "y = {}", car.y());
+        assert!(car.x() < 400.0);
+    }
+
+    #[test]
+    fn test_led_setters_update_display_in_block() {
+        let mut block = Block::new(0.0, 0.0, 0.2, 0.2, 0);
+        block.add_object(Box::new(LEDDisplay::new("WELCOME")));
+        let mut city = City::builder()
+            .add_block(block)
+            .add_block(Block::new(0.3, 0.0, 0.2, 0.2, 1))
+            .build();
+
+        assert!(city.set_led_text(0, "HACKED"));
+        assert!(city.set_led_mode(0, LEDDisplayMode::Broken));
+        assert!(!city.set_led_text(1, "NO DISPLAY HERE"));
+        assert!(!city.set_led_mode(7, LEDDisplayMode::Static));
+
+        let led = city.get_block(0).unwrap().objects[0]
+            .as_any()
+            .downcast_ref::<LEDDisplay>()
+            .unwrap();
+        assert_eq!(led.text, "HACKED");
+        assert!(matches!(led.mode, LEDDisplayMode::Broken));
+    }
+
+    #[test]
+    fn test_led_queue_reaches_displays_and_drains() {
+        let mut block = Block::new(0.0, 0.0, 0.2, 0.2, 0);
+        block.add_object(Box::new(LEDDisplay::new("WELCOME")));
+        let mut city = City::builder().add_block(block).build();
+
+        assert!(city.led_enqueue("DANGER", LEDPriority::Critical, None));
+        assert!(city.led_enqueue("SCADA ALERT", LEDPriority::Warning, Some(1.0)));
+        assert!(city.led_has_message("DANGER"));
+
+        city.update_blocks(1.5);
+        assert!(!city.led_has_message("SCADA ALERT"));
+
+        city.led_remove("DANGER");
+        assert!(!city.led_has_message("DANGER"));
+
+        city.led_enqueue("ROADWORKS", LEDPriority::Info, None);
+        assert!(city.led_enqueue_message(LedMessage::new("HACKED", LEDDisplayMode::Broken)));
+        assert_eq!(city.led_messages().len(), 1);
+        city.led_clear_queue();
+        let led = city.get_block(0).unwrap().objects[0]
+            .as_any()
+            .downcast_ref::<LEDDisplay>()
+            .unwrap();
+        assert_eq!(led.current_alert(), None);
+        assert!(led.messages().is_empty());
+
+        assert!(!City::builder().build().led_enqueue("DANGER", LEDPriority::Critical, None));
+    }
+
+    #[test]
+    fn test_visitors_drive_into_compound_and_back_out() {
+        use crate::block::GuardedCompound;
+        use crate::road::generate_road_network;
+        use macroquad::color::BROWN;
+        use std::collections::HashSet;
+
+        // Block 8, between the middle and right vertical roads and the two
+        // horizontal roads
+        let (roads, intersections) = generate_road_network();
+        let mut block = Block::new(430.0 / 800.0, 180.0 / 600.0, 220.0 / 800.0, 240.0 / 600.0, 8);
+        block.add_object(Box::new(GuardedCompound::new(BROWN)));
+        let mut city = City::builder()
+            .add_roads(roads)
+            .add_intersections(intersections)
+            .add_block(block)
+            .build();
+        city.link_blocks_to_roads();
+        city.set_spawn_config(SpawnConfig {
+            max_cars: 10,
+            visitor_chance: 1.0,
+            ..SpawnConfig::default()
+        });
+        assert_eq!(city.compound_block_ids(), [8]);
+
+        // Run until a car let in through the gate is back on the road
+        let mut admitted = HashSet::new();
+        let left = (0..90 * 60).any(|_| {
+            city.update(1.0 / 60.0, false);
+            admitted.extend(city.gates()[0].admitting);
+            city.cars
+                .iter()
+                .any(|car| admitted.contains(&car.id) && car.destination.is_none())
+        });
+        assert!(!admitted.is_empty(), "no visitor reached the gate");
+        assert!(left, "no visitor left the compound");
+    }
+
+    #[test]
+    fn test_routed_car_turns_only_where_its_route_does() {
+        use crate::road::generate_road_network;
+
+        let (roads, intersections) = generate_road_network();
+        let mut city = City::builder()
+            .add_roads(roads)
+            .add_intersections(intersections)
+            .build();
+        city.set_spawn_config(SpawnConfig {
+            max_cars: 0,
+            ..SpawnConfig::default()
+        });
+
+        // Entering at the top of the left road, bound for the bottom right
+        let mut car = car_at(120.0 - LANE_OFFSET, -20.0, Direction::Down, false);
+        car.road_index = 0;
+        car.location = CarLocation::OnRoad { road_id: 0 };
+        city.cars.push(car);
+        assert!(city.route_car(0, 5));
+        assert_eq!(city.cars[0].route, [0, 1, 3, 5]);
+
+        let mut directions = vec![Direction::Down];
+        let arrived = (0..60 * 60).any(|_| {
+            city.update(1.0 / 60.0, false);
+            let Some(car) = city.cars.first() else {
+                return false;
+            };
+            if directions.last() != Some(&car.direction) {
+                directions.push(car.direction);
+            }
+            car.x() > 680.0 + ROAD_WIDTH
+        });
+        assert!(arrived, "the car never got past intersection 5");
+        // One turn, at 1, then straight on through 3 and 5
+        assert_eq!(directions, [Direction::Down, Direction::Right]);
+        assert!(city.cars[0].route.is_empty());
+        assert!(!city.route_car(7, 5));
+    }
+
+    #[test]
+    fn test_cars_sent_to_a_parking_lot_park_in_it() {
+        use crate::block::ParkingLot;
+        use crate::road::generate_road_network;
+
+        let lot_occupancy = |city: &City, block_id: usize| {
+            city.get_block(block_id).unwrap().objects[0]
+                .as_any()
+                .downcast_ref::<ParkingLot>()
+                .unwrap()
+                .occupied
+        };
+
+        // A one-stall lot in the top-left corner block, a bigger one in the
+        // bottom-right corner block
+        let half_road = ROAD_WIDTH / 2.0;
+        let mut top_left = Block::new(0.0, 0.0, (120.0 - half_road) / 800.0, 0.2, 1);
+        top_left.add_object(Box::new(ParkingLot::new(0.0, 0.0, 1.0, 1.0, 1)));
+        let mut bottom_right =
+            Block::new((680.0 + half_road) / 800.0, (450.0 + half_road) / 600.0, 0.1, 0.1, 12);
+        bottom_right.add_object(Box::new(ParkingLot::new(0.0, 0.0, 1.0, 1.0, 4)));
+
+        let (roads, intersections) = generate_road_network();
+        let mut city = City::builder()
+            .add_roads(roads)
+            .add_intersections(intersections)
+            .add_blocks(vec![top_left, bottom_right])
+            .build();
+        city.set_spawn_config(SpawnConfig {
+            max_cars: 0,
+            ..SpawnConfig::default()
+        });
+
+        // Cars park in the nearest lot, even when it is full
+        assert!(city.park_car_near(10.0, 10.0));
+        assert!(!city.park_car_near(10.0, 10.0));
+        assert_eq!(lot_occupancy(&city, 1), 1);
+
+        let car_id = city.spawn_car_to(12).unwrap();
+        assert_eq!(city.cars[0].destination, Some(12));
+        let parked = (0..90 * 60).any(|_| {
+            city.update(1.0 / 60.0, false);
+            city.cars.is_empty()
+        });
+        assert!(parked, "car {} never reached the lot", car_id);
+        assert_eq!(lot_occupancy(&city, 12), 1);
+    }
+
+    #[test]
+    fn test_resize_keeps_cars_in_their_lanes() {
+        use crate::block::{Barrier, Building, ParkingLot};
+        use crate::constants::effects::FIRE_BASE_OFFSET;
+        use crate::intersection::district_at;
+        use crate::models::{TurnPath, TurnProgress};
+        use crate::road::generate_road_network;
+        use crate::snapshot::Controls;
+        use macroquad::prelude::vec2;
+
+        let (roads, _) = generate_road_network();
+        let vertical = roads[0].clone();
+        let horizontal = roads
+            .iter()
+            .find(|road| road.orientation == Orientation::Horizontal)
+            .unwrap()
+            .clone();
+
+        let mut down = car_at(0.0, 100.0, Direction::Down, false);
+        down.x_percent = vertical.position_percent - LANE_OFFSET / 800.0;
+        let mut left = car_at(500.0, 0.0, Direction::Left, false);
+        left.y_percent = horizontal.position_percent - LANE_OFFSET / 600.0;
+        let mut turning = left.clone();
+        turning.turn = Some(TurnProgress {
+            path: TurnPath::new(
+                vec2(500.0, 288.0),
+                vec2(488.0, 288.0),
+                vec2(488.0, 276.0),
+                Direction::Up,
+            )
+            .into(),
+            t: 0.5,
+        });
+        // Blocks as generated for a window size, shifted by `x` across it
+        let blocks = |x: f32| {
+            let mut scada_block = Block::new(x, 0.0, 0.1, 0.1, 1);
+            scada_block.add_object(Box::new(Building::builder().has_scada(true).build()));
+            let mut lot_block = Block::new(x, 0.3, 0.1, 0.1, 2);
+            lot_block.add_object(Box::new(ParkingLot::new(0.0, 0.0, 1.0, 1.0, 4)));
+            let mut gate_block = Block::new(x, 0.8, 0.1, 0.1, 3);
+            gate_block.add_object(Box::new(Barrier::new(0.5)));
+            let mut led_block = Block::new(x, 0.5, 0.1, 0.1, 4);
+            led_block.add_object(Box::new(LEDDisplay::new("WELCOME")));
+            vec![scada_block, lot_block, gate_block, led_block]
+        };
+        let mut city = City::builder()
+            .add_roads(vec![vertical.clone(), horizontal.clone()])
+            .add_cars(vec![down, left, turning])
+            .add_blocks(blocks(0.0))
+            .build();
+
+        let removed = city.handle_resize((800.0, 600.0), (1600.0, 900.0));
+
+        assert_eq!(removed, 1);
+        assert_eq!(city.car_count(), 2);
+        let down = &city.cars[0];
+        let left = &city.cars[1];
+        // Still LANE_OFFSET pixels from the road center at the new size
+        let down_offset = (down.x_percent - vertical.position_percent) * 1600.0;
+        let left_offset = (left.y_percent - horizontal.position_percent) * 900.0;
+        assert!((down_offset + LANE_OFFSET).abs() < 1e-3, "{}", down_offset);
+        assert!((left_offset + LANE_OFFSET).abs() < 1e-3, "{}", left_offset);
+        assert_eq!((down.y_percent, down.road_index), (100.0 / 600.0, vertical.index));
+        assert_eq!(left.road_index, horizontal.index);
+
+        // The blocks generated again for the new size keep the state of
+        // their objects, and the fire follows its building
+        city.compromise_scada(Some(1), &red_team());
+        city.set_district_power(district_at(0.05), false);
+        city.set_barrier_broken_by(Some(&red_team()));
+        let (lot_x, lot_y) = city.building_world_position(2).unwrap();
+        assert!(city.park_car_near(lot_x, lot_y));
+        city.set_building_fire(1, 0.8);
+        city.update_particles(0.5);
+        city.led_enqueue("DANGER", LEDPriority::Critical, None);
+        city.led_enqueue_message(LedMessage::new("SCADA ALERT", LEDDisplayMode::Static));
+        let before = city.to_snapshot(Controls::default());
+        let flames = city.fires[&1].particles.len();
+
+        city.regenerate_blocks(|city| {
+            for block in blocks(0.5) {
+                city.add_block(block);
+            }
+        });
+
+        let after = city.to_snapshot(Controls::default());
+        assert_eq!(compromised(&city), [1]);
+        assert_eq!(after.scada, before.scada);
+        assert_eq!(after.unpowered_blocks, [1]);
+        assert_eq!(after.parked_cars, before.parked_cars);
+        assert_eq!(after.barrier_broken_by, Some(red_team().color));
+        assert!(city.led_has_message("DANGER"));
+        let messages: Vec<_> = city.led_messages().into_iter().map(|msg| msg.text).collect();
+        assert_eq!(messages, ["SCADA ALERT"]);
+        let fire = &city.fires[&1];
+        let (x, y) = city.building_world_position(1).unwrap();
+        assert_eq!((fire.x, fire.y + FIRE_BASE_OFFSET, fire.intensity), (x, y, 0.8));
+        assert!(flames > 0);
+        assert_eq!(fire.particles.len(), flames);
+    }
+
+    #[test]
+    fn test_link_blocks_to_roads() {
+        use crate::road::generate_road_network;
+
+        let (roads, _) = generate_road_network();
+        let half_road = ROAD_WIDTH / 2.0;
+        // Top-left corner block and the block right of the middle road
+        let corner = Block::new(0.0, 0.0, (120.0 - half_road) / 800.0, (150.0 - half_road) / 600.0, 1);
+        let middle = Block::new(
+            (400.0 + half_road) / 800.0,
+            0.0,
+            (280.0 - ROAD_WIDTH) / 800.0,
+            (150.0 - half_road) / 600.0,
+            7,
+        );
+        let mut city = City::builder().add_roads(roads).add_blocks(vec![corner, middle]).build();
+
+        city.link_blocks_to_roads();
+        city.link_blocks_to_roads(); // Relinking replaces rather than duplicates
+
+        let corner = city.get_block(1).unwrap();
+        assert_eq!(corner.get_adjacent_road(Direction::Right), Some(0));
+        assert_eq!(corner.get_adjacent_road(Direction::Down), Some(3));
+        assert_eq!(corner.adjacent_roads.len(), 2);
+
+        let middle = city.get_block(7).unwrap();
+        assert_eq!(middle.get_adjacent_road(Direction::Left), Some(1));
+        assert_eq!(middle.get_adjacent_road(Direction::Right), Some(2));
+
+        assert_eq!(city.get_road(0).unwrap().adjacent_block_ids, vec![1]);
+        assert_eq!(city.get_road(3).unwrap().adjacent_block_ids, vec![1, 7]);
+        assert_eq!(city.find_road_at_position(400.0, 60.0), Some(1));
+        assert_eq!(city.find_road_at_position(250.0, 60.0), None);
+    }
+
+    #[test]
+    fn test_generated_blocks_are_connected_to_the_roads_they_face() {
+        use crate::block::generate_grass_blocks;
+        use crate::road::generate_road_network;
+
+        let blocks = generate_grass_blocks();
+        let generated: HashMap<usize, _> = blocks
+            .iter()
+            .map(|block| (block.id, block.adjacent_roads.clone()))
+            .collect();
+        let (roads, _) = generate_road_network();
+        let mut city = City::builder().add_roads(roads).add_blocks(blocks).build();
+        city.link_blocks_to_roads();
+
+        for (id, adjacent_roads) in generated {
+            assert_eq!(city.get_block(id).unwrap().adjacent_roads, adjacent_roads, "block {}", id);
+        }
+        // The corner blocks face two roads, the center ones four
+        assert_eq!(city.get_block(1).unwrap().adjacent_roads.len(), 2);
+        assert_eq!(city.get_block(5).unwrap().adjacent_roads.len(), 4);
+    }
+
+    /// City with SCADA buildings in blocks 1-3 and a plain building in block 4
+    fn scada_city() -> City {
+        use crate::block::{Block, Building};
+
+        let mut builder = City::builder();
+        for id in 1..=4 {
+            let mut block = Block::new(0.0, 0.0, 0.1, 0.1, id);
+            block.add_object(Box::new(Building::builder().has_scada(id != 4).build()));
+            builder = builder.add_block(block);
+        }
+        builder.build()
+    }
+
+    fn compromised(city: &City) -> Vec<usize> {
+        let mut ids: Vec<_> = city
+            .blocks
+            .values()
+            .filter(|block| {
+                block.objects.iter().any(|obj| {
+                    obj.as_any()
+                        .downcast_ref::<crate::block::Building>()
+                        .is_some_and(|building| building.is_scada_compromised())
+                })
+            })
+            .map(|block| block.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    fn compromised_by(city: &City, block_id: usize) -> Option<String> {
+        city.get_block(block_id)?.objects.iter().find_map(|obj| {
+            obj.as_any()
+                .downcast_ref::<crate::block::Building>()?
+                .compromised_by
+                .as_ref()
+                .map(|team| team.name.clone())
+        })
+    }
+
+    fn red_team() -> crate::team::Team {
+        crate::team::TeamRegistry::new().get_or_register("Red Team").clone()
+    }
+
+    #[test]
+    fn test_set_scada_single_and_all() {
+        let mut city = scada_city();
+
+        city.set_scada(Some(2), true);
+        assert_eq!(compromised(&city), [2]);
+
+        city.set_scada(None, true);
+        assert_eq!(compromised(&city), [1, 2, 3], "buildings without SCADA are unaffected");
+
+        city.set_scada(Some(1), false);
+        assert_eq!(compromised(&city), [2, 3]);
+
+        city.reset_all_scada();
+        assert!(compromised(&city).is_empty());
+    }
+
+    #[test]
+    fn test_compromise_scada_records_team_until_restored() {
+        let mut city = scada_city();
+
+        city.compromise_scada(Some(3), &red_team());
+        assert_eq!(compromised(&city), [3]);
+        assert_eq!(compromised_by(&city, 3).as_deref(), Some("Red Team"));
+        assert_eq!(compromised_by(&city, 1), None);
+
+        city.set_scada(None, false);
+        assert!(compromised(&city).is_empty());
+        assert_eq!(compromised_by(&city, 3), None);
+    }
+
+    #[test]
+    fn test_set_building_scada_state() {
+        let mut city = scada_city();
+        let state = |city: &City, block_id: usize| {
+            city.get_block(block_id)?.objects.iter().find_map(|obj| {
+                Some(obj.as_any().downcast_ref::<crate::block::Building>()?.scada_state)
+            })
+        };
+
+        city.compromise_scada(Some(1), &red_team());
+        city.set_building_scada_state(2, ScadaState::Offline);
+        assert_eq!(state(&city, 2), Some(ScadaState::Offline));
+        assert_eq!(compromised(&city), [1], "offline is not compromised");
+
+        city.set_building_scada_state(1, ScadaState::Normal);
+        assert_eq!(state(&city, 1), Some(ScadaState::Normal));
+        assert_eq!(compromised_by(&city, 1), None);
+        assert_eq!(state(&city, 2), Some(ScadaState::Offline));
+
+        // Buildings without SCADA keep the default state
+        city.set_building_scada_state(4, ScadaState::Compromised);
+        assert!(compromised(&city).is_empty());
+    }
+
+    #[test]
+    fn test_building_fire_emits_flames_until_extinguished() {
+        let mut city = City::builder()
+            .add_block(Block::new(0.0, 0.0, 0.2, 0.2, 1))
+            .build();
+
+        assert!(city.set_building_fire(1, 0.5));
+        assert!(!city.set_building_fire(9, 1.0));
+        city.update_particles(0.5);
+        let fire = &city.fires[&1];
+        assert!(!fire.particles.is_empty());
+        // Flames start above the block center and rise
+        assert!(fire.particles.iter().all(|p| p.y < 60.0));
+
+        // Re-igniting changes the intensity of the existing fire
+        assert!(city.set_building_fire(1, 2.0));
+        assert_eq!(city.fires[&1].intensity, 1.0);
+        assert!(!city.fires[&1].particles.is_empty());
+
+        // A fire this strong smokes
+        city.update_particles(0.5);
+        assert!(!city.fires[&1].smoke.is_empty());
+
+        // Once put out, the fire fades instead of vanishing
+        assert!(city.extinguish_building_fire(1));
+        assert!(!city.extinguish_building_fire(1));
+        city.update_particles(FIRE_FADE_OUT_TIME / 2.0);
+        assert!((city.fires[&1].opacity() - 0.5).abs() < 1e-6);
+
+        city.update_particles(FIRE_FADE_OUT_TIME / 2.0);
+        assert!(city.fires.is_empty());
+        assert!(!city.extinguish_building_fire(1));
+    }
+
+    #[test]
+    fn test_relighting_a_fading_fire_keeps_it_burning() {
+        let mut city = City::builder()
+            .add_block(Block::new(0.0, 0.0, 0.2, 0.2, 1))
+            .build();
+
+        city.set_building_fire(1, 0.5);
+        city.extinguish_building_fire(1);
+        city.update_particles(FIRE_FADE_OUT_TIME / 2.0);
+
+        assert!(city.set_building_fire(1, 0.7));
+        assert_eq!(city.fires[&1].opacity(), 1.0);
+
+        // Still burning long after it would have faded, too weak to smoke
+        city.update_particles(FIRE_FADE_OUT_TIME);
+        assert!(!city.fires[&1].particles.is_empty());
+        assert!(city.fires[&1].smoke.is_empty());
+    }
+
+    #[test]
+    fn test_closed_road_is_lined_with_cones_until_reopened() {
+        use crate::block::TrafficCone;
+        use crate::road::generate_road_network;
+
+        let (roads, _) = generate_road_network();
+        let half_road = ROAD_WIDTH / 2.0;
+        // Top-left corner block, between road 0 on the right and road 3 below
+        let corner = Block::new(0.0, 0.0, (120.0 - half_road) / 800.0, (150.0 - half_road) / 600.0, 1);
+        let mut city = City::builder().add_roads(roads).add_block(corner).build();
+        city.link_blocks_to_roads();
+        let cones = |city: &City| -> Vec<(usize, f32)> {
+            city.blocks[&1]
+                .objects
+                .iter()
+                .filter_map(|obj| obj.as_any().downcast_ref::<TrafficCone>())
+                .map(|cone| (cone.road_id, cone.y_offset_percent))
+                .collect()
+        };
+
+        assert!(city.set_road_closed(3, true));
+        let lining = cones(&city);
+        assert!(city.set_road_closed(3, true));
+        assert_eq!(cones(&city), lining, "closing twice adds no more cones");
+        assert!(!city.set_road_closed(9, true));
+        assert!(lining.len() > 1);
+        assert!(lining.iter().all(|&(road, y)| road == 3 && y > 0.9), "{:?}", lining);
+        assert_eq!(city.stops().closed_roads, [3]);
+
+        city.set_road_closed(0, true);
+        city.set_road_closed(3, false);
+        assert!(cones(&city).iter().all(|&(road, _)| road == 0));
+        city.reopen_all_roads();
+        assert!(cones(&city).is_empty());
+        assert!(city.roads.values().all(|road| !road.closed));
+    }
+
+    #[test]
+    fn test_power_outage_darkens_only_its_district() {
+        use crate::block::Building;
+        use crate::intersection::generate_intersections;
+
+        let mut builder = City::builder().add_intersections(generate_intersections());
+        // One block west of the central road, one east of it
+        for (id, x) in [(1, 0.2), (2, 0.6)] {
+            let mut block = Block::new(x, 0.0, 0.1, 0.1, id);
+            block.add_object(Box::new(Building::builder().build()));
+            builder = builder.add_block(block);
+        }
+        let mut city = builder.build();
+
+        let powered_buildings = |city: &City| {
+            let mut ids: Vec<_> = city
+                .blocks
+                .values()
+                .filter(|block| {
+                    block.objects[0]
+                        .as_any()
+                        .downcast_ref::<Building>()
+                        .is_some_and(|building| building.powered)
+                })
+                .map(|block| block.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        let dark_intersections = |city: &City| {
+            let mut ids: Vec<_> = city
+                .intersections
+                .values()
+                .filter(|intersection| !intersection.powered)
+                .map(|intersection| intersection.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        assert!(city.set_district_power("central", false));
+        assert_eq!(dark_intersections(&city), [2, 3]);
+        assert_eq!(powered_buildings(&city), [1]);
+
+        assert!(!city.set_district_power("uptown", false));
+        assert!(city.set_district_power("west", false));
+        assert_eq!(powered_buildings(&city), Vec::<usize>::new());
+
+        city.restore_all_power();
+        assert!(dark_intersections(&city).is_empty());
+        assert_eq!(powered_buildings(&city), [1, 2]);
+    }
+
+    #[test]
+    fn test_minimap_state_shows_cars_and_compromised_buildings() {
+        use crate::road::Orientation;
+
+        let mut city = scada_city();
+        city.add_road(Road::new(0.5, Orientation::Vertical, 1));
+        city.add_car(car_at(400.0, 150.0, Direction::Down, false));
+        city.compromise_scada(Some(2), &red_team());
+
+        let mut lit = Intersection::new(0.5, 0.5, 0);
+        lit.set_light(IntersectionTrafficLight::new(0.5, 0.5, 0, true));
+        city.add_intersection(lit);
+        city.add_intersection(Intersection::new(0.25, 0.5, 1));
+
+        let state = city.minimap_state(false);
+        assert_eq!(state.roads, [(Orientation::Vertical, 0.5)]);
+        assert_eq!(state.blocks.len(), 4);
+        assert_eq!(state.cars, [(macroquad::math::vec2(0.5, 0.25), RED)]);
+
+        // Block 2 spans the top-left tenth of the screen; its one building
+        // sits somewhere inside it
+        let [building] = state.compromised_buildings[..] else {
+            panic!("expected one compromised building: {:?}", state.compromised_buildings);
+        };
+        assert!(building.x > 0.0 && building.x < 0.1, "{:?}", building);
+        assert!(building.y > 0.0 && building.y < 0.1, "{:?}", building);
+
+        // Only the intersection with a light is marked, green for vertical traffic
+        let [intersection] = state.intersections[..] else {
+            panic!("expected one intersection: {:?}", state.intersections);
+        };
+        assert_eq!(intersection.position, macroquad::math::vec2(0.5, 0.5));
+        assert!(intersection.vertical.is_some_and(|state| state.is_green()));
+        assert!(intersection.horizontal.is_some_and(|state| state.is_red()));
+
+        let stopped = city.minimap_state(true).intersections[0];
+        assert!(stopped.vertical.is_some_and(|state| state.is_red()));
+    }
+
+    #[test]
+    fn test_snapshot_restores_into_a_fresh_city() {
+        use crate::block::{Barrier, Building, ParkingLot};
+        use crate::led_display_object::{LEDDisplay, LEDPriority};
+        use crate::road::generate_road_network;
+        use crate::snapshot::{CitySnapshot, Controls};
+
+        // Blocks are generated again rather than saved, as the dashboard does
+        let new_city = || {
+            let mut led_block = Block::new(0.3, 0.0, 0.1, 0.1, 0);
+            led_block.add_object(Box::new(LEDDisplay::new("WELCOME")));
+            let mut lot_block = Block::new(0.0, 0.0, 0.1, 0.1, 1);
+            lot_block.add_object(Box::new(ParkingLot::new(0.0, 0.0, 1.0, 1.0, 4)));
+            let mut scada_block = Block::new(0.0, 0.3, 0.1, 0.1, 2);
+            scada_block.add_object(Box::new(Building::builder().has_scada(true).build()));
+            let mut gate_block = Block::new(0.0, 0.8, 0.1, 0.1, 3);
+            gate_block.add_object(Box::new(Barrier::new(0.5)));
+            let (roads, intersections) = generate_road_network();
+            let mut city = City::builder()
+                .add_roads(roads)
+                .add_intersections(intersections)
+                .add_blocks(vec![led_block, lot_block, scada_block, gate_block])
+                .build();
+            city.set_spawn_config(SpawnConfig {
+                max_cars: 0,
+                ..SpawnConfig::default()
+            });
+            city
+        };
+        let controls = Controls {
+            all_lights_red: true,
+            barrier_open: false,
+            led_broken: Some("PWNED".to_string()),
+        };
+        let as_json =
+            |city: &City| serde_json::to_value(city.to_snapshot(controls.clone())).unwrap();
+
+        let mut city = new_city();
+        for _ in 0..3 {
+            city.spawn_car_to(1).unwrap();
         }
+        city.park_car_near(10.0, 10.0);
+        city.set_adaptive_lights(true);
+        city.compromise_scada(Some(2), &red_team());
+        city.set_building_fire(2, 0.5);
+        city.set_road_closed(1, true);
+        city.set_barrier_broken_by(Some(&red_team()));
+        city.led_enqueue("DANGER", LEDPriority::Critical, None);
+        for _ in 0..120 {
+            city.update(1.0 / 60.0, false);
+        }
+        let saved = serde_json::to_string(&city.to_snapshot(controls.clone())).unwrap();
+
+        // Re-serializing the restored city gives the same snapshot
+        let mut restored = new_city();
+        let snapshot = serde_json::from_str::<CitySnapshot>(&saved).unwrap();
+        assert_eq!(restored.restore_snapshot(snapshot), controls);
+        assert_eq!(as_json(&restored), as_json(&city));
+        assert_eq!(compromised(&restored), [2]);
+        assert!(restored.led_has_message("DANGER"));
+
+        // Cars spawned after the restore don't reuse a restored car's ID
+        let max_id = restored.cars.iter().map(|car| car.id).max().unwrap();
+        assert!(restored.spawn_car_to(1).unwrap() > max_id);
+    }
+
+    #[test]
+    fn test_toggle_scada() {
+        let mut city = scada_city();
+
+        city.toggle_scada(1);
+        assert_eq!(compromised(&city), [1]);
+
+        city.toggle_all_scada();
+        assert_eq!(compromised(&city), [2, 3]);
+
+        city.toggle_scada(4);
+        assert_eq!(compromised(&city), [2, 3]);
     }
 }
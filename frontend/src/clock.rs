@@ -0,0 +1,272 @@
+//! Simulation clock
+//!
+//! Scales and pauses simulation time independently of the real frame time.
+//! Everything that moves or animates (cars, the spawner, traffic lights, LED
+//! scrolling and flashing) runs on this clock, while input and the log window
+//! keep using real time so they stay responsive while the city is paused.
+//! Server events are still handled while paused, but the animations they
+//! start (fires, particle bursts, LED messages) only play once it resumes.
+//!
+//! In step mode (Ctrl+S) the simulation stays paused and advances one
+//! `STEP_DURATION` frame each time N is pressed, for following the car and
+//! light logic frame by frame.
+
+use crate::constants::simulation::{DEFAULT_TIME_SCALE_INDEX, STEP_DURATION, TIME_SCALES};
+use crate::input::ctrl_down;
+use macroquad::prelude::*;
+
+/// How the simulation clock advances
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationMode {
+    /// Every frame, at the current speed
+    Running,
+    /// Not at all
+    Paused,
+    /// One frame at a time, on request
+    StepMode,
+}
+
+/// Pausable, time-scaled clock driving the simulation
+pub struct SimulationClock {
+    /// Accumulated simulation time in seconds
+    time: f64,
+    /// Index into `TIME_SCALES` of the current speed
+    scale_index: usize,
+    mode: SimulationMode,
+    /// Number of frames the simulation has advanced
+    frame: u64,
+    /// Whether a step was requested in step mode and not taken yet
+    step_requested: bool,
+    /// Whether the last tick took a requested step
+    stepped: bool,
+}
+
+impl SimulationClock {
+    /// Creates a running clock at real-time speed
+    pub fn new() -> Self {
+        Self {
+            time: 0.0,
+            scale_index: DEFAULT_TIME_SCALE_INDEX,
+            mode: SimulationMode::Running,
+            frame: 0,
+            step_requested: false,
+            stepped: false,
+        }
+    }
+
+    /// Advances the clock by one frame
+    ///
+    /// # Arguments
+    /// * `real_dt` - Real frame duration in seconds
+    ///
+    /// # Returns
+    /// The simulation time step for this frame (zero while paused, and in
+    /// step mode unless a step was requested)
+    pub fn tick(&mut self, real_dt: f32) -> f32 {
+        self.stepped = self.mode == SimulationMode::StepMode && self.step_requested;
+        self.step_requested = false;
+        let dt = match self.mode {
+            SimulationMode::Running => real_dt * self.time_scale(),
+            SimulationMode::Paused => 0.0,
+            SimulationMode::StepMode if self.stepped => STEP_DURATION,
+            SimulationMode::StepMode => 0.0,
+        };
+        if dt > 0.0 {
+            self.frame += 1;
+        }
+        self.time += dt as f64;
+        dt
+    }
+
+    /// Accumulated simulation time in seconds, for animations
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Current speed multiplier
+    pub fn time_scale(&self) -> f32 {
+        TIME_SCALES[self.scale_index]
+    }
+
+    /// Number of frames the simulation has advanced
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Whether the simulation advances one frame at a time
+    pub fn is_stepping(&self) -> bool {
+        self.mode == SimulationMode::StepMode
+    }
+
+    /// Whether the last tick advanced the simulation by a requested step
+    pub fn stepped(&self) -> bool {
+        self.stepped
+    }
+
+    /// Pauses a running simulation, or resumes a paused or stepped one
+    pub fn toggle_pause(&mut self) {
+        self.mode = match self.mode {
+            SimulationMode::Running => SimulationMode::Paused,
+            SimulationMode::Paused | SimulationMode::StepMode => SimulationMode::Running,
+        };
+    }
+
+    /// Enters step mode, or leaves it to run normally
+    pub fn toggle_step_mode(&mut self) {
+        self.mode = match self.mode {
+            SimulationMode::StepMode => SimulationMode::Running,
+            SimulationMode::Running | SimulationMode::Paused => SimulationMode::StepMode,
+        };
+        self.step_requested = false;
+    }
+
+    /// Advances one frame on the next tick, if in step mode
+    pub fn request_step(&mut self) {
+        self.step_requested = self.is_stepping();
+    }
+
+    /// Steps up to the next faster speed, if there is one
+    pub fn speed_up(&mut self) {
+        self.scale_index = (self.scale_index + 1).min(TIME_SCALES.len() - 1);
+    }
+
+    /// Steps down to the next slower speed, if there is one
+    pub fn slow_down(&mut self) {
+        self.scale_index = self.scale_index.saturating_sub(1);
+    }
+
+    /// Short description of the clock state, e.g. "SIM: 0.5×", "PAUSED" or
+    /// "STEP: frame 120, 2.00s"
+    ///
+    /// The speed is kept while paused and shown again on resuming.
+    pub fn label(&self) -> String {
+        match self.mode {
+            SimulationMode::Running => format!("SIM: {:.1}×", self.time_scale()),
+            SimulationMode::Paused => "PAUSED".to_string(),
+            SimulationMode::StepMode => {
+                format!("STEP: frame {}, {:.2}s", self.frame, self.time)
+            }
+        }
+    }
+
+    /// Handles the clock's keyboard input
+    ///
+    /// Space pauses or resumes the simulation, + and - step through the
+    /// speeds in `TIME_SCALES`, Ctrl+S enters or leaves step mode and N
+    /// advances one frame in step mode.
+    ///
+    /// # Returns
+    /// `true` if the mode or speed changed
+    pub fn handle_input(&mut self) -> bool {
+        let before = (self.mode, self.scale_index);
+
+        if is_key_pressed(KeyCode::Space) {
+            self.toggle_pause();
+        }
+        if is_key_pressed(KeyCode::S) && ctrl_down() {
+            self.toggle_step_mode();
+        }
+        if is_key_pressed(KeyCode::N) {
+            self.request_step();
+        }
+        if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+            self.speed_up();
+        }
+        if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+            self.slow_down();
+        }
+
+        (self.mode, self.scale_index) != before
+    }
+
+    /// Draws the current speed in the bottom-right corner
+    pub fn render(&self) {
+        let text = self.label();
+        let width = measure_text(&text, None, 20, 1.0).width;
+        let x = screen_width() - width - 20.0;
+        let y = screen_height() - 35.0;
+
+        draw_rectangle(
+            x - 10.0,
+            y,
+            width + 20.0,
+            25.0,
+            Color::new(0.1, 0.1, 0.15, 0.9),
+        );
+        let color = if self.mode != SimulationMode::Running {
+            Color::new(1.0, 0.85, 0.3, 1.0)
+        } else {
+            Color::new(0.85, 0.85, 0.85, 1.0)
+        };
+        draw_text(&text, x, y + 18.0, 20.0, color);
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_scales_and_pauses_time() {
+        let mut clock = SimulationClock::new();
+        assert_eq!(clock.tick(0.5), 0.5);
+
+        clock.speed_up();
+        assert_eq!(clock.tick(0.5), 1.0);
+        assert_eq!(clock.time(), 1.5);
+
+        clock.toggle_pause();
+        assert_eq!(clock.tick(0.5), 0.0);
+        assert_eq!(clock.time(), 1.5);
+        assert_eq!(clock.label(), "PAUSED");
+
+        clock.toggle_pause();
+        assert_eq!(clock.label(), "SIM: 2.0×");
+    }
+
+    #[test]
+    fn test_time_scale_steps_stop_at_the_ends() {
+        let mut clock = SimulationClock::new();
+        for _ in 0..10 {
+            clock.slow_down();
+        }
+        assert_eq!(clock.time_scale(), 0.25);
+
+        for _ in 0..10 {
+            clock.speed_up();
+        }
+        assert_eq!(clock.time_scale(), 8.0);
+        assert_eq!(clock.label(), "SIM: 8.0×");
+    }
+
+    #[test]
+    fn test_step_mode_advances_one_frame_per_request() {
+        let mut clock = SimulationClock::new();
+        clock.tick(0.5);
+        assert_eq!(clock.frame(), 1);
+
+        clock.toggle_step_mode();
+        assert_eq!(clock.tick(0.5), 0.0);
+        assert!(!clock.stepped());
+
+        // A request advances a single fixed frame, whatever the frame time
+        clock.request_step();
+        assert_eq!(clock.tick(0.5), STEP_DURATION);
+        assert!(clock.stepped());
+        assert_eq!(clock.tick(0.5), 0.0);
+        assert_eq!(clock.frame(), 2);
+        assert_eq!(clock.label(), format!("STEP: frame 2, {:.2}s", 0.5 + STEP_DURATION));
+
+        // Steps are ignored outside step mode, and Space resumes running
+        clock.toggle_pause();
+        clock.request_step();
+        assert_eq!(clock.tick(0.5), 0.5);
+        assert!(!clock.stepped());
+    }
+}
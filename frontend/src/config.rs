@@ -0,0 +1,166 @@
+//! City configuration file
+//!
+//! Optional settings read at startup from `city_config.json` in the working
+//! directory, for tuning the simulation without rebuilding. It holds
+//! per-intersection traffic light timings, keyed by intersection ID, the IDs
+//! of intersections with stop signs instead of lights, the radius of
+//! roundabouts replacing the lights at intersections, what happens to cars
+//! at the screen edges, which side of the road traffic keeps to, the length
+//! of a day/night cycle in seconds, the colors of known teams, how cars are
+//! spawned, and where sounds are loaded from and how loud they play:
+//!
+//! ```json
+//! {
+//!     "intersections": {
+//!         "2": { "green": 6.0, "yellow": 1.5 },
+//!         "3": { "green": 6.0 }
+//!     },
+//!     "all_way_stops": [4],
+//!     "roundabouts": { "1": 30.0 },
+//!     "edge_behavior": "despawn",
+//!     "traffic_rule": "right_hand",
+//!     "day_length": 300.0,
+//!     "team_colors": { "Red Team": "#e03030", "Hackers": "#ff8000" },
+//!     "spawner": {
+//!         "interval": 1.0,
+//!         "max_cars": 25,
+//!         "road_weights": [1.0, 3.0, 1.0, 0.5, 0.5],
+//!         "style_weights": [0.5, 0.2, 0.2, 0.1],
+//!         "visitor_chance": 0.3
+//!     },
+//!     "audio": {
+//!         "directory": "assets/sounds",
+//!         "master": 0.8,
+//!         "alarm": 0.7,
+//!         "effects": 1.0,
+//!         "ambient": 0.3
+//!     }
+//! }
+//! ```
+//!
+//! Intersections and fields left out keep the defaults from
+//! `constants::traffic_light`, cars wrap around and keep left unless told
+//! otherwise, a day lasts `constants::day_night::DAY_CYCLE_SECONDS`, and the
+//! spawner and sounds keep the `SpawnConfig` and `AudioConfig` defaults. Teams without a configured
+//! color are given one when they first show up in an event.
+//! The web build has no file system and always runs with the defaults.
+
+use crate::audio::AudioConfig;
+use crate::car::EdgeBehavior;
+use crate::intersection::IntersectionType;
+use crate::models::TrafficRule;
+use crate::spawner::SpawnConfig;
+use crate::traffic_light::LightTiming;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Path of the configuration file, relative to the working directory
+pub const CONFIG_PATH: &str = "city_config.json";
+
+/// Settings loaded from the configuration file
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CityConfig {
+    /// Light timings of individual intersections, by intersection ID
+    pub intersections: HashMap<usize, LightTiming>,
+
+    /// IDs of the intersections with stop signs on every approach
+    pub all_way_stops: Vec<usize>,
+
+    /// Radius in pixels of the circle cars drive around each roundabout,
+    /// by intersection ID
+    pub roundabouts: HashMap<usize, f32>,
+
+    /// Whether cars driving off the screen despawn or wrap around
+    pub edge_behavior: EdgeBehavior,
+
+    /// Side of the road traffic keeps to at startup
+    pub traffic_rule: TrafficRule,
+
+    /// Length of a full day/night cycle in simulation seconds
+    pub day_length: Option<f32>,
+
+    /// Colors of teams as `#rrggbb`, by team name
+    pub team_colors: HashMap<String, String>,
+
+    /// Car spawn interval, car cap and per-road spawn weights
+    pub spawner: SpawnConfig,
+
+    /// Sound directory and volumes
+    pub audio: AudioConfig,
+}
+
+impl CityConfig {
+    /// Parses a configuration from JSON
+    ///
+    /// # Returns
+    /// The configuration, or a description of what is wrong with the JSON
+    pub fn parse(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|err| err.to_string())
+    }
+
+    /// Loads the configuration file, if there is one
+    ///
+    /// # Returns
+    /// `Ok(None)` if the file does not exist, an error if it cannot be read
+    /// or parsed
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Result<Option<Self>, String> {
+        match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(json) => Self::parse(&json).map(Some),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    /// Loads the configuration file; the web build never has one
+    #[cfg(target_arch = "wasm32")]
+    pub fn load() -> Result<Option<Self>, String> {
+        Ok(None)
+    }
+
+    /// Gets the light timing of an intersection
+    ///
+    /// # Arguments
+    /// * `id` - Intersection ID
+    ///
+    /// # Returns
+    /// The configured timing, or the default one
+    pub fn light_timing(&self, id: usize) -> LightTiming {
+        self.intersections.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Gets how traffic through an intersection is controlled
+    ///
+    /// # Arguments
+    /// * `id` - Intersection ID
+    ///
+    /// # Returns
+    /// `AllWayStop` for the listed all-way stops, `Roundabout` for the listed
+    /// roundabouts, traffic lights otherwise
+    pub fn intersection_type(&self, id: usize) -> IntersectionType {
+        if self.all_way_stops.contains(&id) {
+            IntersectionType::AllWayStop
+        } else if let Some(&radius) = self.roundabouts.get(&id) {
+            IntersectionType::Roundabout { radius }
+        } else {
+            IntersectionType::TrafficLight
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fills_in_default_timings() {
+        let config = CityConfig::parse(r#"{ "intersections": { "2": { "green": 6.0 } } }"#)
+            .unwrap();
+
+        assert_eq!(config.light_timing(2), LightTiming { green: 6.0, ..LightTiming::default() });
+        assert_eq!(config.light_timing(0), LightTiming::default());
+        assert!(CityConfig::parse(r#"{ "intersections": { "2": { "green": "long" } } }"#)
+            .is_err());
+    }
+}
@@ -48,20 +48,60 @@ pub mod vehicle {
     /// Height of car sprite in pixels
     pub const CAR_HEIGHT: f32 = 35.0;
 
+    /// Length and width of each vehicle style in pixels (sedans and police
+    /// cars are `CAR_HEIGHT` long)
+    pub const SEDAN_SIZE: (f32, f32) = (CAR_HEIGHT, CAR_WIDTH);
+    pub const TRUCK_SIZE: (f32, f32) = (55.0, 22.0);
+    pub const BUS_SIZE: (f32, f32) = (62.0, 22.0);
+    pub const POLICE_SIZE: (f32, f32) = (CAR_HEIGHT, CAR_WIDTH);
+
+    /// Length of a truck's cab, in front of the trailer (pixels)
+    pub const TRUCK_CAB_LENGTH: f32 = 15.0;
+
+    /// Top speed of each vehicle style as a multiple of `CAR_SPEED`
+    pub const SEDAN_SPEED_FACTOR: f32 = 1.0;
+    pub const TRUCK_SPEED_FACTOR: f32 = 0.8;
+    pub const BUS_SPEED_FACTOR: f32 = 0.7;
+    pub const POLICE_SPEED_FACTOR: f32 = 1.1;
+
+    /// Relative chance of a spawned car being a sedan, truck, bus or police
+    /// car, in that order
+    pub const STYLE_SPAWN_WEIGHTS: [f32; 4] = [0.7, 0.12, 0.08, 0.1];
+
     /// Normal driving speed in pixels per second
     pub const CAR_SPEED: f32 = 50.0;
 
-    /// Lane offset from road center in pixels (for left-hand traffic)
+    /// Rate at which cars speed back up to `CAR_SPEED` (pixels per second²)
+    pub const ACCELERATION: f32 = 80.0;
+
+    /// Braking rate used when slowing for a stop (pixels per second²)
+    pub const DECELERATION: f32 = 120.0;
+
+    /// Lane offset from road center in pixels, to either side
     pub const LANE_OFFSET: f32 = 12.0;
 
-    /// Minimum safe following distance in pixels
-    pub const SAFE_FOLLOWING_DISTANCE: f32 = 50.0;
+    /// Minimum gap kept between a car's front bumper and the rear bumper of
+    /// the car ahead (pixels)
+    pub const FOLLOWING_GAP: f32 = 15.0;
 
     /// Minimum distance before intersection to stop (pixels)
     pub const STOP_DISTANCE_MIN: f32 = 30.0;
 
-    /// Maximum distance to consider stopping before intersection (pixels)
-    pub const STOP_DISTANCE_MAX: f32 = 80.0;
+    /// Distance before an intersection's center within which stopped cars
+    /// count as queued at it (pixels)
+    pub const STOP_DISTANCE_MAX: f32 = 250.0;
+
+    /// Speed below which a car counts as stopped in a queue (pixels per second)
+    pub const QUEUED_SPEED: f32 = 1.0;
+
+    /// Distance before a stop point at which stopping cars come to rest (pixels)
+    ///
+    /// Keeps the front of a waiting car clear of the crosswalk.
+    pub const STOP_LINE_DISTANCE: f32 = 70.0;
+
+    /// Distance from an all-way stop's stop line within which a standing
+    /// car counts as waiting at it (pixels)
+    pub const STOP_LINE_REACH: f32 = 10.0;
 
     /// Tolerance for lane detection (pixels)
     pub const LANE_TOLERANCE: f32 = 20.0;
@@ -69,13 +109,104 @@ pub mod vehicle {
     /// Radius to consider as "in intersection" (pixels)
     pub const INTERSECTION_RADIUS: f32 = 40.0;
 
+    /// Side length of the grid cells cars are bucketed into for collision
+    /// checks (pixels), about as far as a car looks for the car ahead
+    pub const COLLISION_CELL_SIZE: f32 = 100.0;
+
+    /// Distance from the intersection center at which turns start and end
+    /// (pixels, half the road width)
+    pub const TURN_ENTRY_DISTANCE: f32 = 30.0;
+
+    /// Distance before an intersection's center at which a car about to turn
+    /// there turns its signal on (pixels)
+    pub const TURN_SIGNAL_DISTANCE: f32 = 60.0;
+
+    /// Angle between consecutive points of a car's path around a
+    /// roundabout (radians, 5 degrees)
+    pub const ROUNDABOUT_STEP: f32 = std::f32::consts::PI / 36.0;
+
+    /// How far around a roundabout, back from where a car enters, circling
+    /// cars make it give way (radians, a quarter turn)
+    pub const ROUNDABOUT_GIVE_WAY_ANGLE: f32 = std::f32::consts::FRAC_PI_2;
+
+    /// Emergency vehicle top speed as a multiple of `CAR_SPEED`
+    pub const EMERGENCY_SPEED_FACTOR: f32 = 1.6;
+
+    /// Distance ahead of an emergency vehicle within which cars in its lane
+    /// pull over (pixels)
+    pub const EMERGENCY_YIELD_DISTANCE: f32 = 150.0;
+
+    /// Distance before an intersection at which an approaching emergency
+    /// vehicle turns its light green (pixels)
+    pub const EMERGENCY_PREEMPT_DISTANCE: f32 = 180.0;
+
+    /// How far yielding cars move toward the road edge (pixels)
+    ///
+    /// Lanes are centered `LANE_OFFSET` from the road center, so this leaves
+    /// the car just inside the edge of a `ROAD_WIDTH` road.
+    pub const PULL_OVER_OFFSET: f32 = 8.0;
+
+    /// Sideways speed of cars pulling over or back into their lane
+    /// (pixels per second)
+    pub const PULL_OVER_SPEED: f32 = 20.0;
+
+    /// Distance from its gate within which a stopped visiting car has
+    /// arrived (pixels)
+    pub const GATE_REACH: f32 = 6.0;
+
+    /// How far before the gate of a full compound visiting cars wait, so
+    /// cars leaving it have room to join the road (pixels)
+    pub const GATE_QUEUE_GAP: f32 = 40.0;
+
+    /// Chance of a newly spawned sedan or police car visiting a guarded
+    /// compound
+    pub const VISITOR_CHANCE: f32 = 0.15;
+
     /// Time between car spawns (in seconds)
     pub const CAR_SPAWN_INTERVAL: f32 = 1.5;
 
+    /// Shortest and longest time between car spawns the `[` and `]` keys
+    /// step to (in seconds)
+    pub const MIN_SPAWN_INTERVAL: f32 = 0.25;
+    pub const MAX_SPAWN_INTERVAL: f32 = 10.0;
+
+    /// Factor each `[` or `]` press changes the time between spawns by
+    pub const SPAWN_RATE_STEP: f32 = 1.5;
+
+    /// Number of cars above which the spawner pauses while cars wrap around
+    /// the screen edges, about as many as it keeps on screen when they despawn
+    pub const MAX_WRAPPING_CARS: usize = 12;
+
     /// Probability of car planning a turn (0.0-1.0)
     pub const TURN_PROBABILITY: f32 = 0.3;
 }
 
+// ============================================================================
+// Pedestrian Constants
+// ============================================================================
+
+/// Constants related to pedestrian movement and appearance
+pub mod pedestrian {
+    /// Average walking speed in pixels per second
+    pub const PEDESTRIAN_SPEED: f32 = 20.0;
+
+    /// How much a pedestrian's walking speed may differ from
+    /// `PEDESTRIAN_SPEED`, as a fraction of it
+    pub const PEDESTRIAN_SPEED_VARIATION: f32 = 0.2;
+
+    /// Radius of the circle a pedestrian is drawn as (pixels)
+    pub const PEDESTRIAN_RADIUS: f32 = 3.0;
+
+    /// Time between pedestrian spawns (in seconds)
+    pub const PEDESTRIAN_SPAWN_INTERVAL: f32 = 2.0;
+
+    /// How high pedestrians bob while walking (pixels)
+    pub const PEDESTRIAN_BOB_HEIGHT: f32 = 1.5;
+
+    /// Steps per pixel walked, for the bobbing animation
+    pub const PEDESTRIAN_STEP_FREQUENCY: f32 = 0.4;
+}
+
 // ============================================================================
 // Traffic Light Constants
 // ============================================================================
@@ -93,8 +224,30 @@ pub mod traffic_light {
     /// Red light duration in seconds
     pub const RED_DURATION: f32 = 3.0;
 
-    /// Total traffic light cycle duration in seconds
-    pub const CYCLE_DURATION: f32 = GREEN_DURATION + YELLOW_DURATION + RED_DURATION;
+    /// Longest green an adaptive light gives a congested direction, as a
+    /// multiple of its configured green duration
+    pub const ADAPTIVE_GREEN_MAX_FACTOR: f32 = 2.0;
+
+    /// Shortest green an adaptive light gives an empty direction, as a
+    /// multiple of its configured green duration
+    pub const ADAPTIVE_GREEN_MIN_FACTOR: f32 = 0.5;
+
+    /// Seconds a car stands at an all-way stop's stop line before it may go
+    pub const ALL_WAY_STOP_WAIT: f32 = 2.0;
+
+    /// Radius of the stop signs at the corners of an all-way stop (pixels)
+    pub const STOP_SIGN_RADIUS: f32 = 6.0;
+
+    /// Longest green an adaptive light gives any direction, in seconds,
+    /// unless the configured green duration is already longer
+    pub const MAX_GREEN_DURATION: f32 = 8.0;
+
+    /// Font size of the queue lengths shown at intersections
+    pub const QUEUE_LABEL_FONT_SIZE: f32 = 16.0;
+
+    /// Distance from an intersection's center at which its queue lengths
+    /// are shown, on the side each queue approaches from (pixels)
+    pub const QUEUE_LABEL_DISTANCE: f32 = 45.0;
 
     /// Diameter of each light circle in pixels
     pub const TRAFFIC_LIGHT_SIZE: f32 = 12.0;
@@ -123,6 +276,9 @@ pub mod traffic_light {
     /// Dim green color for inactive green light
     pub const GREEN_DIM: Color = Color::new(0.0, 0.3, 0.0, 1.0);
 
+    /// Seconds each flash of an unpowered light's yellow lamp stays on (and off)
+    pub const POWER_OUTAGE_FLASH_INTERVAL: f64 = 0.5;
+
     /// Traffic light box background color
     pub const BOX_COLOR: Color = Color::new(0.2, 0.2, 0.2, 1.0);
 
@@ -189,6 +345,24 @@ pub mod led {
     /// Flash speed in flashes per second (danger mode)
     pub const LED_FLASH_SPEED: f32 = 3.0;
 
+    /// Seconds each of several equally urgent alerts is shown
+    pub const LED_MESSAGE_ROTATE_INTERVAL: f32 = 4.0;
+
+    /// Seconds a queued static or flashing message without a duration is shown
+    pub const LED_STATIC_MESSAGE_DURATION: f32 = 4.0;
+
+    /// Glitch frames per second (broken mode)
+    pub const LED_GLITCH_RATE: f32 = 12.0;
+
+    /// Chance that a character is garbled during a glitch frame (broken mode)
+    pub const LED_GLITCH_CHANCE: f32 = 0.25;
+
+    /// Chance that the text drops out for a glitch frame (broken mode)
+    pub const LED_BLACKOUT_CHANCE: f32 = 0.1;
+
+    /// Fraction of dots lit by static noise (broken mode)
+    pub const LED_NOISE_DENSITY: f32 = 0.08;
+
     /// Frame thickness in pixels
     pub const FRAME_THICKNESS: f32 = 8.0;
 
@@ -237,6 +411,12 @@ pub mod road_network {
 
     /// Number of horizontal roads
     pub const HORIZONTAL_ROAD_COUNT: usize = 2;
+
+    /// Power districts from west to east
+    ///
+    /// Each district starts at a vertical road and runs east to the next one;
+    /// the first also covers the blocks west of the first road.
+    pub const DISTRICT_NAMES: [&str; VERTICAL_ROAD_COUNT] = ["west", "central", "east"];
 }
 
 // ============================================================================
@@ -273,6 +453,270 @@ pub mod rendering {
 
     /// Window color for car windshields
     pub const CAR_WINDOW_COLOR: Color = Color::new(0.6, 0.8, 1.0, 1.0);
+
+    /// Body colors sedans and truck cabs are picked from
+    pub const CAR_COLORS: [Color; 5] = [BLUE, RED, YELLOW, Color::new(1.0, 0.5, 0.0, 1.0), PURPLE];
+
+    /// Color of truck trailers
+    pub const TRUCK_TRAILER_COLOR: Color = Color::new(0.85, 0.85, 0.8, 1.0);
+
+    /// Body color of buses
+    pub const BUS_COLOR: Color = Color::new(0.95, 0.75, 0.1, 1.0);
+
+    /// Body color of police cars
+    pub const POLICE_COLOR: Color = Color::new(0.1, 0.15, 0.35, 1.0);
+
+    /// Unlit red and blue halves of a police car's light bar
+    pub const POLICE_LIGHT_BAR_COLORS: (Color, Color) =
+        (Color::new(0.5, 0.1, 0.1, 1.0), Color::new(0.1, 0.1, 0.5, 1.0));
+
+    /// Body color of emergency vehicles
+    pub const EMERGENCY_CAR_COLOR: Color = Color::new(0.95, 0.95, 0.95, 1.0);
+
+    /// Time between emergency light bar flashes (seconds)
+    pub const EMERGENCY_FLASH_INTERVAL: f64 = 0.25;
+
+    /// Color of a blinking turn signal
+    pub const TURN_SIGNAL_COLOR: Color = ORANGE;
+
+    /// Length of the triangle a turn signal sticks out of the car (pixels)
+    pub const TURN_SIGNAL_SIZE: f32 = 4.0;
+}
+
+// ============================================================================
+// Effects Constants
+// ============================================================================
+
+/// Constants for particle effects
+pub mod effects {
+    use macroquad::prelude::*;
+
+    /// Minimum number of particles in a burst
+    pub const BURST_PARTICLES_MIN: usize = 20;
+
+    /// Maximum number of particles in a burst
+    pub const BURST_PARTICLES_MAX: usize = 30;
+
+    /// Minimum initial particle size in pixels
+    pub const PARTICLE_SIZE_MIN: f32 = 4.0;
+
+    /// Maximum initial particle size in pixels
+    pub const PARTICLE_SIZE_MAX: f32 = 6.0;
+
+    /// Minimum particle speed in pixels per second
+    pub const PARTICLE_SPEED_MIN: f32 = 40.0;
+
+    /// Maximum particle speed in pixels per second
+    pub const PARTICLE_SPEED_MAX: f32 = 140.0;
+
+    /// Minimum particle lifetime in seconds
+    pub const PARTICLE_LIFE_MIN: f32 = 0.6;
+
+    /// Maximum particle lifetime in seconds
+    pub const PARTICLE_LIFE_MAX: f32 = 1.2;
+
+    /// Fraction of particle velocity lost per second
+    pub const PARTICLE_DRAG: f32 = 1.5;
+
+    /// Burst color for SCADA compromise (red)
+    pub const SCADA_COMPROMISED_COLOR: Color = Color::new(1.0, 0.1, 0.0, 1.0);
+
+    /// Secondary burst color for SCADA compromise (orange)
+    pub const SCADA_COMPROMISED_ALT_COLOR: Color = Color::new(1.0, 0.55, 0.0, 1.0);
+
+    /// Burst color for SCADA restore (green)
+    pub const SCADA_RESTORED_COLOR: Color = Color::new(0.1, 1.0, 0.3, 1.0);
+
+    /// Flame particles emitted per second by a building fire at full intensity
+    pub const FIRE_PARTICLES_PER_SEC: f32 = 45.0;
+
+    /// Half-width in pixels of the area flames rise from
+    pub const FIRE_SPREAD: f32 = 14.0;
+
+    /// Height in pixels above the block center where flames start
+    pub const FIRE_BASE_OFFSET: f32 = 10.0;
+
+    /// Minimum upward flame speed in pixels per second
+    pub const FIRE_RISE_SPEED_MIN: f32 = 35.0;
+
+    /// Maximum upward flame speed in pixels per second
+    pub const FIRE_RISE_SPEED_MAX: f32 = 80.0;
+
+    /// Maximum sideways flame drift in pixels per second
+    pub const FIRE_DRIFT: f32 = 12.0;
+
+    /// Minimum flame particle lifetime in seconds
+    pub const FIRE_LIFE_MIN: f32 = 0.4;
+
+    /// Maximum flame particle lifetime in seconds
+    pub const FIRE_LIFE_MAX: f32 = 0.9;
+
+    /// Fraction of a flame's brightness that flickers from frame to frame
+    pub const FIRE_FLICKER: f32 = 0.4;
+
+    /// Flame color (orange)
+    pub const FIRE_COLOR: Color = Color::new(1.0, 0.5, 0.0, 1.0);
+
+    /// Secondary flame color (yellow)
+    pub const FIRE_ALT_COLOR: Color = Color::new(1.0, 0.85, 0.2, 1.0);
+
+    /// Height in pixels of the flame shapes of a fire at full intensity
+    pub const FIRE_FLAME_HEIGHT: f32 = 30.0;
+
+    /// Number of flame tongues drawn over a burning building
+    pub const FIRE_FLAME_TONGUES: usize = 4;
+
+    /// How fast the flame tongues sway, in radians per second
+    pub const FIRE_SWAY_SPEED: f32 = 7.0;
+
+    /// Farthest a flame tongue sways sideways, in pixels
+    pub const FIRE_SWAY: f32 = 2.5;
+
+    /// Seconds a fire takes to fade away once put out
+    pub const FIRE_FADE_OUT_TIME: f32 = 2.0;
+
+    /// Intensity above which a fire gives off smoke
+    pub const FIRE_SMOKE_THRESHOLD: f32 = 0.8;
+
+    /// Smoke puffs emitted per second by a smoking fire
+    pub const SMOKE_PARTICLES_PER_SEC: f32 = 10.0;
+
+    /// Upward smoke speed in pixels per second
+    pub const SMOKE_RISE_SPEED: f32 = 22.0;
+
+    /// Minimum smoke puff lifetime in seconds
+    pub const SMOKE_LIFE_MIN: f32 = 1.5;
+
+    /// Maximum smoke puff lifetime in seconds
+    pub const SMOKE_LIFE_MAX: f32 = 2.5;
+
+    /// Initial smoke puff size in pixels (doubles as it rises)
+    pub const SMOKE_SIZE: f32 = 9.0;
+
+    /// Smoke color (dark gray, translucent)
+    pub const SMOKE_COLOR: Color = Color::new(0.25, 0.25, 0.25, 0.5);
+}
+
+// ============================================================================
+// Simulation Clock Constants
+// ============================================================================
+
+/// Constants for the pausable, time-scaled simulation clock
+pub mod simulation {
+    /// Speeds the simulation can run at, doubled or halved with +/-
+    pub const TIME_SCALES: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+    /// Index into `TIME_SCALES` of real-time speed
+    pub const DEFAULT_TIME_SCALE_INDEX: usize = 2;
+
+    /// Simulation seconds one step advances in step mode, a frame at 60 FPS
+    pub const STEP_DURATION: f32 = 1.0 / 60.0;
+}
+
+// ============================================================================
+// Day/Night Cycle Constants
+// ============================================================================
+
+/// Constants for the day/night cycle and night lighting
+pub mod day_night {
+    use super::*;
+
+    /// Length of a full day/night cycle in simulation seconds
+    pub const DAY_CYCLE_SECONDS: f32 = 120.0;
+
+    /// Point of the cycle the city starts at (0.0 = midnight, 0.5 = noon)
+    pub const START_PHASE: f32 = 0.3;
+
+    /// Height of the sun (-1.0 to 1.0) above and below the horizon over which
+    /// daylight fades in and out; larger values give longer dawns and dusks
+    pub const TWILIGHT: f32 = 0.4;
+
+    /// Amount subtracted from RGB channels of the scenery at full night
+    pub const NIGHT_DARKEN: f32 = 0.35;
+
+    /// Dark blue the scenery is tinted toward at night; its alpha is how far
+    /// colors are blended toward it at full night
+    pub const NIGHT_OVERLAY_COLOR: Color = Color::new(0.05, 0.08, 0.25, 0.35);
+
+    /// Ambient light level below which windows and headlights are on
+    pub const LIGHTS_ON_AMBIENT: f32 = 0.5;
+
+    /// Length of a headlight cone in front of a car, in pixels
+    pub const HEADLIGHT_LENGTH: f32 = 70.0;
+
+    /// Width of a headlight cone at its far end, in pixels
+    pub const HEADLIGHT_SPREAD: f32 = 40.0;
+
+    /// Headlight cone color at full night
+    pub const HEADLIGHT_COLOR: Color = Color::new(1.0, 0.95, 0.7, 0.3);
+
+    /// Radius of the glow around a lit traffic light lamp, in pixels
+    pub const LAMP_GLOW_RADIUS: f32 = 14.0;
+
+    /// Width of the bloom behind a traffic light's housing, in pixels
+    pub const LIGHT_BLOOM_WIDTH: f32 = 10.0;
+
+    /// Width of the glow around the LED display, in pixels
+    pub const LED_GLOW_WIDTH: f32 = 24.0;
+
+    /// Opacity of the night glows at full night, right at the glowing edge
+    pub const GLOW_ALPHA: f32 = 0.35;
+
+    /// Number of translucent layers a glow is drawn with
+    pub const GLOW_LAYERS: usize = 4;
+}
+
+// ============================================================================
+// Weather Constants
+// ============================================================================
+
+/// Constants for rain and its effect on traffic
+pub mod weather {
+    use macroquad::prelude::*;
+
+    /// Number of rain drops on screen at full intensity
+    pub const MAX_RAIN_DROPS: usize = 600;
+
+    /// Slowest drop fall speed, in screen heights per second
+    pub const RAIN_SPEED_MIN: f32 = 0.9;
+
+    /// Fastest drop fall speed, in screen heights per second
+    pub const RAIN_SPEED_MAX: f32 = 1.4;
+
+    /// Fraction of full fall speed drops keep in the lightest drizzle
+    pub const DRIZZLE_SPEED_FACTOR: f32 = 0.5;
+
+    /// Angle of the falling rain from vertical in radians (positive slants
+    /// to the right, as if blown by wind)
+    pub const RAIN_ANGLE: f32 = 0.25;
+
+    /// Random spread of each drop's angle around `RAIN_ANGLE` in radians
+    pub const RAIN_ANGLE_SPREAD: f32 = 0.05;
+
+    /// Length in pixels of a drop's streak at full fall speed
+    pub const RAIN_STREAK_LENGTH: f32 = 14.0;
+
+    /// Rain streak color
+    pub const RAIN_COLOR: Color = Color::new(0.7, 0.78, 0.9, 0.55);
+
+    /// Fraction of their speed cars lose in the heaviest rain
+    pub const RAIN_MAX_SLOWDOWN: f32 = 0.3;
+}
+
+// ============================================================================
+// Camera Constants
+// ============================================================================
+
+/// Constants for zooming and panning the city view
+pub mod camera {
+    /// Zoom level showing the whole city
+    pub const MIN_ZOOM: f32 = 1.0;
+
+    /// Closest zoom level
+    pub const MAX_ZOOM: f32 = 4.0;
+
+    /// Zoom factor applied per mouse wheel notch
+    pub const ZOOM_STEP: f32 = 1.1;
 }
 
 // ============================================================================
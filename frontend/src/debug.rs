@@ -0,0 +1,261 @@
+//! Debug overlay with frame timings
+//!
+//! This module handles:
+//! - Timing the phases of each frame (simulation update, rendering layers,
+//!   log window) with `begin_phase`/`end_phase` around them
+//! - Rolling averages over the last `WINDOW_FRAMES` frames
+//! - A translucent panel with the FPS, frame time, entity counts and one bar
+//!   per phase, proportional to its milliseconds
+//!
+//! F3 shows or hides the overlay. While it is shown the city also draws car
+//! bounding boxes and intersection radii (`City::render_debug_bounds`).
+//! Phases are timed with `get_time()` whether or not the overlay is shown,
+//! so the averages are ready as soon as it opens.
+
+use crate::city::City;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+// ============================================================================
+// Debug Overlay Constants
+// ============================================================================
+
+/// Number of frames the timings are averaged over
+const WINDOW_FRAMES: usize = 60;
+
+/// Panel dimensions
+const PANEL_WIDTH: f32 = 300.0;
+const TITLE_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 18.0;
+
+/// Width of a bar for a phase taking one full 60 FPS frame (16.7 ms)
+const BAR_WIDTH: f32 = 150.0;
+
+/// Milliseconds in one frame at 60 FPS, the full width of a bar
+const FRAME_BUDGET_MS: f64 = 1000.0 / 60.0;
+
+// ============================================================================
+// Rolling Average
+// ============================================================================
+
+/// Last `WINDOW_FRAMES` samples of a timing, in milliseconds
+#[derive(Default)]
+struct Samples(VecDeque<f64>);
+
+impl Samples {
+    /// Adds a sample, dropping the oldest once the window is full
+    fn push(&mut self, ms: f64) {
+        if self.0.len() == WINDOW_FRAMES {
+            self.0.pop_front();
+        }
+        self.0.push_back(ms);
+    }
+
+    /// Average of the samples, or 0.0 before the first one
+    fn average(&self) -> f64 {
+        if self.0.is_empty() {
+            0.0
+        } else {
+            self.0.iter().sum::<f64>() / self.0.len() as f64
+        }
+    }
+}
+
+/// Timings of one named phase of the frame
+struct Phase {
+    name: &'static str,
+
+    /// When the phase began this frame, in seconds, while it runs
+    started: Option<f64>,
+
+    samples: Samples,
+}
+
+// ============================================================================
+// Debug Overlay
+// ============================================================================
+
+/// Frame timings and the panel showing them
+pub struct DebugOverlay {
+    /// Phases in the order they were first timed
+    phases: Vec<Phase>,
+
+    frame_times: Samples,
+
+    visible: bool,
+}
+
+impl DebugOverlay {
+    /// Creates a hidden overlay with no timings yet
+    pub fn new() -> Self {
+        Self {
+            phases: Vec::new(),
+            frame_times: Samples::default(),
+            visible: false,
+        }
+    }
+
+    /// Shows or hides the overlay when F3 is pressed
+    pub fn handle_input(&mut self) {
+        if is_key_pressed(KeyCode::F3) {
+            self.visible = !self.visible;
+        }
+    }
+
+    /// Whether the overlay is shown
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Starts timing a phase
+    ///
+    /// # Arguments
+    /// * `name` - Name of the phase, as shown in the panel
+    pub fn begin_phase(&mut self, name: &'static str) {
+        let now = get_time();
+        self.phase(name).started = Some(now);
+    }
+
+    /// Stops timing a phase and records how long it took
+    ///
+    /// Ending a phase that was not begun does nothing.
+    ///
+    /// # Arguments
+    /// * `name` - Name given to `begin_phase`
+    pub fn end_phase(&mut self, name: &'static str) {
+        let phase = self.phase(name);
+        if let Some(started) = phase.started.take() {
+            phase.samples.push((get_time() - started) * 1000.0);
+        }
+    }
+
+    /// Records the duration of the frame just finished
+    ///
+    /// # Arguments
+    /// * `frame_time` - Real frame duration in seconds
+    pub fn end_frame(&mut self, frame_time: f32) {
+        self.frame_times.push(frame_time as f64 * 1000.0);
+    }
+
+    /// Gets a phase by name, adding it after the others the first time
+    fn phase(&mut self, name: &'static str) -> &mut Phase {
+        let index = match self.phases.iter().position(|phase| phase.name == name) {
+            Some(index) => index,
+            None => {
+                self.phases.push(Phase {
+                    name,
+                    started: None,
+                    samples: Samples::default(),
+                });
+                self.phases.len() - 1
+            }
+        };
+        &mut self.phases[index]
+    }
+
+    /// Average duration of each phase in milliseconds, in the order first timed
+    fn averages(&self) -> Vec<(&'static str, f64)> {
+        self.phases
+            .iter()
+            .map(|phase| (phase.name, phase.samples.average()))
+            .collect()
+    }
+
+    /// Draws the panel in the top-left corner, unless the overlay is hidden
+    ///
+    /// # Arguments
+    /// * `city` - City whose cars, intersections and blocks are counted
+    pub fn render(&self, city: &City) {
+        if !self.visible {
+            return;
+        }
+
+        let phases = self.averages();
+        let panel_x = 10.0;
+        let panel_y = 10.0;
+        let panel_height = TITLE_HEIGHT + (phases.len() + 3) as f32 * ROW_HEIGHT + 10.0;
+
+        // Background and title
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            Color::new(0.05, 0.05, 0.1, 0.75),
+        );
+        draw_rectangle_lines(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            1.0,
+            Color::new(0.4, 0.8, 0.4, 0.8),
+        );
+        draw_text("DEBUG (F3)", panel_x + 10.0, panel_y + 18.0, 20.0, GREEN);
+
+        let text_color = Color::new(0.85, 0.85, 0.85, 1.0);
+        let mut y = panel_y + TITLE_HEIGHT + ROW_HEIGHT - 4.0;
+        let lines = [
+            format!(
+                "FPS: {}   Frame: {:.2} ms",
+                get_fps(),
+                self.frame_times.average()
+            ),
+            format!(
+                "Cars: {}   Intersections: {}   Blocks: {}",
+                city.car_count(),
+                city.intersection_count(),
+                city.block_count()
+            ),
+        ];
+        for line in &lines {
+            draw_text(line, panel_x + 10.0, y, 16.0, text_color);
+            y += ROW_HEIGHT;
+        }
+
+        // One bar per phase, full width at a whole 60 FPS frame
+        y += 4.0;
+        for (name, ms) in phases {
+            draw_text(name, panel_x + 10.0, y, 14.0, text_color);
+            let width = (ms / FRAME_BUDGET_MS).min(1.0) as f32 * BAR_WIDTH;
+            let color = if ms > FRAME_BUDGET_MS / 2.0 { ORANGE } else { GREEN };
+            draw_rectangle(panel_x + 90.0, y - 10.0, width, 10.0, color);
+            draw_text(
+                &format!("{:.2} ms", ms),
+                panel_x + 95.0 + BAR_WIDTH,
+                y,
+                14.0,
+                text_color,
+            );
+            y += ROW_HEIGHT;
+        }
+    }
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_averages_cover_the_last_frames() {
+        let mut overlay = DebugOverlay::new();
+        for ms in 0..WINDOW_FRAMES + 10 {
+            let update = overlay.phase("update");
+            update.samples.push(if ms < 10 { 100.0 } else { 2.0 });
+        }
+        overlay.phase("render").samples.push(4.0);
+
+        // The slow first frames have left the window
+        assert_eq!(overlay.averages(), [("update", 2.0), ("render", 4.0)]);
+
+        // A phase ended without beginning records nothing
+        overlay.end_phase("spawn");
+        assert_eq!(overlay.averages()[2], ("spawn", 0.0));
+    }
+}
@@ -0,0 +1,402 @@
+//! Visual effects
+//!
+//! Particle effects used to highlight events in the city, such as bursts of
+//! sparks when a building's SCADA system is compromised or restored, and the
+//! flames rising from a burning building.
+
+use crate::constants::effects::*;
+use macroquad::prelude::*;
+
+// ============================================================================
+// Burst Particle
+// ============================================================================
+
+/// A single particle in a burst effect
+#[derive(Clone, Debug)]
+pub struct BurstParticle {
+    /// X position in pixels
+    pub x: f32,
+
+    /// Y position in pixels
+    pub y: f32,
+
+    /// Horizontal velocity in pixels per second
+    pub vx: f32,
+
+    /// Vertical velocity in pixels per second
+    pub vy: f32,
+
+    /// Remaining lifetime in seconds
+    pub life: f32,
+
+    /// Total lifetime in seconds
+    pub max_life: f32,
+
+    /// Initial size in pixels (shrinks to 0 as life expires)
+    pub size: f32,
+
+    /// Particle color
+    pub color: Color,
+}
+
+impl BurstParticle {
+    /// Returns true while the particle still has life left
+    pub fn is_alive(&self) -> bool {
+        self.life > 0.0
+    }
+
+    /// Returns the current size, shrinking linearly with remaining life
+    pub fn current_size(&self) -> f32 {
+        self.size * (self.life / self.max_life).max(0.0)
+    }
+
+    /// Advances the particle by one frame
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update(&mut self, dt: f32) {
+        self.x += self.vx * dt;
+        self.y += self.vy * dt;
+
+        // Slow the particle down so the burst settles instead of flying off
+        let drag = (1.0 - PARTICLE_DRAG * dt).max(0.0);
+        self.vx *= drag;
+        self.vy *= drag;
+
+        self.life -= dt;
+    }
+}
+
+// ============================================================================
+// Particle Burst
+// ============================================================================
+
+/// A group of particles exploding outward from a single point
+#[derive(Clone, Debug)]
+pub struct ParticleBurst {
+    /// Particles in this burst
+    pub particles: Vec<BurstParticle>,
+
+    /// False once every particle has expired
+    pub alive: bool,
+}
+
+impl ParticleBurst {
+    /// Creates a burst of particles with random velocities in all directions
+    ///
+    /// # Arguments
+    /// * `x` - Burst center X position in pixels
+    /// * `y` - Burst center Y position in pixels
+    /// * `color` - Color of every particle in the burst
+    /// * `count` - Number of particles to spawn
+    pub fn new(x: f32, y: f32, color: Color, count: usize) -> Self {
+        let particles = (0..count)
+            .map(|_| {
+                let angle = rand::gen_range(0.0, std::f32::consts::TAU);
+                let speed = rand::gen_range(PARTICLE_SPEED_MIN, PARTICLE_SPEED_MAX);
+                let life = rand::gen_range(PARTICLE_LIFE_MIN, PARTICLE_LIFE_MAX);
+
+                BurstParticle {
+                    x,
+                    y,
+                    vx: angle.cos() * speed,
+                    vy: angle.sin() * speed,
+                    life,
+                    max_life: life,
+                    size: rand::gen_range(PARTICLE_SIZE_MIN, PARTICLE_SIZE_MAX),
+                    color,
+                }
+            })
+            .collect();
+
+        Self {
+            particles,
+            alive: count > 0,
+        }
+    }
+
+    /// Advances all particles and drops the expired ones
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.update(dt);
+        }
+        self.particles.retain(|p| p.is_alive());
+        self.alive = !self.particles.is_empty();
+    }
+
+    /// Draws all particles, fading them out as they shrink
+    pub fn render(&self) {
+        for particle in &self.particles {
+            let fade = (particle.life / particle.max_life).clamp(0.0, 1.0);
+            let color = Color::new(
+                particle.color.r,
+                particle.color.g,
+                particle.color.b,
+                particle.color.a * fade,
+            );
+            draw_circle(particle.x, particle.y, particle.current_size() / 2.0, color);
+        }
+    }
+}
+
+// ============================================================================
+// Building Fire
+// ============================================================================
+
+/// Flickering flames rising from a burning building
+///
+/// A fire is drawn as layered flame shapes swaying over the building, with
+/// sparks rising from them. Unlike a `ParticleBurst`, it keeps emitting sparks
+/// at a rate proportional to its intensity until it is put out, and the
+/// strongest fires give off smoke as well. Once put out, it fades away over
+/// `FIRE_FADE_OUT_TIME`.
+#[derive(Clone, Debug)]
+pub struct BuildingFire {
+    /// Flame base center X position in pixels
+    pub x: f32,
+
+    /// Flame base center Y position in pixels
+    pub y: f32,
+
+    /// Fire intensity from 0.0 to 1.0
+    pub intensity: f32,
+
+    /// Animation phase in radians, so fires don't sway in step
+    pub phase: f32,
+
+    /// Live flame particles
+    pub particles: Vec<BurstParticle>,
+
+    /// Live smoke particles, only emitted above `FIRE_SMOKE_THRESHOLD`
+    pub smoke: Vec<BurstParticle>,
+
+    /// Seconds left until a fire that was put out is gone, or None while
+    /// it burns
+    fade_out: Option<f32>,
+
+    /// Fractional flame and smoke particles carried over to the next frame
+    pending: f32,
+    pending_smoke: f32,
+}
+
+impl BuildingFire {
+    /// Creates a fire with no flames yet
+    ///
+    /// # Arguments
+    /// * `x` - Flame base center X position in pixels
+    /// * `y` - Flame base center Y position in pixels
+    /// * `intensity` - Fire intensity (clamped to 0.0-1.0)
+    pub fn new(x: f32, y: f32, intensity: f32) -> Self {
+        Self {
+            x,
+            y,
+            intensity: intensity.clamp(0.0, 1.0),
+            phase: rand::gen_range(0.0, std::f32::consts::TAU),
+            particles: Vec::new(),
+            smoke: Vec::new(),
+            fade_out: None,
+            pending: 0.0,
+            pending_smoke: 0.0,
+        }
+    }
+
+    /// Changes the intensity, relighting the fire if it was fading out
+    ///
+    /// # Arguments
+    /// * `intensity` - Fire intensity (clamped to 0.0-1.0)
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0.0, 1.0);
+        self.fade_out = None;
+    }
+
+    /// Moves the fire, along with its flames and smoke, to a new base position
+    ///
+    /// # Arguments
+    /// * `x` - New flame base center X position in pixels
+    /// * `y` - New flame base center Y position in pixels
+    pub fn move_to(&mut self, x: f32, y: f32) {
+        let (dx, dy) = (x - self.x, y - self.y);
+        for particle in self.particles.iter_mut().chain(&mut self.smoke) {
+            particle.x += dx;
+            particle.y += dy;
+        }
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Puts the fire out, starting its fade
+    ///
+    /// # Returns
+    /// `false` if the fire was already put out
+    pub fn extinguish(&mut self) -> bool {
+        if self.fade_out.is_some() {
+            return false;
+        }
+        self.fade_out = Some(FIRE_FADE_OUT_TIME);
+        true
+    }
+
+    /// Returns true once a fire that was put out has faded away
+    pub fn is_gone(&self) -> bool {
+        self.fade_out.is_some_and(|left| left <= 0.0)
+    }
+
+    /// Opacity of the whole fire, 1.0 while burning and falling to 0.0 as it
+    /// fades out
+    pub fn opacity(&self) -> f32 {
+        self.fade_out
+            .map_or(1.0, |left| (left / FIRE_FADE_OUT_TIME).clamp(0.0, 1.0))
+    }
+
+    /// Emits new flames and smoke and advances the existing particles
+    ///
+    /// A fire that was put out emits nothing more and counts down its fade.
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update(&mut self, dt: f32) {
+        for particle in self.particles.iter_mut().chain(&mut self.smoke) {
+            particle.update(dt);
+        }
+        self.particles.retain(|p| p.is_alive());
+        self.smoke.retain(|p| p.is_alive());
+
+        if let Some(left) = &mut self.fade_out {
+            *left -= dt;
+            return;
+        }
+
+        self.pending += FIRE_PARTICLES_PER_SEC * self.intensity * dt;
+        while self.pending >= 1.0 {
+            self.pending -= 1.0;
+            self.particles.push(self.spawn_flame());
+        }
+
+        if self.intensity > FIRE_SMOKE_THRESHOLD {
+            self.pending_smoke += SMOKE_PARTICLES_PER_SEC * dt;
+            while self.pending_smoke >= 1.0 {
+                self.pending_smoke -= 1.0;
+                self.smoke.push(self.spawn_smoke());
+            }
+        }
+    }
+
+    /// Creates one flame particle at the base of the fire
+    fn spawn_flame(&self) -> BurstParticle {
+        let life = rand::gen_range(FIRE_LIFE_MIN, FIRE_LIFE_MAX);
+        // Stronger fires burn taller and hotter
+        let rise = rand::gen_range(FIRE_RISE_SPEED_MIN, FIRE_RISE_SPEED_MAX)
+            * (0.5 + self.intensity / 2.0);
+        let color = if rand::gen_range(0.0, 1.0) < 0.6 {
+            FIRE_COLOR
+        } else {
+            FIRE_ALT_COLOR
+        };
+
+        BurstParticle {
+            x: self.x + rand::gen_range(-FIRE_SPREAD, FIRE_SPREAD),
+            y: self.y,
+            vx: rand::gen_range(-FIRE_DRIFT, FIRE_DRIFT),
+            vy: -rise,
+            life,
+            max_life: life,
+            size: rand::gen_range(PARTICLE_SIZE_MIN, PARTICLE_SIZE_MAX),
+            color,
+        }
+    }
+
+    /// Creates one smoke puff above the flames
+    fn spawn_smoke(&self) -> BurstParticle {
+        let life = rand::gen_range(SMOKE_LIFE_MIN, SMOKE_LIFE_MAX);
+
+        BurstParticle {
+            x: self.x + rand::gen_range(-FIRE_SPREAD, FIRE_SPREAD) / 2.0,
+            y: self.y - FIRE_FLAME_HEIGHT * self.intensity,
+            vx: rand::gen_range(-FIRE_DRIFT, FIRE_DRIFT),
+            vy: -SMOKE_RISE_SPEED,
+            life,
+            max_life: life,
+            size: SMOKE_SIZE,
+            color: SMOKE_COLOR,
+        }
+    }
+
+    /// Draws one layer of swaying flame tongues as a fan of triangles
+    ///
+    /// # Arguments
+    /// * `time` - Current simulation time, which moves the tongues
+    /// * `scale` - Size of this layer relative to the outer flames
+    /// * `color` - Layer color
+    fn draw_flame_layer(&self, time: f32, scale: f32, color: Color) {
+        let half_width = FIRE_SPREAD * scale;
+        let height = FIRE_FLAME_HEIGHT * scale * (0.5 + self.intensity / 2.0);
+        let base = vec2(self.x, self.y);
+
+        // Outline from the left base corner over alternating tips and dips
+        // to the right one; each tip sways at its own phase
+        let points = FIRE_FLAME_TONGUES * 2 + 1;
+        let outline: Vec<Vec2> = (0..points)
+            .map(|i| {
+                let along = i as f32 / (points - 1) as f32;
+                let x = self.x - half_width + along * half_width * 2.0;
+                // Tongues are tallest in the middle of the fire
+                let arch = (along * std::f32::consts::PI).sin();
+                let wave = (time * FIRE_SWAY_SPEED + self.phase + i as f32 * 1.7).sin();
+                let y = if i % 2 == 1 {
+                    self.y - height * arch * (0.8 + 0.2 * wave)
+                } else {
+                    self.y - height * arch * (0.35 + 0.1 * wave)
+                };
+                vec2(x + wave * FIRE_SWAY * scale, y)
+            })
+            .collect();
+
+        for pair in outline.windows(2) {
+            draw_triangle(base, pair[0], pair[1], color);
+        }
+    }
+
+    /// Draws the flames and smoke
+    ///
+    /// Smoke goes under the flames, then an orange and a yellow layer of
+    /// flame shapes, then the rising sparks. Everything fades with
+    /// `opacity` once the fire is put out.
+    ///
+    /// # Arguments
+    /// * `time` - Current simulation time, which animates the flame shapes
+    pub fn render(&self, time: f64) {
+        let opacity = self.opacity();
+
+        for particle in &self.smoke {
+            let fade = (particle.life / particle.max_life).clamp(0.0, 1.0);
+            // Smoke spreads out as it rises
+            let size = particle.size * (2.0 - fade);
+            let color = Color::new(
+                particle.color.r,
+                particle.color.g,
+                particle.color.b,
+                particle.color.a * fade * opacity,
+            );
+            draw_circle(particle.x, particle.y, size / 2.0, color);
+        }
+
+        let fade = |color: Color| Color::new(color.r, color.g, color.b, color.a * opacity);
+        self.draw_flame_layer(time as f32, 1.0, fade(FIRE_COLOR));
+        self.draw_flame_layer(time as f32, 0.55, fade(FIRE_ALT_COLOR));
+
+        for particle in &self.particles {
+            let fade = (particle.life / particle.max_life).clamp(0.0, 1.0);
+            let flicker = 1.0 - rand::gen_range(0.0, FIRE_FLICKER);
+            let color = Color::new(
+                particle.color.r,
+                particle.color.g * flicker,
+                particle.color.b,
+                particle.color.a * fade * flicker * opacity,
+            );
+            draw_circle(particle.x, particle.y, particle.current_size() / 2.0, color);
+        }
+    }
+}
@@ -1,86 +1,13 @@
 //! Event system for handling server-sent events and game state changes
 //!
-//! This module defines event types that can be received from the server via SSE
-//! or triggered locally via keyboard. Events are passed through channels from
-//! the SSE background thread to the main game loop.
+//! The event types themselves live in the shared `city-events` crate, so
+//! they always match what the backend sends. This module re-exports them and
+//! provides the channel that passes events from the SSE background thread to
+//! the main game loop.
 
-use serde::{Deserialize, Serialize};
 use std::sync::mpsc;
 
-/// Game events that can be triggered by server or keyboard
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum GameEvent {
-    /// Barrier gate broken by a team
-    BarrierBroken {
-        team: String,
-        message: Option<String>,
-    },
-
-    /// Barrier gate repaired/reset
-    BarrierRepaired {
-        team: Option<String>,
-    },
-
-    /// LED display broken or damaged
-    LedDisplayBroken {
-        team: String,
-        message: Option<String>,
-    },
-
-    /// LED display repaired
-    LedDisplayRepaired,
-
-    /// SCADA system compromised
-    ScadaCompromised {
-        building_id: Option<usize>,
-        team: String,
-        message: Option<String>,
-    },
-
-    /// SCADA system restored
-    ScadaRestored {
-        building_id: Option<usize>,
-    },
-
-    /// Emergency traffic stop activated
-    EmergencyStop {
-        reason: String,
-    },
-
-    /// Emergency stop deactivated
-    EmergencyStopDeactivated,
-
-    /// Danger mode activated
-    DangerModeActivated {
-        reason: String,
-    },
-
-    /// Danger mode deactivated
-    DangerModeDeactivated,
-
-    /// Custom log message
-    LogMessage {
-        level: LogLevel,
-        message: String,
-    },
-
-    /// Server connection status change
-    ConnectionStatus {
-        connected: bool,
-        error: Option<String>,
-    },
-}
-
-/// Log severity level
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum LogLevel {
-    Info,
-    Warning,
-    Error,
-    Critical,
-}
+pub use city_events::{GameEvent, GameState, LogLevel};
 
 /// Event receiver that can be polled in the main game loop
 pub struct EventReceiver {
@@ -169,4 +96,36 @@ mod tests {
             _ => panic!("Wrong event type"),
         }
     }
+
+    #[test]
+    fn test_state_snapshot_parsing() {
+        let json = r#"{
+            "type": "state_snapshot",
+            "state": {
+                "barrier_broken": true,
+                "led_broken": null,
+                "danger_mode_active": true,
+                "emergency_stop_active": false,
+                "scada_compromised_buildings": { "5": true, "7": false }
+            }
+        }"#;
+
+        let event: GameEvent = serde_json::from_str(json).unwrap();
+        match event {
+            GameEvent::StateSnapshot { state } => {
+                assert!(state.barrier_broken);
+                assert_eq!(state.scada_compromised_buildings.get(&5), Some(&true));
+                assert_eq!(state.scada_compromised_buildings.get(&7), Some(&false));
+                assert!(state.danger_mode_active);
+                assert!(state.team_scores.is_empty());
+            }
+            _ => panic!("Wrong event type"),
+        }
+    }
+
+    #[test]
+    fn test_game_reset_parsing() {
+        let event: GameEvent = serde_json::from_str(r#"{"type": "game_reset"}"#).unwrap();
+        assert!(matches!(event, GameEvent::GameReset));
+    }
 }
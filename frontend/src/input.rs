@@ -37,6 +37,11 @@ impl WindowState {
         }
     }
 
+    /// Gets the stored window width and height in pixels
+    pub fn size(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+
     /// Checks if window was resized beyond the given threshold
     ///
     /// Compares current screen dimensions with stored dimensions.
@@ -87,32 +92,36 @@ impl WindowState {
 ///
 /// # Arguments
 /// * `all_lights_red` - Current state of emergency stop mode
-/// * `danger_mode` - Current state of danger warning display
+/// * `escape_resets` - Whether Escape resets the modes; false while it is
+///   used to deselect the inspected entity instead
 ///
 /// # Returns
-/// Tuple of (new_all_lights_red, new_danger_mode, toggle_all_scada, reset_scada, toggle_barrier) with updated states
+/// Tuple of (new_all_lights_red, toggle_danger, toggle_all_scada, reset, toggle_barrier)
+/// toggle_danger is true if the danger warning should be shown or removed
 /// toggle_all_scada is true if all SCADA systems should be toggled
-/// reset_scada is true if SCADA should be reset to working state
+/// reset is true if SCADA and the danger warning should be reset
 /// toggle_barrier is true if barrier gate should be toggled
 ///
 /// # Keyboard Controls
 /// - **Enter**: Toggle all traffic lights to red (emergency stop)
-/// - **Escape**: Reset all modes to normal (including SCADA)
+/// - **Escape**: Reset all modes to normal (including SCADA), unless an entity
+///   is selected
 /// - **Left Shift**: Toggle danger warning on LED display
-/// - **S**: Toggle SCADA broken state for ALL buildings with SCADA
+/// - **S**: Toggle SCADA broken state for ALL buildings with SCADA (not with Ctrl)
 /// - **B**: Toggle barrier gate (open/close)
 ///
 /// # Example
 /// ```
-/// let (all_lights_red, danger_mode, toggle_scada, reset_scada, toggle_barrier) = handle_input(false, false);
+/// let (all_lights_red, toggle_danger, toggle_scada, reset, toggle_barrier) =
+///     handle_input(false, true);
 /// // User pressed 'B'
 /// // toggle_barrier is true
 /// ```
-pub fn handle_input(all_lights_red: bool, danger_mode: bool) -> (bool, bool, bool, bool, bool) {
+pub fn handle_input(all_lights_red: bool, escape_resets: bool) -> (bool, bool, bool, bool, bool) {
     let mut new_all_lights_red = all_lights_red;
-    let mut new_danger_mode = danger_mode;
+    let mut toggle_danger = false;
     let mut toggle_all_scada = false;
-    let mut reset_scada = false;
+    let mut reset = false;
     let mut toggle_barrier = false;
 
     // Toggle all traffic lights to red (emergency stop)
@@ -121,19 +130,18 @@ pub fn handle_input(all_lights_red: bool, danger_mode: bool) -> (bool, bool, boo
     }
 
     // Reset all modes to normal (including SCADA)
-    if is_key_pressed(KeyCode::Escape) {
+    if escape_resets && is_key_pressed(KeyCode::Escape) {
         new_all_lights_red = false;
-        new_danger_mode = false;
-        reset_scada = true;
+        reset = true;
     }
 
     // Toggle danger warning on LED display
     if is_key_pressed(KeyCode::LeftShift) {
-        new_danger_mode = !new_danger_mode;
+        toggle_danger = true;
     }
 
-    // Toggle all SCADA systems
-    if is_key_pressed(KeyCode::S) {
+    // Toggle all SCADA systems (Ctrl+S is step mode)
+    if is_key_pressed(KeyCode::S) && !ctrl_down() {
         toggle_all_scada = true;
     }
 
@@ -142,5 +150,10 @@ pub fn handle_input(all_lights_red: bool, danger_mode: bool) -> (bool, bool, boo
         toggle_barrier = true;
     }
 
-    (new_all_lights_red, new_danger_mode, toggle_all_scada, reset_scada, toggle_barrier)
+    (new_all_lights_red, toggle_danger, toggle_all_scada, reset, toggle_barrier)
+}
+
+/// Whether either Ctrl key is held, for Ctrl+key shortcuts
+pub fn ctrl_down() -> bool {
+    is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
 }
@@ -1,26 +1,106 @@
 //! Intersection structure and generation
 //!
 //! This module defines:
-//! - Intersection struct: Road crossings with traffic lights
+//! - Intersection struct: Road crossings with traffic lights, stop signs or
+//!   a roundabout
+//! - IntersectionBuilder: Builder for intersections of any type
+//! - Queue lengths per approach, and adaptive light timing based on them
+//! - The first-come-first-served queue of cars at all-way stops
 //! - City road network topology (3x2 grid)
+//! - Power districts the grid is divided into
 //! - Intersection generation logic
 
-use crate::constants::road_network::{HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS};
+use crate::constants::road_network::{
+    DISTRICT_NAMES, HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS,
+};
 use crate::constants::rendering::INTERSECTION_SIZE;
+use crate::constants::traffic_light::{
+    ALL_WAY_STOP_WAIT, QUEUE_LABEL_DISTANCE, QUEUE_LABEL_FONT_SIZE, STOP_SIGN_RADIUS,
+};
 use crate::models::Direction;
-use crate::traffic_light::IntersectionTrafficLight;
+use crate::traffic_light::{
+    adaptive_green_durations, IntersectionTrafficLight, LightState, LightTiming,
+};
+use crate::screen::{screen_height, screen_width};
 use macroquad::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+// ============================================================================
+// Light Override
+// ============================================================================
+
+/// Manual override forcing an intersection's lights to a fixed state
+///
+/// While an override is set the light cycle is paused; clearing it
+/// (back to automatic) resumes the cycle where it left off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LightOverride {
+    /// All directions red
+    AllRed,
+    /// All directions green
+    AllGreen,
+}
+
+impl LightOverride {
+    /// Light state shown in every direction under this override
+    pub fn light_state(self) -> LightState {
+        match self {
+            LightOverride::AllRed => LightState::default_red(),
+            LightOverride::AllGreen => LightState::default_green(),
+        }
+    }
+
+    /// Short label drawn above an overridden intersection
+    pub fn label(self) -> &'static str {
+        match self {
+            LightOverride::AllRed => "ALL RED",
+            LightOverride::AllGreen => "ALL GREEN",
+        }
+    }
+
+    /// Next override in the click cycle: Auto → AllRed → AllGreen → Auto
+    ///
+    /// `None` stands for automatic operation.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(LightOverride::AllRed),
+            Some(LightOverride::AllRed) => Some(LightOverride::AllGreen),
+            Some(LightOverride::AllGreen) => None,
+        }
+    }
+}
 
 // ============================================================================
 // Intersection Model
 // ============================================================================
 
-/// Represents a road intersection with traffic lights
+/// How traffic through an intersection is controlled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum IntersectionType {
+    /// Traffic lights let each road through in turn
+    #[default]
+    TrafficLight,
+
+    /// Stop signs on every approach: each car stops, and cars go one at a
+    /// time in the order they arrived
+    AllWayStop,
+
+    /// Cars drive around a central island and give way to the cars already
+    /// circling it
+    Roundabout {
+        /// Radius of the circle cars drive along, in pixels; larger than
+        /// `LANE_OFFSET` so the lanes meet it
+        radius: f32,
+    },
+}
+
+/// Represents a road intersection with traffic lights, stop signs or a
+/// roundabout
 ///
 /// Intersections are positioned at grid points where roads cross.
 /// Each intersection manages its own traffic light controller and connections to roads.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Intersection {
     /// Horizontal position as percentage of screen width
     pub x_percent: f32,
@@ -34,8 +114,37 @@ pub struct Intersection {
     /// Unified traffic light controller for this intersection
     pub light: Option<IntersectionTrafficLight>,
 
+    /// Manual light override (None = automatic cycle)
+    pub override_state: Option<LightOverride>,
+
+    /// Power district the intersection belongs to, if any
+    pub district: Option<String>,
+
+    /// Whether the lights have power (false during a district power outage)
+    pub powered: bool,
+
     /// Roads connected to this intersection (direction -> road_id)
     pub connected_roads: HashMap<Direction, usize>,
+
+    /// Cars queued before the intersection by direction of travel, as last
+    /// measured
+    queue_lengths: HashMap<Direction, usize>,
+
+    /// Whether green durations follow the queue lengths
+    adaptive: bool,
+
+    /// Whether traffic is controlled by lights or stop signs
+    pub intersection_type: IntersectionType,
+
+    /// IDs of the cars standing at the stop lines of an all-way stop, in
+    /// the order they arrived
+    waiting_cars: VecDeque<usize>,
+
+    /// Seconds each waiting car has stood at its stop line
+    waited: HashMap<usize, f32>,
+
+    /// Car let go from the front of the queue that has not entered yet
+    released: Option<usize>,
 }
 
 impl Intersection {
@@ -51,10 +160,57 @@ impl Intersection {
             y_percent,
             id,
             light: None,
+            override_state: None,
+            district: None,
+            powered: true,
             connected_roads: HashMap::new(),
+            queue_lengths: HashMap::new(),
+            adaptive: false,
+            intersection_type: IntersectionType::TrafficLight,
+            waiting_cars: VecDeque::new(),
+            waited: HashMap::new(),
+            released: None,
         }
     }
 
+    /// Creates a builder for an intersection
+    pub fn builder() -> IntersectionBuilder {
+        IntersectionBuilder::new()
+    }
+
+    /// Sets the power district of this intersection
+    pub fn with_district(mut self, district: impl Into<String>) -> Self {
+        self.district = Some(district.into());
+        self
+    }
+
+    /// Sets the timing of this intersection's light cycle (builder style)
+    pub fn with_timing(mut self, timing: LightTiming) -> Self {
+        self.set_timing(timing);
+        self
+    }
+
+    /// Sets how traffic through this intersection is controlled (builder style)
+    ///
+    /// All-way stops and roundabouts have no traffic light, so turning an
+    /// intersection into one removes its light. Traffic light intersections
+    /// without a light get one with the default timing, green first for
+    /// vertical traffic at even IDs.
+    pub fn with_type(mut self, intersection_type: IntersectionType) -> Self {
+        self.intersection_type = intersection_type;
+        match intersection_type {
+            IntersectionType::AllWayStop | IntersectionType::Roundabout { .. } => {
+                self.clear_light()
+            }
+            IntersectionType::TrafficLight if !self.has_light() => {
+                let (x, y, id) = (self.x_percent, self.y_percent, self.id);
+                self.set_light(IntersectionTrafficLight::new(x, y, id, id.is_multiple_of(2)));
+            }
+            IntersectionType::TrafficLight => {}
+        }
+        self
+    }
+
     /// Converts the percentage-based x position to absolute pixel coordinates
     ///
     /// # Returns
@@ -79,11 +235,177 @@ impl Intersection {
         self.light = Some(light);
     }
 
+    /// Sets the timing of the traffic light cycle
+    ///
+    /// Has no effect on an intersection without a traffic light.
+    ///
+    /// # Arguments
+    /// * `timing` - State durations for the light
+    pub fn set_timing(&mut self, timing: LightTiming) {
+        if let Some(light) = &mut self.light {
+            light.set_timing(timing);
+        }
+    }
+
+    /// Restarts the light cycle at a point in it
+    ///
+    /// Has no effect on an intersection without a traffic light.
+    ///
+    /// # Arguments
+    /// * `elapsed` - Seconds since vertical traffic turned green, see
+    ///   `IntersectionTrafficLight::set_cycle_position`
+    pub fn set_cycle_position(&mut self, elapsed: f32) {
+        if let Some(light) = &mut self.light {
+            light.set_cycle_position(elapsed);
+        }
+    }
+
+    /// Sets or clears the manual light override
+    ///
+    /// # Arguments
+    /// * `override_state` - Forced state, or None to resume the automatic cycle
+    pub fn set_override(&mut self, override_state: Option<LightOverride>) {
+        self.override_state = override_state;
+    }
+
+    /// Turns the lights' power off or back on
+    ///
+    /// # Arguments
+    /// * `powered` - False during a power outage
+    pub fn set_powered(&mut self, powered: bool) {
+        self.powered = powered;
+    }
+
+    /// Cars queued before the intersection by direction of travel
+    ///
+    /// Directions without a queue are missing.
+    pub fn queue_lengths(&self) -> &HashMap<Direction, usize> {
+        &self.queue_lengths
+    }
+
+    /// Records the cars queued before the intersection
+    ///
+    /// With adaptive timing on, the next green phase of each direction is
+    /// retimed for the new queues.
+    ///
+    /// # Arguments
+    /// * `queue_lengths` - Queued cars by direction of travel
+    pub fn set_queue_lengths(&mut self, queue_lengths: HashMap<Direction, usize>) {
+        self.queue_lengths = queue_lengths;
+        if !self.adaptive {
+            return;
+        }
+
+        let queued = |vertical: bool| -> usize {
+            self.queue_lengths
+                .iter()
+                .filter(|(direction, _)| direction.is_vertical() == vertical)
+                .map(|(_, length)| length)
+                .sum()
+        };
+        let (vertical, horizontal) = (queued(true), queued(false));
+        if let Some(light) = &mut self.light {
+            let (vertical_green, horizontal_green) =
+                adaptive_green_durations(light.timing.green, vertical, horizontal);
+            light.set_green_durations(vertical_green, horizontal_green);
+        }
+    }
+
+    /// Turns adaptive light timing on or off
+    ///
+    /// Turning it off restores the configured green duration.
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.adaptive = adaptive;
+        if let Some(light) = &mut self.light {
+            light.set_green_durations(light.timing.green, light.timing.green);
+        }
+    }
+
+    /// Turns the light green for an emergency vehicle approaching it
+    ///
+    /// Manual overrides and power outages take precedence, as they do over
+    /// the normal cycle.
+    ///
+    /// # Arguments
+    /// * `direction` - Direction the emergency vehicle is travelling
+    pub fn preempt_for_emergency(&mut self, direction: Direction) {
+        if self.override_state.is_some() || !self.powered {
+            return;
+        }
+        if let Some(light) = &mut self.light {
+            light.preempt(direction);
+        }
+    }
+
+    /// Checks if cars stop at this intersection and take turns
+    pub fn is_all_way_stop(&self) -> bool {
+        self.intersection_type == IntersectionType::AllWayStop
+    }
+
+    /// Radius of the circle cars drive around, if this is a roundabout
+    pub fn roundabout_radius(&self) -> Option<f32> {
+        match self.intersection_type {
+            IntersectionType::Roundabout { radius } => Some(radius),
+            IntersectionType::TrafficLight | IntersectionType::AllWayStop => None,
+        }
+    }
+
+    /// Moves the all-way stop queue along for one frame
+    ///
+    /// Cars that start standing at a stop line join the back of the queue.
+    /// The car at the front is let go once it has waited
+    /// `ALL_WAY_STOP_WAIT` seconds and the car let go before it is no longer
+    /// approaching.
+    ///
+    /// # Arguments
+    /// * `standing` - IDs of the cars standing at the stop lines
+    /// * `approaching` - IDs of all cars driving up to the stop lines,
+    ///   standing or not
+    /// * `dt` - Delta time in seconds
+    pub fn update_stop_queue(&mut self, standing: &[usize], approaching: &[usize], dt: f32) {
+        if self.released.is_some_and(|id| !approaching.contains(&id)) {
+            self.released = None;
+        }
+
+        self.waiting_cars.retain(|id| standing.contains(id));
+        self.waited.retain(|id, _| standing.contains(id));
+        for &id in standing {
+            if Some(id) != self.released && !self.waited.contains_key(&id) {
+                self.waiting_cars.push_back(id);
+                self.waited.insert(id, 0.0);
+            }
+        }
+        for waited in self.waited.values_mut() {
+            *waited += dt;
+        }
+
+        let front_ready = self
+            .waiting_cars
+            .front()
+            .is_some_and(|id| self.waited[id] >= ALL_WAY_STOP_WAIT);
+        if self.released.is_none() && front_ready {
+            self.released = self.waiting_cars.pop_front();
+        }
+    }
+
+    /// Checks if a car has had its turn at this all-way stop and may enter
+    ///
+    /// # Arguments
+    /// * `car_id` - ID of the car
+    pub fn may_proceed(&self, car_id: usize) -> bool {
+        self.released == Some(car_id)
+    }
+
     /// Updates the traffic light at this intersection
     ///
+    /// The light cycle is paused while an override is set or the power is out.
+    ///
     /// # Arguments
     /// * `dt` - Delta time in seconds
     pub fn update_lights(&mut self, dt: f32) {
+        if self.override_state.is_some() || !self.powered {
+            return;
+        }
         if let Some(light) = &mut self.light {
             light.update(dt);
         }
@@ -95,11 +417,91 @@ impl Intersection {
     /// - Vertical lights (up/down): top-right corner
     /// - Horizontal lights (left/right): bottom-left corner
     ///
+    /// Overridden intersections show the forced state and a label above them.
+    /// Without power the lights go dark and flash yellow in every direction.
+    ///
     /// # Arguments
     /// * `force_red` - If true, forces all lights to show red (emergency mode)
-    pub fn render_lights(&self, force_red: bool) {
-        if let Some(light) = &self.light {
-            light.render(force_red);
+    /// * `time` - Current simulation time, for flashing unpowered lights
+    /// * `glow` - Opacity of the glow around lit lamps (0.0 in daylight)
+    pub fn render_lights(&self, force_red: bool, time: f64, glow: f32) {
+        if self.is_all_way_stop() {
+            self.render_stop_signs();
+        }
+        let Some(light) = &self.light else {
+            return;
+        };
+
+        if !self.powered && !force_red {
+            light.render_unpowered(time, glow);
+            return;
+        }
+
+        let forced_state = if force_red {
+            Some(LightState::default_red())
+        } else {
+            self.override_state.map(LightOverride::light_state)
+        };
+        light.render(forced_state, glow);
+
+        if let Some(override_state) = self.override_state {
+            self.render_override_label(override_state);
+        }
+    }
+
+    /// Draws a stop sign at each corner, on the right of the lane leading
+    /// in past it
+    fn render_stop_signs(&self) {
+        let reach = INTERSECTION_SIZE + STOP_SIGN_RADIUS;
+        for (dx, dy) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+            let x = self.x() + dx * reach;
+            let y = self.y() + dy * reach;
+            draw_poly(x, y, 8, STOP_SIGN_RADIUS, 22.5, RED);
+            draw_poly_lines(x, y, 8, STOP_SIGN_RADIUS, 22.5, 1.0, WHITE);
+        }
+    }
+
+    /// Draws the override label centered above the intersection
+    fn render_override_label(&self, override_state: LightOverride) {
+        let text = override_state.label();
+        let color = match override_state {
+            LightOverride::AllRed => RED,
+            LightOverride::AllGreen => GREEN,
+        };
+
+        let font_size = 16.0;
+        let dimensions = measure_text(text, None, font_size as u16, 1.0);
+        let x = self.x() - dimensions.width / 2.0;
+        let y = self.y() - INTERSECTION_SIZE - 6.0;
+
+        draw_rectangle(
+            x - 3.0,
+            y - dimensions.offset_y - 2.0,
+            dimensions.width + 6.0,
+            dimensions.height + 4.0,
+            Color::new(0.0, 0.0, 0.0, 0.7),
+        );
+        draw_text(text, x, y, font_size, color);
+    }
+
+    /// Draws the queue lengths on the side each queue approaches from
+    pub fn render_queue_lengths(&self) {
+        for (&direction, &length) in &self.queue_lengths {
+            let text = length.to_string();
+            let dimensions = measure_text(&text, None, QUEUE_LABEL_FONT_SIZE as u16, 1.0);
+            // Queues stand behind the intersection, against their direction of travel
+            let (dx, dy) = direction.to_vector();
+            let x = self.x() - dx * QUEUE_LABEL_DISTANCE - dimensions.width / 2.0;
+            let y = self.y() - dy * QUEUE_LABEL_DISTANCE + dimensions.offset_y / 2.0;
+
+            draw_rectangle(
+                x - 3.0,
+                y - dimensions.offset_y - 2.0,
+                dimensions.width + 6.0,
+                dimensions.height + 4.0,
+                Color::new(0.0, 0.0, 0.0, 0.7),
+            );
+            draw_text(&text, x, y, QUEUE_LABEL_FONT_SIZE, YELLOW);
         }
     }
 
@@ -152,18 +554,93 @@ impl Intersection {
 
     /// Gets the traffic light state for a given direction
     ///
+    /// An override takes precedence over the light's own cycle. Lights
+    /// without power show no state, so cars treat the intersection as an
+    /// all-way yield and only wait for traffic already crossing it.
+    ///
     /// # Arguments
     /// * `direction` - Direction of travel (Down/Up for vertical, Left/Right for horizontal)
     ///
     /// # Returns
-    /// Traffic light state: 0 = red, 1 = yellow, 2 = green
-    pub fn get_light_state_for_direction(&self, direction: Direction) -> u8 {
-        if let Some(light) = &self.light {
-            light.get_state_for_direction(direction)
-        } else {
-            // Default to red if no light found
-            0
+    /// Light state for that direction, or None if the intersection has no
+    /// working light
+    pub fn get_light_state_for_direction(&self, direction: Direction) -> Option<LightState> {
+        if !self.powered {
+            return None;
+        }
+        self.light.as_ref().map(|light| match self.override_state {
+            Some(override_state) => override_state.light_state(),
+            None => light.get_state_for_direction(direction),
+        })
+    }
+}
+
+// ============================================================================
+// Intersection Builder
+// ============================================================================
+
+/// Builder for creating intersections
+///
+/// Traffic light intersections get a light with the default timing that
+/// starts green for vertical traffic at even IDs and for horizontal traffic at
+/// odd IDs, so neighbouring lights are staggered.
+pub struct IntersectionBuilder {
+    x_percent: Option<f32>,
+    y_percent: Option<f32>,
+    id: Option<usize>,
+    district: Option<String>,
+    intersection_type: Option<IntersectionType>,
+}
+
+impl IntersectionBuilder {
+    /// Creates a new IntersectionBuilder
+    fn new() -> Self {
+        Self {
+            x_percent: None,
+            y_percent: None,
+            id: None,
+            district: None,
+            intersection_type: None,
+        }
+    }
+
+    /// Sets the position as percentages of the screen (default: the center)
+    pub fn position(mut self, x_percent: f32, y_percent: f32) -> Self {
+        self.x_percent = Some(x_percent);
+        self.y_percent = Some(y_percent);
+        self
+    }
+
+    /// Sets the unique identifier (default: 0)
+    pub fn id(mut self, id: usize) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the power district
+    pub fn district(mut self, district: impl Into<String>) -> Self {
+        self.district = Some(district.into());
+        self
+    }
+
+    /// Sets how traffic is controlled (default: traffic lights)
+    pub fn intersection_type(mut self, intersection_type: IntersectionType) -> Self {
+        self.intersection_type = Some(intersection_type);
+        self
+    }
+
+    /// Builds the intersection
+    pub fn build(self) -> Intersection {
+        let x_percent = self.x_percent.unwrap_or(0.5);
+        let y_percent = self.y_percent.unwrap_or(0.5);
+        let id = self.id.unwrap_or(0);
+
+        let mut intersection = Intersection::new(x_percent, y_percent, id)
+            .with_type(self.intersection_type.unwrap_or_default());
+        if let Some(district) = self.district {
+            intersection = intersection.with_district(district);
         }
+        intersection
     }
 }
 
@@ -191,6 +668,25 @@ pub fn get_road_positions() -> (Vec<f32>, Vec<f32>) {
     (vertical_positions, horizontal_positions)
 }
 
+// ============================================================================
+// Power Districts
+// ============================================================================
+
+/// Returns the power district containing a horizontal position
+///
+/// Districts are the strips between vertical roads; a position exactly on a
+/// road belongs to the district east of it.
+///
+/// # Arguments
+/// * `x_percent` - X position as percentage of screen width (0.0-1.0)
+pub fn district_at(x_percent: f32) -> &'static str {
+    let index = VERTICAL_ROAD_POSITIONS[1..]
+        .iter()
+        .take_while(|&&road| x_percent >= road)
+        .count();
+    DISTRICT_NAMES[index]
+}
+
 // ============================================================================
 // Intersection Generation
 // ============================================================================
@@ -201,15 +697,12 @@ pub fn get_road_positions() -> (Vec<f32>, Vec<f32>) {
 /// Each intersection gets:
 /// - Unique ID (0-5)
 /// - Position as percentages (for dynamic resizing)
-/// - Staggered time offset for traffic light synchronization
+/// - One traffic light controller for both directions, starting green for
+///   vertical traffic at even IDs and for horizontal traffic at odd IDs
+/// - The power district of its vertical road
 ///
 /// # Returns
 /// Vector of 6 intersections
-///
-/// # Traffic Light Staggering
-/// Each intersection has a 1-second time offset from the previous one,
-/// preventing all lights from turning green simultaneously and creating
-/// more realistic traffic flow patterns.
 pub fn generate_intersections() -> Vec<Intersection> {
     // Store positions as percentages (0.0 to 1.0) for dynamic resizing
     let vertical_percents = VERTICAL_ROAD_POSITIONS.to_vec();
@@ -219,20 +712,14 @@ pub fn generate_intersections() -> Vec<Intersection> {
     let mut id = 0;
 
     // Create intersection at each grid point with unified traffic light
+    // Lights start vertical green at even IDs and horizontal green at odd IDs
     for &x_percent in &vertical_percents {
         for &y_percent in &horizontal_percents {
-            let mut intersection = Intersection::new(x_percent, y_percent, id);
-
-            // Create unified traffic light controller
-            // Start with vertical green for even IDs, horizontal green for odd IDs (creates staggering)
-            let light = IntersectionTrafficLight::new(
-                x_percent,
-                y_percent,
-                id,
-                id % 2 == 0, // vertical_starts_green
-            );
-
-            intersection.set_light(light);
+            let intersection = Intersection::builder()
+                .position(x_percent, y_percent)
+                .id(id)
+                .district(district_at(x_percent))
+                .build();
 
             intersections.push(intersection);
             id += 1;
@@ -241,3 +728,128 @@ pub fn generate_intersections() -> Vec<Intersection> {
 
     intersections
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signalled_intersection() -> Intersection {
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        intersection.set_light(IntersectionTrafficLight::new(0.5, 0.5, 0, true));
+        intersection
+    }
+
+    #[test]
+    fn test_generated_lights_never_let_both_directions_go() {
+        // Small steps over many cycles, with timings whose phases don't
+        // divide each other, so any drift between the directions shows up
+        for timing in [LightTiming::default(), LightTiming::new(2.3, 0.7, 4.1)] {
+            for intersection in &mut generate_intersections() {
+                intersection.set_timing(timing);
+                for step in 0..120 * 60 {
+                    intersection.update_lights(1.0 / 60.0);
+                    let vertical = intersection.get_light_state_for_direction(Direction::Down);
+                    let horizontal = intersection.get_light_state_for_direction(Direction::Right);
+                    assert!(
+                        vertical.is_some_and(|state| state.is_red())
+                            || horizontal.is_some_and(|state| state.is_red()),
+                        "intersection {} at step {}: {:?} and {:?}",
+                        intersection.id,
+                        step,
+                        vertical,
+                        horizontal
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_override_wins_over_light_cycle() {
+        let mut intersection = signalled_intersection();
+        assert!(intersection.get_light_state_for_direction(Direction::Down).unwrap().is_green());
+
+        intersection.set_override(Some(LightOverride::AllRed));
+        for direction in [Direction::Up, Direction::Down, Direction::Left, Direction::Right] {
+            assert!(intersection.get_light_state_for_direction(direction).unwrap().is_red());
+        }
+
+        intersection.set_override(Some(LightOverride::AllGreen));
+        assert!(intersection.get_light_state_for_direction(Direction::Left).unwrap().is_green());
+    }
+
+    #[test]
+    fn test_override_pauses_cycle_until_cleared() {
+        let mut intersection = signalled_intersection();
+        let before = intersection.light.as_ref().unwrap().time_in_state;
+
+        intersection.set_override(Some(LightOverride::AllGreen));
+        intersection.update_lights(1000.0);
+        assert_eq!(intersection.light.as_ref().unwrap().time_in_state, before);
+
+        intersection.set_override(None);
+        intersection.update_lights(0.5);
+        assert!(intersection.light.as_ref().unwrap().time_in_state < before);
+        assert!(intersection.get_light_state_for_direction(Direction::Down).unwrap().is_green());
+    }
+
+    #[test]
+    fn test_unpowered_lights_stop_cycling_and_signal_nothing() {
+        let mut intersection = signalled_intersection();
+        let before = intersection.light.as_ref().unwrap().time_in_state;
+
+        intersection.set_powered(false);
+        intersection.update_lights(1.0);
+        assert_eq!(intersection.light.as_ref().unwrap().time_in_state, before);
+        assert_eq!(intersection.get_light_state_for_direction(Direction::Down), None);
+
+        intersection.set_powered(true);
+        assert!(intersection.get_light_state_for_direction(Direction::Down).unwrap().is_green());
+    }
+
+    #[test]
+    fn test_all_way_stop_lets_cars_go_in_arrival_order() {
+        let mut stop = Intersection::builder()
+            .intersection_type(IntersectionType::AllWayStop)
+            .build();
+        assert!(stop.light.is_none());
+
+        // Car 7 stops first, car 3 half a second later
+        stop.update_stop_queue(&[7], &[7], 1.0);
+        stop.update_stop_queue(&[7, 3], &[7, 3], 0.5);
+        assert!(!stop.may_proceed(7), "7 has stood for only 1.5 seconds");
+        stop.update_stop_queue(&[7, 3], &[7, 3], 0.5);
+        assert!(stop.may_proceed(7));
+        assert!(!stop.may_proceed(3));
+
+        // Car 3 has waited long enough, but goes only once car 7 has entered
+        stop.update_stop_queue(&[3], &[7, 3], 2.0);
+        assert!(!stop.may_proceed(3));
+        stop.update_stop_queue(&[3], &[3], 0.1);
+        assert!(stop.may_proceed(3));
+    }
+
+    #[test]
+    fn test_districts_follow_vertical_roads() {
+        let districts: Vec<_> = generate_intersections()
+            .iter()
+            .map(|intersection| intersection.district.clone().unwrap())
+            .collect();
+        assert_eq!(districts, ["west", "west", "central", "central", "east", "east"]);
+
+        assert_eq!(district_at(0.0), "west");
+        assert_eq!(district_at(0.3), "west");
+        assert_eq!(district_at(0.6), "central");
+        assert_eq!(district_at(0.95), "east");
+    }
+
+    #[test]
+    fn test_override_cycle() {
+        let auto = None;
+        let red = LightOverride::cycle(auto);
+        assert_eq!(red, Some(LightOverride::AllRed));
+        let green = LightOverride::cycle(red);
+        assert_eq!(green, Some(LightOverride::AllGreen));
+        assert_eq!(LightOverride::cycle(green), None);
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This module contains bitmap patterns for rendering text on LED displays.
 //! Each character is represented as a 5-bit wide by 7-bit tall pattern.
+//! Letters, digits, space and common punctuation have patterns; any other
+//! character is drawn as a hollow box.
 //!
 //! # Pattern Format
 //!
@@ -115,9 +117,93 @@ pub fn get_led_char_pattern(c: char) -> [u8; 7] {
         'Z' => [
             0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
         ],
+        '0' => [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+        '1' => [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        '2' => [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '3' => [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+        '4' => [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+        '5' => [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+        '6' => [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+        '7' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+        '8' => [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        '9' => [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
         ' ' => [
             0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
         ],
+        '!' => [
+            0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100,
+        ],
+        '.' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        ',' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000,
+        ],
+        '?' => [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        '+' => [
+            0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000,
+        ],
+        '=' => [
+            0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000,
+        ],
+        '(' => [
+            0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010,
+        ],
+        ')' => [
+            0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000,
+        ],
+        '/' => [
+            0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000,
+        ],
+        '\\' => [
+            0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000,
+        ],
+        '@' => [
+            0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110,
+        ],
+        '#' => [
+            0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010,
+        ],
+        '%' => [
+            0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011,
+        ],
+        ':' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+        ';' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000,
+        ],
+        '<' => [
+            0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010,
+        ],
+        '>' => [
+            0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000,
+        ],
         _ => [
             // Default box pattern for unknown characters
             0b11111, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11111,
@@ -135,7 +221,27 @@ pub fn get_led_char_pattern(c: char) -> [u8; 7] {
 pub fn has_pattern(c: char) -> bool {
     matches!(
         c.to_ascii_uppercase(),
-        'A'..='Z' | ' '
+        'A'..='Z'
+            | '0'..='9'
+            | ' '
+            | '!'
+            | '.'
+            | ','
+            | '?'
+            | '-'
+            | '+'
+            | '='
+            | '('
+            | ')'
+            | '/'
+            | '\\'
+            | '@'
+            | '#'
+            | '%'
+            | ':'
+            | ';'
+            | '<'
+            | '>'
     )
 }
 
@@ -171,7 +277,7 @@ mod tests {
 
     #[test]
     fn test_unknown_char_returns_box() {
-        let pattern = get_led_char_pattern('!');
+        let pattern = get_led_char_pattern('~');
         assert_eq!(pattern[0], 0b11111); // Full top row
         assert_eq!(pattern[6], 0b11111); // Full bottom row
     }
@@ -181,7 +287,35 @@ mod tests {
         assert!(has_pattern('A'));
         assert!(has_pattern('z'));
         assert!(has_pattern(' '));
-        assert!(!has_pattern('!'));
-        assert!(!has_pattern('1'));
+        assert!(has_pattern('!'));
+        assert!(has_pattern('#'));
+        assert!(has_pattern('1'));
+        assert!(!has_pattern('~'));
+        assert!(!has_pattern('\''));
+    }
+
+    #[test]
+    fn test_all_supported_chars_have_patterns() {
+        let supported: Vec<char> = ('A'..='Z')
+            .chain('0'..='9')
+            .chain(" !.,?-+=()/\\@#%:;<>".chars())
+            .collect();
+        assert_eq!(supported.len(), 55);
+
+        let unknown = get_led_char_pattern('~');
+        for c in supported {
+            assert!(has_pattern(c), "{:?}", c);
+            let pattern = get_led_char_pattern(c);
+            assert!(
+                pattern.iter().all(|&row| row < 1 << LED_CHAR_WIDTH),
+                "{:?} is wider than {} dots",
+                c,
+                LED_CHAR_WIDTH
+            );
+            if c != ' ' {
+                assert!(pattern.iter().any(|&row| row != 0), "{:?} is blank", c);
+                assert_ne!(pattern, unknown, "{:?} uses the unknown box", c);
+            }
+        }
     }
 }
@@ -1,10 +1,29 @@
 //! LED Display as a BlockObject
 //!
 //! This module defines LED displays that can be placed in any block.
+//!
+//! A display shows its own text unless alerts are raised or messages are
+//! queued on it:
+//! - LEDPriority: How urgent an alert is, and how it is shown
+//! - LEDAlert: Standing text, either sticky or expiring after a duration
+//! - The most urgent alerts preempt the rest; alerts of equal priority
+//!   take turns every `LED_MESSAGE_ROTATE_INTERVAL` seconds, or once a
+//!   scrolling alert has scrolled through when that takes longer
+//! - LedMessage: One-off text, such as a game event's, shown once after the
+//!   messages queued before it and then dropped; messages wait while an
+//!   alert is shown
+//! - Each alert or message starts scrolling from its first character when
+//!   its turn comes, so none is cut off partway through
+//! - Once nothing is raised or queued, the display returns to its own text
 
 use crate::block::{Block, BlockObject};
-use crate::rendering::led_display::draw_led_display_at;
+use crate::constants::day_night::{GLOW_ALPHA, LED_GLOW_WIDTH};
+use crate::constants::led::{LED_MESSAGE_ROTATE_INTERVAL, LED_STATIC_MESSAGE_DURATION};
+use crate::rendering::draw_rectangle_glow;
+use crate::rendering::led_display::{draw_led_display_at, scroll_duration};
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 /// Display mode for LED text
 #[derive(Clone, Debug)]
@@ -15,6 +34,9 @@ pub enum LEDDisplayMode {
     Scrolling,
     /// Flashing text (3 flashes per second)
     Flashing,
+    /// Malfunctioning display: scrolling text with garbled characters and
+    /// static noise, always in the red theme
+    Broken,
 }
 
 /// Color theme for LED display
@@ -60,12 +82,100 @@ impl LEDColorTheme {
     }
 }
 
+/// Urgency of an LED alert, lowest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LEDPriority {
+    /// Scrolls in the display's own colors
+    Info,
+    /// Scrolls in amber
+    Warning,
+    /// Flashes in red, e.g. "DANGER" or "EMERGENCY STOP"
+    Critical,
+}
+
+impl LEDPriority {
+    /// Mode and colors an alert of this priority is shown with
+    ///
+    /// # Arguments
+    /// * `theme` - The display's own theme, used for info alerts
+    fn presentation(self, theme: &LEDColorTheme) -> (LEDDisplayMode, LEDColorTheme) {
+        match self {
+            LEDPriority::Info => (LEDDisplayMode::Scrolling, theme.clone()),
+            LEDPriority::Warning => (LEDDisplayMode::Scrolling, LEDColorTheme::amber()),
+            LEDPriority::Critical => (LEDDisplayMode::Flashing, LEDColorTheme::red()),
+        }
+    }
+}
+
+/// Alert raised on an LED display
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LEDAlert {
+    /// Text to show
+    pub text: String,
+
+    /// Urgency; only the most urgent alerts are shown
+    pub priority: LEDPriority,
+
+    /// Seconds until the alert expires, or None for a sticky alert that
+    /// stays until removed
+    pub remaining: Option<f32>,
+}
+
+/// Message shown once on an LED display, after those queued before it
+#[derive(Clone, Debug)]
+pub struct LedMessage {
+    /// Text to show
+    pub text: String,
+
+    /// Seconds to show the message, or None to show it until it has
+    /// scrolled through once (`LED_STATIC_MESSAGE_DURATION` for static and
+    /// flashing messages)
+    pub duration_secs: Option<f32>,
+
+    /// How the message is shown, in the display's own colors
+    pub mode: LEDDisplayMode,
+}
+
+impl LedMessage {
+    /// Creates a message shown until it has scrolled through once
+    ///
+    /// # Arguments
+    /// * `text` - Text to show
+    /// * `mode` - How the message is shown
+    pub fn new(text: impl Into<String>, mode: LEDDisplayMode) -> Self {
+        Self {
+            text: text.into(),
+            duration_secs: None,
+            mode,
+        }
+    }
+
+    /// Sets how many seconds the message is shown
+    pub fn with_duration(mut self, duration_secs: f32) -> Self {
+        self.duration_secs = Some(duration_secs);
+        self
+    }
+
+    /// Seconds the message is shown before the next one
+    fn length(&self) -> f32 {
+        match (self.duration_secs, &self.mode) {
+            (Some(duration_secs), _) => duration_secs,
+            (None, LEDDisplayMode::Scrolling | LEDDisplayMode::Broken) => {
+                scroll_duration(&self.text)
+            }
+            (None, LEDDisplayMode::Static | LEDDisplayMode::Flashing) => {
+                LED_STATIC_MESSAGE_DURATION
+            }
+        }
+    }
+}
+
 /// LED Display object that can be placed in blocks
 pub struct LEDDisplay {
     /// Text to display
     pub text: String,
 
-    /// Display mode (static, scrolling, flashing)
+    /// Display mode (static, scrolling, flashing, broken)
     pub mode: LEDDisplayMode,
 
     /// Color theme
@@ -78,6 +188,24 @@ pub struct LEDDisplay {
     /// Size as fraction of block size (0.0-1.0)
     pub width_scale: f32,
     pub height_scale: f32,
+
+    /// Raised alerts in the order they were raised
+    alerts: Vec<LEDAlert>,
+
+    /// Turn of the alert shown among those of the highest priority
+    rotation: usize,
+
+    /// Seconds the current alert has been shown
+    rotation_timer: f32,
+
+    /// Text of the alert shown as of the last update
+    shown: Option<String>,
+
+    /// Messages waiting to be shown, the one being shown first
+    message_queue: VecDeque<LedMessage>,
+
+    /// Seconds the first queued message has been shown
+    message_timer: f32,
 }
 
 impl LEDDisplay {
@@ -97,6 +225,12 @@ impl LEDDisplay {
             y_offset_percent: 0.3,  // 30% from top
             width_scale: 0.8,       // 80% of block width
             height_scale: 0.4,      // 40% of block height
+            alerts: Vec::new(),
+            rotation: 0,
+            rotation_timer: 0.0,
+            shown: None,
+            message_queue: VecDeque::new(),
+            message_timer: 0.0,
         }
     }
 
@@ -113,6 +247,12 @@ impl LEDDisplay {
             y_offset_percent: 0.3,
             width_scale: 0.8,
             height_scale: 0.4,
+            alerts: Vec::new(),
+            rotation: 0,
+            rotation_timer: 0.0,
+            shown: None,
+            message_queue: VecDeque::new(),
+            message_timer: 0.0,
         }
     }
 
@@ -147,13 +287,191 @@ impl LEDDisplay {
         self.height_scale = height_scale;
         self
     }
+
+    /// Changes the text shown when no alert is raised and no message queued
+    ///
+    /// While the display is broken this is the text it garbles, such as the
+    /// attacker's message.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// Raises an alert on the display
+    ///
+    /// An alert with the same text as a raised one replaces it, so raising
+    /// it again changes its priority or restarts its duration.
+    ///
+    /// # Arguments
+    /// * `text` - Text to show
+    /// * `priority` - Urgency; more urgent alerts preempt less urgent ones
+    /// * `duration` - Seconds until the alert expires, or None to keep it
+    ///   until `remove_alert` is called
+    pub fn enqueue_alert(
+        &mut self,
+        text: impl Into<String>,
+        priority: LEDPriority,
+        duration: Option<f32>,
+    ) {
+        let text = text.into();
+        self.alerts.retain(|alert| alert.text != text);
+        self.alerts.push(LEDAlert {
+            text,
+            priority,
+            remaining: duration,
+        });
+    }
+
+    /// Removes a raised alert
+    ///
+    /// # Returns
+    /// `true` if an alert with this text was raised
+    pub fn remove_alert(&mut self, text: &str) -> bool {
+        let len = self.alerts.len();
+        self.alerts.retain(|alert| alert.text != text);
+        self.alerts.len() != len
+    }
+
+    /// Queues a message, shown once after those queued before it
+    ///
+    /// Unlike alerts, messages never replace one another: each is shown for
+    /// its duration and then dropped. While an alert is shown the messages
+    /// wait their turn.
+    pub fn enqueue(&mut self, msg: LedMessage) {
+        self.message_queue.push_back(msg);
+    }
+
+    /// Removes every alert and queued message, returning to the display's
+    /// own text
+    pub fn clear_queue(&mut self) {
+        self.alerts.clear();
+        self.message_queue.clear();
+        self.message_timer = 0.0;
+    }
+
+    /// Gets the raised alerts in the order they were raised
+    pub fn alerts(&self) -> &[LEDAlert] {
+        &self.alerts
+    }
+
+    /// Gets the queued messages, the one being shown first
+    pub fn messages(&self) -> &VecDeque<LedMessage> {
+        &self.message_queue
+    }
+
+    /// Gets whether an alert with this text is raised
+    pub fn has_alert(&self, text: &str) -> bool {
+        self.alerts.iter().any(|alert| alert.text == text)
+    }
+
+    /// Gets the alert whose turn it is, if any
+    ///
+    /// # Returns
+    /// The turn's alert among those of the highest raised priority, or None
+    /// when no alert is raised
+    pub fn current_alert(&self) -> Option<&LEDAlert> {
+        let top = self.alerts.iter().map(|alert| alert.priority).max()?;
+        let mut candidates = self.alerts.iter().filter(|alert| alert.priority == top);
+        let count = candidates.clone().count();
+        candidates.nth(self.rotation % count)
+    }
+
+    /// Gets the alert on the display, if any
+    ///
+    /// A broken display shows no alerts.
+    fn shown_alert(&self) -> Option<&LEDAlert> {
+        if matches!(self.mode, LEDDisplayMode::Broken) {
+            return None;
+        }
+        self.current_alert()
+    }
+
+    /// Seconds the current alert's turn lasts
+    ///
+    /// A scrolling alert keeps its turn until it has scrolled through once,
+    /// even if that takes longer than `LED_MESSAGE_ROTATE_INTERVAL`.
+    fn turn_length(&self) -> f32 {
+        match self.current_alert() {
+            Some(alert)
+                if matches!(
+                    alert.priority.presentation(&self.theme).0,
+                    LEDDisplayMode::Scrolling
+                ) =>
+            {
+                LED_MESSAGE_ROTATE_INTERVAL.max(scroll_duration(&alert.text))
+            }
+            _ => LED_MESSAGE_ROTATE_INTERVAL,
+        }
+    }
+
+    /// Text, mode and theme to draw
+    ///
+    /// A broken display garbles whatever it shows, and shows no alerts.
+    fn displayed(&self) -> (&str, LEDDisplayMode, LEDColorTheme) {
+        let broken = matches!(self.mode, LEDDisplayMode::Broken);
+        if let Some(alert) = self.shown_alert() {
+            let (mode, theme) = alert.priority.presentation(&self.theme);
+            return (alert.text.as_str(), mode, theme);
+        }
+        let (text, mode) = match self.message_queue.front() {
+            Some(message) if !broken => (message.text.as_str(), message.mode.clone()),
+            Some(message) => (message.text.as_str(), LEDDisplayMode::Broken),
+            None => (self.text.as_str(), self.mode.clone()),
+        };
+        let theme = match mode {
+            LEDDisplayMode::Broken => LEDColorTheme::red(),
+            _ => self.theme.clone(),
+        };
+        (text, mode, theme)
+    }
 }
 
 impl BlockObject for LEDDisplay {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
 
+    /// Expires timed alerts, rotates between equally urgent ones, and moves
+    /// on to the next queued message once the shown one is done
+    fn update(&mut self, dt: f32) {
+        for alert in &mut self.alerts {
+            if let Some(remaining) = &mut alert.remaining {
+                *remaining -= dt;
+            }
+        }
+        self.alerts
+            .retain(|alert| alert.remaining.is_none_or(|remaining| remaining > 0.0));
+
+        // An alert that was just raised, or came up because the shown one
+        // expired or was preempted, gets its full turn
+        let current = self.current_alert().map(|alert| alert.text.clone());
+        if current != self.shown {
+            self.shown = current;
+            self.rotation_timer = 0.0;
+        }
+
+        self.rotation_timer += dt;
+        if self.rotation_timer >= self.turn_length() {
+            self.rotation_timer = 0.0;
+            self.rotation += 1;
+            self.shown = self.current_alert().map(|alert| alert.text.clone());
+        }
+
+        // Messages wait while an alert is shown
+        if self.shown_alert().is_none()
+            && let Some(message) = self.message_queue.front()
+        {
+            self.message_timer += dt;
+            if self.message_timer >= message.length() {
+                self.message_queue.pop_front();
+                self.message_timer = 0.0;
+            }
+        }
+    }
+
     fn render(&self, block: &Block, context: &crate::block::RenderContext) {
         // Calculate absolute position and size
         let block_x = block.x();
@@ -166,15 +484,32 @@ impl BlockObject for LEDDisplay {
         let display_width = block_width * self.width_scale;
         let display_height = block_height * self.height_scale;
 
-        // Override text, mode, and theme based on danger_mode
-        let (text, mode, theme) = if context.danger_mode {
-            // Danger mode: red flashing "DANGER"
-            ("DANGER", LEDDisplayMode::Flashing, LEDColorTheme::red())
-        } else {
-            // Normal mode: use configured settings
-            (self.text.as_str(), self.mode.clone(), self.theme.clone())
+        // Alerts and queued messages override the configured text, mode,
+        // and theme, and scroll from their start when their turn comes
+        let (text, mode, theme) = self.displayed();
+        let time = match (self.shown_alert(), self.message_queue.front()) {
+            (Some(_), _) => self.rotation_timer as f64,
+            (None, Some(_)) => self.message_timer as f64,
+            (None, None) => context.time,
         };
 
+        // Soft glow of the lit dots around the display after dark
+        let darkness = 1.0 - context.ambient;
+        if darkness > 0.0 {
+            let glow = Color {
+                a: GLOW_ALPHA * darkness,
+                ..theme.on_color
+            };
+            draw_rectangle_glow(
+                display_x,
+                display_y,
+                display_width,
+                display_height,
+                LED_GLOW_WIDTH,
+                glow,
+            );
+        }
+
         // Render the LED display
         draw_led_display_at(
             display_x,
@@ -184,7 +519,145 @@ impl BlockObject for LEDDisplay {
             text,
             &mode,
             &theme,
-            context.time,
+            time,
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(display: &LEDDisplay) -> &str {
+        display.displayed().0
+    }
+
+    #[test]
+    fn test_higher_priority_preempts_until_removed() {
+        let mut display = LEDDisplay::new("WELCOME");
+        display.enqueue_alert("ROADWORKS", LEDPriority::Info, None);
+        assert_eq!(shown(&display), "ROADWORKS");
+
+        display.enqueue_alert("DANGER", LEDPriority::Critical, None);
+        display.enqueue_alert("SCADA ALERT", LEDPriority::Warning, None);
+        assert_eq!(shown(&display), "DANGER");
+        assert!(matches!(display.displayed().1, LEDDisplayMode::Flashing));
+
+        // Sticky messages never expire on their own
+        display.update(LED_MESSAGE_ROTATE_INTERVAL * 10.0);
+        assert_eq!(shown(&display), "DANGER");
+
+        assert!(display.remove_alert("DANGER"));
+        assert!(!display.remove_alert("DANGER"));
+        assert_eq!(shown(&display), "SCADA ALERT");
+    }
+
+    #[test]
+    fn test_timed_alerts_expire_in_order_back_to_default_text() {
+        let mut display = LEDDisplay::new("WELCOME");
+        display.enqueue_alert("LONG", LEDPriority::Warning, Some(5.0));
+        display.enqueue_alert("SHORT", LEDPriority::Critical, Some(2.0));
+        assert_eq!(shown(&display), "SHORT");
+
+        display.update(2.5);
+        assert_eq!(shown(&display), "LONG");
+        assert!(!display.has_alert("SHORT"));
+
+        display.update(2.5);
+        assert_eq!(display.current_alert(), None);
+        assert_eq!(shown(&display), "WELCOME");
+        assert!(matches!(display.displayed().1, LEDDisplayMode::Scrolling));
+    }
+
+    #[test]
+    fn test_equal_priority_alerts_rotate() {
+        let mut display = LEDDisplay::new("WELCOME");
+        display.enqueue_alert("DANGER", LEDPriority::Critical, None);
+        display.enqueue_alert("EMERGENCY STOP", LEDPriority::Critical, None);
+        display.enqueue_alert("ROADWORKS", LEDPriority::Info, None);
+
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            seen.push(shown(&display).to_string());
+            display.update(LED_MESSAGE_ROTATE_INTERVAL);
+        }
+        assert_eq!(seen, ["DANGER", "EMERGENCY STOP", "DANGER"]);
+
+        // Raising the same text again replaces the alert
+        display.enqueue_alert("DANGER", LEDPriority::Info, None);
+        assert_eq!(shown(&display), "EMERGENCY STOP");
+    }
+
+    #[test]
+    fn test_scrolling_alert_keeps_turn_until_scrolled_through() {
+        let mut display = LEDDisplay::new("WELCOME");
+        display.enqueue_alert("ROADWORKS AHEAD", LEDPriority::Info, None);
+        display.enqueue_alert("DETOUR", LEDPriority::Info, None);
+        let scroll_time = scroll_duration("ROADWORKS AHEAD");
+        assert!(scroll_time > LED_MESSAGE_ROTATE_INTERVAL);
+
+        display.update(LED_MESSAGE_ROTATE_INTERVAL);
+        assert_eq!(shown(&display), "ROADWORKS AHEAD");
+        display.update(scroll_time - LED_MESSAGE_ROTATE_INTERVAL);
+        assert_eq!(shown(&display), "DETOUR");
+        assert_eq!(display.rotation_timer, 0.0, "next alert scrolls from its start");
+
+        display.update(scroll_duration("DETOUR"));
+        assert_eq!(shown(&display), "ROADWORKS AHEAD");
+    }
+
+    #[test]
+    fn test_queued_messages_show_once_in_order() {
+        let mut display = LEDDisplay::new("WELCOME");
+        let alert = LedMessage::new("SCADA ALERT", LEDDisplayMode::Flashing).with_duration(2.0);
+        display.enqueue(alert);
+        display.enqueue(LedMessage::new("HACKED", LEDDisplayMode::Scrolling));
+        assert_eq!(shown(&display), "SCADA ALERT");
+        assert!(matches!(display.displayed().1, LEDDisplayMode::Flashing));
+
+        // The later message waits for the shown one's duration
+        display.update(1.5);
+        assert_eq!(shown(&display), "SCADA ALERT");
+        display.update(0.5);
+        assert_eq!(shown(&display), "HACKED");
+        assert_eq!(display.message_timer, 0.0, "next message scrolls from its start");
+
+        // Without a duration, a scrolling message stays until it has scrolled through
+        display.update(scroll_duration("HACKED") - 0.1);
+        assert_eq!(shown(&display), "HACKED");
+        display.update(0.2);
+        assert!(display.messages().is_empty());
+        assert_eq!(shown(&display), "WELCOME");
+    }
+
+    #[test]
+    fn test_alerts_hold_queued_messages() {
+        let mut display = LEDDisplay::new("WELCOME");
+        display.enqueue(LedMessage::new("FIRE", LEDDisplayMode::Static).with_duration(1.0));
+        display.enqueue_alert("DANGER", LEDPriority::Critical, None);
+
+        display.update(5.0);
+        assert_eq!(shown(&display), "DANGER");
+        assert_eq!(display.messages().len(), 1);
+
+        display.remove_alert("DANGER");
+        assert_eq!(shown(&display), "FIRE");
+        display.update(1.0);
+        assert_eq!(shown(&display), "WELCOME");
+    }
+
+    #[test]
+    fn test_broken_display_ignores_alerts_and_garbles_messages() {
+        let mut display = LEDDisplay::new("HACKED");
+        display.mode = LEDDisplayMode::Broken;
+        display.enqueue_alert("DANGER", LEDPriority::Critical, None);
+        assert_eq!(shown(&display), "HACKED");
+        assert!(matches!(display.displayed().1, LEDDisplayMode::Broken));
+
+        display.enqueue(LedMessage::new("SCADA ALERT", LEDDisplayMode::Static));
+        assert_eq!(shown(&display), "SCADA ALERT");
+        assert!(matches!(display.displayed().1, LEDDisplayMode::Broken));
+        display.update(LED_STATIC_MESSAGE_DURATION);
+        assert_eq!(shown(&display), "HACKED");
+    }
+}
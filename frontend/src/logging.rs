@@ -1,30 +1,138 @@
 //! System logging and log window rendering
 //!
-//! This module provides a logging system for tracking critical system events
-//! such as SCADA failures, barrier state changes, LED display modes, and
-//! emergency traffic control activations.
+//! This module provides a logging system for tracking system events such as
+//! SCADA failures, barrier state changes, LED display modes, and emergency
+//! traffic control activations.
 //!
-//! All logged events are marked as CRITICAL and displayed in red.
+//! Entries carry a severity level (Info, Warning, Critical) and a wall-clock
+//! timestamp, and are colored by level in the log window. Entries about a
+//! team show its name in the team's color.
 
+use crate::team::Team;
+use macroquad::miniquad::date;
 use macroquad::prelude::*;
 use std::collections::VecDeque;
 
-/// A single log entry with timestamp and message
+/// Severity of a log entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl LogLevel {
+    /// Color used for the level prefix and message
+    pub fn color(&self) -> Color {
+        match self {
+            LogLevel::Info => Color::new(0.8, 0.8, 0.8, 1.0),
+            LogLevel::Warning => Color::new(1.0, 0.8, 0.0, 1.0),
+            LogLevel::Critical => Color::new(1.0, 0.2, 0.2, 1.0),
+        }
+    }
+
+    /// Prefix shown before the message
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "[INFO]",
+            LogLevel::Warning => "[WARN]",
+            LogLevel::Critical => "[CRITICAL]",
+        }
+    }
+}
+
+impl From<crate::events::LogLevel> for LogLevel {
+    fn from(level: crate::events::LogLevel) -> Self {
+        use crate::events::LogLevel as ServerLevel;
+
+        match level {
+            ServerLevel::Info => LogLevel::Info,
+            ServerLevel::Warning => LogLevel::Warning,
+            ServerLevel::Error | ServerLevel::Critical => LogLevel::Critical,
+        }
+    }
+}
+
+/// Which entries the log window shows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFilter {
+    All,
+    WarningAndAbove,
+    CriticalOnly,
+}
+
+impl LogFilter {
+    /// Next filter in the cycle All → Warning+ → Critical only → All
+    pub fn next(self) -> Self {
+        match self {
+            LogFilter::All => LogFilter::WarningAndAbove,
+            LogFilter::WarningAndAbove => LogFilter::CriticalOnly,
+            LogFilter::CriticalOnly => LogFilter::All,
+        }
+    }
+
+    /// Whether an entry of the given level passes the filter
+    pub fn allows(&self, level: LogLevel) -> bool {
+        match self {
+            LogFilter::All => true,
+            LogFilter::WarningAndAbove => level >= LogLevel::Warning,
+            LogFilter::CriticalOnly => level == LogLevel::Critical,
+        }
+    }
+
+    /// Short label shown in the title bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogFilter::All => "ALL",
+            LogFilter::WarningAndAbove => "WARNING+",
+            LogFilter::CriticalOnly => "CRITICAL",
+        }
+    }
+}
+
+/// A single log entry with timestamp, severity and message
 #[derive(Clone)]
 pub struct LogEntry {
+    /// Wall-clock time in seconds since the Unix epoch
     pub timestamp: f64,
+    pub level: LogLevel,
     pub message: String,
+
+    /// Team the entry is about, whose name is drawn in its color
+    pub team: Option<Team>,
 }
 
-/// Log window for displaying critical system events
+/// Formats a Unix timestamp as a UTC time of day (HH:MM:SS)
+fn format_timestamp(timestamp: f64) -> String {
+    let secs_of_day = (timestamp.max(0.0) as u64) % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs_of_day / 3600,
+        (secs_of_day / 60) % 60,
+        secs_of_day % 60
+    )
+}
+
+/// Log window dimensions
+const WINDOW_WIDTH: f32 = 450.0;
+const WINDOW_HEIGHT: f32 = 300.0;
+const LINE_HEIGHT: f32 = 20.0;
+
+/// Number of entries that fit between the title bar and the help text
+const VISIBLE_ROWS: usize = ((WINDOW_HEIGHT - 35.0 - 30.0) / LINE_HEIGHT) as usize + 1;
+
+/// Log window for displaying system events
 ///
-/// Displays recent log entries in a window overlay with timestamps.
-/// All entries are critical level (red) and the window can be toggled
-/// with the 'L' key.
+/// Displays recent log entries in a window overlay with timestamps, colored
+/// by level. The window can be toggled with the 'L' key, scrolled with
+/// PageUp/PageDown or the mouse wheel, and filtered by level with 'F'.
 pub struct LogWindow {
     entries: VecDeque<LogEntry>,
     max_entries: usize,
     visible: bool,
+    /// Number of matching entries hidden above the top row (0 = newest)
+    scroll_offset: usize,
+    filter: LogFilter,
 }
 
 impl LogWindow {
@@ -40,34 +148,71 @@ impl LogWindow {
             entries: VecDeque::with_capacity(max_entries),
             max_entries,
             visible: true,
+            scroll_offset: 0,
+            filter: LogFilter::All,
         }
     }
 
-    /// Logs a critical event message
-    ///
-    /// Adds a new log entry with current timestamp. If the number of entries
-    /// exceeds max_entries, the oldest entry is removed.
-    ///
-    /// # Arguments
-    /// * `message` - The message to log (automatically marked as CRITICAL)
+    /// Logs an informational message
     ///
     /// # Example
     /// ```
-    /// log_window.log("SCADA systems toggled");
-    /// log_window.log("Barrier gate opened");
+    /// log_window.log("Barrier gate closed");
     /// ```
     pub fn log(&mut self, message: impl Into<String>) {
-        let entry = LogEntry {
-            timestamp: get_time(),
+        self.log_with_level(LogLevel::Info, message);
+    }
+
+    /// Logs a warning message
+    pub fn log_warning(&mut self, message: impl Into<String>) {
+        self.log_with_level(LogLevel::Warning, message);
+    }
+
+    /// Logs a critical message
+    pub fn log_critical(&mut self, message: impl Into<String>) {
+        self.log_with_level(LogLevel::Critical, message);
+    }
+
+    /// Logs a message at the given level
+    ///
+    /// Adds a new log entry with the current wall-clock time. If the number
+    /// of entries exceeds max_entries, the oldest entry is removed. While the
+    /// window is scrolled back, the view stays on the same entries.
+    pub fn log_with_level(&mut self, level: LogLevel, message: impl Into<String>) {
+        self.push(LogEntry {
+            timestamp: date::now(),
+            level,
             message: message.into(),
-        };
+            team: None,
+        });
+    }
 
+    /// Logs a message about a team at the given level
+    ///
+    /// The team's name is shown in its color where the message mentions it.
+    pub fn log_team(&mut self, level: LogLevel, team: &Team, message: impl Into<String>) {
+        self.push(LogEntry {
+            timestamp: date::now(),
+            level,
+            message: message.into(),
+            team: Some(team.clone()),
+        });
+    }
+
+    /// Adds an entry, evicting the oldest one past max_entries
+    fn push(&mut self, entry: LogEntry) {
+        let level = entry.level;
+
+        if self.scroll_offset > 0 && self.filter.allows(level) {
+            self.scroll_offset += 1;
+        }
         self.entries.push_back(entry);
 
         // Keep only max_entries
         if self.entries.len() > self.max_entries {
             self.entries.pop_front();
         }
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
     }
 
     /// Toggles log window visibility
@@ -77,31 +222,98 @@ impl LogWindow {
         self.visible = !self.visible;
     }
 
+    /// Switches to the next level filter and jumps back to the newest entry
+    pub fn cycle_filter(&mut self) {
+        self.filter = self.filter.next();
+        self.scroll_offset = 0;
+    }
+
+    /// Scrolls towards older (positive) or newer (negative) entries
+    pub fn scroll(&mut self, rows: i32) {
+        let offset = self.scroll_offset as i64 + rows as i64;
+        self.scroll_offset = offset.clamp(0, self.max_scroll() as i64) as usize;
+    }
+
+    /// Handles the log window's keyboard and mouse input
+    ///
+    /// L toggles the window; while it is visible, F cycles the level filter
+    /// and PageUp/PageDown scroll through older entries, as does the mouse
+    /// wheel over the window.
+    pub fn handle_input(&mut self) {
+        if is_key_pressed(KeyCode::L) {
+            self.toggle_visibility();
+        }
+        if !self.visible {
+            return;
+        }
+
+        if is_key_pressed(KeyCode::F) {
+            self.cycle_filter();
+        }
+        if is_key_pressed(KeyCode::PageUp) {
+            self.scroll(VISIBLE_ROWS as i32);
+        }
+        if is_key_pressed(KeyCode::PageDown) {
+            self.scroll(-(VISIBLE_ROWS as i32));
+        }
+
+        if !self.is_under_mouse() {
+            return;
+        }
+        let (_, wheel_y) = mouse_wheel();
+        if wheel_y > 0.0 {
+            self.scroll(1);
+        } else if wheel_y < 0.0 {
+            self.scroll(-1);
+        }
+    }
+
+    /// Screen area of the window, in the bottom-left corner
+    fn area() -> Rect {
+        Rect::new(10.0, screen_height() - 310.0, WINDOW_WIDTH, WINDOW_HEIGHT)
+    }
+
+    /// Checks if the window is shown under the mouse cursor
+    ///
+    /// The mouse wheel scrolls the window there instead of zooming the city.
+    pub fn is_under_mouse(&self) -> bool {
+        let (mouse_x, mouse_y) = mouse_position();
+        self.visible && Self::area().contains(vec2(mouse_x, mouse_y))
+    }
+
+    /// Entries passing the current filter, newest first
+    fn filtered_entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| self.filter.allows(entry.level))
+    }
+
+    /// Largest scroll offset that still leaves the last page full
+    fn max_scroll(&self) -> usize {
+        self.filtered_entries().count().saturating_sub(VISIBLE_ROWS)
+    }
+
     /// Renders the log window overlay
     ///
     /// Displays a semi-transparent window in the bottom-left corner with:
     /// - Dark background with border
-    /// - Title bar "CRITICAL SYSTEM LOGS"
-    /// - Timestamped log entries (newest at top)
-    /// - Help text for toggling visibility
-    ///
-    /// All log entries are displayed in red (critical level).
+    /// - Title bar "SYSTEM LOGS" and the active level filter
+    /// - Timestamped log entries colored by level (newest at top)
+    /// - Scroll position and help text for the window's controls
     pub fn render(&self) {
         if !self.visible {
             return;
         }
 
-        let window_x = 10.0;
-        let window_y = screen_height() - 310.0;
-        let window_width = 450.0;
-        let window_height = 300.0;
+        let Rect { x: window_x, y: window_y, .. } = Self::area();
 
         // Draw window background (dark semi-transparent)
         draw_rectangle(
             window_x,
             window_y,
-            window_width,
-            window_height,
+            WINDOW_WIDTH,
+            WINDOW_HEIGHT,
             Color::new(0.1, 0.1, 0.15, 0.95),
         );
 
@@ -109,99 +321,197 @@ impl LogWindow {
         draw_rectangle_lines(
             window_x,
             window_y,
-            window_width,
-            window_height,
+            WINDOW_WIDTH,
+            WINDOW_HEIGHT,
             2.0,
-            Color::new(0.8, 0.2, 0.2, 1.0), // Red border for critical
+            Color::new(0.8, 0.2, 0.2, 1.0), // Red border
         );
 
         // Draw title bar
         draw_rectangle(
             window_x,
             window_y,
-            window_width,
+            WINDOW_WIDTH,
             25.0,
             Color::new(0.2, 0.05, 0.05, 1.0), // Dark red
         );
 
         draw_text(
-            "CRITICAL SYSTEM LOGS",
+            "SYSTEM LOGS",
             window_x + 10.0,
             window_y + 18.0,
             20.0,
             Color::new(1.0, 0.3, 0.3, 1.0), // Light red
         );
 
-        // Draw log entries (newest at top)
-        let mut y_offset = window_y + 35.0;
-        let line_height = 20.0;
-        let padding = 5.0;
-
-        for entry in self.entries.iter().rev() {
-            if y_offset > window_y + window_height - 30.0 {
-                break; // Don't draw beyond window (leave space for help text)
-            }
+        let filter_text = format!("Filter: {}", self.filter.label());
+        draw_text(
+            &filter_text,
+            window_x + WINDOW_WIDTH - 130.0,
+            window_y + 17.0,
+            14.0,
+            Color::new(0.7, 0.7, 0.7, 1.0),
+        );
 
-            // Format timestamp (MM:SS.MS)
-            let mins = (entry.timestamp / 60.0) as i32;
-            let secs = (entry.timestamp % 60.0) as i32;
-            let millis = ((entry.timestamp % 1.0) * 1000.0) as i32;
-            let time_str = format!("{:02}:{:02}.{:03}", mins, secs, millis);
+        // Draw log entries (newest at top, starting at the scroll position)
+        let mut y_offset = window_y + 35.0;
 
-            // Draw timestamp
+        for entry in self
+            .filtered_entries()
+            .skip(self.scroll_offset)
+            .take(VISIBLE_ROWS)
+        {
+            // Draw wall-clock timestamp
             draw_text(
-                &time_str,
+                &format_timestamp(entry.timestamp),
                 window_x + 10.0,
                 y_offset,
                 14.0,
                 Color::new(0.5, 0.5, 0.5, 1.0),
             );
 
-            // Draw [CRITICAL] prefix
-            draw_text(
-                "[CRITICAL]",
-                window_x + 95.0,
-                y_offset,
-                14.0,
-                Color::new(1.0, 0.0, 0.0, 1.0), // Bright red
-            );
+            // Draw level prefix
+            let color = entry.level.color();
+            draw_text(entry.level.prefix(), window_x + 80.0, y_offset, 14.0, color);
 
             // Draw message (truncate if too long)
             let max_msg_len = 40;
-            let msg = if entry.message.len() > max_msg_len {
-                format!("{}...", &entry.message[..max_msg_len])
+            let msg = if entry.message.chars().count() > max_msg_len {
+                let truncated: String = entry.message.chars().take(max_msg_len).collect();
+                format!("{}...", truncated)
             } else {
                 entry.message.clone()
             };
 
-            draw_text(
-                &msg,
-                window_x + 185.0,
-                y_offset,
-                14.0,
-                WHITE,
-            );
+            draw_message(&msg, entry.team.as_ref(), window_x + 165.0, y_offset, color);
 
-            y_offset += line_height;
+            y_offset += LINE_HEIGHT;
         }
 
         // Draw help text at bottom
         draw_text(
-            "Press 'L' to toggle log window",
+            "L: hide  F: filter  PgUp/PgDn: scroll",
             window_x + 10.0,
-            window_y + window_height - 10.0,
+            window_y + WINDOW_HEIGHT - 10.0,
             12.0,
             Color::new(0.5, 0.5, 0.5, 1.0),
         );
 
-        // Draw entry count
-        let count_text = format!("{}/{} entries", self.entries.len(), self.max_entries);
+        // Draw scroll position and entry count
+        let count_text = if self.scroll_offset > 0 {
+            format!(
+                "+{} newer  {}/{} entries",
+                self.scroll_offset,
+                self.entries.len(),
+                self.max_entries
+            )
+        } else {
+            format!("{}/{} entries", self.entries.len(), self.max_entries)
+        };
         draw_text(
             &count_text,
-            window_x + window_width - 100.0,
-            window_y + window_height - 10.0,
+            window_x + WINDOW_WIDTH - 10.0 - measure_text(&count_text, None, 12, 1.0).width,
+            window_y + WINDOW_HEIGHT - 10.0,
             12.0,
             Color::new(0.5, 0.5, 0.5, 1.0),
         );
     }
 }
+
+/// Draws a log message, with the team's name in the team's color
+///
+/// # Arguments
+/// * `message` - Message text, already truncated to fit
+/// * `team` - Team the entry is about, if any
+/// * `x`, `y` - Baseline start of the text
+/// * `color` - Color of the rest of the message
+fn draw_message(message: &str, team: Option<&Team>, x: f32, y: f32, color: Color) {
+    let Some((team, start)) = team.and_then(|team| Some((team, message.find(&team.name)?))) else {
+        draw_text(message, x, y, 14.0, color);
+        return;
+    };
+
+    let end = start + team.name.len();
+    let mut x = x;
+    for (part, part_color) in [
+        (&message[..start], color),
+        (&message[start..end], team.color),
+        (&message[end..], color),
+    ] {
+        draw_text(part, x, y, 14.0, part_color);
+        x += measure_text(part, None, 14, 1.0).width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_with(levels: &[LogLevel]) -> LogWindow {
+        let mut window = LogWindow::new(50);
+        for (i, level) in levels.iter().enumerate() {
+            window.log_with_level(*level, format!("entry {}", i));
+        }
+        window
+    }
+
+    fn visible_messages(window: &LogWindow) -> Vec<String> {
+        window
+            .filtered_entries()
+            .skip(window.scroll_offset)
+            .take(VISIBLE_ROWS)
+            .map(|entry| entry.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_format_timestamp_is_utc_time_of_day() {
+        // 2024-01-01 13:05:09 UTC
+        assert_eq!(format_timestamp(1_704_114_309.75), "13:05:09");
+        assert_eq!(format_timestamp(0.0), "00:00:00");
+    }
+
+    #[test]
+    fn test_filter_cycles_and_hides_lower_levels() {
+        use LogLevel::*;
+        let mut window = window_with(&[Info, Warning, Critical, Info]);
+        assert_eq!(visible_messages(&window).len(), 4);
+
+        window.cycle_filter();
+        assert_eq!(visible_messages(&window), vec!["entry 2", "entry 1"]);
+
+        window.cycle_filter();
+        assert_eq!(visible_messages(&window), vec!["entry 2"]);
+
+        window.cycle_filter();
+        assert_eq!(window.filter, LogFilter::All);
+    }
+
+    #[test]
+    fn test_scroll_is_clamped_and_holds_position_on_new_entries() {
+        let mut window = window_with(&[LogLevel::Info; 20]);
+        let max_scroll = 20 - VISIBLE_ROWS;
+
+        window.scroll(-5);
+        assert_eq!(window.scroll_offset, 0);
+        window.scroll(100);
+        assert_eq!(window.scroll_offset, max_scroll);
+        assert_eq!(visible_messages(&window)[0], format!("entry {}", 19 - max_scroll));
+
+        // A new entry must not shift the entries being read
+        window.log("newest");
+        assert_eq!(visible_messages(&window)[0], format!("entry {}", 19 - max_scroll));
+    }
+
+    #[test]
+    fn test_oldest_entries_are_evicted() {
+        let mut window = LogWindow::new(3);
+        window.log("a");
+        window.log_warning("b");
+        window.log_critical("c");
+        window.log("d");
+
+        let levels: Vec<_> = window.entries.iter().map(|e| e.level).collect();
+        assert_eq!(levels, vec![LogLevel::Warning, LogLevel::Critical, LogLevel::Info]);
+    }
+}
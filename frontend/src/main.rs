@@ -1,9 +1,15 @@
 use macroquad::prelude::*;
 
+mod audio;
 mod block;
+mod camera;
 mod car;
 mod city;
+mod clock;
+mod config;
+mod debug;
 mod constants;
+mod effects;
 mod events;
 mod input;
 mod intersection;
@@ -11,18 +17,42 @@ mod led_chars;
 mod led_display_object;
 mod logging;
 mod models;
+mod pedestrian;
 mod rendering;
 mod road;
+mod route;
+mod scoreboard;
+mod screen;
+mod selection;
+mod snapshot;
+mod spatial_grid;
 mod spawner;
 mod sse_client;
+mod team;
+mod time_of_day;
 mod traffic_light;
+mod weather;
 
+use audio::AudioPlayer;
+use block::ScadaState;
+use led_display_object::{LEDDisplayMode, LEDPriority, LedMessage};
+use camera::Camera;
 use city::City;
-use events::{create_event_channel, GameEvent};
+use clock::SimulationClock;
+use config::{CityConfig, CONFIG_PATH};
+use debug::DebugOverlay;
+use events::{create_event_channel, GameEvent, GameState};
 use input::{handle_input, WindowState};
-use intersection::generate_intersections;
-use logging::LogWindow;
-use sse_client::start_sse_client;
+use road::generate_road_network;
+use logging::{LogLevel, LogWindow};
+use rendering::Minimap;
+use scoreboard::Scoreboard;
+use selection::{Selection, Selector};
+use snapshot::{CitySnapshot, Controls};
+use sse_client::{sse_url, start_sse_client};
+use team::{parse_hex_color, TeamRegistry};
+use time_of_day::TimeOfDay;
+use weather::RainSystem;
 
 // ============================================================================
 // Configuration Constants
@@ -30,6 +60,48 @@ use sse_client::start_sse_client;
 
 use constants::{visual::ROAD_COLOR, window::RESIZE_THRESHOLD};
 
+/// Block ID of the LED display block
+const LED_BLOCK_ID: usize = 0;
+
+/// Text shown on the LED display while it works normally
+const LED_WELCOME_TEXT: &str = "  WELCOME TO CITY  ";
+
+/// Text flashed on the LED display once the round countdown runs out
+const LED_TIME_UP_TEXT: &str = "TIME UP";
+
+/// Alerts raised on the LED display while danger mode or the emergency stop
+/// is active
+const LED_DANGER_TEXT: &str = "DANGER";
+const LED_EMERGENCY_TEXT: &str = "EMERGENCY STOP";
+
+/// Messages queued on the LED display when a SCADA system is compromised or
+/// restored
+const LED_SCADA_ALERT_TEXT: &str = "SCADA ALERT";
+const LED_SCADA_RESTORED_TEXT: &str = "SYSTEMS RESTORED";
+
+/// Seconds the SCADA alert and restore messages are shown on the LED display
+const LED_SCADA_ALERT_DURATION: f32 = 10.0;
+
+/// Round countdown as shown on the LED display
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RoundTimer {
+    /// No countdown; the display shows its usual text
+    Idle,
+    /// Counting down, with this many seconds left
+    Running(u32),
+    /// The countdown ran out
+    Expired,
+}
+
+/// Formats a countdown as MM:SS, or H:MM:SS for an hour or more
+fn format_countdown(secs: u32) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -53,10 +125,10 @@ fn create_led_display_block() -> block::Block {
     let block_width = v2 - (ROAD_WIDTH / 2.0) / screen_width() - block_x;
     let block_height = h1 - (ROAD_WIDTH / 2.0) / screen_height();
 
-    let mut display_block = Block::new(block_x, block_y, block_width, block_height, 0);
+    let mut display_block = Block::new(block_x, block_y, block_width, block_height, LED_BLOCK_ID);
 
     // Add LED display to the block
-    let led = LEDDisplay::new("  WELCOME TO CITY  ")
+    let led = LEDDisplay::new(LED_WELCOME_TEXT)
         .with_position(0.1, 0.3)
         .with_size(0.8, 0.4);
     display_block.add_object(Box::new(led));
@@ -64,6 +136,138 @@ fn create_led_display_block() -> block::Block {
     display_block
 }
 
+/// Adds the grass blocks and the LED display block to the city
+///
+/// Roads are a fixed number of pixels wide, so the blocks' percentages
+/// depend on the screen size; this runs at startup and again after the
+/// window is resized.
+fn add_blocks(city: &mut City) {
+    for grass_block in block::generate_grass_blocks() {
+        city.add_block(grass_block);
+    }
+    city.add_block(create_led_display_block());
+    city.link_blocks_to_roads();
+}
+
+/// Plays a particle burst over SCADA buildings
+///
+/// Compromise bursts are red/orange, restore bursts are green.
+///
+/// # Arguments
+/// * `city` - The city containing the buildings
+/// * `building_id` - Block ID of the affected building, or None for all SCADA buildings
+/// * `compromised` - True for a compromise burst, false for a restore burst
+fn play_scada_burst(city: &mut City, building_id: Option<usize>, compromised: bool) {
+    use constants::effects::*;
+
+    let block_ids = match building_id {
+        Some(id) => vec![id],
+        None => city.scada_block_ids(),
+    };
+
+    for block_id in block_ids {
+        let Some((x, y)) = city.building_world_position(block_id) else {
+            continue;
+        };
+        let count = rand::gen_range(BURST_PARTICLES_MIN, BURST_PARTICLES_MAX + 1);
+
+        if compromised {
+            // Split the burst between red and orange sparks
+            let red_count = count / 2;
+            city.trigger_particle_burst(x, y, SCADA_COMPROMISED_COLOR, red_count);
+            city.trigger_particle_burst(x, y, SCADA_COMPROMISED_ALT_COLOR, count - red_count);
+        } else {
+            city.trigger_particle_burst(x, y, SCADA_RESTORED_COLOR, count);
+        }
+    }
+}
+
+/// Marks where an event happened on the minimap
+///
+/// # Arguments
+/// * `minimap` - The minimap to mark
+/// * `positions` - Where the event happened, in pixels
+/// * `color` - Marker color
+fn mark_on_minimap(
+    minimap: &mut Minimap,
+    positions: impl IntoIterator<Item = (f32, f32)>,
+    color: Color,
+) {
+    for (x, y) in positions {
+        minimap.mark_event(vec2(x / screen_width(), y / screen_height()), color);
+    }
+}
+
+/// Puts the LED display into broken, countdown, or normal mode
+///
+/// A broken display keeps showing the attacker's message over the countdown.
+///
+/// # Arguments
+/// * `city` - The city containing the LED display block
+/// * `led_broken` - The attacker's message while the display is broken (empty
+///   to garble the welcome text), or None when it works
+/// * `timer` - Round countdown to show while the display works
+fn apply_led_state(city: &mut City, led_broken: Option<&str>, timer: RoundTimer) {
+    let (text, mode) = match (led_broken, timer) {
+        (Some(message), _) if !message.is_empty() => {
+            (format!("  {}  ", message), LEDDisplayMode::Broken)
+        }
+        (Some(_), _) => (LED_WELCOME_TEXT.to_string(), LEDDisplayMode::Broken),
+        (None, RoundTimer::Running(secs)) => (format_countdown(secs), LEDDisplayMode::Static),
+        (None, RoundTimer::Expired) => (LED_TIME_UP_TEXT.to_string(), LEDDisplayMode::Flashing),
+        (None, RoundTimer::Idle) => (LED_WELCOME_TEXT.to_string(), LEDDisplayMode::Scrolling),
+    };
+    city.set_led_text(LED_BLOCK_ID, &text);
+    city.set_led_mode(LED_BLOCK_ID, mode);
+}
+
+/// Raises a sticky critical alert on the LED display, or removes it
+///
+/// # Arguments
+/// * `city` - The city containing the LED display
+/// * `text` - Alert text, e.g. `LED_DANGER_TEXT`
+/// * `active` - True to show the alert, false to remove it
+fn set_led_alert(city: &mut City, text: &str, active: bool) {
+    if active {
+        city.led_enqueue(text, LEDPriority::Critical, None);
+    } else {
+        city.led_remove(text);
+    }
+}
+
+/// Sets every building's SCADA, fire and power state, and the closed roads,
+/// to match a server snapshot
+///
+/// Compromised buildings take the color of the team holding them, which is
+/// registered if it has not been seen yet.
+fn apply_buildings_snapshot(city: &mut City, state: &GameState, teams: &mut TeamRegistry) {
+    city.reset_all_scada();
+    for (&block_id, &compromised) in &state.scada_compromised_buildings {
+        let building_id = Some(block_id as usize);
+        match state.scada_compromised_by.get(&block_id) {
+            Some(team) if compromised => {
+                city.compromise_scada(building_id, teams.get_or_register(team));
+            }
+            _ => city.set_scada(building_id, compromised),
+        }
+    }
+
+    city.clear_building_fires();
+    for (&block_id, &intensity) in &state.buildings_on_fire {
+        city.set_building_fire(block_id as usize, intensity);
+    }
+
+    city.restore_all_power();
+    for district in &state.districts_without_power {
+        city.set_district_power(district, false);
+    }
+
+    city.reopen_all_roads();
+    for &road_id in &state.closed_roads {
+        city.set_road_closed(road_id, true);
+    }
+}
+
 // ============================================================================
 // Main Application
 // ============================================================================
@@ -74,101 +278,315 @@ async fn main() -> Result<(), macroquad::Error> {
     // Initialization
     // ========================================================================
 
-    // Initialize city with intersections
+    // Initialize log window for system events
+    let mut log_window = LogWindow::new(50); // Keep last 50 entries
+
+    // Load optional settings, falling back to the defaults
+    let config = match CityConfig::load() {
+        Ok(Some(config)) => {
+            log_window.log(format!(
+                "Loaded light timings for {} intersections from {}",
+                config.intersections.len(),
+                CONFIG_PATH
+            ));
+            config
+        }
+        Ok(None) => CityConfig::default(),
+        Err(err) => {
+            log_window.log_warning(format!("Ignoring {}: {}", CONFIG_PATH, err));
+            CityConfig::default()
+        }
+    };
+
+    // Initialize city with roads and intersections
     let mut city = City::new();
-    let intersections = generate_intersections();
+    city.set_edge_behavior(config.edge_behavior);
+    city.set_traffic_rule(config.traffic_rule);
+    city.set_spawn_config(config.spawner.clone());
+    let (roads, intersections) = generate_road_network();
+    for road in roads {
+        city.add_road(road);
+    }
     for intersection in intersections {
-        city.add_intersection(intersection);
+        let timing = config.light_timing(intersection.id);
+        let intersection_type = config.intersection_type(intersection.id);
+        city.add_intersection(intersection.with_timing(timing).with_type(intersection_type));
     }
 
-    // Add grass blocks to the city
-    use block::generate_grass_blocks;
-    let grass_blocks = generate_grass_blocks();
-    for grass_block in grass_blocks {
-        city.add_block(grass_block);
-    }
+    // Add grass blocks and the LED display block to the city
+    add_blocks(&mut city);
 
-    // Create and add LED display block
-    city.add_block(create_led_display_block());
+    // Load the sound effects; missing files only silence their cue
+    let (mut audio, missing_sounds) = AudioPlayer::load(config.audio.clone()).await;
+    if !missing_sounds.is_empty() {
+        log_window.log_warning(format!("Sounds not found: {}", missing_sounds.join(", ")));
+    }
 
     // Initialize window state tracking
     let mut window_state = WindowState::new();
 
-    // Initialize log window for critical events
-    let mut log_window = LogWindow::new(50); // Keep last 50 entries
     log_window.log("City Dashboard initialized");
 
+    // Initialize click-to-inspect selection (nothing selected)
+    let mut selector = Selector::new();
+
+    // Initialize the camera showing the whole city
+    let mut camera = Camera::new();
+
+    // Initialize minimap overlay (shown until M hides it)
+    let mut minimap = Minimap::new();
+
+    // Initialize team scoreboard overlay (hidden until a team shows up)
+    let mut scoreboard = Scoreboard::new();
+
+    // Teams seen this session, starting with the colors from the config
+    let mut teams = TeamRegistry::new();
+    for (name, color) in &config.team_colors {
+        match parse_hex_color(color) {
+            Some(color) => {
+                teams.register(name, color);
+            }
+            None => log_window.log_warning(format!(
+                "Ignoring color '{}' of team {}: expected #rrggbb",
+                color, name
+            )),
+        }
+    }
+
     // Initialize event channel for SSE communication
     let (event_sender, event_receiver) = create_event_channel();
 
     // Start SSE client in background thread
     // URL can be configured via environment variable: SSE_URL
-    let sse_url = std::env::var("SSE_URL")
-        .unwrap_or_else(|_| "http://localhost:3000/events".to_string());
+    let sse_url = sse_url();
     let _sse_handle = start_sse_client(sse_url.clone(), event_sender);
     log_window.log(format!("SSE client connecting to: {}", sse_url));
 
     // Initialize control modes
     let mut all_lights_red = false; // Emergency traffic stop mode
-    let mut danger_mode = false;     // Danger warning on LED display
     let mut barrier_open = false;    // Barrier gate state (false = closed/down)
+    let mut led_broken: Option<String> = None; // Message on the hacked LED display
+    let mut round_timer = RoundTimer::Idle;    // Round countdown on the LED display
+    let mut show_queues = false;               // Queue lengths at intersections (debug)
+
+    // Frame timings, shown with F3
+    let mut debug_overlay = DebugOverlay::new();
+
+    // Simulation time, scaled and pausable independently of real time
+    let mut clock = SimulationClock::new();
+
+    // Day/night cycle, advanced by simulation time
+    let mut time_of_day = config.day_length.map_or_else(TimeOfDay::default, TimeOfDay::new);
+
+    // Rain, set by the server (dry until told otherwise)
+    let mut rain = RainSystem::new();
 
     // Track previous states for event detection
     let mut previous_all_lights_red = false;
-    let mut previous_danger_mode = false;
 
     // ========================================================================
     // Main Game Loop
     // ========================================================================
 
     loop {
-        let dt = get_frame_time();
-        let current_time = get_time();
+        // Everything that moves or animates runs on the simulation clock
+        let dt = clock.tick(get_frame_time());
+        let current_time = clock.time();
 
         // --------------------------------------------------------------------
         // Input Processing
         // --------------------------------------------------------------------
 
-        let (new_all_lights_red, new_danger_mode, toggle_scada, reset_scada, toggle_barrier) =
-            handle_input(all_lights_red, danger_mode);
+        // While an entity is selected Escape only deselects it (below)
+        let escape_resets = selector.selected().is_none();
+        let (new_all_lights_red, toggle_danger, toggle_scada, reset, toggle_barrier) =
+            handle_input(all_lights_red, escape_resets);
+        if new_all_lights_red && !all_lights_red {
+            audio.handle_event(&GameEvent::EmergencyStop {
+                reason: "Operator".to_string(),
+            });
+        }
         all_lights_red = new_all_lights_red;
-        danger_mode = new_danger_mode;
 
-        // Handle log window toggle
-        if is_key_pressed(KeyCode::L) {
-            log_window.toggle_visibility();
+        // V mutes or unmutes all sound
+        if is_key_pressed(KeyCode::V) {
+            if audio.toggle_mute() {
+                log_window.log("Sound muted");
+            } else {
+                log_window.log("Sound unmuted");
+            }
+        }
+
+        // Handle log window toggle, filter and scrolling
+        log_window.handle_input();
+
+        // Space pauses the simulation, +/- change its speed, Ctrl+S enters
+        // step mode and N steps
+        if clock.handle_input() {
+            log_window.log(format!("Simulation {}", clock.label()));
+        }
+
+        // [ and ] throttle or boost the car spawn rate
+        if is_key_pressed(KeyCode::LeftBracket) || is_key_pressed(KeyCode::RightBracket) {
+            use constants::vehicle::{MAX_SPAWN_INTERVAL, MIN_SPAWN_INTERVAL, SPAWN_RATE_STEP};
+
+            let interval = if is_key_pressed(KeyCode::LeftBracket) {
+                city.car_spawn_rate() * SPAWN_RATE_STEP
+            } else {
+                city.car_spawn_rate() / SPAWN_RATE_STEP
+            };
+            city.set_car_spawn_rate(interval.clamp(MIN_SPAWN_INTERVAL, MAX_SPAWN_INTERVAL));
+            log_window.log(format!("Car spawn interval: {:.2}s", city.car_spawn_rate()));
+        }
+
+        // D forces day, N forces night, A resumes the day/night cycle; in
+        // step mode N steps instead
+        if !clock.is_stepping() && time_of_day.handle_input() {
+            log_window.log(format!("Lighting: {}", time_of_day.label()));
+        }
+
+        // E dispatches an emergency vehicle from a random road edge
+        if is_key_pressed(KeyCode::E) {
+            let (road, direction) = city.dispatch_emergency_vehicle();
+            log_window.log_warning(format!(
+                "Emergency vehicle dispatched on road {} heading {:?}",
+                road, direction
+            ));
+        }
+
+        // T turns adaptive light timing on or off
+        if is_key_pressed(KeyCode::T) {
+            city.set_adaptive_lights(!city.adaptive_lights());
+            let mode = if city.adaptive_lights() { "ON" } else { "OFF" };
+            log_window.log(format!("Adaptive light timing: {}", mode));
+        }
+
+        // H switches the side of the road traffic keeps to
+        if is_key_pressed(KeyCode::H) {
+            let cleared = city.set_traffic_rule(city.traffic_rule().toggled());
+            log_window.log(format!(
+                "Traffic: {} ({} cars cleared)",
+                city.traffic_rule().label(),
+                cleared
+            ));
+        }
+
+        // Q shows or hides the queue lengths at intersections
+        if is_key_pressed(KeyCode::Q) {
+            show_queues = !show_queues;
+        }
+
+        // M shows or hides the minimap
+        minimap.handle_input();
+
+        // Tab shows or hides the scoreboard
+        scoreboard.handle_input();
+
+        // F3 shows or hides the debug overlay
+        debug_overlay.handle_input();
+
+        // Mouse wheel zooms (except over the log window), dragging with the
+        // middle button pans
+        camera.handle_input(log_window.is_under_mouse());
+        let mouse = camera.mouse_world_position();
+
+        // Left click inspects a car, intersection or block; Escape deselects
+        selector.handle_input(&city, mouse);
+
+        // G sends a car to the selected block along a planned route
+        if is_key_pressed(KeyCode::G)
+            && let Some(Selection::Block(block_id)) = selector.selected()
+        {
+            match city.spawn_car_to(block_id) {
+                Some(car_id) => {
+                    log_window.log(format!("Car {} routed to block {}", car_id, block_id))
+                }
+                None => log_window.log_warning(format!("No route to block {}", block_id)),
+            }
+        }
+
+        // Right-clicking an intersection cycles its light override
+        if is_mouse_button_pressed(MouseButton::Right) {
+            let (mouse_x, mouse_y) = mouse;
+            if let Some((id, override_state)) =
+                city.cycle_intersection_override_at(mouse_x, mouse_y)
+            {
+                let mode = override_state.map_or("AUTO", |o| o.label());
+                log_window.log(format!("Intersection {} lights: {}", id, mode));
+            }
         }
 
         // --------------------------------------------------------------------
         // Process SSE Events
         // --------------------------------------------------------------------
 
-        let sse_events = event_receiver.poll();
+        let mut sse_events = event_receiver.poll();
+
+        // F5 saves the city and F9 loads it, as the server's city_save and
+        // city_load events do
+        if is_key_pressed(KeyCode::F5) {
+            sse_events.push(GameEvent::CitySave);
+        }
+        if is_key_pressed(KeyCode::F9) {
+            sse_events.push(GameEvent::CityLoad);
+        }
+
         for event in sse_events {
+            audio.handle_event(&event);
             match event {
                 GameEvent::BarrierBroken { team, message } => {
+                    let team = teams.get_or_register(&team);
                     barrier_open = true;
+                    city.set_barrier_broken_by(Some(team));
+                    scoreboard.record_event(&team.name);
+                    mark_on_minimap(&mut minimap, city.barrier_positions(), ORANGE);
                     let msg = message.unwrap_or_else(|| "Gate compromised".to_string());
-                    log_window.log(format!("BARRIER BROKEN by {} - {}", team, msg));
+                    log_window.log_team(
+                        LogLevel::Critical,
+                        team,
+                        format!("BARRIER BROKEN by {} - {}", team, msg),
+                    );
                 }
 
                 GameEvent::BarrierRepaired { team } => {
                     barrier_open = false;
+                    city.set_barrier_broken_by(None);
                     if let Some(team) = team {
-                        log_window.log(format!("Barrier repaired by {}", team));
+                        let team = teams.get_or_register(&team);
+                        scoreboard.record_event(&team.name);
+                        log_window.log_team(
+                            LogLevel::Info,
+                            team,
+                            format!("Barrier repaired by {}", team),
+                        );
                     } else {
                         log_window.log("Barrier repaired");
                     }
                 }
 
                 GameEvent::LedDisplayBroken { team, message } => {
-                    danger_mode = true;
+                    let team = teams.get_or_register(&team);
+                    scoreboard.record_event(&team.name);
+                    led_broken = Some(message.clone().unwrap_or_default());
+                    apply_led_state(&mut city, led_broken.as_deref(), round_timer);
+                    // The attacker's message waits for the one being shown
+                    if let Some(text) = message.as_deref().filter(|text| !text.is_empty()) {
+                        city.led_enqueue_message(LedMessage::new(
+                            format!("  {}  ", text),
+                            LEDDisplayMode::Broken,
+                        ));
+                    }
                     let msg = message.unwrap_or_else(|| "Display damaged".to_string());
-                    log_window.log(format!("LED DISPLAY BROKEN by {} - {}", team, msg));
+                    log_window.log_team(
+                        LogLevel::Critical,
+                        team,
+                        format!("LED DISPLAY BROKEN by {} - {}", team, msg),
+                    );
                 }
 
                 GameEvent::LedDisplayRepaired => {
-                    danger_mode = false;
+                    led_broken = None;
+                    apply_led_state(&mut city, None, round_timer);
                     log_window.log("LED display repaired");
                 }
 
@@ -177,20 +595,43 @@ async fn main() -> Result<(), macroquad::Error> {
                     team,
                     message,
                 } => {
-                    city.toggle_all_scada();
+                    let team = teams.get_or_register(&team);
+                    scoreboard.record_event(&team.name);
+                    let building_id = building_id.map(|id| id as usize);
+                    city.compromise_scada(building_id, team);
+                    play_scada_burst(&mut city, building_id, true);
+                    let block_ids = match building_id {
+                        Some(id) => vec![id],
+                        None => city.scada_block_ids(),
+                    };
+                    let positions = block_ids
+                        .into_iter()
+                        .filter_map(|id| city.building_world_position(id));
+                    mark_on_minimap(&mut minimap, positions, RED);
+                    city.led_enqueue_message(
+                        LedMessage::new(LED_SCADA_ALERT_TEXT, LEDDisplayMode::Flashing)
+                            .with_duration(LED_SCADA_ALERT_DURATION),
+                    );
                     let msg = message.unwrap_or_else(|| "System compromised".to_string());
-                    if let Some(id) = building_id {
-                        log_window.log(format!(
-                            "SCADA COMPROMISED (Building {}) by {} - {}",
-                            id, team, msg
-                        ));
+                    let text = if let Some(id) = building_id {
+                        format!("SCADA COMPROMISED (Building {}) by {} - {}", id, team, msg)
                     } else {
-                        log_window.log(format!("SCADA COMPROMISED by {} - {}", team, msg));
-                    }
+                        format!("SCADA COMPROMISED by {} - {}", team, msg)
+                    };
+                    log_window.log_team(LogLevel::Critical, team, text);
                 }
 
                 GameEvent::ScadaRestored { building_id } => {
-                    city.reset_all_scada();
+                    let building_id = building_id.map(|id| id as usize);
+                    match building_id {
+                        Some(id) => city.set_building_scada_state(id, ScadaState::Normal),
+                        None => city.reset_all_scada(),
+                    }
+                    play_scada_burst(&mut city, building_id, false);
+                    city.led_enqueue_message(
+                        LedMessage::new(LED_SCADA_RESTORED_TEXT, LEDDisplayMode::Static)
+                            .with_duration(LED_SCADA_ALERT_DURATION),
+                    );
                     if let Some(id) = building_id {
                         log_window.log(format!("SCADA restored (Building {})", id));
                     } else {
@@ -198,9 +639,65 @@ async fn main() -> Result<(), macroquad::Error> {
                     }
                 }
 
+                GameEvent::BuildingFire {
+                    building_id,
+                    intensity,
+                } => {
+                    city.set_building_fire(building_id as usize, intensity);
+                    log_window.log_critical(format!(
+                        "FIRE at Building {} (intensity {:.0}%)",
+                        building_id,
+                        intensity * 100.0
+                    ));
+                }
+
+                GameEvent::BuildingFireExtinguished { building_id } => {
+                    city.extinguish_building_fire(building_id as usize);
+                    log_window.log(format!("Fire at Building {} extinguished", building_id));
+                }
+
+                GameEvent::PowerOutage { district } => {
+                    if city.set_district_power(&district, false) {
+                        log_window.log_critical(format!("POWER OUTAGE in {} district", district));
+                    } else {
+                        log_window.log_warning(format!("Power outage in unknown district '{}'", district));
+                    }
+                }
+
+                GameEvent::PowerRestored { district } => {
+                    if city.set_district_power(&district, true) {
+                        log_window.log(format!("Power restored in {} district", district));
+                    }
+                }
+
+                GameEvent::RoadClosure { road_id, blocked } => {
+                    if !city.set_road_closed(road_id, blocked) {
+                        log_window.log_warning(format!("Closure of unknown road {}", road_id));
+                    } else if blocked {
+                        log_window.log_warning(format!("Road {} closed", road_id));
+                    } else {
+                        log_window.log(format!("Road {} reopened", road_id));
+                    }
+                }
+
+                GameEvent::WeatherChange { rain_intensity } => {
+                    rain.set_intensity(rain_intensity);
+                    city.set_speed_factor(rain.speed_factor());
+                    if rain.intensity() > 0.0 {
+                        log_window.log(format!("Rain: {:.0}%", rain.intensity() * 100.0));
+                    } else {
+                        log_window.log("Rain stopped");
+                    }
+                }
+
+                GameEvent::GreenWave { speed } => {
+                    let speed = city.start_green_wave(speed);
+                    log_window.log(format!("Green wave started at {:.0} px/s", speed));
+                }
+
                 GameEvent::EmergencyStop { reason } => {
                     all_lights_red = true;
-                    log_window.log(format!("EMERGENCY STOP - {}", reason));
+                    log_window.log_critical(format!("EMERGENCY STOP - {}", reason));
                 }
 
                 GameEvent::EmergencyStopDeactivated => {
@@ -209,18 +706,125 @@ async fn main() -> Result<(), macroquad::Error> {
                 }
 
                 GameEvent::DangerModeActivated { reason } => {
-                    danger_mode = true;
-                    log_window.log(format!("DANGER MODE - {}", reason));
+                    set_led_alert(&mut city, LED_DANGER_TEXT, true);
+                    log_window.log_critical(format!("DANGER MODE - {}", reason));
                 }
 
                 GameEvent::DangerModeDeactivated => {
-                    danger_mode = false;
+                    set_led_alert(&mut city, LED_DANGER_TEXT, false);
                     log_window.log("Danger mode deactivated");
                 }
 
-                GameEvent::LogMessage { level: _, message } => {
-                    // All logs are critical in this system
-                    log_window.log(message);
+                GameEvent::GameReset => {
+                    barrier_open = false;
+                    all_lights_red = false;
+                    led_broken = None;
+                    round_timer = RoundTimer::Idle;
+                    apply_led_state(&mut city, None, round_timer);
+                    city.led_clear_queue();
+                    city.reset_all_scada();
+                    city.clear_building_fires();
+                    city.restore_all_power();
+                    city.reopen_all_roads();
+                    city.clear_intersection_overrides();
+                    city.set_barrier_broken_by(None);
+                    rain.set_intensity(0.0);
+                    city.set_speed_factor(rain.speed_factor());
+                    scoreboard.clear();
+                    log_window.log_warning("Game reset by server");
+                }
+
+                GameEvent::CitySave => {
+                    let path = snapshot::save_path();
+                    let controls = Controls {
+                        all_lights_red,
+                        barrier_open,
+                        led_broken: led_broken.clone(),
+                    };
+                    match city.to_snapshot(controls).save(&path) {
+                        Ok(()) => log_window.log(format!("City saved to {}", path)),
+                        Err(err) => log_window.log_warning(format!("Could not save city: {}", err)),
+                    }
+                }
+
+                GameEvent::CityLoad => {
+                    let path = snapshot::save_path();
+                    match CitySnapshot::load(&path) {
+                        Ok(saved) => {
+                            let controls = city.restore_snapshot(saved);
+                            all_lights_red = controls.all_lights_red;
+                            barrier_open = controls.barrier_open;
+                            led_broken = controls.led_broken;
+                            apply_led_state(&mut city, led_broken.as_deref(), round_timer);
+                            log_window.log(format!("City loaded from {}", path));
+                        }
+                        Err(err) => log_window.log_warning(format!("Could not load city: {}", err)),
+                    }
+                }
+
+                GameEvent::TeamScoreUpdate { team, delta, total } => {
+                    let team = teams.get_or_register(&team);
+                    log_window.log_team(
+                        LogLevel::Info,
+                        team,
+                        format!("{} score {:+} (total {})", team, delta, total),
+                    );
+                    scoreboard.set_score(team.name.clone(), total);
+                }
+
+                GameEvent::TimerUpdate {
+                    remaining_secs,
+                    running,
+                } => {
+                    if !running {
+                        log_window.log(format!(
+                            "Round timer stopped with {} left",
+                            format_countdown(remaining_secs)
+                        ));
+                        round_timer = RoundTimer::Idle;
+                    } else {
+                        if !matches!(round_timer, RoundTimer::Running(_)) {
+                            log_window.log(format!(
+                                "Round timer started: {}",
+                                format_countdown(remaining_secs)
+                            ));
+                        }
+                        round_timer = RoundTimer::Running(remaining_secs);
+                    }
+                    apply_led_state(&mut city, led_broken.as_deref(), round_timer);
+                }
+
+                GameEvent::TimerExpired => {
+                    round_timer = RoundTimer::Expired;
+                    apply_led_state(&mut city, led_broken.as_deref(), round_timer);
+                    log_window.log_warning("Round time is up");
+                }
+
+                GameEvent::LogMessage { level, message } => {
+                    log_window.log_with_level(level.into(), message);
+                }
+
+                GameEvent::StateSnapshot { state } => {
+                    barrier_open = state.barrier_broken;
+                    let broken_by = state.barrier_broken_by.as_deref();
+                    city.set_barrier_broken_by(broken_by.map(|team| teams.get_or_register(team)));
+                    set_led_alert(&mut city, LED_DANGER_TEXT, state.danger_mode_active);
+                    led_broken = state.led_broken.clone();
+                    round_timer = state
+                        .timer_remaining_secs
+                        .map_or(RoundTimer::Idle, RoundTimer::Running);
+                    apply_led_state(&mut city, led_broken.as_deref(), round_timer);
+                    all_lights_red = state.emergency_stop_active;
+                    apply_buildings_snapshot(&mut city, &state, &mut teams);
+                    rain.set_intensity(state.rain_intensity);
+                    city.set_speed_factor(rain.speed_factor());
+                    let mut scored: Vec<_> = state.team_scores.keys().collect();
+                    scored.sort();
+                    for team in scored {
+                        teams.get_or_register(team);
+                    }
+                    scoreboard.replace(state.team_scores);
+                    log_window.log("Synchronized with server state");
                 }
 
                 GameEvent::ConnectionStatus { connected, error } => {
@@ -229,44 +833,61 @@ async fn main() -> Result<(), macroquad::Error> {
                     } else if let Some(err) = error {
                         // Only log first connection attempt and actual errors
                         if !err.contains("Connecting to server") {
-                            log_window.log(format!("Server: {}", err));
+                            log_window.log_warning(format!("Server: {}", err));
                         }
                     }
                 }
+                // Only there to show the stream is alive
+                GameEvent::Heartbeat { .. } => {}
             }
         }
 
-        // Log emergency traffic stop state changes
+        // Log emergency traffic stop state changes and show them on the LED display
         if all_lights_red && !previous_all_lights_red {
-            log_window.log("EMERGENCY: All traffic lights forced to RED");
+            log_window.log_critical("EMERGENCY: All traffic lights forced to RED");
+            set_led_alert(&mut city, LED_EMERGENCY_TEXT, true);
         } else if !all_lights_red && previous_all_lights_red {
             log_window.log("Emergency traffic stop deactivated");
+            set_led_alert(&mut city, LED_EMERGENCY_TEXT, false);
         }
 
-        // Log danger mode state changes
-        if danger_mode && !previous_danger_mode {
-            log_window.log("LED Display: DANGER MODE ACTIVATED");
-        } else if !danger_mode && previous_danger_mode {
-            log_window.log("LED Display: Normal operation resumed");
+        // Handle danger warning toggle
+        if toggle_danger {
+            if city.led_has_message(LED_DANGER_TEXT) {
+                set_led_alert(&mut city, LED_DANGER_TEXT, false);
+                audio.handle_event(&GameEvent::DangerModeDeactivated);
+                log_window.log("LED Display: Normal operation resumed");
+            } else {
+                set_led_alert(&mut city, LED_DANGER_TEXT, true);
+                audio.handle_event(&GameEvent::DangerModeActivated {
+                    reason: "Operator".to_string(),
+                });
+                log_window.log_critical("LED Display: DANGER MODE ACTIVATED");
+            }
         }
 
         // Handle SCADA toggle for all buildings
         if toggle_scada {
             city.toggle_all_scada();
-            log_window.log("SCADA systems toggled on all buildings");
+            log_window.log_warning("SCADA systems toggled on all buildings");
         }
 
-        // Handle SCADA reset
-        if reset_scada {
+        // Handle SCADA and danger warning reset
+        if reset {
             city.reset_all_scada();
             log_window.log("All SCADA systems reset to working state");
+            if city.led_has_message(LED_DANGER_TEXT) {
+                set_led_alert(&mut city, LED_DANGER_TEXT, false);
+                audio.handle_event(&GameEvent::DangerModeDeactivated);
+                log_window.log("LED Display: Normal operation resumed");
+            }
         }
 
         // Handle barrier toggle
         if toggle_barrier {
             barrier_open = !barrier_open;
             if barrier_open {
-                log_window.log("Barrier gate OPENED");
+                log_window.log_warning("Barrier gate OPENED");
             } else {
                 log_window.log("Barrier gate CLOSED");
             }
@@ -274,51 +895,88 @@ async fn main() -> Result<(), macroquad::Error> {
 
         // Update previous states for next frame
         previous_all_lights_red = all_lights_red;
-        previous_danger_mode = danger_mode;
 
         // --------------------------------------------------------------------
         // Window Resize Handling
         // --------------------------------------------------------------------
 
+        let old_size = window_state.size();
         if window_state.check_resize(RESIZE_THRESHOLD) {
-            // Clear all cars on resize to prevent positioning issues
-            // Cars will naturally respawn at correct positions
-            city.clear_cars();
+            // Move cars back into their lanes; only those that can't be
+            // placed again are removed. Pedestrians are cleared and will
+            // naturally respawn at correct positions
+            city.handle_resize(old_size, window_state.size());
+            city.clear_pedestrians();
 
             // Regenerate all blocks with new screen dimensions
             // Since ROAD_WIDTH is in pixels, percentage calculations need to be updated
-            city.clear_blocks();
-
-            // Recreate grass blocks with updated percentages
-            let grass_blocks = generate_grass_blocks();
-            for grass_block in grass_blocks {
-                city.add_block(grass_block);
-            }
-
-            // Recreate LED display block with updated percentages
-            city.add_block(create_led_display_block());
+            city.regenerate_blocks(add_blocks);
+            apply_led_state(&mut city, led_broken.as_deref(), round_timer);
         }
 
         // --------------------------------------------------------------------
         // Update Phase
         // --------------------------------------------------------------------
 
+        // Barrier arms animate toward the current state during the update
+        city.set_barrier_state(barrier_open);
+        debug_overlay.begin_phase("update");
         city.update(dt, all_lights_red);
+        debug_overlay.end_phase("update");
+        if clock.stepped() {
+            log_window.log(format!("Frame {} ({:.2}s)", clock.frame(), clock.time()));
+            for line in city.step_report() {
+                log_window.log(line);
+            }
+        }
+        audio.set_traffic(city.cars.len());
+        rain.update(dt);
+        minimap.update(dt);
+        time_of_day.advance(dt);
+        city.set_windows_lit(time_of_day.is_night());
 
         // --------------------------------------------------------------------
         // Render Phase
         // --------------------------------------------------------------------
 
-        // Clear screen with road color
-        clear_background(ROAD_COLOR);
-
-        // Render in layers: environment -> traffic -> overlays
-        city.render_environment(current_time, danger_mode, barrier_open);
-        city.render_traffic(all_lights_red);
-        city.render_overlays(current_time, danger_mode, barrier_open);
-
-        // Render log window overlay
+        // Clear screen with road color, darkened and tinted blue at night
+        clear_background(rendering::shade(ROAD_COLOR, time_of_day.ambient()));
+
+        // Render the city through the camera in layers:
+        // environment -> traffic -> overlays
+        set_camera(&camera.to_camera2d());
+        debug_overlay.begin_phase("environment");
+        city.render_environment(current_time, &time_of_day);
+        debug_overlay.end_phase("environment");
+        debug_overlay.begin_phase("traffic");
+        city.render_traffic(current_time, all_lights_red, &time_of_day);
+        debug_overlay.end_phase("traffic");
+        if show_queues {
+            city.render_queue_lengths();
+        }
+        if debug_overlay.is_visible() {
+            city.render_debug_bounds();
+        }
+        city.render_particles();
+        debug_overlay.begin_phase("overlays");
+        city.render_overlays(current_time, &time_of_day);
+        debug_overlay.end_phase("overlays");
+        selector.render_highlight(&city);
+
+        // Rain and the UI stay fixed on screen
+        set_default_camera();
+        rain.render();
+
+        // Render selection, scoreboard, clock, log window and minimap overlays
+        selector.render(&city);
+        scoreboard.render(&teams);
+        clock.render();
+        debug_overlay.begin_phase("log window");
         log_window.render();
+        debug_overlay.end_phase("log window");
+        minimap.render(&city.minimap_state(all_lights_red));
+        debug_overlay.render(&city);
+        debug_overlay.end_frame(get_frame_time());
 
         // Present frame and wait for next
         next_frame().await;
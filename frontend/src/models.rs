@@ -2,10 +2,25 @@
 //!
 //! This module defines the fundamental structures used throughout the application:
 //! - Car: Represents vehicles moving through the city
+//! - VehicleType: Regular traffic or emergency vehicles
+//! - VehicleStyle: Sedan, truck, bus or police car, with its size and speed
+//! - TurnPath: The arc a turning car follows through an intersection
+//! - RoundaboutPath: The loop a car follows around a roundabout's island
+//! - Pedestrian: People walking along block edges and across crosswalks
 //! - Intersection: Represents road crossings with traffic lights
 //! - Direction: Cardinal directions for vehicle movement
+//! - TurnSignalState: Which turn signal a car blinks before turning
+//! - TrafficRule: Which side of the road cars keep to
 
+use crate::constants::pedestrian::PEDESTRIAN_SPEED;
+use crate::constants::vehicle::*;
+use crate::screen::{screen_height, screen_width};
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Next ID handed out by `Car::next_id`
+static NEXT_CAR_ID: AtomicUsize = AtomicUsize::new(0);
 
 // ============================================================================
 // Car Model
@@ -15,8 +30,11 @@ use macroquad::prelude::*;
 ///
 /// Cars store their position as percentages (0.0-1.0) of screen dimensions
 /// to support dynamic window resizing without position corruption.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Car {
+    /// Unique identifier, stable for the car's lifetime
+    pub id: usize,
+
     /// Horizontal position as percentage of screen width (0.0 = left, 1.0 = right)
     pub x_percent: f32,
 
@@ -26,7 +44,22 @@ pub struct Car {
     /// Current direction of travel (Down, Right, Up, or Left)
     pub direction: Direction,
 
+    /// Current speed in pixels per second (0.0 = stopped, up to the kind's
+    /// `max_speed`)
+    pub velocity: f32,
+
+    /// Speed the car is accelerating or braking toward, as decided on the
+    /// last frame (pixels per second)
+    pub target_velocity: f32,
+
+    /// Regular car or emergency vehicle
+    pub vehicle_type: VehicleType,
+
+    /// Body style, which sets the car's size, speed and looks
+    pub style: VehicleStyle,
+
     /// Visual color of the car body
+    #[serde(with = "crate::snapshot::color")]
     pub color: Color,
 
     /// Index of the road this car is currently on
@@ -42,8 +75,31 @@ pub struct Car {
     /// (prevents stopping mid-intersection)
     pub in_intersection: bool,
 
+    /// Progress along the turn arc while the car is turning (None = driving
+    /// straight along `direction`)
+    pub turn: Option<TurnProgress>,
+
+    /// Turn signal blinking as the car nears the intersection where it
+    /// turns, until the turn is complete (None = off)
+    pub turn_signal: Option<TurnSignalState>,
+
+    /// How far the car has pulled toward the road edge to let an emergency
+    /// vehicle pass (pixels, 0.0 = in its lane)
+    pub pull_over: f32,
+
     /// Logical location metadata (which road/intersection/block the car is in)
     pub location: CarLocation,
+
+    /// Block the car is driving to, such as a guarded compound it visits
+    /// (None = just passing through)
+    pub destination: Option<usize>,
+
+    /// IDs of the intersections the car still drives through on a planned
+    /// route, next one first (empty = turning at random)
+    pub route: Vec<usize>,
+
+    /// Side of the road the car keeps to, fixed when it is spawned
+    pub traffic_rule: TrafficRule,
 }
 
 impl Car {
@@ -78,6 +134,396 @@ impl Car {
     pub fn set_y(&mut self, y: f32) {
         self.y_percent = y / screen_height();
     }
+
+    /// Allocates an ID no other car has had
+    pub fn next_id() -> usize {
+        NEXT_CAR_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Keeps `next_id` from handing out an ID already in use, such as that
+    /// of a car restored from a snapshot
+    ///
+    /// # Arguments
+    /// * `id` - ID in use; later IDs are all greater
+    pub fn reserve_id(id: usize) {
+        NEXT_CAR_ID.fetch_max(id + 1, Ordering::Relaxed);
+    }
+
+    /// Length of the car body along its direction of travel, in pixels
+    pub fn length(&self) -> f32 {
+        self.style.size().0
+    }
+
+    /// Width of the car body across its direction of travel, in pixels
+    pub fn width(&self) -> f32 {
+        self.style.size().1
+    }
+
+    /// Top speed in pixels per second, for the car's type and style
+    pub fn max_speed(&self) -> f32 {
+        self.vehicle_type.max_speed() * self.style.speed_factor()
+    }
+
+    /// Screen area covered by the car body, in pixels
+    ///
+    /// While turning the car is rotated, so the box is a square that fits
+    /// it at any angle.
+    pub fn bounds(&self) -> Rect {
+        let (length, width) = (self.length(), self.width());
+        let (width, height) = match (&self.turn, self.direction) {
+            (Some(_), _) => (length, length),
+            (None, Direction::Down | Direction::Up) => (width, length),
+            (None, Direction::Left | Direction::Right) => (length, width),
+        };
+        Rect::new(self.x() - width / 2.0, self.y() - height / 2.0, width, height)
+    }
+
+    /// Checks if a point is on the car
+    ///
+    /// # Arguments
+    /// * `px` - X coordinate in pixels
+    /// * `py` - Y coordinate in pixels
+    pub fn contains_point(&self, px: f32, py: f32) -> bool {
+        self.bounds().contains(vec2(px, py))
+    }
+
+    /// Angle the car is facing in radians (0 = right, clockwise positive)
+    ///
+    /// Follows the path through the intersection while turning.
+    pub fn heading(&self) -> f32 {
+        match &self.turn {
+            Some(turn) => turn.path.heading_at(turn.t),
+            None => self.direction.angle(),
+        }
+    }
+}
+
+// ============================================================================
+// Vehicle Type
+// ============================================================================
+
+/// What kind of vehicle a car is, which decides how it behaves in traffic
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VehicleType {
+    /// Regular traffic: obeys lights and yields to emergency vehicles
+    #[default]
+    Car,
+
+    /// Ambulance or fire truck: faster, turns the lights ahead of it green,
+    /// and other cars pull over to let it pass
+    EmergencyVehicle,
+}
+
+impl VehicleType {
+    /// Top speed in pixels per second
+    pub fn max_speed(self) -> f32 {
+        match self {
+            VehicleType::Car => CAR_SPEED,
+            VehicleType::EmergencyVehicle => CAR_SPEED * EMERGENCY_SPEED_FACTOR,
+        }
+    }
+}
+
+// ============================================================================
+// Vehicle Style
+// ============================================================================
+
+/// Body style of a car, which decides its size, top speed and looks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VehicleStyle {
+    /// Ordinary passenger car
+    #[default]
+    Sedan,
+
+    /// Long and slow, drawn as a cab pulling a trailer
+    Truck,
+
+    /// Longest and slowest, drawn with rows of side windows
+    Bus,
+
+    /// Slightly faster patrol car with a light bar (not an emergency vehicle)
+    Police,
+}
+
+impl VehicleStyle {
+    /// Every style, in the order of `STYLE_SPAWN_WEIGHTS`
+    pub const ALL: [VehicleStyle; 4] = [
+        VehicleStyle::Sedan,
+        VehicleStyle::Truck,
+        VehicleStyle::Bus,
+        VehicleStyle::Police,
+    ];
+
+    /// Length and width of the body in pixels
+    pub fn size(self) -> (f32, f32) {
+        match self {
+            VehicleStyle::Sedan => SEDAN_SIZE,
+            VehicleStyle::Truck => TRUCK_SIZE,
+            VehicleStyle::Bus => BUS_SIZE,
+            VehicleStyle::Police => POLICE_SIZE,
+        }
+    }
+
+    /// Top speed as a multiple of the vehicle type's top speed
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            VehicleStyle::Sedan => SEDAN_SPEED_FACTOR,
+            VehicleStyle::Truck => TRUCK_SPEED_FACTOR,
+            VehicleStyle::Bus => BUS_SPEED_FACTOR,
+            VehicleStyle::Police => POLICE_SPEED_FACTOR,
+        }
+    }
+}
+
+// ============================================================================
+// Turn Path
+// ============================================================================
+
+/// Quarter-circle arc from an entry lane to an exit lane, in pixels
+///
+/// The arc starts tangent to the entry direction and ends tangent to the
+/// exit direction, so position and heading are continuous at both ends.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurnPath {
+    /// Center of the circle the arc lies on
+    pub center: Vec2,
+
+    /// Vector from the center to the start of the arc
+    pub start_radius: Vec2,
+
+    /// Vector from the center to the end of the arc
+    pub end_radius: Vec2,
+
+    /// Direction of travel once the turn is complete
+    pub exit_direction: Direction,
+}
+
+impl TurnPath {
+    /// Builds the arc joining two perpendicular lanes
+    ///
+    /// # Arguments
+    /// * `start` - Point on the entry lane where the turn begins
+    /// * `corner` - Point where the entry and exit lane center lines cross
+    /// * `end` - Point on the exit lane where the turn ends; must be as far
+    ///   from `corner` as `start` is
+    /// * `exit_direction` - Direction of travel on the exit lane
+    pub fn new(start: Vec2, corner: Vec2, end: Vec2, exit_direction: Direction) -> Self {
+        let center = start + end - corner;
+        Self {
+            center,
+            start_radius: start - center,
+            end_radius: end - center,
+            exit_direction,
+        }
+    }
+
+    /// Length of the arc in pixels
+    pub fn length(&self) -> f32 {
+        self.start_radius.length() * std::f32::consts::FRAC_PI_2
+    }
+
+    /// Position along the arc at `t` (0.0 = start, 1.0 = end)
+    pub fn position_at(&self, t: f32) -> Vec2 {
+        let angle = t.clamp(0.0, 1.0) * std::f32::consts::FRAC_PI_2;
+        self.center + self.start_radius * angle.cos() + self.end_radius * angle.sin()
+    }
+
+    /// Heading of the arc's tangent at `t` in radians, as in `Car::heading`
+    pub fn heading_at(&self, t: f32) -> f32 {
+        let angle = t.clamp(0.0, 1.0) * std::f32::consts::FRAC_PI_2;
+        let tangent = self.end_radius * angle.cos() - self.start_radius * angle.sin();
+        tangent.y.atan2(tangent.x)
+    }
+}
+
+/// Points on the circle around a roundabout's island, in pixels
+///
+/// The car drives from point to point in order, from where its entry lane
+/// meets the circle to where its exit lane leaves it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoundaboutPath {
+    /// Points the car drives through, at least two
+    pub path: Vec<(f32, f32)>,
+
+    /// Direction of travel once out of the roundabout
+    pub exit_direction: Direction,
+}
+
+impl RoundaboutPath {
+    /// Length of the path in pixels
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(from, to)| from.distance(to)).sum()
+    }
+
+    /// Straight pieces between consecutive points
+    fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.path
+            .windows(2)
+            .map(|pair| (Vec2::from(pair[0]), Vec2::from(pair[1])))
+    }
+
+    /// Piece of the path the car is on at `t`, and how far along it (0.0-1.0)
+    fn segment_at(&self, t: f32) -> (Vec2, Vec2, f32) {
+        let mut left = t.clamp(0.0, 1.0) * self.length();
+        let mut last = (Vec2::ZERO, Vec2::ZERO);
+        for (from, to) in self.segments() {
+            let length = from.distance(to);
+            if left <= length && length > 0.0 {
+                return (from, to, left / length);
+            }
+            left -= length;
+            last = (from, to);
+        }
+        (last.0, last.1, 1.0)
+    }
+
+    /// Position along the path at `t` (0.0 = start, 1.0 = end)
+    pub fn position_at(&self, t: f32) -> Vec2 {
+        let (from, to, along) = self.segment_at(t);
+        from.lerp(to, along)
+    }
+
+    /// Heading of the path at `t` in radians, as in `Car::heading`
+    pub fn heading_at(&self, t: f32) -> f32 {
+        let (from, to, _) = self.segment_at(t);
+        (to - from).to_angle()
+    }
+}
+
+/// Path a car follows through an intersection instead of its lane
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CrossingPath {
+    /// Turning from one road onto the other at a junction
+    Turn(TurnPath),
+
+    /// Driving around a roundabout's island, whichever way it leaves
+    Roundabout(RoundaboutPath),
+}
+
+impl CrossingPath {
+    /// Length of the path in pixels
+    pub fn length(&self) -> f32 {
+        match self {
+            Self::Turn(path) => path.length(),
+            Self::Roundabout(path) => path.length(),
+        }
+    }
+
+    /// Position along the path at `t` (0.0 = start, 1.0 = end)
+    pub fn position_at(&self, t: f32) -> Vec2 {
+        match self {
+            Self::Turn(path) => path.position_at(t),
+            Self::Roundabout(path) => path.position_at(t),
+        }
+    }
+
+    /// Heading of the path at `t` in radians, as in `Car::heading`
+    pub fn heading_at(&self, t: f32) -> f32 {
+        match self {
+            Self::Turn(path) => path.heading_at(t),
+            Self::Roundabout(path) => path.heading_at(t),
+        }
+    }
+
+    /// Direction of travel once the path is complete
+    pub fn exit_direction(&self) -> Direction {
+        match self {
+            Self::Turn(path) => path.exit_direction,
+            Self::Roundabout(path) => path.exit_direction,
+        }
+    }
+}
+
+impl From<TurnPath> for CrossingPath {
+    fn from(path: TurnPath) -> Self {
+        Self::Turn(path)
+    }
+}
+
+impl From<RoundaboutPath> for CrossingPath {
+    fn from(path: RoundaboutPath) -> Self {
+        Self::Roundabout(path)
+    }
+}
+
+/// A car's progress through a turn or around a roundabout
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurnProgress {
+    pub path: CrossingPath,
+
+    /// Fraction of the path already travelled (0.0-1.0)
+    pub t: f32,
+}
+
+// ============================================================================
+// Pedestrian Model
+// ============================================================================
+
+/// Represents a person walking through the city
+///
+/// Pedestrians walk in a straight line along block edges, level with the
+/// crosswalks, and cross roads only at crosswalks. Like cars, positions are
+/// stored as percentages of the screen dimensions.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pedestrian {
+    /// Horizontal position as percentage of screen width
+    pub x_percent: f32,
+
+    /// Vertical position as percentage of screen height
+    pub y_percent: f32,
+
+    /// Direction of walking
+    pub direction: Direction,
+
+    /// Walking speed in pixels per second
+    pub speed: f32,
+
+    /// Whether the pedestrian is standing at a curb, waiting to cross
+    pub waiting: bool,
+
+    /// Visual color of the pedestrian
+    #[serde(with = "crate::snapshot::color")]
+    pub color: Color,
+
+    /// Center of the crosswalk the pedestrian is on, in pixels (None = on
+    /// the sidewalk)
+    pub crossing: Option<Vec2>,
+
+    /// Distance walked so far in pixels, driving the bobbing animation
+    pub distance_walked: f32,
+}
+
+impl Pedestrian {
+    /// Creates a pedestrian at an absolute pixel position, walking at the
+    /// default speed
+    ///
+    /// # Arguments
+    /// * `x` - Absolute x position in pixels
+    /// * `y` - Absolute y position in pixels
+    /// * `direction` - Direction of walking
+    /// * `color` - Visual color
+    pub fn new(x: f32, y: f32, direction: Direction, color: Color) -> Self {
+        Self {
+            x_percent: x / screen_width(),
+            y_percent: y / screen_height(),
+            direction,
+            speed: PEDESTRIAN_SPEED,
+            waiting: false,
+            color,
+            crossing: None,
+            distance_walked: 0.0,
+        }
+    }
+
+    /// Converts the percentage-based x position to absolute pixel coordinates
+    pub fn x(&self) -> f32 {
+        self.x_percent * screen_width()
+    }
+
+    /// Converts the percentage-based y position to absolute pixel coordinates
+    pub fn y(&self) -> f32 {
+        self.y_percent * screen_height()
+    }
 }
 
 // ============================================================================
@@ -89,7 +535,7 @@ impl Car {
 /// Used to determine car orientation, turning logic, and collision detection.
 /// Implements Copy for efficient passing, PartialEq for direction comparisons,
 /// Hash and Eq for use as HashMap keys.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     /// Moving downward (increasing y)
     Down,
@@ -117,6 +563,105 @@ impl Direction {
             Direction::Left => (-1.0, 0.0),
         }
     }
+
+    /// Whether the direction runs along a vertical road (Down or Up)
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::Down | Direction::Up)
+    }
+
+    /// Angle of the direction in radians (0 = right, clockwise positive, as
+    /// y grows downward on screen)
+    pub fn angle(&self) -> f32 {
+        let (dx, dy) = self.to_vector();
+        dy.atan2(dx)
+    }
+}
+
+// ============================================================================
+// Turn Signal
+// ============================================================================
+
+/// Which turn signal a car blinks, on its own left or right
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnSignalState {
+    Left,
+    Right,
+}
+
+impl TurnSignalState {
+    /// Signal for a turn, as seen from the driver's seat
+    ///
+    /// # Arguments
+    /// * `from` - Direction of travel before the turn
+    /// * `to` - Direction of travel after the turn
+    ///
+    /// # Returns
+    /// The signal to blink, or None when going straight on or back
+    pub fn for_turn(from: Direction, to: Direction) -> Option<Self> {
+        let (dx, dy) = from.to_vector();
+        let (tx, ty) = to.to_vector();
+        // The driver's right is a quarter turn clockwise on screen
+        let toward_right = tx * -dy + ty * dx;
+        if toward_right > 0.0 {
+            Some(TurnSignalState::Right)
+        } else if toward_right < 0.0 {
+            Some(TurnSignalState::Left)
+        } else {
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Traffic Rule
+// ============================================================================
+
+/// Side of the road cars keep to
+///
+/// Lanes are named as seen on screen. With left-hand traffic, cars going
+/// down use the left lane of vertical roads and cars going right the bottom
+/// lane of horizontal roads; right-hand traffic mirrors every lane, and with
+/// it which turns cut the corner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficRule {
+    /// Down = left lane, up = right lane, right = bottom lane, left = top lane
+    #[default]
+    LeftHand,
+
+    /// Down = right lane, up = left lane, right = top lane, left = bottom lane
+    RightHand,
+}
+
+impl TrafficRule {
+    /// Offset of a direction's lane from the road center line (pixels)
+    ///
+    /// # Arguments
+    /// * `direction` - Direction of travel along the road
+    pub fn lane_offset(self, direction: Direction) -> Vec2 {
+        let (dx, dy) = direction.to_vector();
+        let side = match self {
+            TrafficRule::LeftHand => 1.0,
+            TrafficRule::RightHand => -1.0,
+        };
+        vec2(-dy, dx) * LANE_OFFSET * side
+    }
+
+    /// The rule for the other side of the road
+    pub fn toggled(self) -> Self {
+        match self {
+            TrafficRule::LeftHand => TrafficRule::RightHand,
+            TrafficRule::RightHand => TrafficRule::LeftHand,
+        }
+    }
+
+    /// Name shown in the log
+    pub fn label(self) -> &'static str {
+        match self {
+            TrafficRule::LeftHand => "left-hand",
+            TrafficRule::RightHand => "right-hand",
+        }
+    }
 }
 
 // ============================================================================
@@ -127,7 +672,7 @@ impl Direction {
 ///
 /// This is metadata about which city element the car is currently in.
 /// The actual visual position is always stored in Car's x_percent/y_percent.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum CarLocation {
     /// Car is traveling on a road
     OnRoad { road_id: usize },
@@ -0,0 +1,288 @@
+//! Pedestrian spawning and walking
+//!
+//! This module handles:
+//! - PedestrianSpawner: Manages spawning at regular intervals
+//! - spawn_pedestrian: Creates pedestrians on random block corners
+//! - update_pedestrians: Walking, waiting at crosswalks and despawning
+//!
+//! Pedestrians walk along block edges level with the crosswalks, so every
+//! road they meet is crossed on a crosswalk. They only step onto one while
+//! the crossing road's traffic has a red light; once on it they keep walking
+//! and cars wait for them (see `car::check_pedestrians`).
+
+use crate::constants::{
+    pedestrian::{PEDESTRIAN_SPAWN_INTERVAL, PEDESTRIAN_SPEED, PEDESTRIAN_SPEED_VARIATION},
+    rendering::{CROSSWALK_DISTANCE, CROSSWALK_WIDTH},
+    road_network::{HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS},
+    visual::ROAD_WIDTH,
+};
+use crate::intersection::Intersection;
+use crate::models::{Direction, Pedestrian};
+use crate::screen::{screen_height, screen_width};
+use crate::spawner::SpawnTimer;
+use crate::traffic_light::LightState;
+use macroquad::prelude::*;
+
+// ============================================================================
+// PedestrianSpawner - Interval-based spawning
+// ============================================================================
+
+/// Manages pedestrian spawning at regular intervals
+///
+/// Uses the same interval timer as the car spawner.
+pub struct PedestrianSpawner {
+    timer: SpawnTimer,
+}
+
+impl PedestrianSpawner {
+    /// Creates a new PedestrianSpawner with a specified spawn interval
+    ///
+    /// # Arguments
+    /// * `interval` - Time between spawns in seconds
+    pub fn new(interval: f32) -> Self {
+        Self {
+            timer: SpawnTimer::new(interval),
+        }
+    }
+
+    /// Attempts to spawn a pedestrian if enough time has elapsed
+    ///
+    /// # Arguments
+    /// * `dt` - Simulation time step in seconds
+    /// * `pedestrians` - Mutable vector to add the new pedestrian to
+    ///
+    /// # Returns
+    /// `true` if a pedestrian was spawned
+    pub fn try_spawn(&mut self, dt: f32, pedestrians: &mut Vec<Pedestrian>) -> bool {
+        if self.timer.tick(dt) {
+            spawn_pedestrian(pedestrians);
+            return true;
+        }
+        false
+    }
+}
+
+impl Default for PedestrianSpawner {
+    fn default() -> Self {
+        Self::new(PEDESTRIAN_SPAWN_INTERVAL)
+    }
+}
+
+// ============================================================================
+// Pedestrian Spawning
+// ============================================================================
+
+/// Spawns a new pedestrian on a random block corner
+///
+/// The corner is one of the four around a random intersection, where the
+/// lines through its crosswalks meet, and the pedestrian sets off in a
+/// random direction along a block edge, at a walking pace of its own.
+///
+/// # Arguments
+/// * `pedestrians` - Mutable vector to add the new pedestrian to
+pub fn spawn_pedestrian(pedestrians: &mut Vec<Pedestrian>) {
+    let road_x = VERTICAL_ROAD_POSITIONS[rand::gen_range(0, VERTICAL_ROAD_POSITIONS.len())]
+        * screen_width();
+    let road_y = HORIZONTAL_ROAD_POSITIONS[rand::gen_range(0, HORIZONTAL_ROAD_POSITIONS.len())]
+        * screen_height();
+
+    let side = |positive: bool| if positive { 1.0 } else { -1.0 };
+    let corner_x = road_x + side(rand::gen_range(0, 2) == 0) * CROSSWALK_DISTANCE;
+    let corner_y = road_y + side(rand::gen_range(0, 2) == 0) * CROSSWALK_DISTANCE;
+
+    let directions = [Direction::Down, Direction::Right, Direction::Up, Direction::Left];
+    let direction = directions[rand::gen_range(0, directions.len())];
+
+    let colors = [
+        Color::new(0.9, 0.3, 0.3, 1.0),
+        Color::new(0.3, 0.5, 0.9, 1.0),
+        Color::new(0.95, 0.8, 0.3, 1.0),
+        Color::new(0.6, 0.4, 0.8, 1.0),
+        Color::new(0.95, 0.95, 0.95, 1.0),
+    ];
+    let color = colors[rand::gen_range(0, colors.len())];
+
+    let mut pedestrian = Pedestrian::new(corner_x, corner_y, direction, color);
+    let variation = rand::gen_range(-PEDESTRIAN_SPEED_VARIATION, PEDESTRIAN_SPEED_VARIATION);
+    pedestrian.speed = PEDESTRIAN_SPEED * (1.0 + variation);
+    pedestrians.push(pedestrian);
+}
+
+// ============================================================================
+// Crosswalks
+// ============================================================================
+
+/// Finds the crosswalk of an intersection that lies on a pedestrian's path
+///
+/// # Arguments
+/// * `position` - Pedestrian position in pixels
+/// * `direction` - Direction of walking
+/// * `intersection` - Intersection whose four crosswalks are checked
+///
+/// # Returns
+/// The center of the crosswalk the pedestrian walks straight across, if any
+fn crosswalk_on_path(
+    position: Vec2,
+    direction: Direction,
+    intersection: &Intersection,
+) -> Option<Vec2> {
+    let center = vec2(intersection.x(), intersection.y());
+    let (dx, dy) = direction.to_vector();
+    let side = vec2(-dy, dx);
+
+    [
+        vec2(0.0, -CROSSWALK_DISTANCE),
+        vec2(0.0, CROSSWALK_DISTANCE),
+        vec2(-CROSSWALK_DISTANCE, 0.0),
+        vec2(CROSSWALK_DISTANCE, 0.0),
+    ]
+    .into_iter()
+    .map(|offset| center + offset)
+    .find(|crosswalk| (*crosswalk - position).dot(side).abs() < CROSSWALK_WIDTH / 2.0)
+}
+
+/// Checks if pedestrians walking in `direction` may step onto a crosswalk
+///
+/// They cross while the road's traffic has a red light, everywhere in
+/// emergency stop mode, and whenever the intersection has no working light,
+/// since cars always stop for pedestrians already on a crosswalk.
+fn may_cross(intersection: &Intersection, direction: Direction, all_lights_red: bool) -> bool {
+    if all_lights_red {
+        return true;
+    }
+
+    // Traffic on the road being crossed runs perpendicular to the pedestrian
+    let traffic_direction = if direction.is_vertical() {
+        Direction::Right
+    } else {
+        Direction::Down
+    };
+    !matches!(
+        intersection.get_light_state_for_direction(traffic_direction),
+        Some(LightState::Green(_) | LightState::Yellow(_))
+    )
+}
+
+// ============================================================================
+// Main Update Loop
+// ============================================================================
+
+/// Walks one pedestrian for one frame
+///
+/// The pedestrian stops at the curb in front of any crosswalk it may not
+/// step onto yet, waiting there until it may, and never stops once on a
+/// crosswalk.
+fn walk(
+    pedestrian: &mut Pedestrian,
+    intersections: &[Intersection],
+    dt: f32,
+    all_lights_red: bool,
+) {
+    let position = vec2(pedestrian.x(), pedestrian.y());
+    let (dx, dy) = pedestrian.direction.to_vector();
+    let forward = vec2(dx, dy);
+
+    let full_step = pedestrian.speed * dt;
+    let mut step = full_step;
+    for intersection in intersections {
+        let Some(crosswalk) = crosswalk_on_path(position, pedestrian.direction, intersection) else {
+            continue;
+        };
+        // Allow for rounding while standing right at the curb
+        let to_curb = (crosswalk - position).dot(forward) - ROAD_WIDTH / 2.0;
+        if to_curb > -0.01 && !may_cross(intersection, pedestrian.direction, all_lights_red) {
+            step = step.min(to_curb.max(0.0));
+        }
+    }
+
+    let position = position + forward * step;
+    pedestrian.x_percent = position.x / screen_width();
+    pedestrian.y_percent = position.y / screen_height();
+    pedestrian.distance_walked += step;
+    pedestrian.waiting = step < full_step;
+    pedestrian.crossing = intersections.iter().find_map(|intersection| {
+        crosswalk_on_path(position, pedestrian.direction, intersection)
+            .filter(|crosswalk| (*crosswalk - position).dot(forward).abs() < ROAD_WIDTH / 2.0)
+    });
+}
+
+/// Checks if a pedestrian is still on screen
+fn is_pedestrian_on_screen(pedestrian: &Pedestrian) -> bool {
+    (0.0..=1.0).contains(&pedestrian.x_percent) && (0.0..=1.0).contains(&pedestrian.y_percent)
+}
+
+/// Updates all pedestrians for one frame
+///
+/// # Arguments
+/// * `pedestrians` - Mutable vector of all pedestrians
+/// * `intersections` - All intersections with traffic lights
+/// * `dt` - Delta time (frame duration in seconds)
+/// * `all_lights_red` - Emergency mode flag (pedestrians cross everywhere)
+///
+/// # Returns
+/// Number of pedestrians removed for leaving the screen
+pub fn update_pedestrians(
+    pedestrians: &mut Vec<Pedestrian>,
+    intersections: &[Intersection],
+    dt: f32,
+    all_lights_red: bool,
+) -> usize {
+    let before = pedestrians.len();
+    pedestrians.retain_mut(|pedestrian| {
+        walk(pedestrian, intersections, dt, all_lights_red);
+        is_pedestrian_on_screen(pedestrian)
+    });
+    before - pedestrians.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traffic_light::IntersectionTrafficLight;
+
+    /// Intersection at the center of the 800x600 test screen
+    fn intersection_with_vertical_state(state: LightState) -> Intersection {
+        let mut intersection = Intersection::new(0.5, 0.5, 0);
+        let mut light = IntersectionTrafficLight::new(0.5, 0.5, 0, true);
+        light.vertical_state = state;
+        intersection.set_light(light);
+        intersection
+    }
+
+    /// Pedestrian walking right toward the crosswalk north of the test
+    /// intersection, 2 pixels from its curb
+    fn pedestrian_at_curb() -> Pedestrian {
+        let x = 400.0 - ROAD_WIDTH / 2.0 - 2.0;
+        Pedestrian::new(x, 300.0 - CROSSWALK_DISTANCE, Direction::Right, RED)
+    }
+
+    #[test]
+    fn test_pedestrian_waits_on_green() {
+        let intersections = [intersection_with_vertical_state(LightState::default_green())];
+        let mut pedestrians = vec![pedestrian_at_curb()];
+
+        for _ in 0..60 {
+            update_pedestrians(&mut pedestrians, &intersections, 1.0 / 60.0, false);
+        }
+        assert!((pedestrians[0].x() - (400.0 - ROAD_WIDTH / 2.0)).abs() < 0.01);
+        assert_eq!(pedestrians[0].crossing, None);
+        assert!(pedestrians[0].waiting);
+
+        // Emergency stop mode turns every light red
+        update_pedestrians(&mut pedestrians, &intersections, 1.0 / 60.0, true);
+        assert_eq!(pedestrians[0].crossing, Some(vec2(400.0, 300.0 - CROSSWALK_DISTANCE)));
+    }
+
+    #[test]
+    fn test_pedestrian_crosses_on_red() {
+        let intersections = [intersection_with_vertical_state(LightState::default_red())];
+        let mut pedestrians = vec![pedestrian_at_curb()];
+
+        for _ in 0..60 {
+            update_pedestrians(&mut pedestrians, &intersections, 1.0 / 60.0, false);
+        }
+        assert!(pedestrians[0].x() > 400.0 - ROAD_WIDTH / 2.0 + 10.0);
+        assert!(pedestrians[0].crossing.is_some());
+        assert!(!pedestrians[0].waiting);
+    }
+}
@@ -14,6 +14,8 @@ use macroquad::prelude::*;
 /// For each intersection, draws:
 /// - Subtle white box outline marking the intersection area
 /// - Zebra-striped crosswalks on all 4 sides (top, bottom, left, right)
+/// - At roundabouts, a grassy island with a curb and dashed lane markings
+///   curving around it
 ///
 /// # Arguments
 /// * `intersections` - All intersections to draw markings for
@@ -91,6 +93,39 @@ pub fn draw_intersection_markings(intersections: &[Intersection]) {
             );
             stripe_y += CROSSWALK_STRIPE_WIDTH + CROSSWALK_STRIPE_GAP;
         }
+
+        if let Some(radius) = intersection.roundabout_radius() {
+            draw_roundabout(int_x, int_y, radius);
+        }
+    }
+}
+
+/// Draws a roundabout's central island and the markings around it
+///
+/// The island fills the circle inside the lane cars drive around, and the
+/// outer edge of that lane is marked with dashes along the curve.
+///
+/// # Arguments
+/// * `x` - Center X position in pixels
+/// * `y` - Center Y position in pixels
+/// * `radius` - Radius of the circle cars drive along
+fn draw_roundabout(x: f32, y: f32, radius: f32) {
+    use crate::constants::vehicle::LANE_OFFSET;
+
+    let island = (radius - LANE_OFFSET).max(LANE_OFFSET / 2.0);
+    draw_circle(x, y, island, GRASS_COLOR);
+    draw_circle_lines(x, y, island, LINE_WIDTH, INTERSECTION_MARK_COLOR);
+
+    // Dashes of about DASH_LENGTH with DASH_GAP between them
+    let edge = radius + LANE_OFFSET;
+    let dashes = (std::f32::consts::TAU * edge / (DASH_LENGTH + DASH_GAP)).round().max(1.0);
+    let dash_angle = std::f32::consts::TAU / dashes;
+    let dash_sweep = dash_angle * DASH_LENGTH / (DASH_LENGTH + DASH_GAP);
+    for dash in 0..dashes as usize {
+        let start = dash as f32 * dash_angle;
+        let from = vec2(x, y) + Vec2::from_angle(start) * edge;
+        let to = vec2(x, y) + Vec2::from_angle(start + dash_sweep) * edge;
+        draw_line(from.x, from.y, to.x, to.y, LINE_WIDTH, INTERSECTION_MARK_COLOR);
     }
 }
 
@@ -14,6 +14,17 @@ use macroquad::prelude::*;
 // Configurable LED Display API (for BlockObjects)
 // ============================================================================
 
+/// Seconds scrolling text takes to scroll through the display once
+///
+/// After this long the text is back where it started, at the left edge.
+///
+/// # Arguments
+/// * `text` - Text as drawn with `draw_led_display_at`
+pub fn scroll_duration(text: &str) -> f32 {
+    let text_width_dots = text.len() * (LED_CHAR_WIDTH + LED_CHAR_SPACING);
+    text_width_dots as f32 * (LED_DOT_SIZE + LED_SPACING) / LED_SCROLL_SPEED
+}
+
 /// Draws an LED display at a specific position with custom configuration
 ///
 /// This is the core rendering function used by LED Display BlockObjects.
@@ -88,23 +99,41 @@ pub fn draw_led_display_at(
     let cols = (matrix_width / dot_pitch) as usize;
     let rows = (matrix_height / dot_pitch) as usize;
 
-    // Draw LED matrix background (all dots dim)
+    let is_broken = matches!(mode, LEDDisplayMode::Broken);
+    let glitch_tick = (time * LED_GLITCH_RATE as f64) as u32;
+
+    // Draw LED matrix background (all dots dim, with static noise when broken)
     for row in 0..rows {
         for col in 0..cols {
             let dot_x = x + LED_PADDING + (col as f32 * dot_pitch);
             let dot_y = y + LED_PADDING + (row as f32 * dot_pitch);
             draw_rectangle(dot_x, dot_y, LED_DOT_SIZE, LED_DOT_SIZE, theme.off_color);
+
+            if is_broken {
+                let noise = glitch_noise(row as u32, col as u32, glitch_tick);
+                if noise < LED_NOISE_DENSITY {
+                    let brightness = 0.3 + 0.7 * noise / LED_NOISE_DENSITY;
+                    let color = Color::new(
+                        theme.on_color.r,
+                        theme.on_color.g,
+                        theme.on_color.b,
+                        brightness,
+                    );
+                    draw_rectangle(dot_x, dot_y, LED_DOT_SIZE, LED_DOT_SIZE, color);
+                }
+            }
         }
     }
 
     // Show text based on mode
     let show_text = match mode {
         LEDDisplayMode::Flashing => ((time * LED_FLASH_SPEED as f64) % 1.0) > 0.5,
+        LEDDisplayMode::Broken => glitch_noise(u32::MAX, 0, glitch_tick) >= LED_BLACKOUT_CHANCE,
         _ => true,
     };
 
     if show_text {
-        let is_scrolling = matches!(mode, LEDDisplayMode::Scrolling);
+        let is_scrolling = matches!(mode, LEDDisplayMode::Scrolling | LEDDisplayMode::Broken);
         let scroll_speed = if is_scrolling { LED_SCROLL_SPEED } else { 0.0 };
 
         let start_col = if !is_scrolling {
@@ -133,7 +162,13 @@ pub fn draw_led_display_at(
                     start_col + (char_idx * (LED_CHAR_WIDTH + LED_CHAR_SPACING)) as i32
                 };
 
-                let pattern = get_led_char_pattern(c);
+                let pattern = if is_broken
+                    && glitch_noise(char_idx as u32, u32::MAX, glitch_tick) < LED_GLITCH_CHANCE
+                {
+                    garbled_pattern(char_idx as u32, glitch_tick)
+                } else {
+                    get_led_char_pattern(c)
+                };
 
                 for row in 0..LED_CHAR_HEIGHT {
                     for col in 0..LED_CHAR_WIDTH {
@@ -179,6 +214,29 @@ pub fn draw_led_display_at(
     draw_pole(x + width - pole_spacing, pole_start_y);
 }
 
+/// Deterministic pseudo-random value in [0, 1) for a glitch frame
+///
+/// Hashing instead of drawing from the global RNG keeps a glitch stable for
+/// the whole frame and independent of how often the display is rendered.
+fn glitch_noise(a: u32, b: u32, tick: u32) -> f32 {
+    let mut hash = a
+        .wrapping_mul(0x9E37_79B1)
+        ^ b.wrapping_mul(0x85EB_CA77)
+        ^ tick.wrapping_mul(0xC2B2_AE3D);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^= hash >> 12;
+    (hash >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Random 5x7 pattern shown in place of a garbled character
+fn garbled_pattern(char_idx: u32, tick: u32) -> [u8; LED_CHAR_HEIGHT] {
+    std::array::from_fn(|row| {
+        let noise = glitch_noise(char_idx, row as u32, tick);
+        (noise * (1 << LED_CHAR_WIDTH) as f32) as u8
+    })
+}
+
 fn draw_screw(x: f32, y: f32) {
     draw_circle(x, y, SCREW_SIZE / 2.0, SCREW_COLOR);
     draw_circle(x, y, SCREW_SIZE / 4.0, SCREW_CENTER_COLOR);
@@ -194,3 +252,32 @@ fn draw_pole(x: f32, y: f32) {
         POLE_DEPTH_COLOR,
     );
 }
+
+/// Draws a single character as a 5x7 grid of lit LED dots
+///
+/// Only the lit dots are drawn, so the character can be placed on any
+/// background, e.g. as a small icon.
+///
+/// # Arguments
+/// * `c` - Character to draw
+/// * `x` - Left edge in pixels
+/// * `y` - Top edge in pixels
+/// * `dot_size` - Size of each dot in pixels
+/// * `dot_pitch` - Distance between neighboring dots in pixels
+/// * `color` - Color of the lit dots
+pub fn draw_led_char(c: char, x: f32, y: f32, dot_size: f32, dot_pitch: f32, color: Color) {
+    let pattern = get_led_char_pattern(c);
+    for (row, bits) in pattern.iter().enumerate() {
+        for col in 0..LED_CHAR_WIDTH {
+            if bits & (1 << (LED_CHAR_WIDTH - 1 - col)) != 0 {
+                draw_rectangle(
+                    x + col as f32 * dot_pitch,
+                    y + row as f32 * dot_pitch,
+                    dot_size,
+                    dot_size,
+                    color,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Minimap overlay
+//!
+//! This module handles:
+//! - MinimapState: Positions of the roads, blocks, cars, intersection
+//!   lights and compromised buildings, collected from the city
+//! - Minimap: Scaled-down map of the city in the bottom-right corner
+//! - Flashing markers where recent events happened, fading over
+//!   `EVENT_MARKER_LIFETIME` seconds
+//! - Showing and hiding the minimap with the M key
+//!
+//! Positions in `MinimapState` and of event markers are fractions of the
+//! screen (0.0-1.0), like the positions the city stores, so the map scales
+//! to any screen size.
+
+use crate::constants::visual::ROAD_WIDTH;
+use crate::road::Orientation;
+use crate::screen::{screen_height, screen_width};
+use crate::traffic_light::LightState;
+use macroquad::prelude::*;
+
+/// Size of the map in pixels
+const MAP_WIDTH: f32 = 200.0;
+const MAP_HEIGHT: f32 = 150.0;
+
+/// Gap between the map and the right screen edge, and between the map and
+/// the bottom edge (room for the simulation clock)
+const MAP_MARGIN_RIGHT: f32 = 10.0;
+const MAP_MARGIN_BOTTOM: f32 = 45.0;
+
+/// Diameter of a car dot in pixels
+const CAR_DOT_SIZE: f32 = 2.0;
+
+/// Radius of a compromised building's dot in pixels
+const COMPROMISED_DOT_RADIUS: f32 = 3.0;
+
+/// Length of each arm of an intersection's light marker in pixels
+const INTERSECTION_MARKER_SIZE: f32 = 3.0;
+
+/// Seconds an event marker stays on the map, fading out as it ages
+pub const EVENT_MARKER_LIFETIME: f32 = 10.0;
+
+/// Times per second an event marker flashes
+const EVENT_MARKER_FLASH_RATE: f32 = 2.0;
+
+/// Radius of an event marker's ring in pixels
+const EVENT_MARKER_RADIUS: f32 = 6.0;
+
+const BACKGROUND_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.6);
+const ROAD_COLOR: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+const BLOCK_COLOR: Color = Color::new(0.13, 0.55, 0.13, 1.0);
+
+/// What the minimap shows, collected by `City::minimap_state`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MinimapState {
+    /// Roads as their orientation and center line position (fraction of
+    /// the screen width for vertical roads, height for horizontal ones)
+    pub roads: Vec<(Orientation, f32)>,
+
+    /// Block areas
+    pub blocks: Vec<Rect>,
+
+    /// Car positions with each car's color
+    pub cars: Vec<(Vec2, Color)>,
+
+    /// Centers of buildings whose SCADA system is compromised
+    pub compromised_buildings: Vec<Vec2>,
+
+    /// Intersections with traffic lights
+    pub intersections: Vec<MinimapIntersection>,
+}
+
+/// An intersection's position and what its lights show
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimapIntersection {
+    pub position: Vec2,
+
+    /// Light for vertical traffic, or None if it is dark
+    pub vertical: Option<LightState>,
+
+    /// Light for horizontal traffic, or None if it is dark
+    pub horizontal: Option<LightState>,
+}
+
+/// Color a light is drawn in on the map
+fn light_color(state: Option<LightState>) -> Color {
+    match state {
+        Some(LightState::Green(_)) => GREEN,
+        Some(LightState::Yellow(_)) => YELLOW,
+        Some(LightState::Red(_)) => RED,
+        None => DARKGRAY,
+    }
+}
+
+/// Flashing ring where an event happened
+#[derive(Clone, Copy, Debug)]
+struct EventMarker {
+    /// Position as fractions of the screen
+    position: Vec2,
+
+    color: Color,
+
+    /// Seconds since the event
+    age: f32,
+}
+
+impl EventMarker {
+    /// Opacity of the ring, blinking and fading out over its lifetime
+    fn alpha(&self) -> f32 {
+        let fade = 1.0 - self.age / EVENT_MARKER_LIFETIME;
+        let lit = (self.age * EVENT_MARKER_FLASH_RATE).fract() < 0.5;
+        if lit { fade } else { fade * 0.3 }
+    }
+}
+
+/// Scaled-down map of the city, drawn over the bottom-right corner
+pub struct Minimap {
+    visible: bool,
+
+    /// Recent events, oldest first
+    markers: Vec<EventMarker>,
+}
+
+impl Minimap {
+    /// Creates a minimap, shown until toggled off
+    pub fn new() -> Self {
+        Self {
+            visible: true,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Marks where an event happened with a flashing ring
+    ///
+    /// # Arguments
+    /// * `position` - Position as fractions of the screen (0.0-1.0)
+    /// * `color` - Ring color
+    pub fn mark_event(&mut self, position: Vec2, color: Color) {
+        self.markers.push(EventMarker {
+            position,
+            color,
+            age: 0.0,
+        });
+    }
+
+    /// Ages the event markers, removing those older than
+    /// `EVENT_MARKER_LIFETIME`
+    ///
+    /// Markers age while the map is hidden too.
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time in seconds
+    pub fn update(&mut self, dt: f32) {
+        for marker in &mut self.markers {
+            marker.age += dt;
+        }
+        self.markers.retain(|marker| marker.age < EVENT_MARKER_LIFETIME);
+    }
+
+    /// Handles the minimap key
+    ///
+    /// M shows or hides the map.
+    pub fn handle_input(&mut self) {
+        if is_key_pressed(KeyCode::M) {
+            self.visible = !self.visible;
+        }
+    }
+
+    /// Screen area the map is drawn in
+    fn area() -> Rect {
+        Rect::new(
+            screen_width() - MAP_WIDTH - MAP_MARGIN_RIGHT,
+            screen_height() - MAP_HEIGHT - MAP_MARGIN_BOTTOM,
+            MAP_WIDTH,
+            MAP_HEIGHT,
+        )
+    }
+
+    /// Converts a position in fractions of the screen to a point on the map
+    fn to_map(area: Rect, position: Vec2) -> Vec2 {
+        vec2(area.x + position.x * area.w, area.y + position.y * area.h)
+    }
+
+    /// Draws the map if it is shown
+    ///
+    /// Blocks are drawn first, then roads, car dots, intersection lights,
+    /// the red dots of compromised buildings and event markers on top.
+    ///
+    /// # Arguments
+    /// * `state` - What to show, from `City::minimap_state`
+    pub fn render(&self, state: &MinimapState) {
+        if !self.visible {
+            return;
+        }
+
+        let area = Self::area();
+        draw_rectangle(area.x, area.y, area.w, area.h, BACKGROUND_COLOR);
+
+        for block in &state.blocks {
+            let corner = Self::to_map(area, block.point());
+            draw_rectangle(
+                corner.x,
+                corner.y,
+                block.w * area.w,
+                block.h * area.h,
+                BLOCK_COLOR,
+            );
+        }
+
+        for &(orientation, position) in &state.roads {
+            match orientation {
+                Orientation::Vertical => {
+                    let x = area.x + position * area.w;
+                    let thickness = ROAD_WIDTH / screen_width() * area.w;
+                    draw_line(x, area.y, x, area.y + area.h, thickness, ROAD_COLOR);
+                }
+                Orientation::Horizontal => {
+                    let y = area.y + position * area.h;
+                    let thickness = ROAD_WIDTH / screen_height() * area.h;
+                    draw_line(area.x, y, area.x + area.w, y, thickness, ROAD_COLOR);
+                }
+            }
+        }
+
+        for &(position, color) in &state.cars {
+            let dot = Self::to_map(area, position);
+            // Cars just off screen stay off the map
+            if area.contains(dot) {
+                draw_rectangle(
+                    dot.x - CAR_DOT_SIZE / 2.0,
+                    dot.y - CAR_DOT_SIZE / 2.0,
+                    CAR_DOT_SIZE,
+                    CAR_DOT_SIZE,
+                    color,
+                );
+            }
+        }
+
+        for intersection in &state.intersections {
+            let center = Self::to_map(area, intersection.position);
+            let size = INTERSECTION_MARKER_SIZE;
+            let vertical = light_color(intersection.vertical);
+            let horizontal = light_color(intersection.horizontal);
+            draw_line(center.x, center.y - size, center.x, center.y + size, 2.0, vertical);
+            draw_line(center.x - size, center.y, center.x + size, center.y, 2.0, horizontal);
+        }
+
+        for &position in &state.compromised_buildings {
+            let dot = Self::to_map(area, position);
+            draw_circle(dot.x, dot.y, COMPROMISED_DOT_RADIUS, RED);
+        }
+
+        for marker in &self.markers {
+            let center = Self::to_map(area, marker.position);
+            let color = Color {
+                a: marker.alpha(),
+                ..marker.color
+            };
+            draw_circle_lines(center.x, center.y, EVENT_MARKER_RADIUS, 2.0, color);
+        }
+
+        draw_rectangle_lines(area.x, area.y, area.w, area.h, 1.0, GRAY);
+    }
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_markers_fade_out() {
+        let mut minimap = Minimap::new();
+        minimap.mark_event(vec2(0.5, 0.5), RED);
+        minimap.update(1.0);
+        minimap.mark_event(vec2(0.2, 0.8), ORANGE);
+
+        let alphas: Vec<f32> = minimap.markers.iter().map(EventMarker::alpha).collect();
+        assert_eq!(alphas, [0.9, 1.0]);
+
+        minimap.update(EVENT_MARKER_LIFETIME - 1.0);
+        assert_eq!(minimap.markers.len(), 1);
+        assert_eq!(minimap.markers[0].position, vec2(0.2, 0.8));
+
+        minimap.update(1.0);
+        assert!(minimap.markers.is_empty());
+    }
+}
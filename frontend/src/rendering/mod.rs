@@ -3,23 +3,33 @@
 //! This module handles all drawing operations for the application:
 //! - Environment rendering (grass blocks, roads, intersection markings)
 //! - Vehicle rendering with directional sprites
+//! - Pedestrians walking along block edges
 //! - LED display with scrolling text and danger warnings
+//! - Minimap of the city in the bottom-right corner
 //! - 2.5D depth effects for visual polish
+//! - Night lighting (scenery shading, headlights, glows)
 //!
 //! The rendering pipeline is organized into distinct layers:
 //! 1. Background (grass blocks with depth edges)
 //! 2. Road markings (center lines, crosswalks)
-//! 3. Traffic elements (traffic lights, cars)
+//! 3. Traffic elements (pedestrians, cars, traffic lights)
 //! 4. UI overlays (LED display)
 
 mod environment;
 pub mod led_display;  // Make public for led_display_object
+mod minimap;
+mod pedestrians;
 mod roads;
 mod vehicles;
 mod utils;
 
 // Re-export public API
 pub use environment::draw_intersection_markings;
+pub use minimap::{Minimap, MinimapIntersection, MinimapState};
+pub use pedestrians::draw_pedestrian;
 pub use roads::draw_road_lines;
-pub use vehicles::{draw_car, draw_guarded_building};
-pub use utils::draw_rounded_rectangle;
+pub use vehicles::{draw_car, draw_headlights};
+pub use utils::{
+    darken_color, draw_circle_glow, draw_rectangle_glow, draw_rounded_rectangle, lighten_color,
+    shade,
+};
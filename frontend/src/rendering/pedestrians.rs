@@ -0,0 +1,37 @@
+//! Pedestrian rendering
+
+use crate::constants::pedestrian::{
+    PEDESTRIAN_BOB_HEIGHT, PEDESTRIAN_RADIUS, PEDESTRIAN_STEP_FREQUENCY,
+};
+use crate::models::Pedestrian;
+use macroquad::prelude::*;
+
+/// Draws a pedestrian as a small circle with a shadow
+///
+/// The circle bobs up and down with each step while the shadow stays on the
+/// ground; the bobbing follows the distance walked, so pedestrians waiting
+/// at a crosswalk stand still.
+///
+/// # Arguments
+/// * `pedestrian` - The pedestrian to render
+pub fn draw_pedestrian(pedestrian: &Pedestrian) {
+    let x = pedestrian.x();
+    let y = pedestrian.y();
+    let bob = (pedestrian.distance_walked * PEDESTRIAN_STEP_FREQUENCY).sin().abs()
+        * PEDESTRIAN_BOB_HEIGHT;
+
+    draw_circle(x + 1.0, y + 1.0, PEDESTRIAN_RADIUS, Color::new(0.0, 0.0, 0.0, 0.3));
+    draw_circle(x, y - bob, PEDESTRIAN_RADIUS, pedestrian.color);
+    draw_circle_lines(
+        x,
+        y - bob,
+        PEDESTRIAN_RADIUS,
+        1.0,
+        Color::new(
+            pedestrian.color.r * 0.5,
+            pedestrian.color.g * 0.5,
+            pedestrian.color.b * 0.5,
+            1.0,
+        ),
+    );
+}
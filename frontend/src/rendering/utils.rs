@@ -1,7 +1,63 @@
 //! Rendering utility functions
 
+use crate::constants::day_night::{GLOW_LAYERS, NIGHT_DARKEN, NIGHT_OVERLAY_COLOR};
 use macroquad::prelude::*;
 
+/// Darkens a color by a specified amount, clamping to prevent negative values
+///
+/// # Arguments
+/// * `color` - The original color
+/// * `amount` - Amount to subtract from RGB channels (0.0-1.0)
+///
+/// # Returns
+/// A new color with darkened RGB values, alpha channel unchanged
+pub fn darken_color(color: Color, amount: f32) -> Color {
+    Color::new(
+        (color.r - amount).max(0.0),
+        (color.g - amount).max(0.0),
+        (color.b - amount).max(0.0),
+        color.a,
+    )
+}
+
+/// Lightens a color by a specified amount, clamping to prevent values > 1.0
+///
+/// # Arguments
+/// * `color` - The original color
+/// * `amount` - Amount to add to RGB channels (0.0-1.0)
+///
+/// # Returns
+/// A new color with lightened RGB values, alpha channel unchanged
+pub fn lighten_color(color: Color, amount: f32) -> Color {
+    Color::new(
+        (color.r + amount).min(1.0),
+        (color.g + amount).min(1.0),
+        (color.b + amount).min(1.0),
+        color.a,
+    )
+}
+
+/// Shades a color for the ambient light level of the day/night cycle
+///
+/// # Arguments
+/// * `color` - The color in full daylight
+/// * `ambient` - Ambient light level (0.0 = night, 1.0 = full daylight)
+///
+/// # Returns
+/// The color darkened and tinted toward `NIGHT_OVERLAY_COLOR` in proportion
+/// to how dark it is, alpha channel unchanged
+pub fn shade(color: Color, ambient: f32) -> Color {
+    let darkness = 1.0 - ambient;
+    let dark = darken_color(color, darkness * NIGHT_DARKEN);
+    let tint = darkness * NIGHT_OVERLAY_COLOR.a;
+    Color::new(
+        dark.r + (NIGHT_OVERLAY_COLOR.r - dark.r) * tint,
+        dark.g + (NIGHT_OVERLAY_COLOR.g - dark.g) * tint,
+        dark.b + (NIGHT_OVERLAY_COLOR.b - dark.b) * tint,
+        color.a,
+    )
+}
+
 /// Draws a rectangle with rounded corners
 ///
 /// # Arguments
@@ -40,3 +96,38 @@ pub fn draw_rounded_rectangle(
     draw_circle(x + radius, y + height - radius, radius, color); // Bottom-left
     draw_circle(x + width - radius, y + height - radius, radius, color); // Bottom-right
 }
+
+/// Draws a soft glow around a rectangle
+///
+/// Translucent layers grow outward from the rectangle, so the glow is
+/// strongest at its edge and fades out over `spread` pixels.
+///
+/// # Arguments
+/// * `x` - X position of the rectangle
+/// * `y` - Y position of the rectangle
+/// * `width` - Width of the rectangle
+/// * `height` - Height of the rectangle
+/// * `spread` - How far the glow reaches past the rectangle, in pixels
+/// * `color` - Glow color; its alpha is the opacity right at the edge
+pub fn draw_rectangle_glow(x: f32, y: f32, width: f32, height: f32, spread: f32, color: Color) {
+    let layer_color = Color::new(color.r, color.g, color.b, color.a / GLOW_LAYERS as f32);
+    for layer in 1..=GLOW_LAYERS {
+        let grow = spread * layer as f32 / GLOW_LAYERS as f32;
+        draw_rectangle(x - grow, y - grow, width + grow * 2.0, height + grow * 2.0, layer_color);
+    }
+}
+
+/// Draws a soft glow around a circle
+///
+/// # Arguments
+/// * `x` - X position of the center
+/// * `y` - Y position of the center
+/// * `radius` - Radius of the circle
+/// * `spread` - How far the glow reaches past the circle, in pixels
+/// * `color` - Glow color; its alpha is the opacity right at the edge
+pub fn draw_circle_glow(x: f32, y: f32, radius: f32, spread: f32, color: Color) {
+    let layer_color = Color::new(color.r, color.g, color.b, color.a / GLOW_LAYERS as f32);
+    for layer in 1..=GLOW_LAYERS {
+        draw_circle(x, y, radius + spread * layer as f32 / GLOW_LAYERS as f32, layer_color);
+    }
+}
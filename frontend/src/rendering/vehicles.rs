@@ -1,11 +1,15 @@
-//! Vehicle rendering - cars and related decorative elements
+//! Vehicle rendering - cars, headlights and related decorative elements
 
 use crate::constants::{
-    rendering::CAR_WINDOW_COLOR,
-    vehicle::{CAR_HEIGHT, CAR_WIDTH},
+    day_night::{HEADLIGHT_COLOR, HEADLIGHT_LENGTH, HEADLIGHT_SPREAD},
+    rendering::{
+        CAR_WINDOW_COLOR, EMERGENCY_FLASH_INTERVAL, POLICE_LIGHT_BAR_COLORS, TRUCK_TRAILER_COLOR,
+        TURN_SIGNAL_COLOR, TURN_SIGNAL_SIZE,
+    },
+    vehicle::{CAR_HEIGHT, TRUCK_CAB_LENGTH},
     visual::DEPTH_OFFSET,
 };
-use crate::models::{Car, Direction};
+use crate::models::{Car, VehicleStyle, VehicleType, Direction, TurnSignalState};
 use macroquad::prelude::*;
 
 /// Draws a car with directional sprite and depth effect
@@ -13,21 +17,122 @@ use macroquad::prelude::*;
 /// Renders a colored car rectangle with:
 /// - Orientation based on travel direction
 /// - 2.5D depth edges (darker shading on right and bottom)
-/// - Windshield window positioned based on direction
+/// - Details for the car's style: a trailer behind a truck's cab, rows of
+///   side windows on a bus, an unlit light bar on a police car
+/// - Windshield toward the front
+///
+/// Turning cars are drawn rotated along their turn arc instead, and
+/// emergency vehicles get a flashing light bar on the roof. A car about to
+/// turn blinks its turn signal at a front corner.
 ///
 /// # Arguments
 /// * `car` - The car to render
+/// * `time` - Current simulation time, for the light bar and turn signals
+///   flashing
 ///
 /// # Car Dimensions
-/// - Width: 20px, Height: 35px (rotated based on direction)
-/// - Window size: ~60% of car width, ~30% of car height
-pub fn draw_car(car: &Car) {
+/// - Size from `VehicleStyle::size` (a sedan is 20px wide, 35px long)
+/// - Window size: ~60% of car width, 30% of a sedan's length
+pub fn draw_car(car: &Car, time: f64) {
+    if car.turn.is_some() {
+        draw_turning_car(car);
+    } else {
+        draw_straight_car(car);
+    }
+
+    match car.style {
+        VehicleStyle::Truck => draw_trailer(car),
+        VehicleStyle::Bus => draw_bus_windows(car),
+        VehicleStyle::Sedan | VehicleStyle::Police => {}
+    }
+
+    // Windshield, just behind the front bumper (inside a truck's cab)
+    draw_part(
+        car,
+        car.length() / 2.0 - CAR_HEIGHT * 0.25,
+        0.0,
+        CAR_HEIGHT * 0.3,
+        car.width() * 0.6,
+        CAR_WINDOW_COLOR,
+    );
+
+    if car.vehicle_type == VehicleType::EmergencyVehicle {
+        let phase = (time / EMERGENCY_FLASH_INTERVAL) as u64 % 2;
+        draw_light_bar(car, if phase == 0 { (RED, BLUE) } else { (BLUE, RED) });
+    } else if car.style == VehicleStyle::Police {
+        draw_light_bar(car, POLICE_LIGHT_BAR_COLORS);
+    }
+
+    if let Some(signal) = car.turn_signal
+        && (time * 2.0) % 1.0 > 0.5
+    {
+        draw_turn_signal(car, signal);
+    }
+}
+
+/// Draws a lit turn signal: a small triangle pointing out from the front
+/// corner on the signalled side
+///
+/// # Arguments
+/// * `car` - The signalling car
+/// * `signal` - Side the car is turning to, as seen by its driver
+fn draw_turn_signal(car: &Car, signal: TurnSignalState) {
+    let heading = car.heading();
+    let forward = vec2(heading.cos(), heading.sin());
+    let right = vec2(-forward.y, forward.x);
+    let outward = match signal {
+        TurnSignalState::Left => -right,
+        TurnSignalState::Right => right,
+    };
+
+    let corner = vec2(car.x(), car.y())
+        + forward * (car.length() / 2.0 - TURN_SIGNAL_SIZE / 2.0)
+        + outward * (car.width() / 2.0);
+    draw_triangle(
+        corner + forward * (TURN_SIGNAL_SIZE / 2.0),
+        corner - forward * (TURN_SIGNAL_SIZE / 2.0),
+        corner + outward * TURN_SIGNAL_SIZE,
+        TURN_SIGNAL_COLOR,
+    );
+}
+
+/// Draws a rectangle on a car, placed and rotated in the car's own frame
+///
+/// # Arguments
+/// * `car` - The car to draw on
+/// * `along` - Offset of the rectangle's center toward the car's front (pixels)
+/// * `across` - Offset of the rectangle's center toward the car's right (pixels)
+/// * `length` - Size along the car's heading (pixels)
+/// * `width` - Size across the car's heading (pixels)
+/// * `color` - Fill color
+fn draw_part(car: &Car, along: f32, across: f32, length: f32, width: f32, color: Color) {
+    let heading = car.heading();
+    let forward = vec2(heading.cos(), heading.sin());
+    let side = vec2(-forward.y, forward.x);
+    let center = vec2(car.x(), car.y()) + forward * along + side * across;
+
+    draw_rectangle_ex(
+        center.x,
+        center.y,
+        length,
+        width,
+        DrawRectangleParams {
+            offset: vec2(0.5, 0.5),
+            rotation: heading,
+            color,
+        },
+    );
+}
+
+/// Draws the body of a car driving along one of the four cardinal directions
+fn draw_straight_car(car: &Car) {
+
     let car_x = car.x();
     let car_y = car.y();
 
     let (width, height) = match car.direction {
-        Direction::Down | Direction::Up => (CAR_WIDTH, CAR_HEIGHT),
-        Direction::Left | Direction::Right => (CAR_HEIGHT, CAR_WIDTH),
+        Direction::Down | Direction::Up => (car.width(), car.length()),
+        Direction::Left | Direction::Right => (car.length(), car.width()),
     };
 
     // Draw car body
@@ -54,57 +159,127 @@ pub fn draw_car(car: &Car) {
         DEPTH_OFFSET,
         Color::new(car.color.r * 0.5, car.color.g * 0.5, car.color.b * 0.5, 1.0),
     );
+}
+
+/// Draws the body of a car rotated to its heading, for cars between
+/// cardinal directions
+///
+/// The body is drawn lengthwise along the heading with a darker copy offset
+/// down and right for depth.
+fn draw_turning_car(car: &Car) {
+    let center = vec2(car.x(), car.y());
+    let heading = car.heading();
+
+    let draw_rotated = |center: Vec2, color: Color| {
+        draw_rectangle_ex(
+            center.x,
+            center.y,
+            car.length(),
+            car.width(),
+            DrawRectangleParams {
+                offset: vec2(0.5, 0.5),
+                rotation: heading,
+                color,
+            },
+        );
+    };
+
+    // Depth shadow, then body
+    draw_rotated(
+        center + vec2(DEPTH_OFFSET, DEPTH_OFFSET),
+        Color::new(car.color.r * 0.5, car.color.g * 0.5, car.color.b * 0.5, 1.0),
+    );
+    draw_rotated(center, car.color);
+}
+
+/// Draws a truck's trailer over the back of its body, leaving the cab in
+/// front in the body color
+///
+/// A dark strip between cab and trailer marks the coupling.
+fn draw_trailer(car: &Car) {
+    let coupling = 2.0;
+    let trailer_length = car.length() - TRUCK_CAB_LENGTH - coupling;
+    let trailer_front = car.length() / 2.0 - TRUCK_CAB_LENGTH - coupling;
+
+    draw_part(
+        car,
+        trailer_front - trailer_length / 2.0,
+        0.0,
+        trailer_length,
+        car.width(),
+        TRUCK_TRAILER_COLOR,
+    );
+    draw_part(
+        car,
+        trailer_front + coupling / 2.0,
+        0.0,
+        coupling,
+        car.width() * 0.5,
+        DARKGRAY,
+    );
+}
+
+/// Draws a strip of windows along each side of a bus, behind the windshield
+fn draw_bus_windows(car: &Car) {
+    let strip_length = car.length() * 0.6;
+    let strip_center = car.length() / 2.0 - CAR_HEIGHT * 0.6 - strip_length / 2.0;
 
-    // Draw windows
-    match car.direction {
-        Direction::Down => {
-            draw_rectangle(
-                car_x - width / 3.0,
-                car_y - height / 4.0,
-                width * 0.6,
-                height * 0.3,
-                CAR_WINDOW_COLOR,
-            );
-        }
-        Direction::Up => {
-            draw_rectangle(
-                car_x - width / 3.0,
-                car_y - height / 6.0,
-                width * 0.6,
-                height * 0.3,
-                CAR_WINDOW_COLOR,
-            );
-        }
-        Direction::Right => {
-            draw_rectangle(
-                car_x - height / 6.0,
-                car_y - width / 3.0,
-                height * 0.3,
-                width * 0.6,
-                CAR_WINDOW_COLOR,
-            );
-        }
-        Direction::Left => {
-            draw_rectangle(
-                car_x - height / 4.0,
-                car_y - width / 3.0,
-                height * 0.3,
-                width * 0.6,
-                CAR_WINDOW_COLOR,
-            );
-        }
+    for side in [-1.0, 1.0] {
+        draw_part(
+            car,
+            strip_center,
+            side * car.width() * 0.3,
+            strip_length,
+            car.width() * 0.15,
+            CAR_WINDOW_COLOR,
+        );
     }
 }
 
-/// Placeholder for removed building/parking lot feature
+/// Draws a red and blue light bar across a car's roof
 ///
-/// This function was previously used to draw a guarded building with
-/// parking lot, but that feature has been removed. Kept for compatibility
-/// with the main rendering pipeline.
+/// Emergency vehicles swap the two colors every `EMERGENCY_FLASH_INTERVAL`
+/// seconds; police cars patrol with the bar unlit.
 ///
 /// # Arguments
-/// * `_time` - Unused simulation time
-/// * `_cars` - Unused car list
-pub fn draw_guarded_building(_time: f64, _cars: &[Car]) {
-    // Function removed - no parking lot or buildings
+/// * `car` - The car to draw on
+/// * `(left, right)` - Colors of the two halves of the bar
+fn draw_light_bar(car: &Car, (left, right): (Color, Color)) {
+    for (offset, color) in [(-1.0, left), (1.0, right)] {
+        draw_part(
+            car,
+            0.0,
+            offset * car.width() * 0.2,
+            CAR_HEIGHT * 0.15,
+            car.width() * 0.4,
+            color,
+        );
+    }
+}
+
+/// Draws the headlight cone in front of a car
+///
+/// The cone widens from the car's front bumper along its heading and is
+/// drawn under the cars, so it lights up the road ahead.
+///
+/// # Arguments
+/// * `car` - The car whose headlights to draw
+/// * `darkness` - How dark it is (0.0 = full daylight, 1.0 = night); scales
+///   the cone's opacity so headlights fade in over dusk
+pub fn draw_headlights(car: &Car, darkness: f32) {
+    let heading = car.heading();
+    let forward = vec2(heading.cos(), heading.sin());
+    let side = vec2(-forward.y, forward.x);
+
+    let front = vec2(car.x(), car.y()) + forward * (car.length() / 2.0);
+    let far = front + forward * HEADLIGHT_LENGTH;
+    let near_half = side * (car.width() * 0.3);
+    let far_half = side * (HEADLIGHT_SPREAD / 2.0);
+
+    let color = Color {
+        a: HEADLIGHT_COLOR.a * darkness,
+        ..HEADLIGHT_COLOR
+    };
+    draw_triangle(front - near_half, front + near_half, far + far_half, color);
+    draw_triangle(front - near_half, far + far_half, far - far_half, color);
 }
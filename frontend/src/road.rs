@@ -2,19 +2,24 @@
 //!
 //! This module defines the Road structure and related functionality for:
 //! - Road positioning and orientation
-//! - Lane calculations for left-hand traffic
+//! - Lane calculations for left-hand and right-hand traffic
 //! - Car spawn position calculations
+//! - Road network generation (roads, intersections, and their links)
 
-use crate::constants::vehicle::LANE_OFFSET;
-use crate::models::Direction;
-use macroquad::prelude::*;
+use crate::block::Block;
+use crate::constants::road_network::{HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS};
+use crate::constants::visual::ROAD_WIDTH;
+use crate::intersection::{generate_intersections, Intersection};
+use crate::models::{Direction, TrafficRule};
+use crate::screen::{screen_height, screen_width};
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Road Orientation
 // ============================================================================
 
 /// Orientation of a road (vertical or horizontal)
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Orientation {
     /// Road runs vertically (cars move up/down)
     Vertical,
@@ -30,8 +35,8 @@ pub enum Orientation {
 /// Represents a road in the city grid
 ///
 /// Roads are the pathways where cars travel. Each road has a fixed position
-/// and orientation. The simulation uses left-hand traffic rules.
-#[derive(Clone)]
+/// and orientation, and two lanes whose sides follow the traffic rule.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Road {
     /// Position as percentage of screen dimension (0.0-1.0)
     /// For vertical roads: percentage of screen width
@@ -44,14 +49,20 @@ pub struct Road {
     /// Unique identifier for this road
     pub index: usize,
 
-    /// Intersection at the start of the road (None if road extends off-screen)
+    /// First intersection along the road, top or left (None if the road has none)
     pub start_intersection_id: Option<usize>,
 
-    /// Intersection at the end of the road (None if road extends off-screen)
+    /// Last intersection along the road, bottom or right (None if the road has none)
     pub end_intersection_id: Option<usize>,
 
+    /// All intersections along the road, ordered top to bottom or left to right
+    pub intersection_ids: Vec<usize>,
+
     /// IDs of blocks adjacent to this road
     pub adjacent_block_ids: Vec<usize>,
+
+    /// Whether the road is closed to traffic
+    pub closed: bool,
 }
 
 impl Road {
@@ -71,41 +82,84 @@ impl Road {
             index,
             start_intersection_id: None,
             end_intersection_id: None,
+            intersection_ids: Vec::new(),
             adjacent_block_ids: Vec::new(),
+            closed: false,
+        }
+    }
+
+    /// Returns the road's center line position in pixels
+    ///
+    /// # Returns
+    /// X position for vertical roads, Y position for horizontal roads
+    pub fn position(&self) -> f32 {
+        match self.orientation {
+            Orientation::Vertical => self.position_percent * screen_width(),
+            Orientation::Horizontal => self.position_percent * screen_height(),
+        }
+    }
+
+    /// Returns which side of a block this road runs along, if any
+    ///
+    /// # Arguments
+    /// * `block` - The block to check
+    ///
+    /// # Returns
+    /// The direction from the block to the road, or None if they don't touch
+    pub fn side_of_block(&self, block: &Block) -> Option<Direction> {
+        // Blocks are generated flush with the road edges; allow for rounding
+        const TOLERANCE: f32 = 1.0;
+        let near_edge = self.position() - ROAD_WIDTH / 2.0;
+        let far_edge = self.position() + ROAD_WIDTH / 2.0;
+
+        let (block_start, block_end, before, after) = match self.orientation {
+            Orientation::Vertical => (
+                block.x(),
+                block.x() + block.width(),
+                Direction::Left,
+                Direction::Right,
+            ),
+            Orientation::Horizontal => (
+                block.y(),
+                block.y() + block.height(),
+                Direction::Up,
+                Direction::Down,
+            ),
+        };
+
+        if (block_end - near_edge).abs() <= TOLERANCE {
+            Some(after)
+        } else if (block_start - far_edge).abs() <= TOLERANCE {
+            Some(before)
+        } else {
+            None
         }
     }
 
     /// Calculates the lane position for a car based on its direction
     ///
-    /// Uses left-hand traffic rules:
+    /// With left-hand traffic:
     /// - Vertical roads: down = left lane, up = right lane
     /// - Horizontal roads: right = bottom lane, left = top lane
     ///
+    /// Right-hand traffic uses the other lane in each case.
+    ///
     /// # Arguments
     /// * `going_positive` - True if moving in positive direction (down/right), false otherwise (up/left)
+    /// * `rule` - Side of the road the car keeps to
     ///
     /// # Returns
     /// Position percentage for the correct lane
-    pub fn get_lane_position(&self, going_positive: bool) -> f32 {
+    pub fn get_lane_position(&self, going_positive: bool, rule: TrafficRule) -> f32 {
+        let direction = if going_positive {
+            self.get_positive_direction()
+        } else {
+            self.get_negative_direction()
+        };
+        let offset = rule.lane_offset(direction);
         match self.orientation {
-            Orientation::Vertical => {
-                // going_positive = going down
-                let offset_percent = LANE_OFFSET / screen_width();
-                if going_positive {
-                    self.position_percent - offset_percent // Left lane
-                } else {
-                    self.position_percent + offset_percent // Right lane
-                }
-            }
-            Orientation::Horizontal => {
-                // going_positive = going right
-                let offset_percent = LANE_OFFSET / screen_height();
-                if going_positive {
-                    self.position_percent + offset_percent // Bottom lane
-                } else {
-                    self.position_percent - offset_percent // Top lane
-                }
-            }
+            Orientation::Vertical => self.position_percent + offset.x / screen_width(),
+            Orientation::Horizontal => self.position_percent + offset.y / screen_height(),
         }
     }
 
@@ -116,45 +170,17 @@ impl Road {
     ///
     /// # Arguments
     /// * `going_positive` - True if moving in positive direction (down/right), false otherwise (up/left)
+    /// * `rule` - Side of the road the car keeps to
     ///
     /// # Returns
     /// Tuple of (x_percent, y_percent) for spawning the car
-    pub fn get_spawn_position(&self, going_positive: bool) -> (f32, f32) {
+    pub fn get_spawn_position(&self, going_positive: bool, rule: TrafficRule) -> (f32, f32) {
+        let lane = self.get_lane_position(going_positive, rule);
+        // Top or left of screen going down or right, bottom or right going up or left
+        let edge = if going_positive { -0.05 } else { 1.05 };
         match self.orientation {
-            Orientation::Vertical => {
-                // X position is the lane
-                let x = if going_positive {
-                    self.position_percent - (LANE_OFFSET / screen_width()) // Left lane (going down)
-                } else {
-                    self.position_percent + (LANE_OFFSET / screen_width()) // Right lane (going up)
-                };
-
-                // Y position is off-screen
-                let y = if going_positive {
-                    -0.05 // Top of screen (going down)
-                } else {
-                    1.05 // Bottom of screen (going up)
-                };
-
-                (x, y)
-            }
-            Orientation::Horizontal => {
-                // X position is off-screen
-                let x = if going_positive {
-                    -0.05 // Left of screen (going right)
-                } else {
-                    1.05 // Right of screen (going left)
-                };
-
-                // Y position is the lane
-                let y = if going_positive {
-                    self.position_percent + (LANE_OFFSET / screen_height()) // Bottom lane (going right)
-                } else {
-                    self.position_percent - (LANE_OFFSET / screen_height()) // Top lane (going left)
-                };
-
-                (x, y)
-            }
+            Orientation::Vertical => (lane, edge),
+            Orientation::Horizontal => (edge, lane),
         }
     }
 
@@ -180,3 +206,134 @@ impl Road {
         }
     }
 }
+
+// ============================================================================
+// Road Network Generation
+// ============================================================================
+
+/// Generates the city's roads and intersections with their links filled in
+///
+/// Creates the vertical roads (IDs 0-2) and horizontal roads (IDs 3-4) from
+/// the road network constants, plus an intersection wherever two roads cross.
+/// Each road lists its intersections in order, and each intersection knows
+/// which road leaves it in every direction.
+///
+/// Block adjacency depends on the blocks' pixel layout, so it is linked
+/// separately with `City::link_blocks_to_roads`.
+///
+/// # Returns
+/// Tuple of (roads, intersections)
+pub fn generate_road_network() -> (Vec<Road>, Vec<Intersection>) {
+    let vertical = VERTICAL_ROAD_POSITIONS
+        .iter()
+        .map(|&position| (position, Orientation::Vertical));
+    let horizontal = HORIZONTAL_ROAD_POSITIONS
+        .iter()
+        .map(|&position| (position, Orientation::Horizontal));
+
+    let mut roads: Vec<Road> = vertical
+        .chain(horizontal)
+        .enumerate()
+        .map(|(index, (position, orientation))| Road::new(position, orientation, index))
+        .collect();
+    let mut intersections = generate_intersections();
+
+    for intersection in &mut intersections {
+        for road in &mut roads {
+            let on_road = match road.orientation {
+                Orientation::Vertical => intersection.x_percent,
+                Orientation::Horizontal => intersection.y_percent,
+            };
+            if (on_road - road.position_percent).abs() > f32::EPSILON {
+                continue;
+            }
+
+            // Roads span the whole screen, so they continue both ways
+            intersection.connect_road(road.get_positive_direction(), road.index);
+            intersection.connect_road(road.get_negative_direction(), road.index);
+            road.intersection_ids.push(intersection.id);
+        }
+    }
+
+    // Order each road's intersections top to bottom or left to right
+    for road in &mut roads {
+        let orientation = road.orientation;
+        road.intersection_ids.sort_by(|&a, &b| {
+            // generate_intersections() numbers intersections by index
+            let along = |id: usize| {
+                let intersection = &intersections[id];
+                match orientation {
+                    Orientation::Vertical => intersection.y_percent,
+                    Orientation::Horizontal => intersection.x_percent,
+                }
+            };
+            along(a).total_cmp(&along(b))
+        });
+        road.start_intersection_id = road.intersection_ids.first().copied();
+        road.end_intersection_id = road.intersection_ids.last().copied();
+    }
+
+    (roads, intersections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersections_know_their_roads() {
+        let (_, intersections) = generate_road_network();
+
+        // Top-left intersection: left vertical road and top horizontal road
+        let top_left = &intersections[0];
+        assert_eq!(top_left.get_road_in_direction(Direction::Down), Some(0));
+        assert_eq!(top_left.get_road_in_direction(Direction::Right), Some(3));
+        assert_eq!(top_left.get_road_in_direction(Direction::Up), Some(0));
+        assert_eq!(top_left.get_road_in_direction(Direction::Left), Some(3));
+
+        // Bottom-right intersection: right vertical road and bottom horizontal road
+        let bottom_right = &intersections[5];
+        assert_eq!(bottom_right.get_road_in_direction(Direction::Up), Some(2));
+        assert_eq!(bottom_right.get_road_in_direction(Direction::Left), Some(4));
+    }
+
+    #[test]
+    fn test_roads_list_their_intersections_in_order() {
+        let (roads, intersections) = generate_road_network();
+        assert_eq!(roads.len(), 5);
+        assert_eq!(intersections.len(), 6);
+
+        // Middle vertical road crosses both horizontal roads, top first
+        let middle = &roads[1];
+        assert_eq!(middle.orientation, Orientation::Vertical);
+        assert_eq!(middle.intersection_ids, vec![2, 3]);
+        assert_eq!(middle.start_intersection_id, Some(2));
+        assert_eq!(middle.end_intersection_id, Some(3));
+
+        // Each horizontal road crosses all three vertical roads, left first
+        assert_eq!(roads[3].orientation, Orientation::Horizontal);
+        assert_eq!(roads[3].intersection_ids, vec![0, 2, 4]);
+        assert_eq!(roads[4].intersection_ids, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn test_side_of_block() {
+        let (roads, _) = generate_road_network();
+        let half_road = ROAD_WIDTH / 2.0;
+
+        // Block between the left and middle vertical roads, below the top horizontal road
+        let block = Block::new(
+            (120.0 + half_road) / 800.0,
+            (150.0 + half_road) / 600.0,
+            (280.0 - ROAD_WIDTH) / 800.0,
+            (300.0 - ROAD_WIDTH) / 600.0,
+            5,
+        );
+
+        assert_eq!(roads[0].side_of_block(&block), Some(Direction::Left));
+        assert_eq!(roads[1].side_of_block(&block), Some(Direction::Right));
+        assert_eq!(roads[2].side_of_block(&block), None);
+        assert_eq!(roads[3].side_of_block(&block), Some(Direction::Up));
+        assert_eq!(roads[4].side_of_block(&block), Some(Direction::Down));
+    }
+}
@@ -0,0 +1,124 @@
+//! Route planning through the road network
+//!
+//! This module handles:
+//! - plan_route: The shortest way from the intersection a car is driving
+//!   toward to a target intersection, as the intersections it drives through
+//! - Following the roads from one intersection to the next in a direction
+//!
+//! Routes are found by a breadth-first search over the intersections, linked
+//! to their neighbors along the roads through them. Cars never turn around,
+//! so the search tracks the direction each intersection is entered in, and
+//! it tries going straight on before turning so that of equally short
+//! routes the one with the fewest turns is picked.
+
+use crate::models::Direction;
+use crate::road::{Orientation, Road};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Directions a car can leave an intersection in: straight on, then turning
+///
+/// # Arguments
+/// * `direction` - Direction the car entered the intersection in
+fn exits(direction: Direction) -> [Direction; 3] {
+    if direction.is_vertical() {
+        [direction, Direction::Right, Direction::Left]
+    } else {
+        [direction, Direction::Down, Direction::Up]
+    }
+}
+
+/// Next intersection along the road leaving an intersection in a direction
+///
+/// # Arguments
+/// * `roads` - The road network
+/// * `from` - ID of the intersection to leave
+/// * `direction` - Direction to leave it in
+///
+/// # Returns
+/// The neighboring intersection's ID, or None if the road leaves the city
+fn neighbor(roads: &HashMap<usize, Road>, from: usize, direction: Direction) -> Option<usize> {
+    roads
+        .values()
+        .filter(|road| (road.orientation == Orientation::Vertical) == direction.is_vertical())
+        .find_map(|road| {
+            let index = road.intersection_ids.iter().position(|&id| id == from)?;
+            let next = if direction == road.get_positive_direction() {
+                index + 1
+            } else {
+                index.checked_sub(1)?
+            };
+            road.intersection_ids.get(next).copied()
+        })
+}
+
+/// Plans the shortest route to an intersection
+///
+/// # Arguments
+/// * `roads` - The road network, with the intersections along each road
+/// * `start` - ID of the next intersection the car reaches
+/// * `heading` - Direction the car drives into `start` in
+/// * `target` - ID of the intersection to reach
+///
+/// # Returns
+/// The IDs of the intersections the car drives through, from `start` to
+/// `target`, or None if the target can't be reached without turning around
+pub fn plan_route(
+    roads: &HashMap<usize, Road>,
+    start: usize,
+    heading: Direction,
+    target: usize,
+) -> Option<Vec<usize>> {
+    // Each intersection entered in a direction, with the one before it
+    let mut previous: HashMap<(usize, Direction), (usize, Direction)> = HashMap::new();
+    let mut visited = HashSet::from([(start, heading)]);
+    let mut queue = VecDeque::from([(start, heading)]);
+
+    while let Some(state) = queue.pop_front() {
+        if state.0 == target {
+            let mut route = vec![state.0];
+            let mut state = state;
+            while let Some(&before) = previous.get(&state) {
+                route.push(before.0);
+                state = before;
+            }
+            route.reverse();
+            return Some(route);
+        }
+
+        let (intersection, direction) = state;
+        for exit in exits(direction) {
+            let Some(next) = neighbor(roads, intersection, exit) else {
+                continue;
+            };
+            if visited.insert((next, exit)) {
+                previous.insert((next, exit), state);
+                queue.push_back((next, exit));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::road::generate_road_network;
+
+    #[test]
+    fn test_routes_are_shortest_with_fewest_turns() {
+        let (roads, _) = generate_road_network();
+        let roads: HashMap<usize, Road> = roads.into_iter().map(|road| (road.index, road)).collect();
+
+        // Intersections are numbered down each vertical road, left to right:
+        // 0 2 4
+        // 1 3 5
+        // Down road 0 to the bottom right: one turn, at 1
+        assert_eq!(plan_route(&roads, 0, Direction::Down, 5), Some(vec![0, 1, 3, 5]));
+        // Rightward along the top to the bottom left, which is behind
+        assert_eq!(plan_route(&roads, 2, Direction::Right, 1), Some(vec![2, 3, 1]));
+        assert_eq!(plan_route(&roads, 3, Direction::Left, 3), Some(vec![3]));
+        // Cars can't turn around, so heading up at 0 goes the long way round
+        assert_eq!(plan_route(&roads, 0, Direction::Up, 1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(plan_route(&roads, 0, Direction::Down, 9), None);
+    }
+}
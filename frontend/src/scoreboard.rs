@@ -0,0 +1,232 @@
+//! Team scoreboard overlay
+//!
+//! Tracks each team's score from `TeamScoreUpdate` events and state
+//! snapshots, counts the events attributed to each team this session, and
+//! draws a small ranked table in the top-right corner, with each team in its
+//! color. Tab shows or hides the table.
+
+use crate::team::TeamRegistry;
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// Scoreboard panel dimensions
+const PANEL_WIDTH: f32 = 260.0;
+const TITLE_HEIGHT: f32 = 25.0;
+const ROW_HEIGHT: f32 = 20.0;
+
+/// Maximum number of teams listed; the rest are summarized in one line
+const MAX_ROWS: usize = 8;
+
+/// Team scores as last reported by the server, and events per team
+pub struct Scoreboard {
+    scores: HashMap<String, i32>,
+
+    /// Number of events attributed to each team this session
+    events: HashMap<String, u32>,
+
+    visible: bool,
+}
+
+impl Scoreboard {
+    /// Creates an empty, visible scoreboard
+    pub fn new() -> Self {
+        Self {
+            scores: HashMap::new(),
+            events: HashMap::new(),
+            visible: true,
+        }
+    }
+
+    /// Shows or hides the scoreboard when Tab is pressed
+    pub fn handle_input(&mut self) {
+        if is_key_pressed(KeyCode::Tab) {
+            self.visible = !self.visible;
+        }
+    }
+
+    /// Counts an event attributed to a team
+    pub fn record_event(&mut self, team: &str) {
+        *self.events.entry(team.to_string()).or_default() += 1;
+    }
+
+    /// Number of events attributed to a team this session
+    pub fn event_count(&self, team: &str) -> u32 {
+        self.events.get(team).copied().unwrap_or(0)
+    }
+
+    /// Records a team's new total score
+    pub fn set_score(&mut self, team: String, total: i32) {
+        self.scores.insert(team, total);
+    }
+
+    /// Replaces every score, e.g. from a state snapshot
+    pub fn replace(&mut self, scores: HashMap<String, i32>) {
+        self.scores = scores;
+    }
+
+    /// Removes every score; event counts last the whole session
+    pub fn clear(&mut self) {
+        self.scores.clear();
+    }
+
+    /// Teams ordered by score, highest first (ties by name)
+    ///
+    /// Teams with events but no score yet are listed with a score of 0.
+    pub fn ranked(&self) -> Vec<(&str, i32)> {
+        let unscored = self
+            .events
+            .keys()
+            .filter(|team| !self.scores.contains_key(*team))
+            .map(|team| (team.as_str(), 0));
+        let mut ranked: Vec<_> = self
+            .scores
+            .iter()
+            .map(|(team, score)| (team.as_str(), *score))
+            .chain(unscored)
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        ranked
+    }
+
+    /// Draws the scoreboard, unless it is hidden or no team has shown up yet
+    ///
+    /// Each row shows the team's rank and name in its color, the number of
+    /// events attributed to it and its score.
+    pub fn render(&self, teams: &TeamRegistry) {
+        if !self.visible || (self.scores.is_empty() && self.events.is_empty()) {
+            return;
+        }
+
+        let ranked = self.ranked();
+        let shown = ranked.len().min(MAX_ROWS);
+        let hidden = ranked.len() - shown;
+        let rows = shown + usize::from(hidden > 0);
+
+        let panel_x = screen_width() - PANEL_WIDTH - 10.0;
+        let panel_y = 10.0;
+        let panel_height = TITLE_HEIGHT + rows as f32 * ROW_HEIGHT + 10.0;
+
+        // Background, border and title bar
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            Color::new(0.1, 0.1, 0.15, 0.9),
+        );
+        draw_rectangle_lines(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            2.0,
+            Color::new(0.9, 0.7, 0.1, 1.0), // Gold border
+        );
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            TITLE_HEIGHT,
+            Color::new(0.25, 0.2, 0.05, 1.0),
+        );
+        draw_text(
+            "SCOREBOARD",
+            panel_x + 10.0,
+            panel_y + 18.0,
+            20.0,
+            Color::new(1.0, 0.85, 0.3, 1.0),
+        );
+
+        draw_text(
+            "EVENTS",
+            panel_x + PANEL_WIDTH - 105.0,
+            panel_y + 17.0,
+            12.0,
+            Color::new(0.7, 0.7, 0.7, 1.0),
+        );
+
+        // One row per team: rank and name on the left, events and score on the right
+        let mut y = panel_y + TITLE_HEIGHT + ROW_HEIGHT;
+        for (rank, (team, score)) in ranked.iter().take(shown).enumerate() {
+            let color = if rank == 0 {
+                Color::new(1.0, 0.85, 0.3, 1.0)
+            } else {
+                Color::new(0.85, 0.85, 0.85, 1.0)
+            };
+            draw_text(
+                &format!("{}. {}", rank + 1, team),
+                panel_x + 10.0,
+                y,
+                16.0,
+                teams.color_of(team),
+            );
+
+            let events_text = self.event_count(team).to_string();
+            let width = measure_text(&events_text, None, 16, 1.0).width;
+            draw_text(
+                &events_text,
+                panel_x + PANEL_WIDTH - 70.0 - width,
+                y,
+                16.0,
+                Color::new(0.6, 0.6, 0.6, 1.0),
+            );
+
+            let score_text = score.to_string();
+            let width = measure_text(&score_text, None, 16, 1.0).width;
+            draw_text(&score_text, panel_x + PANEL_WIDTH - 10.0 - width, y, 16.0, color);
+            y += ROW_HEIGHT;
+        }
+
+        if hidden > 0 {
+            draw_text(
+                &format!("+{} more", hidden),
+                panel_x + 10.0,
+                y,
+                14.0,
+                Color::new(0.5, 0.5, 0.5, 1.0),
+            );
+        }
+    }
+}
+
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranked_orders_by_score_then_name() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.set_score("Red".to_string(), 50);
+        scoreboard.set_score("Blue".to_string(), 80);
+        scoreboard.set_score("Green".to_string(), 50);
+        scoreboard.set_score("Red".to_string(), 90);
+        assert_eq!(scoreboard.ranked(), [("Red", 90), ("Blue", 80), ("Green", 50)]);
+
+        scoreboard.replace(HashMap::from([("Blue".to_string(), -5)]));
+        assert_eq!(scoreboard.ranked(), [("Blue", -5)]);
+
+        scoreboard.clear();
+        assert!(scoreboard.ranked().is_empty());
+    }
+
+    #[test]
+    fn test_event_counts_list_teams_until_they_score() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.record_event("Red");
+        scoreboard.record_event("Blue");
+        scoreboard.record_event("Red");
+        scoreboard.set_score("Blue".to_string(), 10);
+        assert_eq!(scoreboard.ranked(), [("Blue", 10), ("Red", 0)]);
+        assert_eq!((scoreboard.event_count("Red"), scoreboard.event_count("Green")), (2, 0));
+
+        scoreboard.clear();
+        assert_eq!(scoreboard.ranked(), [("Blue", 0), ("Red", 0)]);
+        assert_eq!(scoreboard.event_count("Blue"), 1);
+    }
+}
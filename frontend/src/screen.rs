@@ -0,0 +1,25 @@
+//! Screen dimension access for simulation code
+//!
+//! Simulation modules read the screen size through this module instead of
+//! macroquad directly. In normal builds these are macroquad's own functions;
+//! under `cfg(test)` they return a fixed 800x600 size so simulation logic
+//! can be unit tested without opening a window.
+
+#[cfg(not(test))]
+pub use macroquad::prelude::{screen_height, screen_width};
+
+#[cfg(test)]
+pub use test_screen::{screen_height, screen_width};
+
+#[cfg(test)]
+mod test_screen {
+    /// Test screen width in pixels
+    pub fn screen_width() -> f32 {
+        800.0
+    }
+
+    /// Test screen height in pixels
+    pub fn screen_height() -> f32 {
+        600.0
+    }
+}
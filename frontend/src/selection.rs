@@ -0,0 +1,330 @@
+//! Click-to-inspect selection
+//!
+//! This module handles:
+//! - Selection: The car, intersection or block picked with the mouse
+//! - Hit-testing clicks against cars, then intersections, then blocks
+//! - Highlight outline around the selected entity
+//! - Info panel with the selected entity's live details
+//!
+//! Cars are selected by ID, so the panel follows a car as it drives and the
+//! selection ends on its own once the car leaves the screen.
+
+use crate::block::{Building, ScadaState};
+use crate::city::City;
+use crate::constants::visual::ROAD_WIDTH;
+use crate::models::Direction;
+use crate::traffic_light::LightState;
+use macroquad::prelude::*;
+
+/// Info panel dimensions
+const PANEL_WIDTH: f32 = 240.0;
+const TITLE_HEIGHT: f32 = 25.0;
+const LINE_HEIGHT: f32 = 20.0;
+
+/// Gap between the selected entity and its highlight outline, in pixels
+const HIGHLIGHT_PADDING: f32 = 4.0;
+
+/// Highlight outline and panel border color
+const HIGHLIGHT_COLOR: Color = Color::new(0.3, 0.9, 1.0, 1.0);
+
+/// An entity picked with the mouse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selection {
+    /// Car with this ID
+    Car(usize),
+    /// Intersection with this ID
+    Intersection(usize),
+    /// Block with this ID
+    Block(usize),
+}
+
+impl Selection {
+    /// Finds the entity at a screen position
+    ///
+    /// Cars are checked first since they drive over intersections, and
+    /// intersections before the blocks around them.
+    ///
+    /// # Arguments
+    /// * `city` - City to search
+    /// * `x` - X coordinate in pixels
+    /// * `y` - Y coordinate in pixels
+    ///
+    /// # Returns
+    /// The entity under the point, or None on empty road
+    pub fn pick(city: &City, x: f32, y: f32) -> Option<Self> {
+        city
+            .find_car_at_position(x, y)
+            .map(Selection::Car)
+            .or_else(|| city.find_intersection_at_position(x, y).map(Selection::Intersection))
+            .or_else(|| city.find_block_at_position(x, y).map(Selection::Block))
+    }
+
+    /// Screen area of the selected entity, or None if it no longer exists
+    fn bounds(self, city: &City) -> Option<Rect> {
+        match self {
+            Selection::Car(id) => city.get_car(id).map(|car| car.bounds()),
+            Selection::Intersection(id) => city.get_intersection(id).map(|intersection| {
+                Rect::new(
+                    intersection.x() - ROAD_WIDTH / 2.0,
+                    intersection.y() - ROAD_WIDTH / 2.0,
+                    ROAD_WIDTH,
+                    ROAD_WIDTH,
+                )
+            }),
+            Selection::Block(id) => city
+                .get_block(id)
+                .map(|block| Rect::new(block.x(), block.y(), block.width(), block.height())),
+        }
+    }
+
+    /// Describes the selected entity as it is now
+    ///
+    /// # Returns
+    /// A title and detail lines, or None if the entity no longer exists
+    pub fn describe(self, city: &City) -> Option<(String, Vec<String>)> {
+        match self {
+            Selection::Car(id) => {
+                let car = city.get_car(id)?;
+                let turn = car
+                    .next_turn
+                    .map_or_else(|| "straight".to_string(), |turn| format!("{:?}", turn));
+                Some((
+                    format!("CAR {}", id),
+                    vec![
+                        format!("Type: {:?}", car.vehicle_type),
+                        format!("Style: {:?}", car.style),
+                        format!("Direction: {:?}", car.direction),
+                        format!("Speed: {:.0} px/s", car.velocity),
+                        format!("Road: {}", car.road_index),
+                        format!("Next turn: {}", turn),
+                    ],
+                ))
+            }
+            Selection::Intersection(id) => {
+                let intersection = city.get_intersection(id)?;
+                let mut lines = vec![format!(
+                    "District: {}",
+                    intersection.district.as_deref().unwrap_or("none")
+                )];
+                if !intersection.powered {
+                    lines.push("Power: OUT".to_string());
+                }
+                if let Some(override_state) = intersection.override_state {
+                    lines.push(format!("Override: {}", override_state.label()));
+                }
+                if let Some(light) = &intersection.light {
+                    lines.push(format!("Vertical: {}", light_name(light.vertical_state)));
+                    lines.push(format!("Horizontal: {}", light_name(light.horizontal_state)));
+                    lines.push(format!("Time left: {:.1}s", light.time_in_state.max(0.0)));
+                    lines.push(format!(
+                        "Next green: V {:.1}s / H {:.1}s",
+                        light.green_duration_for_direction(Direction::Down),
+                        light.green_duration_for_direction(Direction::Left)
+                    ));
+                }
+                let queued: usize = intersection.queue_lengths().values().sum();
+                if queued > 0 {
+                    lines.push(format!("Queued: {} cars", queued));
+                }
+                Some((format!("INTERSECTION {}", id), lines))
+            }
+            Selection::Block(id) => {
+                let block = city.get_block(id)?;
+                let mut lines = vec![format!("Objects: {}", block.objects.len())];
+                let scada_buildings = block
+                    .objects
+                    .iter()
+                    .filter_map(|obj| obj.as_any().downcast_ref::<Building>())
+                    .filter(|building| building.has_scada);
+                for (index, building) in scada_buildings.enumerate() {
+                    let status = match (building.scada_state, &building.compromised_by) {
+                        (ScadaState::Normal, _) => "OK".to_string(),
+                        (ScadaState::Offline, _) => "OFFLINE".to_string(),
+                        (ScadaState::Compromised, Some(team)) => {
+                            format!("COMPROMISED by {}", team)
+                        }
+                        (ScadaState::Compromised, None) => "COMPROMISED".to_string(),
+                    };
+                    lines.push(format!("SCADA {}: {}", index + 1, status));
+                }
+                if lines.len() == 1 {
+                    lines.push("SCADA: none".to_string());
+                }
+                Some((format!("BLOCK {}", id), lines))
+            }
+        }
+    }
+}
+
+/// Upper-case name of a light state, for the info panel
+fn light_name(state: LightState) -> &'static str {
+    match state {
+        LightState::Red(_) => "RED",
+        LightState::Yellow(_) => "YELLOW",
+        LightState::Green(_) => "GREEN",
+    }
+}
+
+/// Tracks the selected entity and draws its highlight and info panel
+#[derive(Default)]
+pub struct Selector {
+    selected: Option<Selection>,
+}
+
+impl Selector {
+    /// Creates a selector with nothing selected
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The selected entity, if any
+    pub fn selected(&self) -> Option<Selection> {
+        self.selected
+    }
+
+    /// Handles selection input
+    ///
+    /// Left click selects the entity under the mouse, or deselects on empty
+    /// road; Escape deselects, and the main loop skips its Escape reset
+    /// while something is selected. A selected car that has left the city is
+    /// deselected as well.
+    ///
+    /// # Arguments
+    /// * `city` - City to pick entities from
+    /// * `mouse` - City position under the mouse, from `Camera::mouse_world_position`
+    pub fn handle_input(&mut self, city: &City, mouse: (f32, f32)) {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            self.selected = Selection::pick(city, mouse.0, mouse.1);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.selected = None;
+        }
+        if self.selected.is_some_and(|selection| selection.bounds(city).is_none()) {
+            self.selected = None;
+        }
+    }
+
+    /// Draws the highlight outline around the selected entity
+    ///
+    /// Drawn with the city, so the outline follows the camera.
+    ///
+    /// # Arguments
+    /// * `city` - City the selection belongs to
+    pub fn render_highlight(&self, city: &City) {
+        let Some(bounds) = self.selected.and_then(|selection| selection.bounds(city)) else {
+            return;
+        };
+
+        draw_rectangle_lines(
+            bounds.x - HIGHLIGHT_PADDING,
+            bounds.y - HIGHLIGHT_PADDING,
+            bounds.w + HIGHLIGHT_PADDING * 2.0,
+            bounds.h + HIGHLIGHT_PADDING * 2.0,
+            2.0,
+            HIGHLIGHT_COLOR,
+        );
+    }
+
+    /// Draws the info panel of the selected entity
+    ///
+    /// # Arguments
+    /// * `city` - City the selection belongs to
+    pub fn render(&self, city: &City) {
+        let Some((title, lines)) = self.selected.and_then(|selection| selection.describe(city))
+        else {
+            return;
+        };
+
+        // Panel in the top-left corner: title bar, then one line per detail
+        let panel_x = 10.0;
+        let panel_y = 10.0;
+        let panel_height = TITLE_HEIGHT + lines.len() as f32 * LINE_HEIGHT + 10.0;
+
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            panel_height,
+            Color::new(0.1, 0.1, 0.15, 0.9),
+        );
+        draw_rectangle_lines(panel_x, panel_y, PANEL_WIDTH, panel_height, 2.0, HIGHLIGHT_COLOR);
+        draw_rectangle(
+            panel_x,
+            panel_y,
+            PANEL_WIDTH,
+            TITLE_HEIGHT,
+            Color::new(0.05, 0.2, 0.25, 1.0),
+        );
+        draw_text(&title, panel_x + 10.0, panel_y + 18.0, 20.0, HIGHLIGHT_COLOR);
+
+        let mut y = panel_y + TITLE_HEIGHT + LINE_HEIGHT;
+        for line in &lines {
+            draw_text(line, panel_x + 10.0, y, 16.0, Color::new(0.85, 0.85, 0.85, 1.0));
+            y += LINE_HEIGHT;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intersection::Intersection;
+    use crate::models::{Car, VehicleStyle, VehicleType, CarLocation, Direction, TrafficRule};
+
+    /// City with one intersection at the center of the 800x600 test screen
+    /// and one car driving down across it
+    fn city_with_car_on_intersection() -> City {
+        let mut city = City::new();
+        city.add_intersection(Intersection::new(0.5, 0.5, 0));
+        city.add_car(Car {
+            id: 7,
+            x_percent: 0.5,
+            y_percent: 0.5,
+            direction: Direction::Down,
+            velocity: 42.0,
+            target_velocity: 42.0,
+            vehicle_type: VehicleType::Car,
+            style: VehicleStyle::Sedan,
+            color: RED,
+            road_index: 1,
+            next_turn: Some(Direction::Left),
+            just_turned: false,
+            in_intersection: true,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
+            location: CarLocation::OnRoad { road_id: 1 },
+            destination: None,
+            route: Vec::new(),
+            traffic_rule: TrafficRule::LeftHand,
+        });
+        city
+    }
+
+    #[test]
+    fn test_pick_prefers_cars_over_intersections() {
+        let city = city_with_car_on_intersection();
+
+        assert_eq!(Selection::pick(&city, 400.0, 310.0), Some(Selection::Car(7)));
+        assert_eq!(Selection::pick(&city, 430.0, 300.0), Some(Selection::Intersection(0)));
+        assert_eq!(Selection::pick(&city, 400.0, 100.0), None);
+    }
+
+    #[test]
+    fn test_car_description_follows_the_car() {
+        let mut city = city_with_car_on_intersection();
+        let selection = Selection::Car(7);
+
+        let (title, lines) = selection.describe(&city).unwrap();
+        assert_eq!(title, "CAR 7");
+        assert!(lines.contains(&"Speed: 42 px/s".to_string()));
+        assert!(lines.contains(&"Next turn: Left".to_string()));
+
+        city.cars[0].velocity = 0.0;
+        let (_, lines) = selection.describe(&city).unwrap();
+        assert!(lines.contains(&"Speed: 0 px/s".to_string()));
+
+        city.clear_cars();
+        assert_eq!(selection.describe(&city), None);
+    }
+}
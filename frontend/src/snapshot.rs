@@ -0,0 +1,210 @@
+//! City snapshots for saving and resuming a session
+//!
+//! This module handles:
+//! - CitySnapshot: The whole simulation state of the dashboard
+//! - Controls: The operator's switches, kept outside the city
+//! - Writing snapshots to the save file and reading them back
+//! - The save file path, read from `SAVE_PATH`
+//!
+//! A snapshot holds the cars, pedestrians and intersections (light phases,
+//! timers, overrides and stop sign queues), closed roads, the SCADA state of
+//! every building, fires, power, the LED display's message queue, the
+//! barrier, how many cars are parked in each parking lot, the traffic rule
+//! and light timing mode, and the emergency stop, barrier and broken LED
+//! switches. Danger mode is the `DANGER` message in the LED queue. Blocks
+//! are not saved: they are generated again from the road layout, and only
+//! the state of their objects is restored. Scores and weather come from the
+//! server.
+//!
+//! F5 saves and F9 loads, as do the server's `city_save` and `city_load`
+//! events. The web build has no file system, so it can't do either.
+
+use crate::block::ScadaState;
+use crate::intersection::Intersection;
+use crate::led_display_object::LEDAlert;
+use crate::models::{Car, Pedestrian, TrafficRule};
+use crate::road::Road;
+use crate::team::Team;
+use macroquad::prelude::Color;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Format version written to the save file; files of other versions are refused
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Name of the save file used when `SAVE_PATH` is not set
+pub const DEFAULT_SAVE_FILE: &str = "snapshot.json";
+
+/// Path of the save file: `SAVE_PATH` if set, otherwise `DEFAULT_SAVE_FILE`
+/// next to the executable (or in the working directory if its location is
+/// unknown)
+pub fn save_path() -> String {
+    if let Ok(path) = std::env::var("SAVE_PATH") {
+        return path;
+    }
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(DEFAULT_SAVE_FILE)))
+        .map_or_else(
+            || DEFAULT_SAVE_FILE.to_string(),
+            |path| path.to_string_lossy().into_owned(),
+        )
+}
+
+/// The operator's switches, held by the main loop rather than the city
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Controls {
+    /// Emergency stop: every traffic light forced red
+    pub all_lights_red: bool,
+
+    /// Whether the barrier gate is raised
+    pub barrier_open: bool,
+
+    /// The attacker's message while the LED display is broken
+    pub led_broken: Option<String>,
+}
+
+/// SCADA state of one building
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuildingScada {
+    /// ID of the block containing the building
+    pub block_id: usize,
+
+    pub state: ScadaState,
+
+    /// Team that compromised the building
+    pub compromised_by: Option<Team>,
+}
+
+/// The dashboard's simulation state, as written to the save file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CitySnapshot {
+    /// Format version, `SNAPSHOT_VERSION` when written
+    pub version: u32,
+
+    /// Cars on the road, with their routes and turns in progress
+    pub cars: Vec<Car>,
+
+    /// Pedestrians walking through the city
+    pub pedestrians: Vec<Pedestrian>,
+
+    /// Intersections with their light phases, timers, overrides and queues
+    pub intersections: Vec<Intersection>,
+
+    /// Roads by ID; only whether each is closed is restored
+    pub roads: Vec<Road>,
+
+    /// SCADA state of every building with SCADA, by block ID
+    pub scada: Vec<BuildingScada>,
+
+    /// Intensity of each building fire, by block ID
+    pub fires: HashMap<usize, f32>,
+
+    /// Blocks whose buildings have no power
+    pub unpowered_blocks: Vec<usize>,
+
+    /// Alerts raised on the LED display
+    pub led_messages: Vec<LEDAlert>,
+
+    /// Color of the team that broke the barrier
+    #[serde(with = "crate::snapshot::color::optional")]
+    pub barrier_broken_by: Option<Color>,
+
+    /// Cars parked in each parking lot, by block ID
+    pub parked_cars: HashMap<usize, usize>,
+
+    /// Side of the road traffic keeps to
+    pub traffic_rule: TrafficRule,
+
+    /// Whether green durations follow the queues at each intersection
+    pub adaptive_lights: bool,
+
+    pub controls: Controls,
+}
+
+impl CitySnapshot {
+    /// Writes the snapshot to a file as JSON
+    ///
+    /// # Arguments
+    /// * `path` - File to write, replaced if it exists
+    ///
+    /// # Returns
+    /// A description of what went wrong, if the file could not be written
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string(self).map_err(|err| err.to_string())?;
+        std::fs::write(path, json).map_err(|err| err.to_string())
+    }
+
+    /// Writes the snapshot to a file; the web build has none to write to
+    #[cfg(target_arch = "wasm32")]
+    pub fn save(&self, _path: &str) -> Result<(), String> {
+        Err("the web build has no file system".to_string())
+    }
+
+    /// Reads a snapshot written by `save`
+    ///
+    /// # Arguments
+    /// * `path` - File to read
+    ///
+    /// # Returns
+    /// The snapshot, or a description of why the file could not be read or
+    /// parsed, or was written by another version
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let value: serde_json::Value = serde_json::from_str(&json).map_err(|err| err.to_string())?;
+        match value.get("version").and_then(|version| version.as_u64()) {
+            Some(version) if version == SNAPSHOT_VERSION as u64 => {
+                serde_json::from_value(value).map_err(|err| err.to_string())
+            }
+            version => Err(format!(
+                "snapshot version {} is not supported (expected {})",
+                version.map_or_else(|| "none".to_string(), |v| v.to_string()),
+                SNAPSHOT_VERSION
+            )),
+        }
+    }
+
+    /// Reads a snapshot; the web build has no file system to read it from
+    #[cfg(target_arch = "wasm32")]
+    pub fn load(_path: &str) -> Result<Self, String> {
+        Err("the web build has no file system".to_string())
+    }
+}
+
+/// Serializes a macroquad `Color` as `[r, g, b, a]`, for
+/// `#[serde(with = "crate::snapshot::color")]`
+pub mod color {
+    use macroquad::prelude::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b, color.a].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let [r, g, b, a] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Color::new(r, g, b, a))
+    }
+
+    /// The same for `Option<Color>`, with `None` as `null`
+    pub mod optional {
+        use macroquad::prelude::Color;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            color: &Option<Color>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            color.map(|color| [color.r, color.g, color.b, color.a]).serialize(serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Color>, D::Error> {
+            let color = <Option<[f32; 4]>>::deserialize(deserializer)?;
+            Ok(color.map(|[r, g, b, a]| Color::new(r, g, b, a)))
+        }
+    }
+}
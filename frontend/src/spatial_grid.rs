@@ -0,0 +1,92 @@
+//! Uniform grid for finding things near a point
+//!
+//! This module handles:
+//! - SpatialGrid: Indices of points bucketed into square cells
+//! - Finding every point within a distance of a position by looking only at
+//!   the cells that distance reaches, instead of at every point
+//!
+//! The grid is cheap to build, so it is rebuilt from scratch every frame
+//! rather than kept up to date as things move.
+
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// Indices of points bucketed into square cells by position
+#[derive(Clone, Debug)]
+pub struct SpatialGrid {
+    /// Side length of a cell in pixels
+    cell_size: f32,
+
+    /// Indices of the points in each cell, keyed by (cell_x, cell_y)
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// Builds a grid over a list of points
+    ///
+    /// # Arguments
+    /// * `cell_size` - Side length of a cell in pixels, best about the
+    ///   distance most queries look
+    /// * `points` - Positions in pixels; each is stored under its index in
+    ///   this list
+    pub fn new(cell_size: f32, points: impl IntoIterator<Item = Vec2>) -> Self {
+        let mut grid = Self {
+            cell_size,
+            cells: HashMap::new(),
+        };
+        for (index, point) in points.into_iter().enumerate() {
+            grid.cells.entry(grid.cell_of(point)).or_default().push(index);
+        }
+        grid
+    }
+
+    /// Cell containing a point
+    fn cell_of(&self, point: Vec2) -> (i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Indices of the points that may lie within `radius` of `center`
+    ///
+    /// Every point within the radius is returned, along with some just
+    /// outside it from the same cells, so callers still check the distance
+    /// they care about. The order is unspecified.
+    ///
+    /// # Arguments
+    /// * `center` - Position to search around, in pixels
+    /// * `radius` - Search distance in pixels
+    pub fn query(&self, center: Vec2, radius: f32) -> impl Iterator<Item = usize> + '_ {
+        let (min_x, min_y) = self.cell_of(center - Vec2::splat(radius));
+        let (max_x, max_y) = self.cell_of(center + Vec2::splat(radius));
+        (min_x..=max_x)
+            .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_finds_points_in_reach_only() {
+        let points = [
+            vec2(10.0, 10.0),
+            vec2(95.0, 10.0),
+            vec2(-5.0, -5.0),
+            vec2(400.0, 400.0),
+        ];
+        let grid = SpatialGrid::new(50.0, points);
+
+        let mut found: Vec<_> = grid.query(vec2(50.0, 10.0), 60.0).collect();
+        found.sort();
+        assert_eq!(found, [0, 1, 2]);
+
+        assert_eq!(grid.query(vec2(400.0, 400.0), 1.0).collect::<Vec<_>>(), [3]);
+        assert_eq!(grid.query(vec2(250.0, 250.0), 10.0).count(), 0);
+    }
+}
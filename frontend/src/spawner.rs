@@ -1,17 +1,154 @@
 //! Car spawning system
 //!
 //! This module handles car spawning logic:
-//! - CarSpawner: Manages spawning at regular intervals
+//! - SpawnTimer: Interval timer shared by the car and pedestrian spawners
+//! - SpawnConfig: Spawn interval, car cap, per-road and per-style spawn weights
+//! - CarSpawner: Manages spawning at regular intervals, skipping closed
+//!   roads, and picks which new cars visit a guarded compound
 //! - spawn_car: Creates new cars at random positions with random properties
+//! - spawn_emergency_car: Dispatches an emergency vehicle along a given road
 //!
-//! Cars are spawned off-screen at road edges and follow left-hand traffic rules.
+//! Cars are spawned off-screen at road edges, in the lane the city's traffic
+//! rule gives their direction.
 
 use crate::constants::{
+    rendering::{BUS_COLOR, CAR_COLORS, EMERGENCY_CAR_COLOR, POLICE_COLOR},
     road_network::{HORIZONTAL_ROAD_POSITIONS, VERTICAL_ROAD_POSITIONS},
-    vehicle::{LANE_OFFSET, TURN_PROBABILITY},
+    vehicle::{CAR_SPAWN_INTERVAL, STYLE_SPAWN_WEIGHTS, TURN_PROBABILITY, VISITOR_CHANCE},
 };
-use crate::models::{Car, CarLocation, Direction};
+use crate::models::{Car, VehicleStyle, VehicleType, CarLocation, Direction, TrafficRule};
+use crate::screen::{screen_height, screen_width};
 use macroquad::prelude::*;
+use serde::Deserialize;
+
+/// Number of roads cars spawn on: the vertical roads, then the horizontal ones
+pub const ROAD_COUNT: usize = VERTICAL_ROAD_POSITIONS.len() + HORIZONTAL_ROAD_POSITIONS.len();
+
+// ============================================================================
+// SpawnTimer - Interval timing
+// ============================================================================
+
+/// Tracks the simulation time since the last spawn
+///
+/// Spawners use it so things are spawned at consistent intervals rather
+/// than every frame.
+pub struct SpawnTimer {
+    since_last_spawn: f32,
+    spawn_interval: f32,
+}
+
+impl SpawnTimer {
+    /// Creates a timer with a specified spawn interval
+    ///
+    /// # Arguments
+    /// * `interval` - Time between spawns in seconds
+    pub fn new(interval: f32) -> Self {
+        Self {
+            since_last_spawn: 0.0,
+            spawn_interval: interval,
+        }
+    }
+
+    /// Changes the time between spawns, keeping the time already waited
+    ///
+    /// # Arguments
+    /// * `interval` - Time between spawns in seconds
+    pub fn set_interval(&mut self, interval: f32) {
+        self.spawn_interval = interval;
+    }
+
+    /// Time between spawns in seconds
+    pub fn interval(&self) -> f32 {
+        self.spawn_interval
+    }
+
+    /// Advances the timer, restarting the interval once it has passed
+    ///
+    /// # Arguments
+    /// * `dt` - Simulation time step in seconds
+    ///
+    /// # Returns
+    /// `true` if it is time to spawn
+    pub fn tick(&mut self, dt: f32) -> bool {
+        self.since_last_spawn += dt;
+
+        if self.since_last_spawn > self.spawn_interval {
+            self.since_last_spawn = 0.0;
+            return true;
+        }
+        false
+    }
+}
+
+// ============================================================================
+// SpawnConfig - Spawner settings
+// ============================================================================
+
+/// Settings of the car spawner
+///
+/// Read from the `spawner` section of `city_config.json`; fields left out
+/// keep their defaults.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SpawnConfig {
+    /// Time between spawns in seconds
+    pub interval: f32,
+
+    /// Number of cars in the city at which spawning pauses
+    pub max_cars: usize,
+
+    /// Relative chance of each road being picked for a new car, by road
+    /// index (0-2 vertical, 3-4 horizontal)
+    pub road_weights: [f32; ROAD_COUNT],
+
+    /// Relative chance of a new car being a sedan, truck, bus or police car,
+    /// in that order
+    pub style_weights: [f32; 4],
+
+    /// Chance (0.0-1.0) of a new sedan or police car heading for a guarded
+    /// compound instead of passing through
+    pub visitor_chance: f32,
+}
+
+impl Default for SpawnConfig {
+    /// Spawns a car every `CAR_SPAWN_INTERVAL` seconds, up to 40 cars, with
+    /// the center vertical road twice as busy as the others and mostly
+    /// sedans, as set by `STYLE_SPAWN_WEIGHTS`; `VISITOR_CHANCE` of the cars
+    /// that fit a parking spot are visitors
+    fn default() -> Self {
+        Self {
+            interval: CAR_SPAWN_INTERVAL,
+            max_cars: 40,
+            road_weights: [1.0, 2.0, 1.0, 1.0, 1.0],
+            style_weights: STYLE_SPAWN_WEIGHTS,
+            visitor_chance: VISITOR_CHANCE,
+        }
+    }
+}
+
+/// Picks an index at random, each with a chance proportional to its weight
+///
+/// # Arguments
+/// * `weights` - Relative chance of each index
+///
+/// # Returns
+/// The index, or None if no index has a positive weight
+fn pick_weighted(weights: &[f32]) -> Option<usize> {
+    let total: f32 = weights.iter().map(|weight| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut roll = rand::gen_range(0.0, total);
+    for (index, &weight) in weights.iter().enumerate() {
+        if weight > 0.0 && roll < weight {
+            return Some(index);
+        }
+        roll -= weight.max(0.0);
+    }
+    // Rounding can leave the roll just past the last weight
+    weights.iter().rposition(|&weight| weight > 0.0)
+}
 
 // ============================================================================
 // CarSpawner - Interval-based spawning
@@ -19,44 +156,135 @@ use macroquad::prelude::*;
 
 /// Manages car spawning at regular intervals
 ///
-/// This struct tracks the last spawn time and ensures cars are spawned
-/// at consistent intervals rather than every frame.
+/// Time is passed in by the caller, so the spawner runs on the simulation
+/// clock and tests can step it without waiting.
 pub struct CarSpawner {
-    last_spawn_time: f64,
-    spawn_interval: f32,
+    timer: SpawnTimer,
+    max_cars: usize,
+    road_weights: [f32; ROAD_COUNT],
+    style_weights: [f32; 4],
+    visitor_chance: f32,
+    closed_roads: [bool; ROAD_COUNT],
 }
 
 impl CarSpawner {
-    /// Creates a new CarSpawner with a specified spawn interval
+    /// Creates a new CarSpawner with the given settings
     ///
     /// # Arguments
-    /// * `interval` - Time between spawns in seconds
+    /// * `config` - Spawn interval, car cap, road and style weights, and
+    ///   visitor chance
     ///
     /// # Example
     /// ```
-    /// let spawner = CarSpawner::new(1.5); // Spawn every 1.5 seconds
+    /// let spawner = CarSpawner::new(SpawnConfig { interval: 1.5, ..SpawnConfig::default() });
     /// ```
-    pub fn new(interval: f32) -> Self {
+    pub fn new(config: SpawnConfig) -> Self {
         Self {
-            last_spawn_time: 0.0,
-            spawn_interval: interval,
+            timer: SpawnTimer::new(config.interval),
+            max_cars: config.max_cars,
+            road_weights: config.road_weights,
+            style_weights: config.style_weights,
+            visitor_chance: config.visitor_chance,
+            closed_roads: [false; ROAD_COUNT],
+        }
+    }
+
+    /// Stops or resumes spawning cars on a road
+    ///
+    /// # Arguments
+    /// * `road_index` - Road to change (0-2 vertical, 3-4 horizontal); others
+    ///   are ignored
+    /// * `closed` - True to stop spawning on the road
+    pub fn set_road_closed(&mut self, road_index: usize, closed: bool) {
+        if let Some(road) = self.closed_roads.get_mut(road_index) {
+            *road = closed;
         }
     }
 
+    /// Changes the time between spawns, to throttle traffic
+    ///
+    /// # Arguments
+    /// * `interval` - Time between spawns in seconds
+    pub fn set_spawn_rate(&mut self, interval: f32) {
+        self.timer.set_interval(interval);
+    }
+
+    /// Time between spawns in seconds
+    pub fn spawn_rate(&self) -> f32 {
+        self.timer.interval()
+    }
+
     /// Attempts to spawn a car if enough time has elapsed
     ///
     /// Checks if the spawn interval has passed since the last spawn.
-    /// If so, spawns a new car and updates the last spawn time.
+    /// If so, spawns a new car on an open road picked by the road weights, in
+    /// a style picked by the style weights, and restarts the interval. Nothing
+    /// is spawned while the city already has `max_cars` cars.
     ///
     /// # Arguments
+    /// * `dt` - Simulation time step in seconds
     /// * `cars` - Mutable vector to add the new car to
-    pub fn try_spawn(&mut self, cars: &mut Vec<Car>) {
-        let current_time = get_time();
+    /// * `rule` - Side of the road the new car keeps to
+    ///
+    /// # Returns
+    /// `true` if a car was spawned
+    pub fn try_spawn(&mut self, dt: f32, cars: &mut Vec<Car>, rule: TrafficRule) -> bool {
+        if !self.timer.tick(dt) || cars.len() >= self.max_cars {
+            return false;
+        }
+        let mut road_weights = self.road_weights;
+        for (weight, &closed) in road_weights.iter_mut().zip(&self.closed_roads) {
+            if closed {
+                *weight = 0.0;
+            }
+        }
+        let Some(road_index) = pick_weighted(&road_weights) else {
+            return false;
+        };
+        // Sedans if every style is weighted out
+        let style = pick_weighted(&self.style_weights)
+            .map_or(VehicleStyle::Sedan, |index| VehicleStyle::ALL[index]);
+        spawn_car(cars, road_index, style, rule);
+        true
+    }
 
-        if current_time - self.last_spawn_time > self.spawn_interval as f64 {
-            spawn_car(cars);
-            self.last_spawn_time = current_time;
+    /// Decides whether a new car visits a guarded compound
+    ///
+    /// Only sedans and police cars fit a compound's parking spots.
+    ///
+    /// # Arguments
+    /// * `car` - The newly spawned car
+    /// * `destinations` - Block IDs of the guarded compounds in the city
+    ///
+    /// # Returns
+    /// The block the car drives to, or None if it just passes through
+    pub fn pick_destination(&self, car: &Car, destinations: &[usize]) -> Option<usize> {
+        let fits = matches!(car.style, VehicleStyle::Sedan | VehicleStyle::Police)
+            && car.vehicle_type == VehicleType::Car;
+        if !fits || destinations.is_empty() || rand::gen_range(0.0, 1.0) >= self.visitor_chance {
+            return None;
         }
+        Some(destinations[rand::gen_range(0, destinations.len())])
+    }
+
+    /// Dispatches an emergency vehicle from a random road edge
+    ///
+    /// Emergency vehicles are sent on demand rather than on the interval.
+    ///
+    /// # Arguments
+    /// * `cars` - Mutable vector to add the vehicle to
+    /// * `rule` - Side of the road the vehicle keeps to
+    ///
+    /// # Returns
+    /// The road index and direction the vehicle drives along
+    pub fn spawn_emergency(&self, cars: &mut Vec<Car>, rule: TrafficRule) -> (usize, Direction) {
+        spawn_emergency_car_at_random_edge(cars, rule)
+    }
+}
+
+impl Default for CarSpawner {
+    fn default() -> Self {
+        Self::new(SpawnConfig::default())
     }
 }
 
@@ -64,46 +292,51 @@ impl CarSpawner {
 // Car Spawning Function
 // ============================================================================
 
-/// Spawns a new car at a random road edge
+/// Spawns a new car at one end of a road
 ///
-/// Cars are spawned just off-screen and assigned:
-/// - Random road (3 vertical, 2 horizontal)
-/// - Random direction (with proper lane selection)
-/// - Random color
+/// Cars are spawned just off-screen at their style's top speed and assigned:
+/// - Random direction along the road (with proper lane selection)
+/// - The style's color: random for sedans and trucks, fixed for buses and
+///   police cars
 /// - Random chance of planning a turn at next intersection
 ///
 /// # Arguments
 /// * `cars` - Mutable vector to add the new car to
+/// * `road_index` - Road to spawn on (0-2 vertical, 3-4 horizontal)
+/// * `style` - Body style of the new car
+/// * `rule` - Side of the road the new car keeps to
 ///
-/// # Lane Discipline (Left-hand traffic)
-/// - Vertical roads: Cars going down use left lane, cars going up use right lane
-/// - Horizontal roads: Cars going right use bottom lane, cars going left use top lane
-pub fn spawn_car(cars: &mut Vec<Car>) {
+/// # Lane Discipline
+/// - Left-hand traffic: Cars going down use the left lane and cars going
+///   right the bottom lane; cars going up or left use the other lane
+/// - Right-hand traffic: Every lane is mirrored
+pub fn spawn_car(cars: &mut Vec<Car>, road_index: usize, style: VehicleStyle, rule: TrafficRule) {
     // Road positions as percentages of screen dimensions
     let vertical_percents = VERTICAL_ROAD_POSITIONS;
     let horizontal_percents = HORIZONTAL_ROAD_POSITIONS;
 
-    // Randomly choose vertical or horizontal road
-    let is_vertical = rand::gen_range(0, 2) == 0;
+    let is_vertical = road_index < vertical_percents.len();
 
-    // Random car color selection
-    let car_colors = [BLUE, RED, YELLOW, Color::new(1.0, 0.5, 0.0, 1.0), PURPLE];
-    let color = car_colors[rand::gen_range(0, car_colors.len())];
+    let color = match style {
+        VehicleStyle::Sedan | VehicleStyle::Truck => {
+            CAR_COLORS[rand::gen_range(0, CAR_COLORS.len())]
+        }
+        VehicleStyle::Bus => BUS_COLOR,
+        VehicleStyle::Police => POLICE_COLOR,
+    };
+    let speed = VehicleType::Car.max_speed() * style.speed_factor();
 
     if is_vertical {
         // Spawn on vertical road (moving down or up)
-        let road_index = rand::gen_range(0, vertical_percents.len());
         let road_center_percent = vertical_percents[road_index];
         let going_down = rand::gen_range(0, 2) == 0;
 
-        // Cars going down use left lane (offset to the left)
-        // Cars going up use right lane (offset to the right)
-        let lane_offset_percent = LANE_OFFSET / screen_width(); // Offset in x direction
-        let x_percent = if going_down {
-            road_center_percent - lane_offset_percent
+        let direction = if going_down {
+            Direction::Down
         } else {
-            road_center_percent + lane_offset_percent
+            Direction::Up
         };
+        let x_percent = road_center_percent + rule.lane_offset(direction).x / screen_width();
 
         // Randomly decide if car will turn
         let next_turn = if rand::gen_range(0.0, 1.0) < TURN_PROBABILITY {
@@ -118,36 +351,40 @@ pub fn spawn_car(cars: &mut Vec<Car>) {
         };
 
         cars.push(Car {
+            id: Car::next_id(),
             x_percent,
             y_percent: if going_down { -0.05 } else { 1.05 }, // Spawn just off screen
-            direction: if going_down {
-                Direction::Down
-            } else {
-                Direction::Up
-            },
+            direction,
+            velocity: speed,
+            target_velocity: speed,
+            vehicle_type: VehicleType::Car,
+            style,
             color,
             road_index,
             next_turn,
             just_turned: false,
             in_intersection: false,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
             location: CarLocation::OnRoad {
                 road_id: road_index,
             },
+            destination: None,
+            route: Vec::new(),
+            traffic_rule: rule,
         });
     } else {
         // Spawn on horizontal road (moving right or left)
-        let road_index = rand::gen_range(0, horizontal_percents.len());
-        let road_center_percent = horizontal_percents[road_index];
+        let road_center_percent = horizontal_percents[road_index - vertical_percents.len()];
         let going_right = rand::gen_range(0, 2) == 0;
 
-        // Cars going right use bottom lane (offset down)
-        // Cars going left use top lane (offset up)
-        let lane_offset_percent = LANE_OFFSET / screen_height(); // Offset in y direction
-        let y_percent = if going_right {
-            road_center_percent + lane_offset_percent
+        let direction = if going_right {
+            Direction::Right
         } else {
-            road_center_percent - lane_offset_percent
+            Direction::Left
         };
+        let y_percent = road_center_percent + rule.lane_offset(direction).y / screen_height();
 
         // Randomly decide if car will turn
         let next_turn = if rand::gen_range(0.0, 1.0) < TURN_PROBABILITY {
@@ -162,21 +399,265 @@ pub fn spawn_car(cars: &mut Vec<Car>) {
         };
 
         cars.push(Car {
+            id: Car::next_id(),
             x_percent: if going_right { -0.05 } else { 1.05 }, // Spawn just off screen
             y_percent,
-            direction: if going_right {
-                Direction::Right
-            } else {
-                Direction::Left
-            },
+            direction,
+            velocity: speed,
+            target_velocity: speed,
+            vehicle_type: VehicleType::Car,
+            style,
             color,
-            road_index: road_index + 3, // Offset by 3 since vertical roads are 0-2
+            road_index,
             next_turn,
             just_turned: false,
             in_intersection: false,
+            turn: None,
+            turn_signal: None,
+            pull_over: 0.0,
             location: CarLocation::OnRoad {
-                road_id: road_index + 3,
+                road_id: road_index,
             },
+            destination: None,
+            route: Vec::new(),
+            traffic_rule: rule,
+        });
+    }
+}
+
+// ============================================================================
+// Emergency Vehicles
+// ============================================================================
+
+/// Position just off-screen where a car enters a road, in the lane for its
+/// direction of travel
+///
+/// # Arguments
+/// * `road_index` - Road to enter (vertical roads first, then horizontal)
+/// * `direction` - Direction of travel along the road
+/// * `rule` - Side of the road the car keeps to
+///
+/// # Returns
+/// `(x_percent, y_percent)`, or `None` if the road does not exist or does
+/// not run along `direction`
+fn road_entry_position(
+    road_index: usize,
+    direction: Direction,
+    rule: TrafficRule,
+) -> Option<(f32, f32)> {
+    let lane = rule.lane_offset(direction);
+    match direction {
+        Direction::Down | Direction::Up => {
+            let center = *VERTICAL_ROAD_POSITIONS.get(road_index)?;
+            let x = center + lane.x / screen_width();
+            Some(if direction == Direction::Down {
+                (x, -0.05)
+            } else {
+                (x, 1.05)
+            })
+        }
+        Direction::Right | Direction::Left => {
+            let horizontal_index = road_index.checked_sub(VERTICAL_ROAD_POSITIONS.len())?;
+            let center = *HORIZONTAL_ROAD_POSITIONS.get(horizontal_index)?;
+            let y = center + lane.y / screen_height();
+            Some(if direction == Direction::Right {
+                (-0.05, y)
+            } else {
+                (1.05, y)
+            })
+        }
+    }
+}
+
+/// Spawns an emergency vehicle entering `road_index` from the screen edge
+///
+/// Emergency vehicles drive straight across the city at full speed,
+/// ignoring traffic lights.
+///
+/// # Arguments
+/// * `cars` - Mutable vector to add the new car to
+/// * `road_index` - Road to drive along (0-2 vertical, 3-4 horizontal)
+/// * `direction` - Direction of travel; must run along the road
+/// * `rule` - Side of the road the vehicle keeps to
+///
+/// # Returns
+/// `true` if the vehicle was spawned, `false` if the road does not exist or
+/// runs across `direction`
+pub fn spawn_emergency_car(
+    cars: &mut Vec<Car>,
+    road_index: usize,
+    direction: Direction,
+    rule: TrafficRule,
+) -> bool {
+    let Some((x_percent, y_percent)) = road_entry_position(road_index, direction, rule) else {
+        return false;
+    };
+
+    cars.push(Car {
+        id: Car::next_id(),
+        x_percent,
+        y_percent,
+        direction,
+        velocity: VehicleType::EmergencyVehicle.max_speed(),
+        target_velocity: VehicleType::EmergencyVehicle.max_speed(),
+        vehicle_type: VehicleType::EmergencyVehicle,
+        style: VehicleStyle::Sedan,
+        color: EMERGENCY_CAR_COLOR,
+        road_index,
+        next_turn: None,
+        just_turned: false,
+        in_intersection: false,
+        turn: None,
+        turn_signal: None,
+        pull_over: 0.0,
+        location: CarLocation::OnRoad {
+            road_id: road_index,
+        },
+        destination: None,
+        route: Vec::new(),
+        traffic_rule: rule,
+    });
+    true
+}
+
+/// Spawns an emergency vehicle on a random road, from a random end
+///
+/// # Arguments
+/// * `cars` - Mutable vector to add the vehicle to
+/// * `rule` - Side of the road the vehicle keeps to
+///
+/// # Returns
+/// The road index and direction the vehicle was dispatched along
+pub fn spawn_emergency_car_at_random_edge(
+    cars: &mut Vec<Car>,
+    rule: TrafficRule,
+) -> (usize, Direction) {
+    let vertical = VERTICAL_ROAD_POSITIONS.len();
+    let road_index = rand::gen_range(0, vertical + HORIZONTAL_ROAD_POSITIONS.len());
+    let forward = rand::gen_range(0, 2) == 0;
+    let direction = match (road_index < vertical, forward) {
+        (true, true) => Direction::Down,
+        (true, false) => Direction::Up,
+        (false, true) => Direction::Right,
+        (false, false) => Direction::Left,
+    };
+
+    spawn_emergency_car(cars, road_index, direction, rule);
+    (road_index, direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::vehicle::LANE_OFFSET;
+
+    #[test]
+    fn test_emergency_car_enters_in_its_lane() {
+        let mut cars = Vec::new();
+        assert!(spawn_emergency_car(&mut cars, 0, Direction::Up, TrafficRule::LeftHand));
+        assert!(spawn_emergency_car(&mut cars, 3, Direction::Left, TrafficRule::LeftHand));
+
+        let up = &cars[0];
+        assert_eq!(up.vehicle_type, VehicleType::EmergencyVehicle);
+        assert!((up.x() - (VERTICAL_ROAD_POSITIONS[0] * 800.0 + LANE_OFFSET)).abs() < 0.01);
+        assert!(up.y_percent > 1.0);
+
+        let left = &cars[1];
+        assert!((left.y() - (HORIZONTAL_ROAD_POSITIONS[0] * 600.0 - LANE_OFFSET)).abs() < 0.01);
+        assert!(left.x_percent > 1.0);
+    }
+
+    #[test]
+    fn test_right_hand_traffic_spawns_in_the_mirrored_lane() {
+        let mut cars = Vec::new();
+        while !cars.iter().any(|car: &Car| car.direction == Direction::Down) {
+            cars.clear();
+            spawn_car(&mut cars, 0, VehicleStyle::Sedan, TrafficRule::RightHand);
+        }
+
+        let down = &cars[0];
+        assert_eq!(down.traffic_rule, TrafficRule::RightHand);
+        assert!((down.x() - (VERTICAL_ROAD_POSITIONS[0] * 800.0 + LANE_OFFSET)).abs() < 0.01);
+        assert!(down.y_percent < 0.0);
+    }
+
+    #[test]
+    fn test_spawns_follow_road_weights() {
+        let mut spawner = CarSpawner::new(SpawnConfig {
+            interval: 0.1,
+            max_cars: usize::MAX,
+            road_weights: [1.0, 2.0, 1.0, 1.0, 0.0],
+            ..SpawnConfig::default()
+        });
+        let mut cars = Vec::new();
+        for _ in 0..5000 {
+            assert!(spawner.try_spawn(0.2, &mut cars, TrafficRule::LeftHand));
+        }
+
+        let mut per_road = [0usize; ROAD_COUNT];
+        for car in &cars {
+            per_road[car.road_index] += 1;
+        }
+        // Expected 1000, 2000, 1000, 1000 and 0 cars
+        for (road_index, expected) in [1000, 2000, 1000, 1000].into_iter().enumerate() {
+            assert!(per_road[road_index].abs_diff(expected) < 150, "{:?}", per_road);
+        }
+        assert_eq!(per_road[4], 0);
+    }
+
+    #[test]
+    fn test_closed_roads_get_no_cars() {
+        let mut spawner = CarSpawner::new(SpawnConfig {
+            interval: 0.1,
+            max_cars: usize::MAX,
+            ..SpawnConfig::default()
         });
+        let mut cars = Vec::new();
+        for road_index in [0, 1, 3] {
+            spawner.set_road_closed(road_index, true);
+        }
+        spawner.set_road_closed(3, false);
+        for _ in 0..200 {
+            assert!(spawner.try_spawn(0.2, &mut cars, TrafficRule::LeftHand));
+        }
+        assert!(cars.iter().all(|car| ![0, 1].contains(&car.road_index)));
+
+        // Nothing spawns once every road is closed
+        for road_index in 0..ROAD_COUNT {
+            spawner.set_road_closed(road_index, true);
+        }
+        assert!(!spawner.try_spawn(0.2, &mut cars, TrafficRule::LeftHand));
+    }
+
+    #[test]
+    fn test_spawner_stops_at_max_cars() {
+        let mut spawner = CarSpawner::new(SpawnConfig {
+            interval: 0.5,
+            max_cars: 5,
+            ..SpawnConfig::default()
+        });
+        let mut cars = Vec::new();
+
+        let spawned = (0..100)
+            .filter(|_| spawner.try_spawn(1.0, &mut cars, TrafficRule::LeftHand))
+            .count();
+        assert_eq!(spawned, 5);
+        assert_eq!(cars.len(), 5);
+
+        // Spawning resumes once cars leave, at the throttled rate
+        cars.truncate(3);
+        spawner.set_spawn_rate(2.0);
+        assert!(!spawner.try_spawn(1.0, &mut cars, TrafficRule::LeftHand));
+        assert!(spawner.try_spawn(1.5, &mut cars, TrafficRule::LeftHand));
+        assert_eq!(cars.len(), 4);
+    }
+
+    #[test]
+    fn test_emergency_car_rejects_direction_across_road() {
+        let mut cars = Vec::new();
+        assert!(!spawn_emergency_car(&mut cars, 0, Direction::Right, TrafficRule::LeftHand));
+        assert!(!spawn_emergency_car(&mut cars, 3, Direction::Down, TrafficRule::LeftHand));
+        assert!(!spawn_emergency_car(&mut cars, 99, Direction::Down, TrafficRule::LeftHand));
+        assert!(cars.is_empty());
     }
 }
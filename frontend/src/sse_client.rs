@@ -11,21 +11,34 @@
 //!
 //! data: {"type": "led_display_broken", "team": "Blue Team"}
 //! ```
+//!
+//! Events broadcast by the backend also carry an `id:` line. The client
+//! remembers the last ID it saw and sends it as `Last-Event-ID` when
+//! reconnecting, so the server can replay anything missed in between.
+//!
+//! When the connection drops the client retries with exponential backoff,
+//! starting at `reconnect_interval` and doubling up to `max_reconnect_interval`.
 
 use crate::events::{EventSender, GameEvent};
 use std::io::BufRead;
 use std::thread;
 use std::time::Duration;
 
+/// SSE endpoint used when `SSE_URL` is not set
+const DEFAULT_SSE_URL: &str = "http://localhost:3000/events";
+
 /// Configuration for SSE client
 #[derive(Clone)]
 pub struct SseConfig {
     /// Server URL endpoint (e.g., "http://localhost:3000/events")
     pub url: String,
 
-    /// Reconnection interval in seconds when connection fails
+    /// Initial reconnection delay in seconds when connection fails
     pub reconnect_interval: u64,
 
+    /// Upper bound in seconds for the reconnection delay
+    pub max_reconnect_interval: u64,
+
     /// Request timeout in seconds
     pub timeout: u64,
 }
@@ -33,8 +46,9 @@ pub struct SseConfig {
 impl Default for SseConfig {
     fn default() -> Self {
         Self {
-            url: "http://localhost:3000/events".to_string(),
-            reconnect_interval: 5,
+            url: DEFAULT_SSE_URL.to_string(),
+            reconnect_interval: 1,
+            max_reconnect_interval: 30,
             timeout: 300, // 5 minutes - generous timeout for long-lived SSE connections
         }
     }
@@ -44,6 +58,12 @@ impl Default for SseConfig {
 pub struct SseClient {
     config: SseConfig,
     sender: EventSender,
+
+    /// ID of the last event received (sent as Last-Event-ID on reconnect)
+    last_event_id: Option<String>,
+
+    /// Delay in seconds before the next reconnection attempt
+    reconnect_delay: u64,
 }
 
 impl SseClient {
@@ -53,7 +73,12 @@ impl SseClient {
     /// * `config` - SSE configuration including server URL
     /// * `sender` - Event sender to communicate with main game loop
     pub fn new(config: SseConfig, sender: EventSender) -> Self {
-        Self { config, sender }
+        Self {
+            reconnect_delay: config.reconnect_interval,
+            config,
+            sender,
+            last_event_id: None,
+        }
     }
 
     /// Starts the SSE client in a background thread
@@ -63,14 +88,14 @@ impl SseClient {
     ///
     /// # Returns
     /// JoinHandle for the background thread (can be used to stop it if needed)
-    pub fn start(self) -> thread::JoinHandle<()> {
+    pub fn start(mut self) -> thread::JoinHandle<()> {
         thread::spawn(move || {
             self.run_loop();
         })
     }
 
     /// Main loop that handles connection, reconnection, and event processing
-    fn run_loop(&self) {
+    fn run_loop(&mut self) {
         loop {
             // Notify about connection attempt
             let _ = self.sender.send(GameEvent::ConnectionStatus {
@@ -78,44 +103,47 @@ impl SseClient {
                 error: Some("Connecting to server...".to_string()),
             });
 
-            match self.connect_and_receive() {
-                Ok(_) => {
-                    // Connection closed normally
-                    let _ = self.sender.send(GameEvent::ConnectionStatus {
-                        connected: false,
-                        error: Some("Connection closed".to_string()),
-                    });
-                }
-                Err(e) => {
-                    // Connection failed
-                    let error_msg = format!("Connection error: {}", e);
-                    let _ = self.sender.send(GameEvent::ConnectionStatus {
-                        connected: false,
-                        error: Some(error_msg),
-                    });
-                }
-            }
+            let delay = self.reconnect_delay;
+            let error_msg = match self.connect_and_receive() {
+                // Connection closed by the server
+                Ok(_) => format!("Connection lost, retrying in {}s", delay),
+                // Connection failed
+                Err(e) => format!("Connection error: {} (retrying in {}s)", e, delay),
+            };
+            eprintln!("Warning: {}", error_msg);
+            let _ = self.sender.send(GameEvent::ConnectionStatus {
+                connected: false,
+                error: Some(error_msg),
+            });
 
-            // Wait before reconnecting
-            thread::sleep(Duration::from_secs(self.config.reconnect_interval));
+            // Wait before reconnecting, backing off while the server stays down
+            thread::sleep(Duration::from_secs(delay));
+            self.reconnect_delay = next_reconnect_delay(delay, self.config.max_reconnect_interval);
         }
     }
 
     /// Connects to SSE endpoint and processes events
-    fn connect_and_receive(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn connect_and_receive(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         // Create HTTP request with SSE headers
-        let response = ureq::get(&self.config.url)
+        let mut request = ureq::get(&self.config.url)
             .timeout(Duration::from_secs(self.config.timeout))
             .set("Accept", "text/event-stream")
-            .set("Cache-Control", "no-cache")
-            .call()?;
+            .set("Cache-Control", "no-cache");
+
+        // Ask the server to replay events missed while disconnected
+        if let Some(id) = &self.last_event_id {
+            request = request.set("Last-Event-ID", id);
+        }
+
+        let response = request.call()?;
 
         // Check if connection successful
         if response.status() != 200 {
             return Err(format!("HTTP error: {}", response.status()).into());
         }
 
-        // Notify successful connection
+        // Notify successful connection and start backoff over
+        self.reconnect_delay = self.config.reconnect_interval;
         let _ = self.sender.send(GameEvent::ConnectionStatus {
             connected: true,
             error: None,
@@ -131,6 +159,8 @@ impl SseClient {
                 if !data.trim().is_empty() {
                     self.parse_and_send_event(data);
                 }
+            } else if let Some(id) = line.strip_prefix("id:") {
+                self.last_event_id = Some(id.trim().to_string());
             }
             // Ignore comment lines (starting with :) and empty lines
         }
@@ -158,6 +188,22 @@ impl SseClient {
     }
 }
 
+/// Doubles the reconnection delay, capped at `max`
+fn next_reconnect_delay(current: u64, max: u64) -> u64 {
+    current.saturating_mul(2).clamp(1, max.max(1))
+}
+
+/// SSE endpoint to connect to
+///
+/// `SSE_URL` is read when the dashboard starts, falling back to its value
+/// when the dashboard was built, so a build can carry its server's URL.
+pub fn sse_url() -> String {
+    std::env::var("SSE_URL")
+        .ok()
+        .or_else(|| option_env!("SSE_URL").map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_SSE_URL.to_string())
+}
+
 /// Convenience function to start SSE client with default configuration
 ///
 /// # Arguments
@@ -181,3 +227,19 @@ pub fn start_sse_client(url: impl Into<String>, sender: EventSender) -> thread::
     let client = SseClient::new(config, sender);
     client.start()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backs_off_to_max() {
+        let mut delay = SseConfig::default().reconnect_interval;
+        let mut delays = Vec::new();
+        for _ in 0..7 {
+            delays.push(delay);
+            delay = next_reconnect_delay(delay, 30);
+        }
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+    }
+}
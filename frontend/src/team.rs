@@ -0,0 +1,178 @@
+//! Teams taking part in an exercise
+//!
+//! This module handles:
+//! - Team: A team's ID, display name and color
+//! - TeamRegistry: Every team seen this session, registered the first time
+//!   its name shows up in an event or in `city_config.json`
+//! - Picking a color for a new team: the color its name mentions ("Red
+//!   Team"), otherwise the first palette color no other team has
+//!
+//! Team colors tint the log entries, compromised buildings, the broken
+//! barrier arm and the scoreboard rows attributed to each team.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Colors handed out to teams, in order, with the names that pick them
+const TEAM_PALETTE: [(&str, Color); 8] = [
+    ("red", Color::new(0.95, 0.25, 0.25, 1.0)),
+    ("blue", Color::new(0.3, 0.55, 1.0, 1.0)),
+    ("green", Color::new(0.25, 0.85, 0.35, 1.0)),
+    ("yellow", Color::new(0.95, 0.85, 0.2, 1.0)),
+    ("purple", Color::new(0.7, 0.4, 0.95, 1.0)),
+    ("orange", Color::new(1.0, 0.55, 0.1, 1.0)),
+    ("cyan", Color::new(0.2, 0.85, 0.9, 1.0)),
+    ("pink", Color::new(1.0, 0.45, 0.75, 1.0)),
+];
+
+/// A team taking part in the exercise
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Team {
+    /// Position in registration order, starting at 0
+    pub id: usize,
+
+    /// Name as sent by the server, e.g. "Red Team"
+    pub name: String,
+
+    /// Color the team's actions are shown in
+    #[serde(with = "crate::snapshot::color")]
+    pub color: Color,
+}
+
+impl std::fmt::Display for Team {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Every team seen this session, in registration order
+#[derive(Default)]
+pub struct TeamRegistry {
+    teams: Vec<Team>,
+}
+
+impl TeamRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a team with a fixed color, e.g. from the configuration file
+    ///
+    /// A team already registered keeps its ID and takes the new color.
+    pub fn register(&mut self, name: &str, color: Color) -> &Team {
+        let index = match self.find(name) {
+            Some(index) => {
+                self.teams[index].color = color;
+                index
+            }
+            None => {
+                self.teams.push(Team {
+                    id: self.teams.len(),
+                    name: name.to_string(),
+                    color,
+                });
+                self.teams.len() - 1
+            }
+        };
+        &self.teams[index]
+    }
+
+    /// Looks up a team by name, registering it if it is new
+    ///
+    /// A new team gets the palette color its name mentions, or else the
+    /// first palette color no team has yet, so unknown names never fail.
+    ///
+    /// # Arguments
+    /// * `name` - Team name as sent by the server
+    ///
+    /// # Returns
+    /// The registered team
+    pub fn get_or_register(&mut self, name: &str) -> &Team {
+        if let Some(index) = self.find(name) {
+            return &self.teams[index];
+        }
+        let color = named_color(name).unwrap_or_else(|| self.unused_color());
+        self.register(name, color)
+    }
+
+    /// Color of a team, or gray for a team never seen
+    pub fn color_of(&self, name: &str) -> Color {
+        self.find(name).map_or(LIGHTGRAY, |id| self.teams[id].color)
+    }
+
+    /// ID of a registered team
+    fn find(&self, name: &str) -> Option<usize> {
+        self.teams
+            .iter()
+            .find(|team| team.name == name)
+            .map(|team| team.id)
+    }
+
+    /// First palette color not taken by a team, cycling once all are
+    fn unused_color(&self) -> Color {
+        TEAM_PALETTE
+            .iter()
+            .map(|&(_, color)| color)
+            .find(|&color| self.teams.iter().all(|team| team.color != color))
+            .unwrap_or(TEAM_PALETTE[self.teams.len() % TEAM_PALETTE.len()].1)
+    }
+}
+
+/// Palette color named by a word of a team name ("Blue Team" → blue)
+fn named_color(name: &str) -> Option<Color> {
+    name.split(|c: char| !c.is_alphanumeric()).find_map(|word| {
+        TEAM_PALETTE
+            .iter()
+            .find(|(color_name, _)| word.eq_ignore_ascii_case(color_name))
+            .map(|&(_, color)| color)
+    })
+}
+
+/// Parses a `#rrggbb` color, as used for team colors in the configuration
+///
+/// # Returns
+/// The color, or None if the text is not six hex digits after a '#'
+pub fn parse_hex_color(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    Some(Color::from_rgba(
+        (rgb >> 16) as u8,
+        (rgb >> 8) as u8,
+        rgb as u8,
+        255,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_teams_get_named_or_unused_palette_colors() {
+        let mut teams = TeamRegistry::new();
+        let red = TEAM_PALETTE[0].1;
+        let blue = TEAM_PALETTE[1].1;
+
+        assert_eq!(teams.get_or_register("Blue Team").color, blue);
+        assert_eq!(teams.get_or_register("team-RED").color, red);
+        // Unknown names take the first color left over, then keep it
+        let hackers = teams.get_or_register("Hackers").clone();
+        assert_eq!((hackers.id, hackers.color), (2, TEAM_PALETTE[2].1));
+        assert_eq!(teams.get_or_register("Hackers"), &hackers);
+        assert_eq!(teams.color_of("Nobody"), LIGHTGRAY);
+
+        // More teams than colors never fails
+        for i in 0..TEAM_PALETTE.len() {
+            teams.get_or_register(&format!("Team {}", i));
+        }
+
+        teams.register("Hackers", parse_hex_color("#ff8000").unwrap());
+        assert_eq!(teams.color_of("Hackers"), Color::from_rgba(255, 128, 0, 255));
+        assert_eq!(parse_hex_color("ff8000"), None);
+        assert_eq!(parse_hex_color("#ff80"), None);
+    }
+}
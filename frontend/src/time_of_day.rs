@@ -0,0 +1,172 @@
+//! Day/night cycle
+//!
+//! This module handles:
+//! - TimeOfDay: Position in the day/night cycle, advanced by simulation time
+//! - Ambient light level used to shade the city while rendering
+//! - Forcing day or night, and resuming the automatic cycle
+//!
+//! The ambient light level runs from 0.0 (night) to 1.0 (full daylight) and
+//! follows the height of the sun, with smooth dawn and dusk transitions.
+//! Rendering code reads it through `RenderContext::ambient`.
+
+use crate::constants::day_night::{DAY_CYCLE_SECONDS, LIGHTS_ON_AMBIENT, START_PHASE, TWILIGHT};
+use macroquad::prelude::*;
+
+/// Whether the lighting follows the cycle or is held at day or night
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightingMode {
+    /// Follow the day/night cycle
+    Auto,
+    /// Full daylight
+    Day,
+    /// Full night
+    Night,
+}
+
+/// Position in the day/night cycle
+pub struct TimeOfDay {
+    /// Fraction of the cycle that has passed (0.0 = midnight, 0.5 = noon)
+    phase: f32,
+    /// Length of a full cycle in simulation seconds
+    day_length: f32,
+    mode: LightingMode,
+}
+
+impl TimeOfDay {
+    /// Creates a cycle starting in the morning
+    ///
+    /// # Arguments
+    /// * `day_length` - Length of a full day/night cycle in simulation seconds
+    pub fn new(day_length: f32) -> Self {
+        Self {
+            phase: START_PHASE,
+            day_length: day_length.max(1.0),
+            mode: LightingMode::Auto,
+        }
+    }
+
+    /// Advances the cycle
+    ///
+    /// The cycle stands still while day or night is forced, so resuming it
+    /// carries on from where it was.
+    ///
+    /// # Arguments
+    /// * `dt` - Simulation time step in seconds
+    pub fn advance(&mut self, dt: f32) {
+        if self.mode == LightingMode::Auto {
+            self.phase = (self.phase + dt / self.day_length).fract();
+        }
+    }
+
+    /// Ambient light level, from 0.0 (night) to 1.0 (full daylight)
+    pub fn ambient(&self) -> f32 {
+        match self.mode {
+            LightingMode::Day => 1.0,
+            LightingMode::Night => 0.0,
+            LightingMode::Auto => {
+                // Height of the sun: -1 at midnight, 1 at noon
+                let sun = -(self.phase * std::f32::consts::TAU).cos();
+                let t = ((sun + TWILIGHT) / (2.0 * TWILIGHT)).clamp(0.0, 1.0);
+                t * t * (3.0 - 2.0 * t)
+            }
+        }
+    }
+
+    /// Point of the cycle the lighting shows (0.0 = midnight, 0.5 = noon)
+    ///
+    /// Forced day shows noon and forced night shows midnight.
+    pub fn phase(&self) -> f64 {
+        match self.mode {
+            LightingMode::Day => 0.5,
+            LightingMode::Night => 0.0,
+            LightingMode::Auto => self.phase as f64,
+        }
+    }
+
+    /// Checks if it is dark enough for windows and headlights to be on
+    pub fn is_night(&self) -> bool {
+        self.ambient() < LIGHTS_ON_AMBIENT
+    }
+
+    /// Forces day or night, or resumes the cycle
+    pub fn set_mode(&mut self, mode: LightingMode) {
+        self.mode = mode;
+    }
+
+    /// Short description of the lighting, e.g. "auto (night)" or "forced day"
+    pub fn label(&self) -> String {
+        let time = if self.is_night() { "night" } else { "day" };
+        match self.mode {
+            LightingMode::Auto => format!("auto ({})", time),
+            LightingMode::Day | LightingMode::Night => format!("forced {}", time),
+        }
+    }
+
+    /// Handles the lighting keys
+    ///
+    /// D forces day, N forces night and A resumes the automatic cycle.
+    ///
+    /// # Returns
+    /// `true` if the lighting mode changed
+    pub fn handle_input(&mut self) -> bool {
+        let before = self.mode;
+
+        if is_key_pressed(KeyCode::D) {
+            self.set_mode(LightingMode::Day);
+        }
+        if is_key_pressed(KeyCode::N) {
+            self.set_mode(LightingMode::Night);
+        }
+        if is_key_pressed(KeyCode::A) {
+            self.set_mode(LightingMode::Auto);
+        }
+
+        self.mode != before
+    }
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self::new(DAY_CYCLE_SECONDS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dusk_darkens_smoothly_into_night() {
+        let mut time_of_day = TimeOfDay::new(100.0);
+        time_of_day.advance((0.5 - START_PHASE) * 100.0);
+        assert_eq!(time_of_day.ambient(), 1.0);
+
+        // Noon to midnight only ever gets darker, in small steps
+        let mut previous = time_of_day.ambient();
+        for _ in 0..50 {
+            time_of_day.advance(1.0);
+            let ambient = time_of_day.ambient();
+            assert!(ambient <= previous);
+            assert!(previous - ambient < 0.15);
+            previous = ambient;
+        }
+        assert_eq!(time_of_day.ambient(), 0.0);
+        assert!(time_of_day.is_night());
+    }
+
+    #[test]
+    fn test_forced_lighting_holds_the_cycle() {
+        let mut time_of_day = TimeOfDay::new(100.0);
+        let morning = time_of_day.ambient();
+
+        time_of_day.set_mode(LightingMode::Night);
+        time_of_day.advance(30.0);
+        assert_eq!(time_of_day.ambient(), 0.0);
+        assert_eq!(time_of_day.phase(), 0.0);
+        assert_eq!(time_of_day.label(), "forced night");
+
+        time_of_day.set_mode(LightingMode::Auto);
+        assert_eq!(time_of_day.ambient(), morning);
+        assert_eq!(time_of_day.label(), "auto (day)");
+    }
+}
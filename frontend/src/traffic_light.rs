@@ -1,27 +1,30 @@
 //! Traffic light structure and management
 //!
 //! This module handles:
-//! - Traffic light data structure
+//! - IntersectionTrafficLight: One controller per intersection that cycles
+//!   both directions on a single timer, so they can never both be green
 //! - Traffic light timing and state calculation
 //! - Visual rendering of traffic lights at intersections
 //! - Automatic cycling between green, yellow, and red
-//! - Perpendicular light coordination (vertical vs horizontal)
 //!
-//! Each intersection has two traffic lights positioned diagonally:
+//! Each intersection shows two traffic light heads positioned diagonally:
 //! - Top-right: Controls vertical (north-south) traffic
 //! - Bottom-left: Controls horizontal (east-west) traffic
 
+use crate::constants::day_night::{LAMP_GLOW_RADIUS, LIGHT_BLOOM_WIDTH};
 use crate::constants::traffic_light::*;
 use crate::intersection::Intersection;
+use crate::rendering::{draw_circle_glow, draw_rectangle_glow};
 use crate::models::Direction;
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Traffic Light State
 // ============================================================================
 
 /// Traffic light states with duration
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum LightState {
     /// Red light - stop (duration in seconds)
     Red(f32),
@@ -67,15 +70,6 @@ impl LightState {
         matches!(self, LightState::Green(_))
     }
 
-    /// Converts to u8 for rendering compatibility
-    pub fn to_u8(&self) -> u8 {
-        match self {
-            LightState::Red(_) => 0,
-            LightState::Yellow(_) => 1,
-            LightState::Green(_) => 2,
-        }
-    }
-
     /// Creates a default Red state
     pub fn default_red() -> Self {
         LightState::Red(RED_DURATION)
@@ -93,196 +87,152 @@ impl LightState {
 }
 
 // ============================================================================
-// Traffic Light Structure
+// Light Timing
 // ============================================================================
 
-/// Represents a traffic light at an intersection
+/// Durations of the light states, in seconds
 ///
-/// Traffic lights control vehicle flow and cycle through states
-/// based on internal timing.
-#[derive(Clone)]
-pub struct TrafficLight {
-    /// Horizontal position as percentage of screen width
-    pub x_percent: f32,
-
-    /// Vertical position as percentage of screen height
-    pub y_percent: f32,
-
-    /// Whether this controls vertical (true) or horizontal (false) traffic
-    pub controls_vertical: bool,
-
-    /// Direction the traffic light is facing/controlling
-    pub direction: Direction,
-
-    /// Current light state (contains duration)
-    pub state: LightState,
-
-    /// Time remaining in current state (in seconds)
-    pub time_in_state: f32,
-
-    /// Unique identifier
-    pub id: usize,
+/// Fields missing from a config file keep their default value.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightTiming {
+    /// Green light duration
+    pub green: f32,
+
+    /// Yellow light duration
+    pub yellow: f32,
+
+    /// Red light duration
+    pub red: f32,
 }
 
-impl TrafficLight {
-    /// Creates a new traffic light with an initial state
-    ///
-    /// # Arguments
-    /// * `x_percent` - X position as percentage (0.0-1.0)
-    /// * `y_percent` - Y position as percentage (0.0-1.0)
-    /// * `controls_vertical` - True if controls vertical traffic, false for horizontal
-    /// * `direction` - Direction the light is facing/controlling
-    /// * `initial_state` - Initial state with duration (e.g., LightState::Green(3.0))
-    /// * `id` - Unique identifier
-    pub fn new(
-        x_percent: f32,
-        y_percent: f32,
-        controls_vertical: bool,
-        direction: Direction,
-        initial_state: LightState,
-        id: usize,
-    ) -> Self {
-        let time_in_state = initial_state.duration();
+impl LightTiming {
+    /// Creates a timing from the duration of each state
+    pub fn new(green: f32, yellow: f32, red: f32) -> Self {
+        Self { green, yellow, red }
+    }
 
-        Self {
-            x_percent,
-            y_percent,
-            controls_vertical,
-            direction,
-            state: initial_state,
-            time_in_state,
-            id,
+    /// Gives a state this timing's duration for it
+    pub fn retime(&self, state: LightState) -> LightState {
+        match state {
+            LightState::Green(_) => LightState::Green(self.green),
+            LightState::Yellow(_) => LightState::Yellow(self.yellow),
+            LightState::Red(_) => LightState::Red(self.red),
         }
     }
 
-    /// Creates a traffic light using the builder pattern
-    ///
-    /// # Arguments
-    /// * `id` - Unique identifier
-    pub fn builder(id: usize) -> TrafficLightBuilder {
-        TrafficLightBuilder::new(id)
+    /// Gets the state following `current` in the Green → Yellow → Red cycle
+    pub fn next_state(&self, current: LightState) -> LightState {
+        match current {
+            LightState::Green(_) => LightState::Yellow(self.yellow),
+            LightState::Yellow(_) => LightState::Red(self.red),
+            LightState::Red(_) => LightState::Green(self.green),
+        }
     }
+}
 
-    /// Converts the percentage-based x position to absolute pixels
-    pub fn x(&self) -> f32 {
-        self.x_percent * screen_width()
+impl Default for LightTiming {
+    fn default() -> Self {
+        Self::new(GREEN_DURATION, YELLOW_DURATION, RED_DURATION)
     }
+}
 
-    /// Converts the percentage-based y position to absolute pixels
-    pub fn y(&self) -> f32 {
-        self.y_percent * screen_height()
-    }
+// ============================================================================
+// Adaptive Timing
+// ============================================================================
 
-    /// Updates the traffic light state based on elapsed time
+/// Splits green time between the two directions by how many cars queue
+///
+/// Each direction's green is scaled by its share of the queued cars, so
+/// equal queues keep the configured green and a direction with all of them
+/// gets `ADAPTIVE_GREEN_MAX_FACTOR` times it. Greens never drop below
+/// `ADAPTIVE_GREEN_MIN_FACTOR` times the configured green, and are never
+/// stretched past `MAX_GREEN_DURATION`.
+///
+/// # Arguments
+/// * `green` - Configured green duration in seconds
+/// * `vertical_queue` - Cars queued on the vertical approaches
+/// * `horizontal_queue` - Cars queued on the horizontal approaches
+///
+/// # Returns
+/// The vertical and horizontal green durations in seconds
+pub fn adaptive_green_durations(
+    green: f32,
+    vertical_queue: usize,
+    horizontal_queue: usize,
+) -> (f32, f32) {
+    let total = vertical_queue + horizontal_queue;
+    if total == 0 {
+        return (green, green);
+    }
+
+    let longest = MAX_GREEN_DURATION.max(green);
+    let scaled = |queue: usize| {
+        let factor = 2.0 * queue as f32 / total as f32;
+        (green * factor.clamp(ADAPTIVE_GREEN_MIN_FACTOR, ADAPTIVE_GREEN_MAX_FACTOR)).min(longest)
+    };
+    (scaled(vertical_queue), scaled(horizontal_queue))
+}
+
+// ============================================================================
+// Green Wave
+// ============================================================================
+
+/// Times the lights along vertical roads into a green wave
+///
+/// Each light turns green for vertical traffic as long after the topmost
+/// light on its road as a car driving down at the wave's speed takes to get
+/// there, so the car meets green lights all the way down. The lights keep
+/// in step until their green durations change, e.g. with adaptive timing.
+pub struct GreenWaveController;
+
+impl GreenWaveController {
+    /// Seconds after the topmost light on its road each light turns green
     ///
-    /// This should be called each frame with the delta time to progress the light cycle.
+    /// Intersections less than a pixel apart horizontally are on the same
+    /// vertical road.
     ///
     /// # Arguments
-    /// * `dt` - Delta time (time since last frame in seconds)
-    pub fn update(&mut self, dt: f32) {
-        self.time_in_state -= dt;
-
-        // Check if it's time to transition to next state
-        if self.time_in_state <= 0.0 {
-            self.state = self.get_next_state();
-            self.time_in_state = self.state.duration();
-        }
-    }
-
-    /// Gets the next state in the cycle (preserving durations)
-    fn get_next_state(&self) -> LightState {
-        match self.state {
-            LightState::Green(_) => {
-                // Get yellow duration from current state or use default
-                LightState::Yellow(YELLOW_DURATION)
-            }
-            LightState::Yellow(_) => {
-                // Get red duration from current state or use default
-                LightState::Red(RED_DURATION)
-            }
-            LightState::Red(_) => {
-                // Get green duration from current state or use default
-                LightState::Green(GREEN_DURATION)
-            }
-        }
-    }
-
-    /// Gets the current state of this traffic light
+    /// * `positions` - Intersection centers in pixels
+    /// * `road_speed_px_per_sec` - Speed the wave is timed for
     ///
     /// # Returns
-    /// Current light state (Red, Yellow, or Green with duration)
-    pub fn get_state(&self) -> LightState {
-        self.state
-    }
-
-    /// Sets the traffic light state manually
+    /// The delay of each intersection in seconds, in the order given
+    pub fn delays(positions: &[Vec2], road_speed_px_per_sec: f32) -> Vec<f32> {
+        positions
+            .iter()
+            .map(|position| {
+                let top = positions
+                    .iter()
+                    .filter(|other| (other.x - position.x).abs() < 1.0)
+                    .map(|other| other.y)
+                    .fold(position.y, f32::min);
+                (position.y - top) / road_speed_px_per_sec
+            })
+            .collect()
+    }
+
+    /// Restarts the intersections' light cycles as a green wave
     ///
-    /// # Arguments
-    /// * `state` - The new state to set (with duration)
-    pub fn set_state(&mut self, state: LightState) {
-        self.state = state;
-        self.time_in_state = state.duration();
-    }
-
-    /// Gets the current state as u8 (for compatibility)
-    pub fn get_state_u8(&self) -> u8 {
-        self.state.to_u8()
-    }
-
-    /// Checks if the light is red
-    pub fn is_red(&self) -> bool {
-        self.state.is_red()
-    }
-
-    /// Checks if the light is yellow
-    pub fn is_yellow(&self) -> bool {
-        self.state.is_yellow()
-    }
-
-    /// Checks if the light is green
-    pub fn is_green(&self) -> bool {
-        self.state.is_green()
-    }
-
-    /// Gets the direction this traffic light is facing/controlling
-    pub fn get_direction(&self) -> Direction {
-        self.direction
-    }
-
-    /// Sets the direction this traffic light is facing/controlling
-    pub fn set_direction(&mut self, direction: Direction) {
-        self.direction = direction;
-    }
-
-    /// Checks if this light controls traffic moving in the given direction
-    pub fn controls_direction(&self, direction: Direction) -> bool {
-        self.direction == direction
-    }
-
-    /// Gets the time remaining in the current state
-    pub fn time_remaining(&self) -> f32 {
-        self.time_in_state
-    }
-
-    /// Gets the progress through the current state (0.0 to 1.0)
-    pub fn state_progress(&self) -> f32 {
-        let total_duration = self.state.duration();
-        1.0 - (self.time_in_state / total_duration)
-    }
-
-    /// Gets the duration of the current state
-    pub fn current_state_duration(&self) -> f32 {
-        self.state.duration()
-    }
-
-    /// Renders this traffic light at its position
+    /// The topmost light on each road turns green for vertical traffic right
+    /// away, and the others by their delay from `delays`.
     ///
     /// # Arguments
-    /// * `force_red` - If true, forces the light to show red regardless of current state
-    pub fn render(&self, force_red: bool) {
-        let state = if force_red { 0 } else { self.get_state_u8() };
-
-        draw_traffic_light(self.x(), self.y(), state);
+    /// * `intersections` - Intersections to retime
+    /// * `road_speed_px_per_sec` - Speed the wave is timed for
+    pub fn apply<'a>(
+        intersections: impl IntoIterator<Item = &'a mut Intersection>,
+        road_speed_px_per_sec: f32,
+    ) {
+        let mut intersections: Vec<_> = intersections.into_iter().collect();
+        let positions: Vec<_> = intersections
+            .iter()
+            .map(|intersection| vec2(intersection.x(), intersection.y()))
+            .collect();
+        let delays = Self::delays(&positions, road_speed_px_per_sec);
+        for (intersection, delay) in intersections.iter_mut().zip(delays) {
+            intersection.set_cycle_position(-delay);
+        }
     }
 }
 
@@ -291,7 +241,7 @@ impl TrafficLight {
 // ============================================================================
 
 /// Represents which direction currently has or is transitioning from green light
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 enum ActiveDirection {
     Vertical,
     Horizontal,
@@ -302,7 +252,7 @@ enum ActiveDirection {
 /// This struct manages both vertical and horizontal traffic lights at a single
 /// intersection, ensuring they are always properly coordinated (when one is green,
 /// the perpendicular direction is red).
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct IntersectionTrafficLight {
     /// Horizontal position as percentage of screen width
     pub x_percent: f32,
@@ -322,6 +272,17 @@ pub struct IntersectionTrafficLight {
     /// Which direction is currently active (green or transitioning)
     active_direction: ActiveDirection,
 
+    /// Durations of the states in the cycle
+    ///
+    /// Both directions get `green` then `yellow`, so each one's red phase
+    /// actually lasts as long as the other's green and yellow together.
+    pub timing: LightTiming,
+
+    /// Green durations of the next vertical and horizontal green phases,
+    /// `timing.green` unless adaptive timing changed them
+    vertical_green: f32,
+    horizontal_green: f32,
+
     /// Unique identifier
     pub id: usize,
 }
@@ -360,10 +321,54 @@ impl IntersectionTrafficLight {
                 horizontal_state.duration()
             },
             active_direction,
+            timing: LightTiming::default(),
+            vertical_green: GREEN_DURATION,
+            horizontal_green: GREEN_DURATION,
             id,
         }
     }
 
+    /// Sets the timing of the light cycle
+    ///
+    /// The current state restarts with its new duration, and any adaptive
+    /// green durations are replaced by the new green duration.
+    ///
+    /// # Arguments
+    /// * `timing` - New state durations
+    pub fn set_timing(&mut self, timing: LightTiming) {
+        self.timing = timing;
+        self.vertical_green = timing.green;
+        self.horizontal_green = timing.green;
+        self.vertical_state = timing.retime(self.vertical_state);
+        self.horizontal_state = timing.retime(self.horizontal_state);
+        self.time_in_state = match self.active_direction {
+            ActiveDirection::Vertical => self.vertical_state.duration(),
+            ActiveDirection::Horizontal => self.horizontal_state.duration(),
+        };
+    }
+
+    /// Sets how long each direction's next green phase lasts
+    ///
+    /// Takes effect the next time each direction turns green; a green phase
+    /// already running keeps its duration.
+    ///
+    /// # Arguments
+    /// * `vertical` - Green duration for vertical traffic in seconds
+    /// * `horizontal` - Green duration for horizontal traffic in seconds
+    pub fn set_green_durations(&mut self, vertical: f32, horizontal: f32) {
+        self.vertical_green = vertical;
+        self.horizontal_green = horizontal;
+    }
+
+    /// Gets how long the next green phase for a direction of travel lasts
+    pub fn green_duration_for_direction(&self, direction: Direction) -> f32 {
+        if direction.is_vertical() {
+            self.vertical_green
+        } else {
+            self.horizontal_green
+        }
+    }
+
     /// Converts the percentage-based x position to absolute pixels
     pub fn x(&self) -> f32 {
         self.x_percent * screen_width()
@@ -374,6 +379,63 @@ impl IntersectionTrafficLight {
         self.y_percent * screen_height()
     }
 
+    /// Turns the light green for one direction at once, skipping yellow
+    ///
+    /// The perpendicular direction turns red, and the cycle carries on from
+    /// the start of the new green phase.
+    ///
+    /// # Arguments
+    /// * `direction` - Direction of travel that gets the green light
+    pub fn preempt(&mut self, direction: Direction) {
+        let green = LightState::Green(self.timing.green);
+        let red = LightState::Red(self.timing.red);
+        if direction.is_vertical() {
+            self.active_direction = ActiveDirection::Vertical;
+            self.vertical_state = green;
+            self.horizontal_state = red;
+        } else {
+            self.active_direction = ActiveDirection::Horizontal;
+            self.horizontal_state = green;
+            self.vertical_state = red;
+        }
+        self.time_in_state = self.timing.green;
+    }
+
+    /// Restarts the cycle as if vertical traffic turned green `elapsed`
+    /// seconds ago
+    ///
+    /// The cycle is vertical green and yellow, then horizontal green and
+    /// yellow, with the next green durations.
+    ///
+    /// # Arguments
+    /// * `elapsed` - Seconds into the cycle, wrapped to its length; negative
+    ///   values count back from the end, so vertical traffic turns green
+    ///   that many seconds from now
+    pub fn set_cycle_position(&mut self, elapsed: f32) {
+        let phases = [
+            (ActiveDirection::Vertical, LightState::Green(self.vertical_green)),
+            (ActiveDirection::Vertical, LightState::Yellow(self.timing.yellow)),
+            (ActiveDirection::Horizontal, LightState::Green(self.horizontal_green)),
+            (ActiveDirection::Horizontal, LightState::Yellow(self.timing.yellow)),
+        ];
+        let cycle: f32 = phases.iter().map(|(_, state)| state.duration()).sum();
+        let mut elapsed = elapsed.rem_euclid(cycle);
+
+        for (direction, state) in phases {
+            if elapsed < state.duration() {
+                let red = LightState::Red(self.timing.red);
+                (self.vertical_state, self.horizontal_state) = match direction {
+                    ActiveDirection::Vertical => (state, red),
+                    ActiveDirection::Horizontal => (red, state),
+                };
+                self.active_direction = direction;
+                self.time_in_state = state.duration() - elapsed;
+                return;
+            }
+            elapsed -= state.duration();
+        }
+    }
+
     /// Updates the traffic light states based on elapsed time
     ///
     /// Automatically keeps vertical and horizontal lights coordinated.
@@ -396,13 +458,13 @@ impl IntersectionTrafficLight {
                     // If vertical just turned red, switch to horizontal
                     if new_vertical_state.is_red() {
                         self.active_direction = ActiveDirection::Horizontal;
-                        self.horizontal_state = LightState::default_green();
+                        self.horizontal_state = LightState::Green(self.horizontal_green);
+                        self.time_in_state = self.horizontal_green;
                     } else {
                         // Keep horizontal red while vertical is active
-                        self.horizontal_state = LightState::default_red();
+                        self.horizontal_state = LightState::Red(self.timing.red);
+                        self.time_in_state = new_vertical_state.duration();
                     }
-
-                    self.time_in_state = new_vertical_state.duration();
                 }
                 ActiveDirection::Horizontal => {
                     // Advance horizontal state
@@ -412,25 +474,21 @@ impl IntersectionTrafficLight {
                     // If horizontal just turned red, switch to vertical
                     if new_horizontal_state.is_red() {
                         self.active_direction = ActiveDirection::Vertical;
-                        self.vertical_state = LightState::default_green();
+                        self.vertical_state = LightState::Green(self.vertical_green);
+                        self.time_in_state = self.vertical_green;
                     } else {
                         // Keep vertical red while horizontal is active
-                        self.vertical_state = LightState::default_red();
+                        self.vertical_state = LightState::Red(self.timing.red);
+                        self.time_in_state = new_horizontal_state.duration();
                     }
-
-                    self.time_in_state = new_horizontal_state.duration();
                 }
             }
         }
     }
 
-    /// Gets the next state in the cycle
+    /// Gets the next state in the cycle, timed by this light's timing
     fn get_next_state(&self, current: LightState) -> LightState {
-        match current {
-            LightState::Green(_) => LightState::Yellow(YELLOW_DURATION),
-            LightState::Yellow(_) => LightState::Red(RED_DURATION),
-            LightState::Red(_) => LightState::Green(GREEN_DURATION),
-        }
+        self.timing.next_state(current)
     }
 
     /// Gets the state for a specific direction
@@ -439,34 +497,58 @@ impl IntersectionTrafficLight {
     /// * `direction` - Direction of travel
     ///
     /// # Returns
-    /// Light state as u8: 0=red, 1=yellow, 2=green
-    pub fn get_state_for_direction(&self, direction: Direction) -> u8 {
+    /// Light state controlling traffic moving in that direction
+    pub fn get_state_for_direction(&self, direction: Direction) -> LightState {
         let is_vertical = direction == Direction::Down || direction == Direction::Up;
-        let state = if is_vertical {
+        if is_vertical {
             self.vertical_state
         } else {
             self.horizontal_state
-        };
-        state.to_u8()
+        }
     }
 
     /// Gets the vertical light state
-    pub fn get_vertical_state(&self) -> u8 {
-        self.vertical_state.to_u8()
+    pub fn get_vertical_state(&self) -> LightState {
+        self.vertical_state
     }
 
     /// Gets the horizontal light state
-    pub fn get_horizontal_state(&self) -> u8 {
-        self.horizontal_state.to_u8()
+    pub fn get_horizontal_state(&self) -> LightState {
+        self.horizontal_state
     }
 
     /// Renders both traffic lights for this intersection
     ///
     /// # Arguments
-    /// * `force_red` - If true, forces all lights to show red (emergency mode)
-    pub fn render(&self, force_red: bool) {
+    /// * `forced_state` - If set, both lights show this state (emergency mode or override)
+    /// * `glow` - Opacity of the glow around lit lamps (0.0 in daylight)
+    pub fn render(&self, forced_state: Option<LightState>, glow: f32) {
+        let [(v_x, v_y), (h_x, h_y)] = self.light_positions();
+
+        let v_state = forced_state.unwrap_or_else(|| self.get_vertical_state());
+        draw_traffic_light(v_x, v_y, v_state, glow);
+
+        let h_state = forced_state.unwrap_or_else(|| self.get_horizontal_state());
+        draw_traffic_light(h_x, h_y, h_state, glow);
+    }
+
+    /// Renders both traffic lights without power: every lamp is dark except
+    /// the yellow one, which flashes in all directions
+    ///
+    /// # Arguments
+    /// * `time` - Current simulation time, for the flashing
+    /// * `glow` - Opacity of the glow around lit lamps (0.0 in daylight)
+    pub fn render_unpowered(&self, time: f64, glow: f32) {
+        let flash_on = ((time / POWER_OUTAGE_FLASH_INTERVAL) as u64).is_multiple_of(2);
+        let lamp = flash_on.then(LightState::default_yellow);
+        for (x, y) in self.light_positions() {
+            draw_traffic_light_with_pole_offset(x, y, lamp, 0.0, glow);
+        }
+    }
+
+    /// Top-left corners of the vertical and horizontal light boxes, in pixels
+    fn light_positions(&self) -> [(f32, f32); 2] {
         const ROAD_WIDTH: f32 = 60.0;
-        let offset = ROAD_WIDTH / 2.0 + 10.0;
 
         let int_x = self.x();
         let int_y = self.y();
@@ -476,243 +558,22 @@ impl IntersectionTrafficLight {
         let top_corner_x = int_x + ROAD_WIDTH / 2.0;
         let top_corner_y = int_y - ROAD_WIDTH / 2.0;
 
-        let v_state = if force_red {
-            0
-        } else {
-            self.get_vertical_state()
-        };
-
         // Position relative to corner
         let v_x = top_corner_x + 10.0;
         let v_y = top_corner_y - 70.0;
-        draw_traffic_light(v_x, v_y, v_state);
 
         // Horizontal traffic light (bottom-left corner)
         // Calculate bottom-left grass block corner
         let bottom_corner_x = int_x - ROAD_WIDTH / 2.0;
         let bottom_corner_y = int_y + ROAD_WIDTH / 2.0;
 
-        let h_state = if force_red {
-            0
-        } else {
-            self.get_horizontal_state()
-        };
-
         // Apply same offset from corner as top-right light (mirrored)
         // Top-right is +10 from corner in X, -70 in Y
         // Bottom-left should be -10 from corner in X, +0 in Y (no extra offset needed)
         let h_x = bottom_corner_x - 30.0;
         let h_y = bottom_corner_y - 35.0;
 
-        draw_traffic_light(h_x, h_y, h_state);
-    }
-}
-
-// ============================================================================
-// Traffic Light Builder
-// ============================================================================
-
-/// Builder for creating TrafficLight instances
-pub struct TrafficLightBuilder {
-    id: usize,
-    x_percent: Option<f32>,
-    y_percent: Option<f32>,
-    controls_vertical: Option<bool>,
-    direction: Option<Direction>,
-    initial_state: Option<LightState>,
-}
-
-impl TrafficLightBuilder {
-    /// Creates a new TrafficLightBuilder
-    fn new(id: usize) -> Self {
-        Self {
-            id,
-            x_percent: None,
-            y_percent: None,
-            controls_vertical: None,
-            direction: None,
-            initial_state: None,
-        }
-    }
-
-    /// Sets the position
-    pub fn position(mut self, x_percent: f32, y_percent: f32) -> Self {
-        self.x_percent = Some(x_percent);
-        self.y_percent = Some(y_percent);
-        self
-    }
-
-    /// Sets the x position
-    pub fn x(mut self, x_percent: f32) -> Self {
-        self.x_percent = Some(x_percent);
-        self
-    }
-
-    /// Sets the y position
-    pub fn y(mut self, y_percent: f32) -> Self {
-        self.y_percent = Some(y_percent);
-        self
-    }
-
-    /// Sets the initial state of the traffic light
-    pub fn initial_state(mut self, state: LightState) -> Self {
-        self.initial_state = Some(state);
-        self
-    }
-
-    /// Sets the initial state to Green with default duration
-    pub fn start_green(mut self) -> Self {
-        self.initial_state = Some(LightState::default_green());
-        self
-    }
-
-    /// Sets the initial state to Green with custom duration
-    pub fn start_green_with(mut self, duration: f32) -> Self {
-        self.initial_state = Some(LightState::Green(duration));
-        self
-    }
-
-    /// Sets the initial state to Red with default duration
-    pub fn start_red(mut self) -> Self {
-        self.initial_state = Some(LightState::default_red());
-        self
-    }
-
-    /// Sets the initial state to Red with custom duration
-    pub fn start_red_with(mut self, duration: f32) -> Self {
-        self.initial_state = Some(LightState::Red(duration));
-        self
-    }
-
-    /// Sets the initial state to Yellow with default duration
-    pub fn start_yellow(mut self) -> Self {
-        self.initial_state = Some(LightState::default_yellow());
-        self
-    }
-
-    /// Sets the initial state to Yellow with custom duration
-    pub fn start_yellow_with(mut self, duration: f32) -> Self {
-        self.initial_state = Some(LightState::Yellow(duration));
-        self
-    }
-
-    /// Sets whether this light controls vertical traffic
-    pub fn controls_vertical(mut self, vertical: bool) -> Self {
-        self.controls_vertical = Some(vertical);
-        self
-    }
-
-    /// Sets this light to control vertical traffic
-    pub fn vertical(mut self) -> Self {
-        self.controls_vertical = Some(true);
-        self
-    }
-
-    /// Sets this light to control horizontal traffic
-    pub fn horizontal(mut self) -> Self {
-        self.controls_vertical = Some(false);
-        self
-    }
-
-    /// Sets the direction this light is facing/controlling
-    pub fn direction(mut self, direction: Direction) -> Self {
-        self.direction = Some(direction);
-        // Auto-set controls_vertical based on direction
-        match direction {
-            Direction::Up | Direction::Down => {
-                self.controls_vertical = Some(true);
-            }
-            Direction::Left | Direction::Right => {
-                self.controls_vertical = Some(false);
-            }
-        }
-        self
-    }
-
-    /// Sets this light to face/control upward traffic
-    pub fn facing_up(mut self) -> Self {
-        self.direction = Some(Direction::Up);
-        self.controls_vertical = Some(true);
-        self
-    }
-
-    /// Sets this light to face/control downward traffic
-    pub fn facing_down(mut self) -> Self {
-        self.direction = Some(Direction::Down);
-        self.controls_vertical = Some(true);
-        self
-    }
-
-    /// Sets this light to face/control left traffic
-    pub fn facing_left(mut self) -> Self {
-        self.direction = Some(Direction::Left);
-        self.controls_vertical = Some(false);
-        self
-    }
-
-    /// Sets this light to face/control right traffic
-    pub fn facing_right(mut self) -> Self {
-        self.direction = Some(Direction::Right);
-        self.controls_vertical = Some(false);
-        self
-    }
-
-    /// Builds the TrafficLight
-    ///
-    /// Defaults:
-    /// - x_percent: 0.5
-    /// - y_percent: 0.5
-    /// - controls_vertical: true
-    /// - direction: Direction::Down
-    /// - initial_state: LightState::Red(3.0)
-    pub fn build(self) -> TrafficLight {
-        let controls_vertical = self.controls_vertical.unwrap_or(true);
-        let direction = self.direction.unwrap_or(if controls_vertical {
-            Direction::Down
-        } else {
-            Direction::Right
-        });
-
-        TrafficLight::new(
-            self.x_percent.unwrap_or(0.5),
-            self.y_percent.unwrap_or(0.5),
-            controls_vertical,
-            direction,
-            self.initial_state.unwrap_or(LightState::default_red()),
-            self.id,
-        )
-    }
-}
-
-// ============================================================================
-// Traffic Light State Logic
-// ============================================================================
-
-/// Calculates the current traffic light state based on time
-///
-/// # Arguments
-/// * `time_offset` - Offset in seconds for this specific light (for staggering)
-///
-/// # Returns
-/// Light state as u8:
-/// - 0 = Red (stop)
-/// - 1 = Yellow (caution)
-/// - 2 = Green (go)
-///
-/// # Timing Cycle (7 seconds total)
-/// - 0-3s: Green
-/// - 3-4s: Yellow
-/// - 4-7s: Red
-pub fn get_traffic_light_state(time_offset: f32) -> u8 {
-    // Add time offset and wrap around cycle duration
-    let time = (get_time() as f32 + time_offset) % CYCLE_DURATION;
-
-    if time < GREEN_DURATION {
-        2 // Green
-    } else if time < GREEN_DURATION + YELLOW_DURATION {
-        1 // Yellow
-    } else {
-        0 // Red
+        [(v_x, v_y), (h_x, h_y)]
     }
 }
 
@@ -729,9 +590,10 @@ pub fn get_traffic_light_state(time_offset: f32) -> u8 {
 /// # Arguments
 /// * `x` - X position for top-left corner of light box
 /// * `y` - Y position for top-left corner of light box
-/// * `active_light` - Which light is currently on (0=red, 1=yellow, 2=green)
-pub fn draw_traffic_light(x: f32, y: f32, active_light: u8) {
-    draw_traffic_light_with_pole_offset(x, y, active_light, 0.0);
+/// * `active_light` - Which light is currently on
+/// * `glow` - Opacity of the glow around the lit lamp (0.0 for none)
+pub fn draw_traffic_light(x: f32, y: f32, active_light: LightState, glow: f32) {
+    draw_traffic_light_with_pole_offset(x, y, Some(active_light), 0.0, glow);
 }
 
 /// Renders a traffic light with custom pole positioning
@@ -739,12 +601,32 @@ pub fn draw_traffic_light(x: f32, y: f32, active_light: u8) {
 /// # Arguments
 /// * `x` - X position for top-left corner of light box
 /// * `y` - Y position for top-left corner of light box
-/// * `active_light` - Which light is currently on (0=red, 1=yellow, 2=green)
+/// * `active_light` - Which light is currently on (None = all dark)
 /// * `pole_x_offset` - Horizontal offset for pole position relative to light box center
-pub fn draw_traffic_light_with_pole_offset(x: f32, y: f32, active_light: u8, pole_x_offset: f32) {
+/// * `glow` - Opacity of the glow around the lit lamp (0.0 for none)
+pub fn draw_traffic_light_with_pole_offset(
+    x: f32,
+    y: f32,
+    active_light: Option<LightState>,
+    pole_x_offset: f32,
+    glow: f32,
+) {
     let box_width = TRAFFIC_LIGHT_SIZE + 6.0;
     let box_height = TRAFFIC_LIGHT_SIZE * 3.0 + TRAFFIC_LIGHT_SPACING * 4.0;
 
+    // Bloom of the lit lamp's color behind the housing at night
+    let lit_color = active_light.map(|light| match light {
+        LightState::Red(_) => RED_BRIGHT,
+        LightState::Yellow(_) => YELLOW_BRIGHT,
+        LightState::Green(_) => GREEN_BRIGHT,
+    });
+    if let Some(color) = lit_color
+        && glow > 0.0
+    {
+        let bloom = Color { a: glow, ..color };
+        draw_rectangle_glow(x, y, box_width, box_height, LIGHT_BLOOM_WIDTH, bloom);
+    }
+
     // Draw dark housing box
     draw_rectangle(x, y, box_width, box_height, BOX_COLOR);
 
@@ -770,7 +652,7 @@ pub fn draw_traffic_light_with_pole_offset(x: f32, y: f32, active_light: u8, pol
 
     // RED light (top)
     let red_y = y + TRAFFIC_LIGHT_SPACING + radius;
-    let red_color = if active_light == 0 {
+    let red_color = if active_light.is_some_and(|light| light.is_red()) {
         RED_BRIGHT
     } else {
         RED_DIM
@@ -779,7 +661,7 @@ pub fn draw_traffic_light_with_pole_offset(x: f32, y: f32, active_light: u8, pol
 
     // YELLOW light (middle)
     let yellow_y = red_y + TRAFFIC_LIGHT_SIZE + TRAFFIC_LIGHT_SPACING;
-    let yellow_color = if active_light == 1 {
+    let yellow_color = if active_light.is_some_and(|light| light.is_yellow()) {
         YELLOW_BRIGHT
     } else {
         YELLOW_DIM
@@ -788,12 +670,25 @@ pub fn draw_traffic_light_with_pole_offset(x: f32, y: f32, active_light: u8, pol
 
     // GREEN light (bottom)
     let green_y = yellow_y + TRAFFIC_LIGHT_SIZE + TRAFFIC_LIGHT_SPACING;
-    let green_color = if active_light == 2 {
+    let green_color = if active_light.is_some_and(|light| light.is_green()) {
         GREEN_BRIGHT
     } else {
         GREEN_DIM
     };
     draw_circle(light_x, green_y, radius, green_color);
+
+    // Soft glow around the lit lamp at night
+    let lit_lamp_y = active_light.map(|light| match light {
+        LightState::Red(_) => red_y,
+        LightState::Yellow(_) => yellow_y,
+        LightState::Green(_) => green_y,
+    });
+    if let (Some(lamp_y), Some(color)) = (lit_lamp_y, lit_color)
+        && glow > 0.0
+    {
+        let glow_color = Color { a: glow, ..color };
+        draw_circle_glow(light_x, lamp_y, radius, LAMP_GLOW_RADIUS, glow_color);
+    }
 }
 
 /// Renders all traffic lights for all intersections
@@ -807,8 +702,76 @@ pub fn draw_traffic_light_with_pole_offset(x: f32, y: f32, active_light: u8, pol
 /// # Arguments
 /// * `intersections` - All intersections to draw lights at
 /// * `all_lights_red` - Emergency mode flag (forces all lights to red)
-pub fn draw_traffic_lights(intersections: &[Intersection], all_lights_red: bool) {
+/// * `time` - Current simulation time, for flashing unpowered lights
+/// * `glow` - Opacity of the glow around lit lamps (0.0 in daylight)
+pub fn draw_traffic_lights(
+    intersections: &[Intersection],
+    all_lights_red: bool,
+    time: f64,
+    glow: f32,
+) {
     for intersection in intersections {
-        intersection.render_lights(all_lights_red);
+        intersection.render_lights(all_lights_red, time, glow);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_green_lasts_its_duration() {
+        let timing = LightTiming::new(5.0, 1.0, 3.0);
+        let mut light = IntersectionTrafficLight::new(0.5, 0.5, 0, true);
+        light.set_timing(timing);
+
+        // 4.9 simulated seconds: still green
+        for _ in 0..49 {
+            light.update(0.1);
+        }
+        assert_eq!(light.get_vertical_state(), LightState::Green(5.0));
+
+        // Yellow after 5 seconds, then the horizontal road gets the same green
+        for _ in 0..2 {
+            light.update(0.1);
+        }
+        assert_eq!(light.get_vertical_state(), LightState::Yellow(1.0));
+        for _ in 0..11 {
+            light.update(0.1);
+        }
+        assert_eq!(light.get_horizontal_state(), LightState::Green(5.0));
+        assert_eq!(light.get_vertical_state(), LightState::Red(3.0));
+    }
+
+    #[test]
+    fn test_green_wave_delays_lights_down_each_road() {
+        let positions = [
+            vec2(100.0, 300.0),
+            vec2(100.0, 100.0),
+            vec2(400.0, 200.0),
+            vec2(400.5, 500.0),
+        ];
+        assert_eq!(GreenWaveController::delays(&positions, 50.0), [4.0, 0.0, 0.0, 6.0]);
+
+        // Default cycle: 3s vertical green, 1s yellow, 3s horizontal green, 1s yellow
+        let mut light = IntersectionTrafficLight::new(0.5, 0.5, 0, true);
+        light.set_cycle_position(-1.5);
+        assert_eq!(light.get_horizontal_state(), LightState::Green(3.0));
+        assert_eq!(light.get_vertical_state(), LightState::Red(3.0));
+        light.update(0.5);
+        assert!(light.get_horizontal_state().is_yellow());
+        light.update(1.0);
+        assert!(light.get_vertical_state().is_green());
+    }
+
+    #[test]
+    fn test_adaptive_green_follows_queues_within_limits() {
+        assert_eq!(adaptive_green_durations(3.0, 0, 0), (3.0, 3.0));
+        assert_eq!(adaptive_green_durations(3.0, 4, 4), (3.0, 3.0));
+        assert_eq!(adaptive_green_durations(3.0, 3, 1), (4.5, 1.5));
+        assert_eq!(adaptive_green_durations(3.0, 10, 0), (6.0, 1.5));
+        // Long configured greens are only stretched up to the cap
+        assert_eq!(adaptive_green_durations(5.0, 10, 0), (MAX_GREEN_DURATION, 2.5));
+        assert_eq!(adaptive_green_durations(10.0, 10, 0), (10.0, 5.0));
     }
 }
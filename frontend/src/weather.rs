@@ -0,0 +1,196 @@
+//! Weather effects
+//!
+//! This module handles:
+//! - RainDrop: A single falling drop, positioned as a fraction of the screen
+//! - RainSystem: The drops on screen, their movement and rendering
+//! - Rain intensity, which sets how many drops fall and how fast
+//! - How much cars slow down in the rain
+//!
+//! Rain is set by `WeatherChange` events from the server. An intensity of 0.0
+//! is dry weather and 1.0 a downpour.
+
+use crate::constants::weather::*;
+use crate::screen::{screen_height, screen_width};
+use macroquad::prelude::*;
+
+/// A single falling rain drop
+#[derive(Clone, Debug)]
+pub struct RainDrop {
+    /// X position as a fraction of the screen width
+    pub x_percent: f32,
+
+    /// Y position as a fraction of the screen height
+    pub y_percent: f32,
+
+    /// Fall speed at full intensity, in screen heights per second
+    pub velocity: f32,
+
+    /// Angle from vertical in radians (positive slants to the right)
+    pub angle: f32,
+}
+
+impl RainDrop {
+    /// Creates a drop at a random position on screen
+    fn random() -> Self {
+        Self {
+            x_percent: rand::gen_range(0.0, 1.0),
+            y_percent: rand::gen_range(0.0, 1.0),
+            velocity: rand::gen_range(RAIN_SPEED_MIN, RAIN_SPEED_MAX),
+            angle: RAIN_ANGLE + rand::gen_range(-RAIN_ANGLE_SPREAD, RAIN_ANGLE_SPREAD),
+        }
+    }
+}
+
+/// Rain falling over the city
+#[derive(Default)]
+pub struct RainSystem {
+    drops: Vec<RainDrop>,
+
+    /// Rain intensity from 0.0 (dry) to 1.0 (downpour)
+    intensity: f32,
+}
+
+impl RainSystem {
+    /// Creates a system with dry weather
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current rain intensity, from 0.0 (dry) to 1.0 (downpour)
+    pub fn intensity(&self) -> f32 {
+        self.intensity
+    }
+
+    /// Changes how hard it rains
+    ///
+    /// Drops are added or removed to match the new intensity; the drops
+    /// already falling keep their places.
+    ///
+    /// # Arguments
+    /// * `intensity` - Rain intensity, clamped to 0.0 (dry) to 1.0 (downpour)
+    pub fn set_intensity(&mut self, intensity: f32) {
+        self.intensity = intensity.clamp(0.0, 1.0);
+
+        let count = (MAX_RAIN_DROPS as f32 * self.intensity).round() as usize;
+        self.drops.truncate(count);
+        while self.drops.len() < count {
+            self.drops.push(RainDrop::random());
+        }
+    }
+
+    /// Fraction of their usual speed cars drive at in the current rain
+    ///
+    /// # Returns
+    /// 1.0 in dry weather, down to `1.0 - RAIN_MAX_SLOWDOWN` in a downpour
+    pub fn speed_factor(&self) -> f32 {
+        1.0 - RAIN_MAX_SLOWDOWN * self.intensity
+    }
+
+    /// Fall speed multiplier for the current intensity
+    ///
+    /// Light rain falls slower than a downpour.
+    fn fall_speed_factor(&self) -> f32 {
+        DRIZZLE_SPEED_FACTOR + (1.0 - DRIZZLE_SPEED_FACTOR) * self.intensity
+    }
+
+    /// Moves every drop, wrapping drops that leave the screen
+    ///
+    /// Drops falling past the bottom edge come back in at the top at a new
+    /// random X position, so the rain doesn't repeat in visible columns.
+    ///
+    /// # Arguments
+    /// * `dt` - Delta time (frame duration in seconds)
+    pub fn update(&mut self, dt: f32) {
+        let fall_speed = self.fall_speed_factor();
+        // Horizontal movement is converted to screen widths so the slant
+        // keeps its angle on screens of any shape
+        let aspect = screen_height() / screen_width();
+
+        for drop in &mut self.drops {
+            let distance = drop.velocity * fall_speed * dt;
+            drop.y_percent += distance * drop.angle.cos();
+            drop.x_percent = (drop.x_percent + distance * drop.angle.sin() * aspect).rem_euclid(1.0);
+
+            if drop.y_percent > 1.0 {
+                drop.y_percent = drop.y_percent.fract();
+                drop.x_percent = rand::gen_range(0.0, 1.0);
+            }
+        }
+    }
+
+    /// Draws every drop as a short streak along its direction of travel
+    ///
+    /// Each streak is drawn twice, a faint wide line under a thin one, which
+    /// softens its edges.
+    pub fn render(&self) {
+        if self.drops.is_empty() {
+            return;
+        }
+
+        let width = screen_width();
+        let height = screen_height();
+        let fall_speed = self.fall_speed_factor();
+        let halo = Color::new(RAIN_COLOR.r, RAIN_COLOR.g, RAIN_COLOR.b, RAIN_COLOR.a * 0.35);
+
+        for drop in &self.drops {
+            let length = RAIN_STREAK_LENGTH * fall_speed * drop.velocity / RAIN_SPEED_MAX;
+            let x = drop.x_percent * width;
+            let y = drop.y_percent * height;
+            let tail_x = x - drop.angle.sin() * length;
+            let tail_y = y - drop.angle.cos() * length;
+
+            draw_line(tail_x, tail_y, x, y, 2.5, halo);
+            draw_line(tail_x, tail_y, x, y, 1.0, RAIN_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intensity_sets_drop_count_and_slowdown() {
+        let mut rain = RainSystem::new();
+        assert_eq!(rain.speed_factor(), 1.0);
+
+        rain.set_intensity(0.5);
+        assert_eq!(rain.drops.len(), MAX_RAIN_DROPS / 2);
+
+        rain.set_intensity(2.0);
+        assert_eq!(rain.intensity(), 1.0);
+        assert_eq!(rain.drops.len(), MAX_RAIN_DROPS);
+        assert!((rain.speed_factor() - 0.7).abs() < 1e-6);
+
+        rain.set_intensity(0.0);
+        assert!(rain.drops.is_empty());
+    }
+
+    #[test]
+    fn test_drops_fall_and_wrap_around_the_screen() {
+        let mut rain = RainSystem::new();
+        rain.set_intensity(1.0);
+        rain.drops[0] = RainDrop {
+            x_percent: 0.5,
+            y_percent: 0.2,
+            velocity: 1.0,
+            angle: 0.0,
+        };
+
+        rain.update(0.5);
+        assert!((rain.drops[0].y_percent - 0.7).abs() < 1e-6);
+        assert_eq!(rain.drops[0].x_percent, 0.5);
+
+        // Past the bottom edge, back in at the top
+        rain.update(0.5);
+        assert!((rain.drops[0].y_percent - 0.2).abs() < 1e-5);
+
+        for _ in 0..100 {
+            rain.update(0.1);
+        }
+        for drop in &rain.drops {
+            assert!((0.0..=1.0).contains(&drop.x_percent));
+            assert!((0.0..=1.0).contains(&drop.y_percent));
+        }
+    }
+}